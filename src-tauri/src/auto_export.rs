@@ -0,0 +1,47 @@
+// src/auto_export.rs - Scheduled encrypted backups (Settings: auto_export)
+
+use crate::states::AppState;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Periodically write a backup when one is due (only while unlocked, it needs the master key)
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            let app_state = app.state::<Arc<AppState>>().inner().clone();
+            if app_state.is_locked() {
+                continue;
+            }
+
+            // Decrypting and sealing the whole vault is CPU work, keep it off the async workers
+            let result =
+                tauri::async_runtime::spawn_blocking(move || app_state.run_auto_export(false))
+                    .await;
+
+            match result {
+                Ok(Err(e)) => notify_failure(&app, &e.to_string()),
+                Err(e) => notify_failure(&app, &e.to_string()),
+                Ok(Ok(_)) => {}
+            }
+        }
+    });
+}
+
+fn notify_failure(app: &AppHandle, reason: &str) {
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title("Zap - automatic export failed")
+        .body(reason)
+        .show()
+    {
+        tracing::warn!("Failed to show export notification: {}", e);
+    }
+}
@@ -0,0 +1,26 @@
+// src/bin/zap_bindings.rs - Regenerate the frontend's TypeScript bindings
+// Usage: zap-bindings [OUT_DIR]   (default: src/types/generated in the frontend)
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use zap_lib::bindings::export_bindings;
+
+const DEFAULT_OUT_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../src/types/generated");
+
+fn main() -> ExitCode {
+    let out_dir = std::env::args()
+        .nth(1)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(DEFAULT_OUT_DIR));
+
+    match export_bindings(&out_dir) {
+        Ok(()) => {
+            println!("TypeScript bindings written to {}", out_dir.display());
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("zap-bindings: {}", e);
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,463 @@
+// src/bin/zap_cli.rs - Command line companion to the desktop app
+// Reads the session files DevState writes, so the format can't drift from CliSessionFile.
+
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::path::PathBuf;
+use std::process::{Command, ExitCode};
+use std::time::Duration;
+use zap_lib::models::dev_model::session_activity_file_name;
+use zap_lib::models::project_model::PROJECT_FILE_NAME;
+use zap_lib::models::proxy_model::APP_ACCESS_TIMEOUT_SECONDS;
+use zap_lib::models::secret_model::env_var_name;
+use zap_lib::models::{
+    CliSessionFile, ProxyEndpoint, ProxyReply, ProxyRequest, SessionFileSchema, TemplateMode,
+    ZapError,
+};
+use zap_lib::services::DevService;
+use zap_lib::utils::input_validation::name_key;
+use zap_lib::utils::path_resolvers::{
+    get_cli_schema_path, get_cli_sessions_directory, get_proxy_endpoint_path,
+};
+use zap_lib::utils::secret_template::render_template;
+
+const TOKEN_ENV: &str = "ZAP_SESSION_TOKEN";
+// Long enough for the user to answer the app's access prompt
+const PROXY_TIMEOUT: Duration = Duration::from_secs(APP_ACCESS_TIMEOUT_SECONDS + 15);
+
+const USAGE: &str = "\
+Usage: zap-cli <command> [options]
+
+Commands:
+  list [SESSION]                       List sessions, or the secrets in one session
+  run [-s SESSION] [-p PREFIX] [-v] -- <command...>
+                                       Run a command with the session's secrets as env vars
+  export [-s SESSION] [-p PREFIX] [--shell]
+                                       Print the session's secrets as NAME=value lines
+                                       (--shell: eval \"$(zap-cli export --shell)\")
+  render [-s SESSION] [--lenient] < template > output
+                                       Fill {{secret:Box/Secret}} placeholders from stdin
+                                       (--lenient: leave unknown placeholders as they are)
+
+Options:
+  -t, --token TOKEN                    Proxy session token shown by the app
+
+Without --session, the current_session from ./zap.json is used.
+With --token (or ZAP_SESSION_TOKEN), run/export/render fetch secrets from a proxy session
+in the running Zap app instead of a session file.
+A portable Zap is found from a zap.portable file next to zap-cli, or ZAP_PORTABLE_DIR.";
+
+// Decrypted values of a session, from its session file or the app's session proxy
+struct SessionSecrets {
+    session_name: String,
+    box_name: String,
+    values: BTreeMap<String, String>, // Secret name -> value
+}
+
+// Only the part of zap.json the CLI needs - the desktop app and the Python CLI own the rest
+#[derive(Deserialize)]
+struct ProjectContext {
+    current_session: String,
+}
+
+#[derive(Default)]
+struct Options {
+    session: Option<String>,
+    token: Option<String>,
+    prefix: Option<String>,
+    verbose: bool,
+    shell: bool,
+    lenient: bool,
+    positional: Vec<String>,
+    command: Vec<String>, // Everything after `--`
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let Some((subcommand, rest)) = args.split_first() else {
+        eprintln!("{}", USAGE);
+        return ExitCode::from(2);
+    };
+
+    let options = match parse_options(rest) {
+        Ok(options) => options,
+        Err(message) => {
+            eprintln!("zap: {}\n\n{}", message, USAGE);
+            return ExitCode::from(2);
+        }
+    };
+
+    publish_schema();
+
+    let result = match subcommand.as_str() {
+        "list" => list(&options),
+        "run" => run(&options),
+        "export" => export(&options),
+        "render" => render(&options),
+        "-h" | "--help" | "help" => {
+            println!("{}", USAGE);
+            Ok(ExitCode::SUCCESS)
+        }
+        "-V" | "--version" => {
+            println!("zap-cli {}", env!("CARGO_PKG_VERSION"));
+            Ok(ExitCode::SUCCESS)
+        }
+        other => {
+            eprintln!("zap: unknown command '{}'\n\n{}", other, USAGE);
+            Ok(ExitCode::from(2))
+        }
+    };
+
+    // stdout carries only output (export is eval'd), messages go to stderr
+    result.unwrap_or_else(|e| {
+        eprintln!("zap: {}", e);
+        ExitCode::FAILURE
+    })
+}
+
+fn parse_options(args: &[String]) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.iter();
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--" => {
+                options.command = args.cloned().collect();
+                break;
+            }
+            "-s" | "--session" => options.session = Some(option_value(arg, args.next())?),
+            "-t" | "--token" => options.token = Some(option_value(arg, args.next())?),
+            "-p" | "--prefix" => options.prefix = Some(option_value(arg, args.next())?),
+            "-v" | "--verbose" => options.verbose = true,
+            "--shell" => options.shell = true,
+            "--lenient" => options.lenient = true,
+            flag if flag.starts_with('-') => return Err(format!("unknown option '{}'", flag)),
+            _ => options.positional.push(arg.clone()),
+        }
+    }
+
+    Ok(options)
+}
+
+fn option_value(flag: &str, value: Option<&String>) -> Result<String, String> {
+    value
+        .cloned()
+        .ok_or_else(|| format!("option '{}' needs a value", flag))
+}
+
+// ================================
+// COMMANDS
+// ================================
+
+fn list(options: &Options) -> Result<ExitCode, ZapError> {
+    if let Some(session_name) = options.positional.first() {
+        let session = load_session(session_name)?;
+        let mut names: Vec<&String> = session.encrypted_secrets.keys().collect();
+        names.sort();
+
+        println!("Secrets in session '{}':", session.session_name);
+        for name in &names {
+            println!("  {} -> {}", name, env_var_name(name, None));
+        }
+        println!("({} secrets total)", names.len());
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let mut sessions = list_sessions()?;
+    if sessions.is_empty() {
+        println!("No active sessions found. Start one in the Zap app's Dev Mode.");
+        return Ok(ExitCode::SUCCESS);
+    }
+    sessions.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+    let current_session = load_project_context().ok().map(|ctx| ctx.current_session);
+    for session in &sessions {
+        let marker = if current_session.as_deref() == Some(session.session_name.as_str()) {
+            "*"
+        } else {
+            " "
+        };
+        println!(
+            "{} {}  ({} secrets, box: {})",
+            marker,
+            session.session_name,
+            session.encrypted_secrets.len(),
+            session.box_name
+        );
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn run(options: &Options) -> Result<ExitCode, ZapError> {
+    let Some((program, program_args)) = options.command.split_first() else {
+        eprintln!("zap: no command provided (usage: zap-cli run -- npm start)");
+        return Ok(ExitCode::from(2));
+    };
+
+    let secrets = load_secrets(options)?;
+    let env = env_vars(&secrets, options.prefix.as_deref());
+
+    if options.verbose {
+        eprintln!(
+            "Using session '{}' ({} secrets)",
+            secrets.session_name,
+            env.len()
+        );
+        for name in env.keys() {
+            eprintln!("  {}", name);
+        }
+    }
+
+    // Windows needs the shell to resolve npm/yarn .cmd shims
+    let mut process = if cfg!(windows) {
+        let mut process = Command::new("cmd");
+        process.arg("/C").arg(program).args(program_args);
+        process
+    } else {
+        let mut process = Command::new(program);
+        process.args(program_args);
+        process
+    };
+
+    let status = process
+        .envs(&env)
+        .status()
+        .map_err(|e| ZapError::PlatformError(format!("failed to start '{}': {}", program, e)))?;
+
+    Ok(match status.code() {
+        Some(code) => ExitCode::from(code.clamp(0, 255) as u8),
+        None => ExitCode::FAILURE, // Killed by a signal
+    })
+}
+
+fn export(options: &Options) -> Result<ExitCode, ZapError> {
+    let secrets = load_secrets(options)?;
+
+    for (name, value) in env_vars(&secrets, options.prefix.as_deref()) {
+        if options.shell {
+            println!("export {}={}", name, shell_quote(&value));
+        } else {
+            println!("{}={}", name, serde_json::to_string(&value)?);
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn render(options: &Options) -> Result<ExitCode, ZapError> {
+    let secrets = load_secrets(options)?;
+    let mode = if options.lenient {
+        TemplateMode::Lenient
+    } else {
+        TemplateMode::Strict
+    };
+
+    let mut template = String::new();
+    std::io::stdin().read_to_string(&mut template)?;
+
+    let rendered = render_template(&template, mode, |box_name, secret_name| {
+        if name_key(box_name) != name_key(&secrets.box_name) {
+            return Ok(None);
+        }
+        Ok(secrets
+            .values
+            .iter()
+            .find(|(name, _)| name_key(name) == name_key(secret_name))
+            .map(|(_, value)| value.clone()))
+    })?;
+
+    for reference in &rendered.missing {
+        eprintln!("zap: unresolved placeholder {{{{secret:{}}}}}", reference);
+    }
+    print!("{}", rendered.content);
+    Ok(ExitCode::SUCCESS)
+}
+
+// ================================
+// SESSION FILES
+// ================================
+
+// A proxy token wins over session files
+fn load_secrets(options: &Options) -> Result<SessionSecrets, ZapError> {
+    let token = options
+        .token
+        .clone()
+        .or_else(|| std::env::var(TOKEN_ENV).ok())
+        .filter(|token| !token.trim().is_empty());
+    if let Some(token) = token {
+        return fetch_proxy_session(token.trim());
+    }
+
+    let session_name = resolve_session_name(options)?;
+    let session = load_session(&session_name)?;
+    record_activity(&session_name);
+    Ok(decrypt_session(&session))
+}
+
+// Lets the app tell the session is still in use (Settings: dev_session_idle_hours)
+fn record_activity(session_name: &str) {
+    let Ok(sessions_dir) = sessions_directory() else {
+        return;
+    };
+    let path = sessions_dir.join(session_activity_file_name(session_name));
+    let _ = std::fs::write(path, chrono::Utc::now().timestamp().to_string());
+}
+
+fn load_session(session_name: &str) -> Result<CliSessionFile, ZapError> {
+    let path = sessions_directory()?.join(format!("{}.json", session_name));
+    if !path.exists() {
+        return Err(ZapError::SessionNotFound(session_name.to_string()));
+    }
+
+    let content = std::fs::read_to_string(path)?;
+    CliSessionFile::parse(&content)
+}
+
+// Unreadable files are skipped, like the desktop's untracked-file handling
+fn list_sessions() -> Result<Vec<CliSessionFile>, ZapError> {
+    let sessions_dir = sessions_directory()?;
+    if !sessions_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut sessions = Vec::new();
+    for entry in std::fs::read_dir(sessions_dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("json") {
+            continue;
+        }
+        let Ok(content) = std::fs::read_to_string(&path) else {
+            continue;
+        };
+        match CliSessionFile::parse(&content) {
+            Ok(session) => sessions.push(session),
+            Err(e @ ZapError::IncompatibleSessionFormat(_)) => eprintln!("zap: {}", e),
+            Err(_) => {}
+        }
+    }
+    Ok(sessions)
+}
+
+// Secrets that fail to decrypt are reported and left out
+fn decrypt_session(session: &CliSessionFile) -> SessionSecrets {
+    let dev_service = DevService::new();
+    let mut secrets = SessionSecrets {
+        session_name: session.session_name.clone(),
+        box_name: session.box_name.clone(),
+        values: BTreeMap::new(),
+    };
+
+    let session_key = match dev_service.validate_session_key(&session.session_key) {
+        Ok(key) => key,
+        Err(e) => {
+            eprintln!("zap: session '{}': {}", session.session_name, e);
+            return secrets;
+        }
+    };
+
+    for (name, hex_secret) in &session.encrypted_secrets {
+        let value = CliSessionFile::decode_secret(hex_secret)
+            .and_then(|data| dev_service.decrypt_secret_for_cli(&data, &session_key));
+
+        match value {
+            Ok(value) => {
+                secrets.values.insert(name.clone(), value);
+            }
+            Err(e) => eprintln!("zap: failed to decrypt {}: {}", name, e),
+        }
+    }
+    secrets
+}
+
+fn env_vars(secrets: &SessionSecrets, prefix: Option<&str>) -> BTreeMap<String, String> {
+    let prefix = prefix.filter(|p| !p.is_empty());
+    secrets
+        .values
+        .iter()
+        .map(|(name, value)| (env_var_name(name, prefix), value.clone()))
+        .collect()
+}
+
+// ================================
+// SESSION PROXY
+// ================================
+
+// Values come straight from the app's memory, nothing about the session is on disk
+fn fetch_proxy_session(token: &str) -> Result<SessionSecrets, ZapError> {
+    let endpoint_path = get_proxy_endpoint_path()
+        .map_err(|e| ZapError::StorageError(format!("Failed to find the session proxy: {}", e)))?;
+    let endpoint: ProxyEndpoint = std::fs::read_to_string(endpoint_path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .ok_or_else(|| ZapError::PlatformError("the Zap app isn't running".to_string()))?;
+
+    let mut stream = TcpStream::connect(("127.0.0.1", endpoint.port))
+        .map_err(|e| ZapError::PlatformError(format!("the Zap app isn't reachable: {}", e)))?;
+    stream.set_read_timeout(Some(PROXY_TIMEOUT))?;
+
+    let mut request = serde_json::to_string(&ProxyRequest {
+        token: token.to_string(),
+    })?;
+    request.push('\n');
+    stream.write_all(request.as_bytes())?;
+
+    let mut line = String::new();
+    BufReader::new(stream).read_line(&mut line)?;
+    match serde_json::from_str(&line)? {
+        ProxyReply::Secrets {
+            session_name,
+            box_name,
+            secrets,
+        } => Ok(SessionSecrets {
+            session_name,
+            box_name,
+            values: secrets,
+        }),
+        ProxyReply::Error { message } => Err(ZapError::AuthError(message)),
+    }
+}
+
+// Tell the desktop app which session file formats this CLI reads (best effort)
+fn publish_schema() {
+    let Ok(path) = get_cli_schema_path() else {
+        return;
+    };
+    if let (Some(parent), Ok(json)) = (
+        path.parent(),
+        serde_json::to_string_pretty(&SessionFileSchema::current()),
+    ) {
+        let _ = std::fs::create_dir_all(parent).and_then(|_| std::fs::write(&path, json));
+    }
+}
+
+fn sessions_directory() -> Result<PathBuf, ZapError> {
+    get_cli_sessions_directory()
+        .map_err(|e| ZapError::StorageError(format!("Failed to get sessions directory: {}", e)))
+}
+
+fn resolve_session_name(options: &Options) -> Result<String, ZapError> {
+    match &options.session {
+        Some(session_name) => Ok(session_name.clone()),
+        None => load_project_context().map(|ctx| ctx.current_session),
+    }
+}
+
+fn load_project_context() -> Result<ProjectContext, ZapError> {
+    let path = std::env::current_dir()?.join(PROJECT_FILE_NAME);
+    if !path.exists() {
+        return Err(ZapError::NoCurrentSession);
+    }
+
+    let content = std::fs::read_to_string(path)?;
+    serde_json::from_str(&content).map_err(|_| ZapError::InvalidProjectContext)
+}
+
+// POSIX single quoting, same output as Python's shlex.quote
+fn shell_quote(value: &str) -> String {
+    let safe = |c: char| c.is_ascii_alphanumeric() || "@%+=:,./-_".contains(c);
+    if !value.is_empty() && value.chars().all(safe) {
+        return value.to_string();
+    }
+    format!("'{}'", value.replace('\'', "'\"'\"'"))
+}
@@ -0,0 +1,205 @@
+// src/bindings.rs - TypeScript bindings for command inputs/outputs and event payloads
+// Written by the zap-bindings binary (`npm run bindings`), so the frontend types can't drift.
+
+use crate::models::{
+    ActiveSessionInfo, AppAccessRequest, Box, BoxDiff, BoxSearchResult, CompromisedSecret,
+    ConfigFormat, CsvColumnMapping, DataDirectoryInfo, DeepSearchMatch, DevStats,
+    DiagnosticsReport, EmergencyKit, EnvHygieneReport, EnvShredResult, ErrorCode,
+    ExternalSecretsExport, GithubPushReport, GitlabSyncReport, GitlabTarget, HardwareKeyStatus,
+    ImportProgress, ImportResult, JobInfo, LanTransferOffer, LanTransferReceipt, LogEntry,
+    LogSeverity, MergeStrategy, OneTimeShare, ProjectBinding, ProxySessionInfo, ProxySessionToken,
+    RecentSecret, RedeemedShare, RenderedTemplate, ReplicaSyncReport, ReportFormat, RevealHandle,
+    SavedSecret, Secret, SecretSearchResult, SecretTemplate, SessionFileCompatibility,
+    SessionFileEvent, SessionInfo, Settings, SmartImportPreview, SnapshotReason, SshConfigExport,
+    StartupStatus, StatsReport, StorageRecovery, StorageUsage, SyncConflict, SyncDiff, SyncResult,
+    TemplateMode, TfvarsFormat, TransferScope, UndoResult, VaultDiff, VaultSnapshot, VaultStats,
+};
+use crate::session_watcher::SESSION_FILE_EVENT;
+use crate::states::app_state::{
+    APP_ACCESS_EVENT, IMPORT_PROGRESS_EVENT, SESSION_EXPIRING_EVENT, SESSION_STATE_EVENT,
+};
+use crate::states::job_state::JOB_PROGRESS_EVENT;
+use crate::states::DevBoxInfo;
+use crate::window_manager::{SlideInfo, WindowState};
+use std::path::Path;
+use ts_rs::{ExportError, TS};
+
+const GENERATED_HEADER: &str = "// Generated by zap-bindings from the Rust models. Do not edit.";
+
+// What ZapError serializes to (see its Serialize impl), the error side of every command
+#[derive(TS)]
+#[ts(rename = "ZapError")]
+#[allow(dead_code)] // Never built, only here for its TS definition
+struct ZapErrorPayload {
+    code: ErrorCode,
+    message: String,
+    details: Option<String>,
+}
+
+struct EventBinding {
+    constant: &'static str,
+    event: &'static str,
+    payload: String,
+    import: bool, // false for primitives
+}
+
+fn event<T: TS>(constant: &'static str, event: &'static str) -> EventBinding {
+    EventBinding {
+        constant,
+        event,
+        payload: T::name(),
+        import: T::output_path().is_some(),
+    }
+}
+
+fn events() -> Vec<EventBinding> {
+    vec![
+        event::<SessionFileEvent>("SESSION_FILE_EVENT", SESSION_FILE_EVENT),
+        event::<u32>("SESSION_EXPIRING_EVENT", SESSION_EXPIRING_EVENT), // Seconds left
+        event::<SessionInfo>("SESSION_STATE_EVENT", SESSION_STATE_EVENT),
+        event::<ImportProgress>("IMPORT_PROGRESS_EVENT", IMPORT_PROGRESS_EVENT),
+        event::<JobInfo>("JOB_PROGRESS_EVENT", JOB_PROGRESS_EVENT),
+        event::<AppAccessRequest>("APP_ACCESS_EVENT", APP_ACCESS_EVENT),
+    ]
+}
+
+/// Write one file per type, `events.ts` and an `index.ts` barrel into `out_dir`.
+/// Existing .ts files there are removed first so renamed types don't linger.
+pub fn export_bindings(out_dir: &Path) -> Result<(), ExportError> {
+    std::fs::create_dir_all(out_dir)?;
+    for path in generated_files(out_dir)? {
+        std::fs::remove_file(path)?;
+    }
+
+    // Command results and arguments, their nested types are exported along with them
+    ZapErrorPayload::export_all_to(out_dir)?;
+    ActiveSessionInfo::export_all_to(out_dir)?;
+    Box::export_all_to(out_dir)?;
+    BoxDiff::export_all_to(out_dir)?;
+    BoxSearchResult::export_all_to(out_dir)?;
+    CompromisedSecret::export_all_to(out_dir)?;
+    ConfigFormat::export_all_to(out_dir)?;
+    CsvColumnMapping::export_all_to(out_dir)?;
+    DataDirectoryInfo::export_all_to(out_dir)?;
+    DeepSearchMatch::export_all_to(out_dir)?;
+    DevBoxInfo::export_all_to(out_dir)?;
+    DevStats::export_all_to(out_dir)?;
+    DiagnosticsReport::export_all_to(out_dir)?;
+    EmergencyKit::export_all_to(out_dir)?;
+    EnvHygieneReport::export_all_to(out_dir)?;
+    EnvShredResult::export_all_to(out_dir)?;
+    ExternalSecretsExport::export_all_to(out_dir)?;
+    GithubPushReport::export_all_to(out_dir)?;
+    GitlabSyncReport::export_all_to(out_dir)?;
+    GitlabTarget::export_all_to(out_dir)?;
+    HardwareKeyStatus::export_all_to(out_dir)?;
+    ImportResult::export_all_to(out_dir)?;
+    JobInfo::export_all_to(out_dir)?;
+    LanTransferOffer::export_all_to(out_dir)?;
+    LanTransferReceipt::export_all_to(out_dir)?;
+    LogEntry::export_all_to(out_dir)?;
+    LogSeverity::export_all_to(out_dir)?;
+    MergeStrategy::export_all_to(out_dir)?;
+    OneTimeShare::export_all_to(out_dir)?;
+    ProjectBinding::export_all_to(out_dir)?;
+    ProxySessionInfo::export_all_to(out_dir)?;
+    ProxySessionToken::export_all_to(out_dir)?;
+    RecentSecret::export_all_to(out_dir)?;
+    RedeemedShare::export_all_to(out_dir)?;
+    RenderedTemplate::export_all_to(out_dir)?;
+    ReplicaSyncReport::export_all_to(out_dir)?;
+    ReportFormat::export_all_to(out_dir)?;
+    RevealHandle::export_all_to(out_dir)?;
+    SavedSecret::export_all_to(out_dir)?;
+    Secret::export_all_to(out_dir)?;
+    SecretSearchResult::export_all_to(out_dir)?;
+    SecretTemplate::export_all_to(out_dir)?;
+    SessionFileCompatibility::export_all_to(out_dir)?;
+    SessionInfo::export_all_to(out_dir)?;
+    Settings::export_all_to(out_dir)?;
+    SlideInfo::export_all_to(out_dir)?;
+    SmartImportPreview::export_all_to(out_dir)?;
+    SnapshotReason::export_all_to(out_dir)?;
+    SshConfigExport::export_all_to(out_dir)?;
+    StartupStatus::export_all_to(out_dir)?;
+    StatsReport::export_all_to(out_dir)?;
+    StorageRecovery::export_all_to(out_dir)?;
+    StorageUsage::export_all_to(out_dir)?;
+    SyncConflict::export_all_to(out_dir)?;
+    SyncDiff::export_all_to(out_dir)?;
+    SyncResult::export_all_to(out_dir)?;
+    TemplateMode::export_all_to(out_dir)?;
+    TfvarsFormat::export_all_to(out_dir)?;
+    TransferScope::export_all_to(out_dir)?;
+    UndoResult::export_all_to(out_dir)?;
+    VaultDiff::export_all_to(out_dir)?;
+    VaultSnapshot::export_all_to(out_dir)?;
+    VaultStats::export_all_to(out_dir)?;
+    WindowState::export_all_to(out_dir)?;
+
+    // Event payloads
+    SessionFileEvent::export_all_to(out_dir)?;
+    ImportProgress::export_all_to(out_dir)?;
+    AppAccessRequest::export_all_to(out_dir)?;
+
+    std::fs::write(out_dir.join("events.ts"), render_events(&events()))?;
+    write_index(out_dir)
+}
+
+// Event names as constants plus a name -> payload map for typed listen() wrappers
+fn render_events(events: &[EventBinding]) -> String {
+    let mut lines = vec![GENERATED_HEADER.to_string()];
+    for binding in events.iter().filter(|binding| binding.import) {
+        lines.push(format!(
+            "import type {{ {0} }} from \"./{0}\";",
+            binding.payload
+        ));
+    }
+
+    lines.push(String::new());
+    for binding in events {
+        lines.push(format!(
+            "export const {} = \"{}\";",
+            binding.constant, binding.event
+        ));
+    }
+
+    lines.push(String::new());
+    lines.push("export type ZapEventPayloads = {".to_string());
+    for binding in events {
+        lines.push(format!("  \"{}\": {};", binding.event, binding.payload));
+    }
+    lines.push("};".to_string());
+    lines.push(String::new());
+    lines.join("\n")
+}
+
+fn write_index(out_dir: &Path) -> Result<(), ExportError> {
+    let mut modules: Vec<String> = generated_files(out_dir)?
+        .iter()
+        .filter_map(|path| path.file_stem()?.to_str().map(str::to_string))
+        .filter(|module| module != "index")
+        .collect();
+    modules.sort();
+
+    let mut lines = vec![GENERATED_HEADER.to_string()];
+    lines.extend(
+        modules
+            .iter()
+            .map(|module| format!("export * from \"./{}\";", module)),
+    );
+    lines.push(String::new());
+    std::fs::write(out_dir.join("index.ts"), lines.join("\n"))?;
+    Ok(())
+}
+
+fn generated_files(out_dir: &Path) -> Result<Vec<std::path::PathBuf>, ExportError> {
+    let mut files = Vec::new();
+    for entry in std::fs::read_dir(out_dir)? {
+        let path = entry?.path();
+        if path.extension().and_then(|s| s.to_str()) == Some("ts") {
+            files.push(path);
+        }
+    }
+    Ok(files)
+}
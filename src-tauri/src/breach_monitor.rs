@@ -0,0 +1,56 @@
+// src/breach_monitor.rs - Scheduled Have I Been Pwned checks of passwords (Settings: breach_check)
+
+use crate::models::CompromisedSecret;
+use crate::states::AppState;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Periodically check passwords when a check is due (only while unlocked, values are decrypted)
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            let app_state = app.state::<Arc<AppState>>().inner().clone();
+            if app_state.is_locked() {
+                continue;
+            }
+
+            match app_state.run_breach_check(false, None).await {
+                Ok(found) if !found.is_empty() => notify(&app, &found),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Breach check failed: {}", e),
+            }
+        }
+    });
+}
+
+fn notify(app: &AppHandle, found: &[CompromisedSecret]) {
+    let body = match found {
+        [secret] => format!(
+            "'{}' appears in a known data breach - change it",
+            secret.item.name
+        ),
+        [first, rest @ ..] => format!(
+            "'{}' and {} other password(s) appear in known data breaches",
+            first.item.name,
+            rest.len()
+        ),
+        [] => return,
+    };
+
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title("Zap - compromised password")
+        .body(body)
+        .show()
+    {
+        tracing::warn!("Failed to show breach notification: {}", e);
+    }
+}
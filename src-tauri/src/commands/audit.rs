@@ -0,0 +1,110 @@
+// src/commands/audit.rs - Records every command invoke (name, redacted args, outcome, duration)
+
+use crate::models::{LogAction, ZapError};
+use crate::states::AppState;
+use std::fmt::Debug;
+use std::time::Instant;
+
+const MAX_ARG_LENGTH: usize = 48;
+
+// Opt-out: commands polled by the UI or fired on every keystroke / animation frame
+const UNAUDITED_COMMANDS: &[&str] = &[
+    "get_startup_status",
+    "is_vault_locked",
+    "get_session_info",
+    "touch_session",
+    "get_job",
+    "list_jobs",
+    "get_all_sessions",
+    "validate_session_name",
+    "toggle_visibility",
+    "get_window_state",
+    "show_sidebar",
+    "hide_sidebar",
+    "set_slide_offset",
+    "get_slide_info",
+];
+
+// Arguments that carry secret material (or search it) are never written to the log
+const REDACTED_ARGS: &[&str] = &[
+    "blob",
+    "code",
+    "content",
+    "encrypted",
+    "env_content",
+    "handle",
+    "identity",
+    "json_data",
+    "new_password",
+    "passphrase",
+    "password",
+    "pin",
+    "query",
+    "recovery_key",
+    "session_key_hex",
+    "template",
+    "template_fields",
+    "value",
+];
+
+pub struct CommandAudit {
+    command: &'static str,
+    args: Vec<String>,
+    started: Instant,
+}
+
+impl CommandAudit {
+    pub fn new(command: &'static str) -> Self {
+        Self {
+            command,
+            args: Vec::new(),
+            started: Instant::now(),
+        }
+    }
+
+    /// Add an argument to the summary, redacted when its name is in REDACTED_ARGS
+    pub fn arg(mut self, name: &str, value: &impl Debug) -> Self {
+        let summary = if REDACTED_ARGS.contains(&name) {
+            "[redacted]".to_string()
+        } else {
+            truncate(format!("{:?}", value))
+        };
+        self.args.push(format!("{}={}", name, summary));
+        self
+    }
+
+    /// Log the outcome and pass the result through unchanged
+    pub fn finish<T>(
+        self,
+        app_state: &AppState,
+        result: Result<T, ZapError>,
+    ) -> Result<T, ZapError> {
+        if UNAUDITED_COMMANDS.contains(&self.command) {
+            return result;
+        }
+
+        let outcome = match &result {
+            Ok(_) => "ok".to_string(),
+            Err(e) => format!("failed ({:?})", e.code()),
+        };
+        let args = (!self.args.is_empty()).then(|| self.args.join(", "));
+        let _ = app_state.storage.log(
+            LogAction::Command,
+            format!(
+                "{} {} in {} ms",
+                self.command,
+                outcome,
+                self.started.elapsed().as_millis()
+            ),
+            args,
+        );
+        result
+    }
+}
+
+fn truncate(summary: String) -> String {
+    match summary.char_indices().nth(MAX_ARG_LENGTH) {
+        Some((end, _)) => format!("{}...", &summary[..end]),
+        None => summary,
+    }
+}
@@ -1,45 +1,166 @@
 // src/commands/auth_commands.rs
 
-use crate::models::SessionInfo;
+use crate::commands::audit::CommandAudit;
+use crate::models::{
+    EmergencyKit, HardwareKeyStatus, SessionInfo, StartupStatus, StorageRecovery, ZapError,
+};
 use crate::states::AppState;
 use std::sync::Arc;
 use tauri::State;
 
 #[tauri::command]
-pub async fn initialize_app(app_state: State<'_, Arc<AppState>>) -> Result<bool, String> {
-    app_state.initialize().await.map_err(|e| e.to_string())
+pub async fn initialize_app(app_state: State<'_, Arc<AppState>>) -> Result<bool, ZapError> {
+    let audit = CommandAudit::new("initialize_app");
+    audit.finish(&app_state, app_state.initialize().await)
+}
+
+#[tauri::command]
+pub async fn get_startup_status(
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<StartupStatus, ZapError> {
+    let audit = CommandAudit::new("get_startup_status");
+    audit.finish(&app_state, Ok(app_state.get_startup_status()))
+}
+
+#[tauri::command]
+pub async fn recover_storage(
+    action: StorageRecovery,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<StartupStatus, ZapError> {
+    let audit = CommandAudit::new("recover_storage").arg("action", &action);
+    audit.finish(&app_state, app_state.recover_storage(action))
 }
 
 #[tauri::command]
 pub async fn unlock_vault(
     password: String,
     app_state: State<'_, Arc<AppState>>,
-) -> Result<bool, String> {
-    app_state.unlock(&password).await.map_err(|e| e.to_string())
+) -> Result<bool, ZapError> {
+    let audit = CommandAudit::new("unlock_vault").arg("password", &password);
+    audit.finish(&app_state, app_state.unlock(&password).await)
 }
 
 #[tauri::command]
-pub async fn lock_vault(app_state: State<'_, Arc<AppState>>) -> Result<(), String> {
-    app_state.lock();
-    Ok(())
+pub async fn lock_vault(app_state: State<'_, Arc<AppState>>) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("lock_vault");
+    let result = async {
+        app_state.lock();
+        Ok(())
+    }
+    .await;
+    audit.finish(&app_state, result)
 }
 
 #[tauri::command]
-pub async fn is_vault_locked(app_state: State<'_, Arc<AppState>>) -> Result<bool, String> {
-    Ok(app_state.is_locked())
+pub async fn is_vault_locked(app_state: State<'_, Arc<AppState>>) -> Result<bool, ZapError> {
+    let audit = CommandAudit::new("is_vault_locked");
+    audit.finish(&app_state, Ok(app_state.is_locked()))
 }
 
 #[tauri::command]
 pub async fn verify_master_password(
     password: String,
     app_state: State<'_, Arc<AppState>>,
-) -> Result<(), String> {
-    app_state
-        .verify_password(&password)
-        .map_err(|e| e.to_string())
+) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("verify_master_password").arg("password", &password);
+    audit.finish(&app_state, app_state.verify_password(&password))
+}
+
+#[tauri::command]
+pub async fn get_session_info(
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<SessionInfo, ZapError> {
+    let audit = CommandAudit::new("get_session_info");
+    audit.finish(&app_state, Ok(app_state.get_session_info()))
+}
+
+#[tauri::command]
+pub async fn unlock_with_remembered_key(
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<bool, ZapError> {
+    let audit = CommandAudit::new("unlock_with_remembered_key");
+    audit.finish(&app_state, app_state.unlock_with_remembered_key().await)
+}
+
+#[tauri::command]
+pub async fn touch_session(app_state: State<'_, Arc<AppState>>) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("touch_session");
+    audit.finish(&app_state, app_state.touch_session())
+}
+
+#[tauri::command]
+pub async fn get_emergency_kit(
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<Option<EmergencyKit>, ZapError> {
+    let audit = CommandAudit::new("get_emergency_kit");
+    audit.finish(&app_state, app_state.take_emergency_kit())
+}
+
+#[tauri::command]
+pub async fn regenerate_recovery_key(
+    password: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<EmergencyKit, ZapError> {
+    let audit = CommandAudit::new("regenerate_recovery_key").arg("password", &password);
+    audit.finish(
+        &app_state,
+        app_state.regenerate_recovery_key(&password).await,
+    )
+}
+
+#[tauri::command]
+pub async fn unlock_with_recovery_key(
+    recovery_key: String,
+    new_password: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<EmergencyKit, ZapError> {
+    let audit = CommandAudit::new("unlock_with_recovery_key")
+        .arg("recovery_key", &recovery_key)
+        .arg("new_password", &new_password);
+    audit.finish(
+        &app_state,
+        app_state
+            .unlock_with_recovery_key(&recovery_key, &new_password)
+            .await,
+    )
 }
 
 #[tauri::command]
-pub async fn get_session_info(app_state: State<'_, Arc<AppState>>) -> Result<SessionInfo, String> {
-    Ok(app_state.get_session_info())
+pub async fn get_hardware_key_status(
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<HardwareKeyStatus, ZapError> {
+    let audit = CommandAudit::new("get_hardware_key_status");
+    audit.finish(&app_state, Ok(app_state.get_hardware_key_status()))
+}
+
+#[tauri::command]
+pub async fn enable_hardware_key(
+    password: String,
+    pin: String,
+    slot: u8,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<EmergencyKit, ZapError> {
+    let audit = CommandAudit::new("enable_hardware_key")
+        .arg("password", &password)
+        .arg("pin", &pin)
+        .arg("slot", &slot);
+    audit.finish(
+        &app_state,
+        app_state.enable_hardware_key(&password, &pin, slot).await,
+    )
+}
+
+#[tauri::command]
+pub async fn disable_hardware_key(
+    pin: String,
+    new_password: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<EmergencyKit, ZapError> {
+    let audit = CommandAudit::new("disable_hardware_key")
+        .arg("pin", &pin)
+        .arg("new_password", &new_password);
+    audit.finish(
+        &app_state,
+        app_state.disable_hardware_key(&pin, &new_password).await,
+    )
 }
@@ -1,18 +1,27 @@
 // src/commands/box_commands.rs
 
-use crate::models::Box;
+use crate::commands::audit::CommandAudit;
+use crate::models::{Box, BoxDiff, BoxSearchResult, UndoResult, ZapError};
 use crate::states::AppState;
+use crate::utils::input_validation as input;
 use std::sync::Arc;
 use tauri::State;
 
 #[tauri::command]
-pub async fn get_all_boxes(app_state: State<'_, Arc<AppState>>) -> Result<Vec<Box>, String> {
-    app_state.get_all_boxes().await.map_err(|e| e.to_string())
+pub async fn get_all_boxes(app_state: State<'_, Arc<AppState>>) -> Result<Vec<Box>, ZapError> {
+    let audit = CommandAudit::new("get_all_boxes");
+    audit.finish(&app_state, app_state.get_all_boxes().await)
 }
 
 #[tauri::command]
-pub async fn get_box(box_id: String, app_state: State<'_, Arc<AppState>>) -> Result<Box, String> {
-    app_state.get_box(&box_id).await.map_err(|e| e.to_string())
+pub async fn get_box(box_id: String, app_state: State<'_, Arc<AppState>>) -> Result<Box, ZapError> {
+    let audit = CommandAudit::new("get_box").arg("box_id", &box_id);
+    let result = async {
+        let box_id = input::id("Box id", box_id)?;
+        app_state.get_box(&box_id).await
+    }
+    .await;
+    audit.finish(&app_state, result)
 }
 
 #[tauri::command]
@@ -20,13 +29,30 @@ pub async fn create_box(
     name: String,
     description: Option<String>,
     tags: Vec<String>,
-    dev_mode: bool, 
+    dev_mode: bool,
     app_state: State<'_, Arc<AppState>>,
-) -> Result<String, String> {
-    app_state
-        .create_box(name, description, tags, dev_mode)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<String, ZapError> {
+    let audit = CommandAudit::new("create_box")
+        .arg("name", &name)
+        .arg("description", &description)
+        .arg("tags", &tags)
+        .arg("dev_mode", &dev_mode);
+    let result = async {
+        let name = input::required_text("Box name", name, input::MAX_BOX_NAME_LENGTH)?;
+        let description = input::optional_text(
+            "Box description",
+            description,
+            input::MAX_DESCRIPTION_LENGTH,
+        )?;
+        let tags = input::tags(tags)?;
+        app_state.touched(
+            app_state
+                .create_box(name, description, tags, dev_mode)
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
 }
 
 #[tauri::command]
@@ -37,33 +63,74 @@ pub async fn update_box(
     tags: Option<Vec<String>>,
     dev_mode: Option<bool>,
     app_state: State<'_, Arc<AppState>>,
-) -> Result<(), String> {
-    app_state
-        .update_box(&box_id, name, description, tags, dev_mode)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("update_box")
+        .arg("box_id", &box_id)
+        .arg("name", &name)
+        .arg("description", &description)
+        .arg("tags", &tags)
+        .arg("dev_mode", &dev_mode);
+    let result = async {
+        let box_id = input::id("Box id", box_id)?;
+        let name = name
+            .map(|name| input::required_text("Box name", name, input::MAX_BOX_NAME_LENGTH))
+            .transpose()?;
+        let description = description
+            .map(|desc| {
+                input::optional_text("Box description", desc, input::MAX_DESCRIPTION_LENGTH)
+            })
+            .transpose()?;
+        let tags = tags.map(input::tags).transpose()?;
+        app_state.touched(
+            app_state
+                .update_box(&box_id, name, description, tags, dev_mode)
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
 }
 
 #[tauri::command]
 pub async fn delete_box(
     box_id: String,
     app_state: State<'_, Arc<AppState>>,
-) -> Result<(), String> {
-    app_state
-        .delete_box(&box_id)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("delete_box").arg("box_id", &box_id);
+    let result = async {
+        let box_id = input::id("Box id", box_id)?;
+        app_state.touched(app_state.delete_box(&box_id).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
 }
 
 #[tauri::command]
 pub async fn delete_selected_boxes(
     box_ids: Vec<String>,
     app_state: State<'_, Arc<AppState>>,
-) -> Result<Vec<String>, String> {
-    app_state
-        .delete_selected_boxes(box_ids)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<Vec<String>, ZapError> {
+    let audit = CommandAudit::new("delete_selected_boxes").arg("box_ids", &box_ids);
+    let result = async {
+        let box_ids = input::ids("Box id", box_ids)?;
+        app_state.touched(app_state.delete_selected_boxes(box_ids).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn detach_shared_box(
+    box_id: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<Box, ZapError> {
+    let audit = CommandAudit::new("detach_shared_box").arg("box_id", &box_id);
+    let result = async {
+        let box_id = input::id("Box id", box_id)?;
+        app_state.touched(app_state.detach_shared_box(&box_id).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
 }
 
 #[tauri::command]
@@ -71,9 +138,45 @@ pub async fn search_boxes_global(
     query: String,
     tags: Vec<String>,
     app_state: State<'_, Arc<AppState>>,
-) -> Result<Vec<Box>, String> {
-    app_state
-        .search_boxes_global(query, tags)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<Vec<BoxSearchResult>, ZapError> {
+    let audit = CommandAudit::new("search_boxes_global")
+        .arg("query", &query)
+        .arg("tags", &tags);
+    let result = async {
+        let query = input::text("Search query", query, input::MAX_QUERY_LENGTH)?;
+        let tags = input::tags(tags)?;
+        app_state.touched(app_state.search_boxes_global(query, tags).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn diff_boxes(
+    box_a_id: String,
+    box_b_id: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<BoxDiff, ZapError> {
+    let audit = CommandAudit::new("diff_boxes")
+        .arg("box_a_id", &box_a_id)
+        .arg("box_b_id", &box_b_id);
+    let result = async {
+        let box_a_id = input::id("Box id", box_a_id)?;
+        let box_b_id = input::id("Box id", box_b_id)?;
+        app_state.touched(app_state.diff_boxes(&box_a_id, &box_b_id).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+// Deleted boxes and secrets, and values overwritten by an import, for a few minutes afterwards
+#[tauri::command]
+pub async fn undo_last_operation(
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<UndoResult, ZapError> {
+    let audit = CommandAudit::new("undo_last_operation");
+    audit.finish(
+        &app_state,
+        app_state.touched(app_state.undo_last_operation().await),
+    )
 }
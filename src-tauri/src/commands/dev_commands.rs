@@ -1,123 +1,570 @@
 // src/commands/dev_commands.rs
 
-use crate::models::{ActiveSessionInfo, DevStats};
-use crate::states::{DevBoxInfo, DevState};
+use crate::commands::audit::CommandAudit;
+use crate::models::{
+    ActiveSessionInfo, DevStats, EnvHygieneReport, EnvShredResult, ProdApproval, ProjectBinding,
+    ProxySessionInfo, ProxySessionToken, RenderedTemplate, SecretSelection,
+    SessionFileCompatibility, SshConfigExport, TemplateMode, ZapError,
+};
+use crate::states::{AppState, DevBoxInfo, DevState};
+use crate::utils::input_validation as input;
+use std::sync::Arc;
 use tauri::State;
 
+// include / exclude pick secrets by name, leaving both out copies the whole box
+#[allow(clippy::too_many_arguments)]
 #[tauri::command]
 pub async fn create_session(
     session_name: String,
     box_id: String,
+    environment: Option<String>,
+    include: Option<Vec<String>>,
+    exclude: Option<Vec<String>>,
+    password: Option<String>,
+    justification: Option<String>,
     dev_state: State<'_, tokio::sync::Mutex<DevState>>,
-) -> Result<(), String> {
-    let dev_state_guard = dev_state.lock().await;
-    dev_state_guard
-        .create_session(session_name, box_id)
-        .await
-        .map_err(|e| e.to_string())
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("create_session")
+        .arg("session_name", &session_name)
+        .arg("box_id", &box_id)
+        .arg("include", &include)
+        .arg("exclude", &exclude)
+        .arg("password", &password);
+    let result = async {
+        let session_name =
+            input::required_text("Session name", session_name, input::MAX_SESSION_NAME_LENGTH)?;
+        let box_id = input::id("Box id", box_id)?;
+        let environment = input::optional_environment(environment)?;
+        let selection = secret_selection(include, exclude)?;
+        let approval = prod_approval(password, justification)?;
+        let dev_state_guard = dev_state.lock().await;
+        dev_state_guard.app_state.touched(
+            dev_state_guard
+                .create_session(session_name, box_id, environment, selection, approval)
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+// Returns the token once - the CLI passes it as --token or ZAP_SESSION_TOKEN
+#[allow(clippy::too_many_arguments)]
+#[tauri::command]
+pub async fn create_proxy_session(
+    session_name: String,
+    box_id: String,
+    environment: Option<String>,
+    ttl_minutes: u32,
+    include: Option<Vec<String>>,
+    exclude: Option<Vec<String>>,
+    password: Option<String>,
+    justification: Option<String>,
+    dev_state: State<'_, tokio::sync::Mutex<DevState>>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<ProxySessionToken, ZapError> {
+    let audit = CommandAudit::new("create_proxy_session")
+        .arg("session_name", &session_name)
+        .arg("box_id", &box_id)
+        .arg("ttl_minutes", &ttl_minutes)
+        .arg("include", &include)
+        .arg("exclude", &exclude)
+        .arg("password", &password);
+    let result = async {
+        let session_name =
+            input::required_text("Session name", session_name, input::MAX_SESSION_NAME_LENGTH)?;
+        let box_id = input::id("Box id", box_id)?;
+        let environment = input::optional_environment(environment)?;
+        let selection = secret_selection(include, exclude)?;
+        let approval = prod_approval(password, justification)?;
+        let dev_state_guard = dev_state.lock().await;
+        dev_state_guard.app_state.touched(
+            dev_state_guard
+                .create_proxy_session(
+                    session_name,
+                    box_id,
+                    environment,
+                    ttl_minutes,
+                    selection,
+                    approval,
+                )
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn get_proxy_sessions(
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<Vec<ProxySessionInfo>, ZapError> {
+    let audit = CommandAudit::new("get_proxy_sessions");
+    audit.finish(&app_state, app_state.get_proxy_sessions())
+}
+
+#[tauri::command]
+pub async fn revoke_proxy_session(
+    session_name: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("revoke_proxy_session").arg("session_name", &session_name);
+    let result = async {
+        let session_name =
+            input::required_text("Session name", session_name, input::MAX_SESSION_NAME_LENGTH)?;
+        app_state.touched(app_state.revoke_proxy_session(&session_name))
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+// Reply to an "app-access-requested" event; remember adds the program to Settings: app_access
+#[tauri::command]
+pub async fn answer_app_access(
+    request_id: String,
+    allow: bool,
+    remember: bool,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("answer_app_access")
+        .arg("request_id", &request_id)
+        .arg("allow", &allow)
+        .arg("remember", &remember);
+    let result = async {
+        let request_id = input::id("Request id", request_id)?;
+        app_state.touched(app_state.answer_app_access(&request_id, allow, remember))
+    }
+    .await;
+    audit.finish(&app_state, result)
 }
 
 #[tauri::command]
 pub async fn get_all_sessions(
     dev_state: State<'_, tokio::sync::Mutex<DevState>>,
-) -> Result<Vec<ActiveSessionInfo>, String> {
-    let dev_state_guard = dev_state.lock().await;
-    dev_state_guard
-        .get_all_sessions()
-        .await
-        .map_err(|e| e.to_string())
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<Vec<ActiveSessionInfo>, ZapError> {
+    let audit = CommandAudit::new("get_all_sessions");
+    // Polled by the mini window, so it must not count as activity and keep the vault unlocked
+    let result = async {
+        let dev_state_guard = dev_state.lock().await;
+        dev_state_guard.get_all_sessions().await
+    }
+    .await;
+    audit.finish(&app_state, result)
 }
 
 #[tauri::command]
 pub async fn get_dev_session_info(
     session_name: String,
     dev_state: State<'_, tokio::sync::Mutex<DevState>>,
-) -> Result<Option<ActiveSessionInfo>, String> {
-    let dev_state_guard = dev_state.lock().await;
-    dev_state_guard
-        .get_session_info(&session_name)
-        .await
-        .map_err(|e| e.to_string())
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<Option<ActiveSessionInfo>, ZapError> {
+    let audit = CommandAudit::new("get_dev_session_info").arg("session_name", &session_name);
+    let result = async {
+        let session_name =
+            input::required_text("Session name", session_name, input::MAX_SESSION_NAME_LENGTH)?;
+        let dev_state_guard = dev_state.lock().await;
+        dev_state_guard.get_session_info(&session_name).await
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn export_session_as_env(
+    session_name: String,
+    prefix: Option<String>,
+    dev_state: State<'_, tokio::sync::Mutex<DevState>>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<String, ZapError> {
+    let audit = CommandAudit::new("export_session_as_env")
+        .arg("session_name", &session_name)
+        .arg("prefix", &prefix);
+    let result = async {
+        let session_name =
+            input::required_text("Session name", session_name, input::MAX_SESSION_NAME_LENGTH)?;
+        let prefix = input::optional_text("Prefix", prefix, input::MAX_QUERY_LENGTH)?;
+        let dev_state_guard = dev_state.lock().await;
+        dev_state_guard.app_state.touched(
+            dev_state_guard
+                .export_session_as_env(&session_name, prefix.as_deref())
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn export_session_as_ssh_config(
+    session_name: String,
+    dev_state: State<'_, tokio::sync::Mutex<DevState>>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<SshConfigExport, ZapError> {
+    let audit =
+        CommandAudit::new("export_session_as_ssh_config").arg("session_name", &session_name);
+    let result = async {
+        let session_name =
+            input::required_text("Session name", session_name, input::MAX_SESSION_NAME_LENGTH)?;
+        let dev_state_guard = dev_state.lock().await;
+        dev_state_guard.app_state.touched(
+            dev_state_guard
+                .export_session_as_ssh_config(&session_name)
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn render_template_from_session(
+    session_name: String,
+    template: String,
+    mode: Option<TemplateMode>,
+    dev_state: State<'_, tokio::sync::Mutex<DevState>>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<RenderedTemplate, ZapError> {
+    let audit = CommandAudit::new("render_template_from_session")
+        .arg("session_name", &session_name)
+        .arg("template", &template)
+        .arg("mode", &mode);
+    let result = async {
+        let session_name =
+            input::required_text("Session name", session_name, input::MAX_SESSION_NAME_LENGTH)?;
+        let template = input::template(template)?;
+        let dev_state_guard = dev_state.lock().await;
+        dev_state_guard.app_state.touched(
+            dev_state_guard
+                .render_template_from_session(&session_name, &template, mode.unwrap_or_default())
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+// Keeps the session key, so running shells aren't affected
+#[tauri::command]
+pub async fn rename_session(
+    old_name: String,
+    new_name: String,
+    dev_state: State<'_, tokio::sync::Mutex<DevState>>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("rename_session")
+        .arg("old_name", &old_name)
+        .arg("new_name", &new_name);
+    let result = async {
+        let old_name =
+            input::required_text("Session name", old_name, input::MAX_SESSION_NAME_LENGTH)?;
+        let new_name =
+            input::required_text("New session name", new_name, input::MAX_SESSION_NAME_LENGTH)?;
+        let dev_state_guard = dev_state.lock().await;
+        dev_state_guard
+            .app_state
+            .touched(dev_state_guard.rename_session(old_name, new_name).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
 }
 
 #[tauri::command]
 pub async fn stop_session(
     session_name: String,
     dev_state: State<'_, tokio::sync::Mutex<DevState>>,
-) -> Result<(), String> {
-    let dev_state_guard = dev_state.lock().await;
-    dev_state_guard
-        .stop_session(session_name)
-        .await
-        .map_err(|e| e.to_string())
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("stop_session").arg("session_name", &session_name);
+    let result = async {
+        let session_name =
+            input::required_text("Session name", session_name, input::MAX_SESSION_NAME_LENGTH)?;
+        let dev_state_guard = dev_state.lock().await;
+        dev_state_guard
+            .app_state
+            .touched(dev_state_guard.stop_session(session_name).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
 }
 
 #[tauri::command]
 pub async fn clear_all_sessions(
     dev_state: State<'_, tokio::sync::Mutex<DevState>>,
-) -> Result<(), String> {
-    let dev_state_guard = dev_state.lock().await;
-    dev_state_guard
-        .clear_all_sessions()
-        .await
-        .map_err(|e| e.to_string())
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("clear_all_sessions");
+    let result = async {
+        let dev_state_guard = dev_state.lock().await;
+        dev_state_guard
+            .app_state
+            .touched(dev_state_guard.clear_all_sessions().await)
+    }
+    .await;
+    audit.finish(&app_state, result)
 }
 
 #[tauri::command]
 pub async fn has_any_sessions(
     dev_state: State<'_, tokio::sync::Mutex<DevState>>,
-) -> Result<bool, String> {
-    let dev_state_guard = dev_state.lock().await;
-    dev_state_guard
-        .has_any_sessions()
-        .await
-        .map_err(|e| e.to_string())
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<bool, ZapError> {
+    let audit = CommandAudit::new("has_any_sessions");
+    let result = async {
+        let dev_state_guard = dev_state.lock().await;
+        dev_state_guard.has_any_sessions().await
+    }
+    .await;
+    audit.finish(&app_state, result)
 }
 
 #[tauri::command]
 pub async fn get_available_dev_boxes(
     dev_state: State<'_, tokio::sync::Mutex<DevState>>,
-) -> Result<Vec<DevBoxInfo>, String> {
-    let dev_state_guard = dev_state.lock().await;
-    dev_state_guard
-        .get_available_dev_boxes()
-        .await
-        .map_err(|e| e.to_string())
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<Vec<DevBoxInfo>, ZapError> {
+    let audit = CommandAudit::new("get_available_dev_boxes");
+    let result = async {
+        let dev_state_guard = dev_state.lock().await;
+        dev_state_guard.get_available_dev_boxes().await
+    }
+    .await;
+    audit.finish(&app_state, result)
 }
 
 #[tauri::command]
 pub async fn get_dev_stats(
     dev_state: State<'_, tokio::sync::Mutex<DevState>>,
-) -> Result<DevStats, String> {
-    let dev_state_guard = dev_state.lock().await;
-    dev_state_guard
-        .get_dev_stats()
-        .await
-        .map_err(|e| e.to_string())
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<DevStats, ZapError> {
+    let audit = CommandAudit::new("get_dev_stats");
+    let result = async {
+        let dev_state_guard = dev_state.lock().await;
+        dev_state_guard.get_dev_stats().await
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn get_session_file_schema(
+    dev_state: State<'_, tokio::sync::Mutex<DevState>>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<SessionFileCompatibility, ZapError> {
+    let audit = CommandAudit::new("get_session_file_schema");
+    let result = async {
+        let dev_state_guard = dev_state.lock().await;
+        dev_state_guard.get_session_file_schema().await
+    }
+    .await;
+    audit.finish(&app_state, result)
 }
 
 #[tauri::command]
 pub async fn validate_session_name(
     session_name: String,
     dev_state: State<'_, tokio::sync::Mutex<DevState>>,
-) -> Result<bool, String> {
-    let dev_state_guard = dev_state.lock().await;
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<bool, ZapError> {
+    let audit = CommandAudit::new("validate_session_name").arg("session_name", &session_name);
+    let result = async {
+        let session_name =
+            input::required_text("Session name", session_name, input::MAX_SESSION_NAME_LENGTH)?;
+        let dev_state_guard = dev_state.lock().await;
 
-    // Use the dedicated public method which handles both validation and the database check.
-    dev_state_guard
-        .is_session_name_available(&session_name)
-        .await
-        .map_err(|e| e.to_string())
+        // Use the dedicated public method which handles both validation and the database check.
+        dev_state_guard
+            .is_session_name_available(&session_name)
+            .await
+    }
+    .await;
+    audit.finish(&app_state, result)
 }
 
 #[tauri::command]
 pub async fn validate_session_key(
     session_key_hex: String,
     dev_state: State<'_, tokio::sync::Mutex<DevState>>,
-) -> Result<bool, String> {
-    let dev_state_guard = dev_state.lock().await;
-    match dev_state_guard.validate_session_key(&session_key_hex) {
-        Ok(_) => Ok(true),
-        Err(_) => Ok(false),
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<bool, ZapError> {
+    let audit = CommandAudit::new("validate_session_key").arg("session_key_hex", &session_key_hex);
+    let result = async {
+        let dev_state_guard = dev_state.lock().await;
+        match dev_state_guard.validate_session_key(&session_key_hex) {
+            Ok(_) => Ok(true),
+            Err(_) => Ok(false),
+        }
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn bind_project(
+    project_path: String,
+    box_id: String,
+    session_name: String,
+    dev_state: State<'_, tokio::sync::Mutex<DevState>>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<ProjectBinding, ZapError> {
+    let audit = CommandAudit::new("bind_project")
+        .arg("project_path", &project_path)
+        .arg("box_id", &box_id)
+        .arg("session_name", &session_name);
+    let result = async {
+        let project_path =
+            input::required_text("Project path", project_path, input::MAX_PATH_LENGTH)?;
+        let box_id = input::id("Box id", box_id)?;
+        let session_name =
+            input::required_text("Session name", session_name, input::MAX_SESSION_NAME_LENGTH)?;
+        let dev_state_guard = dev_state.lock().await;
+        dev_state_guard.app_state.touched(
+            dev_state_guard
+                .bind_project(project_path, box_id, session_name)
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn unbind_project(
+    project_path: String,
+    dev_state: State<'_, tokio::sync::Mutex<DevState>>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("unbind_project").arg("project_path", &project_path);
+    let result = async {
+        let project_path =
+            input::required_text("Project path", project_path, input::MAX_PATH_LENGTH)?;
+        let dev_state_guard = dev_state.lock().await;
+        dev_state_guard
+            .app_state
+            .touched(dev_state_guard.unbind_project(project_path).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn get_project_binding(
+    project_path: String,
+    dev_state: State<'_, tokio::sync::Mutex<DevState>>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<Option<ProjectBinding>, ZapError> {
+    let audit = CommandAudit::new("get_project_binding").arg("project_path", &project_path);
+    let result = async {
+        let project_path =
+            input::required_text("Project path", project_path, input::MAX_PATH_LENGTH)?;
+        let dev_state_guard = dev_state.lock().await;
+        dev_state_guard.get_project_binding(&project_path).await
     }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn create_session_for_project(
+    project_path: String,
+    dev_state: State<'_, tokio::sync::Mutex<DevState>>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<String, ZapError> {
+    let audit = CommandAudit::new("create_session_for_project").arg("project_path", &project_path);
+    let result = async {
+        let project_path =
+            input::required_text("Project path", project_path, input::MAX_PATH_LENGTH)?;
+        let dev_state_guard = dev_state.lock().await;
+        dev_state_guard.app_state.touched(
+            dev_state_guard
+                .create_session_for_project(project_path)
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn write_envrc(
+    project_path: String,
+    dev_state: State<'_, tokio::sync::Mutex<DevState>>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<String, ZapError> {
+    let audit = CommandAudit::new("write_envrc").arg("project_path", &project_path);
+    let result = async {
+        let project_path =
+            input::required_text("Project path", project_path, input::MAX_PATH_LENGTH)?;
+        let dev_state_guard = dev_state.lock().await;
+        dev_state_guard
+            .app_state
+            .touched(dev_state_guard.write_envrc(project_path).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn check_env_hygiene(
+    project_path: String,
+    dev_state: State<'_, tokio::sync::Mutex<DevState>>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<EnvHygieneReport, ZapError> {
+    let audit = CommandAudit::new("check_env_hygiene").arg("project_path", &project_path);
+    let result = async {
+        let project_path =
+            input::required_text("Project path", project_path, input::MAX_PATH_LENGTH)?;
+        let dev_state_guard = dev_state.lock().await;
+        dev_state_guard
+            .app_state
+            .touched(dev_state_guard.check_env_hygiene(&project_path).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn import_and_shred_env(
+    project_path: String,
+    dev_state: State<'_, tokio::sync::Mutex<DevState>>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<EnvShredResult, ZapError> {
+    let audit = CommandAudit::new("import_and_shred_env").arg("project_path", &project_path);
+    let result = async {
+        let project_path =
+            input::required_text("Project path", project_path, input::MAX_PATH_LENGTH)?;
+        let dev_state_guard = dev_state.lock().await;
+        dev_state_guard
+            .app_state
+            .touched(dev_state_guard.import_and_shred_env(project_path).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+fn secret_selection(
+    include: Option<Vec<String>>,
+    exclude: Option<Vec<String>>,
+) -> Result<SecretSelection, ZapError> {
+    Ok(SecretSelection {
+        include: include.map(input::secret_names).transpose()?,
+        exclude: input::secret_names(exclude.unwrap_or_default())?,
+    })
+}
+
+// Only asked for by boxes tagged prod, see DevState::approve_prod_session
+fn prod_approval(
+    password: Option<String>,
+    justification: Option<String>,
+) -> Result<Option<ProdApproval>, ZapError> {
+    let justification = input::optional_text(
+        "Justification",
+        justification,
+        input::MAX_JUSTIFICATION_LENGTH,
+    )?;
+    Ok(password.map(|password| ProdApproval {
+        password,
+        justification: justification.unwrap_or_default(),
+    }))
 }
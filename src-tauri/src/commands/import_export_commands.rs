@@ -1,47 +1,496 @@
-// src/commands/import_export_commands.rs 
-use crate::models::ImportResult;
+// src/commands/import_export_commands.rs
+use crate::commands::audit::CommandAudit;
+use crate::models::{
+    ConfigFormat, CsvColumnMapping, ExternalSecretsExport, GithubPushReport, GitlabSyncReport,
+    GitlabTarget, ImportResult, MergeStrategy, RenderedTemplate, SmartImportPreview, TemplateMode,
+    TfvarsFormat, VaultDiff, ZapError,
+};
 use crate::states::AppState;
+use crate::utils::input_validation as input;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::State;
 
 #[tauri::command]
-pub async fn export_vault(
+pub async fn export_vault(app_state: State<'_, Arc<AppState>>) -> Result<String, ZapError> {
+    let audit = CommandAudit::new("export_vault");
+    audit.finish(
+        &app_state,
+        app_state.touched(app_state.export_vault().await),
+    )
+}
+
+#[tauri::command]
+pub async fn get_export_signing_key(
     app_state: State<'_, Arc<AppState>>,
-) -> Result<String, String> {
-    app_state.export_vault().await.map_err(|e| e.to_string())
+) -> Result<String, ZapError> {
+    let audit = CommandAudit::new("get_export_signing_key");
+    audit.finish(&app_state, app_state.get_export_signing_key())
 }
 
 #[tauri::command]
 pub async fn export_box_as_env(
     box_id: String,
     prefix: Option<String>,
+    environment: Option<String>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<String, ZapError> {
+    let audit = CommandAudit::new("export_box_as_env")
+        .arg("box_id", &box_id)
+        .arg("prefix", &prefix)
+        .arg("environment", &environment);
+    let result = async {
+        let environment = input::optional_environment(environment)?;
+        app_state.touched(
+            app_state
+                .export_box_as_env(box_id, prefix, environment)
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn export_box_as_config(
+    box_id: String,
+    format: ConfigFormat,
+    root_key: Option<String>,
+    environment: Option<String>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<String, ZapError> {
+    let audit = CommandAudit::new("export_box_as_config")
+        .arg("box_id", &box_id)
+        .arg("format", &format)
+        .arg("root_key", &root_key)
+        .arg("environment", &environment);
+    let result = async {
+        let box_id = input::id("Box id", box_id)?;
+        let root_key = input::optional_text("Root key", root_key, input::MAX_QUERY_LENGTH)?;
+        let environment = input::optional_environment(environment)?;
+        app_state.touched(
+            app_state
+                .export_box_as_config(box_id, format, root_key, environment)
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+/// terraform.tfvars by default, `Json` for *.auto.tfvars.json
+#[tauri::command]
+pub async fn export_box_as_tfvars(
+    box_id: String,
+    format: Option<TfvarsFormat>,
+    environment: Option<String>,
     app_state: State<'_, Arc<AppState>>,
-) -> Result<String, String> {
-    app_state
-        .export_box_as_env(box_id, prefix)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<String, ZapError> {
+    let audit = CommandAudit::new("export_box_as_tfvars")
+        .arg("box_id", &box_id)
+        .arg("format", &format)
+        .arg("environment", &environment);
+    let result = async {
+        let box_id = input::id("Box id", box_id)?;
+        let format = format.unwrap_or_default();
+        let environment = input::optional_environment(environment)?;
+        app_state.touched(
+            app_state
+                .export_box_as_tfvars(box_id, format, environment)
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+/// `property_paths` maps secret names to dotted properties, e.g. "DB_PASS" -> "database.password"
+#[tauri::command]
+pub async fn export_box_for_external_secrets(
+    box_id: String,
+    property_paths: Option<HashMap<String, String>>,
+    environment: Option<String>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<ExternalSecretsExport, ZapError> {
+    let audit = CommandAudit::new("export_box_for_external_secrets")
+        .arg("box_id", &box_id)
+        .arg("property_paths", &property_paths)
+        .arg("environment", &environment);
+    let result = async {
+        let box_id = input::id("Box id", box_id)?;
+        let property_paths = property_paths
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(name, path)| {
+                Ok((
+                    input::required_text("Secret name", name, input::MAX_SECRET_NAME_LENGTH)?,
+                    input::required_text("Property path", path, input::MAX_QUERY_LENGTH)?,
+                ))
+            })
+            .collect::<Result<HashMap<_, _>, ZapError>>()?;
+        let environment = input::optional_environment(environment)?;
+        app_state.touched(
+            app_state
+                .export_box_for_external_secrets(box_id, property_paths, environment)
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+/// `token_secret_id` is a vault secret holding a GitHub token with Secrets write access.
+/// Without `github_environment` the repository's Actions secrets are set.
+#[tauri::command]
+pub async fn push_box_to_github(
+    box_id: String,
+    repository: String,
+    github_environment: Option<String>,
+    token_secret_id: String,
+    environment: Option<String>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<GithubPushReport, ZapError> {
+    let audit = CommandAudit::new("push_box_to_github")
+        .arg("box_id", &box_id)
+        .arg("repository", &repository)
+        .arg("github_environment", &github_environment)
+        .arg("token_secret_id", &token_secret_id)
+        .arg("environment", &environment);
+    let result = async {
+        let box_id = input::id("Box id", box_id)?;
+        let repository = input::required_text("Repository", repository, input::MAX_QUERY_LENGTH)?;
+        let github_environment = input::optional_text(
+            "GitHub environment",
+            github_environment,
+            input::MAX_QUERY_LENGTH,
+        )?;
+        let token_secret_id = input::id("Token secret id", token_secret_id)?;
+        let environment = input::optional_environment(environment)?;
+        app_state.touched(
+            app_state
+                .push_box_to_github(
+                    box_id,
+                    repository,
+                    github_environment,
+                    token_secret_id,
+                    environment,
+                )
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn export_box_for_recipient(
+    box_id: String,
+    recipient: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<String, ZapError> {
+    let audit = CommandAudit::new("export_box_for_recipient")
+        .arg("box_id", &box_id)
+        .arg("recipient", &recipient);
+    let result = async {
+        let box_id = input::id("Box id", box_id)?;
+        let recipient = input::required_text("Recipient", recipient, input::MAX_KEY_LENGTH)?;
+        app_state.touched(app_state.export_box_for_recipient(box_id, recipient).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn render_template(
+    template: String,
+    mode: Option<TemplateMode>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<RenderedTemplate, ZapError> {
+    let audit = CommandAudit::new("render_template")
+        .arg("template", &template)
+        .arg("mode", &mode);
+    let result = async {
+        let template = input::template(template)?;
+        app_state.touched(
+            app_state
+                .render_template(template, mode.unwrap_or_default())
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
 }
 
 #[tauri::command]
 pub async fn import_vault(
     json_data: String,
     app_state: State<'_, Arc<AppState>>,
-) -> Result<ImportResult, String> {
-    app_state
-        .import_vault(&json_data)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<ImportResult, ZapError> {
+    let audit = CommandAudit::new("import_vault").arg("json_data", &json_data);
+    audit.finish(
+        &app_state,
+        app_state.touched(app_state.import_vault(&json_data).await),
+    )
+}
+
+#[tauri::command]
+pub async fn import_vault_merge(
+    json_data: String,
+    strategy: MergeStrategy,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<ImportResult, ZapError> {
+    let audit = CommandAudit::new("import_vault_merge")
+        .arg("json_data", &json_data)
+        .arg("strategy", &strategy);
+    audit.finish(
+        &app_state,
+        app_state.touched(app_state.import_vault_merge(&json_data, strategy).await),
+    )
+}
+
+#[tauri::command]
+pub async fn diff_vault_against_export(
+    json_data: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<VaultDiff, ZapError> {
+    let audit = CommandAudit::new("diff_vault_against_export").arg("json_data", &json_data);
+    audit.finish(
+        &app_state,
+        app_state.touched(app_state.diff_vault_against_export(&json_data).await),
+    )
+}
+
+#[tauri::command]
+pub async fn import_shared_box(
+    encrypted: String,
+    identity: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<ImportResult, ZapError> {
+    let audit = CommandAudit::new("import_shared_box")
+        .arg("encrypted", &encrypted)
+        .arg("identity", &identity);
+    let result = async {
+        let identity = input::required_text("Private key", identity, input::MAX_KEY_LENGTH)?;
+        app_state.touched(app_state.import_shared_box(&encrypted, &identity).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+// Writes an encrypted backup to the automatic export folder right away, returns its path
+#[tauri::command]
+pub async fn run_auto_export_now(app_state: State<'_, Arc<AppState>>) -> Result<String, ZapError> {
+    let audit = CommandAudit::new("run_auto_export_now");
+    let result = async {
+        let state = app_state.inner().clone();
+        let written = tauri::async_runtime::spawn_blocking(move || state.run_auto_export(true))
+            .await
+            .map_err(|e| ZapError::PlatformError(e.to_string()))?;
+        let path = app_state.touched(written)?;
+        Ok(path
+            .map(|path| path.display().to_string())
+            .unwrap_or_default())
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn import_encrypted_backup(
+    content: String,
+    password: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<ImportResult, ZapError> {
+    let audit = CommandAudit::new("import_encrypted_backup")
+        .arg("content", &content)
+        .arg("password", &password);
+    audit.finish(
+        &app_state,
+        app_state.touched(app_state.import_encrypted_backup(&content, &password).await),
+    )
 }
 
 #[tauri::command]
 pub async fn import_env_to_box(
     env_content: String,
     target_box_id: String,
+    resolve_from_box: Option<bool>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<ImportResult, ZapError> {
+    let audit = CommandAudit::new("import_env_to_box")
+        .arg("env_content", &env_content)
+        .arg("target_box_id", &target_box_id)
+        .arg("resolve_from_box", &resolve_from_box);
+    let result = async {
+        let resolve_from_box = resolve_from_box.unwrap_or(false);
+        app_state.touched(
+            app_state
+                .import_env_to_box(&env_content, target_box_id, resolve_from_box)
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn import_csv_to_box(
+    content: String,
+    target_box_id: String,
+    mapping: CsvColumnMapping,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<ImportResult, ZapError> {
+    let audit = CommandAudit::new("import_csv_to_box")
+        .arg("content", &content)
+        .arg("target_box_id", &target_box_id)
+        .arg("mapping", &mapping);
+    let result = async {
+        let target_box_id = input::id("Box id", target_box_id)?;
+        app_state.touched(
+            app_state
+                .import_csv_to_box(&content, target_box_id, mapping)
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn import_config_to_box(
+    content: String,
+    target_box_id: String,
+    format: ConfigFormat,
+    root_key: Option<String>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<ImportResult, ZapError> {
+    let audit = CommandAudit::new("import_config_to_box")
+        .arg("content", &content)
+        .arg("target_box_id", &target_box_id)
+        .arg("format", &format)
+        .arg("root_key", &root_key);
+    let result = async {
+        let target_box_id = input::id("Box id", target_box_id)?;
+        let root_key = input::optional_text("Root key", root_key, input::MAX_QUERY_LENGTH)?;
+        app_state.touched(
+            app_state
+                .import_config_to_box(&content, target_box_id, format, root_key)
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn preview_smart_import(
+    content: String,
+    target_box_id: String,
     app_state: State<'_, Arc<AppState>>,
-) -> Result<ImportResult, String> {
-    app_state
-        .import_env_to_box(&env_content, target_box_id)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<SmartImportPreview, ZapError> {
+    let audit = CommandAudit::new("preview_smart_import")
+        .arg("content", &content)
+        .arg("target_box_id", &target_box_id);
+    let result = async {
+        let target_box_id = input::id("Box id", target_box_id)?;
+        app_state.touched(
+            app_state
+                .preview_smart_import(&content, target_box_id)
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn smart_import(
+    content: String,
+    target_box_id: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<ImportResult, ZapError> {
+    let audit = CommandAudit::new("smart_import")
+        .arg("content", &content)
+        .arg("target_box_id", &target_box_id);
+    let result = async {
+        let target_box_id = input::id("Box id", target_box_id)?;
+        app_state.touched(app_state.smart_import(&content, target_box_id).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+/// Lists what syncing would create or update in GitLab; values never leave the backend
+#[tauri::command]
+pub async fn preview_gitlab_sync(
+    box_id: String,
+    target: GitlabTarget,
+    token_secret_id: String,
+    environment: Option<String>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<GitlabSyncReport, ZapError> {
+    let audit = CommandAudit::new("preview_gitlab_sync")
+        .arg("box_id", &box_id)
+        .arg("target", &target)
+        .arg("token_secret_id", &token_secret_id)
+        .arg("environment", &environment);
+    let result = async {
+        let box_id = input::id("Box id", box_id)?;
+        let target = gitlab_target(target)?;
+        let token_secret_id = input::id("Token secret id", token_secret_id)?;
+        let environment = input::optional_environment(environment)?;
+        app_state.touched(
+            app_state
+                .preview_gitlab_sync(box_id, target, token_secret_id, environment)
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+/// `token_secret_id` is a vault secret holding a GitLab token with the `api` scope
+#[tauri::command]
+pub async fn sync_box_to_gitlab(
+    box_id: String,
+    target: GitlabTarget,
+    token_secret_id: String,
+    environment: Option<String>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<GitlabSyncReport, ZapError> {
+    let audit = CommandAudit::new("sync_box_to_gitlab")
+        .arg("box_id", &box_id)
+        .arg("target", &target)
+        .arg("token_secret_id", &token_secret_id)
+        .arg("environment", &environment);
+    let result = async {
+        let box_id = input::id("Box id", box_id)?;
+        let target = gitlab_target(target)?;
+        let token_secret_id = input::id("Token secret id", token_secret_id)?;
+        let environment = input::optional_environment(environment)?;
+        app_state.touched(
+            app_state
+                .sync_box_to_gitlab(box_id, target, token_secret_id, environment)
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+fn gitlab_target(target: GitlabTarget) -> Result<GitlabTarget, ZapError> {
+    Ok(GitlabTarget {
+        instance_url: input::optional_text(
+            "GitLab URL",
+            target.instance_url,
+            input::MAX_PATH_LENGTH,
+        )?,
+        scope: target.scope,
+        path: input::required_text("GitLab path", target.path, input::MAX_QUERY_LENGTH)?,
+        environment_scope: input::optional_text(
+            "Environment scope",
+            target.environment_scope,
+            input::MAX_QUERY_LENGTH,
+        )?,
+    })
 }
@@ -0,0 +1,124 @@
+// src/commands/job_commands.rs - Long-running operations as tracked, cancellable jobs
+
+use crate::commands::audit::CommandAudit;
+use crate::models::{JobInfo, LanTransferOffer, TransferScope, ZapError};
+use crate::states::AppState;
+use crate::utils::input_validation as input;
+use std::sync::Arc;
+use tauri::State;
+
+#[tauri::command]
+pub async fn start_import_vault_job(
+    json_data: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<String, ZapError> {
+    let audit = CommandAudit::new("start_import_vault_job").arg("json_data", &json_data);
+    audit.finish(
+        &app_state,
+        app_state.touched(app_state.start_import_vault_job(json_data)),
+    )
+}
+
+#[tauri::command]
+pub async fn start_export_vault_job(
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<String, ZapError> {
+    let audit = CommandAudit::new("start_export_vault_job");
+    audit.finish(
+        &app_state,
+        app_state.touched(app_state.start_export_vault_job()),
+    )
+}
+
+#[tauri::command]
+pub async fn start_breach_check_job(
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<String, ZapError> {
+    let audit = CommandAudit::new("start_breach_check_job");
+    audit.finish(
+        &app_state,
+        app_state.touched(app_state.start_breach_check_job()),
+    )
+}
+
+#[tauri::command]
+pub async fn start_lan_transfer(
+    scope: TransferScope,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<LanTransferOffer, ZapError> {
+    let audit = CommandAudit::new("start_lan_transfer").arg("scope", &scope);
+    let result = async {
+        let scope = match scope {
+            TransferScope::Box { box_id } => TransferScope::Box {
+                box_id: input::id("Box id", box_id)?,
+            },
+            scope => scope,
+        };
+        app_state.touched(app_state.start_lan_transfer(scope).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn start_lan_receive(
+    code: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<String, ZapError> {
+    let audit = CommandAudit::new("start_lan_receive").arg("code", &code);
+    let result = async {
+        let code = input::required_text("Transfer code", code, input::MAX_KEY_LENGTH)?;
+        app_state.touched(app_state.start_lan_receive(code))
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn get_job(
+    job_id: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<JobInfo, ZapError> {
+    let audit = CommandAudit::new("get_job").arg("job_id", &job_id);
+    let result = async {
+        let job_id = input::id("Job id", job_id)?;
+        app_state.get_job(&job_id)
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn list_jobs(app_state: State<'_, Arc<AppState>>) -> Result<Vec<JobInfo>, ZapError> {
+    let audit = CommandAudit::new("list_jobs");
+    audit.finish(&app_state, app_state.list_jobs())
+}
+
+// A finished export job's JSON, handed out once
+#[tauri::command]
+pub async fn take_job_payload(
+    job_id: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<String, ZapError> {
+    let audit = CommandAudit::new("take_job_payload").arg("job_id", &job_id);
+    let result = async {
+        let job_id = input::id("Job id", job_id)?;
+        app_state.touched(app_state.take_job_payload(&job_id))
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn cancel_job(
+    job_id: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("cancel_job").arg("job_id", &job_id);
+    let result = async {
+        let job_id = input::id("Job id", job_id)?;
+        app_state.cancel_job(&job_id)
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
@@ -1,30 +1,52 @@
 // src/commands/log_commands.r
 
-use crate::models::LogEntry;
+use crate::commands::audit::CommandAudit;
+use crate::models::{LogEntry, LogSeverity, ZapError};
 use crate::states::AppState;
 use std::sync::Arc;
 use tauri::State;
 
 /// Get all logs
 #[tauri::command]
-pub async fn get_all_logs(app_state: State<'_, Arc<AppState>>) -> Result<Vec<LogEntry>, String> {
-    app_state.get_all_logs().await.map_err(|e| e.to_string())
+pub async fn get_all_logs(app_state: State<'_, Arc<AppState>>) -> Result<Vec<LogEntry>, ZapError> {
+    let audit = CommandAudit::new("get_all_logs");
+    audit.finish(&app_state, app_state.get_all_logs().await)
+}
+
+/// Get logs at or above a severity and/or for one action
+#[tauri::command]
+pub async fn get_filtered_logs(
+    min_severity: Option<LogSeverity>,
+    action: Option<String>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<Vec<LogEntry>, ZapError> {
+    let audit = CommandAudit::new("get_filtered_logs")
+        .arg("min_severity", &min_severity)
+        .arg("action", &action);
+    audit.finish(
+        &app_state,
+        app_state
+            .get_filtered_logs(min_severity, action.as_deref())
+            .await,
+    )
 }
 
 /// Clear all logs
 #[tauri::command]
 pub async fn clear_all_logs(
-    password: String,
+    password: Option<String>,
     app_state: State<'_, Arc<AppState>>,
-) -> Result<usize, String> {
-    app_state
-        .clear_all_logs(&password)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<usize, ZapError> {
+    let audit = CommandAudit::new("clear_all_logs").arg("password", &password);
+    audit.finish(
+        &app_state,
+        app_state.touched(app_state.clear_all_logs(password.as_deref()).await),
+    )
 }
 
 /// Export logs
 #[tauri::command]
-pub async fn export_logs(app_state: State<'_, Arc<AppState>>) -> Result<String, String> {
-    app_state.export_logs().await.map_err(|e| e.to_string())
+pub async fn export_logs(app_state: State<'_, Arc<AppState>>) -> Result<String, ZapError> {
+    let audit = CommandAudit::new("export_logs");
+    audit.finish(&app_state, app_state.touched(app_state.export_logs().await))
 }
@@ -1,19 +1,24 @@
 // src/commands/mod.rs
+pub mod audit;
 pub mod auth_commands;
 pub mod box_commands;
 pub mod dev_commands;
 pub mod import_export_commands;
+pub mod job_commands;
+pub mod log_commands;
 pub mod secret_commands;
 pub mod settings_commands;
 pub mod stats_commands;
-pub mod log_commands;
+pub mod sync_commands;
 
 // Re-export all commands
 pub use auth_commands::*;
 pub use box_commands::*;
 pub use dev_commands::*;
 pub use import_export_commands::*;
+pub use job_commands::*;
+pub use log_commands::*;
 pub use secret_commands::*;
 pub use settings_commands::*;
 pub use stats_commands::*;
-pub use log_commands::*;
\ No newline at end of file
+pub use sync_commands::*;
@@ -1,37 +1,95 @@
 // src/commands/secret_commands.rs
 
-use crate::models::Secret;
+use crate::commands::audit::CommandAudit;
+use crate::models::{
+    CompromisedSecret, DeepSearchMatch, OneTimeShare, RecentSecret, RedeemedShare, RevealHandle,
+    SavedSecret, Secret, MultilineFormat, SecretSearchResult, SecretTemplate, ValueEncoding,
+    ZapError,
+};
 use crate::states::AppState;
+use crate::utils::input_validation as input;
+use crate::utils::secret_clipboard;
+use std::collections::HashMap;
 use std::sync::Arc;
 use tauri::State;
 
 #[tauri::command]
-pub async fn get_all_secrets(app_state: State<'_, Arc<AppState>>) -> Result<Vec<Secret>, String> {
-    app_state.get_all_secrets().await.map_err(|e| e.to_string())
+pub async fn get_all_secrets(app_state: State<'_, Arc<AppState>>) -> Result<Vec<Secret>, ZapError> {
+    let audit = CommandAudit::new("get_all_secrets");
+    audit.finish(&app_state, app_state.get_all_secrets().await)
 }
 
 #[tauri::command]
 pub async fn get_secrets_by_box_id(
     box_id: String,
     app_state: State<'_, Arc<AppState>>,
-) -> Result<Vec<Secret>, String> {
-    app_state
-        .get_secrets_by_box_id(&box_id)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<Vec<Secret>, ZapError> {
+    let audit = CommandAudit::new("get_secrets_by_box_id").arg("box_id", &box_id);
+    let result = async {
+        let box_id = input::id("Box id", box_id)?;
+        app_state.get_secrets_by_box_id(&box_id).await
+    }
+    .await;
+    audit.finish(&app_state, result)
 }
 
 #[tauri::command]
+pub async fn get_secret_templates(
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<Vec<SecretTemplate>, ZapError> {
+    let audit = CommandAudit::new("get_secret_templates");
+    audit.finish(&app_state, Ok(app_state.get_secret_templates()))
+}
+
+/// Takes either `value` or `template_id` (plus `template_fields` for composed templates)
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
 pub async fn create_secret(
     box_id: String,
     name: String,
-    value: String,
+    value: Option<String>,
+    template_id: Option<String>,
+    template_fields: Option<HashMap<String, String>>,
+    value_encoding: Option<ValueEncoding>,
+    multiline_format: Option<MultilineFormat>,
+    normalize_line_endings: Option<bool>,
     app_state: State<'_, Arc<AppState>>,
-) -> Result<String, String> {
-    app_state
-        .create_secret(box_id, name, value)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<SavedSecret, ZapError> {
+    let audit = CommandAudit::new("create_secret")
+        .arg("box_id", &box_id)
+        .arg("name", &name)
+        .arg("value", &value)
+        .arg("template_id", &template_id)
+        .arg("template_fields", &template_fields)
+        .arg("value_encoding", &value_encoding)
+        .arg("multiline_format", &multiline_format)
+        .arg("normalize_line_endings", &normalize_line_endings);
+    let result = async {
+        let box_id = input::id("Box id", box_id)?;
+        let name = input::required_text("Secret name", name, input::MAX_SECRET_NAME_LENGTH)?;
+        let mut value = match (value, template_id) {
+            (Some(value), None) => input::secret_value(value)?,
+            (None, Some(template_id)) => app_state
+                .generate_template_value(&template_id, &template_fields.unwrap_or_default())?,
+            _ => {
+                return Err(ZapError::ValidationError(
+                    "Give either a value or a template id".to_string(),
+                ))
+            }
+        };
+        if normalize_line_endings.unwrap_or(false) {
+            value = input::normalize_line_endings(value);
+        }
+        let value_encoding = value_encoding.unwrap_or_default();
+        let multiline_format = multiline_format.unwrap_or_default();
+        app_state.touched(
+            app_state
+                .create_secret(box_id, name, value, value_encoding, multiline_format)
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
 }
 
 #[tauri::command]
@@ -39,34 +97,113 @@ pub async fn update_secret(
     secret_id: String,
     name: Option<String>,
     value: Option<String>,
+    value_encoding: Option<ValueEncoding>,
+    multiline_format: Option<MultilineFormat>,
+    normalize_line_endings: Option<bool>,
     app_state: State<'_, Arc<AppState>>,
-) -> Result<(), String> {
-    app_state
-        .update_secret(&secret_id, name, value)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<SavedSecret, ZapError> {
+    let audit = CommandAudit::new("update_secret")
+        .arg("secret_id", &secret_id)
+        .arg("name", &name)
+        .arg("value", &value)
+        .arg("value_encoding", &value_encoding)
+        .arg("multiline_format", &multiline_format)
+        .arg("normalize_line_endings", &normalize_line_endings);
+    let result = async {
+        let secret_id = input::id("Secret id", secret_id)?;
+        let name = name
+            .map(|name| input::required_text("Secret name", name, input::MAX_SECRET_NAME_LENGTH))
+            .transpose()?;
+        let mut value = value.map(input::secret_value).transpose()?;
+        if normalize_line_endings.unwrap_or(false) {
+            value = value.map(input::normalize_line_endings);
+        }
+        app_state.touched(
+            app_state
+                .update_secret(&secret_id, name, value, value_encoding, multiline_format)
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn set_secret_variant(
+    secret_id: String,
+    environment: String,
+    value: String,
+    normalize_line_endings: Option<bool>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<SavedSecret, ZapError> {
+    let audit = CommandAudit::new("set_secret_variant")
+        .arg("secret_id", &secret_id)
+        .arg("environment", &environment);
+    let result = async {
+        let secret_id = input::id("Secret id", secret_id)?;
+        let environment = input::environment(environment)?;
+        let mut value = input::secret_value(value)?;
+        if normalize_line_endings.unwrap_or(false) {
+            value = input::normalize_line_endings(value);
+        }
+        app_state.touched(
+            app_state
+                .set_secret_variant(&secret_id, environment, value)
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn remove_secret_variant(
+    secret_id: String,
+    environment: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("remove_secret_variant")
+        .arg("secret_id", &secret_id)
+        .arg("environment", &environment);
+    let result = async {
+        let secret_id = input::id("Secret id", secret_id)?;
+        let environment = input::environment(environment)?;
+        app_state.touched(
+            app_state
+                .remove_secret_variant(&secret_id, &environment)
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
 }
 
 #[tauri::command]
 pub async fn delete_secret(
     secret_id: String,
     app_state: State<'_, Arc<AppState>>,
-) -> Result<(), String> {
-    app_state
-        .delete_secret(&secret_id)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("delete_secret").arg("secret_id", &secret_id);
+    let result = async {
+        let secret_id = input::id("Secret id", secret_id)?;
+        app_state.touched(app_state.delete_secret(&secret_id).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
 }
 
 #[tauri::command]
 pub async fn delete_selected_secrets(
     secret_ids: Vec<String>,
     app_state: State<'_, Arc<AppState>>,
-) -> Result<Vec<String>, String> {
-    app_state
-        .delete_selected_secrets(secret_ids)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<Vec<String>, ZapError> {
+    let audit = CommandAudit::new("delete_selected_secrets").arg("secret_ids", &secret_ids);
+    let result = async {
+        let secret_ids = input::ids("Secret id", secret_ids)?;
+        app_state.touched(app_state.delete_selected_secrets(secret_ids).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
 }
 
 #[tauri::command]
@@ -74,22 +211,167 @@ pub async fn copy_secrets_to_box(
     secret_ids: Vec<String>,
     target_box_id: String,
     app_state: State<'_, Arc<AppState>>,
-) -> Result<Vec<String>, String> {
-    app_state
-        .copy_secrets_to_box(secret_ids, target_box_id)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<Vec<String>, ZapError> {
+    let audit = CommandAudit::new("copy_secrets_to_box")
+        .arg("secret_ids", &secret_ids)
+        .arg("target_box_id", &target_box_id);
+    let result = async {
+        let secret_ids = input::ids("Secret id", secret_ids)?;
+        let target_box_id = input::id("Box id", target_box_id)?;
+        app_state.touched(
+            app_state
+                .copy_secrets_to_box(secret_ids, target_box_id)
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
 }
 
 #[tauri::command]
 pub async fn reveal_secret_value(
     secret_id: String,
+    environment: Option<String>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<String, ZapError> {
+    let audit = CommandAudit::new("reveal_secret_value")
+        .arg("secret_id", &secret_id)
+        .arg("environment", &environment);
+    let result = async {
+        let secret_id = input::id("Secret id", secret_id)?;
+        let environment = input::optional_environment(environment)?;
+        app_state.touched(app_state.reveal_secret_value(&secret_id, environment).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn reveal_secret_handle(
+    secret_id: String,
+    environment: Option<String>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<RevealHandle, ZapError> {
+    let audit = CommandAudit::new("reveal_secret_handle")
+        .arg("secret_id", &secret_id)
+        .arg("environment", &environment);
+    let result = async {
+        let secret_id = input::id("Secret id", secret_id)?;
+        let environment = input::optional_environment(environment)?;
+        app_state.touched(app_state.reveal_secret_handle(&secret_id, environment).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn copy_revealed_secret(
+    handle: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("copy_revealed_secret").arg("handle", &handle);
+    let result = async {
+        let handle = input::id("Reveal handle", handle)?;
+        let value = app_state.touched(app_state.redeem_reveal_handle(&handle).await)?;
+
+        let exclude_from_history = app_state.get_settings()?.clipboard_exclude_from_history;
+        secret_clipboard::copy_secret(value, exclude_from_history)
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn set_rotation_interval(
+    secret_id: String,
+    days: Option<u32>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<Secret, ZapError> {
+    let audit = CommandAudit::new("set_rotation_interval")
+        .arg("secret_id", &secret_id)
+        .arg("days", &days);
+    let result = async {
+        let secret_id = input::id("Secret id", secret_id)?;
+        app_state.touched(app_state.set_rotation_interval(&secret_id, days).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn mark_rotated(
+    secret_id: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<Secret, ZapError> {
+    let audit = CommandAudit::new("mark_rotated").arg("secret_id", &secret_id);
+    let result = async {
+        let secret_id = input::id("Secret id", secret_id)?;
+        app_state.touched(app_state.mark_rotated(&secret_id).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn get_secrets_due_for_rotation(
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<Vec<Secret>, ZapError> {
+    let audit = CommandAudit::new("get_secrets_due_for_rotation");
+    audit.finish(&app_state, app_state.get_secrets_due_for_rotation().await)
+}
+
+#[tauri::command]
+pub async fn get_compromised_secrets(
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<Vec<CompromisedSecret>, ZapError> {
+    let audit = CommandAudit::new("get_compromised_secrets");
+    audit.finish(&app_state, app_state.get_compromised_secrets().await)
+}
+
+// Checks every password against Have I Been Pwned right away, returns the newly found ones
+#[tauri::command]
+pub async fn run_breach_check_now(
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<Vec<CompromisedSecret>, ZapError> {
+    let audit = CommandAudit::new("run_breach_check_now");
+    audit.finish(
+        &app_state,
+        app_state.touched(app_state.run_breach_check(true, None).await),
+    )
+}
+
+#[tauri::command]
+pub async fn set_secret_favorite(
+    secret_id: String,
+    favorite: bool,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<Secret, ZapError> {
+    let audit = CommandAudit::new("set_secret_favorite")
+        .arg("secret_id", &secret_id)
+        .arg("favorite", &favorite);
+    let result = async {
+        let secret_id = input::id("Secret id", secret_id)?;
+        app_state.touched(app_state.set_secret_favorite(&secret_id, favorite).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn get_favorite_secrets(
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<Vec<Secret>, ZapError> {
+    let audit = CommandAudit::new("get_favorite_secrets");
+    audit.finish(&app_state, app_state.get_favorite_secrets().await)
+}
+
+#[tauri::command]
+pub async fn get_recent_secrets(
+    limit: usize,
     app_state: State<'_, Arc<AppState>>,
-) -> Result<String, String> {
-    app_state
-        .reveal_secret_value(&secret_id)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<Vec<RecentSecret>, ZapError> {
+    let audit = CommandAudit::new("get_recent_secrets").arg("limit", &limit);
+    audit.finish(&app_state, app_state.get_recent_secrets(limit).await)
 }
 
 #[tauri::command]
@@ -97,9 +379,72 @@ pub async fn search_secrets_in_box(
     box_id: String,
     query: String,
     app_state: State<'_, Arc<AppState>>,
-) -> Result<Vec<Secret>, String> {
-    app_state
-        .search_secrets_in_box(box_id, query)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<Vec<SecretSearchResult>, ZapError> {
+    let audit = CommandAudit::new("search_secrets_in_box")
+        .arg("box_id", &box_id)
+        .arg("query", &query);
+    let result = async {
+        let box_id = input::id("Box id", box_id)?;
+        let query = input::text("Search query", query, input::MAX_QUERY_LENGTH)?;
+        app_state.touched(app_state.search_secrets_in_box(box_id, query).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn deep_search(
+    password: String,
+    query: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<Vec<DeepSearchMatch>, ZapError> {
+    let audit = CommandAudit::new("deep_search")
+        .arg("password", &password)
+        .arg("query", &query);
+    let result = async {
+        // Compared to values byte-for-byte, so it is checked like one (no trimming)
+        let query = input::secret_value(query)?;
+        app_state.touched(app_state.deep_search(&password, query).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn create_one_time_share(
+    secret_id: String,
+    ttl_minutes: u32,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<OneTimeShare, ZapError> {
+    let audit = CommandAudit::new("create_one_time_share")
+        .arg("secret_id", &secret_id)
+        .arg("ttl_minutes", &ttl_minutes);
+    let result = async {
+        let secret_id = input::id("Secret id", secret_id)?;
+        app_state.touched(
+            app_state
+                .create_one_time_share(&secret_id, ttl_minutes)
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn redeem_share(
+    blob: String,
+    passphrase: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<RedeemedShare, ZapError> {
+    let audit = CommandAudit::new("redeem_share")
+        .arg("blob", &blob)
+        .arg("passphrase", &passphrase);
+    let result = async {
+        let blob = input::required_text("Share", blob, input::MAX_TEMPLATE_LENGTH)?;
+        let passphrase = input::required_text("Passphrase", passphrase, input::MAX_KEY_LENGTH)?;
+        app_state.touched(app_state.redeem_share(&blob, &passphrase).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
 }
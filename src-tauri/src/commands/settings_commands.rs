@@ -1,22 +1,58 @@
 // src/commands/settings_commands.rs
 
-use crate::models::Settings;
+use crate::commands::audit::CommandAudit;
+use crate::models::{DataDirectoryInfo, Settings, ZapError};
+use crate::session_watcher::SessionWatcher;
 use crate::states::AppState;
 use std::sync::Arc;
-use tauri::State;
+use tauri::{AppHandle, Manager, State};
 
 #[tauri::command]
-pub async fn get_settings(app_state: State<'_, Arc<AppState>>) -> Result<Settings, String> {
-    app_state.get_settings().map_err(|e| e.to_string())
+pub async fn get_settings(app_state: State<'_, Arc<AppState>>) -> Result<Settings, ZapError> {
+    let audit = CommandAudit::new("get_settings");
+    audit.finish(&app_state, app_state.get_settings())
 }
 
 #[tauri::command]
 pub async fn update_settings(
     new_settings: Settings,
+    app: AppHandle,
     app_state: State<'_, Arc<AppState>>,
-) -> Result<(), String> {
-    app_state
-        .update_settings(new_settings)
-        .await
-        .map_err(|e| e.to_string())
+) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("update_settings").arg("new_settings", &new_settings);
+    let result = async {
+        app_state.update_settings(new_settings).await?;
+
+        // Sessions directory may have moved
+        app.state::<SessionWatcher>()
+            .refresh()
+            .map_err(ZapError::PlatformError)
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn get_data_directory_info(
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<DataDirectoryInfo, ZapError> {
+    let audit = CommandAudit::new("get_data_directory_info");
+    audit.finish(&app_state, app_state.get_data_directory_info())
+}
+
+// The open databases can't be swapped, so the app restarts into the new directory
+#[tauri::command]
+pub async fn move_data_directory(
+    path: Option<String>,
+    app: AppHandle,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("move_data_directory").arg("path", &path);
+    audit.finish(
+        &app_state,
+        app_state.touched(app_state.move_data_directory(path).await),
+    )?;
+
+    let _ = app_state.storage.flush();
+    app.restart()
 }
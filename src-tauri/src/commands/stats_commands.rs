@@ -1,11 +1,85 @@
 // src/commands/stats_commands.rs
 
-use crate::services::VaultStats;
+use crate::commands::audit::CommandAudit;
+use crate::models::{
+    DiagnosticsReport, ReportFormat, StorageUsage, VaultSnapshot, VaultStats, ZapError,
+};
 use crate::states::AppState;
+use crate::utils::input_validation as input;
 use std::sync::Arc;
-use tauri::State;
+use tauri::{AppHandle, State};
 
 #[tauri::command]
-pub async fn get_vault_stats(app_state: State<'_, Arc<AppState>>) -> Result<VaultStats, String> {
-    app_state.get_vault_stats().await.map_err(|e| e.to_string())
+pub async fn get_vault_stats(app_state: State<'_, Arc<AppState>>) -> Result<VaultStats, ZapError> {
+    let audit = CommandAudit::new("get_vault_stats");
+    audit.finish(&app_state, app_state.get_vault_stats().await)
+}
+
+#[tauri::command]
+pub async fn export_stats_report(
+    format: ReportFormat,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<String, ZapError> {
+    let audit = CommandAudit::new("export_stats_report").arg("format", &format);
+    audit.finish(
+        &app_state,
+        app_state.touched(app_state.export_stats_report(format).await),
+    )
+}
+
+#[tauri::command]
+pub async fn get_storage_usage(
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<StorageUsage, ZapError> {
+    let audit = CommandAudit::new("get_storage_usage");
+    audit.finish(&app_state, app_state.get_storage_usage().await)
+}
+
+#[tauri::command]
+pub async fn list_vault_snapshots(
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<Vec<VaultSnapshot>, ZapError> {
+    let audit = CommandAudit::new("list_vault_snapshots");
+    audit.finish(&app_state, app_state.list_vault_snapshots().await)
+}
+
+// Locks the vault afterwards, the restored one may have another master password
+#[tauri::command]
+pub async fn restore_snapshot(
+    snapshot_id: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("restore_snapshot").arg("snapshot_id", &snapshot_id);
+    let result = async {
+        let snapshot_id = input::id("Snapshot id", snapshot_id)?;
+        app_state.restore_snapshot(&snapshot_id).await
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+// Works while locked and when storage failed to open, so support can always collect it
+#[tauri::command]
+pub async fn run_diagnostics(
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<DiagnosticsReport, ZapError> {
+    let audit = CommandAudit::new("run_diagnostics");
+    audit.finish(&app_state, Ok(app_state.run_diagnostics().await))
+}
+
+// Compaction runs before the databases open, so the app restarts into it
+#[tauri::command]
+pub async fn compact_storage(
+    app: AppHandle,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("compact_storage");
+    audit.finish(
+        &app_state,
+        app_state.touched(app_state.compact_storage().await),
+    )?;
+
+    // Nothing runs after the restart, so write out the audit entry first
+    let _ = app_state.storage.flush();
+    app.restart()
 }
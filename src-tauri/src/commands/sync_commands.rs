@@ -0,0 +1,179 @@
+// src/commands/sync_commands.rs - Boxes mapped to cloud secret stores (Settings: sync_mappings),
+// one-off pulls from a store into a box, and whole-vault replicas (Settings: replicas)
+
+use crate::commands::audit::CommandAudit;
+use crate::models::{
+    ConflictResolution, ReplicaSyncReport, SyncConflict, SyncDiff, SyncProviderConfig, SyncResult,
+    ZapError,
+};
+use crate::states::AppState;
+use crate::utils::input_validation as input;
+use std::sync::Arc;
+use tauri::State;
+
+/// Which names differ between the box and its store, values never leave the backend
+#[tauri::command]
+pub async fn diff_sync_mapping(
+    mapping_id: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<SyncDiff, ZapError> {
+    let audit = CommandAudit::new("diff_sync_mapping").arg("mapping_id", &mapping_id);
+    let result = async {
+        let mapping_id = input::id("Mapping id", mapping_id)?;
+        app_state.touched(app_state.diff_sync_mapping(&mapping_id).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn push_sync_mapping(
+    mapping_id: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<SyncResult, ZapError> {
+    let audit = CommandAudit::new("push_sync_mapping").arg("mapping_id", &mapping_id);
+    let result = async {
+        let mapping_id = input::id("Mapping id", mapping_id)?;
+        app_state.touched(app_state.push_sync_mapping(&mapping_id).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn pull_sync_mapping(
+    mapping_id: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<SyncResult, ZapError> {
+    let audit = CommandAudit::new("pull_sync_mapping").arg("mapping_id", &mapping_id);
+    let result = async {
+        let mapping_id = input::id("Mapping id", mapping_id)?;
+        app_state.touched(app_state.pull_sync_mapping(&mapping_id).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+/// What pull_from_provider would import. `path` limits it to store names starting with it.
+#[tauri::command]
+pub async fn preview_provider_pull(
+    provider: SyncProviderConfig,
+    credentials_secret_id: String,
+    path: Option<String>,
+    target_box_id: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<SyncDiff, ZapError> {
+    let audit = CommandAudit::new("preview_provider_pull")
+        .arg("provider", &provider)
+        .arg("credentials_secret_id", &credentials_secret_id)
+        .arg("path", &path)
+        .arg("target_box_id", &target_box_id);
+    let result = async {
+        provider.validate()?;
+        let credentials_secret_id = input::id("Credentials secret id", credentials_secret_id)?;
+        let path = input::optional_text("Path", path, input::MAX_QUERY_LENGTH)?;
+        let target_box_id = input::id("Box id", target_box_id)?;
+        app_state.touched(
+            app_state
+                .preview_provider_pull(provider, credentials_secret_id, path, target_box_id)
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+/// `selected` are store names from preview_provider_pull, secrets already equal are skipped
+#[tauri::command]
+pub async fn pull_from_provider(
+    provider: SyncProviderConfig,
+    credentials_secret_id: String,
+    path: Option<String>,
+    target_box_id: String,
+    selected: Vec<String>,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<SyncResult, ZapError> {
+    let audit = CommandAudit::new("pull_from_provider")
+        .arg("provider", &provider)
+        .arg("credentials_secret_id", &credentials_secret_id)
+        .arg("path", &path)
+        .arg("target_box_id", &target_box_id)
+        .arg("selected", &selected);
+    let result = async {
+        provider.validate()?;
+        let credentials_secret_id = input::id("Credentials secret id", credentials_secret_id)?;
+        let path = input::optional_text("Path", path, input::MAX_QUERY_LENGTH)?;
+        let target_box_id = input::id("Box id", target_box_id)?;
+        let selected = selected
+            .into_iter()
+            .map(|name| input::required_text("Secret name", name, input::MAX_KEY_LENGTH))
+            .collect::<Result<Vec<_>, _>>()?;
+        app_state.touched(
+            app_state
+                .pull_from_provider(
+                    provider,
+                    credentials_secret_id,
+                    path,
+                    target_box_id,
+                    selected,
+                )
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+/// Two-way sync with a replica, conflicts are reported and left for resolve_sync_conflict
+#[tauri::command]
+pub async fn sync_replica(
+    replica_id: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<ReplicaSyncReport, ZapError> {
+    let audit = CommandAudit::new("sync_replica").arg("replica_id", &replica_id);
+    let result = async {
+        let replica_id = input::id("Replica id", replica_id)?;
+        app_state.touched(app_state.sync_replica(&replica_id).await)
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+#[tauri::command]
+pub async fn list_sync_conflicts(
+    replica_id: String,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<Vec<SyncConflict>, ZapError> {
+    let audit = CommandAudit::new("list_sync_conflicts").arg("replica_id", &replica_id);
+    let result = async {
+        let replica_id = input::id("Replica id", replica_id)?;
+        app_state.list_sync_conflicts(&replica_id).await
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
+
+/// Returns the replica's open conflicts, the resolution is applied by the next sync_replica
+#[tauri::command]
+pub async fn resolve_sync_conflict(
+    replica_id: String,
+    record_key: String,
+    resolution: ConflictResolution,
+    app_state: State<'_, Arc<AppState>>,
+) -> Result<Vec<SyncConflict>, ZapError> {
+    let audit = CommandAudit::new("resolve_sync_conflict")
+        .arg("replica_id", &replica_id)
+        .arg("record_key", &record_key)
+        .arg("resolution", &resolution);
+    let result = async {
+        let replica_id = input::id("Replica id", replica_id)?;
+        let record_key = input::id("Record key", record_key)?;
+        app_state.touched(
+            app_state
+                .resolve_sync_conflict(&replica_id, &record_key, resolution)
+                .await,
+        )
+    }
+    .await;
+    audit.finish(&app_state, result)
+}
@@ -3,30 +3,75 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use tauri::Manager;
 
+pub mod auto_export;
+pub mod bindings;
+pub mod breach_monitor;
 pub mod commands;
+pub mod logging;
 pub mod models;
+pub mod rotation_reminder;
 pub mod services;
+pub mod session_proxy;
+pub mod session_reaper;
+pub mod session_watcher;
 pub mod states;
 pub mod utils;
 pub mod window_manager;
 
+use crate::commands::audit::CommandAudit;
+use crate::models::ZapError;
 use crate::states::{AppState, DevState};
-use crate::utils::path_resolvers::{ensure_directories_exist, get_app_data_dir_legacy};
+use crate::utils::path_resolvers::{
+    ensure_directories_exist, get_app_data_dir_legacy, migrate_legacy_directories,
+    portable_directory, set_path_provider, PortablePaths, TauriPaths,
+};
 use commands::*;
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-    tauri::Builder::default()
+    logging::init();
+
+    let builder = tauri::Builder::default();
+    // Must be registered first: a second launch hands off to us and exits
+    // before it reaches setup and trips over the locked sled database
+    #[cfg(desktop)]
+    let builder = builder.plugin(tauri_plugin_single_instance::init(|app, _args, _cwd| {
+        if let Err(e) = window_manager::WindowManager::focus_main_window(app) {
+            tracing::warn!("Failed to focus running instance: {}", e);
+        }
+    }));
+
+    builder
         .plugin(tauri_plugin_opener::init())
-        .setup(setup_desktop)
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .plugin(tauri_plugin_notification::init())
+        .setup(setup_app)
+        .on_window_event(|window, event| {
+            if let tauri::WindowEvent::Focused(true) = event {
+                if let Some(app_state) = window.try_state::<Arc<AppState>>() {
+                    app_state.extend_if_expiring();
+                }
+            }
+            window_manager::WindowManager::on_window_event(window, event);
+        })
         .invoke_handler(tauri::generate_handler![
             // Auth Commands
             initialize_app,
+            get_startup_status,
+            recover_storage,
             unlock_vault,
+            unlock_with_remembered_key,
             lock_vault,
             is_vault_locked,
             verify_master_password,
             get_session_info,
+            touch_session,
+            get_emergency_kit,
+            regenerate_recovery_key,
+            unlock_with_recovery_key,
+            get_hardware_key_status,
+            enable_hardware_key,
+            disable_hardware_key,
             // Box Commands
             get_all_boxes,
             get_box,
@@ -34,52 +79,165 @@ pub fn run() {
             update_box,
             delete_box,
             delete_selected_boxes,
+            detach_shared_box,
             search_boxes_global,
+            diff_boxes,
+            undo_last_operation,
             // Secret Commands
             get_all_secrets,
             get_secrets_by_box_id,
+            get_secret_templates,
             create_secret,
             update_secret,
+            set_secret_variant,
+            remove_secret_variant,
             delete_secret,
             delete_selected_secrets,
             copy_secrets_to_box,
             reveal_secret_value,
+            reveal_secret_handle,
+            copy_revealed_secret,
+            set_rotation_interval,
+            mark_rotated,
+            get_secrets_due_for_rotation,
+            get_compromised_secrets,
+            run_breach_check_now,
+            set_secret_favorite,
+            get_favorite_secrets,
+            get_recent_secrets,
             search_secrets_in_box,
+            deep_search,
+            create_one_time_share,
+            redeem_share,
             // Import/Export Commands
             export_vault,
+            get_export_signing_key,
             export_box_as_env,
+            export_box_as_config,
+            export_box_as_tfvars,
+            export_box_for_recipient,
+            export_box_for_external_secrets,
+            push_box_to_github,
+            preview_gitlab_sync,
+            sync_box_to_gitlab,
+            render_template,
             import_vault,
+            import_vault_merge,
+            diff_vault_against_export,
+            import_shared_box,
+            run_auto_export_now,
+            import_encrypted_backup,
             import_env_to_box,
+            import_config_to_box,
+            import_csv_to_box,
+            preview_smart_import,
+            smart_import,
+            // Job Commands
+            start_import_vault_job,
+            start_export_vault_job,
+            start_breach_check_job,
+            start_lan_transfer,
+            start_lan_receive,
+            get_job,
+            list_jobs,
+            take_job_payload,
+            cancel_job,
             // Dev Commands
             create_session,
+            create_proxy_session,
+            get_proxy_sessions,
+            revoke_proxy_session,
+            answer_app_access,
             get_all_sessions,
             get_dev_session_info,
+            export_session_as_env,
+            export_session_as_ssh_config,
+            render_template_from_session,
+            rename_session,
             stop_session,
             clear_all_sessions,
             has_any_sessions,
             get_available_dev_boxes,
             get_dev_stats,
+            get_session_file_schema,
             validate_session_name,
             validate_session_key,
+            bind_project,
+            unbind_project,
+            get_project_binding,
+            create_session_for_project,
+            write_envrc,
+            check_env_hygiene,
+            import_and_shred_env,
             // Log Commands
             get_all_logs,
+            get_filtered_logs,
             clear_all_logs,
             export_logs,
             // Stats Commands
             get_vault_stats,
+            export_stats_report,
+            get_storage_usage,
+            run_diagnostics,
+            compact_storage,
+            list_vault_snapshots,
+            restore_snapshot,
             // Settings Commands
             get_settings,
             update_settings,
+            get_data_directory_info,
+            move_data_directory,
+            // Sync Commands
+            diff_sync_mapping,
+            push_sync_mapping,
+            pull_sync_mapping,
+            preview_provider_pull,
+            pull_from_provider,
+            sync_replica,
+            list_sync_conflicts,
+            resolve_sync_conflict,
             // Window Commands
             toggle_visibility,
             get_window_state,
             initialize_right_edge_position,
+            apply_window_layout,
+            show_sidebar,
+            hide_sidebar,
+            set_slide_offset,
+            get_slide_info,
+            open_mini_window,
+            close_mini_window,
         ])
-        .run(tauri::generate_context!())
-        .expect("error while running tauri application");
+        .build(tauri::generate_context!())
+        .expect("error while building tauri application")
+        .run(|app, event| {
+            // Managed state isn't dropped on exit, so write out pending changes here
+            if let tauri::RunEvent::Exit = event {
+                if let Some(app_state) = app.try_state::<Arc<AppState>>() {
+                    if let Err(e) = app_state.storage.flush() {
+                        tracing::error!("Failed to flush storage on exit: {}", e);
+                    }
+                }
+            }
+        });
 }
 
-fn setup_desktop(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+fn setup_app(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+    let launch_args: Vec<String> = std::env::args().skip(1).collect();
+    if let Some(root) = portable_directory(&launch_args) {
+        // Portable: everything stays in one folder, nothing is written to the user's profile
+        tracing::info!("Running portable from {}", root.display());
+        set_path_provider(PortablePaths::new(root))?;
+    } else {
+        // Use the platform's config, data and log directories as Tauri reports them
+        set_path_provider(TauriPaths::resolve(app.path())?)?;
+
+        // Earlier versions kept everything under one folder, move it to where it now belongs
+        if let Err(e) = migrate_legacy_directories() {
+            tracing::warn!("Failed to move legacy data directories: {}", e);
+        }
+    }
+
     // Ensure directory structure exists
     ensure_directories_exist().map_err(|e| Box::new(e) as Box<dyn std::error::Error>)?;
 
@@ -88,12 +246,41 @@ fn setup_desktop(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>>
 
     // Initialize AppState
     let app_state = Arc::new(AppState::new());
+    app_state.attach_app_handle(app.handle().clone());
     app.manage(app_state.clone());
 
     // Initialize DevState
     let dev_state = DevState::new(Arc::clone(&app_state));
     app.manage(tokio::sync::Mutex::new(dev_state));
 
+    // Notify when secrets are due for rotation
+    rotation_reminder::start(app.handle().clone());
+
+    // Write encrypted backups on the user's schedule
+    auto_export::start(app.handle().clone());
+
+    // Check passwords against known breaches, if the user opted in
+    breach_monitor::start(app.handle().clone());
+
+    #[cfg(desktop)]
+    setup_desktop(app)?;
+
+    Ok(())
+}
+
+// The CLI, the global shortcut and the docked window only exist on desktop
+#[cfg(desktop)]
+fn setup_desktop(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+    // Watch CLI session files for external deletions/tampering
+    let session_watcher = session_watcher::SessionWatcher::start(app.handle().clone())?;
+    app.manage(session_watcher);
+
+    // Serve proxy sessions to the CLI over loopback
+    session_proxy::start(app.handle().clone());
+
+    // Stop dev sessions the CLI hasn't used in a while, if the user set a limit
+    session_reaper::start(app.handle().clone());
+
     // Setup global shortcuts and window management
     setup_global_shortcuts(app)?;
     setup_window_positioning(app)?;
@@ -101,6 +288,7 @@ fn setup_desktop(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>>
     Ok(())
 }
 
+#[cfg(desktop)]
 fn setup_global_shortcuts(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
     use tauri_plugin_global_shortcut::{
         Code, GlobalShortcutExt, Modifiers, Shortcut, ShortcutState,
@@ -126,6 +314,7 @@ fn setup_global_shortcuts(app: &mut tauri::App) -> Result<(), Box<dyn std::error
     Ok(())
 }
 
+#[cfg(desktop)]
 fn setup_window_positioning(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
     window_manager::WindowManager::initialize_right_edge(&app.handle())?;
     Ok(())
@@ -139,16 +328,124 @@ fn get_app_data_dir() -> Result<PathBuf, Box<dyn std::error::Error>> {
 
 // Window Commands
 #[tauri::command]
-fn toggle_visibility(app: tauri::AppHandle) -> Result<(), String> {
-    window_manager::handle_toggle_visibility(app)
+fn toggle_visibility(
+    app: tauri::AppHandle,
+    app_state: tauri::State<'_, Arc<AppState>>,
+) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("toggle_visibility");
+    audit.finish(
+        &app_state,
+        window_manager::handle_toggle_visibility(app).map_err(ZapError::PlatformError),
+    )
+}
+
+#[tauri::command]
+fn get_window_state(
+    app: tauri::AppHandle,
+    app_state: tauri::State<'_, Arc<AppState>>,
+) -> Result<window_manager::WindowState, ZapError> {
+    let audit = CommandAudit::new("get_window_state");
+    audit.finish(
+        &app_state,
+        window_manager::handle_get_window_state(app).map_err(ZapError::PlatformError),
+    )
+}
+
+#[tauri::command]
+fn initialize_right_edge_position(
+    app: tauri::AppHandle,
+    app_state: tauri::State<'_, Arc<AppState>>,
+) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("initialize_right_edge_position");
+    audit.finish(
+        &app_state,
+        window_manager::handle_initialize_right_edge(app).map_err(ZapError::PlatformError),
+    )
+}
+
+#[tauri::command]
+fn apply_window_layout(
+    app: tauri::AppHandle,
+    app_state: tauri::State<'_, Arc<AppState>>,
+) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("apply_window_layout");
+    audit.finish(
+        &app_state,
+        window_manager::handle_apply_window_layout(app).map_err(ZapError::PlatformError),
+    )
+}
+
+#[tauri::command]
+fn show_sidebar(
+    app: tauri::AppHandle,
+    offset: Option<u32>,
+    app_state: tauri::State<'_, Arc<AppState>>,
+) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("show_sidebar").arg("offset", &offset);
+    audit.finish(
+        &app_state,
+        window_manager::handle_show_sidebar(app, offset.unwrap_or(0))
+            .map_err(ZapError::PlatformError),
+    )
+}
+
+#[tauri::command]
+fn hide_sidebar(
+    app: tauri::AppHandle,
+    app_state: tauri::State<'_, Arc<AppState>>,
+) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("hide_sidebar");
+    audit.finish(
+        &app_state,
+        window_manager::handle_hide_sidebar(app).map_err(ZapError::PlatformError),
+    )
+}
+
+#[tauri::command]
+fn set_slide_offset(
+    app: tauri::AppHandle,
+    offset: u32,
+    app_state: tauri::State<'_, Arc<AppState>>,
+) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("set_slide_offset").arg("offset", &offset);
+    audit.finish(
+        &app_state,
+        window_manager::handle_set_slide_offset(app, offset).map_err(ZapError::PlatformError),
+    )
+}
+
+#[tauri::command]
+fn get_slide_info(
+    app: tauri::AppHandle,
+    app_state: tauri::State<'_, Arc<AppState>>,
+) -> Result<window_manager::SlideInfo, ZapError> {
+    let audit = CommandAudit::new("get_slide_info");
+    audit.finish(
+        &app_state,
+        Ok(window_manager::WindowManager::get_slide_info(&app)),
+    )
 }
 
 #[tauri::command]
-fn get_window_state(app: tauri::AppHandle) -> Result<window_manager::WindowState, String> {
-    window_manager::handle_get_window_state(app)
+fn open_mini_window(
+    app: tauri::AppHandle,
+    app_state: tauri::State<'_, Arc<AppState>>,
+) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("open_mini_window");
+    audit.finish(
+        &app_state,
+        window_manager::handle_open_mini_window(app).map_err(ZapError::PlatformError),
+    )
 }
 
 #[tauri::command]
-fn initialize_right_edge_position(app: tauri::AppHandle) -> Result<(), String> {
-    window_manager::handle_initialize_right_edge(app)
+fn close_mini_window(
+    app: tauri::AppHandle,
+    app_state: tauri::State<'_, Arc<AppState>>,
+) -> Result<(), ZapError> {
+    let audit = CommandAudit::new("close_mini_window");
+    audit.finish(
+        &app_state,
+        window_manager::handle_close_mini_window(app).map_err(ZapError::PlatformError),
+    )
 }
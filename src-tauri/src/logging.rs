@@ -0,0 +1,134 @@
+// src/logging.rs - Structured diagnostics via tracing (Settings: file_logging)
+// Console output follows ZAP_LOG (e.g. ZAP_LOG=zap_lib=debug). Log files are opt-in and rotate
+// daily. Audit entries stay in the logs database; these are for troubleshooting only.
+
+use crate::models::{DiagnosticLogLevel, FileLoggingSettings, ZapError};
+use crate::utils::path_resolvers::get_diagnostics_directory;
+use std::fmt;
+use std::io::{self, Write};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::Mutex;
+use tracing::Level;
+use tracing_appender::non_blocking::{NonBlocking, WorkerGuard};
+use tracing_appender::rolling::{Builder, Rotation};
+use tracing_subscriber::filter::{filter_fn, EnvFilter};
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Layer;
+
+pub const LOG_ENV_VAR: &str = "ZAP_LOG";
+const DEFAULT_CONSOLE_FILTER: &str = "zap_lib=info,warn";
+const LOG_FILE_PREFIX: &str = "zap";
+
+// 0 = file logging off, otherwise the most verbose level written (see level_rank)
+static FILE_LEVEL: AtomicU8 = AtomicU8::new(0);
+static FILE_LOG: Mutex<Option<FileLog>> = Mutex::new(None);
+
+struct FileLog {
+    writer: NonBlocking,
+    _guard: WorkerGuard, // Flushes buffered lines when the file log is closed
+}
+
+/// Install the global subscriber. Call once, before anything logs.
+pub fn init() {
+    let console_filter = EnvFilter::try_from_env(LOG_ENV_VAR)
+        .unwrap_or_else(|_| EnvFilter::new(DEFAULT_CONSOLE_FILTER));
+    let console = tracing_subscriber::fmt::layer()
+        .with_writer(io::stderr)
+        .with_target(false)
+        .with_filter(console_filter);
+
+    let file = tracing_subscriber::fmt::layer()
+        .with_writer(FileLogWriter)
+        .with_ansi(false)
+        .with_filter(filter_fn(|metadata| {
+            level_rank(*metadata.level()) <= FILE_LEVEL.load(Ordering::Relaxed)
+        }));
+
+    // Fails only if a subscriber is already set, e.g. init() was called twice
+    let _ = tracing_subscriber::registry()
+        .with(console)
+        .with(file)
+        .try_init();
+}
+
+/// Open, reconfigure or close the log file to match the settings
+pub fn apply_settings(settings: &FileLoggingSettings) -> Result<(), ZapError> {
+    let mut file_log = FILE_LOG.lock().unwrap();
+    if !settings.enabled {
+        FILE_LEVEL.store(0, Ordering::Relaxed);
+        *file_log = None;
+        return Ok(());
+    }
+
+    let dir = get_diagnostics_directory().map_err(|e| {
+        ZapError::StorageError(format!("Failed to get diagnostics directory: {}", e))
+    })?;
+    std::fs::create_dir_all(&dir)?;
+    let appender = Builder::new()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_PREFIX)
+        .filename_suffix("log")
+        .max_log_files(settings.keep_days as usize)
+        .build(&dir)
+        .map_err(|e| ZapError::StorageError(format!("Failed to open log file: {}", e)))?;
+
+    let (writer, guard) = tracing_appender::non_blocking(appender);
+    *file_log = Some(FileLog {
+        writer,
+        _guard: guard,
+    });
+    FILE_LEVEL.store(level_rank(max_level(settings.level)), Ordering::Relaxed);
+    Ok(())
+}
+
+/// Box and secret names in log lines: shown in debug builds, "[redacted]" in release builds
+pub fn redact<T: fmt::Display>(value: T) -> Redacted<T> {
+    Redacted(value)
+}
+
+pub struct Redacted<T>(T);
+
+impl<T: fmt::Display> fmt::Display for Redacted<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if cfg!(debug_assertions) {
+            write!(f, "'{}'", self.0)
+        } else {
+            f.write_str("[redacted]")
+        }
+    }
+}
+
+fn max_level(level: DiagnosticLogLevel) -> Level {
+    match level {
+        DiagnosticLogLevel::Error => Level::ERROR,
+        DiagnosticLogLevel::Warn => Level::WARN,
+        DiagnosticLogLevel::Info => Level::INFO,
+        DiagnosticLogLevel::Debug => Level::DEBUG,
+    }
+}
+
+fn level_rank(level: Level) -> u8 {
+    match level {
+        Level::ERROR => 1,
+        Level::WARN => 2,
+        Level::INFO => 3,
+        Level::DEBUG => 4,
+        _ => 5, // TRACE
+    }
+}
+
+// Writes to the current log file, or nowhere while file logging is off
+struct FileLogWriter;
+
+impl<'a> MakeWriter<'a> for FileLogWriter {
+    type Writer = Box<dyn Write + 'a>;
+
+    fn make_writer(&'a self) -> Self::Writer {
+        match FILE_LOG.lock().unwrap().as_ref() {
+            Some(file_log) => Box::new(file_log.writer.clone()),
+            None => Box::new(io::sink()),
+        }
+    }
+}
@@ -1,8 +1,9 @@
 // src/models/auth_model.rs
 
-use crate::models::ZapError;
+use crate::models::{EncryptedData, ZapError};
 use serde::{Deserialize, Serialize};
 use std::time::Instant;
+use ts_rs::TS;
 
 #[derive(Debug, Clone)]
 pub struct SessionState {
@@ -10,6 +11,9 @@ pub struct SessionState {
     pub time_left_seconds: u32,
     pub master_key: Option<[u8; 32]>,
     pub last_activity: Option<Instant>,
+    pub unlocked_at: Option<Instant>,
+    pub max_session_seconds: u32, // Hard cap since unlock, 0 = no cap
+    pub held_operations: u32,     // Running jobs that pause the idle countdown
 }
 
 impl SessionState {
@@ -19,13 +23,17 @@ impl SessionState {
             time_left_seconds: 0,
             master_key: None,
             last_activity: None,
+            unlocked_at: None,
+            max_session_seconds: 0,
+            held_operations: 0,
         }
     }
 
     pub fn unlock(&mut self, master_key: [u8; 32], timeout_minutes: u32) {
         self.is_unlocked = true;
         self.master_key = Some(master_key);
-        self.time_left_seconds = timeout_minutes * 60;
+        self.unlocked_at = Some(Instant::now());
+        self.time_left_seconds = self.capped_time_left(timeout_minutes * 60);
         self.last_activity = Some(Instant::now());
     }
 
@@ -34,12 +42,16 @@ impl SessionState {
         self.time_left_seconds = 0;
         self.master_key = None;
         self.last_activity = None;
+        self.unlocked_at = None;
     }
 
     pub fn tick(&mut self) {
         if self.is_unlocked && self.time_left_seconds > 0 {
-            self.time_left_seconds -= 1;
-            if self.time_left_seconds == 0 {
+            // A held session doesn't idle out, the absolute cap still applies
+            if self.held_operations == 0 {
+                self.time_left_seconds -= 1;
+            }
+            if self.time_left_seconds == 0 || self.absolute_seconds_left() == Some(0) {
                 self.lock();
             }
         }
@@ -47,10 +59,30 @@ impl SessionState {
 
     pub fn reset_timer(&mut self, timeout_minutes: u32) {
         if self.is_unlocked {
-            self.time_left_seconds = timeout_minutes * 60;
+            self.time_left_seconds = self.capped_time_left(timeout_minutes * 60);
             self.last_activity = Some(Instant::now());
         }
     }
+
+    // Seconds until the absolute cap is reached (None when uncapped)
+    pub fn absolute_seconds_left(&self) -> Option<u32> {
+        if self.max_session_seconds == 0 {
+            return None;
+        }
+        let elapsed = self
+            .unlocked_at
+            .map(|t| t.elapsed().as_secs() as u32)
+            .unwrap_or(0);
+        Some(self.max_session_seconds.saturating_sub(elapsed))
+    }
+
+    // Idle timeout never extends past the absolute cap
+    fn capped_time_left(&self, idle_seconds: u32) -> u32 {
+        match self.absolute_seconds_left() {
+            Some(absolute) => idle_seconds.min(absolute),
+            None => idle_seconds,
+        }
+    }
 }
 
 impl Default for SessionState {
@@ -59,11 +91,36 @@ impl Default for SessionState {
     }
 }
 
+// Argon2id cost parameters, calibrated per machine at first setup
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct KdfParams {
+    pub memory_kib: u32,
+    pub iterations: u32,
+    pub parallelism: u32,
+}
+
+impl Default for KdfParams {
+    // argon2 crate defaults - what vaults created before calibration used
+    fn default() -> Self {
+        Self {
+            memory_kib: 19 * 1024,
+            iterations: 2,
+            parallelism: 1,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthConfig {
     pub session_timeout_minutes: u8,
     pub master_password_hash: Option<String>,
     pub salt: [u8; 32],
+    #[serde(default)]
+    pub recovery: Option<RecoveryConfig>,
+    #[serde(default)]
+    pub kdf_params: KdfParams,
+    #[serde(default)]
+    pub hardware_key: Option<HardwareKeyConfig>, // When set, unlock takes a PIN plus the key
 }
 
 impl AuthConfig {
@@ -72,6 +129,9 @@ impl AuthConfig {
             session_timeout_minutes: 5,
             master_password_hash: None,
             salt,
+            recovery: None,
+            kdf_params: KdfParams::default(),
+            hardware_key: None,
         }
     }
 
@@ -90,8 +150,93 @@ impl AuthConfig {
     }
 }
 
-#[derive(Debug, Serialize)]
+// Master key wrapped with a key held in the OS keyring ("remember me")
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RememberedKey {
+    pub wrapped_master_key: EncryptedData,
+    pub boot_time: u64, // Only valid until the next reboot
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+// Master key wrapped with the user's recovery key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveryConfig {
+    pub wrapped_master_key: EncryptedData,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+// YubiKey HMAC-SHA1 challenge-response slot mixed into the master key derivation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HardwareKeyConfig {
+    pub slot: u8,            // OTP slot 1 or 2, programmed for challenge-response
+    pub challenge: [u8; 32], // Sent on every unlock - the response itself is never stored
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+// What the lock screen should ask for
+#[derive(Debug, Serialize, TS)]
+pub struct HardwareKeyStatus {
+    pub enabled: bool,
+    pub slot: Option<u8>,
+    pub device_present: bool,
+}
+
+// Printable emergency kit - only ever returned right after a recovery key is generated
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct EmergencyKit {
+    pub recovery_key: String,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    #[ts(type = "number")]
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub data_directory: String,
+    pub instructions: String,
+}
+
+// Where the session stands, for the lock screen and countdown UIs
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionPhase {
+    Locked,
+    Unlocked,
+    GracePeriod, // Unlocked, but the re-auth window has lapsed (Settings: reauth_minutes)
+    Expiring,    // Auto-lock is within lock_warning_seconds
+}
+
+impl SessionPhase {
+    pub fn of(
+        is_locked: bool,
+        time_left_seconds: u32,
+        warning_seconds: u32,
+        reauth_lapsed: bool,
+    ) -> Self {
+        if is_locked {
+            SessionPhase::Locked
+        } else if warning_seconds > 0 && time_left_seconds <= warning_seconds {
+            SessionPhase::Expiring
+        } else if reauth_lapsed {
+            SessionPhase::GracePeriod
+        } else {
+            SessionPhase::Unlocked
+        }
+    }
+}
+
+// Payload of get_session_info and the session state event
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, TS)]
 pub struct SessionInfo {
     pub is_locked: bool,
-    pub time_left_seconds: u32,
+    pub time_left_seconds: u32, // Computed by the backend, so it stays right across sleep/wake
+    pub phase: SessionPhase,
+}
+
+impl SessionInfo {
+    // Whether `self` is worth an event after `previous`, `elapsed_seconds` later.
+    // A plain countdown isn't - the UI counts down on its own between events.
+    pub fn is_transition_from(&self, previous: &SessionInfo, elapsed_seconds: u32) -> bool {
+        self.phase != previous.phase
+            || self.time_left_seconds != previous.time_left_seconds.saturating_sub(elapsed_seconds)
+    }
 }
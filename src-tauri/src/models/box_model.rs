@@ -1,9 +1,14 @@
 // src/models/box_model.rs
 
-use crate::models::ZapError;
+use crate::models::{EncryptedData, ZapError};
+use crate::utils::input_validation::grapheme_count;
 use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::sync::OnceLock;
+use ts_rs::TS;
+
+pub const PROD_TAG: &str = "prod"; // Dev sessions may need approval, see Settings
+pub const MAX_SECRETS_PER_BOX: usize = 75;
 
 static TAG_REGEX: OnceLock<Regex> = OnceLock::new();
 
@@ -11,7 +16,19 @@ fn get_tag_regex() -> &'static Regex {
     TAG_REGEX.get_or_init(|| Regex::new(r"^[a-z0-9\-]{1,15}$").unwrap())
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// Where a shared box came from, kept to recognise newer exports of the same box
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct BoxProvenance {
+    pub source_box_id: Option<String>, // Box id on the exporting machine
+    #[serde(with = "chrono::serde::ts_seconds")]
+    #[ts(type = "number")]
+    pub exported_at: chrono::DateTime<chrono::Utc>,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    #[ts(type = "number")]
+    pub imported_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct Box {
     pub id: String,
     pub name: String,                // 50 chars max
@@ -19,9 +36,17 @@ pub struct Box {
     pub tags: Vec<String>,
     pub dev_mode: bool,
     pub secrets_count: usize,
+    #[serde(default)]
+    pub wrapped_key: Option<EncryptedData>, // Data key wrapped by the master key, None = legacy
+    #[serde(default)]
+    pub read_only: bool, // Owned by a teammate, only updated by importing their newer export
+    #[serde(default)]
+    pub provenance: Option<BoxProvenance>,
     #[serde(with = "chrono::serde::ts_seconds")]
+    #[ts(type = "number")]
     pub created_at: chrono::DateTime<chrono::Utc>,
     #[serde(with = "chrono::serde::ts_seconds")]
+    #[ts(type = "number")]
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
@@ -46,11 +71,37 @@ impl Box {
             tags,
             dev_mode,
             secrets_count: 0,
+            wrapped_key: None,
+            read_only: false,
+            provenance: None,
             created_at: now,
             updated_at: now,
         })
     }
 
+    pub fn with_wrapped_key(mut self, wrapped_key: EncryptedData) -> Self {
+        self.wrapped_key = Some(wrapped_key);
+        self
+    }
+
+    pub fn as_shared(mut self, provenance: BoxProvenance) -> Self {
+        self.read_only = true;
+        self.provenance = Some(provenance);
+        self
+    }
+
+    /// Tagged "prod" (tags are always lowercase)
+    pub fn is_prod(&self) -> bool {
+        self.tags.iter().any(|tag| tag == PROD_TAG)
+    }
+
+    pub fn ensure_writable(&self) -> Result<(), ZapError> {
+        if self.read_only {
+            return Err(ZapError::ReadOnlyBox(self.name.clone()));
+        }
+        Ok(())
+    }
+
     pub fn update_fields(
         &mut self,
         name: Option<String>,
@@ -104,7 +155,7 @@ impl Box {
                 "Box name cannot be empty".to_string(),
             ));
         }
-        if trimmed.len() > 50 {
+        if grapheme_count(trimmed) > 50 {
             return Err(ZapError::ValidationError(
                 "Box name cannot exceed 50 characters".to_string(),
             ));
@@ -113,7 +164,7 @@ impl Box {
     }
 
     pub fn validate_description(description: &str) -> Result<(), ZapError> {
-        if description.len() > 75 {
+        if grapheme_count(description) > 75 {
             return Err(ZapError::ValidationError(
                 "Box description cannot exceed 75 characters".to_string(),
             ));
@@ -147,10 +198,11 @@ impl Box {
     }
 
     pub fn can_add_secret(&self) -> Result<(), ZapError> {
-        if self.secrets_count >= 75 {
-            return Err(ZapError::ValidationError(
-                "Box has reached maximum capacity (75 secrets)".to_string(),
-            ));
+        if self.secrets_count >= MAX_SECRETS_PER_BOX {
+            return Err(ZapError::ValidationError(format!(
+                "Box has reached maximum capacity ({} secrets)",
+                MAX_SECRETS_PER_BOX
+            )));
         }
         Ok(())
     }
@@ -1,8 +1,11 @@
 // src/models/dev_model.rs 
 
-use crate::models::EncryptedData;
+use crate::models::{EncryptedData, ZapError};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
+use ts_rs::TS;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DevSession {
@@ -11,6 +14,8 @@ pub struct DevSession {
     pub box_name: String,      // Source box name for display
     pub session_key: [u8; 32], // Unique encryption key for this session
     pub encrypted_secrets: HashMap<String, EncryptedData>, // secret_name -> encrypted_data
+    #[serde(default, with = "chrono::serde::ts_seconds_option")]
+    pub created_at: Option<DateTime<Utc>>, // None = created before this was tracked
 }
 
 impl DevSession {
@@ -27,29 +32,220 @@ impl DevSession {
             box_name,
             session_key,
             encrypted_secrets,
+            created_at: Some(Utc::now()),
         }
     }
 
     pub fn secrets_count(&self) -> usize {
         self.encrypted_secrets.len()
     }
+
+    // None when sessions never expire (lifetime 0) or the creation time is unknown
+    pub fn expires_at(&self, lifetime_hours: u32) -> Option<DateTime<Utc>> {
+        if lifetime_hours == 0 {
+            return None;
+        }
+        self.created_at
+            .map(|created_at| created_at + chrono::Duration::hours(lifetime_hours as i64))
+    }
+
+    pub fn is_expired(&self, lifetime_hours: u32) -> bool {
+        self.expires_at(lifetime_hours)
+            .is_some_and(|expires_at| expires_at <= Utc::now())
+    }
+}
+
+// The CLI writes Unix seconds to `<session-name>.last_used` next to the session file each
+// time it loads the session (it can't write to the app's database)
+pub const SESSION_ACTIVITY_EXTENSION: &str = "last_used";
+
+pub fn session_activity_file_name(session_name: &str) -> String {
+    format!("{}.{}", session_name, SESSION_ACTIVITY_EXTENSION)
+}
+
+/// Whether a file is a session record rather than any other .json sharing the directory.
+/// Formats this build can't read still count, only the shape is checked.
+pub fn is_session_file(path: &Path) -> bool {
+    path.extension().and_then(|s| s.to_str()) == Some("json")
+        && std::fs::read_to_string(path)
+            .is_ok_and(|content| serde_json::from_str::<CliSessionFile>(&content).is_ok())
+}
+
+// Bump when CliSessionFile changes in a way older readers can't handle
+pub const SESSION_FILE_FORMAT_VERSION: u32 = 1;
+pub const MIN_SESSION_FILE_FORMAT_VERSION: u32 = 1; // Oldest format this build still reads
+
+// Files written before versioning are format 1
+fn legacy_format_version() -> u32 {
+    1
+}
+
+// Session file formats one side writes and reads. The CLI publishes its own copy
+// (CLI_SCHEMA_FILE) so the desktop knows what it may write.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct SessionFileSchema {
+    pub format_version: u32,     // Format written
+    pub min_format_version: u32, // Oldest format readable
+}
+
+impl SessionFileSchema {
+    pub fn current() -> Self {
+        Self {
+            format_version: SESSION_FILE_FORMAT_VERSION,
+            min_format_version: MIN_SESSION_FILE_FORMAT_VERSION,
+        }
+    }
+
+    pub fn can_read(&self, format_version: u32) -> bool {
+        (self.min_format_version..=self.format_version).contains(&format_version)
+    }
+}
+
+// Response struct for UI - what this build and the paired CLI support
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SessionFileCompatibility {
+    pub desktop: SessionFileSchema,
+    pub cli: Option<SessionFileSchema>, // None = CLI never ran (or predates versioning)
+    pub compatible: bool,
+}
+
+// Session file written to the sessions directory - the format the CLIs read.
+// Secrets are hex-encoded JSON of EncryptedData, sealed with the session key.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CliSessionFile {
+    #[serde(default = "legacy_format_version")]
+    pub format_version: u32,
+    pub session_name: String,
+    pub box_name: String,
+    pub session_key: String,                        // Hex
+    pub encrypted_secrets: HashMap<String, String>, // secret_name -> hex(json(EncryptedData))
+    pub created_at: DateTime<Utc>,
+}
+
+impl CliSessionFile {
+    pub fn from_session(session: &DevSession) -> Result<Self, ZapError> {
+        Ok(Self {
+            format_version: SESSION_FILE_FORMAT_VERSION,
+            session_name: session.session_name.clone(),
+            box_name: session.box_name.clone(),
+            session_key: hex::encode(session.session_key),
+            encrypted_secrets: Self::encode_secrets(session)?,
+            created_at: Utc::now(),
+        })
+    }
+
+    /// Parse a session file, refusing formats this build can't read
+    pub fn parse(content: &str) -> Result<Self, ZapError> {
+        let file: Self = serde_json::from_str(content)?;
+        let schema = SessionFileSchema::current();
+        if !schema.can_read(file.format_version) {
+            return Err(ZapError::IncompatibleSessionFormat(format!(
+                "session '{}' uses format {}, this version reads formats {}-{}",
+                file.session_name,
+                file.format_version,
+                schema.min_format_version,
+                schema.format_version
+            )));
+        }
+        Ok(file)
+    }
+
+    pub fn encode_secrets(session: &DevSession) -> Result<HashMap<String, String>, ZapError> {
+        let mut hex_secrets = HashMap::new();
+        for (name, encrypted_data) in &session.encrypted_secrets {
+            let serialized = serde_json::to_vec(encrypted_data)?;
+            hex_secrets.insert(name.clone(), hex::encode(serialized));
+        }
+        Ok(hex_secrets)
+    }
+
+    pub fn decode_secret(hex_secret: &str) -> Result<EncryptedData, ZapError> {
+        let serialized = hex::decode(hex_secret)
+            .map_err(|_| ZapError::CryptoError("Invalid secret encoding".to_string()))?;
+        Ok(serde_json::from_slice(&serialized)?)
+    }
+
+    // Same content as the database record (created_at is ignored)
+    pub fn matches(&self, session: &DevSession) -> Result<bool, ZapError> {
+        Ok(self.session_name == session.session_name
+            && self.box_name == session.box_name
+            && self.session_key == hex::encode(session.session_key)
+            && self.encrypted_secrets == Self::encode_secrets(session)?)
+    }
+}
+
+// Given to start a session from a prod box (Settings: require_prod_session_approval)
+#[derive(Debug, Clone)]
+pub struct ProdApproval {
+    pub password: String,
+    pub justification: String, // Stored in the audit log with the session
+}
+
+// Which of a box's secrets go into a session, by name - every secret by default
+#[derive(Debug, Clone, Default)]
+pub struct SecretSelection {
+    pub include: Option<Vec<String>>, // Only these, None = all of them
+    pub exclude: Vec<String>,         // Left out even when included
+}
+
+impl SecretSelection {
+    pub fn is_everything(&self) -> bool {
+        self.include.is_none() && self.exclude.is_empty()
+    }
 }
 
 // Response struct for UI - list of active sessions
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, TS)]
 pub struct ActiveSessionInfo {
     pub session_name: String,
+    pub box_id: String,
     pub box_name: String,
     pub secrets_count: usize,
     pub is_active: bool, // Whether session is still running (not stopped)
+    #[serde(with = "chrono::serde::ts_seconds_option")]
+    #[ts(type = "number | null")]
+    pub created_at: Option<DateTime<Utc>>,
+    #[serde(with = "chrono::serde::ts_seconds_option")]
+    #[ts(type = "number | null")]
+    pub expires_at: Option<DateTime<Utc>>, // None = never expires
+    #[serde(with = "chrono::serde::ts_seconds_option")]
+    #[ts(type = "number | null")]
+    pub last_used_at: Option<DateTime<Utc>>, // None = the CLI hasn't loaded it yet
+    pub idle_seconds: u64, // Since last use, or since creation when never used
+    pub is_stale: bool,    // Source box or its secrets changed since the session was created
+}
+
+// Response struct for UI - ssh_config written for a session, removed when the session stops
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SshConfigExport {
+    pub config: String,      // The Host blocks, also saved at config_path
+    pub config_path: String, // `Include` this from ~/.ssh/config
+    pub hosts: Vec<String>,  // Host aliases
+    pub key_files: usize,    // Private keys written next to the config (0600)
+}
+
+// Result of comparing a session file on disk with its database record
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionFileStatus {
+    Valid,     // File matches the database record
+    Removed,   // File deleted outside the app (e.g. by the CLI)
+    Tampered,  // File contents don't match the database record
+    Untracked, // File exists but no database record
+}
+
+// Event payload emitted to the UI when the session watcher reconciles a file
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SessionFileEvent {
+    pub session_name: String,
+    pub status: SessionFileStatus,
 }
 
 // Stats for dev mode UI
-#[derive(Debug, Serialize)]
+#[derive(Debug, Serialize, TS)]
 pub struct DevStats {
     pub total_boxes: usize,
     pub dev_boxes: usize,
-    pub secure_boxes: usize,
     pub total_secrets: usize,
     pub active_sessions_count: usize,
 }
@@ -0,0 +1,73 @@
+// src/models/diagnostics_model.rs
+
+use crate::models::{DatabaseKind, KdfParams, StartupStatus};
+use serde::Serialize;
+use std::path::Path;
+use ts_rs::TS;
+
+// Health check for support tickets - paths and settings only, never names or secret values
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct DiagnosticsReport {
+    #[serde(with = "chrono::serde::ts_seconds")]
+    #[ts(type = "number")]
+    pub generated_at: chrono::DateTime<chrono::Utc>,
+    pub app_version: String,
+    pub os: String,
+    pub arch: String,
+    pub startup_status: StartupStatus,
+    pub paths: Vec<PathDiagnostic>,
+    pub databases: Vec<DatabaseDiagnostic>,
+    pub schema_version: Option<u32>, // None when the vault isn't open
+    pub latest_schema_version: u32,
+    pub orphaned_session_files: usize, // Session files with no session in the app
+    pub missing_session_files: usize,  // Sessions in the app whose file is gone
+    #[serde(with = "chrono::serde::ts_seconds_option")]
+    #[ts(type = "number | null")]
+    pub last_backup_at: Option<chrono::DateTime<chrono::Utc>>, // Last automatic export
+    pub keyring_available: bool,
+    pub keyring_error: Option<String>,
+    pub kdf_params: Option<KdfParams>, // None before first-time setup
+    pub locked: bool,
+    pub errors: Vec<String>, // Checks that couldn't run
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct PathDiagnostic {
+    pub name: String,
+    pub path: String,
+    pub exists: bool,
+    pub is_dir: bool,
+    pub read_only: bool,
+    pub mode: Option<String>, // Unix permission bits in octal, e.g. "700"
+}
+
+impl PathDiagnostic {
+    pub fn inspect(name: &str, path: &Path) -> Self {
+        let metadata = std::fs::metadata(path).ok();
+        Self {
+            name: name.to_string(),
+            path: path.display().to_string(),
+            exists: metadata.is_some(),
+            is_dir: metadata.as_ref().is_some_and(|m| m.is_dir()),
+            read_only: metadata.as_ref().is_some_and(|m| m.permissions().readonly()),
+            mode: metadata.as_ref().and_then(unix_mode),
+        }
+    }
+}
+
+#[cfg(unix)]
+fn unix_mode(metadata: &std::fs::Metadata) -> Option<String> {
+    use std::os::unix::fs::PermissionsExt;
+    Some(format!("{:o}", metadata.permissions().mode() & 0o7777))
+}
+
+#[cfg(not(unix))]
+fn unix_mode(_metadata: &std::fs::Metadata) -> Option<String> {
+    None
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct DatabaseDiagnostic {
+    pub database: DatabaseKind,
+    pub open: bool,
+}
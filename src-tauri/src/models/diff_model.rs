@@ -0,0 +1,58 @@
+// src/models/diff_model.rs
+
+use serde::Serialize;
+use ts_rs::TS;
+
+// Two boxes side by side by secret name. Values are compared in memory and never returned.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct BoxDiff {
+    pub box_a_id: String,
+    pub box_a_name: String,
+    pub box_b_id: String,
+    pub box_b_name: String,
+    pub only_in_a: Vec<String>,       // Secret names, sorted
+    pub only_in_b: Vec<String>,       // Secret names, sorted
+    pub different_value: Vec<String>, // Names as they appear in box A
+    pub same_value_count: usize,
+}
+
+impl BoxDiff {
+    pub fn is_identical(&self) -> bool {
+        self.only_in_a.is_empty() && self.only_in_b.is_empty() && self.different_value.is_empty()
+    }
+}
+
+// The vault now against an earlier vault export, boxes and secrets matched by name.
+// "Added" is in the vault but not the export, "removed" is in the export but gone here.
+#[derive(Debug, Clone, Default, Serialize, TS)]
+pub struct VaultDiff {
+    pub boxes_added: Vec<String>,
+    pub boxes_removed: Vec<String>,
+    pub boxes_changed: Vec<BoxChanges>, // Boxes on both sides that differ
+}
+
+impl VaultDiff {
+    pub fn is_empty(&self) -> bool {
+        self.boxes_added.is_empty()
+            && self.boxes_removed.is_empty()
+            && self.boxes_changed.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, TS)]
+pub struct BoxChanges {
+    pub box_name: String,
+    pub details_changed: bool, // Description, tags or dev mode
+    pub secrets_added: Vec<String>,
+    pub secrets_removed: Vec<String>,
+    pub secrets_changed: Vec<String>, // Value, encoding, variants or .env settings differ
+}
+
+impl BoxChanges {
+    pub fn is_empty(&self) -> bool {
+        !self.details_changed
+            && self.secrets_added.is_empty()
+            && self.secrets_removed.is_empty()
+            && self.secrets_changed.is_empty()
+    }
+}
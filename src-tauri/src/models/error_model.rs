@@ -2,6 +2,7 @@
 
 use serde::Serialize;
 use thiserror::Error;
+use ts_rs::TS;
 
 #[derive(Error, Debug)]
 pub enum ZapError {
@@ -35,6 +36,12 @@ pub enum ZapError {
     #[error("Box cannot be used as dev session: {0}")]
     InvalidDevBox(String),
 
+    #[error("Box '{0}' is shared read-only, detach it to make changes")]
+    ReadOnlyBox(String),
+
+    #[error("Box '{0}' is tagged prod, enter your master password and a justification")]
+    ProdApprovalRequired(String),
+
     // Secret errors
     #[error("Secret with id '{0}' not found")]
     SecretNotFound(String),
@@ -42,6 +49,15 @@ pub enum ZapError {
     #[error("Secret with name '{0}' already exists in this box")]
     SecretAlreadyExistsInBox(String),
 
+    #[error("Too many secrets revealed, try again in {0} seconds")]
+    RevealRateLimited(u64),
+
+    #[error("Plaintext reveal is disabled, copy the secret to the clipboard instead")]
+    PlaintextRevealDisabled,
+
+    #[error("Reveal handle is invalid or has expired")]
+    InvalidRevealHandle,
+
     // Authentication errors (unchanged)
     #[error("Authentication failed: {0}")]
     AuthError(String),
@@ -52,6 +68,9 @@ pub enum ZapError {
     #[error("Session has expired")]
     SessionExpired,
 
+    #[error("Please re-enter your master password to continue")]
+    ReauthenticationRequired,
+
     // Crypto errors (unchanged)
     #[error("Cryptographic operation failed: {0}")]
     CryptoError(String),
@@ -82,6 +101,20 @@ pub enum ZapError {
     #[error("Sessions database not found")]
     SessionsDatabaseNotFound,
 
+    #[error("Incompatible session file format: {0}")]
+    IncompatibleSessionFormat(String),
+
+    // Job errors
+    #[error("Job '{0}' not found")]
+    JobNotFound(String),
+
+    #[error("Operation was cancelled")]
+    Cancelled,
+
+    // Platform errors (window, clipboard, file watcher, hooks)
+    #[error("Platform operation failed: {0}")]
+    PlatformError(String),
+
     // Serialization errors (unchanged)
     #[error("Serialization error: {0}")]
     SerializationError(#[from] serde_json::Error),
@@ -90,13 +123,60 @@ pub enum ZapError {
     Utf8Error(#[from] std::string::FromUtf8Error),
 }
 
-// Make it compatible with Tauri's error system
+// Stable codes the frontend can branch on - never rename, only add
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    StorageError,
+    DatabaseError,
+    IoError,
+    ValidationError,
+    InvalidTags,
+    BoxNotFound,
+    BoxAlreadyExists,
+    BoxCapacityExceeded,
+    InvalidDevBox,
+    ReadOnlyBox,
+    ProdApprovalRequired,
+    SecretNotFound,
+    SecretAlreadyExistsInBox,
+    RevealRateLimited,
+    PlaintextRevealDisabled,
+    InvalidRevealHandle,
+    AuthError,
+    IncorrectPassword,
+    SessionExpired,
+    ReauthenticationRequired,
+    CryptoError,
+    SessionNotFound,
+    SessionAlreadyExists,
+    NoSessionsExist,
+    InvalidSessionName,
+    InvalidSessionKey,
+    NoCurrentSession,
+    InvalidProjectContext,
+    SessionsDatabaseNotFound,
+    IncompatibleSessionFormat,
+    PlatformError,
+    SerializationError,
+    Utf8Error,
+    JobNotFound,
+    Cancelled,
+}
+
+// Make it compatible with Tauri's error system: `{ code, message, details }`
 impl Serialize for ZapError {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::ser::Serializer,
     {
-        serializer.serialize_str(self.to_string().as_ref())
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("ZapError", 3)?;
+        state.serialize_field("code", &self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        state.serialize_field("details", &self.details())?;
+        state.end()
     }
 }
 
@@ -105,6 +185,87 @@ pub type ZapResult<T> = Result<T, ZapError>;
 
 // Helper implementations
 impl ZapError {
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            Self::StorageError(_) => ErrorCode::StorageError,
+            Self::DatabaseError(_) => ErrorCode::DatabaseError,
+            Self::IoError(_) => ErrorCode::IoError,
+            Self::ValidationError(_) => ErrorCode::ValidationError,
+            Self::InvalidTags(_) => ErrorCode::InvalidTags,
+            Self::BoxNotFound(_) => ErrorCode::BoxNotFound,
+            Self::BoxAlreadyExists(_) => ErrorCode::BoxAlreadyExists,
+            Self::BoxCapacityExceeded => ErrorCode::BoxCapacityExceeded,
+            Self::InvalidDevBox(_) => ErrorCode::InvalidDevBox,
+            Self::ReadOnlyBox(_) => ErrorCode::ReadOnlyBox,
+            Self::ProdApprovalRequired(_) => ErrorCode::ProdApprovalRequired,
+            Self::SecretNotFound(_) => ErrorCode::SecretNotFound,
+            Self::SecretAlreadyExistsInBox(_) => ErrorCode::SecretAlreadyExistsInBox,
+            Self::RevealRateLimited(_) => ErrorCode::RevealRateLimited,
+            Self::PlaintextRevealDisabled => ErrorCode::PlaintextRevealDisabled,
+            Self::InvalidRevealHandle => ErrorCode::InvalidRevealHandle,
+            Self::AuthError(_) => ErrorCode::AuthError,
+            Self::IncorrectPassword => ErrorCode::IncorrectPassword,
+            Self::SessionExpired => ErrorCode::SessionExpired,
+            Self::ReauthenticationRequired => ErrorCode::ReauthenticationRequired,
+            Self::CryptoError(_) => ErrorCode::CryptoError,
+            Self::SessionNotFound(_) => ErrorCode::SessionNotFound,
+            Self::SessionAlreadyExists(_) => ErrorCode::SessionAlreadyExists,
+            Self::NoSessionsExist => ErrorCode::NoSessionsExist,
+            Self::InvalidSessionName(_) => ErrorCode::InvalidSessionName,
+            Self::InvalidSessionKey => ErrorCode::InvalidSessionKey,
+            Self::NoCurrentSession => ErrorCode::NoCurrentSession,
+            Self::InvalidProjectContext => ErrorCode::InvalidProjectContext,
+            Self::SessionsDatabaseNotFound => ErrorCode::SessionsDatabaseNotFound,
+            Self::IncompatibleSessionFormat(_) => ErrorCode::IncompatibleSessionFormat,
+            Self::JobNotFound(_) => ErrorCode::JobNotFound,
+            Self::Cancelled => ErrorCode::Cancelled,
+            Self::PlatformError(_) => ErrorCode::PlatformError,
+            Self::SerializationError(_) => ErrorCode::SerializationError,
+            Self::Utf8Error(_) => ErrorCode::Utf8Error,
+        }
+    }
+
+    // Variant payload without the display prefix (ids, names, underlying error)
+    pub fn details(&self) -> Option<String> {
+        match self {
+            Self::StorageError(detail)
+            | Self::ValidationError(detail)
+            | Self::InvalidTags(detail)
+            | Self::BoxNotFound(detail)
+            | Self::BoxAlreadyExists(detail)
+            | Self::InvalidDevBox(detail)
+            | Self::ReadOnlyBox(detail)
+            | Self::ProdApprovalRequired(detail)
+            | Self::SecretNotFound(detail)
+            | Self::SecretAlreadyExistsInBox(detail)
+            | Self::AuthError(detail)
+            | Self::CryptoError(detail)
+            | Self::SessionNotFound(detail)
+            | Self::SessionAlreadyExists(detail)
+            | Self::InvalidSessionName(detail)
+            | Self::IncompatibleSessionFormat(detail)
+            | Self::JobNotFound(detail)
+            | Self::PlatformError(detail) => Some(detail.clone()),
+            Self::RevealRateLimited(retry_after) => Some(retry_after.to_string()),
+            Self::DatabaseError(e) => Some(e.to_string()),
+            Self::IoError(e) => Some(e.to_string()),
+            Self::SerializationError(e) => Some(e.to_string()),
+            Self::Utf8Error(e) => Some(e.to_string()),
+            Self::BoxCapacityExceeded
+            | Self::PlaintextRevealDisabled
+            | Self::InvalidRevealHandle
+            | Self::IncorrectPassword
+            | Self::SessionExpired
+            | Self::ReauthenticationRequired
+            | Self::NoSessionsExist
+            | Self::InvalidSessionKey
+            | Self::NoCurrentSession
+            | Self::InvalidProjectContext
+            | Self::SessionsDatabaseNotFound
+            | Self::Cancelled => None,
+        }
+    }
+
     pub fn box_not_found(id: &str) -> Self {
         Self::BoxNotFound(id.to_string())
     }
@@ -139,3 +300,10 @@ impl From<hex::FromHexError> for ZapError {
         ZapError::CryptoError(format!("Hex decode error: {}", err))
     }
 }
+
+// Convert from path resolution errors
+impl From<crate::utils::path_resolvers::PathError> for ZapError {
+    fn from(err: crate::utils::path_resolvers::PathError) -> Self {
+        ZapError::StorageError(format!("Path error: {}", err))
+    }
+}
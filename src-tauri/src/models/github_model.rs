@@ -0,0 +1,43 @@
+// src/models/github_model.rs
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+// Repository or environment key that Actions secrets are sealed to before upload
+#[derive(Debug, Clone, Deserialize)]
+pub struct GithubPublicKey {
+    pub key_id: String,
+    pub key: String, // Base64 X25519 public key
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum GithubSecretStatus {
+    Created,
+    Updated,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct GithubSecretResult {
+    pub secret_name: String,
+    pub github_name: String, // As workflows read it: `secrets.DB_PASSWORD`
+    pub status: GithubSecretStatus,
+    pub error: Option<String>, // Set when the push failed
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct GithubPushReport {
+    pub repository: String,                 // "owner/repo"
+    pub github_environment: Option<String>, // None = repository secrets
+    pub results: Vec<GithubSecretResult>,
+}
+
+impl GithubPushReport {
+    pub fn failed_count(&self) -> usize {
+        self.results
+            .iter()
+            .filter(|result| result.status == GithubSecretStatus::Failed)
+            .count()
+    }
+}
@@ -0,0 +1,83 @@
+// src/models/gitlab_model.rs
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+pub const GITLAB_DEFAULT_URL: &str = "https://gitlab.com";
+pub const GITLAB_ALL_ENVIRONMENTS: &str = "*";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum GitlabScope {
+    Project,
+    Group,
+}
+
+// Where CI/CD variables go
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct GitlabTarget {
+    #[serde(default)]
+    pub instance_url: Option<String>, // None = gitlab.com, or a self-managed instance
+    pub scope: GitlabScope,
+    pub path: String, // "group/project", "group/subgroup" or the numeric id
+    #[serde(default)]
+    pub environment_scope: Option<String>, // None = "*", all environments
+}
+
+impl GitlabTarget {
+    pub fn environment_scope(&self) -> &str {
+        self.environment_scope
+            .as_deref()
+            .unwrap_or(GITLAB_ALL_ENVIRONMENTS)
+    }
+}
+
+// A CI/CD variable as the GitLab API reads and writes it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitlabVariable {
+    pub key: String,
+    pub value: String,
+    #[serde(default)]
+    pub masked: bool,
+    #[serde(default)]
+    pub protected: bool,
+    #[serde(default)]
+    pub raw: bool, // No $VAR expansion, so values containing `$` stay as they are
+    #[serde(default = "default_environment_scope")]
+    pub environment_scope: String,
+}
+
+fn default_environment_scope() -> String {
+    GITLAB_ALL_ENVIRONMENTS.to_string()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum GitlabChange {
+    Create,
+    Update, // Value, masked or protected differ
+    Unchanged,
+}
+
+// One secret in a sync. Values are compared in the backend and never sent back.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct GitlabVariableChange {
+    pub secret_name: String,
+    pub key: String,
+    pub change: GitlabChange,
+    pub masked: bool,          // Whenever GitLab can mask the value
+    pub protected: bool,       // Boxes tagged prod, protected branches and tags only
+    pub value_changed: bool,   // False for flag-only updates
+    pub note: Option<String>,  // Why the value isn't masked
+    pub error: Option<String>, // Unusable key, or the update GitLab refused
+}
+
+// Returned by the preview and by the sync itself (`applied`)
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct GitlabSyncReport {
+    pub target: String, // "group/project (project)"
+    pub environment_scope: String,
+    pub applied: bool,
+    pub variables: Vec<GitlabVariableChange>,
+    pub remote_only: Vec<String>, // Keys GitLab has that the box doesn't, left alone
+}
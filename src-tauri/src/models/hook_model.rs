@@ -0,0 +1,103 @@
+// src/models/hook_model.rs
+
+use crate::models::ZapError;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+pub const MAX_HOOK_TIMEOUT_SECONDS: u32 = 60;
+
+fn default_enabled() -> bool {
+    true
+}
+
+fn default_timeout_seconds() -> u32 {
+    10
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum HookEvent {
+    SecretCreated,
+    SecretUpdated,
+    SessionStarted,
+    SessionStopped,
+    VaultUnlocked,
+}
+
+impl HookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            HookEvent::SecretCreated => "secret_created",
+            HookEvent::SecretUpdated => "secret_updated",
+            HookEvent::SessionStarted => "session_started",
+            HookEvent::SessionStopped => "session_stopped",
+            HookEvent::VaultUnlocked => "vault_unlocked",
+        }
+    }
+}
+
+// What a hook does when it fires
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum HookAction {
+    Command { command: String }, // Run through the system shell, payload on stdin
+    Webhook { url: String },     // HTTP POST with the payload as body
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct Hook {
+    pub id: String,
+    pub name: String,
+    pub events: Vec<HookEvent>,
+    pub action: HookAction,
+    #[serde(default)]
+    pub payload_template: Option<String>, // None = default JSON payload
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    #[serde(default = "default_timeout_seconds")]
+    pub timeout_seconds: u32,
+}
+
+impl Hook {
+    pub fn validate(&self) -> Result<(), ZapError> {
+        if self.name.trim().is_empty() {
+            return Err(ZapError::ValidationError(
+                "Hook name cannot be empty".to_string(),
+            ));
+        }
+        if self.events.is_empty() {
+            return Err(ZapError::ValidationError(format!(
+                "Hook '{}' must listen to at least one event",
+                self.name
+            )));
+        }
+        if self.timeout_seconds == 0 || self.timeout_seconds > MAX_HOOK_TIMEOUT_SECONDS {
+            return Err(ZapError::ValidationError(format!(
+                "Hook '{}' timeout must be between 1 and {} seconds",
+                self.name, MAX_HOOK_TIMEOUT_SECONDS
+            )));
+        }
+
+        match &self.action {
+            HookAction::Command { command } if command.trim().is_empty() => {
+                Err(ZapError::ValidationError(format!(
+                    "Hook '{}' command cannot be empty",
+                    self.name
+                )))
+            }
+            HookAction::Webhook { url }
+                if !(url.starts_with("http://") || url.starts_with("https://")) =>
+            {
+                Err(ZapError::ValidationError(format!(
+                    "Hook '{}' URL must start with http:// or https://",
+                    self.name
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    pub fn listens_to(&self, event: HookEvent) -> bool {
+        self.enabled && self.events.contains(&event)
+    }
+}
@@ -1,13 +1,65 @@
 // src/models/import_export_model.rs 
 
+use crate::models::{EncryptedData, KdfParams, MultilineFormat, ValueEncoding, ZapError};
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use ts_rs::TS;
+
+// 2.0 adds ids and created_at / updated_at to boxes and secrets, 1.0 files still import
+pub const VAULT_EXPORT_VERSION: &str = "2.0";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VaultExportVersion {
+    V1,
+    V2,
+}
 
 #[derive(Serialize, Deserialize)]
 pub struct VaultExport {
-    pub version: String, // "1.0" - for future compatibility
+    pub version: String, // See VAULT_EXPORT_VERSION
     pub total_boxes: usize,
     pub total_secrets: usize,
     pub boxes: Vec<BoxExport>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<ExportSignature>, // None in files from before signing, or hand-made
+}
+
+impl VaultExport {
+    pub fn format_version(&self) -> Result<VaultExportVersion, ZapError> {
+        match self.version.as_str() {
+            "1.0" => Ok(VaultExportVersion::V1),
+            "2.0" => Ok(VaultExportVersion::V2),
+            other => Err(ZapError::ValidationError(format!(
+                "Unsupported vault export version '{}'",
+                other
+            ))),
+        }
+    }
+}
+
+// Ed25519 signature over the export's compact JSON with `signature` left out
+#[derive(Serialize, Deserialize)]
+pub struct ExportSignature {
+    pub public_key: String, // Hex, see get_export_signing_key
+    pub signature: String,  // Hex
+}
+
+// What an import file's signature says about where it came from
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureStatus {
+    #[default]
+    Unsigned,
+    Trusted,   // Signed by this vault or a key in Settings.trusted_signing_keys
+    Untrusted, // Intact, but signed by a vault that isn't trusted here
+    Invalid,   // Changed after it was signed
+}
+
+impl SignatureStatus {
+    pub fn needs_warning(&self) -> bool {
+        matches!(self, SignatureStatus::Untrusted | SignatureStatus::Invalid)
+    }
 }
 
 #[derive(Serialize, Deserialize)]
@@ -17,28 +69,209 @@ pub struct BoxExport {
     pub tags: Vec<String>,
     pub dev_mode: bool,
     pub secrets: Vec<SecretExport>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>, // Box id on the exporting machine, reused on import when free
+    #[serde(
+        default,
+        with = "chrono::serde::ts_seconds_option",
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub exported_at: Option<DateTime<Utc>>,
+    #[serde(default, with = "chrono::serde::ts_seconds_option")]
+    pub created_at: Option<DateTime<Utc>>, // None in 1.0 exports
+    #[serde(default, with = "chrono::serde::ts_seconds_option")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl BoxExport {
+    // 1.0 files carry none of the 2.0 fields; drop any a hand-edited file added
+    pub fn strip_v2_fields(&mut self) {
+        self.id = None;
+        self.exported_at = None;
+        self.created_at = None;
+        self.updated_at = None;
+        for secret in &mut self.secrets {
+            secret.id = None;
+            secret.created_at = None;
+            secret.updated_at = None;
+        }
+    }
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct SecretExport {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>, // Reused on import when free
     pub name: String,
     pub value: String, // Decrypted value for export
+    #[serde(default, skip_serializing_if = "ValueEncoding::is_utf8")]
+    pub value_encoding: ValueEncoding, // Base64 values are binary
+    #[serde(default, skip_serializing_if = "MultilineFormat::is_escaped")]
+    pub multiline_format: MultilineFormat,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub variants: BTreeMap<String, String>, // Decrypted per-environment values
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_name: Option<String>,
+    #[serde(default, with = "chrono::serde::ts_seconds_option")]
+    pub created_at: Option<DateTime<Utc>>, // None in 1.0 exports
+    #[serde(default, with = "chrono::serde::ts_seconds_option")]
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+// How a merge import settles a box or secret that exists on both sides with different content
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum MergeStrategy {
+    KeepLocal,
+    KeepImport,
+    KeepNewest, // Compares updated_at; items without one (1.0 exports) keep the local side
+}
+
+impl MergeStrategy {
+    pub fn prefers_import(
+        &self,
+        local_updated_at: DateTime<Utc>,
+        import_updated_at: Option<DateTime<Utc>>,
+    ) -> bool {
+        match self {
+            MergeStrategy::KeepLocal => false,
+            MergeStrategy::KeepImport => true,
+            MergeStrategy::KeepNewest => {
+                import_updated_at.is_some_and(|updated_at| updated_at > local_updated_at)
+            }
+        }
+    }
+}
+
+// Automatic export file: a vault export sealed with the master key. Salt and KDF parameters
+// travel along, so the master password it was made under opens it on any machine.
+pub const BACKUP_FORMAT_VERSION: u32 = 1;
+pub const BACKUP_FILE_PREFIX: &str = "zap-backup-";
+
+#[derive(Serialize, Deserialize)]
+pub struct EncryptedBackup {
+    pub version: u32,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub created_at: DateTime<Utc>,
+    pub salt: String, // Hex
+    pub kdf_params: KdfParams,
+    pub vault: EncryptedData, // VaultExport JSON
+}
+
+// Structured config formats for box import/export
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigFormat {
+    Yaml,
+    Toml,
+}
+
+// Terraform / OpenTofu variable files: terraform.tfvars or *.auto.tfvars.json
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum TfvarsFormat {
+    #[default]
+    Hcl,
+    Json,
+}
+
+// JSON for the external-secrets webhook provider. ExternalSecrets name the box by
+// `remote_key` and pick values out of `document` with each property's `json_path`.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ExternalSecretsExport {
+    pub remote_key: String, // Box name as a DNS-style slug
+    pub document: String,   // Pretty-printed JSON object
+    pub properties: Vec<ExternalSecretProperty>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ExternalSecretProperty {
+    pub secret_name: String,
+    pub property: String,  // remoteRef.property, dotted for nested values
+    pub json_path: String, // "$.database.password"
+}
+
+// CSV column by zero-based index or by header name
+#[derive(Debug, Clone, Deserialize, TS)]
+#[serde(untagged)]
+pub enum CsvColumn {
+    Index(usize),
+    Header(String),
+}
+
+#[derive(Debug, Clone, Deserialize, TS)]
+pub struct CsvColumnMapping {
+    pub name: CsvColumn,
+    pub value: CsvColumn,
+    #[serde(default)]
+    pub notes: Option<CsvColumn>, // Rejected when set, secrets don't store notes
+    #[serde(default)]
+    pub tags: Option<CsvColumn>, // Same, secrets don't store tags
+    #[serde(default = "default_has_header")]
+    pub has_header: bool,
+    #[serde(default)]
+    pub delimiter: Option<char>, // None = detect from the first line
+}
+
+fn default_has_header() -> bool {
+    true
+}
+
+// How smart import read pasted text
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum SmartImportFormat {
+    Env,
+    Json,
+    Csv,
+    KeyValue, // `key: value` lines
+}
+
+// One pasted value as it would be imported (the value itself is never sent back)
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SmartImportEntry {
+    pub name: String,
+    pub env_name: Option<String>,
+    pub already_exists: bool,  // Skipped on import, the box keeps its value
+    pub error: Option<String>, // Invalid name, empty or oversized value, skipped on import
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SmartImportPreview {
+    pub format: SmartImportFormat,
+    pub entries: Vec<SmartImportEntry>,
+    pub warnings: Vec<String>, // Lines or rows that couldn't be read, repeated names
+    pub fits_in_box: bool,     // False when the new secrets would pass the 75 secret limit
+}
+
+// Emitted as `import-progress` after each box of a vault import
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ImportProgress {
+    pub boxes_done: usize,
+    pub boxes_total: usize,
+    pub secrets_imported: usize,
 }
 
 // Keep ImportResult unchanged
-#[derive(Serialize)]
+#[derive(Serialize, TS)]
 pub struct ImportResult {
     pub boxes_imported: usize,
+    pub boxes_updated: usize, // Shared boxes refreshed, or local boxes changed by a merge
+    pub secrets_updated: usize, // Merge conflicts settled in favour of the import
     pub secrets_imported: usize,
     pub errors: Vec<String>,
+    pub signature: SignatureStatus, // Warn before trusting an untrusted or modified file
 }
 
 impl ImportResult {
     pub fn new() -> Self {
         Self {
             boxes_imported: 0,
+            boxes_updated: 0,
+            secrets_updated: 0,
             secrets_imported: 0,
             errors: Vec::new(),
+            signature: SignatureStatus::Unsigned,
         }
     }
 
@@ -51,6 +284,12 @@ impl ImportResult {
     }
 
     pub fn success_summary(&self) -> String {
+        if self.boxes_updated > 0 {
+            return format!(
+                "Imported {} boxes and updated {} shared boxes with {} secrets",
+                self.boxes_imported, self.boxes_updated, self.secrets_imported
+            );
+        }
         format!(
             "Imported {} boxes with {} secrets",
             self.boxes_imported, self.secrets_imported
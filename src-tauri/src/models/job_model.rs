@@ -0,0 +1,60 @@
+// src/models/job_model.rs
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum JobKind {
+    Import,
+    Export,
+    Transfer,    // LAN transfer, sending or receiving
+    Reencrypt,   // Vault encryption migrations at unlock
+    BreachCheck, // Password secrets looked up in Have I Been Pwned
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Running,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+// Snapshot of a tracked long-running operation, also the `job-progress` event payload
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct JobInfo {
+    pub id: String,
+    pub kind: JobKind,
+    pub status: JobStatus,
+    pub done: usize,
+    pub total: usize, // 0 = not known yet
+    pub message: Option<String>,
+    pub result: Option<serde_json::Value>, // Set when completed, never an export's content
+    pub error: Option<String>,             // Set when failed
+    #[serde(with = "chrono::serde::ts_seconds")]
+    #[ts(type = "number")]
+    pub started_at: DateTime<Utc>,
+}
+
+impl JobInfo {
+    pub fn new(kind: JobKind) -> Self {
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            kind,
+            status: JobStatus::Running,
+            done: 0,
+            total: 0,
+            message: None,
+            result: None,
+            error: None,
+            started_at: Utc::now(),
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.status != JobStatus::Running
+    }
+}
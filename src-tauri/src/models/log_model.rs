@@ -1,24 +1,306 @@
 // src/models/log_model.rs
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+use LogSeverity::{Info, Security, Warning};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+// Ordered least to most severe, so filters can ask for "at least" a level
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, TS,
+)]
+#[serde(rename_all = "snake_case")]
+pub enum LogSeverity {
+    #[default]
+    Info,
+    Warning,  // Something failed or was removed without the user asking
+    Security, // Secret material was revealed, exported or guarded by a password
+}
+
+// Every action written to the audit log. Stored as its string name so existing logs still read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(into = "String", from = "String")]
+pub enum LogAction {
+    // App lifecycle
+    AppStart,
+    AppShutdown,
+    Command,
+    UpdateSettings,
+    HookFailed,
+
+    // Authentication
+    SessionUnlock,
+    SessionUnlockRemembered,
+    UnlockFailed,
+    SessionLock,
+    SessionExpired,
+    UnlockWithRecoveryKey,
+    RegenerateRecoveryKey,
+    RememberMeEnabled,
+    RememberMeExpired,
+    RememberMeCleared,
+    EnableHardwareKey,
+    DisableHardwareKey,
+
+    // Boxes & secrets
+    CreateBox,
+    UpdateBox,
+    DeleteBox,
+    DeleteBoxesBulk,
+    CreateSecret,
+    UpdateSecret,
+    DeleteSecret,
+    DeleteSecretsBulk,
+    RotateSecret,
+    SetRotationInterval,
+    BreachCheck,
+    BreachCheckFailed,
+    SecretCompromised,
+    RevealSecret,
+    RevealRateLimited,
+    CopySecret,
+    CopySecrets,
+    DeepSearch,
+    DiffBoxes,
+    RenderTemplate,
+    UndoOperation,
+
+    // Sharing
+    CreateShare,
+    RedeemShare,
+    RedeemShareFailed,
+    DetachSharedBox,
+
+    // Import & export
+    ImportVault,
+    ImportVaultMerge,
+    ImportEnv,
+    ImportCsv,
+    ImportConfig,
+    ImportPaste,
+    ImportFromProvider,
+    ExportVault,
+    ExportBox,
+    ExportDevSession,
+    ExportGithubSecrets,
+    ExportGitlabVariables,
+    ExportToProvider,
+    ExportLogs,
+    ExportStatsReport,
+    DiffExport,
+    ImportSignatureWarning,
+    AutoExport,
+    AutoExportFailed,
+    SyncReplica,
+    LanTransfer,
+    ViewLogs,
+
+    // Dev sessions & projects
+    CreateDevSession,
+    ApproveProdSession,
+    CreateProxySession,
+    ServeProxySession,
+    RevokeProxySession,
+    AllowAppAccess,
+    DenyAppAccess,
+    RenameDevSession,
+    StopDevSession,
+    ClearAllDevSessions,
+    DevSessionExpired,
+    DevSessionIdle,
+    DevSessionRemovedExternally,
+    DevSessionTampered,
+    DevSessionUntracked,
+    MoveSessionsDirectory,
+    BindProject,
+    UnbindProject,
+    WriteEnvrc,
+    ShredEnvFile,
+
+    // Storage maintenance
+    MigrateVault,
+    MigrateSecretAad,
+    MigrateBoxKeys,
+    RecoverStorage,
+    CompactStorage,
+    ScheduleCompaction,
+    MoveDataDirectory,
+    RestoreSnapshot,
+
+    Unknown, // Written by a newer version of Zap
+}
+
+// Stored name and severity of each action
+#[rustfmt::skip]
+const LOG_ACTIONS: &[(LogAction, &str, LogSeverity)] = &[
+    (LogAction::AppStart, "App_Start", Info),
+    (LogAction::AppShutdown, "App_Shutdown", Info),
+    (LogAction::Command, "Command", Info),
+    (LogAction::UpdateSettings, "Update_Settings", Info),
+    (LogAction::HookFailed, "Hook_Failed", Warning),
+    (LogAction::SessionUnlock, "Session_Unlock", Security),
+    (LogAction::SessionUnlockRemembered, "Session_Unlock_Remembered", Security),
+    (LogAction::UnlockFailed, "Unlock_Failed", Security),
+    (LogAction::SessionLock, "Session_Lock", Info),
+    (LogAction::SessionExpired, "Session_Expired", Info),
+    (LogAction::UnlockWithRecoveryKey, "Unlock_With_Recovery_Key", Security),
+    (LogAction::RegenerateRecoveryKey, "Regenerate_Recovery_Key", Security),
+    (LogAction::RememberMeEnabled, "Remember_Me_Enabled", Security),
+    (LogAction::RememberMeExpired, "Remember_Me_Expired", Info),
+    (LogAction::RememberMeCleared, "Remember_Me_Cleared", Info),
+    (LogAction::EnableHardwareKey, "Enable_Hardware_Key", Security),
+    (LogAction::DisableHardwareKey, "Disable_Hardware_Key", Security),
+    (LogAction::CreateBox, "Create_Box", Info),
+    (LogAction::UpdateBox, "Update_Box", Info),
+    (LogAction::DeleteBox, "Delete_Box", Warning),
+    (LogAction::DeleteBoxesBulk, "Delete_Boxes_Bulk", Warning),
+    (LogAction::CreateSecret, "Create_Secret", Info),
+    (LogAction::UpdateSecret, "Update_Secret", Info),
+    (LogAction::DeleteSecret, "Delete_Secret", Warning),
+    (LogAction::DeleteSecretsBulk, "Delete_Secrets_Bulk", Warning),
+    (LogAction::RotateSecret, "Rotate_Secret", Info),
+    (LogAction::SetRotationInterval, "Set_Rotation_Interval", Info),
+    (LogAction::BreachCheck, "Breach_Check", Info),
+    (LogAction::BreachCheckFailed, "Breach_Check_Failed", Warning),
+    (LogAction::SecretCompromised, "Secret_Compromised", Security),
+    (LogAction::RevealSecret, "Reveal_Secret", Security),
+    (LogAction::RevealRateLimited, "Reveal_Rate_Limited", Security),
+    (LogAction::CopySecret, "Copy_Secret", Security),
+    (LogAction::CopySecrets, "Copy_Secrets", Info),
+    (LogAction::DeepSearch, "Deep_Search", Security),
+    (LogAction::DiffBoxes, "Diff_Boxes", Security),
+    (LogAction::RenderTemplate, "Render_Template", Security),
+    (LogAction::UndoOperation, "Undo_Operation", Info),
+    (LogAction::CreateShare, "Create_Share", Security),
+    (LogAction::RedeemShare, "Redeem_Share", Security),
+    (LogAction::RedeemShareFailed, "Redeem_Share_Failed", Security),
+    (LogAction::DetachSharedBox, "Detach_Shared_Box", Info),
+    (LogAction::ImportVault, "Import_Vault", Info),
+    (LogAction::ImportVaultMerge, "Import_Vault_Merge", Info),
+    (LogAction::ImportEnv, "Import_Env", Info),
+    (LogAction::ImportCsv, "Import_Csv", Info),
+    (LogAction::ImportConfig, "Import_Config", Info),
+    (LogAction::ImportPaste, "Import_Paste", Info),
+    (LogAction::ImportFromProvider, "Import_From_Provider", Info),
+    (LogAction::ExportVault, "Export_Vault", Security),
+    (LogAction::ExportBox, "Export_Box", Security),
+    (LogAction::ExportDevSession, "Export_Dev_Session", Security),
+    (LogAction::ExportGithubSecrets, "Export_Github_Secrets", Security),
+    (LogAction::ExportGitlabVariables, "Export_Gitlab_Variables", Security),
+    (LogAction::ExportToProvider, "Export_To_Provider", Security),
+    (LogAction::ExportLogs, "Export_Logs", Info),
+    (LogAction::ExportStatsReport, "Export_Stats_Report", Info),
+    (LogAction::DiffExport, "Diff_Export", Security),
+    (LogAction::ImportSignatureWarning, "Import_Signature_Warning", Security),
+    (LogAction::AutoExport, "Auto_Export", Info),
+    (LogAction::AutoExportFailed, "Auto_Export_Failed", Warning),
+    (LogAction::SyncReplica, "Sync_Replica", Security),
+    (LogAction::LanTransfer, "Lan_Transfer", Security),
+    (LogAction::ViewLogs, "View_Logs", Info),
+    (LogAction::CreateDevSession, "Create_Dev_Session", Info),
+    (LogAction::ApproveProdSession, "Approve_Prod_Session", Security),
+    (LogAction::CreateProxySession, "Create_Proxy_Session", Info),
+    (LogAction::ServeProxySession, "Serve_Proxy_Session", Info),
+    (LogAction::RevokeProxySession, "Revoke_Proxy_Session", Info),
+    (LogAction::AllowAppAccess, "Allow_App_Access", Security),
+    (LogAction::DenyAppAccess, "Deny_App_Access", Security),
+    (LogAction::RenameDevSession, "Rename_Dev_Session", Info),
+    (LogAction::StopDevSession, "Stop_Dev_Session", Info),
+    (LogAction::ClearAllDevSessions, "Clear_All_Dev_Sessions", Info),
+    (LogAction::DevSessionExpired, "Dev_Session_Expired", Info),
+    (LogAction::DevSessionIdle, "Dev_Session_Idle", Info),
+    (LogAction::DevSessionRemovedExternally, "Dev_Session_Removed_Externally", Warning),
+    (LogAction::DevSessionTampered, "Dev_Session_Tampered", Security),
+    (LogAction::DevSessionUntracked, "Dev_Session_Untracked", Warning),
+    (LogAction::MoveSessionsDirectory, "Move_Sessions_Directory", Info),
+    (LogAction::BindProject, "Bind_Project", Info),
+    (LogAction::UnbindProject, "Unbind_Project", Info),
+    (LogAction::WriteEnvrc, "Write_Envrc", Info),
+    (LogAction::ShredEnvFile, "Shred_Env_File", Security),
+    (LogAction::MigrateVault, "Migrate_Vault", Info),
+    (LogAction::MigrateSecretAad, "Migrate_Secret_Aad", Info),
+    (LogAction::MigrateBoxKeys, "Migrate_Box_Keys", Info),
+    (LogAction::RecoverStorage, "Recover_Storage", Warning),
+    (LogAction::CompactStorage, "Compact_Storage", Info),
+    (LogAction::ScheduleCompaction, "Schedule_Compaction", Info),
+    (LogAction::MoveDataDirectory, "Move_Data_Directory", Info),
+    (LogAction::RestoreSnapshot, "Restore_Snapshot", Warning),
+    (LogAction::Unknown, "Unknown", Info),
+];
+
+impl LogAction {
+    fn entry(&self) -> &'static (LogAction, &'static str, LogSeverity) {
+        LOG_ACTIONS
+            .iter()
+            .find(|(action, _, _)| action == self)
+            .expect("every log action has a table entry")
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        self.entry().1
+    }
+
+    pub fn severity(&self) -> LogSeverity {
+        self.entry().2
+    }
+
+    pub fn parse(name: &str) -> Self {
+        LOG_ACTIONS
+            .iter()
+            .find(|(_, stored, _)| *stored == name)
+            .map_or(LogAction::Unknown, |(action, _, _)| *action)
+    }
+
+    pub fn is_export(&self) -> bool {
+        self.as_str().starts_with("Export_")
+    }
+}
+
+impl From<LogAction> for String {
+    fn from(action: LogAction) -> Self {
+        action.as_str().to_string()
+    }
+}
+
+impl From<String> for LogAction {
+    fn from(name: String) -> Self {
+        LogAction::parse(&name)
+    }
+}
+
+impl std::fmt::Display for LogAction {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct LogEntry {
     pub id: String,
-    pub action: String,
+    #[ts(type = "string")]
+    pub action: LogAction,
+    #[serde(default)] // Entries written before severities were recorded read as Info
+    pub severity: LogSeverity,
     pub message: String,
     pub content: Option<String>, // Extra details if needed
     #[serde(with = "chrono::serde::ts_seconds")]
+    #[ts(type = "number")]
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
 impl LogEntry {
-    pub fn new(action: String, message: String, content: Option<String>) -> Self {
+    pub fn new(action: LogAction, message: String, content: Option<String>) -> Self {
         Self {
             id: uuid::Uuid::new_v4().to_string(),
             action,
+            severity: action.severity(),
             message,
             content,
             timestamp: chrono::Utc::now(),
         }
     }
+
+    /// Whether this entry passes the log screen's filters
+    pub fn matches(&self, min_severity: Option<LogSeverity>, action: Option<LogAction>) -> bool {
+        min_severity.is_none_or(|min| self.severity >= min)
+            && action.is_none_or(|action| self.action == action)
+    }
 }
@@ -3,21 +3,89 @@
 pub mod auth_model;
 pub mod box_model;
 pub mod dev_model;
+pub mod diagnostics_model;
+pub mod diff_model;
 pub mod error_model;
+pub mod github_model;
+pub mod gitlab_model;
+pub mod hook_model;
 pub mod import_export_model;
+pub mod job_model;
 pub mod log_model;
+pub mod project_model;
+pub mod proxy_model;
+pub mod replica_model;
+pub mod search_model;
 pub mod secret_model;
 pub mod settings_model;
+pub mod share_model;
+pub mod startup_model;
+pub mod stats_model;
+pub mod storage_model;
+pub mod sync_model;
+pub mod template_model;
+pub mod transfer_model;
+pub mod undo_model;
 
 // Re-export all public types
-pub use auth_model::{AuthConfig, SessionInfo, SessionState};
-pub use box_model::Box;
-pub use dev_model::{ActiveSessionInfo, DevSession, DevStats};
-pub use error_model::ZapError;
-pub use import_export_model::{BoxExport, ImportResult, SecretExport, VaultExport};
-pub use log_model::LogEntry;
-pub use secret_model::{EncryptedData, Secret};
-pub use settings_model::Settings;
+pub use auth_model::{
+    AuthConfig, EmergencyKit, HardwareKeyConfig, HardwareKeyStatus, KdfParams, RecoveryConfig,
+    RememberedKey, SessionInfo, SessionPhase, SessionState,
+};
+pub use box_model::{Box, BoxProvenance};
+pub use dev_model::{
+    ActiveSessionInfo, CliSessionFile, DevSession, DevStats, ProdApproval, SecretSelection,
+    SessionFileCompatibility, SessionFileEvent, SessionFileSchema, SessionFileStatus,
+    SshConfigExport,
+};
+pub use diagnostics_model::{DatabaseDiagnostic, DiagnosticsReport, PathDiagnostic};
+pub use diff_model::{BoxChanges, BoxDiff, VaultDiff};
+pub use error_model::{ErrorCode, ZapError};
+pub use github_model::{GithubPublicKey, GithubPushReport, GithubSecretResult, GithubSecretStatus};
+pub use gitlab_model::{
+    GitlabChange, GitlabScope, GitlabSyncReport, GitlabTarget, GitlabVariable, GitlabVariableChange,
+};
+pub use hook_model::{Hook, HookAction, HookEvent};
+pub use import_export_model::{
+    BoxExport, ConfigFormat, CsvColumn, CsvColumnMapping, ExportSignature, ExternalSecretProperty,
+    ExternalSecretsExport, ImportProgress, ImportResult, MergeStrategy, SecretExport,
+    SignatureStatus, SmartImportEntry, SmartImportFormat, SmartImportPreview, TfvarsFormat,
+    VaultExport,
+};
+pub use job_model::{JobInfo, JobKind, JobStatus};
+pub use log_model::{LogAction, LogEntry, LogSeverity};
+pub use project_model::{EnvHygieneReport, EnvShredResult, ProjectBinding};
+pub use proxy_model::{
+    AppAccessRequest, ProxyEndpoint, ProxyReply, ProxyRequest, ProxySessionInfo, ProxySessionToken,
+};
+pub use replica_model::{
+    ConflictResolution, RecordContent, RecordVersion, ReplicaConfig, ReplicaRecord,
+    ReplicaSnapshot, ReplicaSyncReport, ReplicaTarget, SealedReplica, SyncConflict,
+};
+pub use search_model::{
+    BoxSearchResult, DeepSearchMatch, MatchField, MatchSpan, SecretSearchResult,
+};
+pub use secret_model::{
+    CipherAlgorithm, CompromisedSecret, EncryptedData, PasswordStrength, RecentSecret,
+    RevealHandle, SavedSecret, Secret, SecretBreach, SecretUsage, SecretWarning, MultilineFormat,
+    SecretWarningCode, ValueEncoding,
+};
+pub use settings_model::{
+    AppAccessSettings, AutoExportSettings, BreachCheckSettings, DiagnosticLogLevel, DockSide,
+    FileLoggingSettings, Settings, WidthUnit, WindowLayout,
+};
+pub use share_model::{OneTimeShare, RedeemedShare, SharePayload};
+pub use startup_model::{DatabaseKind, StartupStatus, StorageRecovery};
+pub use stats_model::{ReportFormat, StatsReport, VaultStats};
+pub use storage_model::{
+    DataDirectoryInfo, DatabaseUsage, KeyGroupUsage, SnapshotReason, StorageUsage, VaultSnapshot,
+};
+pub use sync_model::{
+    SyncDiff, SyncDiffEntry, SyncMapping, SyncProviderConfig, SyncResult, SyncState,
+};
+pub use template_model::{RenderedTemplate, SecretTemplate, SecretTemplateField, TemplateMode};
+pub use transfer_model::{LanTransferOffer, LanTransferReceipt, TransferMessage, TransferScope};
+pub use undo_model::UndoResult;
 
 // Type aliases
 pub type BoxId = String;
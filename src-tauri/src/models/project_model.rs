@@ -0,0 +1,72 @@
+// src/models/project_model.rs
+
+use crate::models::ImportResult;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+pub const PROJECT_FILE_NAME: &str = "zap.json";
+pub const ENVRC_FILE_NAME: &str = ".envrc";
+pub const ENV_FILE_NAME: &str = ".env";
+
+// Zap owns only the lines between these markers in a project's .envrc
+pub const ENVRC_BLOCK_START: &str = "# >>> zap >>>";
+pub const ENVRC_BLOCK_END: &str = "# <<< zap <<<";
+
+/// direnv snippet: `zap export` prints shell exports for the project's current session
+pub fn envrc_block() -> String {
+    [
+        ENVRC_BLOCK_START,
+        "# Managed by Zap - loads secrets from the current session without a .env file",
+        "watch_file zap.json",
+        "eval \"$(zap export --shell)\"",
+        ENVRC_BLOCK_END,
+    ]
+    .join("\n")
+}
+
+fn default_app() -> String {
+    "zap".to_string()
+}
+
+// Project binding stored as zap.json in a project directory.
+// Shares the file with the CLI's project context (app, current_session, available_secrets).
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ProjectBinding {
+    #[serde(default = "default_app")]
+    pub app: String,
+    pub box_id: String,               // Box bound to this directory
+    pub box_name: String,             // Box name for display
+    pub default_session_name: String, // Session created by create_session_for_project
+    pub current_session: String,      // Session the CLI uses in this directory
+    #[serde(default)]
+    pub available_secrets: Vec<String>,
+}
+
+impl ProjectBinding {
+    pub fn new(box_id: String, box_name: String, default_session_name: String) -> Self {
+        Self {
+            app: default_app(),
+            box_id,
+            box_name,
+            current_session: default_session_name.clone(),
+            default_session_name,
+            available_secrets: Vec::new(),
+        }
+    }
+}
+
+// Loose .env file in a bound project, checked before moving it into the box
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct EnvHygieneReport {
+    pub env_path: String,
+    pub env_file_exists: bool,
+    pub git_repository: bool,
+    pub gitignored: Option<bool>, // None outside a git repository
+    pub variable_count: usize,    // Variables that an import would move into the box
+}
+
+#[derive(Serialize, TS)]
+pub struct EnvShredResult {
+    pub import: ImportResult,
+    pub shredded: bool, // False when the import had errors, the file is left in place
+}
@@ -0,0 +1,70 @@
+// src/models/proxy_model.rs - Proxy dev sessions: the CLI holds a short-lived bearer token and
+// fetches values from the running app, so no session key is ever written to disk.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use ts_rs::TS;
+
+pub const MAX_PROXY_SESSION_MINUTES: u32 = 480;
+pub const APP_ACCESS_TIMEOUT_SECONDS: u64 = 60; // Unanswered access prompts deny the request
+
+// Returned once when the session is created - the app never shows the token again
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ProxySessionToken {
+    pub session_name: String,
+    pub box_name: String,
+    pub token: String, // Hand to zap-cli as --token or ZAP_SESSION_TOKEN
+    pub secrets_count: usize,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    #[ts(type = "number")]
+    pub expires_at: DateTime<Utc>,
+}
+
+// Response struct for UI - active proxy sessions, without their tokens
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ProxySessionInfo {
+    pub session_name: String,
+    pub box_name: String,
+    pub secrets_count: usize,
+    pub fetch_count: u32,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    #[ts(type = "number")]
+    pub expires_at: DateTime<Utc>,
+}
+
+// Event payload - a program on neither access list wants a proxy session's values
+// (Settings: app_access). Answered with answer_app_access.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct AppAccessRequest {
+    pub request_id: String,
+    pub exe_path: String,
+    pub pid: u32,
+    pub session_name: String,
+}
+
+// Where the app listens, written to proxy_endpoint.json for the CLI (loopback only)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxyEndpoint {
+    pub port: u16,
+}
+
+// CLI -> app, one JSON line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProxyRequest {
+    pub token: String,
+}
+
+// App -> CLI, one JSON line
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProxyReply {
+    Secrets {
+        session_name: String,
+        box_name: String,
+        secrets: BTreeMap<String, String>, // Secret name -> value
+    },
+    Error {
+        message: String,
+    },
+}
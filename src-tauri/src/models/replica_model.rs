@@ -0,0 +1,222 @@
+// src/models/replica_model.rs - Two-way vault sync with a remote replica (Settings: replicas)
+// Every box and secret write is journaled with a Lamport clock; a sync compares each record
+// with the version both sides last agreed on, so edits on both sides surface as conflicts.
+
+use crate::models::{
+    EncryptedData, KdfParams, MultilineFormat, SyncProviderConfig, ValueEncoding, ZapError,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use ts_rs::TS;
+
+pub const MAX_REPLICAS: usize = 10;
+
+// One journaled write to a box or secret, "box:<id>" / "secret:<id>" are the record keys
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordVersion {
+    pub lamport: u64,
+    pub device_id: String,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+    #[serde(default)]
+    pub deleted: bool,
+}
+
+// Where the replica lives. Its snapshot is sealed with the passphrase in key_secret_id,
+// so every device using it needs a vault secret with the same passphrase.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ReplicaTarget {
+    // Any file, e.g. in a synced folder
+    File {
+        path: String,
+    },
+    // A file in a local git clone, pulled before and committed + pushed after each sync
+    Git {
+        repository: String,
+        file_name: String,
+    },
+    // One secret in a cloud store (stores cap values at 25-64 KiB, fine for small vaults)
+    Provider {
+        provider: SyncProviderConfig,
+        credentials_secret_id: String,
+        secret_name: String,
+    },
+    // A folder on a WebDAV server (Nextcloud, ownCloud...), with a file per sync on top of the
+    // snapshot and ETag checks so concurrent syncs can't overwrite each other
+    WebDav {
+        url: String,
+        username: String,
+        password_secret_id: String, // App password
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct ReplicaConfig {
+    pub id: String,
+    pub target: ReplicaTarget,
+    pub key_secret_id: String, // Never synced itself
+}
+
+impl ReplicaConfig {
+    pub fn validate(&self) -> Result<(), ZapError> {
+        let blank = |value: &str| value.trim().is_empty();
+        if blank(&self.id) || blank(&self.key_secret_id) {
+            return Err(ZapError::ValidationError(
+                "Replicas need an id and a passphrase secret".to_string(),
+            ));
+        }
+
+        match &self.target {
+            ReplicaTarget::File { path } => {
+                if blank(path) {
+                    return Err(ZapError::ValidationError(
+                        "File replicas need a path".to_string(),
+                    ));
+                }
+            }
+            ReplicaTarget::Git {
+                repository,
+                file_name,
+            } => {
+                if blank(repository) || blank(file_name) || file_name.contains(['/', '\\']) {
+                    return Err(ZapError::ValidationError(
+                        "Git replicas need a repository and a plain file name".to_string(),
+                    ));
+                }
+            }
+            ReplicaTarget::Provider {
+                provider,
+                credentials_secret_id,
+                secret_name,
+            } => {
+                if blank(credentials_secret_id) || blank(secret_name) {
+                    return Err(ZapError::ValidationError(
+                        "Provider replicas need a credentials secret and a secret name".to_string(),
+                    ));
+                }
+                provider.validate()?;
+            }
+            ReplicaTarget::WebDav {
+                url,
+                username,
+                password_secret_id,
+            } => {
+                if !url.starts_with("https://") || blank(username) || blank(password_secret_id) {
+                    return Err(ZapError::ValidationError(
+                        "WebDAV replicas need an https folder URL, a username and a password secret"
+                            .to_string(),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn label(&self) -> String {
+        match &self.target {
+            ReplicaTarget::File { path } => path.clone(),
+            ReplicaTarget::Git {
+                repository,
+                file_name,
+            } => format!("{} ({})", repository, file_name),
+            ReplicaTarget::Provider {
+                provider,
+                secret_name,
+                ..
+            } => format!("{} ({})", provider.label(), secret_name),
+            ReplicaTarget::WebDav { url, .. } => url.clone(),
+        }
+    }
+}
+
+// What a record holds on the replica. Values are plaintext here, the snapshot as a whole
+// is sealed. Local-only fields (favorite, dev_mode, usage) are left out.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RecordContent {
+    Box {
+        name: String,
+        description: Option<String>,
+        tags: Vec<String>,
+    },
+    Secret {
+        box_id: String,
+        name: String,
+        value: String,
+        #[serde(default)]
+        variants: BTreeMap<String, String>,
+        #[serde(default)]
+        env_name: Option<String>,
+        #[serde(default)]
+        value_encoding: ValueEncoding,
+        #[serde(default)]
+        multiline_format: MultilineFormat,
+        #[serde(default)]
+        rotation_interval_days: Option<u32>,
+    },
+}
+
+impl RecordContent {
+    pub fn name(&self) -> &str {
+        match self {
+            RecordContent::Box { name, .. } | RecordContent::Secret { name, .. } => name,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReplicaRecord {
+    pub version: RecordVersion,
+    pub content: Option<RecordContent>, // None = deleted
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ReplicaSnapshot {
+    pub records: BTreeMap<String, ReplicaRecord>, // By record key
+}
+
+// What the replica target holds: the snapshot sealed with a key derived from the replica
+// passphrase. Salt and KDF parameters travel along, like an EncryptedBackup.
+pub const REPLICA_FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+pub struct SealedReplica {
+    pub version: u32,
+    pub salt: String, // Hex
+    pub kdf_params: KdfParams,
+    pub snapshot: EncryptedData, // ReplicaSnapshot JSON
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ConflictResolution {
+    KeepLocal,
+    KeepRemote,
+}
+
+// A record changed on both sides since the last sync, left alone until resolved
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SyncConflict {
+    pub record_key: String,
+    pub name: String, // Box or secret name, local side if it still exists
+    pub local_deleted: bool,
+    pub remote_deleted: bool,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    #[ts(type = "number")]
+    pub local_updated_at: chrono::DateTime<chrono::Utc>,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    #[ts(type = "number")]
+    pub remote_updated_at: chrono::DateTime<chrono::Utc>,
+    #[serde(default)]
+    pub resolution: Option<ConflictResolution>, // Applied by the next sync
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ReplicaSyncReport {
+    pub replica_id: String,
+    pub pushed: Vec<String>, // Names
+    pub pulled: Vec<String>,
+    pub conflicts: Vec<SyncConflict>,
+    pub errors: Vec<String>,
+}
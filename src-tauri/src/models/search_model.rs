@@ -0,0 +1,69 @@
+// src/models/search_model.rs
+
+use crate::models::{Box, Secret};
+use serde::Serialize;
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchField {
+    Name,
+    Tag,
+}
+
+// Byte range [start, end) into the matched field's UTF-8 text
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct MatchSpan {
+    pub field: MatchField,
+    pub start: usize,
+    pub end: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>, // Set for tag matches
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct BoxSearchResult {
+    pub item: Box,
+    pub matches: Vec<MatchSpan>,
+    pub matched_tags: Vec<String>, // Filter tags this box carries
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SecretSearchResult {
+    pub item: Secret,
+    pub matches: Vec<MatchSpan>,
+}
+
+// A secret whose decrypted value contains the deep search query (the value itself stays private)
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct DeepSearchMatch {
+    pub secret_id: String,
+    pub secret_name: String,
+    pub box_id: String,
+    pub box_name: String,
+}
+
+/// Case-insensitive, non-overlapping occurrences of `query` in `text` as byte ranges of `text`
+pub fn find_match_ranges(text: &str, query: &str) -> Vec<(usize, usize)> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+
+    // Lowercasing can change byte lengths, so remember where each lowered byte came from
+    let mut lowered = String::with_capacity(text.len());
+    let mut origins = Vec::with_capacity(text.len());
+    for (offset, c) in text.char_indices() {
+        for lower in c.to_lowercase() {
+            lowered.push(lower);
+            origins.resize(lowered.len(), offset);
+        }
+    }
+
+    let original_end = |lowered_end: usize| origins.get(lowered_end).copied().unwrap_or(text.len());
+
+    lowered
+        .match_indices(&query)
+        .map(|(start, matched)| (origins[start], original_end(start + matched.len())))
+        .collect()
+}
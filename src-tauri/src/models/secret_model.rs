@@ -1,17 +1,46 @@
 // src/models/secret_model.rs
 
 use crate::models::ZapError;
+use crate::utils::input_validation::grapheme_count;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use ts_rs::TS;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+pub const MAX_ENVIRONMENT_LENGTH: usize = 30;
+pub const MAX_SECRET_VARIANTS: usize = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct Secret {
     pub id: String,
     pub box_id: String, // Foreign key to box
     pub name: String,   // 75 chars max, minimum 2 chars for .ENV
     pub encrypted_value: EncryptedData,
+    #[serde(default)]
+    pub value_in_blob: bool, // Large value kept apart, encrypted_value is empty in listings
+    #[serde(default)]
+    pub value_encoding: ValueEncoding, // How the value is passed in and out, see ValueEncoding
+    #[serde(default)]
+    pub multiline_format: MultilineFormat, // .env export style for values with line breaks
+    #[serde(default)]
+    pub variants: BTreeMap<String, EncryptedData>, // Per-environment values, e.g. "prod"
+    #[serde(default)]
+    pub env_name: Option<String>, // Original env var name from .env import, kept for export
+    #[serde(default)]
+    pub favorite: bool, // Pinned for quick access, ranked first in search (not exported)
+    #[serde(default)]
+    pub strength: Option<PasswordStrength>, // Scored when a password-kind value is saved
+    #[serde(default)]
+    pub rotation_interval_days: Option<u32>, // None = no rotation reminders
+    #[serde(default, with = "chrono::serde::ts_seconds_option")]
+    #[ts(type = "number | null")]
+    pub last_rotated_at: Option<chrono::DateTime<chrono::Utc>>, // None = never, count from creation
     #[serde(with = "chrono::serde::ts_seconds")]
+    #[ts(type = "number")]
     pub created_at: chrono::DateTime<chrono::Utc>,
     #[serde(with = "chrono::serde::ts_seconds")]
+    #[ts(type = "number")]
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
@@ -29,11 +58,41 @@ impl Secret {
             box_id,
             name,
             encrypted_value,
+            value_in_blob: false,
+            value_encoding: ValueEncoding::default(),
+            multiline_format: MultilineFormat::default(),
+            variants: BTreeMap::new(),
+            env_name: None,
+            favorite: false,
+            strength: None,
+            rotation_interval_days: None,
+            last_rotated_at: None,
             created_at: now,
             updated_at: now,
         })
     }
 
+    pub fn with_env_name(mut self, env_name: Option<String>) -> Self {
+        self.set_env_name(env_name);
+        self
+    }
+
+    /// Env names come from pastes, imports and replicas - anything that isn't a plain
+    /// identifier is dropped so exports fall back to the name-derived variable
+    pub fn set_env_name(&mut self, env_name: Option<String>) {
+        self.env_name = env_name.filter(|env_name| is_env_var_name(env_name));
+    }
+
+    pub fn with_value_encoding(mut self, value_encoding: ValueEncoding) -> Self {
+        self.value_encoding = value_encoding;
+        self
+    }
+
+    pub fn with_multiline_format(mut self, multiline_format: MultilineFormat) -> Self {
+        self.multiline_format = multiline_format;
+        self
+    }
+
     pub fn update_fields(
         &mut self,
         name: Option<String>,
@@ -43,12 +102,18 @@ impl Secret {
 
         if let Some(new_name) = name {
             Self::validate_name(&new_name)?;
+            // A renamed secret no longer maps to its imported env var name
+            if new_name != self.name {
+                self.env_name = None;
+            }
             self.name = new_name;
             updated = true;
         }
 
+        // A new value is a rotation
         if let Some(new_encrypted_value) = encrypted_value {
             self.encrypted_value = new_encrypted_value;
+            self.last_rotated_at = Some(chrono::Utc::now());
             updated = true;
         }
 
@@ -59,15 +124,47 @@ impl Secret {
         Ok(())
     }
 
+    /// Environments with their own value, in name order
+    pub fn environments(&self) -> Vec<String> {
+        self.variants.keys().cloned().collect()
+    }
+
+    pub fn set_rotation_interval(&mut self, days: Option<u32>) -> Result<(), ZapError> {
+        if days == Some(0) || days.is_some_and(|days| days > 3650) {
+            return Err(ZapError::ValidationError(
+                "Rotation interval must be between 1 and 3650 days".to_string(),
+            ));
+        }
+        self.rotation_interval_days = days;
+        Ok(())
+    }
+
+    pub fn mark_rotated(&mut self) {
+        let now = chrono::Utc::now();
+        self.last_rotated_at = Some(now);
+        self.updated_at = now;
+    }
+
+    /// When the next rotation is due, None if no interval is set
+    pub fn rotation_due_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        let days = self.rotation_interval_days?;
+        let rotated_at = self.last_rotated_at.unwrap_or(self.created_at);
+        Some(rotated_at + chrono::Duration::days(days as i64))
+    }
+
+    pub fn is_rotation_due(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.rotation_due_at().is_some_and(|due_at| due_at <= now)
+    }
+
     // Keep existing validation methods unchanged
     pub fn validate_name(name: &str) -> Result<(), ZapError> {
         let trimmed = name.trim();
-        if trimmed.len() < 2 {
+        if grapheme_count(trimmed) < 2 {
             return Err(ZapError::ValidationError(
                 "Secret name must be at least 2 characters for .ENV compatibility".to_string(),
             ));
         }
-        if trimmed.len() > 75 {
+        if grapheme_count(trimmed) > 75 {
             return Err(ZapError::ValidationError(
                 "Secret name cannot exceed 75 characters".to_string(),
             ));
@@ -80,45 +177,257 @@ impl Secret {
         Ok(())
     }
 
+    /// Environment names are short lowercase slugs, e.g. "dev", "staging", "prod"
+    pub fn validate_environment(environment: &str) -> Result<(), ZapError> {
+        let valid = !environment.is_empty()
+            && environment.len() <= MAX_ENVIRONMENT_LENGTH
+            && environment
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+        if !valid {
+            return Err(ZapError::ValidationError(format!(
+                "Environment '{}' must be 1-{} lowercase letters, digits, '-' or '_'",
+                environment, MAX_ENVIRONMENT_LENGTH
+            )));
+        }
+        Ok(())
+    }
+
     pub fn to_env_var_name(&self, prefix: Option<&str>) -> String {
-        let clean_name = self
-            .name
-            .to_uppercase()
-            .chars()
-            .map(|c| if c.is_alphanumeric() { c } else { '_' })
-            .collect::<String>();
-
-        let clean_name = clean_name
-            .split('_')
-            .filter(|s| !s.is_empty())
-            .collect::<Vec<&str>>()
-            .join("_");
-
-        match prefix {
-            Some(p) => {
-                let clean_prefix = p
-                    .to_uppercase()
-                    .chars()
-                    .map(|c| if c.is_alphanumeric() { c } else { '_' })
-                    .collect::<String>();
-                format!("{}_{}", clean_prefix, clean_name)
+        match &self.env_name {
+            Some(env_name) if is_env_var_name(env_name) => {
+                with_env_prefix(env_name.clone(), prefix)
             }
-            None => clean_name,
+            _ => env_var_name(&self.name, prefix),
         }
     }
 }
 
-// Keep EncryptedData unchanged
+/// `[A-Za-z_][A-Za-z0-9_]*`, safe to write as the key of a .env line
+pub fn is_env_var_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Environment variable name for a secret name - the same rule the CLIs apply to session files
+pub fn env_var_name(secret_name: &str, prefix: Option<&str>) -> String {
+    let clean_name = secret_name
+        .to_uppercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect::<String>()
+        .split('_')
+        .filter(|s| !s.is_empty())
+        .collect::<Vec<&str>>()
+        .join("_");
+
+    with_env_prefix(clean_name, prefix)
+}
+
+fn with_env_prefix(clean_name: String, prefix: Option<&str>) -> String {
+    match prefix {
+        Some(p) => {
+            let clean_prefix = p
+                .to_uppercase()
+                .chars()
+                .map(|c| if c.is_alphanumeric() { c } else { '_' })
+                .collect::<String>();
+            format!("{}_{}", clean_prefix, clean_name)
+        }
+        None => clean_name,
+    }
+}
+
+// Result of create_secret / update_secret: the secret was saved, warnings are advisory
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SavedSecret {
+    pub id: String,
+    pub warnings: Vec<SecretWarning>,
+    pub strength: Option<PasswordStrength>, // Only for password-kind secrets, see secret_lint
+}
+
+// zxcvbn estimate for a password-kind value; never includes the value itself
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct PasswordStrength {
+    pub score: u8,               // 0 (guessable) to 4 (very unguessable)
+    pub crack_time: String,      // Human readable, e.g. "3 hours"
+    pub crack_time_seconds: f64, // Offline attack on a slow hash, 10k guesses per second
+    pub feedback: Option<String>,
+}
+
+// A value that is probably a mistake, e.g. a pasted URL with a trailing newline
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SecretWarning {
+    pub code: SecretWarningCode,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum SecretWarningCode {
+    SurroundingWhitespace,
+    SurroundingQuotes,
+    InvalidUrl,
+    InvalidAwsAccessKeyId,
+    InvalidAwsSecretAccessKey,
+}
+
+// One-time handle that lets the UI copy a secret without ever receiving its plaintext
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct RevealHandle {
+    pub handle: String,
+    pub secret_name: String,
+    #[ts(type = "number")]
+    pub expires_in_seconds: u64,
+}
+
+// Reveal / copy history of a secret, stored under secret_usage:<id> beside the record
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretUsage {
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub last_used_at: chrono::DateTime<chrono::Utc>,
+    pub use_count: u32,
+}
+
+// Entry of the "Recent" list: a secret that was revealed or copied
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct RecentSecret {
+    pub item: Secret,
+    pub box_name: String,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    #[ts(type = "number")]
+    pub last_used_at: chrono::DateTime<chrono::Utc>,
+    pub use_count: u32,
+}
+
+// Last breach check of a secret's value, stored under secret_breach:<id> beside the record
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecretBreach {
+    pub pwned_count: u64, // Times the value appears in known breaches, 0 = not found
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub checked_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl SecretBreach {
+    /// Found in a breach, and the value hasn't changed since it was checked
+    pub fn flags(&self, secret: &Secret) -> bool {
+        // Whole seconds, as stored - a check in the same second as the save counts
+        let value_set_at = secret.last_rotated_at.unwrap_or(secret.created_at);
+        self.pwned_count > 0 && self.checked_at.timestamp() >= value_set_at.timestamp()
+    }
+}
+
+// Entry of get_compromised_secrets: a password found in a known breach
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct CompromisedSecret {
+    pub item: Secret,
+    pub box_name: String,
+    #[ts(type = "number")]
+    pub pwned_count: u64,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    #[ts(type = "number")]
+    pub checked_at: chrono::DateTime<chrono::Utc>,
+}
+
+// AEAD used for a ciphertext, records from before the field existed are AES-256-GCM
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, TS)]
+pub enum CipherAlgorithm {
+    #[default]
+    #[serde(rename = "aes-256-gcm")]
+    Aes256Gcm,
+    #[serde(rename = "xchacha20-poly1305")]
+    XChaCha20Poly1305, // Fast without AES-NI
+}
+
+impl CipherAlgorithm {
+    pub fn nonce_len(&self) -> usize {
+        match self {
+            CipherAlgorithm::Aes256Gcm => 12,
+            CipherAlgorithm::XChaCha20Poly1305 => 24,
+        }
+    }
+}
+
+// Binary values (certificates, keystores) travel as base64 text and are stored as raw bytes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueEncoding {
+    #[default]
+    Utf8,
+    Base64,
+}
+
+impl ValueEncoding {
+    pub fn is_utf8(&self) -> bool {
+        *self == ValueEncoding::Utf8
+    }
+
+    /// Bytes to encrypt for a value as given (line breaks in base64 are ignored)
+    pub fn decode(&self, value: &str) -> Result<Vec<u8>, ZapError> {
+        match self {
+            ValueEncoding::Utf8 => Ok(value.as_bytes().to_vec()),
+            ValueEncoding::Base64 => {
+                let compact: String = value.split_whitespace().collect();
+                BASE64.decode(compact).map_err(|_| {
+                    ZapError::ValidationError("Secret value is not valid base64".to_string())
+                })
+            }
+        }
+    }
+
+    /// Decrypted bytes back in the form they were given
+    pub fn encode(&self, bytes: Vec<u8>) -> Result<String, ZapError> {
+        match self {
+            ValueEncoding::Utf8 => String::from_utf8(bytes)
+                .map_err(|e| ZapError::CryptoError(format!("Invalid UTF-8: {}", e))),
+            ValueEncoding::Base64 => Ok(BASE64.encode(bytes)),
+        }
+    }
+}
+
+// How .env exports write a value that spans lines (private keys, certificates)
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum MultilineFormat {
+    #[default]
+    Escaped, // KEY="line 1\nline 2" on a single line
+    Quoted,  // Line breaks kept inside double quotes, the way PEM blocks are usually pasted
+}
+
+impl MultilineFormat {
+    pub fn is_escaped(&self) -> bool {
+        *self == MultilineFormat::Escaped
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct EncryptedData {
     pub cipher: Vec<u8>,
     pub nonce: Vec<u8>,
     pub tag: Vec<u8>,
+    #[serde(default)]
+    pub algorithm: CipherAlgorithm,
+    #[serde(default)]
+    pub bound: bool, // Authenticated with its secret id and box id as AAD
 }
 
 impl EncryptedData {
-    pub fn new(cipher: Vec<u8>, nonce: Vec<u8>, tag: Vec<u8>) -> Self {
-        Self { cipher, nonce, tag }
+    pub fn new(
+        cipher: Vec<u8>,
+        nonce: Vec<u8>,
+        tag: Vec<u8>,
+        algorithm: CipherAlgorithm,
+    ) -> Self {
+        Self {
+            cipher,
+            nonce,
+            tag,
+            algorithm,
+            bound: false,
+        }
     }
 
     pub fn empty() -> Self {
@@ -126,11 +435,15 @@ impl EncryptedData {
             cipher: Vec::new(),
             nonce: Vec::new(),
             tag: Vec::new(),
+            algorithm: CipherAlgorithm::default(),
+            bound: false,
         }
     }
 
     pub fn is_valid(&self) -> bool {
-        !self.cipher.is_empty() && self.nonce.len() == 12 && self.tag.len() == 16
+        !self.cipher.is_empty()
+            && self.nonce.len() == self.algorithm.nonce_len()
+            && self.tag.len() == 16
     }
 }
 
@@ -1,11 +1,365 @@
 // src/models/settings_model.rs
 
+use crate::models::{CipherAlgorithm, Hook, ReplicaConfig, SyncMapping, ZapError};
 use serde::{Deserialize, Serialize};
+use ts_rs::TS;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+pub const MAX_DEV_SESSION_HOURS: u32 = 720; // 30 days
+pub const MAX_AUTO_EXPORT_INTERVAL_HOURS: u32 = 720;
+pub const MAX_AUTO_EXPORT_KEEP: u32 = 100;
+pub const MAX_BREACH_CHECK_INTERVAL_HOURS: u32 = 720;
+pub const MAX_LOG_FILE_DAYS: u32 = 90;
+pub const MAX_SECRET_VALUE_KIB: u32 = 1024; // Hard ceiling for max_secret_value_kib
+pub const MAX_TRUSTED_SIGNING_KEYS: usize = 50;
+pub const MAX_APP_ACCESS_ENTRIES: usize = 50; // Per list
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
 pub struct Settings {
     pub password_timeout_minutes: u32,
     pub theme: String,
+    #[serde(default)]
+    pub sessions_directory: Option<String>, // None = default app sessions directory
+    #[serde(default)]
+    pub remember_me: bool, // Keep the master key wrapped in the OS keyring until lock/reboot
+    #[serde(default = "default_max_session_minutes")]
+    pub max_session_minutes: u32, // Absolute unlock cap regardless of activity, 0 = no cap
+    #[serde(default)]
+    pub reauth_minutes: u32, // Re-enter password for sensitive actions after N minutes, 0 = off
+    #[serde(default)]
+    pub clipboard_only_reveal: bool, // No plaintext to the webview (reveal, exports, templates, deep search)
+    #[serde(default = "default_clipboard_exclude_from_history")]
+    pub clipboard_exclude_from_history: bool, // Ask clipboard managers not to keep copied secrets
+    #[serde(default = "default_reveal_limit_per_minute")]
+    pub reveal_limit_per_minute: u32, // Max secret reveals per minute, 0 = unlimited
+    #[serde(default)]
+    pub hooks: Vec<Hook>, // Commands / webhooks fired on vault events
+    #[serde(default = "default_lock_warning_seconds")]
+    pub lock_warning_seconds: u32, // Notify this long before auto-lock, 0 = no warning
+    #[serde(default)]
+    pub cipher: CipherAlgorithm, // Used for new encryptions, both are always decryptable
+    #[serde(default)]
+    pub decrypted_cache_seconds: u32, // Keep decrypted values in memory this long, 0 = off
+    #[serde(default)]
+    pub window_layout: WindowLayout, // Sidebar size and docking, applied via apply_window_layout
+    #[serde(default)]
+    pub auto_hide_on_blur: bool, // Hide the sidebar when it loses focus, like a dropdown terminal
+    #[serde(default)]
+    pub dev_session_hours: u32, // Stop dev sessions this long after creation, 0 = never
+    #[serde(default)]
+    pub dev_session_idle_hours: u32, // Stop dev sessions unused by the CLI this long, 0 = never
+    #[serde(default)]
+    pub require_prod_session_approval: bool, // Password + justification for "prod" box sessions
+    #[serde(default)]
+    pub auto_export: AutoExportSettings, // Encrypted backups written on a schedule
+    #[serde(default)]
+    pub breach_check: BreachCheckSettings, // Check passwords against HIBP, off by default
+    #[serde(default)]
+    pub app_access: AppAccessSettings, // Programs allowed to fetch proxy sessions
+    #[serde(default)]
+    pub file_logging: FileLoggingSettings, // Diagnostic log files for support, off by default
+    #[serde(default = "default_lint_secret_values")]
+    pub lint_secret_values: bool, // Warn about suspicious values (stray quotes, malformed URLs)
+    #[serde(default = "default_max_secret_value_kib")]
+    pub max_secret_value_kib: u32, // Largest value accepted on save or import
+    #[serde(default)]
+    pub default_environment: Option<String>, // Variant used when no environment is given
+    #[serde(default)]
+    pub trusted_signing_keys: Vec<String>, // Teammates' export signing keys (hex), besides our own
+    #[serde(default)]
+    pub sync_mappings: Vec<SyncMapping>, // Boxes pushed to / pulled from cloud secret stores
+    #[serde(default)]
+    pub replicas: Vec<ReplicaConfig>, // Whole-vault two-way sync targets
+}
+
+impl Settings {
+    pub fn max_secret_value_bytes(&self) -> usize {
+        self.max_secret_value_kib as usize * 1024
+    }
+}
+
+// Scheduled encrypted backups, written while the vault is unlocked
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct AutoExportSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_auto_export_interval_hours")]
+    pub interval_hours: u32, // Days are multiples of 24
+    #[serde(default)]
+    pub directory: Option<String>, // Absolute path, e.g. a synced folder
+    #[serde(default = "default_auto_export_keep")]
+    pub keep_last: u32, // Older backups in the directory are removed, 0 = keep all
+}
+
+impl AutoExportSettings {
+    pub fn validate(&self) -> Result<(), ZapError> {
+        if self.interval_hours == 0 || self.interval_hours > MAX_AUTO_EXPORT_INTERVAL_HOURS {
+            return Err(ZapError::ValidationError(format!(
+                "Automatic export interval must be between 1 and {} hours",
+                MAX_AUTO_EXPORT_INTERVAL_HOURS
+            )));
+        }
+        if self.keep_last > MAX_AUTO_EXPORT_KEEP {
+            return Err(ZapError::ValidationError(format!(
+                "Cannot keep more than {} automatic backups",
+                MAX_AUTO_EXPORT_KEEP
+            )));
+        }
+        let has_directory = self
+            .directory
+            .as_deref()
+            .is_some_and(|dir| !dir.trim().is_empty());
+        if self.enabled && !has_directory {
+            return Err(ZapError::ValidationError(
+                "Choose a folder for automatic exports".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for AutoExportSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: default_auto_export_interval_hours(),
+            directory: None,
+            keep_last: default_auto_export_keep(),
+        }
+    }
+}
+
+// Periodic lookup of password-kind secrets in Have I Been Pwned's range API. Only the first
+// 5 hex characters of each value's SHA-1 leave the machine (k-anonymity).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct BreachCheckSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default = "default_breach_check_interval_hours")]
+    pub interval_hours: u32,
+}
+
+impl BreachCheckSettings {
+    pub fn validate(&self) -> Result<(), ZapError> {
+        if self.interval_hours == 0 || self.interval_hours > MAX_BREACH_CHECK_INTERVAL_HOURS {
+            return Err(ZapError::ValidationError(format!(
+                "Breach check interval must be between 1 and {} hours",
+                MAX_BREACH_CHECK_INTERVAL_HOURS
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for BreachCheckSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            interval_hours: default_breach_check_interval_hours(),
+        }
+    }
+}
+
+// Per-program firewall for the session proxy, like a keychain's per-binary access list.
+// Programs are identified by executable path; ones on neither list prompt in the UI.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct AppAccessSettings {
+    #[serde(default = "default_app_access_enabled")]
+    pub enabled: bool, // Off = any local program holding a token is served
+    #[serde(default)]
+    pub allowed_apps: Vec<String>,
+    #[serde(default)]
+    pub denied_apps: Vec<String>,
+}
+
+impl AppAccessSettings {
+    pub fn validate(&self) -> Result<(), ZapError> {
+        if self.allowed_apps.len() > MAX_APP_ACCESS_ENTRIES
+            || self.denied_apps.len() > MAX_APP_ACCESS_ENTRIES
+        {
+            return Err(ZapError::ValidationError(format!(
+                "At most {} programs can be allowed or denied",
+                MAX_APP_ACCESS_ENTRIES
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for AppAccessSettings {
+    fn default() -> Self {
+        Self {
+            enabled: default_app_access_enabled(),
+            allowed_apps: Vec::new(),
+            denied_apps: Vec::new(),
+        }
+    }
+}
+
+// Rotating diagnostic log files in the app's diagnostics folder. Never contain secret values,
+// and box/secret names are redacted in release builds.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct FileLoggingSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub level: DiagnosticLogLevel,
+    #[serde(default = "default_log_file_days")]
+    pub keep_days: u32, // One file per day, older files are removed
+}
+
+impl FileLoggingSettings {
+    pub fn validate(&self) -> Result<(), ZapError> {
+        if self.keep_days == 0 || self.keep_days > MAX_LOG_FILE_DAYS {
+            return Err(ZapError::ValidationError(format!(
+                "Log files must be kept between 1 and {} days",
+                MAX_LOG_FILE_DAYS
+            )));
+        }
+        Ok(())
+    }
+}
+
+impl Default for FileLoggingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            level: DiagnosticLogLevel::default(),
+            keep_days: default_log_file_days(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum DiagnosticLogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum DockSide {
+    Left,
+    #[default]
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum WidthUnit {
+    #[default]
+    Percent, // Of the monitor width
+    Pixels, // Logical pixels, scaled by the monitor's DPI
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+pub struct WindowLayout {
+    #[serde(default)]
+    pub dock: DockSide,
+    #[serde(default = "default_window_width")]
+    pub width: u32, // Interpreted per width_unit
+    #[serde(default)]
+    pub width_unit: WidthUnit,
+    #[serde(default = "default_window_height_percent")]
+    pub height_percent: u32, // Of the monitor height, vertically centered
+    #[serde(default = "default_window_margin")]
+    pub margin: u32, // Logical pixels between the window and the docked edge
+}
+
+impl WindowLayout {
+    pub fn validate(&self) -> Result<(), ZapError> {
+        let width_ok = match self.width_unit {
+            WidthUnit::Percent => (10..=100).contains(&self.width),
+            WidthUnit::Pixels => (200..=7680).contains(&self.width),
+        };
+        if !width_ok {
+            return Err(ZapError::ValidationError(
+                "Window width must be 10-100% or 200-7680 pixels".to_string(),
+            ));
+        }
+        if !(10..=100).contains(&self.height_percent) {
+            return Err(ZapError::ValidationError(
+                "Window height must be between 10% and 100%".to_string(),
+            ));
+        }
+        if self.margin > 500 {
+            return Err(ZapError::ValidationError(
+                "Window margin cannot exceed 500 pixels".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Default for WindowLayout {
+    fn default() -> Self {
+        Self {
+            dock: DockSide::default(),
+            width: default_window_width(),
+            width_unit: WidthUnit::default(),
+            height_percent: default_window_height_percent(),
+            margin: default_window_margin(),
+        }
+    }
+}
+
+fn default_max_session_minutes() -> u32 {
+    480
+}
+
+fn default_reveal_limit_per_minute() -> u32 {
+    10
+}
+
+fn default_clipboard_exclude_from_history() -> bool {
+    true
+}
+
+fn default_lock_warning_seconds() -> u32 {
+    60
+}
+
+fn default_window_width() -> u32 {
+    25
+}
+
+fn default_window_height_percent() -> u32 {
+    90
+}
+
+fn default_window_margin() -> u32 {
+    10
+}
+
+fn default_auto_export_interval_hours() -> u32 {
+    24
+}
+
+fn default_auto_export_keep() -> u32 {
+    7
+}
+
+fn default_breach_check_interval_hours() -> u32 {
+    24
+}
+
+fn default_app_access_enabled() -> bool {
+    true
+}
+
+fn default_log_file_days() -> u32 {
+    7
+}
+
+fn default_lint_secret_values() -> bool {
+    true
+}
+
+fn default_max_secret_value_kib() -> u32 {
+    64
 }
 
 impl Default for Settings {
@@ -13,6 +367,32 @@ impl Default for Settings {
         Self {
             password_timeout_minutes: 5,
             theme: "dark".to_string(),
+            sessions_directory: None,
+            remember_me: false,
+            max_session_minutes: default_max_session_minutes(),
+            reauth_minutes: 0,
+            clipboard_only_reveal: false,
+            clipboard_exclude_from_history: default_clipboard_exclude_from_history(),
+            reveal_limit_per_minute: default_reveal_limit_per_minute(),
+            hooks: Vec::new(),
+            lock_warning_seconds: default_lock_warning_seconds(),
+            cipher: CipherAlgorithm::default(),
+            decrypted_cache_seconds: 0,
+            window_layout: WindowLayout::default(),
+            auto_hide_on_blur: false,
+            dev_session_hours: 0,
+            dev_session_idle_hours: 0,
+            require_prod_session_approval: false,
+            auto_export: AutoExportSettings::default(),
+            breach_check: BreachCheckSettings::default(),
+            app_access: AppAccessSettings::default(),
+            file_logging: FileLoggingSettings::default(),
+            lint_secret_values: default_lint_secret_values(),
+            max_secret_value_kib: default_max_secret_value_kib(),
+            default_environment: None,
+            trusted_signing_keys: Vec::new(),
+            sync_mappings: Vec::new(),
+            replicas: Vec::new(),
         }
     }
 }
@@ -0,0 +1,54 @@
+// src/models/share_model.rs
+
+use crate::models::EncryptedData;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+pub const SHARE_BLOB_PREFIX: &str = "zap-share:";
+pub const SHARE_FORMAT_VERSION: u32 = 1;
+pub const MAX_SHARE_TTL_MINUTES: u32 = 7 * 24 * 60;
+
+// Everything but the passphrase travels in the blob, so any Zap install can redeem it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharePayload {
+    pub version: u32,
+    pub share_id: String, // Single-use nonce, recorded when redeemed
+    pub secret_name: String,
+    pub salt: String, // Hex, for the passphrase key derivation
+    #[serde(with = "chrono::serde::ts_seconds")]
+    pub expires_at: DateTime<Utc>,
+    pub value: EncryptedData, // Bound (AAD) to the fields above
+}
+
+impl SharePayload {
+    // Editing the id, name or expiry in the blob breaks decryption
+    pub fn context(&self) -> String {
+        format!(
+            "zap:share:{}:{}:{}",
+            self.share_id,
+            self.secret_name,
+            self.expires_at.timestamp()
+        )
+    }
+}
+
+// Returned to the sharer - send the passphrase over a different channel than the blob.
+// Single use is only recorded by the vault that redeems it: anyone holding both the blob and
+// the passphrase can open the share on another install until it expires.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct OneTimeShare {
+    pub share_id: String,
+    pub blob: String,
+    pub passphrase: String,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    #[ts(type = "number")]
+    pub expires_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct RedeemedShare {
+    pub share_id: String,
+    pub secret_name: String,
+    pub value: String,
+}
@@ -0,0 +1,57 @@
+// src/models/startup_model.rs
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum DatabaseKind {
+    Vault,
+    Sessions,
+    Logs,
+}
+
+impl DatabaseKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DatabaseKind::Vault => "vault",
+            DatabaseKind::Sessions => "sessions",
+            DatabaseKind::Logs => "logs",
+        }
+    }
+}
+
+// Outcome of opening the databases, reported to the frontend before anything else runs
+#[derive(Debug, Clone, PartialEq, Serialize, TS)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum StartupStatus {
+    Ready,
+    Locked {
+        database: DatabaseKind, // Another process holds the sled lock
+    },
+    Corrupted {
+        database: DatabaseKind,
+        error: String,
+        snapshot_available: bool, // Vault only: a snapshot from the last good start exists
+    },
+    DataDirectoryUnavailable {
+        path: String, // The folder the data directory was moved to, e.g. on a removed drive
+    },
+    Failed {
+        error: String,
+    },
+}
+
+impl StartupStatus {
+    pub fn is_ready(&self) -> bool {
+        matches!(self, StartupStatus::Ready)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum StorageRecovery {
+    Retry,           // Try again, e.g. once the other process has exited
+    RestoreSnapshot, // Replace the corrupted vault with the last good snapshot
+    Repair,          // Move the corrupted database aside and start it empty
+}
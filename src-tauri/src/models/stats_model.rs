@@ -0,0 +1,123 @@
+// src/models/stats_model.rs
+
+use crate::models::PasswordStrength;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct VaultStats {
+    pub total_boxes: usize,
+    pub dev_boxes: usize,
+    pub total_secrets: usize,
+    #[ts(type = "string")]
+    pub last_updated: chrono::DateTime<chrono::Utc>,
+    pub boxes: Vec<BoxStats>,               // Largest boxes first
+    pub tag_distribution: Vec<TagCount>,    // Most used tags first
+    pub activity: ActivityStats,
+    pub log_actions: Vec<ActionCount>,      // Most frequent actions first
+    pub oldest_secrets: Vec<SecretAgeInfo>, // Least recently rotated first
+    pub never_rotated_secrets: usize,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct BoxStats {
+    pub box_id: String,
+    pub name: String,
+    pub dev_mode: bool,
+    pub secrets_count: usize,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    #[ts(type = "number")]
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct TagCount {
+    pub tag: String,
+    pub boxes_count: usize,
+}
+
+#[derive(Debug, Clone, Default, Serialize, TS)]
+pub struct ActivityStats {
+    pub boxes_created_last_30_days: usize,
+    pub boxes_created_last_90_days: usize,
+    pub secrets_created_last_30_days: usize,
+    pub secrets_created_last_90_days: usize,
+    pub secrets_updated_last_30_days: usize,
+    pub secrets_updated_last_90_days: usize,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct ActionCount {
+    pub action: String,
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SecretAgeInfo {
+    pub secret_id: String,
+    pub name: String,
+    pub box_id: String,
+    pub box_name: String,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    #[ts(type = "number")]
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    #[ts(type = "number")]
+    pub rotated_at: chrono::DateTime<chrono::Utc>, // Last rotation, or creation if never rotated
+    pub never_rotated: bool, // Value never rotated since creation
+}
+
+// Compliance report assembled from the stats service
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, TS)]
+#[serde(rename_all = "lowercase")]
+pub enum ReportFormat {
+    Json,
+    Csv,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct StatsReport {
+    #[serde(with = "chrono::serde::ts_seconds")]
+    #[ts(type = "number")]
+    pub generated_at: chrono::DateTime<chrono::Utc>,
+    pub vault: VaultStats,
+    pub audit: AuditSummary,
+    pub box_activity: Vec<BoxActivity>,
+    pub password_strength: Vec<SecretStrengthInfo>, // Weakest first
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct AuditSummary {
+    pub total_entries: usize,
+    #[serde(with = "chrono::serde::ts_seconds_option")]
+    #[ts(type = "number | null")]
+    pub first_entry_at: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(with = "chrono::serde::ts_seconds_option")]
+    #[ts(type = "number | null")]
+    pub last_entry_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub entries_last_30_days: usize,
+    pub reveals_last_30_days: usize,
+    pub exports_last_30_days: usize,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct BoxActivity {
+    pub box_id: String,
+    pub name: String,
+    pub secrets_count: usize,
+    pub secrets_created_last_30_days: usize,
+    pub secrets_updated_last_30_days: usize,
+    #[serde(with = "chrono::serde::ts_seconds_option")]
+    #[ts(type = "number | null")]
+    pub last_secret_update: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+// Password-kind secret scored when its value was last saved
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SecretStrengthInfo {
+    pub secret_id: String,
+    pub name: String,
+    pub box_id: String,
+    pub box_name: String,
+    pub strength: PasswordStrength,
+}
@@ -0,0 +1,91 @@
+// src/models/storage_model.rs
+
+use crate::models::DatabaseKind;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+pub const MAX_VAULT_SNAPSHOTS: usize = 10; // Older ones are removed as new ones are taken
+
+// Records sharing a key prefix (`box:`, `secret:`, `box_name:` ...), the logical tables
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct KeyGroupUsage {
+    pub prefix: String,
+    pub records: usize,
+    #[ts(type = "number")]
+    pub bytes: u64, // Keys + values, before sled's own overhead
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct DatabaseUsage {
+    pub database: DatabaseKind,
+    #[ts(type = "number")]
+    pub bytes_on_disk: u64,
+    #[ts(type = "number")]
+    pub live_bytes: u64, // What a compaction would roughly shrink the database to
+    pub records: usize,
+    pub groups: Vec<KeyGroupUsage>, // Largest first
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct StorageUsage {
+    pub databases: Vec<DatabaseUsage>,
+    #[ts(type = "number")]
+    pub total_bytes_on_disk: u64,
+    pub compaction_scheduled: bool, // Runs on the next start
+}
+
+// Where the databases are kept, see move_data_directory
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct DataDirectoryInfo {
+    pub path: String,
+    pub default_path: String, // Where they go back to when moved with no path
+    pub moved: bool,
+}
+
+// What was about to happen when a vault snapshot was taken
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum SnapshotReason {
+    Import,
+    MergeImport,
+    BulkDelete,
+    Reencrypt, // Master key change or a key migration
+    Restore,   // The vault as it was before another snapshot was restored
+}
+
+impl SnapshotReason {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SnapshotReason::Import => "import",
+            SnapshotReason::MergeImport => "merge_import",
+            SnapshotReason::BulkDelete => "bulk_delete",
+            SnapshotReason::Reencrypt => "reencrypt",
+            SnapshotReason::Restore => "restore",
+        }
+    }
+
+    pub fn parse(reason: &str) -> Option<Self> {
+        [
+            SnapshotReason::Import,
+            SnapshotReason::MergeImport,
+            SnapshotReason::BulkDelete,
+            SnapshotReason::Reencrypt,
+            SnapshotReason::Restore,
+        ]
+        .into_iter()
+        .find(|known| known.as_str() == reason)
+    }
+}
+
+// Copy of the vault taken before a risky operation, see restore_snapshot
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct VaultSnapshot {
+    pub id: String, // `<timestamp>-<reason>`, also its folder name
+    pub reason: SnapshotReason,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    #[ts(type = "number")]
+    pub created_at: DateTime<Utc>,
+    #[ts(type = "number")]
+    pub bytes: u64,
+}
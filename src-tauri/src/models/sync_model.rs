@@ -0,0 +1,124 @@
+// src/models/sync_model.rs - Boxes mapped to a cloud secret store (Settings: sync_mappings)
+
+use crate::models::ZapError;
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+pub const MAX_SYNC_MAPPINGS: usize = 50;
+
+// Where the box's secrets live remotely. The credential itself is a vault secret, see
+// SyncMapping.credentials_secret_id
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SyncProviderConfig {
+    // Service principal, the credential is its client secret
+    AzureKeyVault {
+        vault_url: String, // https://<name>.vault.azure.net
+        tenant_id: String,
+        client_id: String,
+    },
+    // The credential is a service account JSON key
+    GcpSecretManager {
+        project_id: String,
+    },
+}
+
+impl SyncProviderConfig {
+    pub fn label(&self) -> String {
+        match self {
+            SyncProviderConfig::AzureKeyVault { vault_url, .. } => {
+                format!("Azure Key Vault {}", vault_url)
+            }
+            SyncProviderConfig::GcpSecretManager { project_id } => {
+                format!("GCP Secret Manager {}", project_id)
+            }
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), ZapError> {
+        let blank = |value: &str| value.trim().is_empty();
+        match self {
+            SyncProviderConfig::AzureKeyVault {
+                vault_url,
+                tenant_id,
+                client_id,
+            } => {
+                if !vault_url.starts_with("https://") {
+                    return Err(ZapError::ValidationError(
+                        "Key Vault URL must start with https://".to_string(),
+                    ));
+                }
+                if blank(tenant_id) || blank(client_id) {
+                    return Err(ZapError::ValidationError(
+                        "Azure Key Vault needs a tenant id and a client id".to_string(),
+                    ));
+                }
+            }
+            SyncProviderConfig::GcpSecretManager { project_id } => {
+                if blank(project_id) {
+                    return Err(ZapError::ValidationError(
+                        "GCP Secret Manager needs a project id".to_string(),
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct SyncMapping {
+    pub id: String,
+    pub box_id: String,
+    pub provider: SyncProviderConfig,
+    #[serde(default)]
+    pub prefix: Option<String>, // Remote names start with it, e.g. "myapp-"; others are ignored
+    pub credentials_secret_id: String, // Never synced itself
+}
+
+impl SyncMapping {
+    pub fn validate(&self) -> Result<(), ZapError> {
+        let blank = |value: &str| value.trim().is_empty();
+        if blank(&self.id) || blank(&self.box_id) || blank(&self.credentials_secret_id) {
+            return Err(ZapError::ValidationError(
+                "Sync mappings need an id, a box and a credentials secret".to_string(),
+            ));
+        }
+        self.provider.validate()
+    }
+
+    pub fn prefix(&self) -> &str {
+        self.prefix.as_deref().unwrap_or_default()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncState {
+    OnlyLocal,  // Push creates it
+    OnlyRemote, // Pull creates it
+    Different,  // Push or pull overwrites one side
+    Same,
+}
+
+// One name in a diff. Values are compared in the backend and never sent back.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SyncDiffEntry {
+    pub remote_name: String,
+    pub secret_name: Option<String>, // None = not in the box yet
+    pub state: SyncState,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SyncDiff {
+    pub mapping_id: Option<String>, // None for a one-off pull_from_provider
+    pub provider: String,           // "Azure Key Vault https://..."
+    pub entries: Vec<SyncDiffEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SyncResult {
+    pub mapping_id: Option<String>,
+    pub changed: Vec<String>, // Remote names pushed, or secret names pulled
+    pub errors: Vec<String>,
+}
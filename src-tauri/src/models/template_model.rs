@@ -0,0 +1,37 @@
+// src/models/template_model.rs
+
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+// What to do with a `{{secret:Box/Secret}}` placeholder that doesn't resolve
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, TS)]
+#[serde(rename_all = "snake_case")]
+pub enum TemplateMode {
+    #[default]
+    Strict, // Fail the whole render
+    Lenient, // Leave the placeholder as is and report it
+}
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct RenderedTemplate {
+    pub content: String,
+    pub missing: Vec<String>, // "Box/Secret" references left unresolved (lenient mode)
+}
+
+// Built-in recipe create_secret can fill a value from (random tokens, composed URLs)
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SecretTemplate {
+    pub id: String, // Passed to create_secret as template_id
+    pub name: String,
+    pub description: String,
+    pub fields: Vec<SecretTemplateField>, // Empty for pure generators
+}
+
+// An input the user fills in for a composed template
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct SecretTemplateField {
+    pub key: String,
+    pub label: String,
+    pub required: bool,
+    pub sensitive: bool, // Mask the input, e.g. a password
+}
@@ -0,0 +1,57 @@
+// src/models/transfer_model.rs - One-shot vault or box transfer to a device on the same network
+
+use crate::models::EncryptedData;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use ts_rs::TS;
+
+pub const TRANSFER_SERVICE_TYPE: &str = "_zap-transfer._tcp.local.";
+pub const TRANSFER_LINK_PREFIX: &str = "zap-transfer://";
+pub const TRANSFER_PROTOCOL_VERSION: u32 = 1;
+pub const TRANSFER_TTL_MINUTES: i64 = 10;
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TS)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TransferScope {
+    Vault,
+    Box { box_id: String },
+}
+
+// Shown on the sending device. The code is typed on the receiving one (found over mDNS),
+// or the link / QR code is used instead - it also pins the sender's TLS certificate.
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct LanTransferOffer {
+    pub job_id: String, // Completes once the receiver has imported, cancel_job stops waiting
+    pub code: String,   // `xxxx-xxxxx-xxxxx`
+    pub link: Option<String>, // None when no LAN address was found, the code still works
+    pub qr_svg: Option<String>,
+    #[serde(with = "chrono::serde::ts_seconds")]
+    #[ts(type = "number")]
+    pub expires_at: DateTime<Utc>,
+}
+
+// Result of a sending job
+#[derive(Debug, Clone, Serialize, Deserialize, TS)]
+pub struct LanTransferReceipt {
+    pub boxes_imported: usize,
+    pub secrets_imported: usize,
+    pub errors: Vec<String>, // Boxes or secrets the receiver couldn't import
+}
+
+// Frames on the TLS connection, receiver speaks first. Both sealed values use a key derived
+// from the code's secret part, salted with the certificate fingerprint.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TransferMessage {
+    Hello {
+        version: u32,
+        proof: EncryptedData, // Proves the receiver knows the code before anything is sent
+    },
+    Payload {
+        data: EncryptedData, // Vault export JSON
+    },
+    Received(LanTransferReceipt),
+    Rejected {
+        reason: String,
+    },
+}
@@ -0,0 +1,11 @@
+// src/models/undo_model.rs
+
+use serde::Serialize;
+use ts_rs::TS;
+
+#[derive(Debug, Clone, Serialize, TS)]
+pub struct UndoResult {
+    pub description: String,  // The operation that was undone
+    pub restored: usize,      // Boxes or secrets put back
+    pub skipped: Vec<String>, // Names that couldn't come back, e.g. taken by a newer item
+}
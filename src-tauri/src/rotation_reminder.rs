@@ -0,0 +1,53 @@
+// src/rotation_reminder.rs - Background check for secrets due for rotation
+
+use crate::models::Secret;
+use crate::states::AppState;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(5 * 60);
+
+/// Periodically notify about secrets whose rotation interval has passed (only while unlocked)
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            let app_state = app.state::<Arc<AppState>>().inner().clone();
+            if app_state.is_locked() {
+                continue;
+            }
+
+            match app_state.take_rotation_reminders().await {
+                Ok(due) if !due.is_empty() => notify(&app, &due),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Rotation check failed: {}", e),
+            }
+        }
+    });
+}
+
+fn notify(app: &AppHandle, due: &[Secret]) {
+    let body = match due {
+        [secret] => format!("'{}' is due for rotation", secret.name),
+        [first, rest @ ..] => format!(
+            "'{}' and {} other secret(s) are due for rotation",
+            first.name,
+            rest.len()
+        ),
+        [] => return,
+    };
+
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title("Zap - rotation reminder")
+        .body(body)
+        .show()
+    {
+        tracing::warn!("Failed to show rotation notification: {}", e);
+    }
+}
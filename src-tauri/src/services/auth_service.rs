@@ -1,13 +1,23 @@
 // src/services/auth_service.rs
 
-use crate::models::{AuthConfig, SessionState, ZapError}; // Use unified error
-use crate::services::CryptoService;
+use crate::models::{
+    AuthConfig, HardwareKeyConfig, HardwareKeyStatus, RecoveryConfig, SessionState, ZapError,
+}; // Use unified error
+use crate::services::{CryptoService, HardwareKeyService};
 use std::sync::Mutex;
+use std::time::Duration;
+
+// Target time for one key derivation on this machine
+const KDF_TARGET: Duration = Duration::from_millis(500);
+
+// The YubiKey carries most of the strength, so its PIN can be shorter than a password
+const MIN_PIN_LENGTH: usize = 4;
 
 pub struct AuthService {
     session: Mutex<SessionState>,
     config: Mutex<Option<AuthConfig>>,
     crypto: CryptoService,
+    hardware: HardwareKeyService,
 }
 
 impl AuthService {
@@ -16,6 +26,7 @@ impl AuthService {
             session: Mutex::new(SessionState::new()),
             config: Mutex::new(None),
             crypto: CryptoService::new(),
+            hardware: HardwareKeyService::new(),
         }
     }
 
@@ -52,12 +63,7 @@ impl AuthService {
 
         let timeout_minutes = config.session_timeout_minutes as u32;
 
-        // DEBUG: Log the timeout being used
-        println!(
-            "Unlocking with timeout: {} minutes ({} seconds)",
-            timeout_minutes,
-            timeout_minutes * 60
-        );
+        tracing::debug!(timeout_minutes, "Unlocking vault");
 
         if config.is_first_time_setup() {
             // First-time setup
@@ -67,10 +73,15 @@ impl AuthService {
                 ));
             }
 
-            let password_hash = self.crypto.hash_password(password)?;
+            // Tune Argon2 to this machine before the first hash is stored
+            config.kdf_params = self.crypto.calibrate_kdf(KDF_TARGET);
+
+            let password_hash = self.crypto.hash_password(password, &config.kdf_params)?;
             config.master_password_hash = Some(password_hash);
 
-            let master_key = self.crypto.derive_key(password, &config.salt)?;
+            let master_key = self
+                .crypto
+                .derive_key(password, &config.salt, &config.kdf_params)?;
 
             let mut session = self.session.lock().unwrap();
             session.unlock(master_key, timeout_minutes);
@@ -78,13 +89,16 @@ impl AuthService {
             Ok(true) // First-time setup completed
         } else {
             // Existing user login
+            let secret = self.unlock_secret(config, password)?;
             let stored_hash = config.master_password_hash.as_ref().unwrap();
 
-            if !self.crypto.verify_password(password, stored_hash)? {
+            if !self.crypto.verify_password(&secret, stored_hash)? {
                 return Err(ZapError::IncorrectPassword);
             }
 
-            let master_key = self.crypto.derive_key(password, &config.salt)?;
+            let master_key = self
+                .crypto
+                .derive_key(&secret, &config.salt, &config.kdf_params)?;
 
             let mut session = self.session.lock().unwrap();
             session.unlock(master_key, timeout_minutes);
@@ -93,6 +107,151 @@ impl AuthService {
         }
     }
 
+    // Unlock with an already-derived master key (remembered unlock)
+    pub fn unlock_with_master_key(&self, master_key: [u8; 32]) -> Result<(), ZapError> {
+        let config_guard = self.config.lock().unwrap();
+        let config = config_guard
+            .as_ref()
+            .ok_or(ZapError::AuthError("Auth not initialized".to_string()))?;
+
+        if config.is_first_time_setup() {
+            return Err(ZapError::AuthError(
+                "Master password has not been set up".to_string(),
+            ));
+        }
+
+        let mut session = self.session.lock().unwrap();
+        session.unlock(master_key, config.session_timeout_minutes as u32);
+        Ok(())
+    }
+
+    // Replace the master password (recovery flow). Returns the new master key.
+    pub fn reset_master_password(&self, new_password: &str) -> Result<[u8; 32], ZapError> {
+        if new_password.len() < 8 {
+            return Err(ZapError::AuthError(
+                "Password must be at least 8 characters".to_string(),
+            ));
+        }
+
+        let mut config_guard = self.config.lock().unwrap();
+        let config = config_guard
+            .as_mut()
+            .ok_or(ZapError::AuthError("Auth not initialized".to_string()))?;
+
+        // Recalibrate - the vault may have moved to a different machine
+        let kdf_params = self.crypto.calibrate_kdf(KDF_TARGET);
+        let salt = self.crypto.generate_salt();
+        let password_hash = self.crypto.hash_password(new_password, &kdf_params)?;
+        let master_key = self.crypto.derive_key(new_password, &salt, &kdf_params)?;
+
+        config.kdf_params = kdf_params;
+        config.salt = salt;
+        config.master_password_hash = Some(password_hash);
+        config.hardware_key = None; // Back to a plain password
+
+        let mut session = self.session.lock().unwrap();
+        session.unlock(master_key, config.session_timeout_minutes as u32);
+
+        Ok(master_key)
+    }
+
+    // Check the password (or PIN and YubiKey) without unlocking
+    pub fn check_password(&self, password: &str) -> Result<bool, ZapError> {
+        let config_guard = self.config.lock().unwrap();
+        let config = config_guard
+            .as_ref()
+            .ok_or(ZapError::AuthError("Auth not initialized".to_string()))?;
+
+        match &config.master_password_hash {
+            Some(stored_hash) => {
+                let secret = self.unlock_secret(config, password)?;
+                self.crypto.verify_password(&secret, stored_hash)
+            }
+            None => Ok(true),
+        }
+    }
+
+    // Replace the master password with a PIN plus a YubiKey slot. Returns the new master key.
+    pub fn enable_hardware_key(&self, pin: &str, slot: u8) -> Result<[u8; 32], ZapError> {
+        if pin.chars().count() < MIN_PIN_LENGTH {
+            return Err(ZapError::AuthError(format!(
+                "PIN must be at least {} characters",
+                MIN_PIN_LENGTH
+            )));
+        }
+
+        let mut config_guard = self.config.lock().unwrap();
+        let config = config_guard
+            .as_mut()
+            .ok_or(ZapError::AuthError("Auth not initialized".to_string()))?;
+
+        // A fresh challenge per enrollment, so an old response is useless
+        let challenge = self.crypto.generate_salt();
+        let response = self.hardware.challenge_response(slot, &challenge)?;
+        let secret = hardware_secret(pin, &response);
+
+        let salt = self.crypto.generate_salt();
+        let password_hash = self.crypto.hash_password(&secret, &config.kdf_params)?;
+        let master_key = self.crypto.derive_key(&secret, &salt, &config.kdf_params)?;
+
+        config.salt = salt;
+        config.master_password_hash = Some(password_hash);
+        config.hardware_key = Some(HardwareKeyConfig {
+            slot,
+            challenge,
+            created_at: chrono::Utc::now(),
+        });
+
+        let mut session = self.session.lock().unwrap();
+        session.unlock(master_key, config.session_timeout_minutes as u32);
+
+        Ok(master_key)
+    }
+
+    pub fn hardware_key_enabled(&self) -> bool {
+        self.config
+            .lock()
+            .unwrap()
+            .as_ref()
+            .is_some_and(|config| config.hardware_key.is_some())
+    }
+
+    pub fn hardware_key_status(&self) -> HardwareKeyStatus {
+        let slot = self
+            .config
+            .lock()
+            .unwrap()
+            .as_ref()
+            .and_then(|config| config.hardware_key.as_ref().map(|hardware| hardware.slot));
+
+        HardwareKeyStatus {
+            enabled: slot.is_some(),
+            slot,
+            device_present: self.hardware.is_present(),
+        }
+    }
+
+    // What the hash and master key come from: the password, or the PIN plus the key's response
+    fn unlock_secret(&self, config: &AuthConfig, password: &str) -> Result<String, ZapError> {
+        match &config.hardware_key {
+            Some(hardware_key) => {
+                let response = self
+                    .hardware
+                    .challenge_response(hardware_key.slot, &hardware_key.challenge)?;
+                Ok(hardware_secret(password, &response))
+            }
+            None => Ok(password.to_string()),
+        }
+    }
+
+    // Store (or remove) the recovery-key wrapped master key
+    pub fn set_recovery(&self, recovery: Option<RecoveryConfig>) {
+        let mut config_guard = self.config.lock().unwrap();
+        if let Some(config) = config_guard.as_mut() {
+            config.recovery = recovery;
+        }
+    }
+
     // Lock the session
     pub fn lock(&self) {
         let mut session = self.session.lock().unwrap();
@@ -111,6 +270,43 @@ impl AuthService {
         session.time_left_seconds
     }
 
+    // Reset the idle countdown on user activity (bounded by the absolute cap)
+    pub fn touch_session(&self) {
+        let timeout_minutes = match self.config.lock().unwrap().as_ref() {
+            Some(config) => config.session_timeout_minutes as u32,
+            None => return,
+        };
+
+        let mut session = self.session.lock().unwrap();
+        session.reset_timer(timeout_minutes);
+    }
+
+    // Pause the idle countdown for a long-running operation
+    pub fn hold_session(&self) {
+        let mut session = self.session.lock().unwrap();
+        session.held_operations += 1;
+    }
+
+    // End a hold; the last one gives the session a fresh idle timeout
+    pub fn release_session(&self) {
+        let timeout_minutes = match self.config.lock().unwrap().as_ref() {
+            Some(config) => config.session_timeout_minutes as u32,
+            None => return,
+        };
+
+        let mut session = self.session.lock().unwrap();
+        session.held_operations = session.held_operations.saturating_sub(1);
+        if session.held_operations == 0 {
+            session.reset_timer(timeout_minutes);
+        }
+    }
+
+    // Set the hard session cap measured from unlock (0 disables it)
+    pub fn set_max_session_minutes(&self, minutes: u32) {
+        let mut session = self.session.lock().unwrap();
+        session.max_session_seconds = minutes * 60;
+    }
+
     // Tick session timer (called every second)
     pub fn tick_session(&self) {
         let mut session = self.session.lock().unwrap();
@@ -157,3 +353,8 @@ impl Default for AuthService {
         Self::new()
     }
 }
+
+// The PIN and the key's response together stand in for the password
+fn hardware_secret(pin: &str, response: &[u8]) -> String {
+    format!("{}:{}", pin, hex::encode(response))
+}
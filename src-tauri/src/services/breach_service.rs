@@ -0,0 +1,65 @@
+// src/services/breach_service.rs - Have I Been Pwned lookups for password values (Settings:
+// breach_check). Uses the k-anonymity range API: only the first 5 hex characters of the
+// value's SHA-1 are sent, and the matching suffix is searched for locally.
+
+use crate::models::ZapError;
+use sha1::{Digest, Sha1};
+use std::time::Duration;
+
+const RANGE_API_URL: &str = "https://api.pwnedpasswords.com/range/";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+pub struct BreachService {
+    client: reqwest::Client,
+}
+
+impl BreachService {
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("Zap/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default();
+        Self { client }
+    }
+
+    /// How often the value appears in known breaches, 0 when it was never seen
+    pub async fn pwned_count(&self, value: &str) -> Result<u64, ZapError> {
+        let hash = hex::encode_upper(Sha1::digest(value.as_bytes()));
+        let (prefix, suffix) = hash.split_at(5);
+
+        // Padding makes every response a similar size, so its length doesn't hint at the prefix
+        let response = self
+            .client
+            .get(format!("{}{}", RANGE_API_URL, prefix))
+            .header("Add-Padding", "true")
+            .send()
+            .await
+            .map_err(|e| ZapError::PlatformError(format!("Breach check request failed: {}", e)))?;
+
+        if !response.status().is_success() {
+            return Err(ZapError::PlatformError(format!(
+                "Breach check returned {}",
+                response.status()
+            )));
+        }
+        let body = response
+            .text()
+            .await
+            .map_err(|e| ZapError::PlatformError(format!("Breach check request failed: {}", e)))?;
+
+        // One "SUFFIX:COUNT" per line, padding entries have a count of 0
+        Ok(body
+            .lines()
+            .filter_map(|line| line.trim().split_once(':'))
+            .find(|(candidate, _)| candidate.eq_ignore_ascii_case(suffix))
+            .and_then(|(_, count)| count.trim().parse().ok())
+            .unwrap_or(0))
+    }
+}
+
+impl Default for BreachService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
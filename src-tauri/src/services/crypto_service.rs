@@ -1,13 +1,30 @@
 // src/services/crypto_service.rs
-use crate::models::{EncryptedData, ZapError};
+use crate::models::{Box, CipherAlgorithm, EncryptedData, KdfParams, Secret, ZapError};
 use aes_gcm::{
-    aead::{Aead, AeadCore, KeyInit, OsRng},
+    aead::{Aead, AeadCore, KeyInit, OsRng, Payload},
     Aes256Gcm, Key, Nonce,
 };
+use age::armor::{ArmoredReader, ArmoredWriter, Format};
 use argon2::{
     password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
-    Argon2,
+    Algorithm, Argon2, Params, Version,
 };
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use std::io::{Read, Write};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::RwLock;
+use std::time::{Duration, Instant};
+
+const TAG_LENGTH: usize = 16;
+
+// Calibration ceilings so a fast machine doesn't pick something a laptop on battery can't open
+const MAX_KDF_MEMORY_KIB: u32 = 1024 * 1024;
+const MAX_KDF_ITERATIONS: u32 = 10;
+
+// Algorithm for new encryptions (Settings), decryption follows each record's own field
+static DEFAULT_ALGORITHM: RwLock<CipherAlgorithm> = RwLock::new(CipherAlgorithm::Aes256Gcm);
+static REQUIRE_BOUND_SECRETS: AtomicBool = AtomicBool::new(false);
 
 pub struct CryptoService;
 
@@ -16,52 +33,249 @@ impl CryptoService {
         Self
     }
 
-    // Encrypt a string with AES-256-GCM
+    pub fn set_default_algorithm(algorithm: CipherAlgorithm) {
+        *DEFAULT_ALGORITHM.write().unwrap() = algorithm;
+    }
+
+    pub fn default_algorithm() -> CipherAlgorithm {
+        *DEFAULT_ALGORITHM.read().unwrap()
+    }
+
+    /// Once every secret is bound, unbound ciphertexts are rejected (no downgrade by editing).
+    /// Set from the vault's stored marker whenever settings are applied.
+    pub fn set_require_bound_secrets(required: bool) {
+        REQUIRE_BOUND_SECRETS.store(required, Ordering::SeqCst);
+    }
+
+    // Encrypt a string with the configured default algorithm
     pub fn encrypt(&self, text: &str, key: &[u8; 32]) -> Result<EncryptedData, ZapError> {
-        let cipher_key = Key::<Aes256Gcm>::from_slice(key);
-        let cipher = Aes256Gcm::new(cipher_key);
-        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        self.encrypt_with(text, key, Self::default_algorithm())
+    }
+
+    // Encrypt with a specific algorithm (CLI session files must stay AES-256-GCM)
+    pub fn encrypt_with(
+        &self,
+        text: &str,
+        key: &[u8; 32],
+        algorithm: CipherAlgorithm,
+    ) -> Result<EncryptedData, ZapError> {
+        self.seal(text.as_bytes(), key, algorithm, &[])
+    }
+
+    // Decrypt back to string
+    pub fn decrypt(&self, data: &EncryptedData, key: &[u8; 32]) -> Result<String, ZapError> {
+        self.open(data, key, &[])
+    }
+
+    // Encrypt a value for a secret, bound (AAD) to its secret id and box id.
+    // The value is decoded per the secret's value_encoding first.
+    pub fn encrypt_secret(
+        &self,
+        secret: &Secret,
+        value: &str,
+        key: &[u8; 32],
+    ) -> Result<EncryptedData, ZapError> {
+        let plaintext = secret.value_encoding.decode(value)?;
+        let aad = Self::secret_aad(&secret.id, &secret.box_id);
+        let mut data = self.seal(&plaintext, key, Self::default_algorithm(), &aad)?;
+        data.bound = true;
+        Ok(data)
+    }
 
-        let ciphertext = cipher
-            .encrypt(&nonce, text.as_bytes())
-            .map_err(|e| ZapError::CryptoError(format!("Encryption failed: {}", e)))?;
+    // Decrypt a secret value, failing if its ciphertext was moved to another record
+    pub fn decrypt_secret(&self, secret: &Secret, key: &[u8; 32]) -> Result<String, ZapError> {
+        let data = &secret.encrypted_value;
+        let plaintext = if data.bound {
+            self.open_bytes(data, key, &Self::secret_aad(&secret.id, &secret.box_id))?
+        } else if REQUIRE_BOUND_SECRETS.load(Ordering::SeqCst) {
+            return Err(ZapError::CryptoError(format!(
+                "Secret '{}' is not bound to its record",
+                secret.name
+            )));
+        } else {
+            self.open_bytes(data, key, &[])?
+        };
+        secret.value_encoding.encode(plaintext)
+    }
 
-        // AES-GCM appends the 16-byte tag to the ciphertext
-        if ciphertext.len() < 16 {
+    // Encrypt a secret's value for one environment, bound to the secret and the environment name
+    pub fn encrypt_secret_variant(
+        &self,
+        secret: &Secret,
+        environment: &str,
+        value: &str,
+        key: &[u8; 32],
+    ) -> Result<EncryptedData, ZapError> {
+        let plaintext = secret.value_encoding.decode(value)?;
+        let aad = Self::variant_aad(&secret.id, &secret.box_id, environment);
+        let mut data = self.seal(&plaintext, key, Self::default_algorithm(), &aad)?;
+        data.bound = true;
+        Ok(data)
+    }
+
+    // Decrypt the value for an environment: its variant if the secret has one, else the base value
+    pub fn decrypt_secret_for(
+        &self,
+        secret: &Secret,
+        environment: Option<&str>,
+        key: &[u8; 32],
+    ) -> Result<String, ZapError> {
+        let variant = environment.and_then(|env| secret.variants.get(env).map(|data| (env, data)));
+        match variant {
+            Some((environment, data)) => {
+                let aad = Self::variant_aad(&secret.id, &secret.box_id, environment);
+                secret.value_encoding.encode(self.open_bytes(data, key, &aad)?)
+            }
+            None => self.decrypt_secret(secret, key),
+        }
+    }
+
+    // Encrypt bound (AAD) to a caller-defined context, for self-contained blobs
+    pub fn encrypt_bound(
+        &self,
+        text: &str,
+        key: &[u8; 32],
+        context: &[u8],
+    ) -> Result<EncryptedData, ZapError> {
+        let mut data = self.seal(text.as_bytes(), key, Self::default_algorithm(), context)?;
+        data.bound = true;
+        Ok(data)
+    }
+
+    pub fn decrypt_bound(
+        &self,
+        data: &EncryptedData,
+        key: &[u8; 32],
+        context: &[u8],
+    ) -> Result<String, ZapError> {
+        self.open(data, key, context)
+    }
+
+    fn secret_aad(secret_id: &str, box_id: &str) -> Vec<u8> {
+        format!("zap:secret:{}:{}", secret_id, box_id).into_bytes()
+    }
+
+    fn variant_aad(secret_id: &str, box_id: &str, environment: &str) -> Vec<u8> {
+        format!("zap:secret:{}:{}:{}", secret_id, box_id, environment).into_bytes()
+    }
+
+    fn seal(
+        &self,
+        plaintext: &[u8],
+        key: &[u8; 32],
+        algorithm: CipherAlgorithm,
+        aad: &[u8],
+    ) -> Result<EncryptedData, ZapError> {
+        let payload = Payload {
+            msg: plaintext,
+            aad,
+        };
+        let (nonce, ciphertext) = match algorithm {
+            CipherAlgorithm::Aes256Gcm => {
+                let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+                let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+                (nonce.to_vec(), cipher.encrypt(&nonce, payload))
+            }
+            CipherAlgorithm::XChaCha20Poly1305 => {
+                let cipher = XChaCha20Poly1305::new(chacha20poly1305::Key::from_slice(key));
+                let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+                (nonce.to_vec(), cipher.encrypt(&nonce, payload))
+            }
+        };
+        let ciphertext =
+            ciphertext.map_err(|e| ZapError::CryptoError(format!("Encryption failed: {}", e)))?;
+
+        // Both AEADs append the 16-byte tag to the ciphertext
+        if ciphertext.len() < TAG_LENGTH {
             return Err(ZapError::CryptoError(
                 "Invalid ciphertext length".to_string(),
             ));
         }
 
-        let (cipher_bytes, tag_bytes) = ciphertext.split_at(ciphertext.len() - 16);
+        let (cipher_bytes, tag_bytes) = ciphertext.split_at(ciphertext.len() - TAG_LENGTH);
 
         Ok(EncryptedData::new(
             cipher_bytes.to_vec(),
-            nonce.to_vec(),
+            nonce,
             tag_bytes.to_vec(),
+            algorithm,
         ))
     }
 
-    // Decrypt back to string
-    pub fn decrypt(&self, data: &EncryptedData, key: &[u8; 32]) -> Result<String, ZapError> {
+    fn open(&self, data: &EncryptedData, key: &[u8; 32], aad: &[u8]) -> Result<String, ZapError> {
+        String::from_utf8(self.open_bytes(data, key, aad)?)
+            .map_err(|e| ZapError::CryptoError(format!("Invalid UTF-8: {}", e)))
+    }
+
+    fn open_bytes(
+        &self,
+        data: &EncryptedData,
+        key: &[u8; 32],
+        aad: &[u8],
+    ) -> Result<Vec<u8>, ZapError> {
         if !data.is_valid() {
             return Err(ZapError::CryptoError("Invalid encrypted data".to_string()));
         }
 
-        let cipher_key = Key::<Aes256Gcm>::from_slice(key);
-        let cipher = Aes256Gcm::new(cipher_key);
-        let nonce = Nonce::from_slice(&data.nonce);
-
         // Reconstruct ciphertext with appended tag
         let mut full_ciphertext = data.cipher.clone();
         full_ciphertext.extend_from_slice(&data.tag);
+        let payload = Payload {
+            msg: &full_ciphertext,
+            aad,
+        };
 
-        let decrypted_bytes = cipher
-            .decrypt(nonce, full_ciphertext.as_ref())
-            .map_err(|e| ZapError::CryptoError(format!("Decryption failed: {}", e)))?;
+        let decrypted_bytes = match data.algorithm {
+            CipherAlgorithm::Aes256Gcm => {
+                let cipher = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key));
+                cipher.decrypt(Nonce::from_slice(&data.nonce), payload)
+            }
+            CipherAlgorithm::XChaCha20Poly1305 => {
+                let cipher = XChaCha20Poly1305::new(chacha20poly1305::Key::from_slice(key));
+                cipher.decrypt(XNonce::from_slice(&data.nonce), payload)
+            }
+        }
+        .map_err(|e| ZapError::CryptoError(format!("Decryption failed: {}", e)))?;
 
-        String::from_utf8(decrypted_bytes)
-            .map_err(|e| ZapError::CryptoError(format!("Invalid UTF-8: {}", e)))
+        Ok(decrypted_bytes)
+    }
+
+    // PER-BOX DATA KEYS
+
+    // Random 32-byte key that encrypts one box's secrets
+    pub fn generate_data_key(&self) -> [u8; 32] {
+        use rand::RngCore;
+        let mut key = [0u8; 32];
+        rand::rng().fill_bytes(&mut key);
+        key
+    }
+
+    pub fn wrap_key(
+        &self,
+        key: &[u8; 32],
+        wrapping_key: &[u8; 32],
+    ) -> Result<EncryptedData, ZapError> {
+        self.encrypt(&hex::encode(key), wrapping_key)
+    }
+
+    pub fn unwrap_key(
+        &self,
+        wrapped: &EncryptedData,
+        wrapping_key: &[u8; 32],
+    ) -> Result<[u8; 32], ZapError> {
+        let key_bytes = hex::decode(self.decrypt(wrapped, wrapping_key)?)
+            .map_err(|_| ZapError::CryptoError("Invalid wrapped key".to_string()))?;
+        key_bytes
+            .try_into()
+            .map_err(|_| ZapError::CryptoError("Invalid wrapped key length".to_string()))
+    }
+
+    // Key for a box's secrets - boxes not yet migrated still use the master key directly
+    pub fn box_key(&self, box_item: &Box, master_key: &[u8; 32]) -> Result<[u8; 32], ZapError> {
+        match &box_item.wrapped_key {
+            Some(wrapped) => self.unwrap_key(wrapped, master_key),
+            None => Ok(*master_key),
+        }
     }
 
     // Encrypt multiple values at once - useful for dev sessions
@@ -96,10 +310,76 @@ impl CryptoService {
         Ok(results)
     }
 
-    // Hash password with Argon2 for secure storage
-    pub fn hash_password(&self, password: &str) -> Result<String, ZapError> {
+    // AGE RECIPIENTS (sharing exports without a passphrase)
+
+    // Encrypt to an age public key (age1...), ASCII-armored so it can be pasted into chat
+    pub fn encrypt_for_recipient(
+        &self,
+        plaintext: &[u8],
+        recipient: &str,
+    ) -> Result<String, ZapError> {
+        let recipient = Self::parse_age_recipient(recipient)?;
+        let recipients: Vec<std::boxed::Box<dyn age::Recipient + Send>> =
+            vec![std::boxed::Box::new(recipient)];
+        let encryptor = age::Encryptor::with_recipients(recipients)
+            .ok_or_else(|| ZapError::CryptoError("No recipient given".to_string()))?;
+
+        let mut armored = Vec::new();
+        let armor = ArmoredWriter::wrap_output(&mut armored, Format::AsciiArmor)?;
+        let mut writer = encryptor
+            .wrap_output(armor)
+            .map_err(|e| ZapError::CryptoError(e.to_string()))?;
+        writer.write_all(plaintext)?;
+        writer.finish().and_then(|armor| armor.finish())?;
+
+        Ok(String::from_utf8(armored)?)
+    }
+
+    // Decrypt an armored age file with the matching private key (AGE-SECRET-KEY-1...)
+    pub fn decrypt_with_identity(
+        &self,
+        armored: &str,
+        identity: &str,
+    ) -> Result<Vec<u8>, ZapError> {
+        let identity: age::x25519::Identity = identity.trim().parse().map_err(|_| {
+            ZapError::CryptoError("Not an age private key (AGE-SECRET-KEY-1...)".to_string())
+        })?;
+
+        let decryptor = match age::Decryptor::new(ArmoredReader::new(armored.trim().as_bytes()))
+            .map_err(|e| ZapError::CryptoError(e.to_string()))?
+        {
+            age::Decryptor::Recipients(decryptor) => decryptor,
+            _ => {
+                return Err(ZapError::CryptoError(
+                    "Passphrase-encrypted files aren't supported".to_string(),
+                ))
+            }
+        };
+
+        let mut reader = decryptor
+            .decrypt(std::iter::once(&identity as &dyn age::Identity))
+            .map_err(|e| ZapError::CryptoError(e.to_string()))?;
+        let mut plaintext = Vec::new();
+        reader.read_to_end(&mut plaintext)?;
+        Ok(plaintext)
+    }
+
+    fn parse_age_recipient(recipient: &str) -> Result<age::x25519::Recipient, ZapError> {
+        let recipient = recipient.trim();
+        if recipient.starts_with("-----BEGIN PGP") {
+            return Err(ZapError::ValidationError(
+                "OpenPGP keys aren't supported, ask for an age public key (age1...)".to_string(),
+            ));
+        }
+        recipient.parse().map_err(|_| {
+            ZapError::ValidationError("Not an age public key (age1...)".to_string())
+        })
+    }
+
+    // Hash password with Argon2 for secure storage (parameters are embedded in the hash)
+    pub fn hash_password(&self, password: &str, params: &KdfParams) -> Result<String, ZapError> {
         let salt = SaltString::generate(&mut OsRng);
-        let hasher = Argon2::default();
+        let hasher = Self::argon2(params)?;
 
         let hash = hasher
             .hash_password(password.as_bytes(), &salt)
@@ -122,14 +402,19 @@ impl CryptoService {
     }
 
     // Derive 32-byte encryption key from master password using Argon2
-    pub fn derive_key(&self, password: &str, salt: &[u8]) -> Result<[u8; 32], ZapError> {
+    pub fn derive_key(
+        &self,
+        password: &str,
+        salt: &[u8],
+        params: &KdfParams,
+    ) -> Result<[u8; 32], ZapError> {
         if salt.len() < 16 {
             return Err(ZapError::CryptoError(
                 "Salt must be at least 16 bytes".to_string(),
             ));
         }
 
-        let key_derivation = Argon2::default();
+        let key_derivation = Self::argon2(params)?;
         let mut derived_key = [0u8; 32];
 
         key_derivation
@@ -139,6 +424,114 @@ impl CryptoService {
         Ok(derived_key)
     }
 
+    // Benchmark this machine and pick Argon2 parameters so one derivation takes about `target`.
+    // Memory grows first (GPU resistance), then passes; never weaker than the defaults.
+    pub fn calibrate_kdf(&self, target: Duration) -> KdfParams {
+        let mut params = KdfParams::default();
+        let salt = self.generate_salt();
+
+        loop {
+            let started = Instant::now();
+            if self.derive_key("zap-calibration", &salt, &params).is_err() {
+                return KdfParams::default();
+            }
+            let elapsed = started.elapsed();
+
+            if elapsed >= target {
+                break;
+            }
+            if elapsed * 2 <= target && params.memory_kib * 2 <= MAX_KDF_MEMORY_KIB {
+                params.memory_kib *= 2;
+            } else if params.iterations < MAX_KDF_ITERATIONS {
+                params.iterations += 1;
+            } else {
+                break;
+            }
+        }
+
+        params
+    }
+
+    fn argon2(params: &KdfParams) -> Result<Argon2<'static>, ZapError> {
+        let params = Params::new(params.memory_kib, params.iterations, params.parallelism, None)
+            .map_err(|e| ZapError::CryptoError(format!("Invalid Argon2 parameters: {}", e)))?;
+        Ok(Argon2::new(Algorithm::Argon2id, Version::V0x13, params))
+    }
+
+    // Generate a recovery key: 32 random bytes as 8 dash-separated hex groups
+    pub fn generate_recovery_key(&self) -> ([u8; 32], String) {
+        use rand::RngCore;
+        let mut key = [0u8; 32];
+        rand::rng().fill_bytes(&mut key);
+
+        let encoded = hex::encode_upper(key);
+        let groups: Vec<&str> = (0..encoded.len())
+            .step_by(8)
+            .map(|i| &encoded[i..i + 8])
+            .collect();
+
+        (key, groups.join("-"))
+    }
+
+    // Parse a recovery key typed by the user (dashes, spaces and case are ignored)
+    pub fn parse_recovery_key(&self, recovery_key: &str) -> Result<[u8; 32], ZapError> {
+        let cleaned: String = recovery_key
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != '-')
+            .collect();
+
+        let key_bytes = hex::decode(cleaned.to_lowercase())
+            .map_err(|_| ZapError::AuthError("Invalid recovery key format".to_string()))?;
+        if key_bytes.len() != 32 {
+            return Err(ZapError::AuthError(
+                "Invalid recovery key format".to_string(),
+            ));
+        }
+
+        let mut key = [0u8; 32];
+        key.copy_from_slice(&key_bytes);
+        Ok(key)
+    }
+
+    // EXPORT SIGNING (ED25519)
+
+    // Hex public key for a signing seed, shared with teammates who want to trust its exports
+    pub fn signing_public_key(&self, seed: &[u8; 32]) -> String {
+        hex::encode(SigningKey::from_bytes(seed).verifying_key().to_bytes())
+    }
+
+    pub fn sign(&self, message: &[u8], seed: &[u8; 32]) -> String {
+        hex::encode(SigningKey::from_bytes(seed).sign(message).to_bytes())
+    }
+
+    // False when the signature doesn't match, an error only when it isn't well-formed
+    pub fn verify_signature(
+        &self,
+        message: &[u8],
+        public_key: &str,
+        signature: &str,
+    ) -> Result<bool, ZapError> {
+        let verifying_key = VerifyingKey::from_bytes(&Self::parse_signing_public_key(public_key)?)
+            .map_err(|_| ZapError::CryptoError("Invalid signing public key".to_string()))?;
+        let signature: [u8; 64] = hex::decode(signature)
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(ZapError::CryptoError("Malformed signature".to_string()))?;
+
+        Ok(verifying_key
+            .verify(message, &Signature::from_bytes(&signature))
+            .is_ok())
+    }
+
+    pub fn parse_signing_public_key(public_key: &str) -> Result<[u8; 32], ZapError> {
+        hex::decode(public_key.trim())
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(ZapError::ValidationError(
+                "Signing keys are 64 hex characters".to_string(),
+            ))
+    }
+
     // Generate random salt for new users
     pub fn generate_salt(&self) -> [u8; 32] {
         use rand::RngCore;
@@ -1,7 +1,10 @@
 // src/services/dev_service.rs
-use crate::models::{Box, DevSession, EncryptedData, Secret, ZapError};
+use crate::models::{
+    Box, CipherAlgorithm, DevSession, EncryptedData, Secret, SecretSelection, ZapError,
+};
 use crate::services::CryptoService;
-use std::collections::HashMap;
+use crate::utils::input_validation::name_key;
+use std::collections::{HashMap, HashSet};
 
 pub struct DevService {
     crypto: CryptoService,
@@ -17,13 +20,16 @@ impl DevService {
     // === Session Creation ===
 
     /// Create a new dev session
-    pub fn create_session_from_box(
+    pub fn create_session_from_box<F>(
         &self,
         session_name: String,
         box_item: &Box,
         box_secrets: &[Secret],
-        master_key: &[u8; 32],
-    ) -> Result<DevSession, ZapError> {
+        decrypt: F,
+    ) -> Result<DevSession, ZapError>
+    where
+        F: Fn(&Secret) -> Result<String, ZapError>,
+    {
         // Make sure session name is valid format
         self.validate_session_name(&session_name)?;
 
@@ -35,7 +41,7 @@ impl DevService {
 
         // Re-encrypt all secrets with session key 
         let encrypted_secrets =
-            self.prepare_box_for_session(box_secrets, master_key, &session_key)?;
+            self.prepare_box_for_session(box_secrets, decrypt, &session_key)?;
 
         // Create the session object
         let session = DevSession::new(
@@ -49,6 +55,56 @@ impl DevService {
         Ok(session)
     }
 
+    /// Keep the secrets a selection asks for. Names match like secret names do (ignoring
+    /// case and spacing), and naming a secret the box doesn't have is an error.
+    pub fn select_secrets(
+        &self,
+        box_secrets: Vec<Secret>,
+        selection: &SecretSelection,
+    ) -> Result<Vec<Secret>, ZapError> {
+        if selection.is_everything() {
+            return Ok(box_secrets);
+        }
+
+        let box_names: HashSet<String> = box_secrets.iter().map(|s| name_key(&s.name)).collect();
+        let keys = |names: &[String]| -> Result<HashSet<String>, ZapError> {
+            names
+                .iter()
+                .map(|name| {
+                    let key = name_key(name);
+                    if box_names.contains(&key) {
+                        Ok(key)
+                    } else {
+                        Err(ZapError::ValidationError(format!(
+                            "Box has no secret named '{}'",
+                            name
+                        )))
+                    }
+                })
+                .collect()
+        };
+        let include = selection.include.as_deref().map(keys).transpose()?;
+        let exclude = keys(&selection.exclude)?;
+
+        let selected: Vec<Secret> = box_secrets
+            .into_iter()
+            .filter(|secret| {
+                let key = name_key(&secret.name);
+                include
+                    .as_ref()
+                    .is_none_or(|include| include.contains(&key))
+                    && !exclude.contains(&key)
+            })
+            .collect();
+
+        if selected.is_empty() {
+            return Err(ZapError::InvalidDevBox(
+                "No secrets selected for the session".to_string(),
+            ));
+        }
+        Ok(selected)
+    }
+
     // Validation
 
     /// Validate session name format (lowercase, numbers, hyphens only)
@@ -113,20 +169,27 @@ impl DevService {
     // Secret Operations
 
     /// Prepare box secrets for dev session
-    pub fn prepare_box_for_session(
+    pub fn prepare_box_for_session<F>(
         &self,
         box_secrets: &[Secret],
-        master_key: &[u8; 32],
+        decrypt: F,
         session_key: &[u8; 32],
-    ) -> Result<HashMap<String, EncryptedData>, ZapError> {
+    ) -> Result<HashMap<String, EncryptedData>, ZapError>
+    where
+        F: Fn(&Secret) -> Result<String, ZapError>,
+    {
         let mut session_secrets = HashMap::new();
 
         for secret in box_secrets {
-            // Decrypt with master key (from session)
-            let decrypted_value = self.crypto.decrypt(&secret.encrypted_value, master_key)?;
-
-            // Re-encrypt with session key (for CLI usage)
-            let session_encrypted = self.crypto.encrypt(&decrypted_value, session_key)?;
+            // Decrypt with the box key
+            let decrypted_value = decrypt(secret)?;
+
+            // Re-encrypt with session key (for CLI usage, which only speaks AES-256-GCM)
+            let session_encrypted = self.crypto.encrypt_with(
+                &decrypted_value,
+                session_key,
+                CipherAlgorithm::Aes256Gcm,
+            )?;
 
             session_secrets.insert(secret.name.clone(), session_encrypted);
         }
@@ -0,0 +1,203 @@
+// src/services/github_service.rs - GitHub Actions secrets over the REST API. Values are sealed
+// (libsodium crypto_box_seal) to the repository's or environment's public key before upload,
+// so GitHub only ever sees ciphertext in the request body.
+
+use crate::models::{GithubPublicKey, GithubSecretStatus, ZapError};
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use crypto_box::aead::OsRng;
+use crypto_box::PublicKey;
+use reqwest::{Method, StatusCode, Url};
+use std::time::Duration;
+
+const API_URL: &str = "https://api.github.com";
+const API_VERSION: &str = "2022-11-28";
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+// Where Actions secrets go: the repository's, or one of its deployment environments'
+#[derive(Debug, Clone)]
+pub struct ActionsTarget {
+    pub owner: String,
+    pub repo: String,
+    pub environment: Option<String>,
+}
+
+impl ActionsTarget {
+    /// `repository` is "owner/repo"
+    pub fn parse(repository: &str, environment: Option<String>) -> Result<Self, ZapError> {
+        let plain = |part: &str| {
+            !part.is_empty()
+                && part
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        };
+        match repository.trim().split_once('/') {
+            Some((owner, repo)) if plain(owner) && plain(repo) => Ok(Self {
+                owner: owner.to_string(),
+                repo: repo.to_string(),
+                environment,
+            }),
+            _ => Err(ZapError::ValidationError(format!(
+                "'{}' is not a GitHub repository (expected owner/repo)",
+                repository
+            ))),
+        }
+    }
+
+    pub fn repository(&self) -> String {
+        format!("{}/{}", self.owner, self.repo)
+    }
+
+    // .../actions/secrets/<rest> or .../environments/<env>/secrets/<rest>, segments encoded
+    fn url(&self, rest: &[&str]) -> Url {
+        let mut url = Url::parse(API_URL).expect("valid API url");
+        {
+            let mut segments = url.path_segments_mut().expect("API url has a path");
+            segments
+                .pop_if_empty()
+                .extend(["repos", self.owner.as_str(), self.repo.as_str()]);
+            match &self.environment {
+                Some(environment) => {
+                    segments.extend(["environments", environment.as_str(), "secrets"])
+                }
+                None => segments.extend(["actions", "secrets"]),
+            };
+            segments.extend(rest);
+        }
+        url
+    }
+}
+
+/// Actions secret name for an env var name: letters, digits and underscores, not starting
+/// with a digit or GITHUB_ (GitHub reserves those)
+pub fn actions_secret_name(env_var_name: &str) -> Result<String, ZapError> {
+    let name = env_var_name.to_uppercase();
+    if name.is_empty()
+        || !name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_')
+        || name.starts_with(|c: char| c.is_ascii_digit())
+        || name.starts_with("GITHUB_")
+    {
+        return Err(ZapError::ValidationError(format!(
+            "'{}' can't be an Actions secret name",
+            name
+        )));
+    }
+    Ok(name)
+}
+
+pub struct GithubService {
+    client: reqwest::Client,
+}
+
+impl GithubService {
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("Zap/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default();
+        Self { client }
+    }
+
+    pub async fn public_key(
+        &self,
+        token: &str,
+        target: &ActionsTarget,
+    ) -> Result<GithubPublicKey, ZapError> {
+        let response = self
+            .request(Method::GET, target.url(&["public-key"]), token)
+            .send()
+            .await
+            .map_err(request_failed)?;
+        let body = check_status(response)
+            .await?
+            .text()
+            .await
+            .map_err(request_failed)?;
+        Ok(serde_json::from_str(&body)?)
+    }
+
+    /// Create or replace one secret; tells which of the two happened
+    pub async fn put_secret(
+        &self,
+        token: &str,
+        target: &ActionsTarget,
+        public_key: &GithubPublicKey,
+        name: &str,
+        value: &str,
+    ) -> Result<GithubSecretStatus, ZapError> {
+        let body = serde_json::json!({
+            "encrypted_value": seal(public_key, value)?,
+            "key_id": public_key.key_id,
+        });
+        let response = self
+            .request(Method::PUT, target.url(&[name]), token)
+            .header("Content-Type", "application/json")
+            .body(body.to_string())
+            .send()
+            .await
+            .map_err(request_failed)?;
+
+        Ok(match check_status(response).await?.status() {
+            StatusCode::CREATED => GithubSecretStatus::Created,
+            _ => GithubSecretStatus::Updated, // 204
+        })
+    }
+
+    fn request(&self, method: Method, url: Url, token: &str) -> reqwest::RequestBuilder {
+        self.client
+            .request(method, url)
+            .bearer_auth(token)
+            .header("Accept", "application/vnd.github+json")
+            .header("X-GitHub-Api-Version", API_VERSION)
+    }
+}
+
+impl Default for GithubService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Anonymous sealed box, compatible with libsodium's crypto_box_seal
+fn seal(public_key: &GithubPublicKey, value: &str) -> Result<String, ZapError> {
+    let key: [u8; 32] = BASE64
+        .decode(&public_key.key)
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| {
+            ZapError::PlatformError("GitHub returned an unreadable public key".to_string())
+        })?;
+    let sealed = PublicKey::from(key)
+        .seal(&mut OsRng, value.as_bytes())
+        .map_err(|_| ZapError::CryptoError("Failed to seal the secret".to_string()))?;
+    Ok(BASE64.encode(sealed))
+}
+
+// GitHub explains failures in a JSON `message`
+async fn check_status(response: reqwest::Response) -> Result<reqwest::Response, ZapError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+
+    let message = response
+        .text()
+        .await
+        .ok()
+        .and_then(|body| serde_json::from_str::<serde_json::Value>(&body).ok())
+        .and_then(|body| body["message"].as_str().map(str::to_string));
+    Err(match (status, message) {
+        (StatusCode::UNAUTHORIZED, _) => {
+            ZapError::AuthError("GitHub rejected the access token".to_string())
+        }
+        (_, Some(message)) => {
+            ZapError::PlatformError(format!("GitHub returned {}: {}", status, message))
+        }
+        (_, None) => ZapError::PlatformError(format!("GitHub returned {}", status)),
+    })
+}
+
+fn request_failed(e: reqwest::Error) -> ZapError {
+    ZapError::PlatformError(format!("GitHub request failed: {}", e))
+}
@@ -0,0 +1,196 @@
+// src/services/gitlab_service.rs - GitLab CI/CD variables over the REST API (v4), for
+// gitlab.com or a self-managed instance. Authenticates with a PRIVATE-TOKEN header.
+
+use crate::models::gitlab_model::GITLAB_DEFAULT_URL;
+use crate::models::{GitlabScope, GitlabTarget, GitlabVariable, ZapError};
+use reqwest::{Method, Url};
+use std::time::Duration;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+const PAGE_SIZE: usize = 100;
+
+// GitLab masks values of 8+ characters from the Base64 alphabet plus these, on one line
+const MASKABLE_EXTRA_CHARACTERS: &str = "@:.~-_+/=";
+const MIN_MASKED_LENGTH: usize = 8;
+
+/// Why GitLab would refuse to mask `value`, None when it can
+pub fn unmaskable_reason(value: &str) -> Option<&'static str> {
+    if value.chars().count() < MIN_MASKED_LENGTH {
+        return Some("Can't be masked: shorter than 8 characters");
+    }
+    if value.contains(['\n', '\r']) {
+        return Some("Can't be masked: spans several lines");
+    }
+    if !value
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || MASKABLE_EXTRA_CHARACTERS.contains(c))
+    {
+        return Some("Can't be masked: has characters GitLab doesn't mask");
+    }
+    None
+}
+
+/// CI/CD variable key for an env var name: letters, digits and underscores
+pub fn variable_key(env_var_name: &str) -> Result<String, ZapError> {
+    let key = env_var_name.to_uppercase();
+    if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+        return Err(ZapError::ValidationError(format!(
+            "'{}' can't be a CI/CD variable key",
+            key
+        )));
+    }
+    Ok(key)
+}
+
+/// "group/project (project)"
+pub fn describe_target(target: &GitlabTarget) -> String {
+    let scope = match target.scope {
+        GitlabScope::Project => "project",
+        GitlabScope::Group => "group",
+    };
+    format!("{} ({})", target.path, scope)
+}
+
+pub struct GitlabService {
+    client: reqwest::Client,
+}
+
+impl GitlabService {
+    pub fn new() -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("Zap/", env!("CARGO_PKG_VERSION")))
+            .build()
+            .unwrap_or_default();
+        Self { client }
+    }
+
+    /// Every variable of the target in its environment scope
+    pub async fn list_variables(
+        &self,
+        token: &str,
+        target: &GitlabTarget,
+    ) -> Result<Vec<GitlabVariable>, ZapError> {
+        let mut variables = Vec::new();
+        for page in 1.. {
+            let mut url = variables_url(target, None)?;
+            url.query_pairs_mut()
+                .append_pair("per_page", &PAGE_SIZE.to_string())
+                .append_pair("page", &page.to_string());
+
+            let body = self.send(Method::GET, url, token, None).await?;
+            let batch: Vec<GitlabVariable> = serde_json::from_str(&body)?;
+            let done = batch.len() < PAGE_SIZE;
+            variables.extend(
+                batch
+                    .into_iter()
+                    .filter(|variable| variable.environment_scope == target.environment_scope()),
+            );
+            if done {
+                break;
+            }
+        }
+        Ok(variables)
+    }
+
+    /// Create the variable, or replace the one with its key in the same environment scope
+    pub async fn save_variable(
+        &self,
+        token: &str,
+        target: &GitlabTarget,
+        variable: &GitlabVariable,
+        exists: bool,
+    ) -> Result<(), ZapError> {
+        let body = serde_json::to_string(variable)?;
+        if exists {
+            let mut url = variables_url(target, Some(&variable.key))?;
+            url.query_pairs_mut()
+                .append_pair("filter[environment_scope]", &variable.environment_scope);
+            self.send(Method::PUT, url, token, Some(body)).await?;
+        } else {
+            let url = variables_url(target, None)?;
+            self.send(Method::POST, url, token, Some(body)).await?;
+        }
+        Ok(())
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        url: Url,
+        token: &str,
+        body: Option<String>,
+    ) -> Result<String, ZapError> {
+        let mut request = self
+            .client
+            .request(method, url)
+            .header("PRIVATE-TOKEN", token);
+        if let Some(body) = body {
+            request = request
+                .header("Content-Type", "application/json")
+                .body(body);
+        }
+
+        let response = request.send().await.map_err(request_failed)?;
+        let status = response.status();
+        let body = response.text().await.map_err(request_failed)?;
+        if status.is_success() {
+            return Ok(body);
+        }
+
+        // Errors come as {"message": ...}, where message may be a string or a field map
+        let message = serde_json::from_str::<serde_json::Value>(&body)
+            .ok()
+            .and_then(|body| match &body["message"] {
+                serde_json::Value::String(message) => Some(message.clone()),
+                serde_json::Value::Null => None,
+                other => Some(other.to_string()),
+            });
+        Err(match (status.as_u16(), message) {
+            (401, _) => ZapError::AuthError("GitLab rejected the access token".to_string()),
+            (_, Some(message)) => {
+                ZapError::PlatformError(format!("GitLab returned {}: {}", status, message))
+            }
+            (_, None) => ZapError::PlatformError(format!("GitLab returned {}", status)),
+        })
+    }
+}
+
+impl Default for GitlabService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// <instance>/api/v4/projects/<url-encoded path>/variables[/<key>]
+fn variables_url(target: &GitlabTarget, key: Option<&str>) -> Result<Url, ZapError> {
+    let instance = target.instance_url.as_deref().unwrap_or(GITLAB_DEFAULT_URL);
+    let mut url = Url::parse(instance)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "https" | "http") && url.has_host())
+        .ok_or_else(|| {
+            ZapError::ValidationError(format!("'{}' is not a GitLab instance URL", instance))
+        })?;
+
+    let scope = match target.scope {
+        GitlabScope::Project => "projects",
+        GitlabScope::Group => "groups",
+    };
+    {
+        let mut segments = url
+            .path_segments_mut()
+            .map_err(|_| ZapError::ValidationError("Invalid GitLab instance URL".to_string()))?;
+        // The path is a single segment, so its slashes are sent as %2F
+        segments
+            .pop_if_empty()
+            .extend(["api", "v4", scope, target.path.as_str(), "variables"]);
+        if let Some(key) = key {
+            segments.push(key);
+        }
+    }
+    Ok(url)
+}
+
+fn request_failed(e: reqwest::Error) -> ZapError {
+    ZapError::PlatformError(format!("GitLab request failed: {}", e))
+}
@@ -0,0 +1,57 @@
+// src/services/hardware_key_service.rs - YubiKey HMAC-SHA1 challenge-response (OTP slot 1 or 2)
+// The response is mixed into the master key derivation, so it is never written anywhere.
+// FIDO2 hmac-secret keys are not supported yet - program a slot with `ykman otp chalresp`.
+
+use crate::models::ZapError;
+use challenge_response::config::{Config, Mode, Slot};
+use challenge_response::ChallengeResponse;
+
+pub struct HardwareKeyService;
+
+impl HardwareKeyService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    // Whether a YubiKey is plugged in right now
+    pub fn is_present(&self) -> bool {
+        ChallengeResponse::new()
+            .and_then(|mut cr| cr.find_device())
+            .is_ok()
+    }
+
+    // HMAC-SHA1 of the challenge from the given slot (waits for a touch if the slot asks for one)
+    pub fn challenge_response(&self, slot: u8, challenge: &[u8]) -> Result<Vec<u8>, ZapError> {
+        let slot = match slot {
+            1 => Slot::Slot1,
+            2 => Slot::Slot2,
+            _ => {
+                return Err(ZapError::ValidationError(
+                    "YubiKey slot must be 1 or 2".to_string(),
+                ))
+            }
+        };
+
+        let mut cr = ChallengeResponse::new()
+            .map_err(|e| ZapError::AuthError(format!("USB access unavailable: {}", e)))?;
+        let device = cr.find_device().map_err(|_| {
+            ZapError::AuthError("No YubiKey found - insert it and try again".to_string())
+        })?;
+
+        let config = Config::new_from(device)
+            .set_variable_size(true)
+            .set_mode(Mode::Sha1)
+            .set_slot(slot);
+        let response = cr
+            .challenge_response_hmac(challenge, config)
+            .map_err(|e| ZapError::AuthError(format!("YubiKey did not respond: {}", e)))?;
+
+        Ok(response.to_vec())
+    }
+}
+
+impl Default for HardwareKeyService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
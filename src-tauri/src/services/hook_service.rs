@@ -0,0 +1,135 @@
+// src/services/hook_service.rs - Runs user hooks (commands / webhooks) on vault events
+
+use crate::models::{Hook, HookAction, HookEvent, LogAction, ZapError};
+use crate::services::StorageService;
+use chrono::Utc;
+use std::collections::BTreeMap;
+use std::process::Stdio;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+use tokio::process::Command;
+
+pub struct HookService {
+    storage: Arc<StorageService>,
+}
+
+impl HookService {
+    pub fn new(storage: Arc<StorageService>) -> Self {
+        Self { storage }
+    }
+
+    /// Fire every enabled hook listening to `event` in the background.
+    /// Context holds names only (box, secret, session) - never secret values.
+    pub fn fire(&self, hooks: &[Hook], event: HookEvent, context: BTreeMap<String, String>) {
+        for hook in hooks.iter().filter(|hook| hook.listens_to(event)) {
+            let hook = hook.clone();
+            let payload = render_payload(&hook, event, &context);
+            let storage = Arc::clone(&self.storage);
+
+            tauri::async_runtime::spawn(async move {
+                let timeout = Duration::from_secs(hook.timeout_seconds as u64);
+                let result = match tokio::time::timeout(timeout, run_hook(&hook, event, payload))
+                    .await
+                {
+                    Ok(result) => result,
+                    Err(_) => Err(ZapError::PlatformError(format!(
+                        "timed out after {} seconds",
+                        hook.timeout_seconds
+                    ))),
+                };
+
+                if let Err(e) = result {
+                    let _ = storage.log(
+                        LogAction::HookFailed,
+                        format!("Hook '{}' failed on {}", hook.name, event.as_str()),
+                        Some(e.to_string()),
+                    );
+                }
+            });
+        }
+    }
+}
+
+async fn run_hook(hook: &Hook, event: HookEvent, payload: String) -> Result<(), ZapError> {
+    match &hook.action {
+        HookAction::Command { command } => run_command(command, event, payload).await,
+        HookAction::Webhook { url } => post_webhook(url, event, payload).await,
+    }
+}
+
+async fn run_command(command: &str, event: HookEvent, payload: String) -> Result<(), ZapError> {
+    let mut process = if cfg!(windows) {
+        let mut process = Command::new("cmd");
+        process.arg("/C").arg(command);
+        process
+    } else {
+        let mut process = Command::new("sh");
+        process.arg("-c").arg(command);
+        process
+    };
+
+    let mut child = process
+        .env("ZAP_EVENT", event.as_str())
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true) // Dropped by the timeout -> process is killed
+        .spawn()
+        .map_err(|e| ZapError::PlatformError(format!("failed to start command: {}", e)))?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // A command that ignores stdin may close it early, that's fine
+        let _ = stdin.write_all(payload.as_bytes()).await;
+    }
+
+    let status = child
+        .wait()
+        .await
+        .map_err(|e| ZapError::PlatformError(format!("command failed: {}", e)))?;
+
+    if !status.success() {
+        return Err(ZapError::PlatformError(format!("command exited with {}", status)));
+    }
+    Ok(())
+}
+
+async fn post_webhook(url: &str, event: HookEvent, payload: String) -> Result<(), ZapError> {
+    let response = reqwest::Client::new()
+        .post(url)
+        .header("Content-Type", "application/json")
+        .header("X-Zap-Event", event.as_str())
+        .body(payload)
+        .send()
+        .await
+        .map_err(|e| ZapError::PlatformError(format!("request failed: {}", e)))?;
+
+    if !response.status().is_success() {
+        return Err(ZapError::PlatformError(format!(
+            "webhook returned {}",
+            response.status()
+        )));
+    }
+    Ok(())
+}
+
+// `{{event}}`, `{{timestamp}}` and any context key (`{{box_name}}`, `{{secret_name}}`, ...).
+// Values are JSON-escaped, so placeholders belong inside string literals: `"{{box_name}}"`.
+fn render_payload(hook: &Hook, event: HookEvent, context: &BTreeMap<String, String>) -> String {
+    let mut values = context.clone();
+    values.insert("event".to_string(), event.as_str().to_string());
+    values.insert("timestamp".to_string(), Utc::now().to_rfc3339());
+
+    match &hook.payload_template {
+        Some(template) => values.iter().fold(template.clone(), |rendered, (key, value)| {
+            rendered.replace(&format!("{{{{{}}}}}", key), &json_escape(value))
+        }),
+        None => serde_json::to_string(&values).unwrap_or_default(),
+    }
+}
+
+// A string's JSON encoding without the surrounding quotes
+fn json_escape(value: &str) -> String {
+    let quoted = serde_json::Value::String(value.to_string()).to_string();
+    quoted[1..quoted.len() - 1].to_string()
+}
@@ -1,9 +1,48 @@
 // src/services/import_export_service.rs
 
-use crate::models::{Box, BoxExport, ImportResult, Secret, SecretExport, VaultExport, ZapError};
+use crate::models::box_model::MAX_SECRETS_PER_BOX;
+use crate::models::import_export_model::{
+    EncryptedBackup, VaultExportVersion, BACKUP_FORMAT_VERSION, VAULT_EXPORT_VERSION,
+};
+use crate::models::secret_model::{env_var_name, MAX_SECRET_VARIANTS};
+use crate::models::{
+    Box, BoxChanges, BoxExport, BoxProvenance, ConfigFormat, CsvColumn, CsvColumnMapping,
+    EncryptedData, ExportSignature, ExternalSecretProperty, ExternalSecretsExport, ImportProgress,
+    ImportResult, KdfParams, MergeStrategy, Secret, SecretExport, SignatureStatus,
+    SmartImportEntry, SmartImportFormat, SmartImportPreview, TfvarsFormat, VaultDiff, VaultExport,
+    ZapError,
+};
 use crate::services::{CryptoService, StorageService};
+use crate::utils::env_parser::{escape_env_value, parse_env};
+use crate::utils::input_validation::{name_key, secret_value_size, MAX_ID_LENGTH};
+use crate::utils::tfvars;
+use rayon::prelude::*;
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 
+const BACKUP_CONTEXT: &[u8] = b"zap:backup";
+
+// What importing one box from a vault file did
+enum BoxImportOutcome {
+    Created(usize),
+    Updated(usize),
+    Skipped, // Name taken by a local box, or the shared copy is already as new
+}
+
+// Named values split out of pasted text, in paste order
+struct PastedSecrets {
+    format: SmartImportFormat,
+    secrets: Vec<PastedSecret>,
+    warnings: Vec<String>,
+}
+
+struct PastedSecret {
+    name: String,
+    value: String,
+    env_name: Option<String>, // Only for .env pastes
+}
+
 pub struct ImportExportService {
     storage: Arc<StorageService>,
     crypto: CryptoService,
@@ -19,61 +58,162 @@ impl ImportExportService {
 
     // VAULT EXPORT (JSON)
 
-    /// Export entire vault as JSON using session master key
-    pub fn export_vault(&self, master_key: &[u8; 32]) -> Result<String, ZapError> {
+    /// Export entire vault as JSON using session master key, signed with `signing_key`
+    pub fn export_vault<F>(
+        &self,
+        master_key: &[u8; 32],
+        signing_key: &[u8; 32],
+        decrypt: F,
+    ) -> Result<String, ZapError>
+    where
+        F: Fn(&Secret, &[u8; 32]) -> Result<String, ZapError>,
+    {
         // Get all boxes and their secrets
         let all_boxes = self.storage.get_all_boxes()?;
         let mut export_boxes = Vec::new();
         let mut total_secrets = 0;
 
         for box_item in all_boxes {
-            let box_secrets = self.storage.get_secrets_by_box_id(&box_item.id)?;
-            let mut export_secrets = Vec::new();
+            let box_export = self.build_box_export(box_item, master_key, &decrypt)?;
+            total_secrets += box_export.secrets.len();
+            export_boxes.push(box_export);
+        }
 
-            // Decrypt secrets for export using session master key
-            for secret in box_secrets {
-                let decrypted_value = self.crypto.decrypt(&secret.encrypted_value, master_key)?;
+        self.sign_export(export_boxes, total_secrets, signing_key)
+    }
 
-                export_secrets.push(SecretExport {
-                    name: secret.name,
-                    value: decrypted_value,
-                });
-            }
+    /// A single box as a signed vault export, for a LAN transfer
+    pub fn export_box_as_vault<F>(
+        &self,
+        box_id: &str,
+        master_key: &[u8; 32],
+        signing_key: &[u8; 32],
+        decrypt: F,
+    ) -> Result<String, ZapError>
+    where
+        F: Fn(&Secret, &[u8; 32]) -> Result<String, ZapError>,
+    {
+        let box_item = self.storage.get_box(box_id)?;
+        let box_export = self.build_box_export(box_item, master_key, &decrypt)?;
+        let total_secrets = box_export.secrets.len();
+        self.sign_export(vec![box_export], total_secrets, signing_key)
+    }
+
+    fn sign_export(
+        &self,
+        boxes: Vec<BoxExport>,
+        total_secrets: usize,
+        signing_key: &[u8; 32],
+    ) -> Result<String, ZapError> {
+        let mut vault_export = VaultExport {
+            version: VAULT_EXPORT_VERSION.to_string(),
+            total_boxes: boxes.len(),
+            total_secrets,
+            boxes,
+            signature: None,
+        };
+        let message = serde_json::to_vec(&vault_export)?;
+        vault_export.signature = Some(ExportSignature {
+            public_key: self.crypto.signing_public_key(signing_key),
+            signature: self.crypto.sign(&message, signing_key),
+        });
+
+        serde_json::to_string_pretty(&vault_export).map_err(|e| ZapError::SerializationError(e))
+    }
 
-            total_secrets += export_secrets.len();
+    // ENCRYPTED BACKUP
 
-            export_boxes.push(BoxExport {
-                name: box_item.name,
-                description: box_item.description,
-                tags: box_item.tags,
+    /// Seal a vault export with the master key for an automatic backup
+    pub fn seal_backup(
+        &self,
+        vault_json: &str,
+        master_key: &[u8; 32],
+        salt: &[u8; 32],
+        kdf_params: KdfParams,
+    ) -> Result<String, ZapError> {
+        let backup = EncryptedBackup {
+            version: BACKUP_FORMAT_VERSION,
+            created_at: chrono::Utc::now(),
+            salt: hex::encode(salt),
+            kdf_params,
+            vault: self
+                .crypto
+                .encrypt_bound(vault_json, master_key, BACKUP_CONTEXT)?,
+        };
+        Ok(serde_json::to_string_pretty(&backup)?)
+    }
 
-                dev_mode: box_item.dev_mode,
-                secrets: export_secrets,
-            });
+    /// Open a backup from `seal_backup` with the master password, as vault import JSON
+    pub fn open_backup(&self, content: &str, password: &str) -> Result<String, ZapError> {
+        let backup: EncryptedBackup = serde_json::from_str(content)
+            .map_err(|_| ZapError::ValidationError("Not a Zap backup file".to_string()))?;
+
+        if backup.version != BACKUP_FORMAT_VERSION {
+            return Err(ZapError::ValidationError(format!(
+                "Backup format {} is not supported by this version of Zap",
+                backup.version
+            )));
         }
 
+        let salt = hex::decode(&backup.salt)?;
+        let key = self.crypto.derive_key(password, &salt, &backup.kdf_params)?;
+        self.crypto
+            .decrypt_bound(&backup.vault, &key, BACKUP_CONTEXT)
+            .map_err(|_| ZapError::IncorrectPassword)
+    }
+
+    // BOX EXPORT (AGE)
+
+    /// Export a single box encrypted to a teammate's age public key (no shared passphrase)
+    pub fn export_box_for_recipient<F>(
+        &self,
+        box_id: &str,
+        master_key: &[u8; 32],
+        recipient: &str,
+        decrypt: F,
+    ) -> Result<String, ZapError>
+    where
+        F: Fn(&Secret, &[u8; 32]) -> Result<String, ZapError>,
+    {
+        let box_item = self.storage.get_box(box_id)?;
+        let box_export = self.build_box_export(box_item, master_key, &decrypt)?;
+        let json = serde_json::to_vec(&box_export)?;
+
+        self.crypto.encrypt_for_recipient(&json, recipient)
+    }
+
+    /// Open a box shared with `export_box_for_recipient`, as vault import JSON
+    pub fn decrypt_shared_box(&self, armored: &str, identity: &str) -> Result<String, ZapError> {
+        let plaintext = self.crypto.decrypt_with_identity(armored, identity)?;
+        let box_export: BoxExport = serde_json::from_slice(&plaintext)?;
+
         let vault_export = VaultExport {
-            version: "1.0".to_string(),
-            total_boxes: export_boxes.len(),
-            total_secrets,
-            boxes: export_boxes,
+            version: VAULT_EXPORT_VERSION.to_string(),
+            total_boxes: 1,
+            total_secrets: box_export.secrets.len(),
+            boxes: vec![box_export],
+            signature: None, // age already keeps the file intact, it just isn't signed
         };
-
-        serde_json::to_string_pretty(&vault_export).map_err(|e| ZapError::SerializationError(e))
+        Ok(serde_json::to_string(&vault_export)?)
     }
 
     // BOX EXPORT (.ENV)
 
     /// Export single box as .ENV file using session master key
-    pub fn export_box_as_env(
+    pub fn export_box_as_env<F>(
         &self,
         box_id: &str,
         master_key: &[u8; 32],
         prefix: Option<&str>,
-    ) -> Result<String, ZapError> {
+        decrypt: F,
+    ) -> Result<String, ZapError>
+    where
+        F: Fn(&Secret, &[u8; 32]) -> Result<String, ZapError>,
+    {
         // Get box and its secrets
         let box_item = self.storage.get_box(box_id)?;
         let box_secrets = self.storage.get_secrets_by_box_id(box_id)?;
+        let box_key = self.crypto.box_key(&box_item, master_key)?;
 
         if box_secrets.is_empty() {
             return Err(ZapError::StorageError(
@@ -98,38 +238,290 @@ impl ImportExportService {
         ));
         env_content.push_str("\n");
 
-        // Export secrets as environment variables using session master key
+        // Export secrets as environment variables using the box key
         for secret in &box_secrets {
-            let decrypted_value = self.crypto.decrypt(&secret.encrypted_value, master_key)?;
+            let decrypted_value = decrypt(secret, &box_key)?;
             let env_var_name = secret.to_env_var_name(prefix);
 
-            // Escape value if it contains spaces or special characters
-            let escaped_value = if decrypted_value.contains(' ')
-                || decrypted_value.contains('"')
-                || decrypted_value.contains('\'')
+            env_content.push_str(&format!(
+                "{}={}\n",
+                env_var_name,
+                escape_env_value(&decrypted_value, secret.multiline_format)
+            ));
+        }
+
+        Ok(env_content)
+    }
+
+    // Decrypt a box's secrets with its box key into the export format
+    fn build_box_export<F>(
+        &self,
+        box_item: Box,
+        master_key: &[u8; 32],
+        decrypt: &F,
+    ) -> Result<BoxExport, ZapError>
+    where
+        F: Fn(&Secret, &[u8; 32]) -> Result<String, ZapError>,
+    {
+        let box_secrets = self.storage.get_secrets_by_box_id(&box_item.id)?;
+        let box_key = self.crypto.box_key(&box_item, master_key)?;
+        let mut export_secrets = Vec::new();
+
+        for secret in box_secrets {
+            let decrypted_value = decrypt(&secret, &box_key)?;
+            let variants = self.decrypt_variants(&secret, &box_key)?;
+
+            export_secrets.push(SecretExport {
+                id: Some(secret.id),
+                name: secret.name,
+                value: decrypted_value,
+                value_encoding: secret.value_encoding,
+                multiline_format: secret.multiline_format,
+                variants,
+                env_name: secret.env_name,
+                created_at: Some(secret.created_at),
+                updated_at: Some(secret.updated_at),
+            });
+        }
+
+        Ok(BoxExport {
+            name: box_item.name,
+            description: box_item.description,
+            tags: box_item.tags,
+            dev_mode: box_item.dev_mode,
+            secrets: export_secrets,
+            id: Some(box_item.id),
+            exported_at: Some(chrono::Utc::now()),
+            created_at: Some(box_item.created_at),
+            updated_at: Some(box_item.updated_at),
+        })
+    }
+
+    // BOX EXPORT (YAML / TOML)
+
+    /// Export single box as a config file, optionally nested under a dotted root key
+    pub fn export_box_as_config<F>(
+        &self,
+        box_id: &str,
+        master_key: &[u8; 32],
+        format: ConfigFormat,
+        root_key: Option<&str>,
+        decrypt: F,
+    ) -> Result<String, ZapError>
+    where
+        F: Fn(&Secret, &[u8; 32]) -> Result<String, ZapError>,
+    {
+        let box_item = self.storage.get_box(box_id)?;
+        let box_secrets = self.storage.get_secrets_by_box_id(box_id)?;
+        let box_key = self.crypto.box_key(&box_item, master_key)?;
+
+        if box_secrets.is_empty() {
+            return Err(ZapError::StorageError(
+                "Box has no secrets to export".to_string(),
+            ));
+        }
+
+        let mut entries = Map::new();
+        for secret in &box_secrets {
+            let decrypted_value = decrypt(secret, &box_key)?;
+            let config_key = secret.to_env_var_name(None).to_lowercase();
+            entries.insert(config_key, Value::String(decrypted_value));
+        }
+
+        // Wrap from the innermost key outwards: "app.secrets" -> { app: { secrets: {..} } }
+        let mut document = Value::Object(entries);
+        for key in Self::root_key_path(root_key).iter().rev() {
+            let mut wrapper = Map::new();
+            wrapper.insert(key.to_string(), document);
+            document = Value::Object(wrapper);
+        }
+
+        match format {
+            ConfigFormat::Yaml => serde_yaml::to_string(&document)
+                .map_err(|e| ZapError::StorageError(format!("Failed to write YAML: {}", e))),
+            ConfigFormat::Toml => toml::to_string_pretty(&document)
+                .map_err(|e| ZapError::StorageError(format!("Failed to write TOML: {}", e))),
+        }
+    }
+
+    /// Export single box as a Terraform / OpenTofu variable file
+    pub fn export_box_as_tfvars<F>(
+        &self,
+        box_id: &str,
+        master_key: &[u8; 32],
+        format: TfvarsFormat,
+        decrypt: F,
+    ) -> Result<String, ZapError>
+    where
+        F: Fn(&Secret, &[u8; 32]) -> Result<String, ZapError>,
+    {
+        let box_item = self.storage.get_box(box_id)?;
+        let box_secrets = self.storage.get_secrets_by_box_id(box_id)?;
+        let box_key = self.crypto.box_key(&box_item, master_key)?;
+
+        if box_secrets.is_empty() {
+            return Err(ZapError::StorageError(
+                "Box has no secrets to export".to_string(),
+            ));
+        }
+
+        // Variable name -> (secret name, value), sorted like `terraform fmt` leaves a file
+        let mut variables: BTreeMap<String, (&str, String)> = BTreeMap::new();
+        for secret in &box_secrets {
+            let name = tfvars::variable_name(&secret.name)?;
+            if let Some((other, _)) = variables.get(&name) {
+                return Err(ZapError::ValidationError(format!(
+                    "'{}' and '{}' would both be the variable '{}'",
+                    other, secret.name, name
+                )));
+            }
+            variables.insert(name, (&secret.name, decrypt(secret, &box_key)?));
+        }
+
+        match format {
+            TfvarsFormat::Json => {
+                let document: Map<String, Value> = variables
+                    .into_iter()
+                    .map(|(name, (_, value))| (name, Value::String(value)))
+                    .collect();
+                Ok(serde_json::to_string_pretty(&document)?)
+            }
+            TfvarsFormat::Hcl => {
+                let mut tfvars = format!("# Box: {}\n", box_item.name);
+                tfvars.push_str(&format!(
+                    "# Exported: {}\n\n",
+                    chrono::Utc::now().format("%Y-%m-%d %H:%M UTC")
+                ));
+
+                // `terraform fmt` lines up the equals signs
+                let width = variables.keys().map(|name| name.chars().count()).max();
+                for (name, (_, value)) in &variables {
+                    tfvars.push_str(&format!(
+                        "{:<width$} = {}\n",
+                        name,
+                        tfvars::hcl_string(value),
+                        width = width.unwrap_or_default()
+                    ));
+                }
+                Ok(tfvars)
+            }
+        }
+    }
+
+    /// Export single box as the JSON an external-secrets webhook provider reads.
+    /// `property_paths` maps secret names to dotted properties, the rest use their env var name.
+    pub fn export_box_for_external_secrets<F>(
+        &self,
+        box_id: &str,
+        master_key: &[u8; 32],
+        property_paths: &HashMap<String, String>,
+        decrypt: F,
+    ) -> Result<ExternalSecretsExport, ZapError>
+    where
+        F: Fn(&Secret, &[u8; 32]) -> Result<String, ZapError>,
+    {
+        let box_item = self.storage.get_box(box_id)?;
+        let box_secrets = self.storage.get_secrets_by_box_id(box_id)?;
+        let box_key = self.crypto.box_key(&box_item, master_key)?;
+
+        if box_secrets.is_empty() {
+            return Err(ZapError::StorageError(
+                "Box has no secrets to export".to_string(),
+            ));
+        }
+
+        let mut paths: HashMap<String, Vec<String>> = HashMap::new();
+        for (secret_name, path) in property_paths {
+            let key = name_key(secret_name);
+            if !box_secrets
+                .iter()
+                .any(|secret| name_key(&secret.name) == key)
             {
-                format!("\"{}\"", decrypted_value.replace('"', "\\\""))
-            } else {
-                decrypted_value
+                return Err(ZapError::ValidationError(format!(
+                    "Box has no secret named '{}'",
+                    secret_name
+                )));
+            }
+            paths.insert(key, Self::property_path(path)?);
+        }
+
+        let mut document = Map::new();
+        let mut properties = Vec::with_capacity(box_secrets.len());
+        for secret in &box_secrets {
+            let path = match paths.remove(&name_key(&secret.name)) {
+                Some(path) => path,
+                None => vec![secret.to_env_var_name(None).to_lowercase()],
             };
+            let decrypted_value = decrypt(secret, &box_key)?;
+            Self::insert_property(&mut document, &path, decrypted_value, &secret.name)?;
 
-            env_content.push_str(&format!("{}={}\n", env_var_name, escaped_value));
+            let property = path.join(".");
+            properties.push(ExternalSecretProperty {
+                secret_name: secret.name.clone(),
+                json_path: format!("$.{}", property),
+                property,
+            });
         }
+        properties.sort_by(|a, b| a.property.cmp(&b.property));
 
-        Ok(env_content)
+        Ok(ExternalSecretsExport {
+            remote_key: env_var_name(&box_item.name, None)
+                .to_lowercase()
+                .replace('_', "-"),
+            document: serde_json::to_string_pretty(&Value::Object(document))?,
+            properties,
+        })
+    }
+
+    // SIGNATURE CHECK
+
+    /// Who signed a vault file and whether it was changed since.
+    /// `trusted_keys` are hex public keys, this vault's own included.
+    pub fn verify_vault_export(
+        &self,
+        json_data: &str,
+        trusted_keys: &[String],
+    ) -> Result<SignatureStatus, ZapError> {
+        let mut vault_export: VaultExport = serde_json::from_str(json_data)?;
+        let Some(signature) = vault_export.signature.take() else {
+            return Ok(SignatureStatus::Unsigned);
+        };
+
+        // Signed over the same struct, so re-serializing reproduces the signed bytes
+        let message = serde_json::to_vec(&vault_export)?;
+        let intact = self
+            .crypto
+            .verify_signature(&message, &signature.public_key, &signature.signature)
+            .unwrap_or(false);
+
+        Ok(if !intact {
+            SignatureStatus::Invalid
+        } else if trusted_keys
+            .iter()
+            .any(|key| key.eq_ignore_ascii_case(&signature.public_key))
+        {
+            SignatureStatus::Trusted
+        } else {
+            SignatureStatus::Untrusted
+        })
     }
 
     //  IMPORT (JSON) 
 
-    /// Import vault from JSON format using session master key
-    pub fn import_vault(
+    /// Import vault from JSON format using session master key.
+    /// `shared` boxes are created read-only and refreshed in place by newer exports.
+    /// `progress` is called after each box; an error from it stops the import there.
+    pub fn import_vault<P>(
         &self,
         json_data: &str,
         master_key: &[u8; 32],
-    ) -> Result<ImportResult, ZapError> {
-        // Parse JSON
-        let vault_import: VaultExport =
-            serde_json::from_str(json_data).map_err(|e| ZapError::SerializationError(e))?;
+        shared: bool,
+        progress: P,
+    ) -> Result<ImportResult, ZapError>
+    where
+        P: Fn(ImportProgress) -> Result<(), ZapError>,
+    {
+        let vault_import = Self::parse_vault_export(json_data)?;
 
         if vault_import.boxes.is_empty() {
             return Err(ZapError::StorageError(
@@ -138,27 +530,174 @@ impl ImportExportService {
         }
 
         let mut result = ImportResult::new();
+        let boxes_total = vault_import.boxes.len();
 
         // Process each box
-        for box_data in vault_import.boxes {
+        for (index, box_data) in vault_import.boxes.into_iter().enumerate() {
             let box_name = box_data.name.clone();
 
-            match self.import_single_box(box_data, master_key) {
-                Ok((box_imported, secrets_imported)) => {
-                    if box_imported {
-                        result.boxes_imported += 1;
-                    }
+            match self.import_single_box(box_data, master_key, shared) {
+                Ok(BoxImportOutcome::Created(secrets_imported)) => {
+                    result.boxes_imported += 1;
                     result.secrets_imported += secrets_imported;
                 }
+                Ok(BoxImportOutcome::Updated(secrets_changed)) => {
+                    result.boxes_updated += 1;
+                    result.secrets_imported += secrets_changed;
+                }
+                Ok(BoxImportOutcome::Skipped) => {}
                 Err(e) => {
                     result.add_error(format!("Failed to import box '{}': {}", box_name, e));
                 }
             }
+
+            progress(ImportProgress {
+                boxes_done: index + 1,
+                boxes_total,
+                secrets_imported: result.secrets_imported,
+            })?;
+        }
+
+        Ok(result)
+    }
+
+    // VAULT MERGE IMPORT
+
+    /// Merge a vault export into the vault: missing boxes and secrets are added, items on
+    /// both sides with different content are settled by `strategy`, nothing local is removed
+    pub fn import_vault_merge(
+        &self,
+        json_data: &str,
+        master_key: &[u8; 32],
+        strategy: MergeStrategy,
+    ) -> Result<ImportResult, ZapError> {
+        let vault_import = Self::parse_vault_export(json_data)?;
+
+        if vault_import.boxes.is_empty() {
+            return Err(ZapError::StorageError(
+                "No boxes found in import file".to_string(),
+            ));
+        }
+
+        let mut result = ImportResult::new();
+
+        for box_data in vault_import.boxes {
+            let box_name = box_data.name.clone();
+
+            let Some(box_id) = self.storage.get_box_id_by_name(&box_data.name)? else {
+                match self.import_single_box(box_data, master_key, false) {
+                    Ok(BoxImportOutcome::Created(secrets_imported)) => {
+                        result.boxes_imported += 1;
+                        result.secrets_imported += secrets_imported;
+                    }
+                    Ok(_) => {}
+                    Err(e) => {
+                        result.add_error(format!("Failed to import box '{}': {}", box_name, e));
+                    }
+                }
+                continue;
+            };
+
+            let merged = self.merge_into_box(&box_id, box_data, master_key, strategy, &mut result);
+            if let Err(e) = merged {
+                result.add_error(format!("Failed to merge box '{}': {}", box_name, e));
+            }
         }
 
         Ok(result)
     }
 
+    // DIFF
+
+    /// Compare the vault with an earlier vault export by box and secret name.
+    /// Values are decrypted only to compare them, the diff holds names.
+    pub fn diff_vault_against_export(
+        &self,
+        json_data: &str,
+        master_key: &[u8; 32],
+    ) -> Result<VaultDiff, ZapError> {
+        let vault_export = Self::parse_vault_export(json_data)?;
+        let mut exported: HashMap<String, BoxExport> = vault_export
+            .boxes
+            .into_iter()
+            .map(|box_data| (name_key(&box_data.name), box_data))
+            .collect();
+
+        let mut diff = VaultDiff::default();
+        for box_item in self.storage.get_all_boxes()? {
+            match exported.remove(&name_key(&box_item.name)) {
+                Some(box_data) => {
+                    let changes = self.diff_box_against_export(&box_item, &box_data, master_key)?;
+                    if !changes.is_empty() {
+                        diff.boxes_changed.push(changes);
+                    }
+                }
+                None => diff.boxes_added.push(box_item.name),
+            }
+        }
+        diff.boxes_removed = exported.into_values().map(|box_data| box_data.name).collect();
+
+        diff.boxes_added.sort();
+        diff.boxes_removed.sort();
+        diff.boxes_changed.sort_by(|a, b| a.box_name.cmp(&b.box_name));
+        Ok(diff)
+    }
+
+    fn diff_box_against_export(
+        &self,
+        box_item: &Box,
+        box_data: &BoxExport,
+        master_key: &[u8; 32],
+    ) -> Result<BoxChanges, ZapError> {
+        let box_key = self.crypto.box_key(box_item, master_key)?;
+        let mut exported: HashMap<String, &SecretExport> = box_data
+            .secrets
+            .iter()
+            .map(|secret_data| (name_key(&secret_data.name), secret_data))
+            .collect();
+
+        let mut changes = BoxChanges {
+            box_name: box_item.name.clone(),
+            details_changed: box_item.description != box_data.description
+                || box_item.tags != box_data.tags
+                || box_item.dev_mode != box_data.dev_mode,
+            ..BoxChanges::default()
+        };
+        for secret in self.storage.get_secrets_by_box_id(&box_item.id)? {
+            match exported.remove(&name_key(&secret.name)) {
+                Some(secret_data) => {
+                    if !self.matches_export(&secret, secret_data, &box_key)? {
+                        changes.secrets_changed.push(secret.name);
+                    }
+                }
+                None => changes.secrets_added.push(secret.name),
+            }
+        }
+        changes.secrets_removed = exported
+            .into_values()
+            .map(|secret_data| secret_data.name.clone())
+            .collect();
+
+        changes.secrets_added.sort();
+        changes.secrets_removed.sort();
+        changes.secrets_changed.sort();
+        Ok(changes)
+    }
+
+    // Whether a secret still holds what the export has for it
+    fn matches_export(
+        &self,
+        secret: &Secret,
+        secret_data: &SecretExport,
+        box_key: &[u8; 32],
+    ) -> Result<bool, ZapError> {
+        Ok(secret.value_encoding == secret_data.value_encoding
+            && secret.multiline_format == secret_data.multiline_format
+            && secret.env_name == secret_data.env_name
+            && self.crypto.decrypt_secret(secret, box_key)? == secret_data.value
+            && self.decrypt_variants(secret, box_key)? == secret_data.variants)
+    }
+
     // .ENV IMPORT 
 
     /// Import .ENV file into specific box
@@ -167,12 +706,22 @@ impl ImportExportService {
         env_content: &str,
         target_box_id: &str,
         master_key: &[u8; 32], 
+        resolve_from_box: bool,
     ) -> Result<ImportResult, ZapError> {
         // Verify target box exists
-        let _target_box = self.storage.get_box(target_box_id)?;
+        let target_box = self.storage.get_box(target_box_id)?;
+        let box_key = self.crypto.box_key(&target_box, master_key)?;
+
+        // ${VAR} references may point at secrets already in the target box
+        let box_values = if resolve_from_box {
+            self.decrypt_box_env_values(target_box_id, &box_key)?
+        } else {
+            HashMap::new()
+        };
 
         // Parse .ENV content
-        let env_vars = self.parse_env_content(env_content)?;
+        let parsed = parse_env(env_content, |name| box_values.get(name).cloned())?;
+        let env_vars = parsed.vars;
 
         if env_vars.is_empty() {
             return Err(ZapError::StorageError(
@@ -181,10 +730,13 @@ impl ImportExportService {
         }
 
         let mut result = ImportResult::new();
+        for warning in parsed.warnings {
+            result.add_error(warning);
+        }
 
         // Check capacity
-        let current_secrets = self.storage.get_secrets_by_box_id(target_box_id)?;
-        if current_secrets.len() + env_vars.len() > 75 {
+        let current_secrets = self.storage.list_secrets_by_box_id(target_box_id)?;
+        if current_secrets.len() + env_vars.len() > MAX_SECRETS_PER_BOX {
             return Err(ZapError::BoxCapacityExceeded);
         }
 
@@ -209,7 +761,13 @@ impl ImportExportService {
             }
 
             // Create and save secret using session master key
-            match self.create_secret_from_env(&secret_name, &env_value, target_box_id, master_key) {
+            match self.create_secret_from_env(
+                &secret_name,
+                &env_value,
+                Some(&env_name),
+                target_box_id,
+                &box_key,
+            ) {
                 Ok(()) => {
                     result.secrets_imported += 1;
                 }
@@ -222,117 +780,1119 @@ impl ImportExportService {
         Ok(result)
     }
 
-    // PRIVATE HELPERS
+    // YAML / TOML IMPORT
 
-    /// Import single box with all its secrets
-    fn import_single_box(
+    /// Import a config file into specific box, reading entries below the dotted root key
+    pub fn import_config_to_box(
         &self,
-        box_data: BoxExport,
+        content: &str,
+        target_box_id: &str,
+        format: ConfigFormat,
+        root_key: Option<&str>,
         master_key: &[u8; 32],
-    ) -> Result<(bool, usize), ZapError> {
-        // Skip if box already exists
-        if self.storage.get_box_id_by_name(&box_data.name)?.is_some() {
-            return Ok((false, 0));
+    ) -> Result<ImportResult, ZapError> {
+        let target_box = self.storage.get_box(target_box_id)?;
+        let box_key = self.crypto.box_key(&target_box, master_key)?;
+
+        let document: Value = match format {
+            ConfigFormat::Yaml => serde_yaml::from_str(content)
+                .map_err(|e| ZapError::ValidationError(format!("Invalid YAML: {}", e)))?,
+            ConfigFormat::Toml => toml::from_str(content)
+                .map_err(|e| ZapError::ValidationError(format!("Invalid TOML: {}", e)))?,
+        };
+
+        let mut section = &document;
+        for key in Self::root_key_path(root_key) {
+            section = section.get(key).ok_or_else(|| {
+                ZapError::ValidationError(format!("Key '{}' not found in config", key))
+            })?;
         }
 
-        // Create new box
-        let new_box = Box::new(
-            box_data.name,
-            box_data.description,
-            box_data.tags,
-            box_data.dev_mode,
-        )?;
+        let mut result = ImportResult::new();
+        let mut entries = Vec::new();
+        Self::flatten_config_value(section, String::new(), &mut entries, &mut result);
+
+        if entries.is_empty() {
+            return Err(ZapError::StorageError(
+                "No values found in config content".to_string(),
+            ));
+        }
+
+        let mut new_entries = Vec::new();
+        let mut seen_names = HashSet::new();
+        for (config_key, value) in entries {
+            let secret_name = self.env_var_to_secret_name(&config_key);
+
+            if self
+                .storage
+                .get_secret_by_name_in_box(&secret_name, target_box_id)?
+                .is_some()
+            {
+                result.add_error(format!("Secret '{}' already exists in box", secret_name));
+                continue;
+            }
+
+            if let Err(e) = Secret::validate_name(&secret_name) {
+                result.add_error(format!("Invalid secret name '{}': {}", secret_name, e));
+                continue;
+            }
+
+            if !seen_names.insert(name_key(&secret_name)) {
+                result.add_error(format!(
+                    "Secret '{}' appears more than once in the config",
+                    secret_name
+                ));
+                continue;
+            }
+
+            new_entries.push((secret_name, value));
+        }
 
-        self.storage.save_box(&new_box)?;
+        // Only entries that would be saved count against the box's limit
+        let current_secrets = self.storage.list_secrets_by_box_id(target_box_id)?;
+        if current_secrets.len() + new_entries.len() > MAX_SECRETS_PER_BOX {
+            return Err(ZapError::BoxCapacityExceeded);
+        }
 
-        // Import secrets
-        let mut secrets_imported = 0;
-        for secret_data in box_data.secrets {
-            match self.create_secret_from_import(&secret_data, &new_box.id, master_key) {
+        for (secret_name, value) in new_entries {
+            match self.create_secret_from_env(
+                &secret_name,
+                &value,
+                None,
+                target_box_id,
+                &box_key,
+            ) {
                 Ok(()) => {
-                    secrets_imported += 1;
+                    result.secrets_imported += 1;
                 }
-                Err(_) => {
-                    continue;
+                Err(e) => {
+                    result.add_error(format!("Failed to import '{}': {}", secret_name, e));
                 }
             }
         }
 
-        Ok((true, secrets_imported))
+        Ok(result)
     }
 
-    /// Create secret from import data
-    fn create_secret_from_import(
+    // CSV IMPORT
+
+    /// Import CSV rows into specific box using a column mapping
+    pub fn import_csv_to_box(
         &self,
-        secret_data: &SecretExport,
-        box_id: &str,
+        content: &str,
+        target_box_id: &str,
+        mapping: &CsvColumnMapping,
         master_key: &[u8; 32],
-    ) -> Result<(), ZapError> {
-        Secret::validate_name(&secret_data.name)?;
-
-        if secret_data.value.trim().is_empty() {
+    ) -> Result<ImportResult, ZapError> {
+        // Secrets only carry a name and value, refuse rather than drop those columns silently
+        if mapping.notes.is_some() || mapping.tags.is_some() {
             return Err(ZapError::ValidationError(
-                "Secret value cannot be empty".to_string(),
+                "Secrets don't store notes or tags, leave those columns unmapped".to_string(),
             ));
         }
 
-        // Encrypt with session master key
-        let encrypted_value = self.crypto.encrypt(&secret_data.value, master_key)?;
-        let secret = Secret::new(
-            box_id.to_string(),
-            secret_data.name.clone(),
-            encrypted_value,
-        )?;
+        let target_box = self.storage.get_box(target_box_id)?;
+        let box_key = self.crypto.box_key(&target_box, master_key)?;
 
-        self.storage.save_secret(&secret)?;
-        Ok(())
-    }
+        let delimiter = mapping
+            .delimiter
+            .unwrap_or_else(|| Self::detect_csv_delimiter(content));
+        let mut rows = Self::parse_csv_content(content, delimiter)?;
 
-    /// Create secret from environment variable
-    fn create_secret_from_env(
+        let headers = if mapping.has_header && !rows.is_empty() {
+            rows.remove(0)
+        } else {
+            Vec::new()
+        };
+
+        let name_index = Self::resolve_csv_column(&mapping.name, &headers)?;
+        let value_index = Self::resolve_csv_column(&mapping.value, &headers)?;
+
+        if rows.is_empty() {
+            return Err(ZapError::StorageError(
+                "No rows found in CSV content".to_string(),
+            ));
+        }
+
+        let mut result = ImportResult::new();
+        let mut valid_rows = Vec::new();
+        let mut seen_names = HashSet::new();
+
+        // Row numbers match the line a spreadsheet user would see
+        let first_row = if mapping.has_header { 2 } else { 1 };
+        for (offset, row) in rows.iter().enumerate() {
+            let row_number = first_row + offset;
+
+            let (Some(name), Some(value)) = (row.get(name_index), row.get(value_index)) else {
+                result.add_error(format!("Row {}: missing name or value column", row_number));
+                continue;
+            };
+            let name = name.trim();
+
+            if let Err(e) = Secret::validate_name(name) {
+                result.add_error(format!("Row {}: {}", row_number, e));
+                continue;
+            }
+
+            if value.trim().is_empty() {
+                result.add_error(format!("Row {}: value for '{}' is empty", row_number, name));
+                continue;
+            }
+
+            if let Err(e) = secret_value_size(value, self.storage.max_secret_value_bytes()) {
+                result.add_error(format!("Row {}: {}", row_number, e));
+                continue;
+            }
+
+            if !seen_names.insert(name_key(name)) {
+                result.add_error(format!(
+                    "Row {}: secret '{}' appears more than once in the CSV",
+                    row_number, name
+                ));
+                continue;
+            }
+
+            if self
+                .storage
+                .get_secret_by_name_in_box(name, target_box_id)?
+                .is_some()
+            {
+                result.add_error(format!(
+                    "Row {}: secret '{}' already exists in box",
+                    row_number, name
+                ));
+                continue;
+            }
+
+            valid_rows.push((row_number, name, value));
+        }
+
+        // Only rows that would be saved count against the box's limit
+        let current_secrets = self.storage.list_secrets_by_box_id(target_box_id)?;
+        if current_secrets.len() + valid_rows.len() > MAX_SECRETS_PER_BOX {
+            return Err(ZapError::BoxCapacityExceeded);
+        }
+
+        for (row_number, name, value) in valid_rows {
+            match self.create_secret_from_env(name, value, None, target_box_id, &box_key) {
+                Ok(()) => {
+                    result.secrets_imported += 1;
+                }
+                Err(e) => {
+                    result.add_error(format!("Row {}: {}", row_number, e));
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    // SMART IMPORT (pasted text)
+
+    /// What importing pasted text would do, detected format included. Nothing is saved.
+    pub fn preview_smart_import(
+        &self,
+        content: &str,
+        target_box_id: &str,
+    ) -> Result<SmartImportPreview, ZapError> {
+        let target_box = self.storage.get_box(target_box_id)?;
+        let pasted = self.parse_pasted(content)?;
+
+        let mut entries = Vec::new();
+        let mut new_secrets = 0;
+        for secret in &pasted.secrets {
+            let already_exists = self
+                .storage
+                .get_secret_by_name_in_box(&secret.name, target_box_id)?
+                .is_some();
+            let error = self.check_pasted_secret(secret).err();
+            if !already_exists && error.is_none() {
+                new_secrets += 1;
+            }
+            entries.push(SmartImportEntry {
+                name: secret.name.clone(),
+                env_name: secret.env_name.clone(),
+                already_exists,
+                error,
+            });
+        }
+
+        Ok(SmartImportPreview {
+            format: pasted.format,
+            entries,
+            warnings: pasted.warnings,
+            fits_in_box: target_box.secrets_count + new_secrets <= MAX_SECRETS_PER_BOX,
+        })
+    }
+
+    /// Import pasted text into specific box, parsed as whichever format it was detected as
+    pub fn smart_import_to_box(
+        &self,
+        content: &str,
+        target_box_id: &str,
+        master_key: &[u8; 32],
+    ) -> Result<(SmartImportFormat, ImportResult), ZapError> {
+        let target_box = self.storage.get_box(target_box_id)?;
+        let box_key = self.crypto.box_key(&target_box, master_key)?;
+        let pasted = self.parse_pasted(content)?;
+
+        let mut result = ImportResult::new();
+        for warning in pasted.warnings {
+            result.add_error(warning);
+        }
+
+        if target_box.secrets_count + pasted.secrets.len() > MAX_SECRETS_PER_BOX {
+            return Err(ZapError::BoxCapacityExceeded);
+        }
+
+        for secret in pasted.secrets {
+            if let Err(e) = self.check_pasted_secret(&secret) {
+                result.add_error(format!("Skipped '{}': {}", secret.name, e));
+                continue;
+            }
+
+            if self
+                .storage
+                .get_secret_by_name_in_box(&secret.name, target_box_id)?
+                .is_some()
+            {
+                result.add_error(format!("Secret '{}' already exists in box", secret.name));
+                continue;
+            }
+
+            match self.create_secret_from_env(
+                &secret.name,
+                &secret.value,
+                secret.env_name.as_deref(),
+                target_box_id,
+                &box_key,
+            ) {
+                Ok(()) => {
+                    result.secrets_imported += 1;
+                }
+                Err(e) => {
+                    result.add_error(format!("Failed to import '{}': {}", secret.name, e));
+                }
+            }
+        }
+
+        Ok((pasted.format, result))
+    }
+
+    // PRIVATE HELPERS
+
+    /// Import single box with all its secrets: encrypted in parallel, saved in one batch
+    fn import_single_box(
+        &self,
+        box_data: BoxExport,
+        master_key: &[u8; 32],
+        shared: bool,
+    ) -> Result<BoxImportOutcome, ZapError> {
+        // An existing box is only touched when it's a shared copy and this export is newer
+        if let Some(box_id) = self.storage.get_box_id_by_name(&box_data.name)? {
+            let existing = self.storage.get_box(&box_id)?;
+            if shared && Self::is_newer_export(&existing, &box_data) {
+                return self.update_shared_box(existing, box_data, master_key);
+            }
+            return Ok(BoxImportOutcome::Skipped);
+        }
+
+        // Create new box with its own data key
+        let box_key = self.crypto.generate_data_key();
+        let mut new_box = Box::new(
+            box_data.name.clone(),
+            box_data.description.clone(),
+            box_data.tags.clone(),
+            box_data.dev_mode,
+        )?
+        .with_wrapped_key(self.crypto.wrap_key(&box_key, master_key)?);
+        if let Some(id) =
+            Self::reusable_id(box_data.id.as_deref(), |id| self.storage.get_box(id).is_ok())
+        {
+            new_box.id = id;
+        }
+        if let Some(created_at) = box_data.created_at {
+            new_box.created_at = created_at;
+        }
+        if let Some(updated_at) = box_data.updated_at {
+            new_box.updated_at = updated_at;
+        }
+        if shared {
+            new_box = new_box.as_shared(Self::provenance_of(&box_data));
+        }
+
+        // Invalid secrets and repeated names are skipped, first one wins
+        let max_value_bytes = self.storage.max_secret_value_bytes();
+        let mut seen_names = HashSet::new();
+        let mut claimed_ids = HashSet::new();
+        let drafts: Vec<(Secret, &SecretExport)> = box_data
+            .secrets
+            .iter()
+            .filter_map(|secret_data| {
+                let mut secret =
+                    Self::secret_from_import(secret_data, &new_box.id, max_value_bytes).ok()?;
+                if !seen_names.insert(name_key(&secret.name)) {
+                    return None;
+                }
+                if let Some(id) = Self::reusable_id(secret_data.id.as_deref(), |id| {
+                    claimed_ids.contains(id) || self.storage.get_secret(id).is_ok()
+                }) {
+                    claimed_ids.insert(id.clone());
+                    secret.id = id;
+                }
+                Some((secret, secret_data))
+            })
+            .collect();
+
+        let secrets: Vec<Secret> = drafts
+            .into_par_iter()
+            .filter_map(|(mut secret, secret_data)| {
+                // Encrypt with the box key, bound to the new secret's id
+                self.seal_imported(&mut secret, secret_data, &box_key).ok()?;
+                Some(secret)
+            })
+            .collect();
+
+        self.storage.save_box_with_secrets(&new_box, &secrets)?;
+        Ok(BoxImportOutcome::Created(secrets.len()))
+    }
+
+    /// Bring a read-only shared box in line with a newer export: secrets are added,
+    /// re-encrypted when their value changed and removed when the export dropped them
+    fn update_shared_box(
+        &self,
+        box_item: Box,
+        box_data: BoxExport,
+        master_key: &[u8; 32],
+    ) -> Result<BoxImportOutcome, ZapError> {
+        let box_key = self.crypto.box_key(&box_item, master_key)?;
+        let mut existing: HashMap<String, Secret> = self
+            .storage
+            .get_secrets_by_box_id(&box_item.id)?
+            .into_iter()
+            .map(|secret| (name_key(&secret.name), secret))
+            .collect();
+
+        let max_value_bytes = self.storage.max_secret_value_bytes();
+        let mut changed = 0;
+        let mut seen_names = HashSet::new();
+        for secret_data in &box_data.secrets {
+            if !seen_names.insert(name_key(&secret_data.name)) {
+                continue;
+            }
+
+            match existing.remove(&name_key(&secret_data.name)) {
+                Some(mut secret) => {
+                    let current = self.crypto.decrypt_secret(&secret, &box_key)?;
+                    if current == secret_data.value
+                        && secret.value_encoding == secret_data.value_encoding
+                        && secret.env_name == secret_data.env_name
+                        && self.decrypt_variants(&secret, &box_key)? == secret_data.variants
+                    {
+                        continue;
+                    }
+                    // Too large for this vault, the current value stays
+                    if Self::validate_import_values(secret_data, max_value_bytes).is_err() {
+                        continue;
+                    }
+                    secret.value_encoding = secret_data.value_encoding;
+                    self.seal_imported(&mut secret, secret_data, &box_key)?;
+                    secret.set_env_name(secret_data.env_name.clone());
+                    secret.multiline_format = secret_data.multiline_format;
+                    secret.updated_at = chrono::Utc::now();
+                    self.storage.update_secret(&secret)?;
+                }
+                None => {
+                    let Ok(mut secret) =
+                        Self::secret_from_import(secret_data, &box_item.id, max_value_bytes)
+                    else {
+                        continue;
+                    };
+                    self.reuse_secret_id(&mut secret, secret_data);
+                    self.seal_imported(&mut secret, secret_data, &box_key)?;
+                    self.storage.save_secret(&secret)?;
+                }
+            }
+            changed += 1;
+        }
+
+        // Whatever is left was removed on the owner's side
+        for secret in existing.values() {
+            self.storage.delete_secret(&secret.id)?;
+            changed += 1;
+        }
+
+        // Reload for the fresh secrets count; dev_mode stays a local choice
+        self.storage.modify_box(&box_item.id, |box_item| {
+            box_item.description = box_data.description.clone();
+            box_item.tags = box_data.tags.clone();
+            box_item.provenance = Some(Self::provenance_of(&box_data));
+            box_item.updated_at = chrono::Utc::now();
+            Ok(())
+        })?;
+
+        Ok(BoxImportOutcome::Updated(changed))
+    }
+
+    /// Merge one exported box into the local box with the same name
+    fn merge_into_box(
+        &self,
+        box_id: &str,
+        box_data: BoxExport,
+        master_key: &[u8; 32],
+        strategy: MergeStrategy,
+        result: &mut ImportResult,
+    ) -> Result<(), ZapError> {
+        let mut box_item = self.storage.get_box(box_id)?;
+        box_item.ensure_writable()?;
+        let box_key = self.crypto.box_key(&box_item, master_key)?;
+        let mut box_changed = false;
+
+        // Box details first, secret changes below move the box's updated_at
+        let details_differ = box_item.description != box_data.description
+            || box_item.tags != box_data.tags
+            || box_item.dev_mode != box_data.dev_mode;
+        if details_differ && strategy.prefers_import(box_item.updated_at, box_data.updated_at) {
+            box_item.update_fields(
+                None,
+                Some(box_data.description.clone()),
+                Some(box_data.tags.clone()),
+                Some(box_data.dev_mode),
+            )?;
+            if let Some(updated_at) = box_data.updated_at {
+                box_item.updated_at = updated_at;
+            }
+            self.storage.update_box(&box_item)?;
+            box_changed = true;
+        }
+
+        let mut local: HashMap<String, Secret> = self
+            .storage
+            .get_secrets_by_box_id(box_id)?
+            .into_iter()
+            .map(|secret| (name_key(&secret.name), secret))
+            .collect();
+        let mut secrets_count = box_item.secrets_count;
+        let max_value_bytes = self.storage.max_secret_value_bytes();
+        let mut seen_names = HashSet::new();
+
+        for secret_data in &box_data.secrets {
+            if !seen_names.insert(name_key(&secret_data.name)) {
+                continue;
+            }
+
+            match local.remove(&name_key(&secret_data.name)) {
+                Some(mut secret) => {
+                    let current = self.crypto.decrypt_secret(&secret, &box_key)?;
+                    let same_value = current == secret_data.value
+                        && secret.value_encoding == secret_data.value_encoding
+                        && self.decrypt_variants(&secret, &box_key)? == secret_data.variants;
+                    if same_value
+                        || !strategy.prefers_import(secret.updated_at, secret_data.updated_at)
+                    {
+                        continue;
+                    }
+                    if let Err(e) = Self::validate_import_values(secret_data, max_value_bytes) {
+                        result.add_error(format!(
+                            "Secret '{}' in box '{}' not updated: {}",
+                            secret_data.name, box_item.name, e
+                        ));
+                        continue;
+                    }
+                    secret.value_encoding = secret_data.value_encoding;
+                    self.seal_imported(&mut secret, secret_data, &box_key)?;
+                    secret.set_env_name(secret_data.env_name.clone());
+                    secret.multiline_format = secret_data.multiline_format;
+                    secret.updated_at = secret_data.updated_at.unwrap_or_else(chrono::Utc::now);
+                    self.storage.update_secret(&secret)?;
+                    result.secrets_updated += 1;
+                }
+                None => {
+                    if secrets_count >= MAX_SECRETS_PER_BOX {
+                        result.add_error(format!(
+                            "Box '{}' is full, secret '{}' was not merged",
+                            box_item.name, secret_data.name
+                        ));
+                        continue;
+                    }
+                    let mut secret =
+                        match Self::secret_from_import(secret_data, box_id, max_value_bytes) {
+                            Ok(secret) => secret,
+                            Err(e) => {
+                                result.add_error(format!(
+                                    "Secret '{}' in box '{}' skipped: {}",
+                                    secret_data.name, box_item.name, e
+                                ));
+                                continue;
+                            }
+                        };
+                    self.reuse_secret_id(&mut secret, secret_data);
+                    self.seal_imported(&mut secret, secret_data, &box_key)?;
+                    self.storage.save_secret(&secret)?;
+                    secrets_count += 1;
+                    result.secrets_imported += 1;
+                }
+            }
+            box_changed = true;
+        }
+
+        if box_changed {
+            result.boxes_updated += 1;
+        }
+        Ok(())
+    }
+
+    // Exports without a timestamp (older files) never replace anything
+    fn is_newer_export(existing: &Box, box_data: &BoxExport) -> bool {
+        let (Some(provenance), Some(exported_at)) = (&existing.provenance, box_data.exported_at)
+        else {
+            return false;
+        };
+        let same_source = match (&provenance.source_box_id, &box_data.id) {
+            (Some(known), Some(incoming)) => known == incoming,
+            _ => true,
+        };
+        existing.read_only && same_source && exported_at > provenance.exported_at
+    }
+
+    fn provenance_of(box_data: &BoxExport) -> BoxProvenance {
+        let now = chrono::Utc::now();
+        BoxProvenance {
+            source_box_id: box_data.id.clone(),
+            exported_at: box_data.exported_at.unwrap_or(now),
+            imported_at: now,
+        }
+    }
+
+    /// Parse a vault file, dispatching on its `version` field
+    fn parse_vault_export(json_data: &str) -> Result<VaultExport, ZapError> {
+        let mut vault_import: VaultExport = serde_json::from_str(json_data)?;
+
+        match vault_import.format_version()? {
+            VaultExportVersion::V1 => vault_import
+                .boxes
+                .iter_mut()
+                .for_each(BoxExport::strip_v2_fields),
+            VaultExportVersion::V2 => {}
+        }
+        Ok(vault_import)
+    }
+
+    // Keep the exported id when it's well-formed and free here, so references survive
+    fn reusable_id(id: Option<&str>, taken: impl FnOnce(&str) -> bool) -> Option<String> {
+        let id = id?;
+        let well_formed = !id.is_empty()
+            && id.len() <= MAX_ID_LENGTH
+            && id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-');
+        (well_formed && !taken(id)).then(|| id.to_string())
+    }
+
+    /// For secrets saved one at a time (before they're encrypted, the id is bound as AAD)
+    fn reuse_secret_id(&self, secret: &mut Secret, secret_data: &SecretExport) {
+        if let Some(id) =
+            Self::reusable_id(secret_data.id.as_deref(), |id| self.storage.get_secret(id).is_ok())
+        {
+            secret.id = id;
+        }
+    }
+
+    /// Validate import data and create the (not yet encrypted) secret
+    fn secret_from_import(
+        secret_data: &SecretExport,
+        box_id: &str,
+        max_value_bytes: usize,
+    ) -> Result<Secret, ZapError> {
+        Secret::validate_name(&secret_data.name)?;
+
+        if secret_data.value.trim().is_empty() {
+            return Err(ZapError::ValidationError(
+                "Secret value cannot be empty".to_string(),
+            ));
+        }
+        Self::validate_import_values(secret_data, max_value_bytes)?;
+
+        let mut secret = Secret::new(
+            box_id.to_string(),
+            secret_data.name.clone(),
+            EncryptedData::empty(),
+        )?
+        .with_env_name(secret_data.env_name.clone())
+        .with_value_encoding(secret_data.value_encoding)
+        .with_multiline_format(secret_data.multiline_format);
+
+        // 2.0 exports carry their timestamps, so a round trip doesn't make everything new
+        if let Some(created_at) = secret_data.created_at {
+            secret.created_at = created_at;
+        }
+        if let Some(updated_at) = secret_data.updated_at {
+            secret.updated_at = updated_at;
+        }
+        Ok(secret)
+    }
+
+    // Value and environment variants must fit this vault's limits
+    fn validate_import_values(
+        secret_data: &SecretExport,
+        max_value_bytes: usize,
+    ) -> Result<(), ZapError> {
+        secret_value_size(&secret_data.value, max_value_bytes)?;
+        if secret_data.variants.len() > MAX_SECRET_VARIANTS {
+            return Err(ZapError::ValidationError(format!(
+                "A secret can have at most {} environment variants",
+                MAX_SECRET_VARIANTS
+            )));
+        }
+        for (environment, value) in &secret_data.variants {
+            Secret::validate_environment(environment)?;
+            secret_value_size(value, max_value_bytes)?;
+        }
+        Ok(())
+    }
+
+    /// Encrypt an imported value and its variants, bound to the secret's final id
+    fn seal_imported(
+        &self,
+        secret: &mut Secret,
+        secret_data: &SecretExport,
+        box_key: &[u8; 32],
+    ) -> Result<(), ZapError> {
+        secret.encrypted_value = self.crypto.encrypt_secret(secret, &secret_data.value, box_key)?;
+        secret.variants = secret_data
+            .variants
+            .iter()
+            .map(|(environment, value)| {
+                let data = self
+                    .crypto
+                    .encrypt_secret_variant(secret, environment, value, box_key)?;
+                Ok((environment.clone(), data))
+            })
+            .collect::<Result<_, ZapError>>()?;
+        Ok(())
+    }
+
+    // Decrypted environment variants, exported and compared alongside the base value
+    fn decrypt_variants(
+        &self,
+        secret: &Secret,
+        box_key: &[u8; 32],
+    ) -> Result<BTreeMap<String, String>, ZapError> {
+        secret
+            .variants
+            .keys()
+            .map(|environment| {
+                let value = self
+                    .crypto
+                    .decrypt_secret_for(secret, Some(environment), box_key)?;
+                Ok((environment.clone(), value))
+            })
+            .collect()
+    }
+
+    /// Create secret from environment variable
+    fn create_secret_from_env(
         &self,
         name: &str,
         value: &str,
+        env_name: Option<&str>,
         box_id: &str,
-        master_key: &[u8; 32],
+        box_key: &[u8; 32],
     ) -> Result<(), ZapError> {
-        let encrypted_value = self.crypto.encrypt(value, master_key)?;
-        let secret = Secret::new(box_id.to_string(), name.to_string(), encrypted_value)?;
+        secret_value_size(value, self.storage.max_secret_value_bytes())?;
+        let mut secret =
+            Secret::new(box_id.to_string(), name.to_string(), EncryptedData::empty())?
+                .with_env_name(env_name.map(str::to_string));
+        secret.encrypted_value = self.crypto.encrypt_secret(&secret, value, box_key)?;
 
         self.storage.save_secret(&secret)?;
         Ok(())
     }
 
-    /// Parse .ENV content into key-value pairs
-    fn parse_env_content(&self, content: &str) -> Result<Vec<(String, String)>, ZapError> {
-        let mut env_vars = Vec::new();
+    /// Decrypted values of a box keyed by env var name
+    fn decrypt_box_env_values(
+        &self,
+        box_id: &str,
+        box_key: &[u8; 32],
+    ) -> Result<HashMap<String, String>, ZapError> {
+        let mut values = HashMap::new();
+        for secret in self.storage.get_secrets_by_box_id(box_id)? {
+            let decrypted_value = self.crypto.decrypt_secret(&secret, box_key)?;
+            values.insert(secret.to_env_var_name(None), decrypted_value);
+        }
+        Ok(values)
+    }
+
+    fn root_key_path(root_key: Option<&str>) -> Vec<&str> {
+        root_key
+            .map(|key| {
+                key.split('.')
+                    .map(str::trim)
+                    .filter(|part| !part.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
 
-        for line in content.lines() {
-            let line = line.trim();
+    /// "database.password" (or "$.database.password") -> ["database", "password"].
+    /// Plain segments only, so the matching `$.` JSONPath needs no quoting.
+    fn property_path(path: &str) -> Result<Vec<String>, ZapError> {
+        let trimmed = path.trim();
+        let segments: Vec<String> = trimmed
+            .strip_prefix("$.")
+            .unwrap_or(trimmed)
+            .split('.')
+            .map(|segment| segment.trim().to_string())
+            .collect();
 
-            if line.is_empty() || line.starts_with('#') {
-                continue;
+        let plain = |segment: &String| {
+            !segment.is_empty()
+                && segment
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '_')
+        };
+        if !segments.iter().all(plain) {
+            return Err(ZapError::ValidationError(format!(
+                "Property path '{}' must be letters, digits or underscores separated by dots",
+                path
+            )));
+        }
+        Ok(segments)
+    }
+
+    /// Nest `value` under `path`, refusing paths that land on or inside another secret's value
+    fn insert_property(
+        document: &mut Map<String, Value>,
+        path: &[String],
+        value: String,
+        secret_name: &str,
+    ) -> Result<(), ZapError> {
+        let overlaps = || {
+            ZapError::ValidationError(format!(
+                "Property '{}' of '{}' overlaps another secret's property",
+                path.join("."),
+                secret_name
+            ))
+        };
+
+        let Some((last, parents)) = path.split_last() else {
+            return Err(overlaps());
+        };
+        let mut node = document;
+        for segment in parents {
+            let child = node
+                .entry(segment.clone())
+                .or_insert_with(|| Value::Object(Map::new()));
+            node = match child {
+                Value::Object(map) => map,
+                _ => return Err(overlaps()),
+            };
+        }
+
+        if node.contains_key(last) {
+            return Err(overlaps());
+        }
+        node.insert(last.clone(), Value::String(value));
+        Ok(())
+    }
+
+    /// Flatten nested tables into `parent_child` keys with scalar values
+    fn flatten_config_value(
+        value: &Value,
+        prefix: String,
+        entries: &mut Vec<(String, String)>,
+        result: &mut ImportResult,
+    ) {
+        let scalar = match value {
+            Value::Object(map) => {
+                for (key, child) in map {
+                    let child_key = if prefix.is_empty() {
+                        key.clone()
+                    } else {
+                        format!("{}_{}", prefix, key)
+                    };
+                    Self::flatten_config_value(child, child_key, entries, result);
+                }
+                return;
+            }
+            Value::String(text) => text.clone(),
+            Value::Number(number) => number.to_string(),
+            Value::Bool(flag) => flag.to_string(),
+            Value::Null => return,
+            Value::Array(_) => {
+                result.add_error(format!("Skipped '{}': lists are not supported", prefix));
+                return;
+            }
+        };
+
+        if prefix.is_empty() {
+            result.add_error("Skipped top-level value without a key".to_string());
+        } else {
+            entries.push((prefix, scalar));
+        }
+    }
+
+    /// Split pasted text into named values. Later repeats of a name replace earlier ones.
+    fn parse_pasted(&self, content: &str) -> Result<PastedSecrets, ZapError> {
+        let format = Self::detect_paste_format(content);
+        let mut warnings = Vec::new();
+
+        let secrets: Vec<PastedSecret> = match format {
+            SmartImportFormat::Env => {
+                let parsed = parse_env(content, |_| None)?;
+                warnings.extend(parsed.warnings);
+                parsed
+                    .vars
+                    .into_iter()
+                    .map(|(env_name, value)| PastedSecret {
+                        name: self.env_var_to_secret_name(&env_name),
+                        value,
+                        env_name: Some(env_name),
+                    })
+                    .collect()
+            }
+            SmartImportFormat::Json => {
+                let document: Value = serde_json::from_str(content.trim_start_matches('\u{feff}'))
+                    .map_err(|e| ZapError::ValidationError(format!("Invalid JSON: {}", e)))?;
+                let mut skipped = ImportResult::new();
+                let mut entries = Vec::new();
+                Self::flatten_config_value(&document, String::new(), &mut entries, &mut skipped);
+                warnings.extend(skipped.errors);
+                entries
+                    .into_iter()
+                    .map(|(key, value)| PastedSecret {
+                        name: self.env_var_to_secret_name(&key),
+                        value,
+                        env_name: None,
+                    })
+                    .collect()
+            }
+            SmartImportFormat::KeyValue => {
+                let mut secrets = Vec::new();
+                for (index, line) in content.lines().enumerate() {
+                    let line = line.trim();
+                    if line.is_empty() || line.starts_with('#') {
+                        continue;
+                    }
+                    let Some((key, value)) = line.split_once(':') else {
+                        warnings.push(format!("Line {}: expected 'key: value'", index + 1));
+                        continue;
+                    };
+                    secrets.push(PastedSecret {
+                        name: self.env_var_to_secret_name(key.trim()),
+                        value: Self::unquote(value.trim()).to_string(),
+                        env_name: None,
+                    });
+                }
+                secrets
             }
+            SmartImportFormat::Csv => {
+                let content = content.trim_start_matches('\u{feff}');
+                let mut rows =
+                    Self::parse_csv_content(content, Self::detect_csv_delimiter(content))?;
+                if rows.iter().all(|row| row.len() < 2) {
+                    return Err(ZapError::ValidationError(
+                        "Unrecognized format - paste .env, JSON, CSV or key: value lines"
+                            .to_string(),
+                    ));
+                }
 
-            if let Some(eq_pos) = line.find('=') {
-                let key = line[..eq_pos].trim().to_string();
-                let value = line[eq_pos + 1..].trim();
+                // A header row is only recognized by its first column
+                let has_header = rows.first().and_then(|row| row.first()).is_some_and(|cell| {
+                    ["name", "key", "variable", "secret"]
+                        .iter()
+                        .any(|header| cell.trim().eq_ignore_ascii_case(header))
+                });
+                if has_header {
+                    rows.remove(0);
+                }
 
-                let value = if (value.starts_with('"') && value.ends_with('"'))
-                    || (value.starts_with('\'') && value.ends_with('\''))
-                {
-                    value[1..value.len() - 1].replace("\\\"", "\"")
-                } else {
-                    value.to_string()
-                };
+                let first_row = if has_header { 2 } else { 1 };
+                let mut secrets = Vec::new();
+                for (offset, row) in rows.into_iter().enumerate() {
+                    let mut columns = row.into_iter();
+                    let (Some(name), Some(value)) = (columns.next(), columns.next()) else {
+                        warnings.push(format!(
+                            "Row {}: missing name or value column",
+                            first_row + offset
+                        ));
+                        continue;
+                    };
+                    secrets.push(PastedSecret {
+                        name: name.trim().to_string(),
+                        value,
+                        env_name: None,
+                    });
+                }
+                secrets
+            }
+        };
 
-                if !key.is_empty() {
-                    env_vars.push((key, value));
+        let mut positions: HashMap<String, usize> = HashMap::new();
+        let mut unique: Vec<PastedSecret> = Vec::new();
+        for secret in secrets {
+            match positions.get(&name_key(&secret.name)) {
+                Some(&index) => {
+                    warnings.push(format!(
+                        "'{}' appears more than once, the last value is used",
+                        secret.name
+                    ));
+                    unique[index] = secret;
+                }
+                None => {
+                    positions.insert(name_key(&secret.name), unique.len());
+                    unique.push(secret);
                 }
             }
         }
 
-        Ok(env_vars)
+        if unique.is_empty() {
+            return Err(ZapError::StorageError(
+                "No values found in pasted content".to_string(),
+            ));
+        }
+
+        Ok(PastedSecrets {
+            format,
+            secrets: unique,
+            warnings,
+        })
+    }
+
+    /// Judge by the first meaningful line: a JSON object, `NAME=value`, `key: value`, else CSV
+    fn detect_paste_format(content: &str) -> SmartImportFormat {
+        let content = content.trim_start_matches('\u{feff}').trim();
+        if content.starts_with('{') && serde_json::from_str::<Map<String, Value>>(content).is_ok() {
+            return SmartImportFormat::Json;
+        }
+
+        let first_line = content
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with('#'))
+            .unwrap_or_default();
+        let is_key = |key: &str, allow_spaces: bool| {
+            !key.is_empty()
+                && key.chars().all(|c| {
+                    c.is_alphanumeric()
+                        || matches!(c, '_' | '-' | '.')
+                        || (allow_spaces && c == ' ')
+                })
+        };
+
+        match (first_line.split_once('='), first_line.split_once(':')) {
+            (Some((key, _)), _) if is_key(key.trim().trim_start_matches("export "), false) => {
+                SmartImportFormat::Env
+            }
+            (_, Some((key, _))) if is_key(key.trim(), true) => SmartImportFormat::KeyValue,
+            _ => SmartImportFormat::Csv,
+        }
+    }
+
+    // Reject what would fail on save, so the preview can show it up front
+    fn check_pasted_secret(&self, secret: &PastedSecret) -> Result<(), String> {
+        Secret::validate_name(&secret.name).map_err(|e| e.to_string())?;
+        if secret.value.trim().is_empty() {
+            return Err("value is empty".to_string());
+        }
+        secret_value_size(&secret.value, self.storage.max_secret_value_bytes())
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    fn unquote(value: &str) -> &str {
+        for quote in ['"', '\''] {
+            if let Some(inner) = value
+                .strip_prefix(quote)
+                .and_then(|rest| rest.strip_suffix(quote))
+            {
+                return inner;
+            }
+        }
+        value
+    }
+
+    /// Pick the most frequent candidate delimiter in the first line (outside quotes)
+    fn detect_csv_delimiter(content: &str) -> char {
+        let first_line = content.lines().next().unwrap_or_default();
+        let mut in_quotes = false;
+        let mut counts = [(',', 0), (';', 0), ('\t', 0), ('|', 0)];
+
+        for c in first_line.chars() {
+            if c == '"' {
+                in_quotes = !in_quotes;
+            } else if !in_quotes {
+                if let Some(entry) = counts.iter_mut().find(|(d, _)| *d == c) {
+                    entry.1 += 1;
+                }
+            }
+        }
+
+        counts
+            .iter()
+            .filter(|(_, count)| *count > 0)
+            .max_by_key(|(_, count)| *count)
+            .map(|(d, _)| *d)
+            .unwrap_or(',')
+    }
+
+    /// Parse CSV content (RFC 4180 quoting, quoted fields may span lines)
+    fn parse_csv_content(content: &str, delimiter: char) -> Result<Vec<Vec<String>>, ZapError> {
+        let mut rows = Vec::new();
+        let mut row = Vec::new();
+        let mut field = String::new();
+        let mut in_quotes = false;
+        let mut chars = content.trim_start_matches('\u{feff}').chars().peekable();
+
+        while let Some(c) = chars.next() {
+            if in_quotes {
+                match c {
+                    '"' if chars.peek() == Some(&'"') => {
+                        field.push('"');
+                        chars.next();
+                    }
+                    '"' => in_quotes = false,
+                    _ => field.push(c),
+                }
+                continue;
+            }
+
+            match c {
+                '"' if field.is_empty() => in_quotes = true,
+                '\r' => {}
+                '\n' => {
+                    row.push(std::mem::take(&mut field));
+                    rows.push(std::mem::take(&mut row));
+                }
+                c if c == delimiter => row.push(std::mem::take(&mut field)),
+                _ => field.push(c),
+            }
+        }
+
+        if in_quotes {
+            return Err(ZapError::ValidationError(
+                "CSV content has an unterminated quoted field".to_string(),
+            ));
+        }
+
+        if !field.is_empty() || !row.is_empty() {
+            row.push(field);
+            rows.push(row);
+        }
+
+        // Skip blank lines
+        rows.retain(|row| row.iter().any(|field| !field.trim().is_empty()));
+        Ok(rows)
+    }
+
+    fn resolve_csv_column(column: &CsvColumn, headers: &[String]) -> Result<usize, ZapError> {
+        match column {
+            CsvColumn::Index(index) => Ok(*index),
+            CsvColumn::Header(name) => headers
+                .iter()
+                .position(|header| header.trim().eq_ignore_ascii_case(name.trim()))
+                .ok_or_else(|| {
+                    ZapError::ValidationError(format!("CSV column '{}' not found", name))
+                }),
+        }
     }
 
     /// Convert environment variable name to readable secret name
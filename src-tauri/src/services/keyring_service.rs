@@ -0,0 +1,97 @@
+// src/services/keyring_service.rs
+
+use crate::models::ZapError;
+use crate::utils::path_resolvers::APP_IDENTIFIER;
+use keyring::Entry;
+
+const WRAP_KEY_ACCOUNT: &str = "master-key-wrap";
+// Boot time is derived from the clock minus uptime, so it drifts by a second or two and jumps
+// with clock adjustments. A reboot moves it by far more than this.
+const BOOT_TIME_TOLERANCE_SECONDS: u64 = 5;
+
+pub struct KeyringService;
+
+impl KeyringService {
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn entry(&self) -> Result<Entry, ZapError> {
+        Entry::new(APP_IDENTIFIER, WRAP_KEY_ACCOUNT)
+            .map_err(|e| ZapError::AuthError(format!("OS keyring unavailable: {}", e)))
+    }
+
+    // Generate a fresh wrap key and store it in the OS keyring
+    pub fn create_wrap_key(&self) -> Result<[u8; 32], ZapError> {
+        use rand::RngCore;
+        let mut wrap_key = [0u8; 32];
+        rand::rng().fill_bytes(&mut wrap_key);
+
+        self.entry()?
+            .set_password(&hex::encode(wrap_key))
+            .map_err(|e| ZapError::AuthError(format!("Failed to store key in OS keyring: {}", e)))?;
+
+        Ok(wrap_key)
+    }
+
+    // Read the wrap key back (None if it was never stored or has been removed)
+    pub fn load_wrap_key(&self) -> Result<Option<[u8; 32]>, ZapError> {
+        let stored = match self.entry()?.get_password() {
+            Ok(stored) => stored,
+            Err(keyring::Error::NoEntry) => return Ok(None),
+            Err(e) => {
+                return Err(ZapError::AuthError(format!(
+                    "Failed to read OS keyring: {}",
+                    e
+                )))
+            }
+        };
+
+        let key_bytes = hex::decode(stored.trim())?;
+        if key_bytes.len() != 32 {
+            return Ok(None);
+        }
+
+        let mut wrap_key = [0u8; 32];
+        wrap_key.copy_from_slice(&key_bytes);
+        Ok(Some(wrap_key))
+    }
+
+    // Remove the wrap key from the OS keyring
+    pub fn delete_wrap_key(&self) -> Result<(), ZapError> {
+        match self.entry()?.delete_credential() {
+            Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(ZapError::AuthError(format!(
+                "Failed to clear OS keyring: {}",
+                e
+            ))),
+        }
+    }
+
+    // Whether the OS keyring can be read (a missing entry still counts as available)
+    pub fn check_available(&self) -> Result<(), ZapError> {
+        match self.entry()?.get_password() {
+            Ok(_) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(ZapError::AuthError(format!(
+                "OS keyring unavailable: {}",
+                e
+            ))),
+        }
+    }
+
+    // Boot time in seconds - changes on every reboot
+    pub fn current_boot_time(&self) -> u64 {
+        sysinfo::System::boot_time()
+    }
+
+    // Whether `boot_time` was recorded since the last reboot
+    pub fn is_current_boot(&self, boot_time: u64) -> bool {
+        self.current_boot_time().abs_diff(boot_time) <= BOOT_TIME_TOLERANCE_SECONDS
+    }
+}
+
+impl Default for KeyringService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,553 @@
+// src/services/lan_transfer_service.rs - One-shot transfer of a vault export to a device on
+// the same network. The sender listens on TLS with a throwaway certificate and advertises
+// itself over mDNS under the code's public part. The receiver pins that certificate (from the
+// link, or the mDNS record) and proves it knows the code's secret part before anything is
+// sent; a single wrong guess stops the transfer. The export itself is sealed again with the
+// code, so a spoofed mDNS record gets nothing out of a receiver either.
+
+use crate::models::transfer_model::{
+    TRANSFER_LINK_PREFIX, TRANSFER_PROTOCOL_VERSION, TRANSFER_SERVICE_TYPE, TRANSFER_TTL_MINUTES,
+};
+use crate::models::{
+    ImportResult, KdfParams, LanTransferOffer, LanTransferReceipt, TransferMessage, ZapError,
+};
+use crate::services::share_service::{normalize_passphrase, PASSPHRASE_ALPHABET};
+use crate::services::CryptoService;
+use chrono::{DateTime, Utc};
+use mdns_sd::{ServiceDaemon, ServiceEvent, ServiceInfo};
+use rand::Rng;
+use reqwest::Url;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer, ServerName, UnixTime};
+use rustls::{ClientConfig, DigitallySignedStruct, ServerConfig, SignatureScheme};
+use sha2::{Digest, Sha256};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr, UdpSocket};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_rustls::{TlsAcceptor, TlsConnector};
+
+// `xxxx-xxxxx-xxxxx`: the first group finds the sender over mDNS, the rest is the secret
+const CODE_ID_LENGTH: usize = 4;
+const CODE_SECRET_LENGTH: usize = 10;
+
+const TLS_SERVER_NAME: &str = "zap-transfer.local";
+const ID_PROPERTY: &str = "id";
+const FINGERPRINT_PROPERTY: &str = "fp";
+const HELLO_MESSAGE: &str = "zap:transfer:hello";
+const HELLO_CONTEXT: &[u8] = b"zap:transfer:hello";
+const PAYLOAD_CONTEXT: &[u8] = b"zap:transfer:payload";
+
+const MAX_FRAME_BYTES: usize = 64 * 1024 * 1024;
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(15);
+const DISCOVERY_TIMEOUT: Duration = Duration::from_secs(10);
+const EXPORT_TIMEOUT: Duration = Duration::from_secs(5 * 60);
+const IMPORT_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+
+/// The sending side of one transfer, from `open` until a receiver has imported it
+pub struct TransferSender {
+    code: String,
+    key: [u8; 32],
+    fingerprint: [u8; 32],
+    listener: TcpListener,
+    acceptor: TlsAcceptor,
+    mdns: ServiceDaemon,
+    expires_at: DateTime<Utc>,
+}
+
+impl TransferSender {
+    /// Listen on a fresh port under a fresh code and certificate, and advertise it
+    pub async fn open() -> Result<Self, ZapError> {
+        let code = generate_code();
+        let (id, secret) = split_code(&code)?;
+
+        let certified = rcgen::generate_simple_self_signed(vec![TLS_SERVER_NAME.to_string()])
+            .map_err(|e| {
+                ZapError::CryptoError(format!("Couldn't create a transfer certificate: {}", e))
+            })?;
+        let certificate = certified.cert.der().clone();
+        let fingerprint: [u8; 32] = Sha256::digest(certificate.as_ref()).into();
+        let private_key =
+            PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(certified.key_pair.serialize_der()));
+        let config = ServerConfig::builder_with_provider(tls_provider())
+            .with_protocol_versions(&[&rustls::version::TLS13])
+            .map_err(tls_error)?
+            .with_no_client_auth()
+            .with_single_cert(vec![certificate], private_key)
+            .map_err(tls_error)?;
+
+        let listener = TcpListener::bind((Ipv4Addr::UNSPECIFIED, 0)).await?;
+        let port = listener.local_addr()?.port();
+
+        let mdns = ServiceDaemon::new().map_err(mdns_error)?;
+        let instance = format!("zap-{}", id);
+        let fingerprint_hex = hex::encode(fingerprint);
+        let properties = [
+            (ID_PROPERTY, id.as_str()),
+            (FINGERPRINT_PROPERTY, fingerprint_hex.as_str()),
+        ];
+        let service = ServiceInfo::new(
+            TRANSFER_SERVICE_TYPE,
+            &instance,
+            &format!("{}.local.", instance),
+            "",
+            port,
+            &properties[..],
+        )
+        .map_err(mdns_error)?
+        .enable_addr_auto();
+        mdns.register(service).map_err(mdns_error)?;
+
+        Ok(Self {
+            key: derive_key(&secret, &fingerprint)?,
+            code,
+            fingerprint,
+            listener,
+            acceptor: TlsAcceptor::from(Arc::new(config)),
+            mdns,
+            expires_at: Utc::now() + chrono::Duration::minutes(TRANSFER_TTL_MINUTES),
+        })
+    }
+
+    /// What the sending device shows, `job_id` is left for the caller
+    pub fn offer(&self) -> LanTransferOffer {
+        let link = self.listener.local_addr().ok().and_then(|address| {
+            let ip = lan_address()?;
+            Some(format!(
+                "{}{}:{}?code={}&fp={}",
+                TRANSFER_LINK_PREFIX,
+                ip,
+                address.port(),
+                self.code,
+                hex::encode(self.fingerprint)
+            ))
+        });
+        let qr_svg = link.as_deref().and_then(render_qr);
+
+        LanTransferOffer {
+            job_id: String::new(),
+            code: self.code.clone(),
+            link,
+            qr_svg,
+            expires_at: self.expires_at,
+        }
+    }
+
+    /// Wait for the receiver, then send what `payload` exports for it (called with the
+    /// receiver's address) and wait until it has been imported
+    pub async fn serve<C, P>(
+        &self,
+        check_cancelled: C,
+        payload: P,
+    ) -> Result<LanTransferReceipt, ZapError>
+    where
+        C: Fn() -> Result<(), ZapError>,
+        P: FnOnce(IpAddr) -> Result<String, ZapError>,
+    {
+        let crypto = CryptoService::new();
+        let (mut stream, peer) = loop {
+            check_cancelled()?;
+            if Utc::now() >= self.expires_at {
+                return Err(ZapError::ValidationError(
+                    "The transfer code expired before another device used it".to_string(),
+                ));
+            }
+            let Ok(accepted) = tokio::time::timeout(POLL_INTERVAL, self.listener.accept()).await
+            else {
+                continue;
+            };
+            let (tcp, peer) = accepted?;
+
+            // Port scans and connections that drop halfway don't end the transfer
+            let handshake = tokio::time::timeout(HANDSHAKE_TIMEOUT, self.acceptor.accept(tcp));
+            let Ok(Ok(mut stream)) = handshake.await else {
+                continue;
+            };
+            let Ok(TransferMessage::Hello { version, proof }) =
+                read_message(&mut stream, HANDSHAKE_TIMEOUT).await
+            else {
+                continue;
+            };
+
+            if version != TRANSFER_PROTOCOL_VERSION {
+                let reason = "The two devices run incompatible versions of Zap".to_string();
+                reject(&mut stream, &reason).await;
+                return Err(ZapError::ValidationError(reason));
+            }
+            let proven = crypto.decrypt_bound(&proof, &self.key, HELLO_CONTEXT).ok();
+            if proven.as_deref() != Some(HELLO_MESSAGE) {
+                // One guess only, the code would be open to brute force otherwise
+                reject(&mut stream, "Wrong transfer code").await;
+                return Err(ZapError::AuthError(
+                    "A device tried a wrong transfer code, the transfer was stopped".to_string(),
+                ));
+            }
+            break (stream, peer.ip());
+        };
+        let _ = self.mdns.shutdown();
+
+        let json = match payload(peer) {
+            Ok(json) => json,
+            Err(e) => {
+                reject(&mut stream, "The sending device couldn't export").await;
+                return Err(e);
+            }
+        };
+        let data = crypto.encrypt_bound(&json, &self.key, PAYLOAD_CONTEXT)?;
+        write_message(&mut stream, &TransferMessage::Payload { data }).await?;
+
+        match read_message(&mut stream, IMPORT_TIMEOUT).await? {
+            TransferMessage::Received(receipt) => Ok(receipt),
+            TransferMessage::Rejected { reason } => Err(ZapError::PlatformError(format!(
+                "The receiving device couldn't import: {}",
+                reason
+            ))),
+            _ => Err(unexpected_message()),
+        }
+    }
+}
+
+impl Drop for TransferSender {
+    fn drop(&mut self) {
+        let _ = self.mdns.shutdown();
+    }
+}
+
+/// Receive a transfer by its code (found over mDNS) or link, hand the export JSON to
+/// `import` and tell the sender how that went
+pub async fn receive<F>(code_or_link: &str, import: F) -> Result<ImportResult, ZapError>
+where
+    F: FnOnce(&str) -> Result<ImportResult, ZapError>,
+{
+    let (secret, addresses, fingerprint) = match code_or_link.trim() {
+        link if link.starts_with(TRANSFER_LINK_PREFIX) => {
+            let (code, address, fingerprint) = parse_link(link)?;
+            let (_, secret) = split_code(&code)?;
+            (secret, vec![address], fingerprint)
+        }
+        code => {
+            let (id, secret) = split_code(code)?;
+            let (addresses, fingerprint) = discover(&id).await?;
+            (secret, addresses, fingerprint)
+        }
+    };
+    let key = derive_key(&secret, &fingerprint)?;
+    let mut stream = connect(&addresses, fingerprint).await?;
+
+    let crypto = CryptoService::new();
+    let hello = TransferMessage::Hello {
+        version: TRANSFER_PROTOCOL_VERSION,
+        proof: crypto.encrypt_bound(HELLO_MESSAGE, &key, HELLO_CONTEXT)?,
+    };
+    write_message(&mut stream, &hello).await?;
+
+    let data = match read_message(&mut stream, EXPORT_TIMEOUT).await? {
+        TransferMessage::Payload { data } => data,
+        TransferMessage::Rejected { reason } => {
+            return Err(ZapError::ValidationError(format!(
+                "The sending device refused the transfer: {}",
+                reason
+            )))
+        }
+        _ => return Err(unexpected_message()),
+    };
+    let json = crypto
+        .decrypt_bound(&data, &key, PAYLOAD_CONTEXT)
+        .map_err(|_| ZapError::CryptoError("The transfer was damaged on the way".to_string()))?;
+
+    let result = import(&json);
+    let reply = match &result {
+        Ok(imported) => TransferMessage::Received(LanTransferReceipt {
+            boxes_imported: imported.boxes_imported,
+            secrets_imported: imported.secrets_imported,
+            errors: imported.errors.clone(),
+        }),
+        Err(e) => TransferMessage::Rejected {
+            reason: e.to_string(),
+        },
+    };
+    let _ = write_message(&mut stream, &reply).await;
+    let _ = stream.shutdown().await;
+    result
+}
+
+// Shown as `xxxx-xxxxx-xxxxx`
+fn generate_code() -> String {
+    let mut rng = rand::rng();
+    let chars: String = (0..CODE_ID_LENGTH + CODE_SECRET_LENGTH)
+        .map(|_| PASSPHRASE_ALPHABET[rng.random_range(0..PASSPHRASE_ALPHABET.len())] as char)
+        .collect();
+    let (id, secret) = chars.split_at(CODE_ID_LENGTH);
+    format!("{}-{}-{}", id, &secret[..5], &secret[5..])
+}
+
+// (id, secret), typed back with any case, dashes or spaces
+fn split_code(code: &str) -> Result<(String, String), ZapError> {
+    let code = normalize_passphrase(code);
+    if code.len() != CODE_ID_LENGTH + CODE_SECRET_LENGTH {
+        return Err(ZapError::ValidationError(
+            "A transfer code looks like xxxx-xxxxx-xxxxx".to_string(),
+        ));
+    }
+    let (id, secret) = code.split_at(CODE_ID_LENGTH);
+    Ok((id.to_string(), secret.to_string()))
+}
+
+// `zap-transfer://<ip>:<port>?code=<code>&fp=<certificate sha256>`
+fn parse_link(link: &str) -> Result<(String, SocketAddr, [u8; 32]), ZapError> {
+    let invalid = || ZapError::ValidationError("Not a Zap transfer link".to_string());
+    let url = Url::parse(link).map_err(|_| invalid())?;
+    let ip: IpAddr = url
+        .host_str()
+        .map(|host| host.trim_matches(['[', ']']))
+        .and_then(|host| host.parse().ok())
+        .ok_or_else(invalid)?;
+    let port = url.port().ok_or_else(invalid)?;
+
+    let query = |name: &str| {
+        url.query_pairs()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.into_owned())
+    };
+    let code = query("code").ok_or_else(invalid)?;
+    let fingerprint = query("fp")
+        .as_deref()
+        .and_then(parse_fingerprint)
+        .ok_or_else(invalid)?;
+    Ok((code, SocketAddr::new(ip, port), fingerprint))
+}
+
+fn parse_fingerprint(fingerprint: &str) -> Option<[u8; 32]> {
+    hex::decode(fingerprint).ok()?.try_into().ok()
+}
+
+// A spoofed sender could try guesses against the receiver's proof offline, so the secret is
+// stretched like a share passphrase; the fingerprint ties the key to one certificate
+fn derive_key(secret: &str, fingerprint: &[u8; 32]) -> Result<[u8; 32], ZapError> {
+    CryptoService::new().derive_key(secret, fingerprint, &KdfParams::default())
+}
+
+// Addresses and certificate fingerprint of the sender advertising `id`
+async fn discover(id: &str) -> Result<(Vec<SocketAddr>, [u8; 32]), ZapError> {
+    let mdns = ServiceDaemon::new().map_err(mdns_error)?;
+    let events = mdns.browse(TRANSFER_SERVICE_TYPE).map_err(mdns_error)?;
+    let found = tokio::time::timeout(DISCOVERY_TIMEOUT, async {
+        while let Ok(event) = events.recv_async().await {
+            let ServiceEvent::ServiceResolved(service) = event else {
+                continue;
+            };
+            if service.get_property_val_str(ID_PROPERTY) != Some(id) {
+                continue;
+            }
+            let Some(fingerprint) = service
+                .get_property_val_str(FINGERPRINT_PROPERTY)
+                .and_then(parse_fingerprint)
+            else {
+                continue;
+            };
+            let mut addresses: Vec<SocketAddr> = service
+                .get_addresses()
+                .iter()
+                .map(|ip| SocketAddr::new(*ip, service.get_port()))
+                .collect();
+            // The sender only listens on IPv4
+            addresses.sort_by_key(|address| !address.is_ipv4());
+            return Some((addresses, fingerprint));
+        }
+        None
+    })
+    .await;
+    let _ = mdns.shutdown();
+
+    found.ok().flatten().ok_or_else(|| {
+        ZapError::ValidationError(
+            "No device on this network is offering a transfer with that code".to_string(),
+        )
+    })
+}
+
+async fn connect(
+    addresses: &[SocketAddr],
+    fingerprint: [u8; 32],
+) -> Result<tokio_rustls::client::TlsStream<TcpStream>, ZapError> {
+    let provider = tls_provider();
+    let config = ClientConfig::builder_with_provider(Arc::clone(&provider))
+        .with_protocol_versions(&[&rustls::version::TLS13])
+        .map_err(tls_error)?
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(PinnedCertificate {
+            fingerprint,
+            provider,
+        }))
+        .with_no_client_auth();
+    let connector = TlsConnector::from(Arc::new(config));
+    let server_name = ServerName::try_from(TLS_SERVER_NAME).expect("valid server name");
+
+    let mut last_error = "no address".to_string();
+    for address in addresses {
+        let tcp = match tokio::time::timeout(HANDSHAKE_TIMEOUT, TcpStream::connect(address)).await {
+            Ok(Ok(tcp)) => tcp,
+            Ok(Err(e)) => {
+                last_error = e.to_string();
+                continue;
+            }
+            Err(_) => {
+                last_error = "timed out".to_string();
+                continue;
+            }
+        };
+        let handshake = connector.connect(server_name.clone(), tcp);
+        return match tokio::time::timeout(HANDSHAKE_TIMEOUT, handshake).await {
+            Ok(Ok(stream)) => Ok(stream),
+            Ok(Err(e)) => Err(ZapError::CryptoError(format!(
+                "Secure connection to the sending device failed: {}",
+                e
+            ))),
+            Err(_) => Err(ZapError::PlatformError(
+                "The sending device stopped responding".to_string(),
+            )),
+        };
+    }
+    Err(ZapError::PlatformError(format!(
+        "Couldn't reach the sending device: {}",
+        last_error
+    )))
+}
+
+// Accepts exactly the certificate the code or link named, there is no CA to ask
+#[derive(Debug)]
+struct PinnedCertificate {
+    fingerprint: [u8; 32],
+    provider: Arc<CryptoProvider>,
+}
+
+impl ServerCertVerifier for PinnedCertificate {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        if Sha256::digest(end_entity.as_ref()).as_slice() != self.fingerprint {
+            return Err(rustls::Error::General(
+                "certificate doesn't match the transfer".to_string(),
+            ));
+        }
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls12_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        rustls::crypto::verify_tls13_signature(
+            message,
+            cert,
+            dss,
+            &self.provider.signature_verification_algorithms,
+        )
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.provider
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+// Length-prefixed JSON
+async fn write_message<S>(stream: &mut S, message: &TransferMessage) -> Result<(), ZapError>
+where
+    S: AsyncWrite + Unpin,
+{
+    let frame = serde_json::to_vec(message)?;
+    stream.write_u32(frame.len() as u32).await?;
+    stream.write_all(&frame).await?;
+    stream.flush().await?;
+    Ok(())
+}
+
+async fn read_message<S>(stream: &mut S, wait: Duration) -> Result<TransferMessage, ZapError>
+where
+    S: AsyncRead + Unpin,
+{
+    let read = async {
+        let length = stream.read_u32().await? as usize;
+        if length > MAX_FRAME_BYTES {
+            return Err(ZapError::ValidationError(
+                "The other device sent too much data".to_string(),
+            ));
+        }
+        let mut frame = vec![0; length];
+        stream.read_exact(&mut frame).await?;
+        serde_json::from_slice(&frame).map_err(|_| unexpected_message())
+    };
+    match tokio::time::timeout(wait, read).await {
+        Ok(message) => message,
+        Err(_) => Err(ZapError::PlatformError(
+            "The other device stopped responding".to_string(),
+        )),
+    }
+}
+
+async fn reject<S>(stream: &mut S, reason: &str)
+where
+    S: AsyncWrite + Unpin,
+{
+    let reason = reason.to_string();
+    let _ = write_message(stream, &TransferMessage::Rejected { reason }).await;
+}
+
+// The address the LAN route leaves from, for the link. Connecting a UDP socket sends nothing.
+fn lan_address() -> Option<IpAddr> {
+    let socket = UdpSocket::bind((Ipv4Addr::UNSPECIFIED, 0)).ok()?;
+    socket.connect("224.0.0.251:5353").ok()?;
+    let ip = socket.local_addr().ok()?.ip();
+    (!ip.is_unspecified() && !ip.is_loopback()).then_some(ip)
+}
+
+fn render_qr(link: &str) -> Option<String> {
+    let code = qrcode::QrCode::new(link.as_bytes()).ok()?;
+    Some(
+        code.render::<qrcode::render::svg::Color>()
+            .min_dimensions(240, 240)
+            .build(),
+    )
+}
+
+fn tls_provider() -> Arc<CryptoProvider> {
+    Arc::new(rustls::crypto::ring::default_provider())
+}
+
+fn tls_error(e: rustls::Error) -> ZapError {
+    ZapError::CryptoError(format!("TLS setup failed: {}", e))
+}
+
+fn mdns_error(e: mdns_sd::Error) -> ZapError {
+    ZapError::PlatformError(format!("Network discovery failed: {}", e))
+}
+
+fn unexpected_message() -> ZapError {
+    ZapError::ValidationError("The other device sent something unexpected".to_string())
+}
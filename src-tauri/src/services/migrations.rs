@@ -0,0 +1,82 @@
+// src/services/migrations.rs - Ordered vault data migrations, each run once at startup
+
+use crate::models::{LogAction, ZapError};
+use crate::services::StorageService;
+use crate::utils::path_resolvers::get_migration_backup_path;
+
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+pub struct Migration {
+    pub version: u32,
+    pub name: &'static str,
+    pub run: fn(&StorageService) -> Result<(), ZapError>,
+}
+
+// Append only - a released migration keeps its version and never changes.
+// Migrations must be safe to re-run: a crash before the version is saved runs it again.
+pub const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "Normalize box and secret name indexes",
+        run: StorageService::rebuild_name_indexes,
+    },
+    Migration {
+        version: 2,
+        name: "Move large secret values out of secret records",
+        run: StorageService::move_large_secret_values,
+    },
+];
+
+pub fn latest_version() -> u32 {
+    MIGRATIONS.last().map_or(0, |migration| migration.version)
+}
+
+/// Bring the vault up to the latest schema, oldest migration first.
+/// The vault is copied aside before the first pending migration runs.
+pub fn run_pending(storage: &StorageService) -> Result<(), ZapError> {
+    let current = storage.schema_version()?;
+    let latest = latest_version();
+    if current > latest {
+        return Err(ZapError::StorageError(format!(
+            "Vault schema version {} is newer than this version of Zap supports ({})",
+            current, latest
+        )));
+    }
+
+    let pending: Vec<&Migration> = MIGRATIONS
+        .iter()
+        .filter(|migration| migration.version > current)
+        .collect();
+    if pending.is_empty() {
+        return Ok(());
+    }
+
+    // A new vault has nothing to migrate
+    if storage.vault_is_empty()? {
+        return storage.set_schema_version(latest);
+    }
+
+    let backup_path = get_migration_backup_path(current)
+        .map_err(|e| ZapError::StorageError(format!("Failed to get backup path: {}", e)))?;
+    storage.copy_vault_to(&backup_path)?;
+
+    for migration in pending {
+        (migration.run)(storage).map_err(|e| {
+            ZapError::StorageError(format!(
+                "Migration {} ({}) failed: {}. The vault from before the upgrade is at {}",
+                migration.version,
+                migration.name,
+                e,
+                backup_path.display()
+            ))
+        })?;
+        storage.set_schema_version(migration.version)?;
+
+        let _ = storage.log(
+            LogAction::MigrateVault,
+            format!("Vault migrated to schema version {}", migration.version),
+            Some(migration.name.to_string()),
+        );
+    }
+    Ok(())
+}
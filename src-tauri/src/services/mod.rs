@@ -1,14 +1,35 @@
 // src/services/mod.rs
 
 pub mod auth_service;
+pub mod breach_service;
 pub mod crypto_service; 
 pub mod dev_service; 
+pub mod generator_service;
+pub mod github_service;
+pub mod gitlab_service;
+pub mod hardware_key_service;
+pub mod hook_service;
 pub mod import_export_service; 
+pub mod keyring_service;
+pub mod lan_transfer_service;
+pub mod migrations;
+pub mod share_service;
+pub mod stats_service;
 pub mod storage_service;
+pub mod sync;
 
 // Re-export services
 pub use auth_service::AuthService;
+pub use breach_service::BreachService;
 pub use crypto_service::CryptoService;
 pub use dev_service::DevService;
+pub use generator_service::GeneratorService;
+pub use github_service::GithubService;
+pub use gitlab_service::GitlabService;
+pub use hardware_key_service::HardwareKeyService;
+pub use hook_service::HookService;
 pub use import_export_service::ImportExportService;
-pub use storage_service::{StorageService, VaultStats};
+pub use keyring_service::KeyringService;
+pub use share_service::ShareService;
+pub use stats_service::StatsService;
+pub use storage_service::StorageService;
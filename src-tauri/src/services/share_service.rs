@@ -0,0 +1,148 @@
+// src/services/share_service.rs - Passphrase-sealed, expiring one-time secret shares
+
+use crate::models::share_model::{
+    MAX_SHARE_TTL_MINUTES, SHARE_BLOB_PREFIX, SHARE_FORMAT_VERSION,
+};
+use crate::models::{EncryptedData, KdfParams, OneTimeShare, SharePayload, ZapError};
+use crate::services::CryptoService;
+use chrono::Utc;
+use rand::Rng;
+
+// No 0/o, 1/l/i - the passphrase is read out or retyped
+pub const PASSPHRASE_ALPHABET: &[u8] = b"23456789abcdefghjkmnpqrstuvwxyz";
+const PASSPHRASE_LENGTH: usize = 20; // ~99 bits, the blob can be attacked offline
+const PASSPHRASE_GROUP: usize = 5;
+
+pub struct ShareService {
+    crypto: CryptoService,
+}
+
+impl ShareService {
+    pub fn new() -> Self {
+        Self {
+            crypto: CryptoService::new(),
+        }
+    }
+
+    /// Seal a secret value under a fresh passphrase
+    pub fn create_share(
+        &self,
+        secret_name: &str,
+        value: &str,
+        ttl_minutes: u32,
+    ) -> Result<OneTimeShare, ZapError> {
+        if ttl_minutes == 0 || ttl_minutes > MAX_SHARE_TTL_MINUTES {
+            return Err(ZapError::ValidationError(format!(
+                "Share lifetime must be between 1 and {} minutes",
+                MAX_SHARE_TTL_MINUTES
+            )));
+        }
+
+        let passphrase = generate_passphrase();
+        let salt = self.crypto.generate_salt();
+        let mut payload = SharePayload {
+            version: SHARE_FORMAT_VERSION,
+            share_id: uuid::Uuid::new_v4().to_string(),
+            secret_name: secret_name.to_string(),
+            salt: hex::encode(salt),
+            expires_at: Utc::now() + chrono::Duration::minutes(ttl_minutes as i64),
+            value: EncryptedData::default(),
+        };
+
+        let key = self
+            .crypto
+            .derive_key(&normalize_passphrase(&passphrase), &salt, &KdfParams::default())?;
+        payload.value = self
+            .crypto
+            .encrypt_bound(value, &key, payload.context().as_bytes())?;
+
+        let blob = format!(
+            "{}{}",
+            SHARE_BLOB_PREFIX,
+            hex::encode(serde_json::to_vec(&payload)?)
+        );
+
+        Ok(OneTimeShare {
+            share_id: payload.share_id,
+            blob,
+            passphrase,
+            expires_at: payload.expires_at,
+        })
+    }
+
+    /// Parse the blob, check expiry and decrypt - single use is enforced by the caller, and only
+    /// within its own vault
+    pub fn open_share(
+        &self,
+        blob: &str,
+        passphrase: &str,
+    ) -> Result<(SharePayload, String), ZapError> {
+        let payload = parse_blob(blob)?;
+
+        if payload.version != SHARE_FORMAT_VERSION {
+            return Err(ZapError::ValidationError(format!(
+                "Share format {} is not supported by this version of Zap",
+                payload.version
+            )));
+        }
+        if payload.expires_at <= Utc::now() {
+            return Err(ZapError::ValidationError("This share has expired".to_string()));
+        }
+
+        let salt = hex::decode(&payload.salt)
+            .map_err(|_| ZapError::ValidationError("Share is damaged".to_string()))?;
+        let key = self
+            .crypto
+            .derive_key(&normalize_passphrase(passphrase), &salt, &KdfParams::default())?;
+        let value = self
+            .crypto
+            .decrypt_bound(&payload.value, &key, payload.context().as_bytes())
+            .map_err(|_| {
+                ZapError::CryptoError("Wrong passphrase or damaged share".to_string())
+            })?;
+
+        Ok((payload, value))
+    }
+}
+
+impl Default for ShareService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn parse_blob(blob: &str) -> Result<SharePayload, ZapError> {
+    let invalid = || ZapError::ValidationError("Not a Zap share".to_string());
+
+    let encoded: String = blob
+        .trim()
+        .strip_prefix(SHARE_BLOB_PREFIX)
+        .ok_or_else(invalid)?
+        .chars()
+        .filter(|c| !c.is_whitespace()) // Chat apps like to wrap long lines
+        .collect();
+    let json = hex::decode(encoded).map_err(|_| invalid())?;
+    serde_json::from_slice(&json).map_err(|_| invalid())
+}
+
+// Shown as `xxxxx-xxxxx-xxxxx-xxxxx`
+fn generate_passphrase() -> String {
+    let mut rng = rand::rng();
+    let chars: Vec<u8> = (0..PASSPHRASE_LENGTH)
+        .map(|_| PASSPHRASE_ALPHABET[rng.random_range(0..PASSPHRASE_ALPHABET.len())])
+        .collect();
+    chars
+        .chunks(PASSPHRASE_GROUP)
+        .map(|group| String::from_utf8_lossy(group).into_owned())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+// Case, dashes and spaces don't matter when typing it back
+pub fn normalize_passphrase(passphrase: &str) -> String {
+    passphrase
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect()
+}
@@ -0,0 +1,357 @@
+// src/services/stats_service.rs
+
+use crate::models::stats_model::{
+    ActionCount, ActivityStats, AuditSummary, BoxActivity, BoxStats, ReportFormat,
+    SecretAgeInfo, SecretStrengthInfo, StatsReport, TagCount, VaultStats,
+};
+use crate::models::{LogAction, ZapError};
+use crate::services::StorageService;
+use crate::utils::secret_lint::is_password_name;
+use chrono::{DateTime, Duration, Utc};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const OLDEST_SECRETS_LIMIT: usize = 10;
+
+pub struct StatsService {
+    storage: Arc<StorageService>,
+}
+
+impl StatsService {
+    pub fn new(storage: Arc<StorageService>) -> Self {
+        Self { storage }
+    }
+
+    /// Build the full vault statistics report
+    pub fn get_vault_stats(&self) -> Result<VaultStats, ZapError> {
+        let boxes = self.storage.get_all_boxes()?;
+        let secrets = self.storage.list_all_secrets()?;
+        let logs = self.storage.get_all_logs()?;
+
+        let now = Utc::now();
+        let days_30 = now - Duration::days(30);
+        let days_90 = now - Duration::days(90);
+
+        // Per-box counts
+        let box_names: HashMap<&str, &str> = boxes
+            .iter()
+            .map(|b| (b.id.as_str(), b.name.as_str()))
+            .collect();
+        let mut box_stats: Vec<BoxStats> = boxes
+            .iter()
+            .map(|b| BoxStats {
+                box_id: b.id.clone(),
+                name: b.name.clone(),
+                dev_mode: b.dev_mode,
+                secrets_count: b.secrets_count,
+                updated_at: b.updated_at,
+            })
+            .collect();
+        box_stats.sort_by_key(|box_stat| Reverse(box_stat.secrets_count));
+
+        // Tag distribution
+        let mut tag_counts: HashMap<&str, usize> = HashMap::new();
+        for box_item in &boxes {
+            for tag in &box_item.tags {
+                *tag_counts.entry(tag.as_str()).or_insert(0) += 1;
+            }
+        }
+        let mut tag_distribution: Vec<TagCount> = tag_counts
+            .into_iter()
+            .map(|(tag, boxes_count)| TagCount {
+                tag: tag.to_string(),
+                boxes_count,
+            })
+            .collect();
+        tag_distribution
+            .sort_by(|a, b| b.boxes_count.cmp(&a.boxes_count).then(a.tag.cmp(&b.tag)));
+
+        // Activity windows
+        let mut activity = ActivityStats::default();
+        for box_item in &boxes {
+            if box_item.created_at >= days_30 {
+                activity.boxes_created_last_30_days += 1;
+            }
+            if box_item.created_at >= days_90 {
+                activity.boxes_created_last_90_days += 1;
+            }
+        }
+        for secret in &secrets {
+            if secret.created_at >= days_30 {
+                activity.secrets_created_last_30_days += 1;
+            }
+            if secret.created_at >= days_90 {
+                activity.secrets_created_last_90_days += 1;
+            }
+            if secret.updated_at >= days_30 && secret.updated_at > secret.created_at {
+                activity.secrets_updated_last_30_days += 1;
+            }
+            if secret.updated_at >= days_90 && secret.updated_at > secret.created_at {
+                activity.secrets_updated_last_90_days += 1;
+            }
+        }
+
+        // Log action histogram
+        let mut action_counts: HashMap<&str, usize> = HashMap::new();
+        for entry in &logs {
+            *action_counts.entry(entry.action.as_str()).or_insert(0) += 1;
+        }
+        let mut log_actions: Vec<ActionCount> = action_counts
+            .into_iter()
+            .map(|(action, count)| ActionCount {
+                action: action.to_string(),
+                count,
+            })
+            .collect();
+        log_actions.sort_by(|a, b| b.count.cmp(&a.count).then(a.action.cmp(&b.action)));
+
+        // Oldest / never rotated secrets
+        let never_rotated_secrets = secrets
+            .iter()
+            .filter(|s| s.last_rotated_at.is_none())
+            .count();
+        let mut by_age: Vec<_> = secrets.iter().collect();
+        by_age.sort_by_key(|s| s.last_rotated_at.unwrap_or(s.created_at));
+        let oldest_secrets = by_age
+            .into_iter()
+            .take(OLDEST_SECRETS_LIMIT)
+            .map(|s| SecretAgeInfo {
+                secret_id: s.id.clone(),
+                name: s.name.clone(),
+                box_id: s.box_id.clone(),
+                box_name: box_names
+                    .get(s.box_id.as_str())
+                    .map(|n| n.to_string())
+                    .unwrap_or_default(),
+                updated_at: s.updated_at,
+                rotated_at: s.last_rotated_at.unwrap_or(s.created_at),
+                never_rotated: s.last_rotated_at.is_none(),
+            })
+            .collect();
+
+        Ok(VaultStats {
+            total_boxes: boxes.len(),
+            dev_boxes: boxes.iter().filter(|b| b.dev_mode).count(),
+            total_secrets: secrets.len(),
+            last_updated: secrets
+                .iter()
+                .map(|s| s.updated_at)
+                .max()
+                .unwrap_or_else(Utc::now),
+            boxes: box_stats,
+            tag_distribution,
+            activity,
+            log_actions,
+            oldest_secrets,
+            never_rotated_secrets,
+        })
+    }
+
+    /// Assemble stats, audit summary and per-box activity into one report
+    pub fn build_report(&self) -> Result<StatsReport, ZapError> {
+        let vault = self.get_vault_stats()?;
+        let secrets = self.storage.list_all_secrets()?;
+        let logs = self.storage.get_all_logs()?;
+        let days_30 = Utc::now() - Duration::days(30);
+
+        let recent_logs: Vec<_> = logs.iter().filter(|l| l.timestamp >= days_30).collect();
+        let audit = AuditSummary {
+            total_entries: logs.len(),
+            first_entry_at: logs.iter().map(|l| l.timestamp).min(),
+            last_entry_at: logs.iter().map(|l| l.timestamp).max(),
+            entries_last_30_days: recent_logs.len(),
+            reveals_last_30_days: recent_logs
+                .iter()
+                .filter(|l| l.action == LogAction::RevealSecret)
+                .count(),
+            exports_last_30_days: recent_logs
+                .iter()
+                .filter(|l| l.action.is_export())
+                .count(),
+        };
+
+        let box_activity = vault
+            .boxes
+            .iter()
+            .map(|b| {
+                let box_secrets: Vec<_> =
+                    secrets.iter().filter(|s| s.box_id == b.box_id).collect();
+                BoxActivity {
+                    box_id: b.box_id.clone(),
+                    name: b.name.clone(),
+                    secrets_count: b.secrets_count,
+                    secrets_created_last_30_days: box_secrets
+                        .iter()
+                        .filter(|s| s.created_at >= days_30)
+                        .count(),
+                    secrets_updated_last_30_days: box_secrets
+                        .iter()
+                        .filter(|s| s.updated_at >= days_30 && s.updated_at > s.created_at)
+                        .count(),
+                    last_secret_update: box_secrets.iter().map(|s| s.updated_at).max(),
+                }
+            })
+            .collect();
+
+        // A rename away from a password name keeps the old score, so check the name again
+        let mut password_strength: Vec<SecretStrengthInfo> = secrets
+            .iter()
+            .filter(|s| is_password_name(&s.name))
+            .filter_map(|s| {
+                let strength = s.strength.clone()?;
+                Some(SecretStrengthInfo {
+                    secret_id: s.id.clone(),
+                    name: s.name.clone(),
+                    box_id: s.box_id.clone(),
+                    box_name: vault
+                        .boxes
+                        .iter()
+                        .find(|b| b.box_id == s.box_id)
+                        .map(|b| b.name.clone())
+                        .unwrap_or_default(),
+                    strength,
+                })
+            })
+            .collect();
+        password_strength.sort_by(|a, b| {
+            let (a, b) = (&a.strength, &b.strength);
+            a.score.cmp(&b.score).then(a.crack_time_seconds.total_cmp(&b.crack_time_seconds))
+        });
+
+        Ok(StatsReport {
+            generated_at: Utc::now(),
+            vault,
+            audit,
+            box_activity,
+            password_strength,
+        })
+    }
+
+    /// Render the report as JSON or CSV
+    pub fn export_report(&self, format: ReportFormat) -> Result<String, ZapError> {
+        let report = self.build_report()?;
+
+        match format {
+            ReportFormat::Json => {
+                serde_json::to_string_pretty(&report).map_err(ZapError::SerializationError)
+            }
+            ReportFormat::Csv => Ok(self.render_report_csv(&report)),
+        }
+    }
+
+    // One row per metric: section,subject,metric,value
+    fn render_report_csv(&self, report: &StatsReport) -> String {
+        let mut rows: Vec<[String; 4]> = Vec::new();
+        let mut push = |section: &str, subject: &str, metric: &str, value: String| {
+            rows.push([
+                section.to_string(),
+                subject.to_string(),
+                metric.to_string(),
+                value,
+            ]);
+        };
+        let ts = |t: Option<DateTime<Utc>>| t.map(|t| t.to_rfc3339()).unwrap_or_default();
+
+        push("report", "", "generated_at", report.generated_at.to_rfc3339());
+
+        let vault = &report.vault;
+        push("vault", "", "total_boxes", vault.total_boxes.to_string());
+        push("vault", "", "dev_boxes", vault.dev_boxes.to_string());
+        push("vault", "", "total_secrets", vault.total_secrets.to_string());
+        push(
+            "vault",
+            "",
+            "never_rotated_secrets",
+            vault.never_rotated_secrets.to_string(),
+        );
+        push("vault", "", "last_updated", vault.last_updated.to_rfc3339());
+
+        let activity = &vault.activity;
+        for (metric, value) in [
+            ("boxes_created_last_30_days", activity.boxes_created_last_30_days),
+            ("boxes_created_last_90_days", activity.boxes_created_last_90_days),
+            ("secrets_created_last_30_days", activity.secrets_created_last_30_days),
+            ("secrets_created_last_90_days", activity.secrets_created_last_90_days),
+            ("secrets_updated_last_30_days", activity.secrets_updated_last_30_days),
+            ("secrets_updated_last_90_days", activity.secrets_updated_last_90_days),
+        ] {
+            push("activity", "", metric, value.to_string());
+        }
+
+        for tag in &vault.tag_distribution {
+            push("tags", &tag.tag, "boxes_count", tag.boxes_count.to_string());
+        }
+
+        let audit = &report.audit;
+        push("audit", "", "total_entries", audit.total_entries.to_string());
+        push("audit", "", "first_entry_at", ts(audit.first_entry_at));
+        push("audit", "", "last_entry_at", ts(audit.last_entry_at));
+        for (metric, value) in [
+            ("entries_last_30_days", audit.entries_last_30_days),
+            ("reveals_last_30_days", audit.reveals_last_30_days),
+            ("exports_last_30_days", audit.exports_last_30_days),
+        ] {
+            push("audit", "", metric, value.to_string());
+        }
+        for action in &vault.log_actions {
+            push("audit_actions", &action.action, "count", action.count.to_string());
+        }
+
+        for activity in &report.box_activity {
+            let name = activity.name.as_str();
+            push("box", name, "secrets_count", activity.secrets_count.to_string());
+            push(
+                "box",
+                name,
+                "secrets_created_last_30_days",
+                activity.secrets_created_last_30_days.to_string(),
+            );
+            push(
+                "box",
+                name,
+                "secrets_updated_last_30_days",
+                activity.secrets_updated_last_30_days.to_string(),
+            );
+            push("box", name, "last_secret_update", ts(activity.last_secret_update));
+        }
+
+        for secret in &vault.oldest_secrets {
+            let subject = format!("{}/{}", secret.box_name, secret.name);
+            push("oldest_secrets", &subject, "updated_at", secret.updated_at.to_rfc3339());
+            push("oldest_secrets", &subject, "rotated_at", secret.rotated_at.to_rfc3339());
+            push("oldest_secrets", &subject, "never_rotated", secret.never_rotated.to_string());
+        }
+
+        for secret in &report.password_strength {
+            let subject = format!("{}/{}", secret.box_name, secret.name);
+            let strength = &secret.strength;
+            push("password_strength", &subject, "score", strength.score.to_string());
+            push("password_strength", &subject, "crack_time", strength.crack_time.clone());
+        }
+
+        let mut csv = String::from("section,subject,metric,value\n");
+        for row in rows {
+            let escaped: Vec<String> = row.iter().map(|field| csv_escape(field)).collect();
+            csv.push_str(&escaped.join(","));
+            csv.push('\n');
+        }
+        csv
+    }
+}
+
+// Box and secret names are user input, so a leading =, +, - or @ is defused before a
+// spreadsheet reads the cell as a formula
+fn csv_escape(field: &str) -> String {
+    let field = if field.starts_with(['=', '+', '-', '@']) {
+        format!("'{}", field)
+    } else {
+        field.to_string()
+    };
+
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field
+    }
+}
@@ -1,73 +1,599 @@
 // src/services/storage_service.rs
 
-use crate::models::{AuthConfig, Box, DevSession, LogEntry, Secret, Settings, ZapError};
-use crate::utils::path_resolvers::{get_logs_db_path, get_sessions_db_path, get_vault_db_path};
+use crate::logging::redact;
+use crate::models::box_model::MAX_SECRETS_PER_BOX;
+use crate::models::search_model::find_match_ranges;
+use crate::models::storage_model::MAX_VAULT_SNAPSHOTS;
+use crate::services::migrations::{self, SCHEMA_VERSION_KEY};
+use crate::models::{
+    AuthConfig, Box, BoxSearchResult, DatabaseKind, DatabaseUsage, DevSession, EncryptedData,
+    KeyGroupUsage, LogAction, LogEntry, LogSeverity, MatchField, MatchSpan, RecordVersion,
+    RememberedKey, Secret, SecretBreach, SecretSearchResult, SecretUsage, Settings, SnapshotReason,
+    StartupStatus, StorageUsage, SyncConflict, VaultSnapshot, ZapError,
+};
+use crate::utils::input_validation::{name_key, MAX_BOX_NAME_LENGTH, MAX_SECRET_NAME_LENGTH};
+use crate::utils::path_resolvers::{
+    get_compaction_marker_path, get_data_directory, get_logs_db_path, get_sessions_db_path,
+    get_snapshots_directory, get_vault_db_path, get_vault_snapshot_path,
+    unavailable_data_directory,
+};
+use crate::utils::striped_locks::{StripedLocks, BOX_LOCK_STRIPES};
+use crate::utils::write_coordinator::{WriteCoordinator, FLUSH_INTERVAL};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::cmp::Reverse;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+// Set once every secret ciphertext carries its record ids as AAD
+const SECRETS_BOUND_KEY: &str = "secrets_bound";
+
+// Ciphertexts larger than this are stored under secret_blob:<id>, outside the secret record
+const INLINE_SECRET_VALUE_BYTES: usize = 4 * 1024;
+
+// Change journal, see RecordVersion. Writes to these entities get a version in the same batch.
+const JOURNALED_PREFIXES: [&str; 2] = ["box", "secret"];
+const DEVICE_ID_KEY: &str = "device_id";
+const LAMPORT_CLOCK_KEY: &str = "lamport_clock";
+// This device's sync identity, left alone when a snapshot is restored
+const RESTORE_KEPT_KEYS: [&str; 2] = [DEVICE_ID_KEY, LAMPORT_CLOCK_KEY];
+
+// Snapshot ids start with this, so they sort oldest first
+const SNAPSHOT_TIME_FORMAT: &str = "%Y%m%d%H%M%S%3f";
 
 pub struct StorageService {
-    db: Option<sled::Db>,
-    session_db: Option<sled::Db>,
-    logs_db: Option<sled::Db>,
+    // Set once opened, so a database that failed at startup can still be opened after recovery
+    db: OnceLock<sled::Db>,
+    session_db: OnceLock<sled::Db>,
+    logs_db: OnceLock<sled::Db>,
+    writes: WriteCoordinator, // Credentials are still flushed synchronously
+    // Serializes secret writes per box, so capacity and name checks can't race each other
+    box_locks: StripedLocks,
 }
 
 impl StorageService {
     pub fn new() -> Self {
         Self {
-            db: None,
-            session_db: None,
-            logs_db: None,
+            db: OnceLock::new(),
+            session_db: OnceLock::new(),
+            logs_db: OnceLock::new(),
+            writes: WriteCoordinator::new(FLUSH_INTERVAL),
+            box_locks: StripedLocks::new(BOX_LOCK_STRIPES),
         }
     }
 
-    // Initialize databases
-    pub fn initialize(&mut self) -> Result<(), ZapError> {
-        // Use organized paths from path_resolvers (no db_path parameter needed)
-        let vault_db_path = get_vault_db_path()
-            .map_err(|e| ZapError::StorageError(format!("Failed to get vault DB path: {}", e)))?;
-        let sessions_db_path = get_sessions_db_path().map_err(|e| {
-            ZapError::StorageError(format!("Failed to get sessions DB path: {}", e))
-        })?;
-        let logs_db_path = get_logs_db_path()
-            .map_err(|e| ZapError::StorageError(format!("Failed to get logs DB path: {}", e)))?;
+    /// Open any database that isn't open yet. Safe to call again after a recovery step.
+    pub fn initialize(&self) -> StartupStatus {
+        // Opening a database would create the missing directory and an empty vault in it
+        if let Some(dir) = unavailable_data_directory() {
+            return StartupStatus::DataDirectoryUnavailable {
+                path: dir.display().to_string(),
+            };
+        }
+
+        let compacted = self.run_scheduled_compaction();
+
+        for kind in [DatabaseKind::Vault, DatabaseKind::Sessions, DatabaseKind::Logs] {
+            if let Err(status) = self.open_database(kind) {
+                return status;
+            }
+        }
+
+        if let Err(e) = migrations::run_pending(self) {
+            return StartupStatus::Failed {
+                error: e.to_string(),
+            };
+        }
+
+        if let Some(summary) = compacted {
+            let _ = self.log(
+                LogAction::CompactStorage,
+                "Storage compacted on start".to_string(),
+                Some(summary),
+            );
+        }
+
+        // Only a vault that opened cleanly is worth keeping as the recovery snapshot
+        if let Err(e) = self.snapshot_vault() {
+            tracing::error!("Failed to snapshot vault: {}", e);
+        }
 
-        // Open databases in organized data/ folder
-        self.db = Some(sled::open(&vault_db_path)?);
-        self.session_db = Some(sled::open(&sessions_db_path)?);
-        self.logs_db = Some(sled::open(&logs_db_path)?);
+        if let (Ok(vault), Ok(sessions), Ok(logs)) = (
+            database_path(DatabaseKind::Vault),
+            database_path(DatabaseKind::Sessions),
+            database_path(DatabaseKind::Logs),
+        ) {
+            tracing::info!(
+                vault = %vault.display(),
+                sessions = %sessions.display(),
+                logs = %logs.display(),
+                "Databases initialized"
+            );
+        }
 
-        println!("📁 Databases initialized:");
-        println!("   Vault: {}", vault_db_path.display());
-        println!("   Sessions: {}", sessions_db_path.display());
-        println!("   Logs: {}", logs_db_path.display());
+        StartupStatus::Ready
+    }
 
+    /// Write out anything the background flusher hasn't reached yet
+    pub fn flush(&self) -> Result<(), ZapError> {
+        self.writes.flush_now()?;
         Ok(())
     }
 
     pub fn is_initialized(&self) -> bool {
-        self.db.is_some() && self.session_db.is_some() && self.logs_db.is_some()
+        self.db.get().is_some() && self.session_db.get().is_some() && self.logs_db.get().is_some()
+    }
+
+    /// Replace the (unopened) vault with the snapshot from the last good start.
+    /// The corrupted copy is moved aside, never deleted.
+    pub fn restore_vault_snapshot(&self) -> Result<PathBuf, ZapError> {
+        let snapshot_path = get_vault_snapshot_path()
+            .map_err(|e| ZapError::StorageError(format!("Failed to get snapshot path: {}", e)))?;
+        if !snapshot_path.exists() {
+            return Err(ZapError::StorageError(
+                "No vault snapshot is available".to_string(),
+            ));
+        }
+
+        let moved_to = self.quarantine_database(DatabaseKind::Vault)?;
+        copy_dir(&snapshot_path, &database_path(DatabaseKind::Vault)?)?;
+        Ok(moved_to)
+    }
+
+    /// Move an unopened database aside so the next initialize starts it empty
+    pub fn quarantine_database(&self, kind: DatabaseKind) -> Result<PathBuf, ZapError> {
+        if self.slot(kind).get().is_some() {
+            return Err(ZapError::StorageError(format!(
+                "The {} database is open and doesn't need recovery",
+                kind.as_str()
+            )));
+        }
+
+        let path = database_path(kind)?;
+        let moved_to = path.with_extension(format!(
+            "db.corrupt-{}",
+            chrono::Utc::now().format("%Y%m%d%H%M%S")
+        ));
+        if path.exists() {
+            std::fs::rename(&path, &moved_to)?;
+        }
+        Ok(moved_to)
+    }
+
+    /// Size on disk and per key prefix of every open database
+    pub fn get_storage_usage(&self) -> Result<StorageUsage, ZapError> {
+        let mut databases = Vec::new();
+
+        for kind in [DatabaseKind::Vault, DatabaseKind::Sessions, DatabaseKind::Logs] {
+            let Some(db) = self.slot(kind).get() else {
+                continue;
+            };
+
+            let mut groups: HashMap<String, KeyGroupUsage> = HashMap::new();
+            let mut records = 0;
+            for entry in db.iter() {
+                let (key, value) = entry?;
+                let prefix = match key.iter().position(|&b| b == b':') {
+                    Some(end) => String::from_utf8_lossy(&key[..end]).into_owned(),
+                    None => String::from_utf8_lossy(&key).into_owned(),
+                };
+                let group = groups.entry(prefix.clone()).or_insert(KeyGroupUsage {
+                    prefix,
+                    records: 0,
+                    bytes: 0,
+                });
+                group.records += 1;
+                group.bytes += (key.len() + value.len()) as u64;
+                records += 1;
+            }
+
+            let mut groups: Vec<KeyGroupUsage> = groups.into_values().collect();
+            groups.sort_by_key(|group| Reverse(group.bytes));
+
+            databases.push(DatabaseUsage {
+                database: kind,
+                bytes_on_disk: db.size_on_disk()?,
+                live_bytes: groups.iter().map(|group| group.bytes).sum(),
+                records,
+                groups,
+            });
+        }
+
+        Ok(StorageUsage {
+            total_bytes_on_disk: databases.iter().map(|db| db.bytes_on_disk).sum(),
+            databases,
+            compaction_scheduled: get_compaction_marker_path().is_ok_and(|path| path.exists()),
+        })
+    }
+
+    /// Compact every database on the next start, before they're opened
+    /// (sled keeps the files it grew to, and open databases can't be swapped)
+    pub fn schedule_compaction(&self) -> Result<(), ZapError> {
+        let marker = get_compaction_marker_path()
+            .map_err(|e| ZapError::StorageError(format!("Failed to get data directory: {}", e)))?;
+        std::fs::write(marker, chrono::Utc::now().to_rfc3339())?;
+        Ok(())
+    }
+
+    // Returns a before/after summary when a compaction was scheduled
+    fn run_scheduled_compaction(&self) -> Option<String> {
+        let marker = get_compaction_marker_path()
+            .ok()
+            .filter(|path| path.exists())?;
+
+        let mut summary = Vec::new();
+        for kind in [DatabaseKind::Vault, DatabaseKind::Sessions, DatabaseKind::Logs] {
+            if self.slot(kind).get().is_some() {
+                continue;
+            }
+            match database_path(kind).and_then(|path| compact_database(&path)) {
+                Ok((before, after)) => summary.push(format!(
+                    "{}: {} -> {} bytes",
+                    kind.as_str(),
+                    before,
+                    after
+                )),
+                Err(e) => summary.push(format!("{}: not compacted ({})", kind.as_str(), e)),
+            }
+        }
+
+        let _ = std::fs::remove_file(marker);
+        Some(summary.join(", "))
+    }
+
+    fn slot(&self, kind: DatabaseKind) -> &OnceLock<sled::Db> {
+        match kind {
+            DatabaseKind::Vault => &self.db,
+            DatabaseKind::Sessions => &self.session_db,
+            DatabaseKind::Logs => &self.logs_db,
+        }
+    }
+
+    fn open_database(&self, kind: DatabaseKind) -> Result<(), StartupStatus> {
+        let slot = self.slot(kind);
+        if slot.get().is_some() {
+            return Ok(());
+        }
+
+        let path = database_path(kind).map_err(|e| StartupStatus::Failed {
+            error: e.to_string(),
+        })?;
+        let db = sled::open(&path).map_err(|e| classify_open_error(kind, e))?;
+        self.writes.register(&db);
+        let _ = slot.set(db);
+        Ok(())
+    }
+
+    fn snapshot_vault(&self) -> Result<(), ZapError> {
+        let snapshot_path = get_vault_snapshot_path()
+            .map_err(|e| ZapError::StorageError(format!("Failed to get snapshot path: {}", e)))?;
+        self.copy_vault_to(&snapshot_path)
+    }
+
+    pub(crate) fn copy_vault_to(&self, path: &Path) -> Result<(), ZapError> {
+        export_database(self.get_db()?, path)
+    }
+
+    /// Copy the whole data directory into `dir`: the open databases exported, the rest
+    /// (snapshots, migration backups, markers) as files. Each database copy must read back
+    /// exactly like its original, so this fails if anything was written meanwhile.
+    pub fn copy_data_directory_to(&self, dir: &Path) -> Result<(), ZapError> {
+        self.flush()?;
+
+        let mut exported = Vec::new();
+        for kind in [DatabaseKind::Vault, DatabaseKind::Sessions, DatabaseKind::Logs] {
+            let db = self.slot(kind).get().ok_or_else(|| {
+                ZapError::StorageError(format!("The {} database isn't open", kind.as_str()))
+            })?;
+            let path = database_path(kind)?;
+            let target = dir.join(path.file_name().unwrap_or_default());
+
+            let checksum = db.checksum()?;
+            export_database(db, &target)?;
+            let copied = sled::open(&target)?.checksum()?;
+            if copied != checksum || db.checksum()? != checksum {
+                return Err(ZapError::StorageError(format!(
+                    "The {} database changed while it was copied, try again",
+                    kind.as_str()
+                )));
+            }
+            exported.push(path);
+        }
+
+        let data_dir = get_data_directory()
+            .map_err(|e| ZapError::StorageError(format!("Failed to get data directory: {}", e)))?;
+        for entry in std::fs::read_dir(data_dir)? {
+            let entry = entry?;
+            if exported.contains(&entry.path()) {
+                continue;
+            }
+            let target = dir.join(entry.file_name());
+            if entry.file_type()?.is_dir() {
+                copy_dir(&entry.path(), &target)?;
+            } else {
+                std::fs::copy(entry.path(), target)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Copy the vault into the snapshots folder before an operation that's hard to take back
+    pub fn create_vault_snapshot(&self, reason: SnapshotReason) -> Result<VaultSnapshot, ZapError> {
+        let dir = snapshots_directory()?;
+        std::fs::create_dir_all(&dir)?;
+
+        let created_at = chrono::Utc::now();
+        let id = format!(
+            "{}-{}",
+            created_at.format(SNAPSHOT_TIME_FORMAT),
+            reason.as_str()
+        );
+        let path = dir.join(&id);
+        export_database(self.get_db()?, &path)?;
+
+        Ok(VaultSnapshot {
+            id,
+            reason,
+            created_at,
+            bytes: dir_size(&path)?,
+        })
+    }
+
+    /// Remove all but the newest MAX_VAULT_SNAPSHOTS
+    pub fn prune_vault_snapshots(&self) -> Result<usize, ZapError> {
+        let dir = snapshots_directory()?;
+        let snapshots = self.list_vault_snapshots()?;
+        for old in snapshots.iter().skip(MAX_VAULT_SNAPSHOTS) {
+            std::fs::remove_dir_all(dir.join(&old.id))?;
+        }
+        Ok(snapshots.len().saturating_sub(MAX_VAULT_SNAPSHOTS))
+    }
+
+    /// Snapshots still kept, newest first
+    pub fn list_vault_snapshots(&self) -> Result<Vec<VaultSnapshot>, ZapError> {
+        let dir = snapshots_directory()?;
+        if !dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut snapshots = Vec::new();
+        for entry in std::fs::read_dir(&dir)? {
+            let entry = entry?;
+            // Skips anything else in there, like an interrupted snapshot's staging copy
+            let Some((id, (created_at, reason))) = entry
+                .file_name()
+                .to_str()
+                .and_then(|id| Some((id.to_string(), parse_snapshot_id(id)?)))
+            else {
+                continue;
+            };
+            snapshots.push(VaultSnapshot {
+                id,
+                reason,
+                created_at,
+                bytes: dir_size(&entry.path())?,
+            });
+        }
+        snapshots.sort_by(|a, b| b.id.cmp(&a.id));
+        Ok(snapshots)
+    }
+
+    /// Replace everything in the open vault with a snapshot's contents, in one batch.
+    /// The device id and sync clock stay, so versions this device hands out never repeat.
+    /// Every box and secret the restore changes or removes is journaled as a new local write,
+    /// so replicas take the restored state instead of syncing the discarded one back.
+    pub fn restore_vault_from_snapshot(&self, id: &str) -> Result<(), ZapError> {
+        let path = snapshots_directory()?.join(id);
+        if parse_snapshot_id(id).is_none() || !path.is_dir() {
+            return Err(ZapError::ValidationError(format!(
+                "Snapshot '{}' doesn't exist",
+                id
+            )));
+        }
+
+        let db = self.get_db()?;
+        let snapshot = sled::open(&path)?;
+        let kept = |key: &[u8]| RESTORE_KEPT_KEYS.iter().any(|kept| kept.as_bytes() == key);
+
+        let mut batch = sled::Batch::default();
+        let mut changed_records = Vec::new();
+        for entry in db.iter() {
+            let (key, value) = entry?;
+            if kept(key.as_ref()) {
+                continue;
+            }
+            let restored = snapshot.get(&key)?;
+            if restored.as_ref() != Some(&value) {
+                if let Some(record_key) = journaled_record_key(&key) {
+                    changed_records.push((record_key, restored.is_none()));
+                }
+            }
+            if restored.is_none() {
+                batch.remove(key);
+            }
+        }
+        for entry in snapshot.iter() {
+            let (key, value) = entry?;
+            if kept(key.as_ref()) {
+                continue;
+            }
+            if !db.contains_key(&key)? {
+                if let Some(record_key) = journaled_record_key(&key) {
+                    changed_records.push((record_key, false));
+                }
+            }
+            batch.insert(key, value);
+        }
+        // After the snapshot's own versions, so these replace them
+        for (record_key, deleted) in changed_records {
+            self.journal(&mut batch, &record_key, deleted)?;
+        }
+
+        db.apply_batch(batch)?;
+        db.flush()?;
+        Ok(())
+    }
+
+    /// Whether a database opened, at startup or after a recovery step
+    pub fn is_open(&self, kind: DatabaseKind) -> bool {
+        match kind {
+            DatabaseKind::Vault => self.db.get().is_some(),
+            DatabaseKind::Sessions => self.session_db.get().is_some(),
+            DatabaseKind::Logs => self.logs_db.get().is_some(),
+        }
     }
 
     // Database getters
     fn get_db(&self) -> Result<&sled::Db, ZapError> {
-        self.db.as_ref().ok_or(ZapError::StorageError(
+        self.db.get().ok_or(ZapError::StorageError(
             "Database not initialized".to_string(),
         ))
     }
 
     fn get_sessions_db(&self) -> Result<&sled::Db, ZapError> {
-        self.session_db.as_ref().ok_or(ZapError::StorageError(
+        self.session_db.get().ok_or(ZapError::StorageError(
             "Sessions database not initialized".to_string(),
         ))
     }
 
     fn get_logs_db(&self) -> Result<&sled::Db, ZapError> {
-        self.logs_db.as_ref().ok_or(ZapError::StorageError(
+        self.logs_db.get().ok_or(ZapError::StorageError(
             "Logs database not initialized".to_string(),
         ))
     }
 }
 
+// Export an open database into a fresh db next to `path`, then swap it in
+fn export_database(db: &sled::Db, path: &Path) -> Result<(), ZapError> {
+    let mut staging_path = path.as_os_str().to_owned();
+    staging_path.push(".tmp");
+    let staging_path = PathBuf::from(staging_path);
+
+    if staging_path.exists() {
+        std::fs::remove_dir_all(&staging_path)?;
+    }
+    {
+        let staging = sled::open(&staging_path)?;
+        staging.import(db.export());
+        staging.flush()?;
+    }
+
+    if path.exists() {
+        std::fs::remove_dir_all(path)?;
+    }
+    std::fs::rename(&staging_path, path)?;
+    Ok(())
+}
+
+fn snapshots_directory() -> Result<PathBuf, ZapError> {
+    get_snapshots_directory()
+        .map_err(|e| ZapError::StorageError(format!("Failed to get snapshots directory: {}", e)))
+}
+
+// `<timestamp>-<reason>` from create_vault_snapshot
+fn parse_snapshot_id(id: &str) -> Option<(chrono::DateTime<chrono::Utc>, SnapshotReason)> {
+    let (time, reason) = id.split_once('-')?;
+    let created_at = chrono::NaiveDateTime::parse_from_str(time, SNAPSHOT_TIME_FORMAT).ok()?;
+    Some((created_at.and_utc(), SnapshotReason::parse(reason)?))
+}
+
+fn database_path(kind: DatabaseKind) -> Result<PathBuf, ZapError> {
+    let path = match kind {
+        DatabaseKind::Vault => get_vault_db_path(),
+        DatabaseKind::Sessions => get_sessions_db_path(),
+        DatabaseKind::Logs => get_logs_db_path(),
+    };
+    path.map_err(|e| {
+        ZapError::StorageError(format!("Failed to get {} DB path: {}", kind.as_str(), e))
+    })
+}
+
+// sled reports a held lock as a plain IO error, so match on its message
+fn classify_open_error(kind: DatabaseKind, error: sled::Error) -> StartupStatus {
+    let corrupted = match &error {
+        sled::Error::Io(io) if io.to_string().contains("could not acquire lock") => {
+            return StartupStatus::Locked { database: kind };
+        }
+        sled::Error::Io(io) => io.kind() == std::io::ErrorKind::InvalidData,
+        sled::Error::Corruption { .. } | sled::Error::Unsupported(_) => true,
+        _ => false,
+    };
+
+    if !corrupted {
+        return StartupStatus::Failed {
+            error: error.to_string(),
+        };
+    }
+
+    StartupStatus::Corrupted {
+        database: kind,
+        error: error.to_string(),
+        snapshot_available: kind == DatabaseKind::Vault
+            && get_vault_snapshot_path().is_ok_and(|path| path.exists()),
+    }
+}
+
+// Rewrite a closed database into a fresh directory and swap it in, returns (before, after) bytes.
+// The original is only removed once the copy is in place.
+fn compact_database(path: &Path) -> Result<(u64, u64), ZapError> {
+    if !path.exists() {
+        return Ok((0, 0));
+    }
+
+    let staging = path.with_extension("db.compacting");
+    let previous = path.with_extension("db.precompact");
+    for leftover in [&staging, &previous] {
+        if leftover.exists() {
+            std::fs::remove_dir_all(leftover)?;
+        }
+    }
+
+    let before = dir_size(path)?;
+    {
+        let db = sled::open(path)?;
+        let fresh = sled::open(&staging)?;
+        fresh.import(db.export());
+        fresh.flush()?;
+    }
+
+    std::fs::rename(path, &previous)?;
+    if let Err(e) = std::fs::rename(&staging, path) {
+        std::fs::rename(&previous, path)?;
+        return Err(e.into());
+    }
+    std::fs::remove_dir_all(&previous)?;
+
+    Ok((before, dir_size(path)?))
+}
+
+fn dir_size(path: &Path) -> Result<u64, ZapError> {
+    let mut size = 0;
+    for entry in std::fs::read_dir(path)? {
+        let entry = entry?;
+        let metadata = entry.metadata()?;
+        size += if metadata.is_dir() {
+            dir_size(&entry.path())?
+        } else {
+            metadata.len()
+        };
+    }
+    Ok(size)
+}
+
+fn copy_dir(from: &Path, to: &Path) -> Result<(), ZapError> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_dir(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), target)?;
+        }
+    }
+    Ok(())
+}
+
 // GENERIC CRUD OPERATIONS
 impl StorageService {
     fn save_entity<T: Serialize>(
@@ -83,13 +609,16 @@ impl StorageService {
 
         let mut batch = sled::Batch::default();
         batch.insert(entity_key.as_bytes(), serialized);
+        if JOURNALED_PREFIXES.contains(&prefix) {
+            self.journal(&mut batch, &entity_key, false)?;
+        }
 
         if let Some((name_key, _name)) = name_mapping {
             batch.insert(name_key.as_bytes(), id.as_bytes());
         }
 
         db.apply_batch(batch)?;
-        db.flush()?;
+        self.writes.mark_dirty();
         Ok(())
     }
 
@@ -130,13 +659,16 @@ impl StorageService {
 
         let mut batch = sled::Batch::default();
         batch.remove(entity_key.as_bytes());
+        if JOURNALED_PREFIXES.contains(&prefix) {
+            self.journal(&mut batch, &entity_key, true)?;
+        }
 
         for key in cleanup_keys {
             batch.remove(key.as_bytes());
         }
 
         db.apply_batch(batch)?;
-        db.flush()?;
+        self.writes.mark_dirty();
         Ok(())
     }
 
@@ -146,12 +678,128 @@ impl StorageService {
         name: &str,
         name_prefix: &str,
     ) -> Result<Option<String>, ZapError> {
-        let name_key = format!("{}:{}", name_prefix, name);
-        match db.get(name_key.as_bytes())? {
+        match db.get(Self::name_index_key(name_prefix, name).as_bytes())? {
             Some(id_bytes) => Ok(Some(String::from_utf8(id_bytes.to_vec())?)),
             None => Ok(None),
         }
     }
+
+    // Names are unique case-insensitively, display names stay as entered
+    fn name_index_key(name_prefix: &str, name: &str) -> String {
+        format!("{}:{}", name_prefix, name_key(name))
+    }
+
+    fn secret_name_index_key(box_id: &str, name: &str) -> String {
+        Self::name_index_key(&format!("secret_name:{}", box_id), name)
+    }
+
+    // Rebuild box/secret name indexes with normalized keys (schema migration 1)
+    pub(crate) fn rebuild_name_indexes(&self) -> Result<(), ZapError> {
+        let db = self.get_db()?;
+        let mut batch = sled::Batch::default();
+        for prefix in ["box_name:", "secret_name:"] {
+            for result in db.scan_prefix(prefix) {
+                let (key, _) = result?;
+                batch.remove(key);
+            }
+        }
+
+        // Oldest entity keeps the name when two existing names now collide, the newer one is
+        // renamed so it stays reachable by name
+        let mut boxes: Vec<Box> = self.get_all_entities(db, "box")?;
+        boxes.sort_by_key(|box_item| box_item.created_at);
+        let mut seen = HashSet::new();
+        for box_item in &mut boxes {
+            let mut key = Self::name_index_key("box_name", &box_item.name);
+            if seen.contains(&key) {
+                let name = deduplicated_name(&box_item.name, MAX_BOX_NAME_LENGTH, |name| {
+                    seen.contains(&Self::name_index_key("box_name", name))
+                });
+                tracing::warn!(
+                    "Duplicate box name after normalization, renamed to {}",
+                    redact(&name)
+                );
+                box_item.name = name;
+                box_item.updated_at = chrono::Utc::now();
+                key = Self::name_index_key("box_name", &box_item.name);
+
+                let record_key = format!("box:{}", box_item.id);
+                batch.insert(record_key.as_bytes(), serde_json::to_vec(&box_item)?);
+                self.journal(&mut batch, &record_key, false)?;
+            }
+            batch.insert(key.as_bytes(), box_item.id.as_bytes());
+            seen.insert(key);
+        }
+
+        let mut secrets: Vec<Secret> = self.get_all_entities(db, "secret")?;
+        secrets.sort_by_key(|secret| secret.created_at);
+        for secret in &mut secrets {
+            let mut key = Self::secret_name_index_key(&secret.box_id, &secret.name);
+            if seen.contains(&key) {
+                let name = deduplicated_name(&secret.name, MAX_SECRET_NAME_LENGTH, |name| {
+                    seen.contains(&Self::secret_name_index_key(&secret.box_id, name))
+                });
+                tracing::warn!(
+                    "Duplicate secret name after normalization, renamed to {}",
+                    redact(&name)
+                );
+                secret.name = name;
+                secret.updated_at = chrono::Utc::now();
+                key = Self::secret_name_index_key(&secret.box_id, &secret.name);
+
+                Self::insert_secret(&mut batch, secret)?;
+                self.journal(&mut batch, &Self::secret_record_key(&secret.id), false)?;
+            }
+            batch.insert(key.as_bytes(), secret.id.as_bytes());
+            seen.insert(key);
+        }
+
+        // Replaced by the schema version
+        batch.remove("name_index_version");
+        db.apply_batch(batch)?;
+        self.writes.mark_dirty();
+        Ok(())
+    }
+
+    // Values saved inline before large ones had their own key (schema migration 2)
+    pub(crate) fn move_large_secret_values(&self) -> Result<(), ZapError> {
+        let db = self.get_db()?;
+        let secrets: Vec<Secret> = self.get_all_entities(db, "secret")?;
+        let mut batch = sled::Batch::default();
+        for secret in secrets
+            .iter()
+            .filter(|secret| secret.encrypted_value.cipher.len() > INLINE_SECRET_VALUE_BYTES)
+        {
+            Self::insert_secret(&mut batch, secret)?;
+        }
+
+        db.apply_batch(batch)?;
+        self.writes.mark_dirty();
+        Ok(())
+    }
+
+    // 0 for vaults written before schema versions existed
+    pub fn schema_version(&self) -> Result<u32, ZapError> {
+        let db = self.get_db()?;
+        Ok(match db.get(SCHEMA_VERSION_KEY)? {
+            Some(bytes) => u32::from_be_bytes(bytes.as_ref().try_into().map_err(|_| {
+                ZapError::StorageError("Invalid vault schema version".to_string())
+            })?),
+            None => 0,
+        })
+    }
+
+    // Flushed right away so a crash mid-upgrade never re-runs a finished migration
+    pub(crate) fn set_schema_version(&self, version: u32) -> Result<(), ZapError> {
+        let db = self.get_db()?;
+        db.insert(SCHEMA_VERSION_KEY, &version.to_be_bytes())?;
+        db.flush()?;
+        Ok(())
+    }
+
+    pub(crate) fn vault_is_empty(&self) -> Result<bool, ZapError> {
+        Ok(self.get_db()?.is_empty())
+    }
 }
 
 // BOX OPERATIONS
@@ -171,7 +819,7 @@ impl StorageService {
             "box",
             &box_item.id,
             box_item,
-            Some((&format!("box_name:{}", box_item.name), &box_item.id)),
+            Some((&Self::name_index_key("box_name", &box_item.name), &box_item.id)),
         )
     }
 
@@ -181,8 +829,8 @@ impl StorageService {
             .get_entity(db, "box", box_id)?
             .ok_or_else(|| ZapError::BoxNotFound(box_id.to_string()))?;
 
-        let secrets_count = self.count_secrets_in_box(&box_item.id)?;
-        box_item.update_secrets_count(secrets_count);
+        // Derived on read, so it doesn't touch updated_at
+        box_item.secrets_count = self.count_secrets_in_box(&box_item.id)?;
         Ok(box_item)
     }
 
@@ -195,10 +843,9 @@ impl StorageService {
         let db = self.get_db()?;
         let mut boxes: Vec<Box> = self.get_all_entities(db, "box")?;
 
-        // Update secrets counts
+        // Update secrets counts (derived on read, so they don't touch updated_at)
         for box_item in &mut boxes {
-            let count = self.count_secrets_in_box(&box_item.id)?;
-            box_item.update_secrets_count(count);
+            box_item.secrets_count = self.count_secrets_in_box(&box_item.id)?;
         }
 
         boxes.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
@@ -206,6 +853,25 @@ impl StorageService {
     }
 
     pub fn update_box(&self, box_item: &Box) -> Result<(), ZapError> {
+        let _box_lock = self.box_locks.lock(&box_item.id);
+        self.replace_box(box_item)
+    }
+
+    /// Read-modify-write of a box under its lock, so a concurrent secret write can't interleave.
+    /// Returns: the box as saved
+    pub fn modify_box<F>(&self, box_id: &str, change: F) -> Result<Box, ZapError>
+    where
+        F: FnOnce(&mut Box) -> Result<(), ZapError>,
+    {
+        let _box_lock = self.box_locks.lock(box_id);
+        let mut box_item = self.get_box(box_id)?;
+        change(&mut box_item)?;
+        self.replace_box(&box_item)?;
+        Ok(box_item)
+    }
+
+    // Caller holds the box lock
+    fn replace_box(&self, box_item: &Box) -> Result<(), ZapError> {
         let db = self.get_db()?;
         let existing_box = self
             .get_entity::<Box>(db, "box", &box_item.id)?
@@ -213,7 +879,7 @@ impl StorageService {
 
         // Remove old name mapping if changed
         if existing_box.name != box_item.name {
-            let old_name_key = format!("box_name:{}", existing_box.name);
+            let old_name_key = Self::name_index_key("box_name", &existing_box.name);
             db.remove(old_name_key.as_bytes())?;
         }
 
@@ -224,7 +890,7 @@ impl StorageService {
         let box_item = self.get_box(box_id)?;
         let db = self.get_db()?;
 
-        let cleanup_keys = vec![format!("box_name:{}", box_item.name)];
+        let cleanup_keys = vec![Self::name_index_key("box_name", &box_item.name)];
         self.delete_entity(db, "box", box_id, cleanup_keys)
     }
 
@@ -245,34 +911,40 @@ impl StorageService {
 impl StorageService {
     pub fn save_secret(&self, secret: &Secret) -> Result<(), ZapError> {
         let db = self.get_db()?;
+        let _box_lock = self.box_locks.lock(&secret.box_id);
 
-        // Check if box exists
-        self.get_box(&secret.box_id)?;
+        // Check if box exists and has room
+        let box_item = self.get_box(&secret.box_id)?;
+        if box_item.secrets_count >= MAX_SECRETS_PER_BOX {
+            return Err(ZapError::BoxCapacityExceeded);
+        }
 
         // Check name uniqueness within box
-        let name_key = format!("secret_name:{}:{}", secret.box_id, secret.name);
+        let name_key = Self::secret_name_index_key(&secret.box_id, &secret.name);
         if let Some(_existing_id) =
             self.get_entity_id_by_name(db, &secret.name, &format!("secret_name:{}", secret.box_id))?
         {
             return Err(ZapError::SecretAlreadyExistsInBox(secret.name.clone()));
         }
 
-        self.save_entity(
-            db,
-            "secret",
-            &secret.id,
-            secret,
-            Some((&name_key, &secret.id)),
-        )?;
+        let mut batch = sled::Batch::default();
+        Self::insert_secret(&mut batch, secret)?;
+        self.journal(&mut batch, &Self::secret_record_key(&secret.id), false)?;
+        batch.insert(name_key.as_bytes(), secret.id.as_bytes());
+        db.apply_batch(batch)?;
+        self.writes.mark_dirty();
 
         self.update_box_count_after_secret_change(&secret.box_id)?;
         Ok(())
     }
 
+    /// A secret with its value, loaded from the blob tree when stored there
     pub fn get_secret(&self, secret_id: &str) -> Result<Secret, ZapError> {
         let db = self.get_db()?;
-        self.get_entity(db, "secret", secret_id)?
-            .ok_or_else(|| ZapError::SecretNotFound(secret_id.to_string()))
+        let secret = self
+            .get_entity(db, "secret", secret_id)?
+            .ok_or_else(|| ZapError::SecretNotFound(secret_id.to_string()))?;
+        self.load_secret_value(secret)
     }
 
     pub fn get_secret_by_name_in_box(
@@ -292,95 +964,186 @@ impl StorageService {
     }
 
     pub fn get_secrets_by_box_id(&self, box_id: &str) -> Result<Vec<Secret>, ZapError> {
-        let db = self.get_db()?;
-        let mut secrets: Vec<Secret> = self.get_all_entities(db, "secret")?;
+        self.list_secrets_by_box_id(box_id)?
+            .into_iter()
+            .map(|secret| self.load_secret_value(secret))
+            .collect()
+    }
+
+    pub fn get_all_secrets(&self) -> Result<Vec<Secret>, ZapError> {
+        self.list_all_secrets()?
+            .into_iter()
+            .map(|secret| self.load_secret_value(secret))
+            .collect()
+    }
 
+    /// Secrets of a box for listings: large values are left in the blob tree
+    /// (`value_in_blob` set, `encrypted_value` empty)
+    pub fn list_secrets_by_box_id(&self, box_id: &str) -> Result<Vec<Secret>, ZapError> {
+        let mut secrets = self.list_all_secrets()?;
         secrets.retain(|s| s.box_id == box_id);
-        secrets.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
         Ok(secrets)
     }
 
-    pub fn get_all_secrets(&self) -> Result<Vec<Secret>, ZapError> {
+    /// All secrets for listings, see `list_secrets_by_box_id`
+    pub fn list_all_secrets(&self) -> Result<Vec<Secret>, ZapError> {
         let db = self.get_db()?;
         let mut secrets: Vec<Secret> = self.get_all_entities(db, "secret")?;
         secrets.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
         Ok(secrets)
     }
 
+    /// Fill in a value that lives in the blob tree, a no-op for inline values
+    pub fn load_secret_value(&self, mut secret: Secret) -> Result<Secret, ZapError> {
+        if !secret.value_in_blob || !secret.encrypted_value.cipher.is_empty() {
+            return Ok(secret);
+        }
+        let data = self
+            .get_db()?
+            .get(Self::secret_blob_key(&secret.id).as_bytes())?
+            .ok_or_else(|| {
+                ZapError::StorageError(format!("Value of secret '{}' is missing", secret.name))
+            })?;
+        secret.encrypted_value = serde_json::from_slice(&data)?;
+        Ok(secret)
+    }
+
+    fn secret_record_key(secret_id: &str) -> String {
+        format!("secret:{}", secret_id)
+    }
+
+    fn secret_blob_key(secret_id: &str) -> String {
+        format!("secret_blob:{}", secret_id)
+    }
+
+    fn secret_usage_key(secret_id: &str) -> String {
+        format!("secret_usage:{}", secret_id)
+    }
+
+    fn secret_breach_key(secret_id: &str) -> String {
+        format!("secret_breach:{}", secret_id)
+    }
+
+    // Write a secret record, moving a large value into its own blob key.
+    // A listing stub (value not loaded) keeps the stored blob.
+    fn insert_secret(batch: &mut sled::Batch, secret: &Secret) -> Result<(), ZapError> {
+        let blob_key = Self::secret_blob_key(&secret.id);
+        let is_stub = secret.value_in_blob && secret.encrypted_value.cipher.is_empty();
+        let mut record = secret.clone();
+        if secret.encrypted_value.cipher.len() > INLINE_SECRET_VALUE_BYTES {
+            batch.insert(
+                blob_key.as_bytes(),
+                serde_json::to_vec(&secret.encrypted_value)?,
+            );
+            record.encrypted_value = EncryptedData::empty();
+            record.value_in_blob = true;
+        } else if !is_stub {
+            batch.remove(blob_key.as_bytes());
+            record.value_in_blob = false;
+        }
+        batch.insert(
+            Self::secret_record_key(&secret.id).as_bytes(),
+            serde_json::to_vec(&record)?,
+        );
+        Ok(())
+    }
+
     pub fn update_secret(&self, secret: &Secret) -> Result<(), ZapError> {
         let db = self.get_db()?;
+        let _box_lock = self.box_locks.lock(&secret.box_id);
         let existing_secret = self.get_secret(&secret.id)?;
+        let old_name_key =
+            Self::secret_name_index_key(&existing_secret.box_id, &existing_secret.name);
+        let name_key = Self::secret_name_index_key(&secret.box_id, &secret.name);
 
-        // Remove old name mapping if changed
-        if existing_secret.name != secret.name {
-            let old_name_key = format!(
-                "secret_name:{}:{}",
-                existing_secret.box_id, existing_secret.name
-            );
-            db.remove(old_name_key.as_bytes())?;
-
-            // Check new name uniqueness
-            if self
-                .get_secret_by_name_in_box(&secret.name, &secret.box_id)?
-                .is_some()
-            {
+        // A rename must not take another secret's name (a case-only rename keeps its own)
+        if let Some(owner_id) =
+            self.get_entity_id_by_name(db, &secret.name, &format!("secret_name:{}", secret.box_id))?
+        {
+            if owner_id != secret.id {
                 return Err(ZapError::SecretAlreadyExistsInBox(secret.name.clone()));
             }
         }
 
-        let name_key = format!("secret_name:{}:{}", secret.box_id, secret.name);
-        self.save_entity(
-            db,
-            "secret",
-            &secret.id,
-            secret,
-            Some((&name_key, &secret.id)),
-        )
+        let mut batch = sled::Batch::default();
+        // Remove old name mapping if changed, in the same batch as the new one
+        if old_name_key != name_key {
+            batch.remove(old_name_key.as_bytes());
+        }
+        Self::insert_secret(&mut batch, secret)?;
+        self.journal(&mut batch, &Self::secret_record_key(&secret.id), false)?;
+        batch.insert(name_key.as_bytes(), secret.id.as_bytes());
+        db.apply_batch(batch)?;
+        self.writes.mark_dirty();
+        Ok(())
     }
 
     pub fn delete_secret(&self, secret_id: &str) -> Result<(), ZapError> {
-        let secret = self.get_secret(secret_id)?;
-        let box_id = secret.box_id.clone();
         let db = self.get_db()?;
+        let secret: Secret = self
+            .get_entity(db, "secret", secret_id)?
+            .ok_or_else(|| ZapError::SecretNotFound(secret_id.to_string()))?;
 
-        let cleanup_keys = vec![format!("secret_name:{}:{}", secret.box_id, secret.name)];
-        self.delete_entity(db, "secret", secret_id, cleanup_keys)?;
-
-        self.update_box_count_after_secret_change(&box_id)?;
+        let _box_lock = self.box_locks.lock(&secret.box_id);
+        self.delete_secret_record(db, &secret)?;
+        self.update_box_count_after_secret_change(&secret.box_id)?;
         Ok(())
     }
 
     pub fn delete_selected_secrets(&self, secret_ids: &[String]) -> Result<Vec<String>, ZapError> {
-        let mut affected_boxes = std::collections::HashSet::new();
+        let db = self.get_db()?;
+        let mut by_box: HashMap<String, Vec<Secret>> = HashMap::new();
         let mut deleted_names = Vec::new();
 
         for secret_id in secret_ids {
-            let secret = self.get_secret(secret_id)?;
-            affected_boxes.insert(secret.box_id.clone());
+            let secret: Secret = self
+                .get_entity(db, "secret", secret_id)?
+                .ok_or_else(|| ZapError::SecretNotFound(secret_id.to_string()))?;
             deleted_names.push(secret.name.clone());
-
-            let db = self.get_db()?;
-            let cleanup_keys = vec![format!("secret_name:{}:{}", secret.box_id, secret.name)];
-            self.delete_entity(db, "secret", secret_id, cleanup_keys)?;
+            by_box
+                .entry(secret.box_id.clone())
+                .or_default()
+                .push(secret);
         }
 
-        for box_id in affected_boxes {
-            self.update_box_count_after_secret_change(&box_id)?;
+        // One box at a time - the locks aren't re-entrant and boxes may share a stripe
+        for (box_id, secrets) in &by_box {
+            let _box_lock = self.box_locks.lock(box_id);
+            for secret in secrets {
+                self.delete_secret_record(db, secret)?;
+            }
+            self.update_box_count_after_secret_change(box_id)?;
         }
 
         Ok(deleted_names)
     }
 
-    pub fn copy_secrets_to_box(
+    fn delete_secret_record(&self, db: &sled::Db, secret: &Secret) -> Result<(), ZapError> {
+        let cleanup_keys = vec![
+            Self::secret_name_index_key(&secret.box_id, &secret.name),
+            Self::secret_blob_key(&secret.id),
+            Self::secret_usage_key(&secret.id),
+            Self::secret_breach_key(&secret.id),
+        ];
+        self.delete_entity(db, "secret", &secret.id, cleanup_keys)
+    }
+
+    /// Copy secrets into another box, `reencrypt(source, copy)` seals the value and any
+    /// environment variants for the copy
+    pub fn copy_secrets_to_box<F>(
         &self,
         secret_ids: &[String],
         target_box_id: &str,
-        _master_key: &[u8; 32],
-    ) -> Result<Vec<String>, ZapError> {
+        reencrypt: F,
+    ) -> Result<Vec<String>, ZapError>
+    where
+        F: Fn(&Secret, &mut Secret) -> Result<(), ZapError>,
+    {
+        let _box_lock = self.box_locks.lock(target_box_id);
         let _target_box = self.get_box(target_box_id)?;
         let target_secrets_count = self.count_secrets_in_box(target_box_id)?;
 
-        if target_secrets_count + secret_ids.len() > 75 {
+        if target_secrets_count + secret_ids.len() > MAX_SECRETS_PER_BOX {
             return Err(ZapError::BoxCapacityExceeded);
         }
 
@@ -397,21 +1160,26 @@ impl StorageService {
                 continue; // Skip duplicates
             }
 
-            let new_secret = Secret::new(
+            let mut new_secret = Secret::new(
                 target_box_id.to_string(),
                 source_secret.name.clone(),
-                source_secret.encrypted_value.clone(),
-            )?;
+                EncryptedData::empty(),
+            )?
+            .with_env_name(source_secret.env_name.clone())
+            .with_value_encoding(source_secret.value_encoding)
+            .with_multiline_format(source_secret.multiline_format);
+            new_secret.rotation_interval_days = source_secret.rotation_interval_days;
+            new_secret.strength = source_secret.strength.clone();
+            reencrypt(&source_secret, &mut new_secret)?;
 
             let db = self.get_db()?;
-            let name_key = format!("secret_name:{}:{}", new_secret.box_id, new_secret.name);
-            self.save_entity(
-                db,
-                "secret",
-                &new_secret.id,
-                &new_secret,
-                Some((&name_key, &new_secret.id)),
-            )?;
+            let name_key = Self::secret_name_index_key(&new_secret.box_id, &new_secret.name);
+            let mut batch = sled::Batch::default();
+            Self::insert_secret(&mut batch, &new_secret)?;
+            self.journal(&mut batch, &Self::secret_record_key(&new_secret.id), false)?;
+            batch.insert(name_key.as_bytes(), new_secret.id.as_bytes());
+            db.apply_batch(batch)?;
+            self.writes.mark_dirty();
 
             copied_names.push(new_secret.name);
         }
@@ -420,31 +1188,439 @@ impl StorageService {
 
         Ok(copied_names)
     }
+
+    /// Save a new box together with its secrets in one batch (vault import).
+    /// Secret names must already be unique within the box.
+    pub fn save_box_with_secrets(
+        &self,
+        box_item: &Box,
+        secrets: &[Secret],
+    ) -> Result<(), ZapError> {
+        let db = self.get_db()?;
+        if self.get_entity_id_by_name(db, &box_item.name, "box_name")?.is_some() {
+            return Err(ZapError::BoxAlreadyExists(box_item.name.clone()));
+        }
+
+        // Set directly, imported boxes keep their exported timestamps
+        let mut box_item = box_item.clone();
+        box_item.secrets_count = secrets.len();
+
+        let mut batch = sled::Batch::default();
+        let box_key = format!("box:{}", box_item.id);
+        batch.insert(box_key.as_bytes(), serde_json::to_vec(&box_item)?);
+        self.journal(&mut batch, &box_key, false)?;
+        batch.insert(
+            Self::name_index_key("box_name", &box_item.name).as_bytes(),
+            box_item.id.as_bytes(),
+        );
+        for secret in secrets {
+            Self::insert_secret(&mut batch, secret)?;
+            self.journal(&mut batch, &Self::secret_record_key(&secret.id), false)?;
+            batch.insert(
+                Self::secret_name_index_key(&box_item.id, &secret.name).as_bytes(),
+                secret.id.as_bytes(),
+            );
+        }
+
+        db.apply_batch(batch)?;
+        self.writes.mark_dirty();
+        Ok(())
+    }
+
+    /// Rewrite boxes and secrets in one batch after a key change (names must be unchanged)
+    pub fn save_rekeyed(&self, boxes: &[Box], secrets: &[Secret]) -> Result<(), ZapError> {
+        let db = self.get_db()?;
+        let mut batch = sled::Batch::default();
+
+        for box_item in boxes {
+            let key = format!("box:{}", box_item.id);
+            batch.insert(key.as_bytes(), serde_json::to_vec(box_item)?);
+        }
+        for secret in secrets {
+            Self::insert_secret(&mut batch, secret)?;
+        }
+
+        db.apply_batch(batch)?;
+        self.writes.mark_dirty();
+        Ok(())
+    }
+
+    /// Persist a credential change in one flushed batch: re-wrapped box keys, the export
+    /// signing key and the auth config holding the new salt, hash and recovery key
+    pub fn save_rekeyed_credentials(
+        &self,
+        boxes: &[Box],
+        signing_key: Option<&EncryptedData>,
+        config: &AuthConfig,
+    ) -> Result<(), ZapError> {
+        let db = self.get_db()?;
+        let mut batch = sled::Batch::default();
+
+        for box_item in boxes {
+            let key = format!("box:{}", box_item.id);
+            batch.insert(key.as_bytes(), serde_json::to_vec(box_item)?);
+        }
+        if let Some(signing_key) = signing_key {
+            batch.insert("export_signing_key", serde_json::to_vec(signing_key)?);
+        }
+        batch.insert("auth_config", serde_json::to_vec(config)?);
+
+        db.apply_batch(batch)?;
+        db.flush()?;
+        self.writes.mark_dirty();
+        Ok(())
+    }
+
+    pub fn secrets_bound(&self) -> Result<bool, ZapError> {
+        Ok(self.get_db()?.get(SECRETS_BOUND_KEY)?.is_some())
+    }
+
+    /// Save re-encrypted secrets and mark the vault as fully bound in one batch
+    pub fn save_bound_secrets(&self, secrets: &[Secret]) -> Result<(), ZapError> {
+        let db = self.get_db()?;
+        let mut batch = sled::Batch::default();
+
+        for secret in secrets {
+            Self::insert_secret(&mut batch, secret)?;
+        }
+        batch.insert(SECRETS_BOUND_KEY, &[1u8][..]);
+
+        db.apply_batch(batch)?;
+        self.writes.mark_dirty();
+        Ok(())
+    }
 }
 
-// SEARCH OPERATIONS
+// SECRET USAGE (for the recent list)
 impl StorageService {
-    pub fn search_boxes_global(&self, query: &str, tags: &[String]) -> Result<Vec<Box>, ZapError> {
-        let all_boxes = self.get_all_boxes()?;
+    /// Count a reveal or copy. Kept beside the secret record, so using a secret doesn't rewrite it.
+    pub fn record_secret_use(&self, secret_id: &str) -> Result<(), ZapError> {
+        let db = self.get_db()?;
+        let key = Self::secret_usage_key(secret_id);
+        let use_count = match db.get(key.as_bytes())? {
+            Some(data) => serde_json::from_slice::<SecretUsage>(&data)?.use_count,
+            None => 0,
+        };
+
+        let usage = SecretUsage {
+            last_used_at: chrono::Utc::now(),
+            use_count: use_count.saturating_add(1),
+        };
+        db.insert(key.as_bytes(), serde_json::to_vec(&usage)?)?;
+        self.writes.mark_dirty();
+        Ok(())
+    }
+
+    /// Usage of every secret that was ever revealed or copied, by secret id (unordered)
+    pub fn list_secret_usage(&self) -> Result<Vec<(String, SecretUsage)>, ZapError> {
+        let db = self.get_db()?;
+        db.scan_prefix("secret_usage:")
+            .map(|entry| {
+                let (key, data) = entry?;
+                let secret_id = String::from_utf8(key["secret_usage:".len()..].to_vec())?;
+                Ok((secret_id, serde_json::from_slice(&data)?))
+            })
+            .collect()
+    }
+}
+
+// SECRET BREACHES (Have I Been Pwned checks)
+impl StorageService {
+    pub fn get_secret_breach(&self, secret_id: &str) -> Result<Option<SecretBreach>, ZapError> {
+        let db = self.get_db()?;
+        match db.get(Self::secret_breach_key(secret_id).as_bytes())? {
+            Some(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn save_secret_breach(
+        &self,
+        secret_id: &str,
+        breach: &SecretBreach,
+    ) -> Result<(), ZapError> {
+        let db = self.get_db()?;
+        let key = Self::secret_breach_key(secret_id);
+        db.insert(key.as_bytes(), serde_json::to_vec(breach)?)?;
+        self.writes.mark_dirty();
+        Ok(())
+    }
+
+    /// Last check result of every checked secret, by secret id (unordered)
+    pub fn list_secret_breaches(&self) -> Result<Vec<(String, SecretBreach)>, ZapError> {
+        let db = self.get_db()?;
+        db.scan_prefix("secret_breach:")
+            .map(|entry| {
+                let (key, data) = entry?;
+                let secret_id = String::from_utf8(key["secret_breach:".len()..].to_vec())?;
+                Ok((secret_id, serde_json::from_slice(&data)?))
+            })
+            .collect()
+    }
+
+    pub fn load_last_breach_check(
+        &self,
+    ) -> Result<Option<chrono::DateTime<chrono::Utc>>, ZapError> {
+        let db = self.get_db()?;
+        match db.get("last_breach_check")? {
+            Some(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn save_last_breach_check(
+        &self,
+        at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<(), ZapError> {
+        let db = self.get_db()?;
+        db.insert("last_breach_check", serde_json::to_vec(&at)?)?;
+        Ok(())
+    }
+}
+
+// CHANGE JOURNAL
+// Latest version of each box and secret under record_version:<record key>; deletions leave a
+// tombstone. Per replica: the version both sides agreed on at the last sync and open conflicts.
+impl StorageService {
+    /// This vault's id in record versions, made on first use
+    pub fn device_id(&self) -> Result<String, ZapError> {
+        let db = self.get_db()?;
+        if let Some(id) = db.get(DEVICE_ID_KEY)? {
+            return Ok(String::from_utf8(id.to_vec())?);
+        }
+
+        let id = uuid::Uuid::new_v4().to_string();
+        match db.compare_and_swap(DEVICE_ID_KEY, None as Option<&[u8]>, Some(id.as_bytes()))? {
+            Ok(()) => Ok(id),
+            // Made by a concurrent call, keep that one
+            Err(raced) => {
+                let current = raced.current.unwrap_or_default();
+                Ok(String::from_utf8(current.to_vec())?)
+            }
+        }
+    }
+
+    /// Move the Lamport clock past a time seen on a replica
+    pub fn observe_lamport(&self, lamport: u64) -> Result<(), ZapError> {
+        self.get_db()?
+            .fetch_and_update(LAMPORT_CLOCK_KEY, |current| {
+                let time = current.map(clock_time).unwrap_or(0).max(lamport);
+                Some(time.to_be_bytes().to_vec())
+            })?;
+        Ok(())
+    }
+
+    /// Latest version of every journaled box and secret, by record key
+    pub fn record_versions(&self) -> Result<HashMap<String, RecordVersion>, ZapError> {
+        self.scan_versions("record_version:")
+    }
+
+    /// Give a record the version it has on a replica, after writing the replica's content.
+    /// The write itself is then no longer a local change.
+    pub fn adopt_record_version(
+        &self,
+        record_key: &str,
+        version: &RecordVersion,
+    ) -> Result<(), ZapError> {
+        let db = self.get_db()?;
+        db.insert(
+            Self::record_version_key(record_key).as_bytes(),
+            serde_json::to_vec(version)?,
+        )?;
+        self.writes.mark_dirty();
+        Ok(())
+    }
+
+    /// Versions agreed on with a replica at its last sync, by record key
+    pub fn replica_bases(
+        &self,
+        replica_id: &str,
+    ) -> Result<HashMap<String, RecordVersion>, ZapError> {
+        self.scan_versions(&format!("replica_base:{}:", replica_id))
+    }
 
-        if query.trim().is_empty() && tags.is_empty() {
-            return Ok(all_boxes);
+    pub fn save_replica_bases(
+        &self,
+        replica_id: &str,
+        bases: &[(String, RecordVersion)],
+    ) -> Result<(), ZapError> {
+        let db = self.get_db()?;
+        let mut batch = sled::Batch::default();
+        for (record_key, version) in bases {
+            let key = format!("replica_base:{}:{}", replica_id, record_key);
+            batch.insert(key.as_bytes(), serde_json::to_vec(version)?);
         }
+        db.apply_batch(batch)?;
+        self.writes.mark_dirty();
+        Ok(())
+    }
+
+    pub fn list_sync_conflicts(&self, replica_id: &str) -> Result<Vec<SyncConflict>, ZapError> {
+        let db = self.get_db()?;
+        self.get_all_entities(db, &format!("replica_conflict:{}", replica_id))
+    }
+
+    /// Replace a replica's open conflicts with the ones its latest sync found
+    pub fn save_sync_conflicts(
+        &self,
+        replica_id: &str,
+        conflicts: &[SyncConflict],
+    ) -> Result<(), ZapError> {
+        let db = self.get_db()?;
+        let prefix = format!("replica_conflict:{}:", replica_id);
+        let mut batch = sled::Batch::default();
+        for result in db.scan_prefix(&prefix) {
+            let (key, _) = result?;
+            batch.remove(key);
+        }
+        for conflict in conflicts {
+            let key = format!("{}{}", prefix, conflict.record_key);
+            batch.insert(key.as_bytes(), serde_json::to_vec(conflict)?);
+        }
+        db.apply_batch(batch)?;
+        self.writes.mark_dirty();
+        Ok(())
+    }
+
+    /// Forget everything synced with a replica, e.g. once it is removed from Settings
+    pub fn clear_replica_state(&self, replica_id: &str) -> Result<(), ZapError> {
+        let db = self.get_db()?;
+        let mut batch = sled::Batch::default();
+        for prefix in ["replica_base", "replica_conflict"] {
+            for result in db.scan_prefix(format!("{}:{}:", prefix, replica_id)) {
+                let (key, _) = result?;
+                batch.remove(key);
+            }
+        }
+        db.apply_batch(batch)?;
+        self.writes.mark_dirty();
+        Ok(())
+    }
+
+    fn record_version_key(record_key: &str) -> String {
+        format!("record_version:{}", record_key)
+    }
+
+    // Version the write in `batch` to a box or secret with the next Lamport time
+    fn journal(
+        &self,
+        batch: &mut sled::Batch,
+        record_key: &str,
+        deleted: bool,
+    ) -> Result<(), ZapError> {
+        let lamport = self
+            .get_db()?
+            .update_and_fetch(LAMPORT_CLOCK_KEY, |current| {
+                let time = current.map(clock_time).unwrap_or(0) + 1;
+                Some(time.to_be_bytes().to_vec())
+            })?
+            .map(|time| clock_time(&time))
+            .unwrap_or(0);
+        let version = RecordVersion {
+            lamport,
+            device_id: self.device_id()?,
+            updated_at: chrono::Utc::now(),
+            deleted,
+        };
+        batch.insert(
+            Self::record_version_key(record_key).as_bytes(),
+            serde_json::to_vec(&version)?,
+        );
+        Ok(())
+    }
+
+    fn scan_versions(&self, prefix: &str) -> Result<HashMap<String, RecordVersion>, ZapError> {
+        let mut versions = HashMap::new();
+        for result in self.get_db()?.scan_prefix(prefix) {
+            let (key, value) = result?;
+            let record_key = String::from_utf8(key[prefix.len()..].to_vec())?;
+            versions.insert(record_key, serde_json::from_slice(&value)?);
+        }
+        Ok(versions)
+    }
+}
+
+// The record key of a box or secret entry, None for any other key
+// `Name (2)`, `Name (3)`... shortened to fit `max_chars`, the first one `taken` lets through
+fn deduplicated_name(name: &str, max_chars: usize, taken: impl Fn(&str) -> bool) -> String {
+    let mut copy = 2;
+    loop {
+        let suffix = format!(" ({})", copy);
+        let stem: String = name
+            .chars()
+            .take(max_chars.saturating_sub(suffix.chars().count()))
+            .collect();
+        let candidate = format!("{}{}", stem.trim_end(), suffix);
+        if !taken(&candidate) {
+            return candidate;
+        }
+        copy += 1;
+    }
+}
+
+fn journaled_record_key(key: &[u8]) -> Option<String> {
+    let key = std::str::from_utf8(key).ok()?;
+    let (prefix, _) = key.split_once(':')?;
+    JOURNALED_PREFIXES
+        .contains(&prefix)
+        .then(|| key.to_string())
+}
+
+fn clock_time(bytes: &[u8]) -> u64 {
+    bytes.try_into().map(u64::from_be_bytes).unwrap_or(0)
+}
+
+// SEARCH OPERATIONS
+impl StorageService {
+    pub fn search_boxes_global(
+        &self,
+        query: &str,
+        tags: &[String],
+    ) -> Result<Vec<BoxSearchResult>, ZapError> {
+        let all_boxes = self.get_all_boxes()?;
+        let has_query = !query.trim().is_empty();
 
-        let query_lower = query.to_lowercase();
         Ok(all_boxes
             .into_iter()
-            .filter(|box_item| {
-                let name_match =
-                    query.trim().is_empty() || box_item.name.to_lowercase().contains(&query_lower);
-                let tag_match =
-                    tags.is_empty() || tags.iter().any(|tag| box_item.tags.contains(tag));
-
-                if query.trim().is_empty() && !tags.is_empty() {
-                    tag_match
-                } else {
-                    name_match || tag_match
+            .filter_map(|box_item| {
+                let name_ranges = find_match_ranges(&box_item.name, query);
+                let matched_tags: Vec<String> = tags
+                    .iter()
+                    .filter(|tag| box_item.tags.contains(tag))
+                    .cloned()
+                    .collect();
+
+                let keep = match (has_query, tags.is_empty()) {
+                    (false, true) => true,
+                    (false, false) => !matched_tags.is_empty(),
+                    (true, true) => !name_ranges.is_empty(),
+                    (true, false) => !name_ranges.is_empty() || !matched_tags.is_empty(),
+                };
+                if !keep {
+                    return None;
                 }
+
+                let mut matches: Vec<MatchSpan> = name_ranges
+                    .into_iter()
+                    .map(|(start, end)| MatchSpan {
+                        field: MatchField::Name,
+                        start,
+                        end,
+                        tag: None,
+                    })
+                    .collect();
+                matches.extend(matched_tags.iter().map(|tag| MatchSpan {
+                    field: MatchField::Tag,
+                    start: 0,
+                    end: tag.len(),
+                    tag: Some(tag.clone()),
+                }));
+
+                Some(BoxSearchResult {
+                    item: box_item,
+                    matches,
+                    matched_tags,
+                })
             })
             .collect())
     }
@@ -453,18 +1629,33 @@ impl StorageService {
         &self,
         box_id: &str,
         query: &str,
-    ) -> Result<Vec<Secret>, ZapError> {
-        let box_secrets = self.get_secrets_by_box_id(box_id)?;
-
-        if query.trim().is_empty() {
-            return Ok(box_secrets);
-        }
+    ) -> Result<Vec<SecretSearchResult>, ZapError> {
+        let box_secrets = self.list_secrets_by_box_id(box_id)?;
+        let has_query = !query.trim().is_empty();
 
-        let query_lower = query.to_lowercase();
-        Ok(box_secrets
+        let mut results: Vec<SecretSearchResult> = box_secrets
             .into_iter()
-            .filter(|secret| secret.name.to_lowercase().contains(&query_lower))
-            .collect())
+            .filter_map(|secret| {
+                let matches: Vec<MatchSpan> = find_match_ranges(&secret.name, query)
+                    .into_iter()
+                    .map(|(start, end)| MatchSpan {
+                        field: MatchField::Name,
+                        start,
+                        end,
+                        tag: None,
+                    })
+                    .collect();
+
+                (!has_query || !matches.is_empty()).then_some(SecretSearchResult {
+                    item: secret,
+                    matches,
+                })
+            })
+            .collect();
+
+        // Favorites first, the rest keep their order
+        results.sort_by_key(|result| !result.item.favorite);
+        Ok(results)
     }
 }
 
@@ -473,7 +1664,7 @@ impl StorageService {
     /// Add a log entry
     pub fn log(
         &self,
-        action: String,
+        action: LogAction,
         message: String,
         content: Option<String>,
     ) -> Result<(), ZapError> {
@@ -481,8 +1672,8 @@ impl StorageService {
         let logs_db = self.get_logs_db()?; // 🔥 Use separate logs database
         self.save_entity(logs_db, "log", &entry.id, &entry, None)?;
 
-        // Optional: Print to console
-        println!("📋 {}", entry.message);
+        // Messages name boxes and secrets, so only the action goes to diagnostics
+        tracing::debug!(action = %entry.action, severity = ?entry.severity, "Audit entry recorded");
         Ok(())
     }
 
@@ -490,10 +1681,25 @@ impl StorageService {
     pub fn get_all_logs(&self) -> Result<Vec<LogEntry>, ZapError> {
         let logs_db = self.get_logs_db()?;
         let mut entries: Vec<LogEntry> = self.get_all_entities(logs_db, "log")?;
+        for entry in &mut entries {
+            // Entries from before severities were stored default to Info
+            entry.severity = entry.severity.max(entry.action.severity());
+        }
         entries.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
         Ok(entries)
     }
 
+    /// Get logs at or above a severity and/or for one action (newest first)
+    pub fn get_filtered_logs(
+        &self,
+        min_severity: Option<LogSeverity>,
+        action: Option<LogAction>,
+    ) -> Result<Vec<LogEntry>, ZapError> {
+        let mut entries = self.get_all_logs()?;
+        entries.retain(|entry| entry.matches(min_severity, action));
+        Ok(entries)
+    }
+
     /// Clear all log entries
     pub fn clear_all_logs(&self) -> Result<usize, ZapError> {
         let count = self.count_log_entries()?;
@@ -504,7 +1710,7 @@ impl StorageService {
             logs_db.remove(&key)?;
         }
 
-        logs_db.flush()?;
+        self.writes.mark_dirty();
         Ok(count)
     }
 
@@ -576,7 +1782,7 @@ impl StorageService {
             db.remove(&key)?;
         }
 
-        db.flush()?;
+        self.writes.mark_dirty();
         Ok(())
     }
 
@@ -602,11 +1808,18 @@ impl StorageService {
         }
     }
 
+    /// Largest secret value accepted on save or import (Settings: max_secret_value_kib)
+    pub fn max_secret_value_bytes(&self) -> usize {
+        self.load_settings()
+            .unwrap_or_default()
+            .max_secret_value_bytes()
+    }
+
     pub fn save_settings(&self, settings: &Settings) -> Result<(), ZapError> {
         let db = self.get_db()?;
         let serialized = serde_json::to_vec(settings)?;
         db.insert("settings", serialized)?;
-        db.flush()?;
+        self.writes.mark_dirty();
         Ok(())
     }
 
@@ -625,40 +1838,95 @@ impl StorageService {
         db.flush()?;
         Ok(())
     }
+
+    /// Record a redeemed share id, false if it was already redeemed here.
+    /// Flushed right away - a lost marker would let the share open twice.
+    pub fn mark_share_redeemed(&self, share_id: &str) -> Result<bool, ZapError> {
+        let db = self.get_db()?;
+        let key = format!("share_redeemed:{}", share_id);
+        let redeemed_at = chrono::Utc::now().timestamp().to_string();
+
+        let first_redeem = db
+            .compare_and_swap(key.as_bytes(), None as Option<&[u8]>, Some(redeemed_at.as_bytes()))?
+            .is_ok();
+        db.flush()?;
+        Ok(first_redeem)
+    }
+
+    pub fn load_remembered_key(&self) -> Result<Option<RememberedKey>, ZapError> {
+        let db = self.get_db()?;
+        match db.get("remembered_key")? {
+            Some(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn save_remembered_key(&self, remembered: &RememberedKey) -> Result<(), ZapError> {
+        let db = self.get_db()?;
+        let serialized = serde_json::to_vec(remembered)?;
+        db.insert("remembered_key", serialized)?;
+        db.flush()?;
+        Ok(())
+    }
+
+    pub fn clear_remembered_key(&self) -> Result<(), ZapError> {
+        let db = self.get_db()?;
+        db.remove("remembered_key")?;
+        db.flush()?;
+        Ok(())
+    }
+
+    // Export signing seed, wrapped with the master key
+    pub fn load_export_signing_key(&self) -> Result<Option<EncryptedData>, ZapError> {
+        let db = self.get_db()?;
+        match db.get("export_signing_key")? {
+            Some(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn save_export_signing_key(&self, wrapped: &EncryptedData) -> Result<(), ZapError> {
+        let db = self.get_db()?;
+        let serialized = serde_json::to_vec(wrapped)?;
+        db.insert("export_signing_key", serialized)?;
+        db.flush()?;
+        Ok(())
+    }
+
+    pub fn load_last_auto_export(&self) -> Result<Option<chrono::DateTime<chrono::Utc>>, ZapError> {
+        let db = self.get_db()?;
+        match db.get("last_auto_export")? {
+            Some(data) => Ok(Some(serde_json::from_slice(&data)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn save_last_auto_export(&self, at: chrono::DateTime<chrono::Utc>) -> Result<(), ZapError> {
+        let db = self.get_db()?;
+        db.insert("last_auto_export", serde_json::to_vec(&at)?)?;
+        Ok(())
+    }
 }
 
 // STATISTICS & HELPERS
 impl StorageService {
-    pub fn get_vault_stats(&self) -> Result<VaultStats, ZapError> {
-        let boxes = self.get_all_boxes()?;
-        let all_secrets = self.get_all_secrets()?;
-
-        Ok(VaultStats {
-            total_boxes: boxes.len(),
-            dev_boxes: boxes.iter().filter(|b| b.dev_mode).count(),
-            secure_boxes: 0,
-            total_secrets: all_secrets.len(),
-            sensitive_secrets: 0,
-            last_updated: all_secrets
-                .iter()
-                .map(|s| s.updated_at)
-                .max()
-                .unwrap_or_else(chrono::Utc::now),
-        })
-    }
-
     fn count_secrets_in_box(&self, box_id: &str) -> Result<usize, ZapError> {
-        let secrets = self.get_secrets_by_box_id(box_id)?;
+        let secrets = self.list_secrets_by_box_id(box_id)?;
         Ok(secrets.len())
     }
 
+    // Caller holds the box lock
     fn update_box_count_after_secret_change(&self, box_id: &str) -> Result<(), ZapError> {
-        let mut box_item = self.get_box(box_id)?;
+        // The stored count, get_box would hand back the derived one and hide the drift
+        let db = self.get_db()?;
+        let mut box_item: Box = self
+            .get_entity(db, "box", box_id)?
+            .ok_or_else(|| ZapError::BoxNotFound(box_id.to_string()))?;
         let actual_count = self.count_secrets_in_box(box_id)?;
 
         if box_item.secrets_count != actual_count {
             box_item.update_secrets_count(actual_count);
-            self.update_box(&box_item)?;
+            self.replace_box(&box_item)?;
         }
         Ok(())
     }
@@ -670,13 +1938,152 @@ impl Default for StorageService {
     }
 }
 
-// Statistics struct
-#[derive(serde::Serialize)]
-pub struct VaultStats {
-    pub total_boxes: usize,
-    pub dev_boxes: usize,
-    pub secure_boxes: usize,
-    pub total_secrets: usize,
-    pub sensitive_secrets: usize,
-    pub last_updated: chrono::DateTime<chrono::Utc>,
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use std::thread;
+
+    fn temporary_storage() -> StorageService {
+        let storage = StorageService::new();
+        let db = sled::Config::new().temporary(true).open().unwrap();
+        storage.db.set(db).unwrap();
+        storage
+    }
+
+    fn new_secret(box_id: &str, name: String) -> Secret {
+        Secret::new(box_id.to_string(), name, EncryptedData::empty()).unwrap()
+    }
+
+    // The secrets_count as written, not the one get_box derives on read
+    fn stored_secrets_count(storage: &StorageService, box_id: &str) -> usize {
+        let db = storage.get_db().unwrap();
+        storage
+            .get_entity::<Box>(db, "box", box_id)
+            .unwrap()
+            .unwrap()
+            .secrets_count
+    }
+
+    #[test]
+    fn concurrent_saves_never_overfill_a_box() {
+        let storage = Arc::new(temporary_storage());
+        let box_item = Box::new("capacity".to_string(), None, Vec::new(), false).unwrap();
+        storage.save_box(&box_item).unwrap();
+        for i in 0..MAX_SECRETS_PER_BOX - 5 {
+            let secret = new_secret(&box_item.id, format!("EXISTING_{}", i));
+            storage.save_secret(&secret).unwrap();
+        }
+
+        let writers: Vec<_> = (0..16)
+            .map(|i| {
+                let storage = Arc::clone(&storage);
+                let box_id = box_item.id.clone();
+                thread::spawn(move || {
+                    let secret = new_secret(&box_id, format!("RACING_{}", i));
+                    storage.save_secret(&secret).is_ok()
+                })
+            })
+            .collect();
+        let saved = writers
+            .into_iter()
+            .map(|writer| writer.join())
+            .filter(|joined| matches!(joined, Ok(true)))
+            .count();
+
+        assert_eq!(saved, 5);
+        assert_eq!(
+            storage.count_secrets_in_box(&box_item.id).unwrap(),
+            MAX_SECRETS_PER_BOX
+        );
+        assert_eq!(
+            stored_secrets_count(&storage, &box_item.id),
+            MAX_SECRETS_PER_BOX
+        );
+    }
+
+    #[test]
+    fn box_edits_during_deletes_keep_the_stored_count() {
+        let storage = Arc::new(temporary_storage());
+        let box_item = Box::new("edited".to_string(), None, Vec::new(), false).unwrap();
+        storage.save_box(&box_item).unwrap();
+        let mut secret_ids = Vec::new();
+        for i in 0..20 {
+            let secret = new_secret(&box_item.id, format!("SECRET_{}", i));
+            storage.save_secret(&secret).unwrap();
+            secret_ids.push(secret.id);
+        }
+
+        let deleters: Vec<_> = secret_ids
+            .chunks(5)
+            .map(|chunk| {
+                let storage = Arc::clone(&storage);
+                let chunk = chunk.to_vec();
+                thread::spawn(move || storage.delete_selected_secrets(&chunk).unwrap())
+            })
+            .collect();
+        let editors: Vec<_> = (0..4)
+            .map(|i| {
+                let storage = Arc::clone(&storage);
+                let box_id = box_item.id.clone();
+                thread::spawn(move || {
+                    storage
+                        .modify_box(&box_id, |box_item| {
+                            box_item.description = Some(format!("edit {}", i));
+                            Ok(())
+                        })
+                        .unwrap();
+                })
+            })
+            .collect();
+        for deleter in deleters {
+            deleter.join().unwrap();
+        }
+        for editor in editors {
+            editor.join().unwrap();
+        }
+
+        assert_eq!(storage.count_secrets_in_box(&box_item.id).unwrap(), 0);
+        assert_eq!(stored_secrets_count(&storage, &box_item.id), 0);
+    }
+
+    #[test]
+    fn name_index_rebuild_renames_the_newer_collision() {
+        let storage = temporary_storage();
+        let box_item = Box::new("collisions".to_string(), None, Vec::new(), false).unwrap();
+        storage.save_box(&box_item).unwrap();
+
+        // Written around the index, the way names that only differ by case used to be stored
+        let mut older = new_secret(&box_item.id, "api key".to_string());
+        older.created_at -= chrono::Duration::seconds(1);
+        let newer = new_secret(&box_item.id, "API KEY".to_string());
+        let mut batch = sled::Batch::default();
+        StorageService::insert_secret(&mut batch, &older).unwrap();
+        StorageService::insert_secret(&mut batch, &newer).unwrap();
+        storage.get_db().unwrap().apply_batch(batch).unwrap();
+
+        storage.rebuild_name_indexes().unwrap();
+
+        let kept = storage
+            .get_secret_by_name_in_box("API KEY", &box_item.id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(kept.id, older.id);
+        let renamed = storage
+            .get_secret_by_name_in_box("API KEY (2)", &box_item.id)
+            .unwrap()
+            .unwrap();
+        assert_eq!(renamed.id, newer.id);
+        assert_eq!(renamed.name, "API KEY (2)");
+    }
+
+    #[test]
+    fn deduplicated_names_fit_the_length_limit() {
+        let taken = ["Name (2)".to_string()];
+        assert_eq!(
+            deduplicated_name("Name", 50, |name| taken.contains(&name.to_string())),
+            "Name (3)"
+        );
+        assert_eq!(deduplicated_name("abcdefgh", 8, |_| false), "abcd (2)");
+    }
 }
@@ -0,0 +1,193 @@
+// src/services/sync/azure_key_vault.rs - Azure Key Vault over its REST API, signed in as a
+// service principal (client credentials). Key Vault names allow letters, digits and dashes.
+
+use super::{read_response, request_failed, SyncProvider};
+use crate::models::ZapError;
+use async_trait::async_trait;
+use reqwest::{Method, Url};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+const PROVIDER: &str = "Azure Key Vault";
+const API_VERSION: &str = "7.4";
+const LOGIN_URL: &str = "https://login.microsoftonline.com";
+const VAULT_SCOPE: &str = "https://vault.azure.net/.default";
+
+pub struct AzureKeyVault {
+    client: reqwest::Client,
+    vault_url: Url,
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+struct SecretPage {
+    #[serde(default)]
+    value: Vec<SecretItem>,
+    #[serde(default, rename = "nextLink")]
+    next_link: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SecretItem {
+    id: String, // https://<vault>/secrets/<name>
+    #[serde(default)]
+    attributes: SecretAttributes,
+}
+
+#[derive(Deserialize)]
+struct SecretAttributes {
+    #[serde(default = "default_enabled")]
+    enabled: bool,
+}
+
+impl Default for SecretAttributes {
+    fn default() -> Self {
+        Self {
+            enabled: default_enabled(),
+        }
+    }
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+struct SecretBundle {
+    value: String,
+}
+
+impl AzureKeyVault {
+    pub async fn connect(
+        client: reqwest::Client,
+        vault_url: &str,
+        tenant_id: &str,
+        client_id: &str,
+        client_secret: &str,
+    ) -> Result<Self, ZapError> {
+        let vault_url = Url::parse(vault_url)
+            .ok()
+            .filter(|url| url.scheme() == "https" && url.has_host())
+            .ok_or_else(|| {
+                ZapError::ValidationError(format!("'{}' is not a Key Vault URL", vault_url))
+            })?;
+
+        let mut token_url = Url::parse(LOGIN_URL).expect("valid login url");
+        token_url
+            .path_segments_mut()
+            .expect("login url has a path")
+            .pop_if_empty()
+            .extend([tenant_id, "oauth2", "v2.0", "token"]);
+        let response = client
+            .post(token_url)
+            .form(&[
+                ("grant_type", "client_credentials"),
+                ("client_id", client_id),
+                ("client_secret", client_secret),
+                ("scope", VAULT_SCOPE),
+            ])
+            .send()
+            .await
+            .map_err(|e| request_failed(PROVIDER, e))?;
+        let body = read_response(response, PROVIDER, |body| {
+            body["error_description"].as_str().map(str::to_string)
+        })
+        .await?;
+        let token: TokenResponse = serde_json::from_str(&body)?;
+
+        Ok(Self {
+            client,
+            vault_url,
+            access_token: token.access_token,
+        })
+    }
+
+    fn secret_url(&self, name: Option<&str>) -> Url {
+        let mut url = self.vault_url.clone();
+        {
+            let mut segments = url.path_segments_mut().expect("vault url has a path");
+            segments.pop_if_empty().push("secrets");
+            if let Some(name) = name {
+                segments.push(name);
+            }
+        }
+        url.query_pairs_mut()
+            .append_pair("api-version", API_VERSION);
+        url
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        url: Url,
+        body: Option<serde_json::Value>,
+    ) -> Result<String, ZapError> {
+        let mut request = self
+            .client
+            .request(method, url)
+            .bearer_auth(&self.access_token);
+        if let Some(body) = body {
+            request = request
+                .header("Content-Type", "application/json")
+                .body(body.to_string());
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| request_failed(PROVIDER, e))?;
+        read_response(response, PROVIDER, |body| {
+            body["error"]["message"].as_str().map(str::to_string)
+        })
+        .await
+    }
+}
+
+#[async_trait]
+impl SyncProvider for AzureKeyVault {
+    fn remote_name(&self, env_var_name: &str) -> String {
+        env_var_name.replace('_', "-")
+    }
+
+    fn local_name(&self, remote_name: &str) -> String {
+        remote_name.replace('-', "_").to_uppercase()
+    }
+
+    async fn list(&self) -> Result<BTreeMap<String, String>, ZapError> {
+        let mut names = Vec::new();
+        let mut next = Some(self.secret_url(None));
+        while let Some(url) = next.take() {
+            let page: SecretPage = serde_json::from_str(&self.send(Method::GET, url, None).await?)?;
+            // Disabled secrets can't be read, they are left out like deleted ones
+            names.extend(
+                page.value
+                    .into_iter()
+                    .filter(|item| item.attributes.enabled)
+                    .filter_map(|item| item.id.rsplit('/').next().map(str::to_string)),
+            );
+            next = page.next_link.and_then(|link| Url::parse(&link).ok());
+        }
+
+        let mut secrets = BTreeMap::new();
+        for name in names {
+            let body = self
+                .send(Method::GET, self.secret_url(Some(&name)), None)
+                .await?;
+            let bundle: SecretBundle = serde_json::from_str(&body)?;
+            secrets.insert(name, bundle.value);
+        }
+        Ok(secrets)
+    }
+
+    async fn put(&self, remote_name: &str, value: &str) -> Result<(), ZapError> {
+        let body = serde_json::json!({ "value": value });
+        self.send(Method::PUT, self.secret_url(Some(remote_name)), Some(body))
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,213 @@
+// src/services/sync/engine.rs - Three-way reconcile of the vault with a replica snapshot.
+// Each record is compared with its base, the version both sides agreed on at the last sync:
+// a side whose version moved since then has changed it. When both sides changed a record to
+// different content it is a conflict, and stays as it is on both sides until resolved.
+
+use crate::models::{ConflictResolution, RecordVersion, ReplicaRecord};
+use std::collections::{BTreeMap, HashMap};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyncStep {
+    Push,     // Write the local record to the replica
+    Pull,     // Write the replica's record locally
+    Settle,   // Same content on both sides, only the versions need to agree
+    Conflict, // Changed on both sides
+}
+
+/// What to do with each record that needs anything done, in record key order
+/// (boxes before their secrets)
+pub fn reconcile(
+    local: &BTreeMap<String, ReplicaRecord>,
+    remote: &BTreeMap<String, ReplicaRecord>,
+    bases: &HashMap<String, RecordVersion>,
+    resolutions: &HashMap<String, ConflictResolution>,
+) -> Vec<(String, SyncStep)> {
+    let mut keys: Vec<&String> = local.keys().chain(remote.keys()).collect();
+    keys.sort();
+    keys.dedup();
+
+    keys.into_iter()
+        .filter_map(|key| {
+            let base = bases.get(key);
+            let step = match (local.get(key), remote.get(key)) {
+                (Some(_), None) => SyncStep::Push,
+                (None, Some(theirs)) if theirs.content.is_none() => {
+                    // Deleted there, never here
+                    if base == Some(&theirs.version) {
+                        return None;
+                    }
+                    SyncStep::Settle
+                }
+                (None, Some(_)) => SyncStep::Pull,
+                (Some(ours), Some(theirs)) if ours.content == theirs.content => {
+                    if ours.version == theirs.version && base == Some(&theirs.version) {
+                        return None;
+                    }
+                    SyncStep::Settle
+                }
+                (Some(ours), Some(theirs)) => {
+                    let ours_changed = base != Some(&ours.version);
+                    let theirs_changed = base != Some(&theirs.version);
+                    match (resolutions.get(key), ours_changed, theirs_changed) {
+                        (Some(ConflictResolution::KeepLocal), _, _) => SyncStep::Push,
+                        (Some(ConflictResolution::KeepRemote), _, _) => SyncStep::Pull,
+                        (None, true, false) => SyncStep::Push,
+                        (None, false, true) => SyncStep::Pull,
+                        // Both changed, or no base to tell which one did
+                        (None, _, _) => SyncStep::Conflict,
+                    }
+                }
+                (None, None) => return None,
+            };
+            Some((key.clone(), step))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::RecordContent;
+
+    const KEY: &str = "box:1";
+
+    fn version(lamport: u64, device_id: &str, deleted: bool) -> RecordVersion {
+        RecordVersion {
+            lamport,
+            device_id: device_id.to_string(),
+            updated_at: chrono::DateTime::from_timestamp(lamport as i64, 0).unwrap(),
+            deleted,
+        }
+    }
+
+    fn named(name: &str, version: RecordVersion) -> ReplicaRecord {
+        ReplicaRecord {
+            version,
+            content: Some(RecordContent::Box {
+                name: name.to_string(),
+                description: None,
+                tags: Vec::new(),
+            }),
+        }
+    }
+
+    fn tombstone(version: RecordVersion) -> ReplicaRecord {
+        ReplicaRecord {
+            version,
+            content: None,
+        }
+    }
+
+    fn side(record: Option<ReplicaRecord>) -> BTreeMap<String, ReplicaRecord> {
+        record
+            .map(|record| (KEY.to_string(), record))
+            .into_iter()
+            .collect()
+    }
+
+    fn base(version: Option<RecordVersion>) -> HashMap<String, RecordVersion> {
+        version
+            .map(|version| (KEY.to_string(), version))
+            .into_iter()
+            .collect()
+    }
+
+    fn step_for(
+        local: Option<ReplicaRecord>,
+        remote: Option<ReplicaRecord>,
+        base_version: Option<RecordVersion>,
+    ) -> Option<SyncStep> {
+        let steps = reconcile(
+            &side(local),
+            &side(remote),
+            &base(base_version),
+            &HashMap::new(),
+        );
+        steps
+            .into_iter()
+            .find(|(key, _)| key == KEY)
+            .map(|(_, step)| step)
+    }
+
+    #[test]
+    fn delete_here_and_edit_there_conflicts() {
+        let synced = version(1, "a", false);
+        let step = step_for(
+            Some(tombstone(version(2, "a", true))),
+            Some(named("renamed", version(2, "b", false))),
+            Some(synced),
+        );
+        assert_eq!(step, Some(SyncStep::Conflict));
+    }
+
+    #[test]
+    fn delete_here_with_no_edit_there_is_pushed() {
+        let synced = version(1, "a", false);
+        let step = step_for(
+            Some(tombstone(version(2, "a", true))),
+            Some(named("api", synced.clone())),
+            Some(synced),
+        );
+        assert_eq!(step, Some(SyncStep::Push));
+    }
+
+    #[test]
+    fn edit_here_and_delete_there_conflicts() {
+        let synced = version(1, "a", false);
+        let step = step_for(
+            Some(named("renamed", version(2, "a", false))),
+            Some(tombstone(version(2, "b", true))),
+            Some(synced),
+        );
+        assert_eq!(step, Some(SyncStep::Conflict));
+    }
+
+    #[test]
+    fn missing_base_with_different_content_conflicts() {
+        let step = step_for(
+            Some(named("api", version(1, "a", false))),
+            Some(named("web", version(1, "b", false))),
+            None,
+        );
+        assert_eq!(step, Some(SyncStep::Conflict));
+    }
+
+    #[test]
+    fn missing_base_with_same_content_settles() {
+        let current = version(1, "a", false);
+        let step = step_for(
+            Some(named("api", current.clone())),
+            Some(named("api", current)),
+            None,
+        );
+        assert_eq!(step, Some(SyncStep::Settle));
+    }
+
+    #[test]
+    fn remote_tombstone_settles_until_the_base_catches_up() {
+        let deleted = version(2, "b", true);
+        let step_before = step_for(
+            None,
+            Some(tombstone(deleted.clone())),
+            Some(version(1, "a", false)),
+        );
+        assert_eq!(step_before, Some(SyncStep::Settle));
+
+        let step_after = step_for(None, Some(tombstone(deleted.clone())), Some(deleted));
+        assert_eq!(step_after, None);
+    }
+
+    #[test]
+    fn resolution_picks_a_side_for_a_conflict() {
+        let synced = version(1, "a", false);
+        let mut resolutions = HashMap::new();
+        resolutions.insert(KEY.to_string(), ConflictResolution::KeepRemote);
+        let steps = reconcile(
+            &side(Some(tombstone(version(2, "a", true)))),
+            &side(Some(named("renamed", version(2, "b", false)))),
+            &base(Some(synced)),
+            &resolutions,
+        );
+        assert_eq!(steps, vec![(KEY.to_string(), SyncStep::Pull)]);
+    }
+}
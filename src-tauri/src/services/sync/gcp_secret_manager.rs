@@ -0,0 +1,237 @@
+// src/services/sync/gcp_secret_manager.rs - Google Cloud Secret Manager (v1 REST API), signed
+// in with a service account key. Pushes add a version; secrets are created on first push.
+
+use super::{read_response, request_failed, SyncProvider};
+use crate::models::ZapError;
+use async_trait::async_trait;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use jsonwebtoken::{Algorithm, EncodingKey, Header};
+use reqwest::{Method, StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+const PROVIDER: &str = "GCP Secret Manager";
+const API_URL: &str = "https://secretmanager.googleapis.com/v1";
+const SCOPE: &str = "https://www.googleapis.com/auth/cloud-platform";
+const PAGE_SIZE: &str = "250";
+
+pub struct GcpSecretManager {
+    client: reqwest::Client,
+    project_id: String,
+    access_token: String,
+}
+
+// The fields of a service account JSON key that signing in needs
+#[derive(Deserialize)]
+struct ServiceAccountKey {
+    client_email: String,
+    private_key: String, // PEM
+    token_uri: String,
+}
+
+#[derive(Serialize)]
+struct JwtClaims<'a> {
+    iss: &'a str,
+    scope: &'a str,
+    aud: &'a str,
+    iat: i64,
+    exp: i64,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+}
+
+#[derive(Deserialize)]
+struct SecretPage {
+    #[serde(default)]
+    secrets: Vec<SecretItem>,
+    #[serde(default, rename = "nextPageToken")]
+    next_page_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct SecretItem {
+    name: String, // projects/<number>/secrets/<name>
+}
+
+#[derive(Deserialize)]
+struct AccessResponse {
+    payload: Payload,
+}
+
+#[derive(Deserialize)]
+struct Payload {
+    data: String, // Base64
+}
+
+impl GcpSecretManager {
+    pub async fn connect(
+        client: reqwest::Client,
+        project_id: &str,
+        service_account_key: &str,
+    ) -> Result<Self, ZapError> {
+        let key: ServiceAccountKey = serde_json::from_str(service_account_key).map_err(|_| {
+            ZapError::ValidationError(
+                "The credentials secret is not a service account JSON key".to_string(),
+            )
+        })?;
+
+        // OAuth 2.0 JWT bearer grant, the assertion is signed with the account's key
+        let now = chrono::Utc::now().timestamp();
+        let claims = JwtClaims {
+            iss: &key.client_email,
+            scope: SCOPE,
+            aud: &key.token_uri,
+            iat: now,
+            exp: now + 3600,
+        };
+        let signing_key = EncodingKey::from_rsa_pem(key.private_key.as_bytes())
+            .map_err(|e| ZapError::CryptoError(format!("Invalid service account key: {}", e)))?;
+        let assertion = jsonwebtoken::encode(&Header::new(Algorithm::RS256), &claims, &signing_key)
+            .map_err(|e| ZapError::CryptoError(format!("Failed to sign in to GCP: {}", e)))?;
+
+        let response = client
+            .post(&key.token_uri)
+            .form(&[
+                ("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"),
+                ("assertion", assertion.as_str()),
+            ])
+            .send()
+            .await
+            .map_err(|e| request_failed(PROVIDER, e))?;
+        let body = read_response(response, PROVIDER, |body| {
+            body["error_description"].as_str().map(str::to_string)
+        })
+        .await?;
+        let token: TokenResponse = serde_json::from_str(&body)?;
+
+        Ok(Self {
+            client,
+            project_id: project_id.to_string(),
+            access_token: token.access_token,
+        })
+    }
+
+    // <api>/projects/<project>/secrets[/<name>][<suffix>], e.g. suffix ":addVersion"
+    fn secrets_url(&self, name: Option<&str>, suffix: &str) -> Url {
+        let mut url = Url::parse(API_URL).expect("valid API url");
+        {
+            let mut segments = url.path_segments_mut().expect("API url has a path");
+            segments.extend(["projects", self.project_id.as_str(), "secrets"]);
+            if let Some(name) = name {
+                segments.push(name);
+            }
+        }
+        if !suffix.is_empty() {
+            let path = format!("{}{}", url.path(), suffix);
+            url.set_path(&path);
+        }
+        url
+    }
+
+    async fn send(
+        &self,
+        method: Method,
+        url: Url,
+        body: Option<serde_json::Value>,
+    ) -> Result<(StatusCode, Result<String, ZapError>), ZapError> {
+        let mut request = self
+            .client
+            .request(method, url)
+            .bearer_auth(&self.access_token);
+        if let Some(body) = body {
+            request = request
+                .header("Content-Type", "application/json")
+                .body(body.to_string());
+        }
+
+        let response = request
+            .send()
+            .await
+            .map_err(|e| request_failed(PROVIDER, e))?;
+        let status = response.status();
+        let body = read_response(response, PROVIDER, |body| {
+            body["error"]["message"].as_str().map(str::to_string)
+        })
+        .await;
+        Ok((status, body))
+    }
+}
+
+#[async_trait]
+impl SyncProvider for GcpSecretManager {
+    fn remote_name(&self, env_var_name: &str) -> String {
+        env_var_name.to_string()
+    }
+
+    fn local_name(&self, remote_name: &str) -> String {
+        remote_name.replace('-', "_").to_uppercase()
+    }
+
+    async fn list(&self) -> Result<BTreeMap<String, String>, ZapError> {
+        let mut names = Vec::new();
+        let mut page_token: Option<String> = None;
+        loop {
+            let mut url = self.secrets_url(None, "");
+            url.query_pairs_mut().append_pair("pageSize", PAGE_SIZE);
+            if let Some(token) = &page_token {
+                url.query_pairs_mut().append_pair("pageToken", token);
+            }
+            let (_, body) = self.send(Method::GET, url, None).await?;
+            let page: SecretPage = serde_json::from_str(&body?)?;
+            names.extend(
+                page.secrets
+                    .into_iter()
+                    .filter_map(|item| item.name.rsplit('/').next().map(str::to_string)),
+            );
+            page_token = page.next_page_token.filter(|token| !token.is_empty());
+            if page_token.is_none() {
+                break;
+            }
+        }
+
+        let mut secrets = BTreeMap::new();
+        for name in names {
+            let url = self.secrets_url(Some(&name), "/versions/latest:access");
+            let (status, body) = self.send(Method::GET, url, None).await?;
+            // No enabled version yet (404) or the latest one is disabled (400): nothing to read
+            if matches!(status, StatusCode::NOT_FOUND | StatusCode::BAD_REQUEST) {
+                continue;
+            }
+            let access: AccessResponse = serde_json::from_str(&body?)?;
+            let value = BASE64
+                .decode(&access.payload.data)
+                .ok()
+                .and_then(|bytes| String::from_utf8(bytes).ok())
+                .ok_or_else(|| {
+                    ZapError::ValidationError(format!("'{}' does not hold text", name))
+                })?;
+            secrets.insert(name, value);
+        }
+        Ok(secrets)
+    }
+
+    async fn put(&self, remote_name: &str, value: &str) -> Result<(), ZapError> {
+        let version = serde_json::json!({ "payload": { "data": BASE64.encode(value) } });
+        let url = self.secrets_url(Some(remote_name), ":addVersion");
+        let (status, body) = self
+            .send(Method::POST, url.clone(), Some(version.clone()))
+            .await?;
+        if status != StatusCode::NOT_FOUND {
+            return body.map(|_| ());
+        }
+
+        // First push of this secret
+        let mut create_url = self.secrets_url(None, "");
+        create_url
+            .query_pairs_mut()
+            .append_pair("secretId", remote_name);
+        let secret = serde_json::json!({ "replication": { "automatic": {} } });
+        self.send(Method::POST, create_url, Some(secret)).await?.1?;
+        self.send(Method::POST, url, Some(version)).await?.1?;
+        Ok(())
+    }
+}
@@ -0,0 +1,94 @@
+// src/services/sync/mod.rs - Cloud secret stores a box can be pushed to and pulled from.
+// Each store implements SyncProvider; `connect` picks one from a mapping's provider config.
+// Whole-vault two-way sync with a replica lives in `engine`, `replica` and `webdav`.
+
+pub mod azure_key_vault;
+pub mod engine;
+pub mod gcp_secret_manager;
+pub mod replica;
+pub mod webdav;
+
+use crate::models::{SyncProviderConfig, ZapError};
+use async_trait::async_trait;
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+pub use azure_key_vault::AzureKeyVault;
+pub use gcp_secret_manager::GcpSecretManager;
+pub use webdav::WebDavStore;
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(15);
+
+#[async_trait]
+pub trait SyncProvider: Send + Sync {
+    /// Store name for a secret's env var name, e.g. "DB_PASSWORD" -> "DB-PASSWORD"
+    fn remote_name(&self, env_var_name: &str) -> String;
+
+    /// Env var name for a store name, the inverse of `remote_name`
+    fn local_name(&self, remote_name: &str) -> String;
+
+    /// Current value of every secret in the store, by store name
+    async fn list(&self) -> Result<BTreeMap<String, String>, ZapError>;
+
+    /// Create the secret, or add a new version of it
+    async fn put(&self, remote_name: &str, value: &str) -> Result<(), ZapError>;
+}
+
+/// Sign in to the store `config` points at with `credential` (see SyncProviderConfig)
+pub async fn connect(
+    config: &SyncProviderConfig,
+    credential: &str,
+) -> Result<Box<dyn SyncProvider>, ZapError> {
+    let client = http_client();
+    Ok(match config {
+        SyncProviderConfig::AzureKeyVault {
+            vault_url,
+            tenant_id,
+            client_id,
+        } => Box::new(
+            AzureKeyVault::connect(client, vault_url, tenant_id, client_id, credential).await?,
+        ),
+        SyncProviderConfig::GcpSecretManager { project_id } => {
+            Box::new(GcpSecretManager::connect(client, project_id, credential).await?)
+        }
+    })
+}
+
+fn http_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .timeout(REQUEST_TIMEOUT)
+        .user_agent(concat!("Zap/", env!("CARGO_PKG_VERSION")))
+        .build()
+        .unwrap_or_default()
+}
+
+// Stores explain failures in their JSON body; `message` picks the explanation out of it
+async fn read_response(
+    response: reqwest::Response,
+    provider: &str,
+    message: fn(&serde_json::Value) -> Option<String>,
+) -> Result<String, ZapError> {
+    let status = response.status();
+    let body = response
+        .text()
+        .await
+        .map_err(|e| request_failed(provider, e))?;
+    if status.is_success() {
+        return Ok(body);
+    }
+
+    let detail = serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|body| message(&body));
+    Err(match (status.as_u16(), detail) {
+        (401, _) => ZapError::AuthError(format!("{} rejected the credentials", provider)),
+        (_, Some(detail)) => {
+            ZapError::PlatformError(format!("{} returned {}: {}", provider, status, detail))
+        }
+        (_, None) => ZapError::PlatformError(format!("{} returned {}", provider, status)),
+    })
+}
+
+fn request_failed(provider: &str, e: reqwest::Error) -> ZapError {
+    ZapError::PlatformError(format!("{} request failed: {}", provider, e))
+}
@@ -0,0 +1,250 @@
+// src/services/sync/replica.rs - Where a replica snapshot is kept (see ReplicaTarget), and
+// sealing it with the replica passphrase. The salt travels with the snapshot, so the same
+// passphrase opens it on every device.
+
+use super::{SyncProvider, WebDavStore};
+use crate::models::replica_model::REPLICA_FORMAT_VERSION;
+use crate::models::{KdfParams, ReplicaSnapshot, ReplicaTarget, SealedReplica, ZapError};
+use crate::services::CryptoService;
+use async_trait::async_trait;
+use std::path::PathBuf;
+use std::process::Stdio;
+
+const REPLICA_CONTEXT: &[u8] = b"zap:replica";
+const GIT_COMMIT_MESSAGE: &str = "Sync Zap vault";
+
+#[async_trait]
+pub trait ReplicaStore: Send + Sync {
+    /// The sealed snapshot, None before the first sync
+    async fn load(&self) -> Result<Option<String>, ZapError>;
+
+    /// Replaces the snapshot, and whatever change files it now includes
+    async fn store(&self, sealed: &str) -> Result<(), ZapError>;
+
+    /// Sealed change files written on top of the loaded snapshot, oldest first
+    async fn load_changes(&self) -> Result<Vec<String>, ZapError> {
+        Ok(Vec::new())
+    }
+
+    /// Add just the records a sync pushed instead of rewriting the snapshot. False when the
+    /// store keeps no change files (or wants the snapshot rewritten), then `store` is used.
+    async fn store_change(&self, _sealed: &str) -> Result<bool, ZapError> {
+        Ok(false)
+    }
+}
+
+/// Open the store `target` points at. Provider and WebDAV targets sign in with `credential`.
+pub async fn open_store(
+    target: &ReplicaTarget,
+    credential: Option<&str>,
+) -> Result<Box<dyn ReplicaStore>, ZapError> {
+    Ok(match target {
+        ReplicaTarget::File { path } => Box::new(FileStore {
+            path: PathBuf::from(path),
+        }),
+        ReplicaTarget::Git {
+            repository,
+            file_name,
+        } => Box::new(GitStore {
+            repository: PathBuf::from(repository),
+            file_name: file_name.clone(),
+        }),
+        ReplicaTarget::Provider {
+            provider,
+            secret_name,
+            ..
+        } => {
+            let credential = credential.ok_or_else(|| {
+                ZapError::ValidationError("Provider replicas need a credential".to_string())
+            })?;
+            Box::new(ProviderStore {
+                provider: super::connect(provider, credential).await?,
+                secret_name: secret_name.clone(),
+            })
+        }
+        ReplicaTarget::WebDav { url, username, .. } => {
+            let credential = credential.ok_or_else(|| {
+                ZapError::ValidationError("WebDAV replicas need a password".to_string())
+            })?;
+            Box::new(WebDavStore::new(
+                super::http_client(),
+                url,
+                username,
+                credential,
+            )?)
+        }
+    })
+}
+
+// Key for one sealed snapshot, reused to seal the next one
+pub struct ReplicaKey {
+    key: [u8; 32],
+    salt: [u8; 32],
+    kdf_params: KdfParams,
+}
+
+impl ReplicaKey {
+    /// Key for a replica that has no snapshot yet
+    pub fn generate(passphrase: &str) -> Result<Self, ZapError> {
+        let crypto = CryptoService::new();
+        let salt = crypto.generate_salt();
+        let kdf_params = KdfParams::default();
+        Ok(Self {
+            key: crypto.derive_key(passphrase, &salt, &kdf_params)?,
+            salt,
+            kdf_params,
+        })
+    }
+}
+
+/// Open a snapshot from `seal_snapshot` with the replica passphrase
+pub fn open_snapshot(
+    sealed: &str,
+    passphrase: &str,
+) -> Result<(ReplicaSnapshot, ReplicaKey), ZapError> {
+    let sealed = parse_sealed(sealed)?;
+    let crypto = CryptoService::new();
+    let salt: [u8; 32] = hex::decode(&sealed.salt)?
+        .try_into()
+        .map_err(|_| ZapError::ValidationError("Replica salt is malformed".to_string()))?;
+    let key = crypto.derive_key(passphrase, &salt, &sealed.kdf_params)?;
+    let json = crypto
+        .decrypt_bound(&sealed.snapshot, &key, REPLICA_CONTEXT)
+        .map_err(|_| ZapError::AuthError("The replica passphrase doesn't match".to_string()))?;
+
+    let key = ReplicaKey {
+        key,
+        salt,
+        kdf_params: sealed.kdf_params,
+    };
+    Ok((serde_json::from_str(&json)?, key))
+}
+
+/// Open a change file, sealed with the key of the snapshot it was written on top of
+pub fn open_change(sealed: &str, key: &ReplicaKey) -> Result<ReplicaSnapshot, ZapError> {
+    let sealed = parse_sealed(sealed)?;
+    if sealed.salt != hex::encode(key.salt) {
+        return Err(ZapError::ValidationError(
+            "A replica change file doesn't belong to its snapshot".to_string(),
+        ));
+    }
+    let json = CryptoService::new()
+        .decrypt_bound(&sealed.snapshot, &key.key, REPLICA_CONTEXT)
+        .map_err(|_| ZapError::AuthError("The replica passphrase doesn't match".to_string()))?;
+    Ok(serde_json::from_str(&json)?)
+}
+
+fn parse_sealed(sealed: &str) -> Result<SealedReplica, ZapError> {
+    let sealed: SealedReplica = serde_json::from_str(sealed)
+        .map_err(|_| ZapError::ValidationError("Not a Zap replica".to_string()))?;
+    if sealed.version != REPLICA_FORMAT_VERSION {
+        return Err(ZapError::ValidationError(format!(
+            "Replica format {} is not supported by this version of Zap",
+            sealed.version
+        )));
+    }
+    Ok(sealed)
+}
+
+pub fn seal_snapshot(snapshot: &ReplicaSnapshot, key: &ReplicaKey) -> Result<String, ZapError> {
+    let sealed = SealedReplica {
+        version: REPLICA_FORMAT_VERSION,
+        salt: hex::encode(key.salt),
+        kdf_params: key.kdf_params,
+        snapshot: CryptoService::new().encrypt_bound(
+            &serde_json::to_string(snapshot)?,
+            &key.key,
+            REPLICA_CONTEXT,
+        )?,
+    };
+    Ok(serde_json::to_string_pretty(&sealed)?)
+}
+
+struct FileStore {
+    path: PathBuf,
+}
+
+#[async_trait]
+impl ReplicaStore for FileStore {
+    async fn load(&self) -> Result<Option<String>, ZapError> {
+        match tokio::fs::read_to_string(&self.path).await {
+            Ok(sealed) => Ok(Some(sealed)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    // Written next to the target and renamed over it, so readers never see half a snapshot
+    async fn store(&self, sealed: &str) -> Result<(), ZapError> {
+        let partial = self.path.with_extension("partial");
+        tokio::fs::write(&partial, sealed).await?;
+        tokio::fs::rename(&partial, &self.path).await?;
+        Ok(())
+    }
+}
+
+struct GitStore {
+    repository: PathBuf,
+    file_name: String,
+}
+
+impl GitStore {
+    async fn git(&self, args: &[&str]) -> Result<(), ZapError> {
+        let output = tokio::process::Command::new("git")
+            .arg("-C")
+            .arg(&self.repository)
+            .args(args)
+            .stdin(Stdio::null())
+            .output()
+            .await
+            .map_err(|e| ZapError::PlatformError(format!("Failed to run git: {}", e)))?;
+        if !output.status.success() {
+            return Err(ZapError::PlatformError(format!(
+                "git {} failed: {}",
+                args[0],
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ReplicaStore for GitStore {
+    async fn load(&self) -> Result<Option<String>, ZapError> {
+        self.git(&["pull", "--ff-only"]).await?;
+        FileStore {
+            path: self.repository.join(&self.file_name),
+        }
+        .load()
+        .await
+    }
+
+    async fn store(&self, sealed: &str) -> Result<(), ZapError> {
+        FileStore {
+            path: self.repository.join(&self.file_name),
+        }
+        .store(sealed)
+        .await?;
+        self.git(&["add", "--", &self.file_name]).await?;
+        self.git(&["commit", "-m", GIT_COMMIT_MESSAGE, "--", &self.file_name])
+            .await?;
+        self.git(&["push"]).await
+    }
+}
+
+struct ProviderStore {
+    provider: Box<dyn SyncProvider>,
+    secret_name: String,
+}
+
+#[async_trait]
+impl ReplicaStore for ProviderStore {
+    async fn load(&self) -> Result<Option<String>, ZapError> {
+        Ok(self.provider.list().await?.remove(&self.secret_name))
+    }
+
+    async fn store(&self, sealed: &str) -> Result<(), ZapError> {
+        self.provider.put(&self.secret_name, sealed).await
+    }
+}
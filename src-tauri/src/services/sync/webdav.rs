@@ -0,0 +1,235 @@
+// src/services/sync/webdav.rs - Replica on a WebDAV server (Nextcloud, ownCloud, Apache...).
+// The folder holds the sealed snapshot and a changes/ folder with one sealed file per sync
+// since the snapshot was last rewritten. Writes are conditional on what the sync read (ETag
+// for the snapshot, If-None-Match for new change files), so two devices syncing at once
+// can't overwrite each other - the later one fails and syncs again.
+
+use super::replica::ReplicaStore;
+use crate::models::ZapError;
+use async_trait::async_trait;
+use regex::Regex;
+use reqwest::header::{ETAG, IF_MATCH, IF_NONE_MATCH};
+use reqwest::{Method, RequestBuilder, Response, StatusCode, Url};
+use std::sync::{Mutex, OnceLock};
+
+const SNAPSHOT_FILE: &str = "zap-vault.json";
+const CHANGES_FOLDER: &str = "changes/";
+const CHANGE_FILE_PREFIX: &str = "change-";
+// Past this many change files the next sync rewrites the snapshot and removes them
+const MAX_CHANGE_FILES: usize = 20;
+const PROPFIND_BODY: &str = r#"<?xml version="1.0"?>
+<d:propfind xmlns:d="DAV:"><d:prop><d:resourcetype/></d:prop></d:propfind>"#;
+
+pub struct WebDavStore {
+    client: reqwest::Client,
+    folder: Url,
+    username: String,
+    password: String,
+    // What load() read, the writes are conditional on it
+    snapshot_etag: Mutex<Option<String>>,
+    change_files: Mutex<Vec<String>>,
+}
+
+impl WebDavStore {
+    pub fn new(
+        client: reqwest::Client,
+        url: &str,
+        username: &str,
+        password: &str,
+    ) -> Result<Self, ZapError> {
+        let folder = Url::parse(&format!("{}/", url.trim_end_matches('/')))
+            .ok()
+            .filter(|url| url.scheme() == "https" && url.has_host())
+            .ok_or_else(|| {
+                ZapError::ValidationError(format!("'{}' is not an https WebDAV folder", url))
+            })?;
+        Ok(Self {
+            client,
+            folder,
+            username: username.to_string(),
+            password: password.to_string(),
+            snapshot_etag: Mutex::new(None),
+            change_files: Mutex::new(Vec::new()),
+        })
+    }
+
+    fn url(&self, path: &str) -> Url {
+        self.folder.join(path).expect("relative WebDAV path")
+    }
+
+    fn request(&self, method: Method, url: Url) -> RequestBuilder {
+        self.client
+            .request(method, url)
+            .basic_auth(&self.username, Some(&self.password))
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<Response, ZapError> {
+        request
+            .send()
+            .await
+            .map_err(|e| ZapError::PlatformError(format!("WebDAV request failed: {}", e)))
+    }
+
+    async fn get(&self, path: &str) -> Result<Option<(String, Option<String>)>, ZapError> {
+        let response = self.send(self.request(Method::GET, self.url(path))).await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        let response = check(response)?;
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|etag| etag.to_str().ok())
+            .map(str::to_string);
+        let body = response
+            .text()
+            .await
+            .map_err(|e| ZapError::PlatformError(format!("WebDAV request failed: {}", e)))?;
+        Ok(Some((body, etag)))
+    }
+
+    // Change file names in the changes folder, oldest first
+    async fn list_changes(&self) -> Result<Vec<String>, ZapError> {
+        let propfind = Method::from_bytes(b"PROPFIND").expect("valid method");
+        let request = self
+            .request(propfind, self.url(CHANGES_FOLDER))
+            .header("Depth", "1")
+            .header("Content-Type", "application/xml")
+            .body(PROPFIND_BODY);
+        let response = self.send(request).await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(Vec::new());
+        }
+        let body = check(response)?
+            .text()
+            .await
+            .map_err(|e| ZapError::PlatformError(format!("WebDAV request failed: {}", e)))?;
+
+        let mut names: Vec<String> = href_pattern()
+            .captures_iter(&body)
+            .filter_map(|href| href.get(1))
+            .filter_map(|href| href.as_str().trim_end_matches('/').rsplit('/').next())
+            .filter(|name| name.starts_with(CHANGE_FILE_PREFIX) && name.ends_with(".json"))
+            .map(String::from)
+            .collect();
+        names.sort();
+        Ok(names)
+    }
+
+    // Create a folder, fine if it is already there
+    async fn make_folder(&self, path: &str) -> Result<(), ZapError> {
+        let mkcol = Method::from_bytes(b"MKCOL").expect("valid method");
+        let response = self.send(self.request(mkcol, self.url(path))).await?;
+        if response.status() == StatusCode::METHOD_NOT_ALLOWED {
+            return Ok(());
+        }
+        check(response).map(|_| ())
+    }
+
+    async fn put(&self, path: &str, body: &str, condition: (&str, &str)) -> Result<(), ZapError> {
+        let request = self
+            .request(Method::PUT, self.url(path))
+            .header(condition.0, condition.1)
+            .header("Content-Type", "application/json")
+            .body(body.to_string());
+        check(self.send(request).await?).map(|_| ())
+    }
+}
+
+#[async_trait]
+impl ReplicaStore for WebDavStore {
+    async fn load(&self) -> Result<Option<String>, ZapError> {
+        let snapshot = self.get(SNAPSHOT_FILE).await?;
+        *self.snapshot_etag.lock().unwrap() = snapshot.as_ref().and_then(|(_, etag)| etag.clone());
+        Ok(snapshot.map(|(sealed, _)| sealed))
+    }
+
+    async fn load_changes(&self) -> Result<Vec<String>, ZapError> {
+        let names = self.list_changes().await?;
+        let mut changes = Vec::with_capacity(names.len());
+        for name in &names {
+            // Removed by a compaction since the listing, the snapshot check catches that on write
+            if let Some((sealed, _)) = self.get(&format!("{}{}", CHANGES_FOLDER, name)).await? {
+                changes.push(sealed);
+            }
+        }
+        *self.change_files.lock().unwrap() = names;
+        Ok(changes)
+    }
+
+    // Rewrites the snapshot, then removes the change files it now includes
+    async fn store(&self, sealed: &str) -> Result<(), ZapError> {
+        self.make_folder("").await?;
+        let etag = self.snapshot_etag.lock().unwrap().clone();
+        let condition = match &etag {
+            Some(etag) => (IF_MATCH.as_str(), etag.as_str()),
+            None => (IF_NONE_MATCH.as_str(), "*"),
+        };
+        self.put(SNAPSHOT_FILE, sealed, condition).await?;
+
+        let included = std::mem::take(&mut *self.change_files.lock().unwrap());
+        for name in included {
+            let url = self.url(&format!("{}{}", CHANGES_FOLDER, name));
+            let response = self.send(self.request(Method::DELETE, url)).await?;
+            if response.status() != StatusCode::NOT_FOUND {
+                check(response)?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn store_change(&self, sealed: &str) -> Result<bool, ZapError> {
+        let Some(etag) = self.snapshot_etag.lock().unwrap().clone() else {
+            return Ok(false);
+        };
+        let known = self.change_files.lock().unwrap().clone();
+        if known.len() >= MAX_CHANGE_FILES {
+            return Ok(false);
+        }
+
+        // Nothing may have been added since load(), or this change was planned on stale data
+        let current = self.get(SNAPSHOT_FILE).await?.and_then(|(_, etag)| etag);
+        if current.as_deref() != Some(etag.as_str()) || self.list_changes().await? != known {
+            return Err(replica_changed());
+        }
+
+        self.make_folder(CHANGES_FOLDER).await?;
+        let name = format!("{}{:06}.json", CHANGE_FILE_PREFIX, known.len() + 1);
+        self.put(
+            &format!("{}{}", CHANGES_FOLDER, name),
+            sealed,
+            (IF_NONE_MATCH.as_str(), "*"),
+        )
+        .await?;
+        self.change_files.lock().unwrap().push(name);
+        Ok(true)
+    }
+}
+
+fn check(response: Response) -> Result<Response, ZapError> {
+    match response.status() {
+        status if status.is_success() => Ok(response),
+        StatusCode::UNAUTHORIZED => Err(ZapError::AuthError(
+            "The WebDAV server rejected the credentials".to_string(),
+        )),
+        StatusCode::PRECONDITION_FAILED => Err(replica_changed()),
+        status => Err(ZapError::PlatformError(format!(
+            "WebDAV server returned {}",
+            status
+        ))),
+    }
+}
+
+fn replica_changed() -> ZapError {
+    ZapError::ValidationError(
+        "The replica was changed by another device during the sync, sync again".to_string(),
+    )
+}
+
+// <d:href>, <D:href> or <href>, whatever prefix the server gives the DAV: namespace
+fn href_pattern() -> &'static Regex {
+    static PATTERN: OnceLock<Regex> = OnceLock::new();
+    PATTERN.get_or_init(|| {
+        Regex::new(r"(?i)<(?:[a-z0-9]+:)?href>([^<]*)</(?:[a-z0-9]+:)?href>").expect("valid regex")
+    })
+}
@@ -0,0 +1,110 @@
+// src/session_proxy.rs - Loopback endpoint serving proxy dev sessions to zap-cli
+// One JSON line in (ProxyRequest), one JSON line out (ProxyReply). Bound to 127.0.0.1 on a
+// random port, published in proxy_endpoint.json so the CLI can find it. The calling program
+// is identified from the socket table and checked against Settings: app_access.
+
+use crate::models::{ProxyEndpoint, ProxyReply, ProxyRequest, ZapError};
+use crate::states::AppState;
+use crate::utils::path_resolvers::get_proxy_endpoint_path;
+use crate::utils::peer_process;
+use std::net::SocketAddr;
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader};
+use tokio::net::{TcpListener, TcpStream};
+
+const READ_TIMEOUT: Duration = Duration::from_secs(5);
+const MAX_REQUEST_BYTES: u64 = 4096;
+
+/// Listen for the CLI for the lifetime of the app
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let listener = match TcpListener::bind(("127.0.0.1", 0)).await {
+            Ok(listener) => listener,
+            Err(e) => {
+                tracing::warn!("Session proxy unavailable: {}", e);
+                return;
+            }
+        };
+        if let Err(e) = listener
+            .local_addr()
+            .and_then(|addr| publish_endpoint(addr.port()))
+        {
+            tracing::warn!("Failed to publish session proxy endpoint: {}", e);
+        }
+
+        loop {
+            let stream = match listener.accept().await {
+                Ok((stream, _)) => stream,
+                Err(e) => {
+                    tracing::warn!("Session proxy accept failed: {}", e);
+                    continue;
+                }
+            };
+            let app_state = app.state::<Arc<AppState>>().inner().clone();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = serve(stream, &app_state).await {
+                    tracing::debug!("Session proxy connection failed: {}", e);
+                }
+            });
+        }
+    });
+}
+
+async fn serve(stream: TcpStream, app_state: &AppState) -> std::io::Result<()> {
+    let (local, peer) = (stream.local_addr()?, stream.peer_addr()?);
+    let (reader, mut writer) = stream.into_split();
+    let mut line = String::new();
+    let mut reader = BufReader::new(reader.take(MAX_REQUEST_BYTES));
+    match tokio::time::timeout(READ_TIMEOUT, reader.read_line(&mut line)).await {
+        Ok(Ok(_)) => {}
+        Ok(Err(e)) => return Err(e),
+        Err(_) => return Ok(()), // Nothing sent in time
+    }
+
+    let reply = match serde_json::from_str::<ProxyRequest>(&line) {
+        Ok(request) => authorize_and_serve(app_state, &request.token, local, peer)
+            .await
+            .unwrap_or_else(|e| ProxyReply::Error {
+                // The CLI re-raises this as an AuthError, so skip its prefix
+                message: match e {
+                    ZapError::AuthError(message) => message,
+                    e => e.to_string(),
+                },
+            }),
+        Err(_) => ProxyReply::Error {
+            message: "Malformed proxy request".to_string(),
+        },
+    };
+
+    let mut json = serde_json::to_string(&reply)?;
+    json.push('\n');
+    writer.write_all(json.as_bytes()).await?;
+    writer.shutdown().await
+}
+
+// Unknown tokens are turned away before anyone is prompted about the caller
+async fn authorize_and_serve(
+    app_state: &AppState,
+    token: &str,
+    local: SocketAddr,
+    peer: SocketAddr,
+) -> Result<ProxyReply, ZapError> {
+    if let Some(session_name) = app_state.proxy_session_name(token) {
+        let caller = tokio::task::spawn_blocking(move || peer_process::identify(local, peer))
+            .await
+            .ok()
+            .flatten();
+        app_state
+            .check_app_access(caller.as_ref(), &session_name)
+            .await?;
+    }
+    app_state.serve_proxy_session(token)
+}
+
+fn publish_endpoint(port: u16) -> std::io::Result<()> {
+    let path = get_proxy_endpoint_path().map_err(std::io::Error::other)?;
+    let json = serde_json::to_string_pretty(&ProxyEndpoint { port })?;
+    std::fs::write(path, json)
+}
@@ -0,0 +1,50 @@
+// src/session_reaper.rs - Stops dev sessions the CLI stopped using (Settings: dev_session_idle_hours)
+
+use crate::states::{AppState, DevState};
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// Periodically stop idle sessions (only while unlocked)
+pub fn start(app: AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut interval = tokio::time::interval(CHECK_INTERVAL);
+        loop {
+            interval.tick().await;
+
+            let app_state = app.state::<Arc<AppState>>().inner().clone();
+            if app_state.is_locked() {
+                continue;
+            }
+
+            let dev_state = app.state::<tokio::sync::Mutex<DevState>>();
+            let reaped = dev_state.lock().await.reap_idle_sessions().await;
+            match reaped {
+                Ok(0) => {}
+                Ok(count) => notify(&app, count),
+                Err(e) => tracing::warn!("Idle session check failed: {}", e),
+            }
+        }
+    });
+}
+
+fn notify(app: &AppHandle, count: usize) {
+    let body = if count == 1 {
+        "An idle dev session was stopped".to_string()
+    } else {
+        format!("{} idle dev sessions were stopped", count)
+    };
+
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title("Zap - dev sessions")
+        .body(body)
+        .show()
+    {
+        tracing::warn!("Failed to show idle session notification: {}", e);
+    }
+}
@@ -0,0 +1,129 @@
+// src/session_watcher.rs - Watches CLI session files for external changes
+
+use crate::logging::redact;
+use crate::models::dev_model::is_session_file;
+use crate::models::{SessionFileEvent, SessionFileStatus};
+use crate::states::DevState;
+use crate::utils::path_resolvers::get_sessions_directory;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+use tokio::sync::mpsc;
+
+pub const SESSION_FILE_EVENT: &str = "session-file-changed";
+
+/// Keeps the filesystem watcher alive for the lifetime of the app
+pub struct SessionWatcher {
+    watcher: Mutex<RecommendedWatcher>,
+    watched_dir: Mutex<PathBuf>,
+}
+
+impl SessionWatcher {
+    /// Start watching the sessions directory and reconcile every change against the DB
+    pub fn start(app: AppHandle) -> Result<Self, String> {
+        let sessions_dir = get_sessions_directory()
+            .map_err(|e| format!("Failed to get sessions directory: {}", e))?;
+        std::fs::create_dir_all(&sessions_dir)
+            .map_err(|e| format!("Failed to create sessions directory: {}", e))?;
+
+        let (sender, receiver) = mpsc::unbounded_channel::<String>();
+
+        let mut watcher = notify::recommended_watcher(move |res: notify::Result<Event>| {
+            let event = match res {
+                Ok(event) => event,
+                Err(e) => {
+                    tracing::warn!("Session watcher error: {}", e);
+                    return;
+                }
+            };
+
+            if !matches!(
+                event.kind,
+                EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+            ) {
+                return;
+            }
+
+            for path in &event.paths {
+                if let Some(session_name) = session_name_from_path(path) {
+                    let _ = sender.send(session_name);
+                }
+            }
+        })
+        .map_err(|e| format!("Failed to create session watcher: {}", e))?;
+
+        watcher
+            .watch(&sessions_dir, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Failed to watch sessions directory: {}", e))?;
+
+        tauri::async_runtime::spawn(reconcile_loop(app, receiver));
+
+        Ok(Self {
+            watcher: Mutex::new(watcher),
+            watched_dir: Mutex::new(sessions_dir),
+        })
+    }
+
+    /// Follow the sessions directory after it was relocated in Settings
+    pub fn refresh(&self) -> Result<(), String> {
+        let sessions_dir = get_sessions_directory()
+            .map_err(|e| format!("Failed to get sessions directory: {}", e))?;
+
+        let mut watched_dir = self.watched_dir.lock().unwrap();
+        if *watched_dir == sessions_dir {
+            return Ok(());
+        }
+
+        let mut watcher = self.watcher.lock().unwrap();
+        let _ = watcher.unwatch(&watched_dir);
+        watcher
+            .watch(&sessions_dir, RecursiveMode::NonRecursive)
+            .map_err(|e| format!("Failed to watch sessions directory: {}", e))?;
+
+        *watched_dir = sessions_dir;
+        Ok(())
+    }
+}
+
+async fn reconcile_loop(app: AppHandle, mut receiver: mpsc::UnboundedReceiver<String>) {
+    while let Some(session_name) = receiver.recv().await {
+        let dev_state = app.state::<tokio::sync::Mutex<DevState>>();
+        let status = {
+            let dev_state_guard = dev_state.lock().await;
+            dev_state_guard.reconcile_session_file(&session_name).await
+        };
+
+        match status {
+            Ok(SessionFileStatus::Valid) => {}
+            Ok(status) => {
+                let _ = app.emit(
+                    SESSION_FILE_EVENT,
+                    SessionFileEvent {
+                        session_name,
+                        status,
+                    },
+                );
+            }
+            Err(e) => tracing::warn!(
+                "Failed to reconcile session {}: {}",
+                redact(&session_name),
+                e
+            ),
+        }
+    }
+}
+
+// Only `<session-name>.json` files are session files (temp files use .tmp). One that still
+// exists must hold a session record, other .json files in the directory aren't ours.
+fn session_name_from_path(path: &Path) -> Option<String> {
+    if path.extension().and_then(|s| s.to_str()) != Some("json") {
+        return None;
+    }
+    if path.exists() && !is_session_file(path) {
+        return None;
+    }
+    path.file_stem()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_string())
+}
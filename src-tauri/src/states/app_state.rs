@@ -1,98 +1,523 @@
 // src/states/app_state.rs
 
-use crate::models::{Box, LogEntry, Secret, SessionInfo, Settings, ZapError};
+use crate::models::{
+    AppAccessRequest, Box, BoxDiff, BoxSearchResult, CompromisedSecret, ConfigFormat,
+    ConflictResolution, CsvColumnMapping, DataDirectoryInfo, DatabaseDiagnostic, DatabaseKind,
+    DeepSearchMatch, DiagnosticsReport, EmergencyKit, EncryptedData, ExternalSecretsExport,
+    GithubPushReport, GithubSecretResult, GithubSecretStatus, GitlabChange, GitlabSyncReport,
+    GitlabTarget, GitlabVariable, GitlabVariableChange, HardwareKeyStatus, HookEvent, ImportResult,
+    JobInfo, JobKind, LanTransferOffer, LogAction, LogEntry, LogSeverity, MergeStrategy,
+    MultilineFormat, OneTimeShare, PasswordStrength, PathDiagnostic, ProxyReply, ProxySessionInfo,
+    RecentSecret, RecordContent, RecordVersion, RecoveryConfig, RedeemedShare, RememberedKey,
+    RenderedTemplate, ReplicaConfig, ReplicaRecord, ReplicaSnapshot, ReplicaSyncReport,
+    ReplicaTarget, ReportFormat, RevealHandle, SavedSecret, Secret, SecretBreach,
+    SecretSearchResult, SecretTemplate, SecretWarning, SessionInfo, SessionPhase, Settings,
+    SignatureStatus, SmartImportPreview, SnapshotReason, StartupStatus, StorageRecovery,
+    StorageUsage, SyncConflict, SyncDiff, SyncDiffEntry, SyncMapping, SyncProviderConfig,
+    SyncResult, SyncState, TemplateMode, TfvarsFormat, TransferScope, UndoResult, ValueEncoding,
+    VaultDiff, VaultSnapshot, VaultStats, ZapError,
+};
+use crate::logging;
+use crate::models::dev_model::{is_session_file, session_activity_file_name};
+use crate::models::import_export_model::BACKUP_FILE_PREFIX;
+use crate::models::secret_model::MAX_SECRET_VARIANTS;
+use crate::models::proxy_model::APP_ACCESS_TIMEOUT_SECONDS;
+use crate::models::replica_model::MAX_REPLICAS;
+use crate::models::sync_model::MAX_SYNC_MAPPINGS;
+use crate::models::settings_model::{
+    MAX_APP_ACCESS_ENTRIES, MAX_DEV_SESSION_HOURS, MAX_SECRET_VALUE_KIB, MAX_TRUSTED_SIGNING_KEYS,
+};
+use crate::services::migrations;
 use crate::services::{
-    AuthService, CryptoService, ImportExportService, StorageService, VaultStats,
+    AuthService, BreachService, CryptoService, GeneratorService, GithubService, GitlabService,
+    HookService, ImportExportService, KeyringService, ShareService, StatsService, StorageService,
 };
+use crate::services::github_service::{actions_secret_name, ActionsTarget};
+use crate::services::gitlab_service;
+use crate::services::lan_transfer_service::{self, TransferSender};
+use crate::services::sync::engine::{self, SyncStep};
+use crate::services::sync::replica::{self, ReplicaKey};
+use crate::services::sync::{self, SyncProvider};
+use crate::states::job_state::{JobContext, JobRegistry};
+use crate::utils::decrypted_cache::{DecryptedCache, DECRYPTED_CACHE_CAPACITY};
+use crate::utils::peer_process::PeerProcess;
+use crate::utils::proxy_sessions::{ProxySession, ProxySessions, MAX_PROXY_SESSIONS};
+use crate::utils::undo_journal::{UndoJournal, UndoOperation, UNDO_JOURNAL_CAPACITY};
+use crate::utils::input_validation::{name_key, secret_value_size};
+use crate::utils::path_resolvers::{
+    finish_data_directory_move, get_all_paths, get_data_directory, get_default_data_directory,
+    get_default_sessions_directory, get_sessions_directory, migrate_session_files, prune_backups,
+    set_data_directory_pointer, set_sessions_directory_override, validate_backup_directory,
+    validate_data_directory, validate_sessions_directory,
+};
+use crate::utils::secret_lint::{is_password_name, lint_secret, password_strength};
+use crate::utils::secret_template;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+use tokio::sync::oneshot;
+use tokio::time::MissedTickBehavior;
+use tauri_plugin_notification::NotificationExt;
+
+pub const SESSION_EXPIRING_EVENT: &str = "session-expiring";
+pub const SESSION_STATE_EVENT: &str = "session-state";
+pub const IMPORT_PROGRESS_EVENT: &str = "import-progress";
+pub const APP_ACCESS_EVENT: &str = "app-access-requested";
+
+const REVEAL_WINDOW: Duration = Duration::from_secs(60);
+const REVEAL_HANDLE_TTL: Duration = Duration::from_secs(30);
+const MAX_RECENT_SECRETS: usize = 50;
+
+// Holds the session open while a tracked job runs, released when dropped
+struct SessionHold(Arc<AuthService>);
+
+impl SessionHold {
+    fn new(auth: &Arc<AuthService>) -> Self {
+        auth.hold_session();
+        Self(Arc::clone(auth))
+    }
+}
+
+impl Drop for SessionHold {
+    fn drop(&mut self) {
+        self.0.release_session();
+    }
+}
+
+// The user's answer to an app access prompt
+struct AppAccessAnswer {
+    allow: bool,
+    remember: bool, // Add the program to Settings: app_access
+}
+
+// One name of a sync mapping, with the values behind its diff entry
+struct PlannedSync {
+    entry: SyncDiffEntry,
+    secret_id: Option<String>, // None = only in the store
+    local_name: String,        // Secret name a pull creates
+    local_value: Option<String>,
+    remote_value: Option<String>,
+}
+
+// Secret waiting to be copied through a one-time handle
+struct PendingReveal {
+    secret_id: String,
+    environment: Option<String>,
+    issued_at: Instant,
+}
 
 pub struct AppState {
     pub storage: Arc<StorageService>,
+    pub stats: StatsService,
     crypto: CryptoService,
-    auth: Arc<Mutex<AuthService>>,
+    // Locks its session and config internally, so it is shared without an outer guard
+    auth: Arc<AuthService>,
     import_export: ImportExportService,
+    keyring: KeyringService,
+    hooks: HookService,
+    shares: ShareService,
+    generator: GeneratorService,
+    breach: BreachService,
+    github: GithubService,
+    gitlab: GitlabService,
+    last_verified: Arc<Mutex<Option<Instant>>>, // Last successful password entry
+    pending_emergency_kit: Mutex<Option<EmergencyKit>>,
+    reveal_times: Mutex<VecDeque<Instant>>, // Reveals within the last minute
+    reveal_handles: Mutex<HashMap<String, PendingReveal>>,
+    rotation_notified: Mutex<HashSet<String>>, // Secrets already reminded about this unlock
+    decrypted_cache: Arc<Mutex<DecryptedCache>>, // Opt-in, emptied on lock and expiry
+    undo_journal: Arc<Mutex<UndoJournal>>, // Destructive operations, emptied on lock and expiry
+    proxy_sessions: Arc<Mutex<ProxySessions>>, // CLI token sessions, emptied on lock and expiry
+    // Unanswered app access prompts by request id, emptied on lock
+    app_access_prompts: Mutex<HashMap<String, oneshot::Sender<AppAccessAnswer>>>,
     session_timer: Mutex<Option<tokio::task::JoinHandle<()>>>,
+    app_handle: Mutex<Option<AppHandle>>, // For notifications from the session timer
+    lock_warning_shown: Arc<AtomicBool>,
+    startup_status: Mutex<StartupStatus>,
+    jobs: JobRegistry, // Long-running operations, cancelled on lock
 }
 
 impl AppState {
     pub fn new() -> Self {
-        let mut storage = StorageService::new();
-        if let Err(e) = storage.initialize() {
-            // No parameters needed now!
-            eprintln!("Failed to initialize storage: {}", e);
+        let storage = StorageService::new();
+        let startup_status = storage.initialize();
+        let decrypted_cache = Arc::new(Mutex::new(DecryptedCache::new(DECRYPTED_CACHE_CAPACITY)));
+        if startup_status.is_ready() {
+            apply_stored_settings(&storage, &decrypted_cache);
+        } else {
+            tracing::error!("Storage is not ready: {:?}", startup_status);
         }
 
         let storage = Arc::new(storage);
         let import_export = ImportExportService::new(Arc::clone(&storage));
+        let stats = StatsService::new(Arc::clone(&storage));
+        let hooks = HookService::new(Arc::clone(&storage));
 
         Self {
             storage,
+            stats,
             crypto: CryptoService::new(),
-            auth: Arc::new(Mutex::new(AuthService::new())),
+            auth: Arc::new(AuthService::new()),
             import_export,
+            keyring: KeyringService::new(),
+            hooks,
+            shares: ShareService::new(),
+            generator: GeneratorService::new(),
+            breach: BreachService::new(),
+            github: GithubService::new(),
+            gitlab: GitlabService::new(),
+            last_verified: Arc::new(Mutex::new(None)),
+            pending_emergency_kit: Mutex::new(None),
+            reveal_times: Mutex::new(VecDeque::new()),
+            reveal_handles: Mutex::new(HashMap::new()),
+            rotation_notified: Mutex::new(HashSet::new()),
+            decrypted_cache,
+            undo_journal: Arc::new(Mutex::new(UndoJournal::new(UNDO_JOURNAL_CAPACITY))),
+            proxy_sessions: Arc::new(Mutex::new(ProxySessions::new())),
+            app_access_prompts: Mutex::new(HashMap::new()),
             session_timer: Mutex::new(None),
+            app_handle: Mutex::new(None),
+            lock_warning_shown: Arc::new(AtomicBool::new(false)),
+            startup_status: Mutex::new(startup_status),
+            jobs: JobRegistry::new(),
         }
     }
     // INITIALIZATION
 
+    pub fn get_startup_status(&self) -> StartupStatus {
+        self.startup_status.lock().unwrap().clone()
+    }
+
+    /// Apply a recovery step for the current startup problem, then reopen the databases
+    pub fn recover_storage(&self, action: StorageRecovery) -> Result<StartupStatus, ZapError> {
+        let status = self.get_startup_status();
+        let moved_to = match (action, &status) {
+            (_, StartupStatus::Ready) => return Ok(status),
+            (StorageRecovery::Retry, _) => None,
+            (
+                StorageRecovery::RestoreSnapshot,
+                StartupStatus::Corrupted {
+                    database: DatabaseKind::Vault,
+                    ..
+                },
+            ) => Some(self.storage.restore_vault_snapshot()?),
+            (StorageRecovery::Repair, StartupStatus::Corrupted { database, .. }) => {
+                Some(self.storage.quarantine_database(*database)?)
+            }
+            _ => {
+                return Err(ZapError::ValidationError(
+                    "This recovery step doesn't apply to the current startup problem".to_string(),
+                ))
+            }
+        };
+
+        let status = self.storage.initialize();
+        if status.is_ready() {
+            apply_stored_settings(&self.storage, &self.decrypted_cache);
+            let _ = self.storage.log(
+                LogAction::RecoverStorage,
+                "Storage recovered after a failed startup".to_string(),
+                moved_to.map(|path| format!("Damaged database moved to {}", path.display())),
+            );
+        }
+
+        *self.startup_status.lock().unwrap() = status.clone();
+        Ok(status)
+    }
+
     pub async fn initialize(&self) -> Result<bool, ZapError> {
+        if !self.get_startup_status().is_ready() {
+            return Err(ZapError::StorageError(
+                "Storage is unavailable, check the startup status".to_string(),
+            ));
+        }
+
         // Log app start
         let _ = self.storage.log(
-            "App_Start".to_string(),
+            LogAction::AppStart,
             "Application started".to_string(),
             None,
         );
 
         let auth_config = self.storage.load_auth_config()?;
-        let auth = self.auth.lock().unwrap();
-        let is_first_time = auth.initialize(auth_config)?;
+        let settings = self.storage.load_settings()?;
+        let is_first_time = self.auth.initialize(auth_config)?;
+        self.auth.set_max_session_minutes(settings.max_session_minutes);
 
-        println!("Initializing app...");
+        tracing::info!("Initializing app");
         Ok(is_first_time)
     }
 
     // AUTHENTICATION
 
     pub async fn unlock(&self, password: &str) -> Result<bool, ZapError> {
-        let auth = self.auth.lock().unwrap();
-        let is_first_time = auth.unlock(password)?;
+        let unlocked = self
+            .auth
+            .unlock(password)
+            .map(|is_first_time| (is_first_time, self.auth.get_config()));
+        if let Err(ZapError::IncorrectPassword) = &unlocked {
+            let _ = self.storage.log(
+                LogAction::UnlockFailed,
+                "Failed unlock attempt: incorrect password".to_string(),
+                None,
+            );
+        }
+        let (is_first_time, config) = unlocked?;
 
         if is_first_time {
-            if let Some(config) = auth.get_config() {
-                drop(auth);
+            if let Some(config) = config {
                 self.storage.save_auth_config(&config)?;
             }
+
+            // Hand out the emergency kit once, right after setup
+            let kit = self.create_recovery_key()?;
+            *self.pending_emergency_kit.lock().unwrap() = Some(kit);
         }
 
+        *self.last_verified.lock().unwrap() = Some(Instant::now());
+        self.run_unlock_migrations();
+
         let _ = self.storage.log(
-            "Session_Unlock".to_string(),
+            LogAction::SessionUnlock,
             "User session unlocked".to_string(),
             None,
         );
 
-        println!("Session unlocked");
+        tracing::info!("Session unlocked");
+
+        if self.storage.load_settings()?.remember_me {
+            if let Err(e) = self.remember_master_key() {
+                tracing::warn!("Failed to remember master key: {}", e);
+            }
+        }
+
         self.start_session_timer();
+        self.fire_hook(HookEvent::VaultUnlocked, &[]);
         Ok(is_first_time)
     }
 
+    /// Unlock using the master key wrapped in the OS keyring (returns false if unavailable)
+    pub async fn unlock_with_remembered_key(&self) -> Result<bool, ZapError> {
+        if !self.storage.load_settings()?.remember_me {
+            return Ok(false);
+        }
+
+        // A key in the keyring would unlock without the YubiKey
+        if self.auth.hardware_key_enabled() {
+            self.forget_master_key();
+            return Ok(false);
+        }
+
+        let remembered = match self.storage.load_remembered_key()? {
+            Some(remembered) => remembered,
+            None => return Ok(false),
+        };
+
+        // Remembered keys don't survive a reboot
+        if !self.keyring.is_current_boot(remembered.boot_time) {
+            self.forget_master_key();
+            let _ = self.storage.log(
+                LogAction::RememberMeExpired,
+                "Remembered unlock expired after reboot".to_string(),
+                None,
+            );
+            return Ok(false);
+        }
+
+        let wrap_key = match self.keyring.load_wrap_key()? {
+            Some(wrap_key) => wrap_key,
+            None => {
+                self.forget_master_key();
+                return Ok(false);
+            }
+        };
+
+        let master_key_hex = self
+            .crypto
+            .decrypt(&remembered.wrapped_master_key, &wrap_key)?;
+        let master_key = self.decode_master_key(&master_key_hex)?;
+
+        self.auth.unlock_with_master_key(master_key)?;
+        self.run_unlock_migrations();
+
+        let _ = self.storage.log(
+            LogAction::SessionUnlockRemembered,
+            "User session unlocked with key from OS keyring (no password)".to_string(),
+            None,
+        );
+
+        self.start_session_timer();
+        self.fire_hook(HookEvent::VaultUnlocked, &[]);
+        Ok(true)
+    }
+
+    // RECOVERY KEY
+
+    /// Emergency kit generated at first-time setup (returned only once)
+    pub fn take_emergency_kit(&self) -> Result<Option<EmergencyKit>, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        Ok(self.pending_emergency_kit.lock().unwrap().take())
+    }
+
+    /// Replace the recovery key - the old one stops working immediately
+    pub async fn regenerate_recovery_key(&self, password: &str) -> Result<EmergencyKit, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        self.verify_password(password)?;
+        let kit = self.create_recovery_key()?;
+
+        let _ = self.storage.log(
+            LogAction::RegenerateRecoveryKey,
+            "Recovery key regenerated".to_string(),
+            None,
+        );
+
+        Ok(kit)
+    }
+
+    /// Unlock with the recovery key and set a new master password.
+    /// Box keys are re-wrapped and a fresh recovery key is issued.
+    pub async fn unlock_with_recovery_key(
+        &self,
+        recovery_key: &str,
+        new_password: &str,
+    ) -> Result<EmergencyKit, ZapError> {
+        let config = self
+            .auth
+            .get_config()
+            .ok_or(ZapError::AuthError("Auth not initialized".to_string()))?;
+        let recovery = config
+            .recovery
+            .ok_or(ZapError::AuthError("No recovery key has been set up".to_string()))?;
+
+        let recovery_bytes = self.crypto.parse_recovery_key(recovery_key)?;
+        let master_key_hex = self
+            .crypto
+            .decrypt(&recovery.wrapped_master_key, &recovery_bytes)
+            .map_err(|_| ZapError::AuthError("Recovery key is incorrect".to_string()))?;
+        let old_master_key = self.decode_master_key(&master_key_hex)?;
+
+        let kit = self.rekey_master_key(&old_master_key, |auth| {
+            auth.reset_master_password(new_password)
+        })?;
+        self.run_unlock_migrations();
+        *self.last_verified.lock().unwrap() = Some(Instant::now());
+
+        let _ = self.storage.log(
+            LogAction::UnlockWithRecoveryKey,
+            "Vault unlocked with recovery key and master password reset".to_string(),
+            None,
+        );
+
+        self.start_session_timer();
+        Ok(kit)
+    }
+
+    // HARDWARE KEY
+
+    /// Whether unlock needs a YubiKey, and whether one is plugged in (works while locked)
+    pub fn get_hardware_key_status(&self) -> HardwareKeyStatus {
+        self.auth.hardware_key_status()
+    }
+
+    /// Switch unlock to a PIN plus YubiKey challenge-response.
+    /// Box keys are re-wrapped and a fresh recovery key is issued.
+    pub async fn enable_hardware_key(
+        &self,
+        password: &str,
+        pin: &str,
+        slot: u8,
+    ) -> Result<EmergencyKit, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        self.verify_password(password)?;
+        let old_master_key = self.get_master_key()?;
+        let kit =
+            self.rekey_master_key(&old_master_key, |auth| auth.enable_hardware_key(pin, slot))?;
+
+        // The rekey already cleared the remembered key, keep unlock from wrapping a new one
+        let mut settings = self.storage.load_settings()?;
+        if settings.remember_me {
+            settings.remember_me = false;
+            self.storage.save_settings(&settings)?;
+        }
+
+        let _ = self.storage.log(
+            LogAction::EnableHardwareKey,
+            format!("Unlock now requires a PIN and the YubiKey in slot {}", slot),
+            None,
+        );
+
+        Ok(kit)
+    }
+
+    /// Go back to a master password (the PIN and YubiKey are checked first)
+    pub async fn disable_hardware_key(
+        &self,
+        pin: &str,
+        new_password: &str,
+    ) -> Result<EmergencyKit, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        self.verify_password(pin)?;
+        let old_master_key = self.get_master_key()?;
+        let kit = self.rekey_master_key(&old_master_key, |auth| {
+            auth.reset_master_password(new_password)
+        })?;
+
+        let _ = self.storage.log(
+            LogAction::DisableHardwareKey,
+            "YubiKey unlock removed and master password set".to_string(),
+            None,
+        );
+
+        Ok(kit)
+    }
+
     pub fn lock(&self) {
-        let auth = self.auth.lock().unwrap();
-        auth.lock();
-        drop(auth);
+        self.auth.lock();
+
+        // Explicit lock always requires the password next time
+        self.forget_master_key();
+        *self.last_verified.lock().unwrap() = None;
+        self.reveal_handles.lock().unwrap().clear();
+        self.rotation_notified.lock().unwrap().clear();
+        self.decrypted_cache.lock().unwrap().clear();
+        self.undo_journal.lock().unwrap().clear();
+        self.proxy_sessions.lock().unwrap().clear();
+        self.app_access_prompts.lock().unwrap().clear(); // Waiting requests are denied
+        self.jobs.clear();
 
         let _ = self.storage.log(
-            "Session_Lock".to_string(),
+            LogAction::SessionLock,
             "User session locked".to_string(),
             None,
         );
+        if let Some(app) = self.app_handle.lock().unwrap().as_ref() {
+            let _ = app.emit(SESSION_STATE_EVENT, self.get_session_info());
+        }
+        if let Err(e) = self.storage.flush() {
+            tracing::error!("Failed to flush storage on lock: {}", e);
+        }
 
         self.stop_session_timer();
     }
 
     pub fn is_locked(&self) -> bool {
-        let auth = self.auth.lock().unwrap();
-        !auth.is_unlocked()
+        !self.auth.is_unlocked()
     }
 
     pub fn verify_password(&self, password: &str) -> Result<(), ZapError> {
@@ -100,17 +525,28 @@ impl AppState {
             return Err(ZapError::AuthError("Password cannot be empty".to_string()));
         }
 
-        let auth = self.auth.lock().unwrap();
-        let config = auth
-            .get_config()
-            .ok_or(ZapError::AuthError("Auth not initialized".to_string()))?;
+        if !self.auth.check_password(password)? {
+            return Err(ZapError::IncorrectPassword);
+        }
 
-        if let Some(stored_hash) = &config.master_password_hash {
-            if !self.crypto.verify_password(password, stored_hash)? {
-                return Err(ZapError::IncorrectPassword);
-            }
+        *self.last_verified.lock().unwrap() = Some(Instant::now());
+        Ok(())
+    }
+
+    /// Sensitive actions need a password entry within the configured re-auth window
+    fn require_recent_verification(&self) -> Result<(), ZapError> {
+        let reauth_minutes = self.storage.load_settings()?.reauth_minutes;
+        if reauth_lapsed(&self.last_verified, reauth_minutes) {
+            return Err(ZapError::ReauthenticationRequired);
         }
+        Ok(())
+    }
 
+    // Everything that hands decrypted values to the webview, not just reveal_secret_value
+    fn require_plaintext_reveal(&self) -> Result<(), ZapError> {
+        if self.storage.load_settings()?.clipboard_only_reveal {
+            return Err(ZapError::PlaintextRevealDisabled);
+        }
         Ok(())
     }
 
@@ -142,13 +578,16 @@ impl AppState {
         if self.is_locked() {
             return Err(ZapError::SessionExpired);
         }
-        let new_box = Box::new(name.clone(), description, tags, dev_mode)?;
+        let data_key = self.crypto.generate_data_key();
+        let wrapped_key = self.crypto.wrap_key(&data_key, &self.get_master_key()?)?;
+        let new_box =
+            Box::new(name.clone(), description, tags, dev_mode)?.with_wrapped_key(wrapped_key);
         let box_id = new_box.id.clone();
 
         self.storage.save_box(&new_box)?;
 
         let _ = self.storage.log(
-            "Create_Box".to_string(),
+            LogAction::CreateBox,
             format!("Box '{}' created", name),
             None,
         );
@@ -168,30 +607,36 @@ impl AppState {
             return Err(ZapError::SessionExpired);
         }
 
-        let mut box_item = self.storage.get_box(box_id)?;
-        let old_name = box_item.name.clone();
-
-        // Track changes for logging
+        let mut old_name = String::new();
         let mut changes = Vec::new();
-        if let Some(ref new_name) = name {
-            if new_name != &box_item.name {
-                changes.push(format!("name: '{}' -> '{}'", box_item.name, new_name));
+        self.storage.modify_box(box_id, |box_item| {
+            old_name = box_item.name.clone();
+
+            // Dev mode is a local choice, the rest of a shared box belongs to its owner
+            if name.is_some() || description.is_some() || tags.is_some() {
+                box_item.ensure_writable()?;
             }
-        }
-        if let Some(new_dev_mode) = dev_mode {
-            if new_dev_mode != box_item.dev_mode {
-                changes.push(format!(
-                    "dev_mode: {} -> {}",
-                    box_item.dev_mode, new_dev_mode
-                ));
+
+            // Track changes for logging
+            if let Some(ref new_name) = name {
+                if new_name != &box_item.name {
+                    changes.push(format!("name: '{}' -> '{}'", box_item.name, new_name));
+                }
+            }
+            if let Some(new_dev_mode) = dev_mode {
+                if new_dev_mode != box_item.dev_mode {
+                    changes.push(format!(
+                        "dev_mode: {} -> {}",
+                        box_item.dev_mode, new_dev_mode
+                    ));
+                }
             }
-        }
 
-        box_item.update_fields(name, description, tags, dev_mode)?;
-        self.storage.update_box(&box_item)?;
+            box_item.update_fields(name, description, tags, dev_mode)
+        })?;
 
         let _ = self.storage.log(
-            "Update_Box".to_string(),
+            LogAction::UpdateBox,
             format!("Box '{}' updated", old_name),
             if changes.is_empty() {
                 None
@@ -210,11 +655,16 @@ impl AppState {
 
         let box_item = self.storage.get_box(box_id)?;
         let box_name = box_item.name.clone();
+        let secrets = self.storage.get_secrets_by_box_id(box_id)?;
 
         self.storage.delete_box(box_id)?;
+        self.record_undo(
+            format!("Delete box '{}'", box_name),
+            UndoOperation::Boxes(vec![(box_item, secrets)]),
+        );
 
         let _ = self.storage.log(
-            "Delete_Box".to_string(),
+            LogAction::DeleteBox,
             format!("Box '{}' deleted", box_name),
             None,
         );
@@ -230,10 +680,21 @@ impl AppState {
             return Err(ZapError::SessionExpired);
         }
 
+        let mut deleted = Vec::new();
+        for box_id in &box_ids {
+            let box_item = self.storage.get_box(box_id)?;
+            deleted.push((box_item, self.storage.get_secrets_by_box_id(box_id)?));
+        }
+
+        self.take_vault_snapshot(SnapshotReason::BulkDelete)?;
         let deleted_names = self.storage.delete_selected_boxes(&box_ids)?;
+        self.record_undo(
+            format!("Delete {} boxes", deleted_names.len()),
+            UndoOperation::Boxes(deleted),
+        );
 
         let _ = self.storage.log(
-            "Delete_Boxes_Bulk".to_string(),
+            LogAction::DeleteBoxesBulk,
             format!("Bulk deleted {} boxes", deleted_names.len()),
             Some(deleted_names.join(", ")),
         );
@@ -241,6 +702,32 @@ impl AppState {
         Ok(deleted_names)
     }
 
+    /// Take ownership of a shared box: it becomes editable and newer exports no longer apply
+    pub async fn detach_shared_box(&self, box_id: &str) -> Result<Box, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let box_item = self.storage.get_box(box_id)?;
+        if !box_item.read_only {
+            return Ok(box_item);
+        }
+
+        let box_item = self.storage.modify_box(box_id, |box_item| {
+            box_item.read_only = false;
+            box_item.updated_at = chrono::Utc::now();
+            Ok(())
+        })?;
+
+        let _ = self.storage.log(
+            LogAction::DetachSharedBox,
+            format!("Shared box '{}' detached and is now editable", box_item.name),
+            None,
+        );
+
+        Ok(box_item)
+    }
+
     // SECRET OPERATIONS - PASSWORDLESS
 
     pub async fn get_secrets_by_box_id(&self, box_id: &str) -> Result<Vec<Secret>, ZapError> {
@@ -248,7 +735,7 @@ impl AppState {
             return Err(ZapError::SessionExpired);
         }
 
-        self.storage.get_secrets_by_box_id(box_id)
+        self.storage.list_secrets_by_box_id(box_id)
     }
 
     pub async fn get_all_secrets(&self) -> Result<Vec<Secret>, ZapError> {
@@ -256,7 +743,25 @@ impl AppState {
             return Err(ZapError::SessionExpired);
         }
 
-        self.storage.get_all_secrets()
+        self.storage.list_all_secrets()
+    }
+
+    /// Built-in templates a secret value can be generated from (works while locked)
+    pub fn get_secret_templates(&self) -> Vec<SecretTemplate> {
+        self.generator.templates()
+    }
+
+    /// Generate a value from a secret template, composed templates read `fields`
+    pub fn generate_template_value(
+        &self,
+        template_id: &str,
+        fields: &HashMap<String, String>,
+    ) -> Result<String, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        self.generator.generate(template_id, fields)
     }
 
     pub async fn create_secret(
@@ -264,29 +769,46 @@ impl AppState {
         box_id: String,
         name: String,
         value: String,
-    ) -> Result<String, ZapError> {
+        value_encoding: ValueEncoding,
+        multiline_format: MultilineFormat,
+    ) -> Result<SavedSecret, ZapError> {
         if self.is_locked() {
             return Err(ZapError::SessionExpired);
         }
 
         let box_item = self.storage.get_box(&box_id)?;
+        box_item.ensure_writable()?;
         box_item.can_add_secret()?;
-
-        let master_key = self.get_master_key()?;
-        let encrypted_value = self.crypto.encrypt(&value, &master_key)?;
-
-        let new_secret = Secret::new(box_id, name.clone(), encrypted_value)?;
+        secret_value_size(&value, self.storage.max_secret_value_bytes())?;
+        let warnings = self.lint_secret_value(&name, &value, value_encoding);
+        let strength = Self::secret_strength(&name, &value, value_encoding);
+
+        let box_key = self.box_key(&box_item)?;
+        let mut new_secret = Secret::new(box_id, name.clone(), EncryptedData::empty())?
+            .with_value_encoding(value_encoding)
+            .with_multiline_format(multiline_format);
+        new_secret.strength = strength.clone();
+        new_secret.encrypted_value = self.crypto.encrypt_secret(&new_secret, &value, &box_key)?;
         let secret_id = new_secret.id.clone();
 
         self.storage.save_secret(&new_secret)?;
 
         let _ = self.storage.log(
-            "Create_Secret".to_string(),
+            LogAction::CreateSecret,
             format!("Secret '{}' created in box '{}'", name, box_item.name),
             None,
         );
 
-        Ok(secret_id)
+        self.fire_hook(
+            HookEvent::SecretCreated,
+            &[("box_name", &box_item.name), ("secret_name", &name)],
+        );
+
+        Ok(SavedSecret {
+            id: secret_id,
+            warnings,
+            strength,
+        })
     }
 
     pub async fn update_secret(
@@ -294,14 +816,44 @@ impl AppState {
         secret_id: &str,
         name: Option<String>,
         value: Option<String>,
-    ) -> Result<(), ZapError> {
+        value_encoding: Option<ValueEncoding>,
+        multiline_format: Option<MultilineFormat>,
+    ) -> Result<SavedSecret, ZapError> {
         if self.is_locked() {
             return Err(ZapError::SessionExpired);
         }
 
         let mut secret = self.storage.get_secret(secret_id)?;
         let box_item = self.storage.get_box(&secret.box_id)?;
+        box_item.ensure_writable()?;
         let old_name = secret.name.clone();
+        if let Some(value) = &value {
+            secret_value_size(value, self.storage.max_secret_value_bytes())?;
+        }
+
+        // The stored bytes are only reinterpreted along with a new value
+        let value_encoding = value_encoding.unwrap_or(secret.value_encoding);
+        if value_encoding != secret.value_encoding && value.is_none() {
+            return Err(ZapError::ValidationError(
+                "Changing a secret's value encoding requires a new value".to_string(),
+            ));
+        }
+        if value_encoding != secret.value_encoding && !secret.variants.is_empty() {
+            return Err(ZapError::ValidationError(
+                "Remove the secret's environment variants before changing its value encoding"
+                    .to_string(),
+            ));
+        }
+
+        // A rename can change the inferred kind, so lint against the name being saved
+        let saved_name = name.as_deref().unwrap_or(&old_name);
+        let (warnings, strength) = match &value {
+            Some(value) => (
+                self.lint_secret_value(saved_name, value, value_encoding),
+                Self::secret_strength(saved_name, value, value_encoding),
+            ),
+            None => (Vec::new(), None),
+        };
 
         // Track changes for logging
         let mut changes = Vec::new();
@@ -313,11 +865,19 @@ impl AppState {
         if value.is_some() {
             changes.push("value updated".to_string());
         }
+        let new_format = multiline_format.filter(|format| *format != secret.multiline_format);
+        if let Some(format) = new_format {
+            changes.push(format!("multiline format: {:?}", format));
+            secret.multiline_format = format;
+            secret.updated_at = chrono::Utc::now();
+        }
 
         // Encrypt new value if provided
         let encrypted_value = if let Some(new_value) = value {
-            let master_key = self.get_master_key()?;
-            Some(self.crypto.encrypt(&new_value, &master_key)?)
+            secret.value_encoding = value_encoding;
+            secret.strength = strength.clone();
+            let box_key = self.box_key(&box_item)?;
+            Some(self.crypto.encrypt_secret(&secret, &new_value, &box_key)?)
         } else {
             None
         };
@@ -326,7 +886,7 @@ impl AppState {
         self.storage.update_secret(&secret)?;
 
         let _ = self.storage.log(
-            "Update_Secret".to_string(),
+            LogAction::UpdateSecret,
             format!("Secret '{}' updated in box '{}'", old_name, box_item.name),
             if changes.is_empty() {
                 None
@@ -335,9 +895,127 @@ impl AppState {
             },
         );
 
+        self.fire_hook(
+            HookEvent::SecretUpdated,
+            &[("box_name", &box_item.name), ("secret_name", &secret.name)],
+        );
+
+        Ok(SavedSecret {
+            id: secret.id,
+            warnings,
+            strength,
+        })
+    }
+
+    /// Set the value a secret takes in one environment, replacing any earlier one
+    pub async fn set_secret_variant(
+        &self,
+        secret_id: &str,
+        environment: String,
+        value: String,
+    ) -> Result<SavedSecret, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let mut secret = self.storage.get_secret(secret_id)?;
+        let box_item = self.storage.get_box(&secret.box_id)?;
+        box_item.ensure_writable()?;
+        secret_value_size(&value, self.storage.max_secret_value_bytes())?;
+        if !secret.variants.contains_key(&environment)
+            && secret.variants.len() >= MAX_SECRET_VARIANTS
+        {
+            return Err(ZapError::ValidationError(format!(
+                "A secret can have at most {} environment variants",
+                MAX_SECRET_VARIANTS
+            )));
+        }
+        let warnings = self.lint_secret_value(&secret.name, &value, secret.value_encoding);
+        // Reported for this value only - the stored strength belongs to the main value
+        let strength = Self::secret_strength(&secret.name, &value, secret.value_encoding);
+
+        let box_key = self.box_key(&box_item)?;
+        let data = self
+            .crypto
+            .encrypt_secret_variant(&secret, &environment, &value, &box_key)?;
+        secret.variants.insert(environment.clone(), data);
+        secret.updated_at = chrono::Utc::now();
+        self.storage.update_secret(&secret)?;
+
+        let _ = self.storage.log(
+            LogAction::UpdateSecret,
+            format!("Secret '{}' updated in box '{}'", secret.name, box_item.name),
+            Some(format!("{} value set", environment)),
+        );
+
+        Ok(SavedSecret {
+            id: secret.id,
+            warnings,
+            strength,
+        })
+    }
+
+    pub async fn remove_secret_variant(
+        &self,
+        secret_id: &str,
+        environment: &str,
+    ) -> Result<(), ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let mut secret = self.storage.get_secret(secret_id)?;
+        let box_item = self.storage.get_box(&secret.box_id)?;
+        box_item.ensure_writable()?;
+        if secret.variants.remove(environment).is_none() {
+            return Err(ZapError::ValidationError(format!(
+                "Secret '{}' has no value for environment '{}'",
+                secret.name, environment
+            )));
+        }
+        secret.updated_at = chrono::Utc::now();
+        self.storage.update_secret(&secret)?;
+
+        let _ = self.storage.log(
+            LogAction::UpdateSecret,
+            format!("Secret '{}' updated in box '{}'", secret.name, box_item.name),
+            Some(format!("{} value removed", environment)),
+        );
+
         Ok(())
     }
 
+    // Advisory only - a value is saved whatever the linter thinks of it (binary values are skipped)
+    fn lint_secret_value(
+        &self,
+        name: &str,
+        value: &str,
+        value_encoding: ValueEncoding,
+    ) -> Vec<SecretWarning> {
+        let enabled = self
+            .storage
+            .load_settings()
+            .map_or(true, |settings| settings.lint_secret_values);
+        if enabled && value_encoding.is_utf8() {
+            lint_secret(name, value)
+        } else {
+            Vec::new()
+        }
+    }
+
+    // Binary values are never passwords, so they aren't scored
+    fn secret_strength(
+        name: &str,
+        value: &str,
+        value_encoding: ValueEncoding,
+    ) -> Option<PasswordStrength> {
+        if value_encoding.is_utf8() {
+            password_strength(name, value)
+        } else {
+            None
+        }
+    }
+
     pub async fn delete_secret(&self, secret_id: &str) -> Result<(), ZapError> {
         if self.is_locked() {
             return Err(ZapError::SessionExpired);
@@ -345,12 +1023,17 @@ impl AppState {
 
         let secret = self.storage.get_secret(secret_id)?;
         let box_item = self.storage.get_box(&secret.box_id)?;
+        box_item.ensure_writable()?;
         let secret_name = secret.name.clone();
 
         self.storage.delete_secret(secret_id)?;
+        self.record_undo(
+            format!("Delete secret '{}'", secret_name),
+            UndoOperation::Secrets(vec![secret]),
+        );
 
         let _ = self.storage.log(
-            "Delete_Secret".to_string(),
+            LogAction::DeleteSecret,
             format!(
                 "Secret '{}' deleted from box '{}'",
                 secret_name, box_item.name
@@ -369,10 +1052,22 @@ impl AppState {
             return Err(ZapError::SessionExpired);
         }
 
+        let mut deleted = Vec::new();
+        for secret_id in &secret_ids {
+            let secret = self.storage.get_secret(secret_id)?;
+            self.storage.get_box(&secret.box_id)?.ensure_writable()?;
+            deleted.push(secret);
+        }
+
+        self.take_vault_snapshot(SnapshotReason::BulkDelete)?;
         let deleted_names = self.storage.delete_selected_secrets(&secret_ids)?;
+        self.record_undo(
+            format!("Delete {} secrets", deleted_names.len()),
+            UndoOperation::Secrets(deleted),
+        );
 
         let _ = self.storage.log(
-            "Delete_Secrets_Bulk".to_string(),
+            LogAction::DeleteSecretsBulk,
             format!("Bulk deleted {} secrets", deleted_names.len()),
             Some(deleted_names.join(", ")),
         );
@@ -380,30 +1075,164 @@ impl AppState {
         Ok(deleted_names)
     }
 
-    pub async fn copy_secrets_to_box(
-        &self,
-        secret_ids: Vec<String>,
-        target_box_id: String,
-    ) -> Result<Vec<String>, ZapError> {
+    /// Put back what the most recent destructive operation (within UNDO_WINDOW) deleted or
+    /// overwrote. Items whose name has been taken since are skipped and listed.
+    pub async fn undo_last_operation(&self) -> Result<UndoResult, ZapError> {
         if self.is_locked() {
             return Err(ZapError::SessionExpired);
         }
 
-        // Check 75 secrets per box limit
-        let target_box = self.storage.get_box(&target_box_id)?;
-        if target_box.secrets_count + secret_ids.len() > 75 {
-            return Err(ZapError::ValidationError(
-                "Target box would exceed 75 secrets limit".to_string(),
-            ));
+        let entry = self
+            .undo_journal
+            .lock()
+            .unwrap()
+            .take_latest()
+            .ok_or_else(|| ZapError::ValidationError("Nothing to undo".to_string()))?;
+
+        let mut result = UndoResult {
+            description: entry.description,
+            restored: 0,
+            skipped: Vec::new(),
+        };
+        match entry.operation {
+            UndoOperation::Secrets(secrets) => {
+                for secret in secrets {
+                    match self.restore_secret(&secret) {
+                        Ok(()) => result.restored += 1,
+                        Err(e) => {
+                            tracing::warn!(
+                                "Undo skipped secret {}: {}",
+                                logging::redact(&secret.name),
+                                e
+                            );
+                            result.skipped.push(secret.name);
+                        }
+                    }
+                }
+            }
+            UndoOperation::Boxes(boxes) => {
+                for (box_item, secrets) in boxes {
+                    match self.storage.save_box_with_secrets(&box_item, &secrets) {
+                        Ok(()) => result.restored += 1,
+                        Err(e) => {
+                            tracing::warn!(
+                                "Undo skipped box {}: {}",
+                                logging::redact(&box_item.name),
+                                e
+                            );
+                            result.skipped.push(box_item.name);
+                        }
+                    }
+                }
+            }
         }
 
-        let master_key = self.get_master_key()?;
-        let copied_names =
-            self.storage
-                .copy_secrets_to_box(&secret_ids, &target_box_id, &master_key)?;
-
         let _ = self.storage.log(
-            "Copy_Secrets".to_string(),
+            LogAction::UndoOperation,
+            format!("Undid '{}' ({} restored)", result.description, result.restored),
+            (!result.skipped.is_empty())
+                .then(|| format!("skipped: {}", result.skipped.join(", "))),
+        );
+
+        Ok(result)
+    }
+
+    // Overwrite if the secret is still there, otherwise bring it back
+    fn restore_secret(&self, secret: &Secret) -> Result<(), ZapError> {
+        self.storage.get_box(&secret.box_id)?;
+        match self.storage.get_secret(&secret.id) {
+            Ok(_) => self.storage.update_secret(secret),
+            Err(_) => self.storage.save_secret(secret),
+        }
+    }
+
+    fn record_undo(&self, description: String, operation: UndoOperation) {
+        self.undo_journal.lock().unwrap().record(description, operation);
+    }
+
+    // Copy of the whole vault, restorable with restore_snapshot after the undo window is gone
+    fn take_vault_snapshot(&self, reason: SnapshotReason) -> Result<(), ZapError> {
+        let snapshot = self.storage.create_vault_snapshot(reason)?;
+        tracing::info!(id = %snapshot.id, bytes = snapshot.bytes, "Vault snapshot taken");
+        self.storage.prune_vault_snapshots()?;
+        Ok(())
+    }
+
+    // Every secret as stored, taken before an import that may overwrite some of them
+    fn snapshot_secrets(&self) -> Result<HashMap<String, Secret>, ZapError> {
+        Ok(self
+            .storage
+            .get_all_secrets()?
+            .into_iter()
+            .map(|secret| (secret.id.clone(), secret))
+            .collect())
+    }
+
+    // Journal what an import replaced or removed - a re-encrypted value always has a new nonce
+    fn record_overwritten(&self, description: &str, before: HashMap<String, Secret>) {
+        let after: HashMap<String, Vec<u8>> = match self.storage.get_all_secrets() {
+            Ok(secrets) => secrets
+                .into_iter()
+                .map(|secret| (secret.id, secret.encrypted_value.nonce))
+                .collect(),
+            Err(e) => {
+                tracing::warn!("Failed to journal import for undo: {}", e);
+                return;
+            }
+        };
+
+        let overwritten: Vec<Secret> = before
+            .into_values()
+            .filter(|secret| after.get(&secret.id) != Some(&secret.encrypted_value.nonce))
+            .collect();
+        if !overwritten.is_empty() {
+            self.record_undo(
+                format!("{} ({} secrets overwritten)", description, overwritten.len()),
+                UndoOperation::Secrets(overwritten),
+            );
+        }
+    }
+
+    pub async fn copy_secrets_to_box(
+        &self,
+        secret_ids: Vec<String>,
+        target_box_id: String,
+    ) -> Result<Vec<String>, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        // Check 75 secrets per box limit
+        let target_box = self.storage.get_box(&target_box_id)?;
+        target_box.ensure_writable()?;
+        if target_box.secrets_count + secret_ids.len() > 75 {
+            return Err(ZapError::ValidationError(
+                "Target box would exceed 75 secrets limit".to_string(),
+            ));
+        }
+
+        // Values move from the source box key to the target box key
+        let target_key = self.box_key(&target_box)?;
+        let copied_names = self
+            .storage
+            .copy_secrets_to_box(&secret_ids, &target_box_id, |source, copy| {
+                let source_key = self.box_key(&self.storage.get_box(&source.box_id)?)?;
+                let value = self.crypto.decrypt_secret(source, &source_key)?;
+                copy.encrypted_value = self.crypto.encrypt_secret(copy, &value, &target_key)?;
+                for environment in source.variants.keys() {
+                    let value = self
+                        .crypto
+                        .decrypt_secret_for(source, Some(environment), &source_key)?;
+                    let data = self
+                        .crypto
+                        .encrypt_secret_variant(copy, environment, &value, &target_key)?;
+                    copy.variants.insert(environment.clone(), data);
+                }
+                Ok(())
+            })?;
+
+        let _ = self.storage.log(
+            LogAction::CopySecrets,
             format!(
                 "Copied {} secrets to box '{}'",
                 copied_names.len(),
@@ -415,305 +1244,3835 @@ impl AppState {
         Ok(copied_names)
     }
 
-    pub async fn reveal_secret_value(&self, secret_id: &str) -> Result<String, ZapError> {
+    pub async fn reveal_secret_value(
+        &self,
+        secret_id: &str,
+        environment: Option<String>,
+    ) -> Result<String, ZapError> {
         if self.is_locked() {
             return Err(ZapError::SessionExpired);
         }
+        self.require_recent_verification()?;
+        self.require_plaintext_reveal()?;
 
         let secret = self.storage.get_secret(secret_id)?;
         let box_item = self.storage.get_box(&secret.box_id)?;
-        let master_key = self.get_master_key()?;
-        let decrypted_value = self.crypto.decrypt(&secret.encrypted_value, &master_key)?;
+        self.check_reveal_rate_limit(&secret.name)?;
+
+        let box_key = self.box_key(&box_item)?;
+        let environment = self.environment_or_default(environment)?;
+        let decrypted_value =
+            self.decrypt_variant_cached(&secret, environment.as_deref(), &box_key)?;
+        let _ = self.storage.record_secret_use(&secret.id);
 
         let _ = self.storage.log(
-            "Reveal_Secret".to_string(),
+            LogAction::RevealSecret,
             format!(
                 "Secret '{}' revealed from box '{}'",
                 secret.name, box_item.name
             ),
-            None,
+            environment.map(|environment| format!("environment: {}", environment)),
         );
 
         Ok(decrypted_value)
     }
 
-    // SEARCH OPERATIONS
-
-    pub async fn search_boxes_global(
+    /// Issue a one-time handle the UI can exchange for a clipboard copy
+    pub async fn reveal_secret_handle(
         &self,
-        query: String,
-        tags: Vec<String>,
-    ) -> Result<Vec<Box>, ZapError> {
+        secret_id: &str,
+        environment: Option<String>,
+    ) -> Result<RevealHandle, ZapError> {
         if self.is_locked() {
             return Err(ZapError::SessionExpired);
         }
+        self.require_recent_verification()?;
 
-        self.storage.search_boxes_global(&query, &tags)
-    }
+        let secret = self.storage.get_secret(secret_id)?;
+        self.check_reveal_rate_limit(&secret.name)?;
+        let environment = self.environment_or_default(environment)?;
 
-    pub async fn search_secrets_in_box(
-        &self,
-        box_id: String,
-        query: String,
-    ) -> Result<Vec<Secret>, ZapError> {
-        if self.is_locked() {
-            return Err(ZapError::SessionExpired);
+        let handle = uuid::Uuid::new_v4().to_string();
+        {
+            let mut handles = self.reveal_handles.lock().unwrap();
+            handles.retain(|_, pending| pending.issued_at.elapsed() <= REVEAL_HANDLE_TTL);
+            handles.insert(
+                handle.clone(),
+                PendingReveal {
+                    secret_id: secret.id.clone(),
+                    environment,
+                    issued_at: Instant::now(),
+                },
+            );
         }
 
-        self.storage.search_secrets_in_box(&box_id, &query)
+        Ok(RevealHandle {
+            handle,
+            secret_name: secret.name,
+            expires_in_seconds: REVEAL_HANDLE_TTL.as_secs(),
+        })
     }
 
-    // IMPORT/EXPORT
-
-    pub async fn export_vault(&self) -> Result<String, ZapError> {
+    /// Consume a reveal handle and return the plaintext for the clipboard (never the webview)
+    pub async fn redeem_reveal_handle(&self, handle: &str) -> Result<String, ZapError> {
         if self.is_locked() {
             return Err(ZapError::SessionExpired);
         }
 
-        let master_key = self.get_master_key()?;
-        let result = self.import_export.export_vault(&master_key)?;
+        let pending = self
+            .reveal_handles
+            .lock()
+            .unwrap()
+            .remove(handle)
+            .filter(|pending| pending.issued_at.elapsed() <= REVEAL_HANDLE_TTL)
+            .ok_or(ZapError::InvalidRevealHandle)?;
+
+        let secret = self.storage.get_secret(&pending.secret_id)?;
+        let box_item = self.storage.get_box(&secret.box_id)?;
+        let box_key = self.box_key(&box_item)?;
+        let environment = pending.environment.as_deref();
+        let decrypted_value = self.decrypt_variant_cached(&secret, environment, &box_key)?;
+        let _ = self.storage.record_secret_use(&secret.id);
 
-        let vault_stats = self.storage.get_vault_stats()?;
         let _ = self.storage.log(
-            "Export_Vault".to_string(),
+            LogAction::CopySecret,
             format!(
-                "Exported vault ({} boxes, {} secrets)",
-                vault_stats.total_boxes, vault_stats.total_secrets
+                "Secret '{}' copied to clipboard from box '{}'",
+                secret.name, box_item.name
             ),
-            None,
+            environment.map(|environment| format!("environment: {}", environment)),
         );
 
-        Ok(result)
+        Ok(decrypted_value)
     }
 
-    pub async fn export_box_as_env(
+    // ROTATION REMINDERS
+
+    pub async fn set_rotation_interval(
         &self,
-        box_id: String,
-        prefix: Option<String>,
-    ) -> Result<String, ZapError> {
+        secret_id: &str,
+        days: Option<u32>,
+    ) -> Result<Secret, ZapError> {
         if self.is_locked() {
             return Err(ZapError::SessionExpired);
         }
 
-        let box_item = self.storage.get_box(&box_id)?;
-        let master_key = self.get_master_key()?;
-        let result =
-            self.import_export
-                .export_box_as_env(&box_id, &master_key, prefix.as_deref())?;
+        let mut secret = self.storage.get_secret(secret_id)?;
+        secret.set_rotation_interval(days)?;
+        self.storage.update_secret(&secret)?;
 
         let _ = self.storage.log(
-            "Export_Box".to_string(),
-            format!(
-                "Exported box '{}' as .env ({} secrets)",
-                box_item.name, box_item.secrets_count
-            ),
-            prefix,
+            LogAction::SetRotationInterval,
+            format!("Rotation reminder updated for secret '{}'", secret.name),
+            Some(match days {
+                Some(days) => format!("every {} days", days),
+                None => "disabled".to_string(),
+            }),
         );
 
-        Ok(result)
+        Ok(secret)
     }
 
-    pub async fn import_vault(
-        &self,
-        json_data: &str,
-    ) -> Result<crate::models::ImportResult, ZapError> {
+    /// Reset the rotation clock after the value was rotated outside Zap
+    pub async fn mark_rotated(&self, secret_id: &str) -> Result<Secret, ZapError> {
         if self.is_locked() {
             return Err(ZapError::SessionExpired);
         }
 
-        let master_key = self.get_master_key()?;
-        let result = self.import_export.import_vault(json_data, &master_key)?;
+        let mut secret = self.storage.get_secret(secret_id)?;
+        let box_item = self.storage.get_box(&secret.box_id)?;
+        secret.mark_rotated();
+        self.storage.update_secret(&secret)?;
+        self.rotation_notified.lock().unwrap().remove(&secret.id);
 
         let _ = self.storage.log(
-            "Import_Vault".to_string(),
+            LogAction::RotateSecret,
             format!(
-                "Imported vault ({} boxes, {} secrets)",
-                result.boxes_imported, result.secrets_imported
+                "Secret '{}' marked as rotated in box '{}'",
+                secret.name, box_item.name
             ),
-            if result.has_errors() {
-                Some(format!("{} errors", result.errors.len()))
-            } else {
-                None
-            },
+            None,
         );
 
-        Ok(result)
+        Ok(secret)
     }
 
-    pub async fn import_env_to_box(
-        &self,
-        env_content: &str,
-        target_box_id: String,
-    ) -> Result<crate::models::ImportResult, ZapError> {
+    pub async fn get_secrets_due_for_rotation(&self) -> Result<Vec<Secret>, ZapError> {
         if self.is_locked() {
             return Err(ZapError::SessionExpired);
         }
 
-        let target_box = self.storage.get_box(&target_box_id)?;
-        let master_key = self.get_master_key()?;
-        let result =
-            self.import_export
-                .import_env_to_box(env_content, &target_box_id, &master_key)?;
-
-        let _ = self.storage.log(
-            "Import_Env".to_string(),
-            format!(
-                "Imported {} env variables to box '{}'",
-                result.secrets_imported, target_box.name
-            ),
-            if result.has_errors() {
-                Some(format!("{} errors", result.errors.len()))
-            } else {
-                None
-            },
-        );
+        let now = chrono::Utc::now();
+        let mut due: Vec<Secret> = self
+            .storage
+            .list_all_secrets()?
+            .into_iter()
+            .filter(|secret| secret.is_rotation_due(now))
+            .collect();
+        due.sort_by_key(|secret| secret.rotation_due_at());
+        Ok(due)
+    }
 
-        Ok(result)
+    /// Due secrets not yet announced since unlock (each reminder shows once per unlock)
+    pub async fn take_rotation_reminders(&self) -> Result<Vec<Secret>, ZapError> {
+        let due = self.get_secrets_due_for_rotation().await?;
+        let mut notified = self.rotation_notified.lock().unwrap();
+        Ok(due
+            .into_iter()
+            .filter(|secret| notified.insert(secret.id.clone()))
+            .collect())
     }
 
-    // LOG OPERATIONS
+    // BREACH MONITORING
 
-    /// Get all logs (no password required)
-    pub async fn get_all_logs(&self) -> Result<Vec<LogEntry>, ZapError> {
+    /// Look up password-kind secrets in Have I Been Pwned when a check is due (or right away
+    /// with `force`). Returns the secrets newly found in a breach. As a job it reports each
+    /// lookup and can stop between any two of them.
+    pub async fn run_breach_check(
+        &self,
+        force: bool,
+        job: Option<&JobContext>,
+    ) -> Result<Vec<CompromisedSecret>, ZapError> {
         if self.is_locked() {
             return Err(ZapError::SessionExpired);
         }
 
-        // Auto-log that someone viewed logs
+        let settings = self.storage.load_settings()?.breach_check;
+        if !force {
+            if !settings.enabled {
+                return Ok(Vec::new());
+            }
+            let interval = chrono::Duration::hours(settings.interval_hours as i64);
+            if let Some(last) = self.storage.load_last_breach_check()? {
+                if chrono::Utc::now() - last < interval {
+                    return Ok(Vec::new());
+                }
+            }
+        }
+
+        // Recorded up front, so an unreachable API is retried next interval rather than every check
+        self.storage.save_last_breach_check(chrono::Utc::now())?;
+
+        let boxes: HashMap<String, Box> = self
+            .storage
+            .get_all_boxes()?
+            .into_iter()
+            .map(|box_item| (box_item.id.clone(), box_item))
+            .collect();
+        let candidates: Vec<Secret> = self
+            .storage
+            .list_all_secrets()?
+            .into_iter()
+            .filter(|secret| secret.value_encoding.is_utf8() && is_password_name(&secret.name))
+            .collect();
+
+        let mut box_keys: HashMap<String, [u8; 32]> = HashMap::new();
+        let mut newly_compromised = Vec::new();
+        for (checked, secret) in candidates.iter().cloned().enumerate() {
+            if let Some(job) = job {
+                job.check_cancelled()?;
+                job.progress(checked, candidates.len(), None);
+            }
+            let Some(box_item) = boxes.get(&secret.box_id) else {
+                continue;
+            };
+            // Each lookup waits on the network, the vault may have locked in the meantime
+            if self.is_locked() {
+                return Err(ZapError::SessionExpired);
+            }
+            let box_key = match box_keys.get(&box_item.id) {
+                Some(key) => *key,
+                None => {
+                    let key = self.box_key(box_item)?;
+                    box_keys.insert(box_item.id.clone(), key);
+                    key
+                }
+            };
+            let secret = self.storage.load_secret_value(secret)?;
+            let value = self.crypto.decrypt_secret(&secret, &box_key)?;
+
+            let pwned_count = match self.breach.pwned_count(&value).await {
+                Ok(count) => count,
+                Err(e) => {
+                    let _ = self.storage.log(
+                        LogAction::BreachCheckFailed,
+                        "Breach check stopped".to_string(),
+                        Some(e.to_string()),
+                    );
+                    return Err(e);
+                }
+            };
+
+            let previous = self.storage.get_secret_breach(&secret.id)?;
+            let breach = SecretBreach {
+                pwned_count,
+                checked_at: chrono::Utc::now(),
+            };
+            self.storage.save_secret_breach(&secret.id, &breach)?;
+
+            // Reported once, until the value changes and is found again
+            if breach.flags(&secret) && !previous.is_some_and(|previous| previous.flags(&secret)) {
+                let _ = self.storage.log(
+                    LogAction::SecretCompromised,
+                    format!(
+                        "Secret '{}' in box '{}' found in a known breach",
+                        secret.name, box_item.name
+                    ),
+                    Some(format!("seen {} times", pwned_count)),
+                );
+                newly_compromised.push(CompromisedSecret {
+                    box_name: box_item.name.clone(),
+                    item: secret,
+                    pwned_count,
+                    checked_at: breach.checked_at,
+                });
+            }
+        }
+
         let _ = self.storage.log(
-            "View_Logs".to_string(),
-            "Viewed audit logs".to_string(),
-            None,
+            LogAction::BreachCheck,
+            format!("Checked {} password(s) for breaches", candidates.len()),
+            Some(format!("{} newly compromised", newly_compromised.len())),
         );
 
-        self.storage.get_all_logs()
+        Ok(newly_compromised)
     }
 
-    /// Clear all logs
-    pub async fn clear_all_logs(&self, password: &str) -> Result<usize, ZapError> {
+    /// Secrets whose current value was found in a breach, most often seen first
+    pub async fn get_compromised_secrets(&self) -> Result<Vec<CompromisedSecret>, ZapError> {
         if self.is_locked() {
             return Err(ZapError::SessionExpired);
         }
 
-        self.verify_password(password)?;
+        let box_names: HashMap<String, String> = self
+            .storage
+            .get_all_boxes()?
+            .into_iter()
+            .map(|box_item| (box_item.id, box_item.name))
+            .collect();
+
+        let mut compromised = Vec::new();
+        for (secret_id, breach) in self.storage.list_secret_breaches()? {
+            if breach.pwned_count == 0 {
+                continue;
+            }
+            let Ok(secret) = self.storage.get_secret(&secret_id) else {
+                continue;
+            };
+            // A value changed since the check is no longer flagged
+            if !breach.flags(&secret) {
+                continue;
+            }
+            let Some(box_name) = box_names.get(&secret.box_id) else {
+                continue;
+            };
+            compromised.push(CompromisedSecret {
+                box_name: box_name.clone(),
+                item: secret,
+                pwned_count: breach.pwned_count,
+                checked_at: breach.checked_at,
+            });
+        }
+        compromised.sort_by_key(|compromised| Reverse(compromised.pwned_count));
 
-        let count = self.storage.clear_all_logs()?;
+        Ok(compromised)
+    }
 
-        // Note: clearing action is already logged in storage.clear_all_logs()
+    // FAVORITES
 
-        Ok(count)
+    /// Pin or unpin a secret. A local preference, so updated_at is left alone.
+    pub async fn set_secret_favorite(
+        &self,
+        secret_id: &str,
+        favorite: bool,
+    ) -> Result<Secret, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let mut secret = self.storage.get_secret(secret_id)?;
+        secret.favorite = favorite;
+        self.storage.update_secret(&secret)?;
+        Ok(secret)
     }
 
-    /// Export logs (no password required)
-    pub async fn export_logs(&self) -> Result<String, ZapError> {
+    /// Favorite secrets across all boxes, by name
+    pub async fn get_favorite_secrets(&self) -> Result<Vec<Secret>, ZapError> {
         if self.is_locked() {
             return Err(ZapError::SessionExpired);
         }
 
-        // Auto-log the export
-        let _ = self.storage.log(
-            "Export_Logs".to_string(),
-            "Exported audit logs".to_string(),
-            None,
-        );
+        let mut favorites: Vec<Secret> = self
+            .storage
+            .list_all_secrets()?
+            .into_iter()
+            .filter(|secret| secret.favorite)
+            .collect();
+        favorites.sort_by_cached_key(|secret| name_key(&secret.name));
+        Ok(favorites)
+    }
 
-        self.storage.export_logs()
+    /// Secrets most recently revealed or copied, across boxes, newest first
+    pub async fn get_recent_secrets(&self, limit: usize) -> Result<Vec<RecentSecret>, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let mut usage = self.storage.list_secret_usage()?;
+        usage.sort_by_key(|(_, used)| Reverse(used.last_used_at));
+
+        let box_names: HashMap<String, String> = self
+            .storage
+            .get_all_boxes()?
+            .into_iter()
+            .map(|box_item| (box_item.id, box_item.name))
+            .collect();
+
+        let mut recent = Vec::new();
+        for (secret_id, usage) in usage {
+            if recent.len() >= limit.min(MAX_RECENT_SECRETS) {
+                break;
+            }
+            // Usage is removed along with its secret, this only skips leftovers
+            let Ok(secret) = self.storage.get_secret(&secret_id) else {
+                continue;
+            };
+            let Some(box_name) = box_names.get(&secret.box_id) else {
+                continue;
+            };
+            recent.push(RecentSecret {
+                box_name: box_name.clone(),
+                item: secret,
+                last_used_at: usage.last_used_at,
+                use_count: usage.use_count,
+            });
+        }
+
+        Ok(recent)
     }
 
-    // STATISTICS & UTILITIES
+    // SEARCH OPERATIONS
 
-    pub async fn get_vault_stats(&self) -> Result<VaultStats, ZapError> {
+    pub async fn search_boxes_global(
+        &self,
+        query: String,
+        tags: Vec<String>,
+    ) -> Result<Vec<BoxSearchResult>, ZapError> {
         if self.is_locked() {
             return Err(ZapError::SessionExpired);
         }
 
-        self.storage.get_vault_stats()
+        self.storage.search_boxes_global(&query, &tags)
     }
 
-    pub fn get_settings(&self) -> Result<Settings, ZapError> {
-        self.storage.load_settings()
+    pub async fn search_secrets_in_box(
+        &self,
+        box_id: String,
+        query: String,
+    ) -> Result<Vec<SecretSearchResult>, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        self.storage.search_secrets_in_box(&box_id, &query)
     }
 
-    pub async fn update_settings(&self, settings: Settings) -> Result<(), ZapError> {
-        self.storage.save_settings(&settings)?;
+    /// Search decrypted secret values for `query` (exact substring). Values are decrypted one
+    /// at a time and dropped after the check - only the matching secrets' names come back.
+    pub async fn deep_search(
+        &self,
+        password: &str,
+        query: String,
+    ) -> Result<Vec<DeepSearchMatch>, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
 
-        // Update auth timeout in memory AND save to AuthConfig
-        {
-            let auth = self.auth.lock().unwrap();
-            auth.set_timeout_minutes(settings.password_timeout_minutes as u8)?;
+        self.verify_password(password)?;
+        // Matches leak values one probe at a time, so treat searching like revealing
+        self.require_plaintext_reveal()?;
+        if query.is_empty() {
+            return Err(ZapError::ValidationError(
+                "Search query cannot be empty".to_string(),
+            ));
+        }
 
-            if let Some(mut config) = auth.get_config() {
-                config.session_timeout_minutes = settings.password_timeout_minutes as u8;
-                drop(auth);
-                self.storage.save_auth_config(&config)?;
+        let mut matches = Vec::new();
+        let mut scanned = 0;
+        for box_item in self.storage.get_all_boxes()? {
+            let box_key = self.box_key(&box_item)?;
+
+            for secret in self.storage.get_secrets_by_box_id(&box_item.id)? {
+                scanned += 1;
+                // Bypass the decrypted cache so a search never fills it with the whole vault
+                let value = self.crypto.decrypt_secret(&secret, &box_key)?;
+                if value.contains(&query) {
+                    matches.push(DeepSearchMatch {
+                        secret_id: secret.id,
+                        secret_name: secret.name,
+                        box_id: box_item.id.clone(),
+                        box_name: box_item.name.clone(),
+                    });
+                }
             }
         }
 
         let _ = self.storage.log(
-            "Update_Settings".to_string(),
-            "Settings updated".to_string(),
-            Some(format!(
-                "timeout: {} minutes",
-                settings.password_timeout_minutes
-            )),
+            LogAction::DeepSearch,
+            format!(
+                "Searched secret values: {} of {} secrets matched",
+                matches.len(),
+                scanned
+            ),
+            None,
         );
 
-        Ok(())
+        Ok(matches)
     }
 
-    pub fn get_session_info(&self) -> SessionInfo {
-        SessionInfo {
-            is_locked: self.is_locked(),
-            time_left_seconds: self.get_session_time_left(),
+    /// Compare two boxes by secret name. Values (and environment variants) are decrypted only
+    /// to compare them - the result holds names, never values.
+    pub async fn diff_boxes(&self, box_a_id: &str, box_b_id: &str) -> Result<BoxDiff, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
         }
-    }
+        self.require_recent_verification()?;
 
-    // PRIVATE HELPERS
+        if box_a_id == box_b_id {
+            return Err(ZapError::ValidationError(
+                "Choose two different boxes to compare".to_string(),
+            ));
+        }
 
-    pub fn get_master_key(&self) -> Result<[u8; 32], ZapError> {
-        let auth = self.auth.lock().unwrap();
-        auth.get_master_key().ok_or(ZapError::SessionExpired)
-    }
+        let box_a = self.storage.get_box(box_a_id)?;
+        let box_b = self.storage.get_box(box_b_id)?;
+        let key_a = self.box_key(&box_a)?;
+        let key_b = self.box_key(&box_b)?;
+
+        let mut secrets_b: HashMap<String, Secret> = self
+            .storage
+            .get_secrets_by_box_id(box_b_id)?
+            .into_iter()
+            .map(|secret| (name_key(&secret.name), secret))
+            .collect();
+
+        let mut diff = BoxDiff {
+            box_a_id: box_a.id.clone(),
+            box_a_name: box_a.name.clone(),
+            box_b_id: box_b.id.clone(),
+            box_b_name: box_b.name.clone(),
+            only_in_a: Vec::new(),
+            only_in_b: Vec::new(),
+            different_value: Vec::new(),
+            same_value_count: 0,
+        };
 
-    pub fn get_session_time_left(&self) -> u32 {
-        let auth = self.auth.lock().unwrap();
-        auth.get_session_time_left()
-    }
+        for secret_a in self.storage.get_secrets_by_box_id(box_a_id)? {
+            match secrets_b.remove(&name_key(&secret_a.name)) {
+                Some(secret_b) => {
+                    if self.same_values(&secret_a, &key_a, &secret_b, &key_b)? {
+                        diff.same_value_count += 1;
+                    } else {
+                        diff.different_value.push(secret_a.name);
+                    }
+                }
+                None => diff.only_in_a.push(secret_a.name),
+            }
+        }
+        diff.only_in_b = secrets_b.into_values().map(|secret| secret.name).collect();
 
-    fn start_session_timer(&self) {
-        let mut timer_guard = self.session_timer.lock().unwrap();
+        diff.only_in_a.sort();
+        diff.only_in_b.sort();
+        diff.different_value.sort();
 
-        if let Some(handle) = timer_guard.take() {
-            handle.abort();
-        }
+        let _ = self.storage.log(
+            LogAction::DiffBoxes,
+            format!("Compared box '{}' with box '{}'", box_a.name, box_b.name),
+            Some(format!(
+                "{} only in '{}', {} only in '{}', {} different",
+                diff.only_in_a.len(),
+                box_a.name,
+                diff.only_in_b.len(),
+                box_b.name,
+                diff.different_value.len()
+            )),
+        );
 
-        let auth_clone = Arc::clone(&self.auth);
-        let storage_clone = Arc::clone(&self.storage);
+        Ok(diff)
+    }
+
+    // Same encoding, same value and the same value in every environment.
+    // Bypasses the decrypted cache like deep_search.
+    fn same_values(
+        &self,
+        secret_a: &Secret,
+        key_a: &[u8; 32],
+        secret_b: &Secret,
+        key_b: &[u8; 32],
+    ) -> Result<bool, ZapError> {
+        if secret_a.value_encoding != secret_b.value_encoding
+            || secret_a.environments() != secret_b.environments()
+        {
+            return Ok(false);
+        }
+
+        let environments = secret_a.variants.keys().map(|environment| Some(environment.as_str()));
+        for environment in std::iter::once(None).chain(environments) {
+            let value_a = self.crypto.decrypt_secret_for(secret_a, environment, key_a)?;
+            let value_b = self.crypto.decrypt_secret_for(secret_b, environment, key_b)?;
+            if value_a != value_b {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    // IMPORT/EXPORT
+
+    pub async fn export_vault(&self) -> Result<String, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+        self.require_recent_verification()?;
+
+        self.run_export_vault(None)
+    }
+
+    /// Public key that signs this vault's exports, for teammates to add to their trusted keys
+    pub fn get_export_signing_key(&self) -> Result<String, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let signing_key = self.export_signing_key(&self.get_master_key()?)?;
+        Ok(self.crypto.signing_public_key(&signing_key))
+    }
+
+    pub async fn export_box_as_env(
+        &self,
+        box_id: String,
+        prefix: Option<String>,
+        environment: Option<String>,
+    ) -> Result<String, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+        self.require_plaintext_reveal()?;
+
+        let box_item = self.storage.get_box(&box_id)?;
+        let master_key = self.get_master_key()?;
+        let environment = self.environment_or_default(environment)?;
+        let result = self.import_export.export_box_as_env(
+            &box_id,
+            &master_key,
+            prefix.as_deref(),
+            |secret, key| self.decrypt_variant_cached(secret, environment.as_deref(), key),
+        )?;
+
+        let _ = self.storage.log(
+            LogAction::ExportBox,
+            format!(
+                "Exported box '{}' as .env ({} secrets{})",
+                box_item.name,
+                box_item.secrets_count,
+                environment_note(environment.as_deref())
+            ),
+            prefix,
+        );
+
+        Ok(result)
+    }
+
+    pub async fn export_box_as_config(
+        &self,
+        box_id: String,
+        format: ConfigFormat,
+        root_key: Option<String>,
+        environment: Option<String>,
+    ) -> Result<String, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+        self.require_plaintext_reveal()?;
+
+        let box_item = self.storage.get_box(&box_id)?;
+        let master_key = self.get_master_key()?;
+        let environment = self.environment_or_default(environment)?;
+        let result = self.import_export.export_box_as_config(
+            &box_id,
+            &master_key,
+            format,
+            root_key.as_deref(),
+            |secret, key| self.decrypt_variant_cached(secret, environment.as_deref(), key),
+        )?;
+
+        let _ = self.storage.log(
+            LogAction::ExportBox,
+            format!(
+                "Exported box '{}' as {:?} ({} secrets{})",
+                box_item.name,
+                format,
+                box_item.secrets_count,
+                environment_note(environment.as_deref())
+            ),
+            root_key,
+        );
+
+        Ok(result)
+    }
+
+    pub async fn export_box_as_tfvars(
+        &self,
+        box_id: String,
+        format: TfvarsFormat,
+        environment: Option<String>,
+    ) -> Result<String, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let box_item = self.storage.get_box(&box_id)?;
+        let master_key = self.get_master_key()?;
+        let environment = self.environment_or_default(environment)?;
+        let result = self.import_export.export_box_as_tfvars(
+            &box_id,
+            &master_key,
+            format,
+            |secret, key| self.decrypt_variant_cached(secret, environment.as_deref(), key),
+        )?;
+
+        let _ = self.storage.log(
+            LogAction::ExportBox,
+            format!(
+                "Exported box '{}' as {:?} tfvars ({} secrets{})",
+                box_item.name,
+                format,
+                box_item.secrets_count,
+                environment_note(environment.as_deref())
+            ),
+            None,
+        );
+
+        Ok(result)
+    }
+
+    /// JSON for the external-secrets webhook provider, see ExternalSecretsExport
+    pub async fn export_box_for_external_secrets(
+        &self,
+        box_id: String,
+        property_paths: HashMap<String, String>,
+        environment: Option<String>,
+    ) -> Result<ExternalSecretsExport, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let box_item = self.storage.get_box(&box_id)?;
+        let master_key = self.get_master_key()?;
+        let environment = self.environment_or_default(environment)?;
+        let result = self.import_export.export_box_for_external_secrets(
+            &box_id,
+            &master_key,
+            &property_paths,
+            |secret, key| self.decrypt_variant_cached(secret, environment.as_deref(), key),
+        )?;
+
+        let _ = self.storage.log(
+            LogAction::ExportBox,
+            format!(
+                "Exported box '{}' for external-secrets ({} secrets{})",
+                box_item.name,
+                box_item.secrets_count,
+                environment_note(environment.as_deref())
+            ),
+            Some(result.remote_key.clone()),
+        );
+
+        Ok(result)
+    }
+
+    /// Push a box's secrets to GitHub Actions with the access token stored in `token_secret_id`.
+    /// Each secret is reported on its own, a failed one doesn't stop the rest.
+    pub async fn push_box_to_github(
+        &self,
+        box_id: String,
+        repository: String,
+        github_environment: Option<String>,
+        token_secret_id: String,
+        environment: Option<String>,
+    ) -> Result<GithubPushReport, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+        self.require_recent_verification()?;
+
+        let target = ActionsTarget::parse(&repository, github_environment)?;
+        let box_item = self.storage.get_box(&box_id)?;
+        let box_key = self.box_key(&box_item)?;
+        let environment = self.environment_or_default(environment)?;
+
+        let token = self.access_token(&token_secret_id)?;
+        let token = token.as_str();
+
+        // The token itself is never pushed, even when it lives in the same box
+        let mut pending = Vec::new();
+        for secret in self.storage.get_secrets_by_box_id(&box_id)? {
+            if secret.id == token_secret_id {
+                continue;
+            }
+            let value = self.decrypt_variant_cached(&secret, environment.as_deref(), &box_key)?;
+            pending.push((secret.name.clone(), secret.to_env_var_name(None), value));
+        }
+        if pending.is_empty() {
+            return Err(ZapError::StorageError(
+                "Box has no secrets to export".to_string(),
+            ));
+        }
+
+        let public_key = self.github.public_key(token, &target).await?;
+        let mut results = Vec::with_capacity(pending.len());
+        for (secret_name, env_var_name, value) in pending {
+            // Each upload waits on the network, the vault may have locked in the meantime
+            if self.is_locked() {
+                return Err(ZapError::SessionExpired);
+            }
+            let (github_name, outcome) = match actions_secret_name(&env_var_name) {
+                Ok(name) => {
+                    let outcome = self
+                        .github
+                        .put_secret(token, &target, &public_key, &name, &value)
+                        .await;
+                    (name, outcome)
+                }
+                Err(e) => (env_var_name, Err(e)),
+            };
+            let (status, error) = match outcome {
+                Ok(status) => (status, None),
+                Err(e) => (GithubSecretStatus::Failed, Some(e.to_string())),
+            };
+            results.push(GithubSecretResult {
+                secret_name,
+                github_name,
+                status,
+                error,
+            });
+        }
+
+        let report = GithubPushReport {
+            repository: target.repository(),
+            github_environment: target.environment,
+            results,
+        };
+        let _ = self.storage.log(
+            LogAction::ExportGithubSecrets,
+            format!(
+                "Pushed box '{}' to GitHub {} ({} of {} secrets{})",
+                box_item.name,
+                report.repository,
+                report.results.len() - report.failed_count(),
+                report.results.len(),
+                environment_note(environment.as_deref())
+            ),
+            report
+                .github_environment
+                .as_ref()
+                .map(|name| format!("GitHub environment: {}", name)),
+        );
+
+        Ok(report)
+    }
+
+    /// What syncing a box to GitLab CI/CD variables would create or update, changing nothing
+    pub async fn preview_gitlab_sync(
+        &self,
+        box_id: String,
+        target: GitlabTarget,
+        token_secret_id: String,
+        environment: Option<String>,
+    ) -> Result<GitlabSyncReport, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let token = self.access_token(&token_secret_id)?;
+        let (report, _) = self
+            .plan_gitlab_sync(&box_id, &target, &token, &token_secret_id, environment)
+            .await?;
+        Ok(report)
+    }
+
+    /// Create and update the CI/CD variables a preview lists; variables only GitLab has stay
+    pub async fn sync_box_to_gitlab(
+        &self,
+        box_id: String,
+        target: GitlabTarget,
+        token_secret_id: String,
+        environment: Option<String>,
+    ) -> Result<GitlabSyncReport, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let box_item = self.storage.get_box(&box_id)?;
+        let token = self.access_token(&token_secret_id)?;
+        let (mut report, values) = self
+            .plan_gitlab_sync(
+                &box_id,
+                &target,
+                &token,
+                &token_secret_id,
+                environment.clone(),
+            )
+            .await?;
+
+        for (variable, value) in report.variables.iter_mut().zip(values) {
+            if variable.change == GitlabChange::Unchanged || variable.error.is_some() {
+                continue;
+            }
+            // Each update waits on the network, the vault may have locked in the meantime
+            if self.is_locked() {
+                return Err(ZapError::SessionExpired);
+            }
+            let gitlab_variable = GitlabVariable {
+                key: variable.key.clone(),
+                value,
+                masked: variable.masked,
+                protected: variable.protected,
+                raw: true,
+                environment_scope: report.environment_scope.clone(),
+            };
+            let exists = variable.change == GitlabChange::Update;
+            if let Err(e) = self
+                .gitlab
+                .save_variable(&token, &target, &gitlab_variable, exists)
+                .await
+            {
+                variable.error = Some(e.to_string());
+            }
+        }
+        report.applied = true;
+
+        let changed = report
+            .variables
+            .iter()
+            .filter(|variable| variable.change != GitlabChange::Unchanged)
+            .count();
+        let failed = report
+            .variables
+            .iter()
+            .filter(|variable| variable.error.is_some())
+            .count();
+        let _ = self.storage.log(
+            LogAction::ExportGitlabVariables,
+            format!(
+                "Synced box '{}' to GitLab {} ({} changed, {} failed{})",
+                box_item.name,
+                report.target,
+                changed,
+                failed,
+                environment_note(environment.as_deref())
+            ),
+            Some(format!("environment scope: {}", report.environment_scope)),
+        );
+
+        Ok(report)
+    }
+
+    // The report plus each listed variable's value, in the same order
+    async fn plan_gitlab_sync(
+        &self,
+        box_id: &str,
+        target: &GitlabTarget,
+        token: &str,
+        token_secret_id: &str,
+        environment: Option<String>,
+    ) -> Result<(GitlabSyncReport, Vec<String>), ZapError> {
+        let box_item = self.storage.get_box(box_id)?;
+        let box_key = self.box_key(&box_item)?;
+        let environment = self.environment_or_default(environment)?;
+
+        let mut remote: HashMap<String, GitlabVariable> = self
+            .gitlab
+            .list_variables(token, target)
+            .await?
+            .into_iter()
+            .map(|variable| (variable.key.clone(), variable))
+            .collect();
+
+        let mut variables = Vec::new();
+        let mut values = Vec::new();
+        for secret in self.storage.get_secrets_by_box_id(box_id)? {
+            if secret.id == token_secret_id {
+                continue;
+            }
+            let value = self.decrypt_variant_cached(&secret, environment.as_deref(), &box_key)?;
+            let env_var_name = secret.to_env_var_name(None);
+            let (key, error) = match gitlab_service::variable_key(&env_var_name) {
+                Ok(key) => (key, None),
+                Err(e) => (env_var_name.to_uppercase(), Some(e.to_string())),
+            };
+
+            let note = gitlab_service::unmaskable_reason(&value);
+            let masked = note.is_none();
+            let protected = box_item.is_prod();
+            let (change, value_changed) = match remote.remove(&key) {
+                None => (GitlabChange::Create, true),
+                Some(existing) => {
+                    let value_changed = existing.value != value;
+                    if value_changed || existing.masked != masked || existing.protected != protected
+                    {
+                        (GitlabChange::Update, value_changed)
+                    } else {
+                        (GitlabChange::Unchanged, false)
+                    }
+                }
+            };
+
+            variables.push(GitlabVariableChange {
+                secret_name: secret.name,
+                key,
+                change,
+                masked,
+                protected,
+                value_changed,
+                note: note.map(str::to_string),
+                error,
+            });
+            values.push(value);
+        }
+        if variables.is_empty() {
+            return Err(ZapError::StorageError(
+                "Box has no secrets to export".to_string(),
+            ));
+        }
+
+        let mut remote_only: Vec<String> = remote.into_keys().collect();
+        remote_only.sort();
+        let report = GitlabSyncReport {
+            target: gitlab_service::describe_target(target),
+            environment_scope: target.environment_scope().to_string(),
+            applied: false,
+            variables,
+            remote_only,
+        };
+        Ok((report, values))
+    }
+
+    /// Names in the box and in the mapped store, and which side differs
+    pub async fn diff_sync_mapping(&self, mapping_id: &str) -> Result<SyncDiff, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let mapping = self.sync_mapping(mapping_id)?;
+        let (_, plan) = self
+            .plan_sync(
+                &mapping.box_id,
+                &mapping.provider,
+                mapping.prefix(),
+                &mapping.credentials_secret_id,
+            )
+            .await?;
+        Ok(SyncDiff {
+            mapping_id: Some(mapping.id),
+            provider: mapping.provider.label(),
+            entries: plan.into_iter().map(|planned| planned.entry).collect(),
+        })
+    }
+
+    /// Write the box's values to the mapped store wherever the store lacks or differs from them
+    pub async fn push_sync_mapping(&self, mapping_id: &str) -> Result<SyncResult, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let mapping = self.sync_mapping(mapping_id)?;
+        let box_item = self.storage.get_box(&mapping.box_id)?;
+        let (provider, plan) = self
+            .plan_sync(
+                &mapping.box_id,
+                &mapping.provider,
+                mapping.prefix(),
+                &mapping.credentials_secret_id,
+            )
+            .await?;
+
+        let mut result = SyncResult {
+            mapping_id: Some(mapping.id.clone()),
+            changed: Vec::new(),
+            errors: Vec::new(),
+        };
+        for planned in plan {
+            let Some(value) = planned.local_value else {
+                continue;
+            };
+            if planned.entry.state == SyncState::Same {
+                continue;
+            }
+            // Each write waits on the network, the vault may have locked in the meantime
+            if self.is_locked() {
+                return Err(ZapError::SessionExpired);
+            }
+            match provider.put(&planned.entry.remote_name, &value).await {
+                Ok(()) => result.changed.push(planned.entry.remote_name),
+                Err(e) => result
+                    .errors
+                    .push(format!("{}: {}", planned.entry.remote_name, e)),
+            }
+        }
+
+        let _ = self.storage.log(
+            LogAction::ExportToProvider,
+            format!(
+                "Pushed {} secrets of box '{}' to {}",
+                result.changed.len(),
+                box_item.name,
+                mapping.provider.label()
+            ),
+            (!result.errors.is_empty()).then(|| format!("{} errors", result.errors.len())),
+        );
+
+        Ok(result)
+    }
+
+    /// Copy the mapped store's values into the box: missing secrets are created, differing
+    /// ones overwritten
+    pub async fn pull_sync_mapping(&self, mapping_id: &str) -> Result<SyncResult, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let mapping = self.sync_mapping(mapping_id)?;
+        let mut result = self
+            .pull_into_box(
+                &mapping.box_id,
+                &mapping.provider,
+                mapping.prefix(),
+                &mapping.credentials_secret_id,
+                None,
+            )
+            .await?;
+        result.mapping_id = Some(mapping.id);
+        Ok(result)
+    }
+
+    /// A store's secrets under `path` (a name prefix) against a box, without a saved mapping.
+    /// Run before pull_from_provider to choose what to import.
+    pub async fn preview_provider_pull(
+        &self,
+        provider: SyncProviderConfig,
+        credentials_secret_id: String,
+        path: Option<String>,
+        target_box_id: String,
+    ) -> Result<SyncDiff, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let (_, plan) = self
+            .plan_sync(
+                &target_box_id,
+                &provider,
+                path.as_deref().unwrap_or_default(),
+                &credentials_secret_id,
+            )
+            .await?;
+        Ok(SyncDiff {
+            mapping_id: None,
+            provider: provider.label(),
+            entries: plan.into_iter().map(|planned| planned.entry).collect(),
+        })
+    }
+
+    /// Import the `selected` store names (see preview_provider_pull) into the box: missing
+    /// secrets are created, differing ones overwritten
+    pub async fn pull_from_provider(
+        &self,
+        provider: SyncProviderConfig,
+        credentials_secret_id: String,
+        path: Option<String>,
+        target_box_id: String,
+        selected: Vec<String>,
+    ) -> Result<SyncResult, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let selected: HashSet<String> = selected.into_iter().collect();
+        self.pull_into_box(
+            &target_box_id,
+            &provider,
+            path.as_deref().unwrap_or_default(),
+            &credentials_secret_id,
+            Some(&selected),
+        )
+        .await
+    }
+
+    fn sync_mapping(&self, mapping_id: &str) -> Result<SyncMapping, ZapError> {
+        self.storage
+            .load_settings()?
+            .sync_mappings
+            .into_iter()
+            .find(|mapping| mapping.id == mapping_id)
+            .ok_or_else(|| {
+                ZapError::ValidationError(format!("Sync mapping '{}' not found", mapping_id))
+            })
+    }
+
+    // Pull every missing or differing store name, or only the `selected` ones
+    async fn pull_into_box(
+        &self,
+        box_id: &str,
+        provider: &SyncProviderConfig,
+        prefix: &str,
+        credentials_secret_id: &str,
+        selected: Option<&HashSet<String>>,
+    ) -> Result<SyncResult, ZapError> {
+        let box_item = self.storage.get_box(box_id)?;
+        box_item.ensure_writable()?;
+        let (_, plan) = self
+            .plan_sync(box_id, provider, prefix, credentials_secret_id)
+            .await?;
+
+        if let Some(selected) = selected {
+            let remote_names: HashSet<&str> = plan
+                .iter()
+                .filter(|planned| planned.remote_value.is_some())
+                .map(|planned| planned.entry.remote_name.as_str())
+                .collect();
+            if let Some(unknown) = selected
+                .iter()
+                .find(|name| !remote_names.contains(name.as_str()))
+            {
+                return Err(ZapError::ValidationError(format!(
+                    "{} has no secret named '{}'",
+                    provider.label(),
+                    unknown
+                )));
+            }
+        }
+
+        let mut result = SyncResult {
+            mapping_id: None,
+            changed: Vec::new(),
+            errors: Vec::new(),
+        };
+        for planned in plan {
+            if selected.is_some_and(|selected| !selected.contains(&planned.entry.remote_name)) {
+                continue;
+            }
+            let Some(value) = planned.remote_value else {
+                continue;
+            };
+            let (secret_name, saved) = match (planned.entry.state, planned.secret_id) {
+                (SyncState::OnlyRemote, _) => {
+                    let name = planned.local_name;
+                    let saved = self
+                        .create_secret(
+                            box_id.to_string(),
+                            name.clone(),
+                            value,
+                            ValueEncoding::default(),
+                            MultilineFormat::default(),
+                        )
+                        .await;
+                    (name, saved)
+                }
+                (SyncState::Different, Some(secret_id)) => {
+                    let saved = self
+                        .update_secret(&secret_id, None, Some(value), None, None)
+                        .await;
+                    (planned.entry.secret_name.unwrap_or_default(), saved)
+                }
+                _ => continue,
+            };
+            match saved {
+                Ok(_) => result.changed.push(secret_name),
+                Err(e) => result.errors.push(format!("{}: {}", secret_name, e)),
+            }
+        }
+
+        let _ = self.storage.log(
+            LogAction::ImportFromProvider,
+            format!(
+                "Pulled {} secrets into box '{}' from {}",
+                result.changed.len(),
+                box_item.name,
+                provider.label()
+            ),
+            (!result.errors.is_empty()).then(|| format!("{} errors", result.errors.len())),
+        );
+
+        Ok(result)
+    }
+
+    // Signs in to the store and pairs each box secret with the store name it maps to.
+    // Store names outside `prefix` are left out.
+    async fn plan_sync(
+        &self,
+        box_id: &str,
+        provider_config: &SyncProviderConfig,
+        prefix: &str,
+        credentials_secret_id: &str,
+    ) -> Result<(std::boxed::Box<dyn SyncProvider>, Vec<PlannedSync>), ZapError> {
+        let box_item = self.storage.get_box(box_id)?;
+        let box_key = self.box_key(&box_item)?;
+        let credential = self.access_token(credentials_secret_id)?;
+        let provider = sync::connect(provider_config, &credential).await?;
+
+        let mut remote: BTreeMap<String, String> = provider
+            .list()
+            .await?
+            .into_iter()
+            .filter(|(name, _)| name.starts_with(prefix))
+            .collect();
+
+        let mut plan = Vec::new();
+        for secret in self.storage.get_secrets_by_box_id(box_id)? {
+            if secret.id == credentials_secret_id {
+                continue;
+            }
+            let env_var_name = secret.to_env_var_name(None);
+            let remote_name = format!("{}{}", prefix, provider.remote_name(&env_var_name));
+            let local_value = self.decrypt_secret_cached(&secret, &box_key)?;
+            let remote_value = remote.remove(&remote_name);
+            let state = match &remote_value {
+                None => SyncState::OnlyLocal,
+                Some(value) if *value == local_value => SyncState::Same,
+                Some(_) => SyncState::Different,
+            };
+            plan.push(PlannedSync {
+                entry: SyncDiffEntry {
+                    remote_name,
+                    secret_name: Some(secret.name.clone()),
+                    state,
+                },
+                secret_id: Some(secret.id),
+                local_name: env_var_name,
+                local_value: Some(local_value),
+                remote_value,
+            });
+        }
+        for (remote_name, value) in remote {
+            let local_name = provider.local_name(&remote_name[prefix.len()..]);
+            plan.push(PlannedSync {
+                entry: SyncDiffEntry {
+                    remote_name,
+                    secret_name: None,
+                    state: SyncState::OnlyRemote,
+                },
+                secret_id: None,
+                local_name,
+                local_value: None,
+                remote_value: Some(value),
+            });
+        }
+        plan.sort_by(|a, b| a.entry.remote_name.cmp(&b.entry.remote_name));
+
+        Ok((provider, plan))
+    }
+
+    /// Two-way sync of the vault with a replica (Settings: replicas). Records changed on both
+    /// sides are left alone and reported as conflicts; resolve them and sync again.
+    pub async fn sync_replica(&self, replica_id: &str) -> Result<ReplicaSyncReport, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let replica = self.replica(replica_id)?;
+        let passphrase = self.access_token(&replica.key_secret_id)?;
+        let mut skipped = HashSet::from([replica.key_secret_id.clone()]);
+        let credential = match &replica.target {
+            ReplicaTarget::Provider {
+                credentials_secret_id,
+                ..
+            } => {
+                skipped.insert(credentials_secret_id.clone());
+                Some(self.access_token(credentials_secret_id)?)
+            }
+            ReplicaTarget::WebDav {
+                password_secret_id, ..
+            } => {
+                skipped.insert(password_secret_id.clone());
+                Some(self.access_token(password_secret_id)?)
+            }
+            _ => None,
+        };
+
+        let store = replica::open_store(&replica.target, credential.as_deref()).await?;
+        let (mut remote, replica_key) = match store.load().await? {
+            Some(sealed) => {
+                let (mut snapshot, key) = replica::open_snapshot(&sealed, &passphrase)?;
+                for change in store.load_changes().await? {
+                    let change = replica::open_change(&change, &key)?;
+                    snapshot.records.extend(change.records);
+                }
+                (snapshot, key)
+            }
+            None => (
+                ReplicaSnapshot::default(),
+                ReplicaKey::generate(&passphrase)?,
+            ),
+        };
+        let local = self.replica_records(&skipped)?;
+        let bases = self.storage.replica_bases(&replica.id)?;
+        let resolutions: HashMap<String, ConflictResolution> = self
+            .storage
+            .list_sync_conflicts(&replica.id)?
+            .into_iter()
+            .filter_map(|conflict| Some((conflict.record_key, conflict.resolution?)))
+            .collect();
+
+        let mut report = ReplicaSyncReport {
+            replica_id: replica.id.clone(),
+            pushed: Vec::new(),
+            pulled: Vec::new(),
+            conflicts: Vec::new(),
+            errors: Vec::new(),
+        };
+        let mut agreed = Vec::new();
+        let mut pushed = Vec::new();
+        let plan = engine::reconcile(&local, &remote.records, &bases, &resolutions);
+        for (record_key, step) in plan {
+            let ours = local.get(&record_key);
+            let theirs = remote.records.get(&record_key);
+            match (step, ours, theirs) {
+                (SyncStep::Push, Some(ours), _) => pushed.push((record_key, ours.clone())),
+                (SyncStep::Pull, _, Some(theirs)) => {
+                    // Each write can wait on a box lock, the vault may have locked in the meantime
+                    if self.is_locked() {
+                        return Err(ZapError::SessionExpired);
+                    }
+                    let name = replica_record_name(ours, theirs);
+                    match self.apply_replica_record(&record_key, theirs.content.as_ref()) {
+                        Ok(()) => {
+                            self.storage
+                                .adopt_record_version(&record_key, &theirs.version)?;
+                            agreed.push((record_key, theirs.version.clone()));
+                            report.pulled.push(name);
+                        }
+                        Err(e) => report.errors.push(format!("{}: {}", name, e)),
+                    }
+                }
+                (SyncStep::Settle, ours, Some(theirs)) => {
+                    if ours.is_some() {
+                        self.storage
+                            .adopt_record_version(&record_key, &theirs.version)?;
+                    }
+                    agreed.push((record_key, theirs.version.clone()));
+                }
+                (SyncStep::Conflict, Some(ours), Some(theirs)) => {
+                    report.conflicts.push(SyncConflict {
+                        name: replica_record_name(Some(ours), theirs),
+                        record_key,
+                        local_deleted: ours.content.is_none(),
+                        remote_deleted: theirs.content.is_none(),
+                        local_updated_at: ours.version.updated_at,
+                        remote_updated_at: theirs.version.updated_at,
+                        resolution: None,
+                    })
+                }
+                _ => {}
+            }
+        }
+
+        let latest = remote.records.values().map(|record| record.version.lamport);
+        if let Some(latest) = latest.max() {
+            self.storage.observe_lamport(latest)?;
+        }
+        if !pushed.is_empty() {
+            let mut change = ReplicaSnapshot::default();
+            for (record_key, record) in &pushed {
+                report.pushed.push(replica_record_name(None, record));
+                change.records.insert(record_key.clone(), record.clone());
+            }
+            let change = replica::seal_snapshot(&change, &replica_key)?;
+            if !store.store_change(&change).await? {
+                remote.records.extend(pushed.iter().cloned());
+                store
+                    .store(&replica::seal_snapshot(&remote, &replica_key)?)
+                    .await?;
+            }
+            agreed.extend(
+                pushed
+                    .into_iter()
+                    .map(|(record_key, record)| (record_key, record.version)),
+            );
+        }
+        self.storage.save_replica_bases(&replica.id, &agreed)?;
+        self.storage
+            .save_sync_conflicts(&replica.id, &report.conflicts)?;
+
+        let _ = self.storage.log(
+            LogAction::SyncReplica,
+            format!(
+                "Synced with {}: {} pushed, {} pulled",
+                replica.label(),
+                report.pushed.len(),
+                report.pulled.len()
+            ),
+            (!report.conflicts.is_empty() || !report.errors.is_empty()).then(|| {
+                format!(
+                    "{} conflicts, {} errors",
+                    report.conflicts.len(),
+                    report.errors.len()
+                )
+            }),
+        );
+
+        Ok(report)
+    }
+
+    /// Conflicts the last sync with a replica left open
+    pub async fn list_sync_conflicts(
+        &self,
+        replica_id: &str,
+    ) -> Result<Vec<SyncConflict>, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        self.storage.list_sync_conflicts(replica_id)
+    }
+
+    /// Pick the side that wins a conflict, applied by the next sync_replica
+    pub async fn resolve_sync_conflict(
+        &self,
+        replica_id: &str,
+        record_key: &str,
+        resolution: ConflictResolution,
+    ) -> Result<Vec<SyncConflict>, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let mut conflicts = self.storage.list_sync_conflicts(replica_id)?;
+        let conflict = conflicts
+            .iter_mut()
+            .find(|conflict| conflict.record_key == record_key)
+            .ok_or_else(|| {
+                ZapError::ValidationError(format!("No open conflict for '{}'", record_key))
+            })?;
+        conflict.resolution = Some(resolution);
+        self.storage.save_sync_conflicts(replica_id, &conflicts)?;
+        Ok(conflicts)
+    }
+
+    fn replica(&self, replica_id: &str) -> Result<ReplicaConfig, ZapError> {
+        self.storage
+            .load_settings()?
+            .replicas
+            .into_iter()
+            .find(|replica| replica.id == replica_id)
+            .ok_or_else(|| ZapError::ValidationError(format!("Replica '{}' not found", replica_id)))
+    }
+
+    // The vault as replica records, with tombstones for deleted ones. Shared boxes belong to
+    // their owner and are left out, like the `skipped` secrets (the replica's own credentials).
+    fn replica_records(
+        &self,
+        skipped: &HashSet<String>,
+    ) -> Result<BTreeMap<String, ReplicaRecord>, ZapError> {
+        let versions = self.storage.record_versions()?;
+        let device_id = self.storage.device_id()?;
+        // Records written before the journal existed
+        let version_of = |record_key: &str, updated_at| {
+            versions
+                .get(record_key)
+                .cloned()
+                .unwrap_or_else(|| RecordVersion {
+                    lamport: 0,
+                    device_id: device_id.clone(),
+                    updated_at,
+                    deleted: false,
+                })
+        };
+
+        let mut records = BTreeMap::new();
+        let mut box_keys = HashMap::new();
+        for box_item in self.storage.get_all_boxes()? {
+            if box_item.read_only {
+                continue;
+            }
+            let record_key = format!("box:{}", box_item.id);
+            box_keys.insert(box_item.id.clone(), self.box_key(&box_item)?);
+            records.insert(
+                record_key.clone(),
+                ReplicaRecord {
+                    version: version_of(&record_key, box_item.updated_at),
+                    content: Some(RecordContent::Box {
+                        name: box_item.name,
+                        description: box_item.description,
+                        tags: box_item.tags,
+                    }),
+                },
+            );
+        }
+
+        for secret in self.storage.get_all_secrets()? {
+            if skipped.contains(&secret.id) {
+                continue;
+            }
+            let record_key = format!("secret:{}", secret.id);
+            let Some(box_key) = box_keys.get(&secret.box_id) else {
+                // Deleting a box keeps its secrets for undo, the replica loses them with it
+                let box_version = versions.get(&format!("box:{}", secret.box_id));
+                if let Some(version) = box_version.filter(|version| version.deleted) {
+                    records.insert(
+                        record_key,
+                        ReplicaRecord {
+                            version: version.clone(),
+                            content: None,
+                        },
+                    );
+                }
+                continue;
+            };
+            let value = self.decrypt_secret_cached(&secret, box_key)?;
+            let mut variants = BTreeMap::new();
+            for environment in secret.environments() {
+                let variant = self.decrypt_variant_cached(&secret, Some(&environment), box_key)?;
+                variants.insert(environment, variant);
+            }
+            records.insert(
+                record_key.clone(),
+                ReplicaRecord {
+                    version: version_of(&record_key, secret.updated_at),
+                    content: Some(RecordContent::Secret {
+                        box_id: secret.box_id,
+                        name: secret.name,
+                        value,
+                        variants,
+                        env_name: secret.env_name,
+                        value_encoding: secret.value_encoding,
+                        multiline_format: secret.multiline_format,
+                        rotation_interval_days: secret.rotation_interval_days,
+                    }),
+                },
+            );
+        }
+
+        for (record_key, version) in versions {
+            if version.deleted {
+                records.entry(record_key).or_insert(ReplicaRecord {
+                    version,
+                    content: None,
+                });
+            }
+        }
+        Ok(records)
+    }
+
+    // Write a replica record's content to the vault under the same id, None deletes it
+    fn apply_replica_record(
+        &self,
+        record_key: &str,
+        content: Option<&RecordContent>,
+    ) -> Result<(), ZapError> {
+        let malformed =
+            || ZapError::ValidationError(format!("Replica record '{}' is malformed", record_key));
+        let (kind, id) = record_key.split_once(':').ok_or_else(malformed)?;
+
+        match (kind, content) {
+            ("box", None) => match self.storage.delete_box(id) {
+                Err(ZapError::BoxNotFound(_)) => Ok(()),
+                deleted => deleted,
+            },
+            ("secret", None) => match self.storage.delete_secret(id) {
+                Err(ZapError::SecretNotFound(_)) => Ok(()),
+                deleted => deleted,
+            },
+            (
+                "box",
+                Some(RecordContent::Box {
+                    name,
+                    description,
+                    tags,
+                }),
+            ) => match self.storage.get_box(id) {
+                Ok(mut box_item) => {
+                    box_item.ensure_writable()?;
+                    box_item.update_fields(
+                        Some(name.clone()),
+                        Some(description.clone()),
+                        Some(tags.clone()),
+                        None,
+                    )?;
+                    self.storage.update_box(&box_item)
+                }
+                Err(ZapError::BoxNotFound(_)) => {
+                    let data_key = self.crypto.generate_data_key();
+                    let wrapped_key = self.crypto.wrap_key(&data_key, &self.get_master_key()?)?;
+                    let mut new_box =
+                        Box::new(name.clone(), description.clone(), tags.clone(), false)?
+                            .with_wrapped_key(wrapped_key);
+                    new_box.id = id.to_string();
+                    self.storage.save_box(&new_box)
+                }
+                Err(e) => Err(e),
+            },
+            (
+                "secret",
+                Some(RecordContent::Secret {
+                    box_id,
+                    name,
+                    value,
+                    variants,
+                    env_name,
+                    value_encoding,
+                    multiline_format,
+                    rotation_interval_days,
+                }),
+            ) => {
+                let box_item = self.storage.get_box(box_id)?;
+                box_item.ensure_writable()?;
+                let box_key = self.box_key(&box_item)?;
+                let (mut secret, exists) = match self.storage.get_secret(id) {
+                    Ok(secret) if secret.box_id == *box_id => (secret, true),
+                    Ok(_) => return Err(malformed()),
+                    Err(ZapError::SecretNotFound(_)) => {
+                        let mut secret =
+                            Secret::new(box_id.clone(), name.clone(), EncryptedData::empty())?;
+                        secret.id = id.to_string();
+                        (secret, false)
+                    }
+                    Err(e) => return Err(e),
+                };
+
+                // Only a new value counts as a rotation
+                let value_changed = !exists
+                    || secret.value_encoding != *value_encoding
+                    || self.decrypt_secret_cached(&secret, &box_key)? != *value;
+                secret.value_encoding = *value_encoding;
+                secret.multiline_format = *multiline_format;
+                secret.set_rotation_interval(*rotation_interval_days)?;
+                let encrypted_value = if value_changed {
+                    Some(self.crypto.encrypt_secret(&secret, value, &box_key)?)
+                } else {
+                    None
+                };
+                secret.update_fields(Some(name.clone()), encrypted_value)?;
+                secret.set_env_name(env_name.clone());
+                let mut sealed_variants = BTreeMap::new();
+                for (environment, variant) in variants {
+                    let sealed = self.crypto.encrypt_secret_variant(
+                        &secret,
+                        environment,
+                        variant,
+                        &box_key,
+                    )?;
+                    sealed_variants.insert(environment.clone(), sealed);
+                }
+                secret.variants = sealed_variants;
+
+                if exists {
+                    self.storage.update_secret(&secret)
+                } else {
+                    self.storage.save_secret(&secret)
+                }
+            }
+            _ => Err(malformed()),
+        }
+    }
+
+    // Decrypted access token for an integration, kept in the vault as a regular secret
+    fn access_token(&self, secret_id: &str) -> Result<String, ZapError> {
+        let secret = self.storage.get_secret(secret_id)?;
+        let box_item = self.storage.get_box(&secret.box_id)?;
+        let token = self.decrypt_secret_cached(&secret, &self.box_key(&box_item)?)?;
+        Ok(token.trim().to_string())
+    }
+
+    /// Box encrypted to a teammate's age public key, safe to send over chat
+    pub async fn export_box_for_recipient(
+        &self,
+        box_id: String,
+        recipient: String,
+    ) -> Result<String, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+        self.require_recent_verification()?;
+
+        let box_item = self.storage.get_box(&box_id)?;
+        let master_key = self.get_master_key()?;
+        let result = self.import_export.export_box_for_recipient(
+            &box_id,
+            &master_key,
+            &recipient,
+            |secret, key| self.decrypt_secret_cached(secret, key),
+        )?;
+
+        let _ = self.storage.log(
+            LogAction::ExportBox,
+            format!(
+                "Exported box '{}' encrypted to a recipient ({} secrets)",
+                box_item.name, box_item.secrets_count
+            ),
+            Some(recipient),
+        );
+
+        Ok(result)
+    }
+
+    /// Decrypt a box shared with `export_box_for_recipient` and import it like a vault file
+    pub async fn import_shared_box(
+        &self,
+        encrypted: &str,
+        identity: &str,
+    ) -> Result<ImportResult, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let json_data = self.import_export.decrypt_shared_box(encrypted, identity)?;
+        self.run_import_vault(&json_data, true, None)
+    }
+
+    pub async fn import_vault(&self, json_data: &str) -> Result<ImportResult, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        self.run_import_vault(json_data, false, None)
+    }
+
+    pub async fn import_vault_merge(
+        &self,
+        json_data: &str,
+        strategy: MergeStrategy,
+    ) -> Result<ImportResult, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let master_key = self.get_master_key()?;
+        let signature = self.check_export_signature(json_data, &master_key)?;
+        self.take_vault_snapshot(SnapshotReason::MergeImport)?;
+        let before = self.snapshot_secrets()?;
+        let merged = self
+            .import_export
+            .import_vault_merge(json_data, &master_key, strategy);
+        self.record_overwritten("Merge import", before);
+        let mut result = merged?;
+        result.signature = signature;
+
+        let _ = self.storage.log(
+            LogAction::ImportVaultMerge,
+            format!(
+                "Merged vault ({} boxes added, {} boxes changed, {} secrets added, {} replaced)",
+                result.boxes_imported,
+                result.boxes_updated,
+                result.secrets_imported,
+                result.secrets_updated
+            ),
+            Some(format!(
+                "strategy {:?}{}",
+                strategy,
+                if result.has_errors() {
+                    format!(", {} errors", result.errors.len())
+                } else {
+                    String::new()
+                }
+            )),
+        );
+
+        Ok(result)
+    }
+
+    /// What changed since an earlier vault export, e.g. before overwriting a shared copy
+    pub async fn diff_vault_against_export(&self, json_data: &str) -> Result<VaultDiff, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+        self.require_recent_verification()?;
+
+        let master_key = self.get_master_key()?;
+        let diff = self
+            .import_export
+            .diff_vault_against_export(json_data, &master_key)?;
+
+        let _ = self.storage.log(
+            LogAction::DiffExport,
+            "Compared vault with an earlier export".to_string(),
+            Some(format!(
+                "{} boxes added, {} removed, {} changed",
+                diff.boxes_added.len(),
+                diff.boxes_removed.len(),
+                diff.boxes_changed.len()
+            )),
+        );
+
+        Ok(diff)
+    }
+
+    pub async fn import_env_to_box(
+        &self,
+        env_content: &str,
+        target_box_id: String,
+        resolve_from_box: bool,
+    ) -> Result<crate::models::ImportResult, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let target_box = self.storage.get_box(&target_box_id)?;
+        target_box.ensure_writable()?;
+        let master_key = self.get_master_key()?;
+        let result = self.import_export.import_env_to_box(
+            env_content,
+            &target_box_id,
+            &master_key,
+            resolve_from_box,
+        )?;
+
+        let _ = self.storage.log(
+            LogAction::ImportEnv,
+            format!(
+                "Imported {} env variables to box '{}'",
+                result.secrets_imported, target_box.name
+            ),
+            if result.has_errors() {
+                Some(format!("{} errors", result.errors.len()))
+            } else {
+                None
+            },
+        );
+
+        Ok(result)
+    }
+
+    pub async fn import_config_to_box(
+        &self,
+        content: &str,
+        target_box_id: String,
+        format: ConfigFormat,
+        root_key: Option<String>,
+    ) -> Result<ImportResult, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let target_box = self.storage.get_box(&target_box_id)?;
+        target_box.ensure_writable()?;
+        let master_key = self.get_master_key()?;
+        let result = self.import_export.import_config_to_box(
+            content,
+            &target_box_id,
+            format,
+            root_key.as_deref(),
+            &master_key,
+        )?;
+
+        let _ = self.storage.log(
+            LogAction::ImportConfig,
+            format!(
+                "Imported {} {:?} values to box '{}'",
+                result.secrets_imported, format, target_box.name
+            ),
+            if result.has_errors() {
+                Some(format!("{} errors", result.errors.len()))
+            } else {
+                None
+            },
+        );
+
+        Ok(result)
+    }
+
+    pub async fn import_csv_to_box(
+        &self,
+        content: &str,
+        target_box_id: String,
+        mapping: CsvColumnMapping,
+    ) -> Result<ImportResult, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let target_box = self.storage.get_box(&target_box_id)?;
+        target_box.ensure_writable()?;
+        let master_key = self.get_master_key()?;
+        let result =
+            self.import_export
+                .import_csv_to_box(content, &target_box_id, &mapping, &master_key)?;
+
+        let _ = self.storage.log(
+            LogAction::ImportCsv,
+            format!(
+                "Imported {} CSV rows to box '{}'",
+                result.secrets_imported, target_box.name
+            ),
+            if result.has_errors() {
+                Some(format!("{} errors", result.errors.len()))
+            } else {
+                None
+            },
+        );
+
+        Ok(result)
+    }
+
+    /// Detect the format of pasted text and list what `smart_import` would add
+    pub async fn preview_smart_import(
+        &self,
+        content: &str,
+        target_box_id: String,
+    ) -> Result<SmartImportPreview, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        self.import_export.preview_smart_import(content, &target_box_id)
+    }
+
+    pub async fn smart_import(
+        &self,
+        content: &str,
+        target_box_id: String,
+    ) -> Result<ImportResult, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let target_box = self.storage.get_box(&target_box_id)?;
+        target_box.ensure_writable()?;
+        let master_key = self.get_master_key()?;
+        let (format, result) =
+            self.import_export
+                .smart_import_to_box(content, &target_box_id, &master_key)?;
+
+        let _ = self.storage.log(
+            LogAction::ImportPaste,
+            format!(
+                "Imported {} pasted {:?} values to box '{}'",
+                result.secrets_imported, format, target_box.name
+            ),
+            if result.has_errors() {
+                Some(format!("{} errors", result.errors.len()))
+            } else {
+                None
+            },
+        );
+
+        Ok(result)
+    }
+
+    /// Fill `{{secret:Box/Secret}}` placeholders from the vault (names are matched like lookups)
+    pub async fn render_template(
+        &self,
+        template: String,
+        mode: TemplateMode,
+    ) -> Result<RenderedTemplate, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+        self.require_plaintext_reveal()?;
+
+        // Box name -> (box id, data key), None = no such box
+        let mut boxes: HashMap<String, Option<(String, [u8; 32])>> = HashMap::new();
+        let mut resolved = 0;
+
+        let rendered = secret_template::render_template(&template, mode, |box_name, secret_name| {
+            let box_entry = match boxes.get(&name_key(box_name)) {
+                Some(entry) => entry.clone(),
+                None => {
+                    let entry = match self.storage.get_box_id_by_name(box_name)? {
+                        Some(box_id) => {
+                            let box_item = self.storage.get_box(&box_id)?;
+                            Some((box_id, self.box_key(&box_item)?))
+                        }
+                        None => None,
+                    };
+                    boxes.insert(name_key(box_name), entry.clone());
+                    entry
+                }
+            };
+
+            let Some((box_id, box_key)) = box_entry else {
+                return Ok(None);
+            };
+            match self.storage.get_secret_by_name_in_box(secret_name, &box_id)? {
+                Some(secret) => {
+                    resolved += 1;
+                    Ok(Some(self.decrypt_secret_cached(&secret, &box_key)?))
+                }
+                None => Ok(None),
+            }
+        })?;
+
+        let _ = self.storage.log(
+            LogAction::RenderTemplate,
+            format!("Rendered a template with {} secret references", resolved),
+            (!rendered.missing.is_empty())
+                .then(|| format!("Unresolved: {}", rendered.missing.join(", "))),
+        );
+
+        Ok(rendered)
+    }
+
+    // SHARING
+
+    /// Seal a secret into a self-contained blob plus a short passphrase, redeemable once
+    pub async fn create_one_time_share(
+        &self,
+        secret_id: &str,
+        ttl_minutes: u32,
+    ) -> Result<OneTimeShare, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+        self.require_recent_verification()?;
+
+        let secret = self.storage.get_secret(secret_id)?;
+        let box_item = self.storage.get_box(&secret.box_id)?;
+        let box_key = self.box_key(&box_item)?;
+        let value = self.decrypt_secret_cached(&secret, &box_key)?;
+
+        let share = self.shares.create_share(&secret.name, &value, ttl_minutes)?;
+
+        let _ = self.storage.log(
+            LogAction::CreateShare,
+            format!(
+                "One-time share created for secret '{}' from box '{}'",
+                secret.name, box_item.name
+            ),
+            Some(format!(
+                "share {}, expires {}",
+                share.share_id,
+                share.expires_at.to_rfc3339()
+            )),
+        );
+
+        Ok(share)
+    }
+
+    /// Open a share once - its id is recorded so this vault refuses the same blob afterwards.
+    /// Other installs don't know about it, so a leaked blob and passphrase work until expiry.
+    pub async fn redeem_share(
+        &self,
+        blob: &str,
+        passphrase: &str,
+    ) -> Result<RedeemedShare, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let (payload, value) = match self.shares.open_share(blob, passphrase) {
+            Ok(opened) => opened,
+            Err(e) => {
+                let _ = self.storage.log(
+                    LogAction::RedeemShareFailed,
+                    "A one-time share could not be opened".to_string(),
+                    Some(e.to_string()),
+                );
+                return Err(e);
+            }
+        };
+
+        if !self.storage.mark_share_redeemed(&payload.share_id)? {
+            let _ = self.storage.log(
+                LogAction::RedeemShareFailed,
+                format!("One-time share for '{}' was already redeemed", payload.secret_name),
+                Some(format!("share {}", payload.share_id)),
+            );
+            return Err(ZapError::ValidationError(
+                "This share has already been redeemed".to_string(),
+            ));
+        }
+
+        let _ = self.storage.log(
+            LogAction::RedeemShare,
+            format!("One-time share for secret '{}' redeemed", payload.secret_name),
+            Some(format!("share {}", payload.share_id)),
+        );
+
+        Ok(RedeemedShare {
+            share_id: payload.share_id,
+            secret_name: payload.secret_name,
+            value,
+        })
+    }
+
+    // PROXY SESSIONS
+
+    /// Keep a proxy session in memory until it expires, is revoked or the vault locks
+    pub fn add_proxy_session(&self, token: String, session: ProxySession) -> Result<(), ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let mut sessions = self.proxy_sessions.lock().unwrap();
+        let replaces = sessions
+            .sessions()
+            .iter()
+            .any(|existing| existing.session_name == session.session_name);
+        if !replaces && sessions.len() >= MAX_PROXY_SESSIONS {
+            return Err(ZapError::ValidationError(format!(
+                "At most {} proxy sessions can be active, revoke one first",
+                MAX_PROXY_SESSIONS
+            )));
+        }
+        sessions.insert(token, session);
+        Ok(())
+    }
+
+    /// Decrypted values for the CLI holding `token` (called by the session proxy)
+    pub fn serve_proxy_session(&self, token: &str) -> Result<ProxyReply, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let mut sessions = self.proxy_sessions.lock().unwrap();
+        let session = sessions.get_mut(token).ok_or_else(|| {
+            ZapError::AuthError("Proxy session token is invalid or has expired".to_string())
+        })?;
+
+        let mut secrets = BTreeMap::new();
+        for (name, data) in &session.encrypted_secrets {
+            let value = self.crypto.decrypt(data, &session.session_key)?;
+            secrets.insert(name.clone(), value);
+        }
+        session.fetch_count = session.fetch_count.saturating_add(1);
+
+        let _ = self.storage.log(
+            LogAction::ServeProxySession,
+            format!("Proxy session '{}' served to the CLI", session.session_name),
+            Some(format!("{} secrets", secrets.len())),
+        );
+
+        Ok(ProxyReply::Secrets {
+            session_name: session.session_name.clone(),
+            box_name: session.box_name.clone(),
+            secrets,
+        })
+    }
+
+    /// Name of the live proxy session behind `token`, without serving it
+    pub fn proxy_session_name(&self, token: &str) -> Option<String> {
+        let mut sessions = self.proxy_sessions.lock().unwrap();
+        sessions
+            .get_mut(token)
+            .map(|session| session.session_name.clone())
+    }
+
+    pub fn get_proxy_sessions(&self) -> Result<Vec<ProxySessionInfo>, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let mut sessions = self.proxy_sessions.lock().unwrap();
+        let mut infos: Vec<ProxySessionInfo> = sessions
+            .sessions()
+            .into_iter()
+            .map(|session| ProxySessionInfo {
+                session_name: session.session_name.clone(),
+                box_name: session.box_name.clone(),
+                secrets_count: session.encrypted_secrets.len(),
+                fetch_count: session.fetch_count,
+                expires_at: session.expires_at,
+            })
+            .collect();
+        infos.sort_by(|a, b| a.session_name.cmp(&b.session_name));
+        Ok(infos)
+    }
+
+    /// Invalidate a proxy session's token right away
+    pub fn revoke_proxy_session(&self, session_name: &str) -> Result<(), ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        if !self.proxy_sessions.lock().unwrap().remove(session_name) {
+            return Err(ZapError::session_not_found(session_name));
+        }
+
+        let _ = self.storage.log(
+            LogAction::RevokeProxySession,
+            format!("Proxy session '{}' revoked", session_name),
+            None,
+        );
+        Ok(())
+    }
+
+    // APP ACCESS
+
+    /// Whether the program behind a session proxy request may have the values
+    /// (Settings: app_access). Programs on neither list prompt the UI, and an unanswered
+    /// prompt denies.
+    pub async fn check_app_access(
+        &self,
+        caller: Option<&PeerProcess>,
+        session_name: &str,
+    ) -> Result<(), ZapError> {
+        let access = self.storage.load_settings()?.app_access;
+        if !access.enabled {
+            return Ok(());
+        }
+
+        let Some(caller) = caller else {
+            return Err(ZapError::AuthError(
+                "Couldn't tell which program is asking for secrets".to_string(),
+            ));
+        };
+        let exe_path = caller.exe_path.display().to_string();
+        if access.denied_apps.contains(&exe_path) {
+            return Err(ZapError::AuthError(format!(
+                "{} is not allowed to read secrets",
+                exe_path
+            )));
+        }
+        if access.allowed_apps.contains(&exe_path) {
+            return Ok(());
+        }
+
+        let request = AppAccessRequest {
+            request_id: uuid::Uuid::new_v4().to_string(),
+            exe_path: exe_path.clone(),
+            pid: caller.pid,
+            session_name: session_name.to_string(),
+        };
+        let (sender, receiver) = oneshot::channel();
+        self.app_access_prompts
+            .lock()
+            .unwrap()
+            .insert(request.request_id.clone(), sender);
+        if let Some(app) = self.app_handle.lock().unwrap().as_ref() {
+            prompt_app_access(app, &request);
+        }
+
+        let timeout = Duration::from_secs(APP_ACCESS_TIMEOUT_SECONDS);
+        let answer = match tokio::time::timeout(timeout, receiver).await {
+            Ok(Ok(answer)) => answer,
+            _ => AppAccessAnswer {
+                allow: false,
+                remember: false,
+            },
+        };
+        self.app_access_prompts
+            .lock()
+            .unwrap()
+            .remove(&request.request_id);
+
+        if answer.remember {
+            let mut settings = self.storage.load_settings()?;
+            let list = if answer.allow {
+                &mut settings.app_access.allowed_apps
+            } else {
+                &mut settings.app_access.denied_apps
+            };
+            if !list.contains(&exe_path) && list.len() < MAX_APP_ACCESS_ENTRIES {
+                list.push(exe_path.clone());
+                self.storage.save_settings(&settings)?;
+            }
+        }
+
+        let (action, verb) = if answer.allow {
+            (LogAction::AllowAppAccess, "allowed")
+        } else {
+            (LogAction::DenyAppAccess, "denied")
+        };
+        let _ = self.storage.log(
+            action,
+            format!(
+                "{} {} access to proxy session '{}'",
+                exe_path, verb, session_name
+            ),
+            answer.remember.then(|| "remembered".to_string()),
+        );
+
+        if answer.allow {
+            Ok(())
+        } else {
+            Err(ZapError::AuthError(format!(
+                "Access to secrets was denied for {}",
+                exe_path
+            )))
+        }
+    }
+
+    /// Answer a prompt sent with APP_ACCESS_EVENT
+    pub fn answer_app_access(
+        &self,
+        request_id: &str,
+        allow: bool,
+        remember: bool,
+    ) -> Result<(), ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let sender = self
+            .app_access_prompts
+            .lock()
+            .unwrap()
+            .remove(request_id)
+            .ok_or_else(|| {
+                ZapError::ValidationError(
+                    "This access request is no longer waiting for an answer".to_string(),
+                )
+            })?;
+        let _ = sender.send(AppAccessAnswer { allow, remember });
+        Ok(())
+    }
+
+    // AUTOMATIC EXPORT
+
+    /// Write an encrypted backup when one is due (or right away with `force`).
+    /// Returns the new file, None when nothing was due.
+    pub fn run_auto_export(&self, force: bool) -> Result<Option<PathBuf>, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let settings = self.storage.load_settings()?.auto_export;
+        if !force {
+            if !settings.enabled {
+                return Ok(None);
+            }
+            let interval = chrono::Duration::hours(settings.interval_hours as i64);
+            if let Some(last) = self.storage.load_last_auto_export()? {
+                if chrono::Utc::now() - last < interval {
+                    return Ok(None);
+                }
+            }
+        }
+
+        let Some(directory) = settings.directory.as_deref() else {
+            return Err(ZapError::ValidationError(
+                "Choose a folder for automatic exports".to_string(),
+            ));
+        };
+
+        // Recorded up front, so a broken folder is retried next interval rather than every check
+        self.storage.save_last_auto_export(chrono::Utc::now())?;
+
+        match self.write_backup(directory, settings.keep_last as usize) {
+            Ok(path) => {
+                let _ = self.storage.log(
+                    LogAction::AutoExport,
+                    "Encrypted backup written".to_string(),
+                    Some(path.display().to_string()),
+                );
+                Ok(Some(path))
+            }
+            Err(e) => {
+                let _ = self.storage.log(
+                    LogAction::AutoExportFailed,
+                    format!("Encrypted backup to '{}' failed", directory),
+                    Some(e.to_string()),
+                );
+                Err(e)
+            }
+        }
+    }
+
+    /// Restore a backup written by `run_auto_export` with the password it was made under
+    pub async fn import_encrypted_backup(
+        &self,
+        content: &str,
+        password: &str,
+    ) -> Result<ImportResult, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let json_data = self.import_export.open_backup(content, password)?;
+        self.run_import_vault(&json_data, false, None)
+    }
+
+    // JOBS
+
+    /// Run `work` on a blocking thread as a tracked job and return its id right away.
+    /// The session can't idle out while it runs, so a long import doesn't stop halfway.
+    pub fn spawn_job<F>(&self, kind: JobKind, work: F) -> String
+    where
+        F: FnOnce(&JobContext) -> Result<serde_json::Value, ZapError> + Send + 'static,
+    {
+        let job = self.jobs.start(kind, self.app_handle.lock().unwrap().clone());
+        let job_id = job.id().to_string();
+        let hold = SessionHold::new(&self.auth);
+
+        tauri::async_runtime::spawn_blocking(move || {
+            let result = work(&job);
+            job.finish(result);
+            drop(hold);
+        });
+        job_id
+    }
+
+    pub fn start_import_vault_job(self: &Arc<Self>, json_data: String) -> Result<String, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let state = Arc::clone(self);
+        Ok(self.spawn_job(JobKind::Import, move |job| {
+            let result = state.run_import_vault(&json_data, false, Some(job))?;
+            Ok(serde_json::to_value(result)?)
+        }))
+    }
+
+    pub fn start_export_vault_job(self: &Arc<Self>) -> Result<String, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+        self.require_recent_verification()?;
+
+        // The export JSON holds every value in plaintext: fetched once with take_job_payload
+        let state = Arc::clone(self);
+        Ok(self.spawn_job(JobKind::Export, move |job| {
+            job.hand_over(state.run_export_vault(Some(job))?);
+            Ok(serde_json::Value::Null)
+        }))
+    }
+
+    /// Check every password secret for breaches as a job, its result the newly compromised ones
+    pub fn start_breach_check_job(self: &Arc<Self>) -> Result<String, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let state = Arc::clone(self);
+        Ok(self.spawn_job(JobKind::BreachCheck, move |job| {
+            let checked = state.run_breach_check(true, Some(job));
+            let newly_compromised = tauri::async_runtime::block_on(checked)?;
+            Ok(serde_json::to_value(newly_compromised)?)
+        }))
+    }
+
+    pub fn get_job(&self, job_id: &str) -> Result<JobInfo, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+        self.jobs.get(job_id)
+    }
+
+    pub fn list_jobs(&self) -> Result<Vec<JobInfo>, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+        Ok(self.jobs.list())
+    }
+
+    /// Output a job kept out of its JobInfo (a vault export), handed out once
+    pub fn take_job_payload(&self, job_id: &str) -> Result<String, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+        self.jobs.take_payload(job_id)
+    }
+
+    pub fn cancel_job(&self, job_id: &str) -> Result<(), ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+        self.jobs.cancel(job_id)
+    }
+
+    /// Offer the vault or one box to a device on the same network. Its job finishes once the
+    /// receiver has imported it; cancel_job, locking or the code expiring withdraw the offer.
+    pub async fn start_lan_transfer(
+        self: &Arc<Self>,
+        scope: TransferScope,
+    ) -> Result<LanTransferOffer, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+        self.require_recent_verification()?;
+        let label = match &scope {
+            TransferScope::Vault => "the vault".to_string(),
+            TransferScope::Box { box_id } => {
+                format!("box '{}'", self.storage.get_box(box_id)?.name)
+            }
+        };
+
+        let sender = TransferSender::open().await?;
+        let offer = sender.offer();
+        let state = Arc::clone(self);
+        let job_id = self.spawn_job(JobKind::Transfer, move |job| {
+            job.progress(0, 0, Some("Waiting for the other device".to_string()));
+            let mut receiver = None;
+            let served = sender.serve(
+                || job.check_cancelled(),
+                |peer| {
+                    receiver = Some(peer);
+                    job.progress(0, 0, Some(format!("Sending {} to {}", label, peer)));
+                    state.transfer_payload(&scope)
+                },
+            );
+            let receipt = tauri::async_runtime::block_on(served)?;
+
+            let _ = state.storage.log(
+                LogAction::LanTransfer,
+                format!(
+                    "Sent {} over the network ({} boxes, {} secrets imported)",
+                    label, receipt.boxes_imported, receipt.secrets_imported
+                ),
+                receiver.map(|peer| peer.to_string()),
+            );
+            Ok(serde_json::to_value(receipt)?)
+        });
+
+        Ok(LanTransferOffer { job_id, ..offer })
+    }
+
+    /// Import what another device offers with start_lan_transfer, by its code or link. The
+    /// job's result is the ImportResult.
+    pub fn start_lan_receive(self: &Arc<Self>, code: String) -> Result<String, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let state = Arc::clone(self);
+        Ok(self.spawn_job(JobKind::Transfer, move |job| {
+            job.progress(0, 0, Some("Connecting to the other device".to_string()));
+            let received = lan_transfer_service::receive(&code, |json| {
+                state.run_import_vault(json, false, Some(job))
+            });
+            let result = tauri::async_runtime::block_on(received)?;
+
+            let _ = state.storage.log(
+                LogAction::LanTransfer,
+                format!(
+                    "Received {} boxes and {} secrets over the network",
+                    result.boxes_imported, result.secrets_imported
+                ),
+                None,
+            );
+            Ok(serde_json::to_value(result)?)
+        }))
+    }
+
+    // Signed vault export JSON of what a LAN transfer sends
+    fn transfer_payload(&self, scope: &TransferScope) -> Result<String, ZapError> {
+        match scope {
+            TransferScope::Vault => self.run_export_vault(None),
+            TransferScope::Box { box_id } => {
+                let master_key = self.get_master_key()?;
+                let signing_key = self.export_signing_key(&master_key)?;
+                self.import_export.export_box_as_vault(
+                    box_id,
+                    &master_key,
+                    &signing_key,
+                    |secret, key| self.decrypt_secret_cached(secret, key),
+                )
+            }
+        }
+    }
+
+    // LOG OPERATIONS
+
+    /// Get all logs (no password required)
+    pub async fn get_all_logs(&self) -> Result<Vec<LogEntry>, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        // Auto-log that someone viewed logs
+        let _ = self.storage.log(
+            LogAction::ViewLogs,
+            "Viewed audit logs".to_string(),
+            None,
+        );
+
+        self.storage.get_all_logs()
+    }
+
+    /// Get logs filtered by minimum severity and/or action name
+    pub async fn get_filtered_logs(
+        &self,
+        min_severity: Option<LogSeverity>,
+        action: Option<&str>,
+    ) -> Result<Vec<LogEntry>, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let action = match action {
+            Some(name) => match LogAction::parse(name) {
+                LogAction::Unknown => {
+                    return Err(ZapError::ValidationError(format!(
+                        "Unknown log action '{}'",
+                        name
+                    )))
+                }
+                action => Some(action),
+            },
+            None => None,
+        };
+
+        let _ = self.storage.log(
+            LogAction::ViewLogs,
+            "Viewed audit logs".to_string(),
+            None,
+        );
+
+        self.storage.get_filtered_logs(min_severity, action)
+    }
+
+    /// Clear all logs
+    pub async fn clear_all_logs(&self, password: Option<&str>) -> Result<usize, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        match password {
+            Some(password) => self.verify_password(password)?,
+            // Without a password, only a verification inside the re-auth window counts
+            None => {
+                if self.storage.load_settings()?.reauth_minutes == 0 {
+                    return Err(ZapError::ReauthenticationRequired);
+                }
+                self.require_recent_verification()?;
+            }
+        }
+
+        let count = self.storage.clear_all_logs()?;
+
+        // Note: clearing action is already logged in storage.clear_all_logs()
+
+        Ok(count)
+    }
+
+    /// Export logs (no password required)
+    pub async fn export_logs(&self) -> Result<String, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        // Auto-log the export
+        let _ = self.storage.log(
+            LogAction::ExportLogs,
+            "Exported audit logs".to_string(),
+            None,
+        );
+
+        self.storage.export_logs()
+    }
+
+    // STATISTICS & UTILITIES
+
+    pub async fn get_vault_stats(&self) -> Result<VaultStats, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        self.stats.get_vault_stats()
+    }
+
+    pub async fn export_stats_report(&self, format: ReportFormat) -> Result<String, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        let report = self.stats.export_report(format)?;
+
+        let _ = self.storage.log(
+            LogAction::ExportStatsReport,
+            "Exported statistics report".to_string(),
+            Some(format!("{:?}", format).to_lowercase()),
+        );
+
+        Ok(report)
+    }
+
+    /// Health check for support - works while locked and when storage failed to open
+    pub async fn run_diagnostics(&self) -> DiagnosticsReport {
+        let mut errors = Vec::new();
+
+        let mut paths = match get_all_paths() {
+            Ok(paths) => paths,
+            Err(e) => {
+                errors.push(format!("Failed to resolve paths: {}", e));
+                Vec::new()
+            }
+        };
+        let settings = self.storage.load_settings().ok();
+        if let Some(dir) = settings
+            .as_ref()
+            .and_then(|s| s.auto_export.directory.clone())
+        {
+            paths.push(("Automatic Export Directory".to_string(), PathBuf::from(dir)));
+        }
+        let paths = paths
+            .iter()
+            .map(|(name, path)| PathDiagnostic::inspect(name, path))
+            .collect();
+
+        let databases = [
+            DatabaseKind::Vault,
+            DatabaseKind::Sessions,
+            DatabaseKind::Logs,
+        ]
+        .into_iter()
+        .map(|database| DatabaseDiagnostic {
+            database,
+            open: self.storage.is_open(database),
+        })
+        .collect();
+
+        let schema_version = match self.storage.schema_version() {
+            Ok(version) => Some(version),
+            Err(e) => {
+                errors.push(format!("Failed to read schema version: {}", e));
+                None
+            }
+        };
+
+        let session_files = self.count_orphaned_sessions();
+        let (orphaned_session_files, missing_session_files) = match session_files {
+            Ok(counts) => counts,
+            Err(e) => {
+                errors.push(format!("Failed to check session files: {}", e));
+                (0, 0)
+            }
+        };
+
+        let last_backup_at = self.storage.load_last_auto_export().unwrap_or_else(|e| {
+            errors.push(format!("Failed to read last backup time: {}", e));
+            None
+        });
+
+        let keyring_error = self.keyring.check_available().err().map(|e| e.to_string());
+        let kdf_params = self
+            .auth
+            .get_config()
+            .filter(|config| !config.is_first_time_setup())
+            .map(|config| config.kdf_params);
+
+        DiagnosticsReport {
+            generated_at: chrono::Utc::now(),
+            app_version: env!("CARGO_PKG_VERSION").to_string(),
+            os: std::env::consts::OS.to_string(),
+            arch: std::env::consts::ARCH.to_string(),
+            startup_status: self.get_startup_status(),
+            paths,
+            databases,
+            schema_version,
+            latest_schema_version: migrations::latest_version(),
+            orphaned_session_files,
+            missing_session_files,
+            last_backup_at,
+            keyring_available: keyring_error.is_none(),
+            keyring_error,
+            kdf_params,
+            locked: self.is_locked(),
+            errors,
+        }
+    }
+
+    // (files with no session entry, session entries with no file)
+    fn count_orphaned_sessions(&self) -> Result<(usize, usize), ZapError> {
+        let sessions = self.storage.get_all_dev_sessions()?;
+        let sessions_dir = get_sessions_directory()
+            .map_err(|e| ZapError::StorageError(format!("Failed to get sessions dir: {}", e)))?;
+
+        let mut files = HashSet::new();
+        if sessions_dir.exists() {
+            for entry in std::fs::read_dir(&sessions_dir)? {
+                let path = entry?.path();
+                if path.extension().and_then(|s| s.to_str()) != Some("json") {
+                    continue;
+                }
+                if let Some(stem) = path.file_stem().and_then(|s| s.to_str()) {
+                    files.insert(stem.to_string());
+                }
+            }
+        }
+
+        let orphaned = files
+            .iter()
+            .filter(|name| !sessions.contains_key(*name))
+            .count();
+        let missing = sessions
+            .keys()
+            .filter(|name| !files.contains(*name))
+            .count();
+        Ok((orphaned, missing))
+    }
+
+    pub async fn get_storage_usage(&self) -> Result<StorageUsage, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        self.storage.get_storage_usage()
+    }
+
+    /// Schedule compaction for the next start; the caller restarts the app
+    pub async fn compact_storage(&self) -> Result<(), ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+        self.require_recent_verification()?;
+
+        let usage = self.storage.get_storage_usage()?;
+        self.storage.schedule_compaction()?;
+
+        let _ = self.storage.log(
+            LogAction::ScheduleCompaction,
+            "Storage compaction scheduled for restart".to_string(),
+            Some(format!("{} bytes on disk", usage.total_bytes_on_disk)),
+        );
+
+        self.storage.flush()
+    }
+
+    pub fn get_data_directory_info(&self) -> Result<DataDirectoryInfo, ZapError> {
+        let path = get_data_directory()?;
+        let default_path = get_default_data_directory()?;
+        Ok(DataDirectoryInfo {
+            path: path.display().to_string(),
+            default_path: default_path.display().to_string(),
+            moved: path != default_path,
+        })
+    }
+
+    /// Copy the databases to an empty folder (None = back to the default directory) and open
+    /// them from there on the next start; the caller restarts the app. The copies are checked
+    /// before anything changes, and the originals are removed once that start opened them.
+    pub async fn move_data_directory(&self, path: Option<String>) -> Result<(), ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+        self.require_recent_verification()?;
+
+        let current_dir = get_data_directory()?;
+        let target_dir = match path.as_deref() {
+            Some(path) => validate_data_directory(path)?,
+            None => {
+                let default_dir = get_default_data_directory()?;
+                validate_data_directory(&default_dir.to_string_lossy())?
+            }
+        };
+        if target_dir.starts_with(&current_dir) || current_dir.starts_with(&target_dir) {
+            return Err(ZapError::ValidationError(
+                "Choose a folder outside the current data directory".to_string(),
+            ));
+        }
+
+        if let Err(e) = self.storage.copy_data_directory_to(&target_dir) {
+            // Was empty, so everything in it is a partial copy
+            for entry in std::fs::read_dir(&target_dir)?.flatten() {
+                let _ = match entry.file_type() {
+                    Ok(kind) if kind.is_dir() => std::fs::remove_dir_all(entry.path()),
+                    _ => std::fs::remove_file(entry.path()),
+                };
+            }
+            return Err(e);
+        }
+        set_data_directory_pointer(path.is_some().then_some(target_dir.as_path()), &current_dir)?;
+
+        tracing::info!(
+            "Data directory copied to {}, opens there on restart",
+            target_dir.display()
+        );
+        Ok(())
+    }
+
+    pub async fn list_vault_snapshots(&self) -> Result<Vec<VaultSnapshot>, ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        self.storage.list_vault_snapshots()
+    }
+
+    /// Put the vault back as it was when a snapshot was taken. The current vault is
+    /// snapshotted first, so this can be undone the same way. The snapshot may hold another
+    /// master password, so the vault locks and is unlocked with the one in use back then.
+    pub async fn restore_snapshot(&self, snapshot_id: &str) -> Result<(), ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+        self.require_recent_verification()?;
+
+        let snapshot = self
+            .storage
+            .list_vault_snapshots()?
+            .into_iter()
+            .find(|snapshot| snapshot.id == snapshot_id)
+            .ok_or_else(|| {
+                ZapError::ValidationError(format!("Snapshot '{}' doesn't exist", snapshot_id))
+            })?;
+
+        // Pruned only afterwards, the snapshot being restored may be the oldest one
+        self.storage.create_vault_snapshot(SnapshotReason::Restore)?;
+        self.storage.restore_vault_from_snapshot(&snapshot.id)?;
+        self.storage.prune_vault_snapshots()?;
+
+        let _ = self.storage.log(
+            LogAction::RestoreSnapshot,
+            format!(
+                "Vault restored from the snapshot taken before {}",
+                snapshot.reason.as_str().replace('_', " ")
+            ),
+            Some(snapshot.created_at.to_rfc3339()),
+        );
+
+        self.lock();
+        self.auth.initialize(self.storage.load_auth_config()?)?;
+        apply_stored_settings(&self.storage, &self.decrypted_cache);
+        Ok(())
+    }
+
+    pub fn get_settings(&self) -> Result<Settings, ZapError> {
+        self.storage.load_settings()
+    }
+
+    pub async fn update_settings(&self, settings: Settings) -> Result<(), ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        if settings.max_session_minutes != 0
+            && settings.max_session_minutes < settings.password_timeout_minutes
+        {
+            return Err(ZapError::ValidationError(
+                "Maximum session length cannot be shorter than the inactivity timeout".to_string(),
+            ));
+        }
+        if settings.decrypted_cache_seconds > 600 {
+            return Err(ZapError::ValidationError(
+                "Decrypted cache lifetime cannot exceed 10 minutes".to_string(),
+            ));
+        }
+        if settings.dev_session_hours > MAX_DEV_SESSION_HOURS {
+            return Err(ZapError::ValidationError(format!(
+                "Dev sessions cannot last longer than {} hours",
+                MAX_DEV_SESSION_HOURS
+            )));
+        }
+        if settings.dev_session_idle_hours > MAX_DEV_SESSION_HOURS {
+            return Err(ZapError::ValidationError(format!(
+                "Dev session idle timeout cannot be longer than {} hours",
+                MAX_DEV_SESSION_HOURS
+            )));
+        }
+        if settings.lock_warning_seconds > 600 {
+            return Err(ZapError::ValidationError(
+                "Lock warning cannot be more than 10 minutes before auto-lock".to_string(),
+            ));
+        }
+        if !(1..=MAX_SECRET_VALUE_KIB).contains(&settings.max_secret_value_kib) {
+            return Err(ZapError::ValidationError(format!(
+                "Maximum secret value size must be between 1 and {} KiB",
+                MAX_SECRET_VALUE_KIB
+            )));
+        }
+        if let Some(environment) = &settings.default_environment {
+            Secret::validate_environment(environment)?;
+        }
+        if settings.trusted_signing_keys.len() > MAX_TRUSTED_SIGNING_KEYS {
+            return Err(ZapError::ValidationError(format!(
+                "Cannot trust more than {} signing keys",
+                MAX_TRUSTED_SIGNING_KEYS
+            )));
+        }
+        for public_key in &settings.trusted_signing_keys {
+            CryptoService::parse_signing_public_key(public_key)?;
+        }
+        for hook in &settings.hooks {
+            hook.validate()?;
+        }
+        settings.window_layout.validate()?;
+        settings.auto_export.validate()?;
+        settings.breach_check.validate()?;
+        settings.app_access.validate()?;
+        settings.file_logging.validate()?;
+        if settings.sync_mappings.len() > MAX_SYNC_MAPPINGS {
+            return Err(ZapError::ValidationError(format!(
+                "Cannot have more than {} sync mappings",
+                MAX_SYNC_MAPPINGS
+            )));
+        }
+        for mapping in &settings.sync_mappings {
+            mapping.validate()?;
+        }
+        if settings.replicas.len() > MAX_REPLICAS {
+            return Err(ZapError::ValidationError(format!(
+                "Cannot have more than {} replicas",
+                MAX_REPLICAS
+            )));
+        }
+        for replica in &settings.replicas {
+            replica.validate()?;
+        }
+
+        let previous = self.storage.load_settings()?;
+        if security_settings_changed(&previous, &settings) {
+            self.require_recent_verification()?;
+        }
+        if previous.remember_me != settings.remember_me {
+            if settings.remember_me && !self.is_locked() {
+                self.remember_master_key()?;
+            } else if !settings.remember_me {
+                self.forget_master_key();
+            }
+        }
+
+        if previous.file_logging != settings.file_logging {
+            logging::apply_settings(&settings.file_logging)?;
+        }
+
+        self.storage.save_settings(&settings)?;
+        // Moved after saving, so a failed move can put the old directory back in the settings
+        if previous.sessions_directory != settings.sessions_directory {
+            if let Err(e) = self.relocate_sessions_directory(settings.sessions_directory.as_deref())
+            {
+                self.storage.save_settings(&previous)?;
+                return Err(e);
+            }
+        }
+        CryptoService::set_default_algorithm(settings.cipher);
+        for replica in &previous.replicas {
+            if !settings.replicas.iter().any(|kept| kept.id == replica.id) {
+                self.storage.clear_replica_state(&replica.id)?;
+            }
+        }
+        self.decrypted_cache
+            .lock()
+            .unwrap()
+            .set_ttl(Duration::from_secs(settings.decrypted_cache_seconds as u64));
+
+        // The session timer reads the warning threshold when it starts
+        if previous.lock_warning_seconds != settings.lock_warning_seconds && !self.is_locked() {
+            self.start_session_timer();
+        }
+
+        // Update auth timeout in memory AND save to AuthConfig
+        self.auth.set_max_session_minutes(settings.max_session_minutes);
+        self.auth.set_timeout_minutes(settings.password_timeout_minutes as u8)?;
+
+        if let Some(mut config) = self.auth.get_config() {
+            config.session_timeout_minutes = settings.password_timeout_minutes as u8;
+            self.storage.save_auth_config(&config)?;
+        }
+
+        let _ = self.storage.log(
+            LogAction::UpdateSettings,
+            "Settings updated".to_string(),
+            Some(format!(
+                "timeout: {} minutes, max session: {} minutes",
+                settings.password_timeout_minutes, settings.max_session_minutes
+            )),
+        );
+
+        Ok(())
+    }
+
+    /// Reset the inactivity countdown (user interaction in the UI)
+    pub fn touch_session(&self) -> Result<(), ZapError> {
+        if self.is_locked() {
+            return Err(ZapError::SessionExpired);
+        }
+
+        self.auth.touch_session();
+        Ok(())
+    }
+
+    pub fn attach_app_handle(&self, app: AppHandle) {
+        *self.app_handle.lock().unwrap() = Some(app);
+    }
+
+    /// Clicking the lock warning focuses the window - treat that as activity
+    pub fn extend_if_expiring(&self) {
+        if self.lock_warning_shown.load(Ordering::SeqCst) && !self.is_locked() {
+            let _ = self.touch_session();
+        }
+    }
+
+    /// Pass a command result through, counting a success as session activity. Only for actions
+    /// the user takes - reads the UI may poll don't extend the session.
+    pub fn touched<T>(&self, result: Result<T, ZapError>) -> Result<T, ZapError> {
+        if result.is_ok() {
+            self.auth.touch_session();
+        }
+        result
+    }
+
+    /// Fire configured hooks for `event` in the background (context is names only)
+    pub fn fire_hook(&self, event: HookEvent, context: &[(&str, &str)]) {
+        let hooks = match self.storage.load_settings() {
+            Ok(settings) => settings.hooks,
+            Err(_) => return,
+        };
+
+        let context: BTreeMap<String, String> = context
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        self.hooks.fire(&hooks, event, context);
+    }
+
+    pub fn get_session_info(&self) -> SessionInfo {
+        let settings = self.storage.load_settings().unwrap_or_default();
+        let is_locked = self.is_locked();
+        let time_left_seconds = self.get_session_time_left();
+        SessionInfo {
+            is_locked,
+            time_left_seconds,
+            phase: SessionPhase::of(
+                is_locked,
+                time_left_seconds,
+                settings.lock_warning_seconds,
+                reauth_lapsed(&self.last_verified, settings.reauth_minutes),
+            ),
+        }
+    }
+
+    // PRIVATE HELPERS
+
+    // Shared by the direct command and the tracked job (progress + cancellation between boxes)
+    fn run_import_vault(
+        &self,
+        json_data: &str,
+        shared: bool,
+        job: Option<&JobContext>,
+    ) -> Result<ImportResult, ZapError> {
+        let master_key = self.get_master_key()?;
+        let signature = self.check_export_signature(json_data, &master_key)?;
+        let app_handle = self.app_handle.lock().unwrap().clone();
+        self.take_vault_snapshot(SnapshotReason::Import)?;
+        // Only shared box updates overwrite anything, new boxes are left to a plain delete
+        let before = self.snapshot_secrets()?;
+        let imported = self
+            .import_export
+            .import_vault(json_data, &master_key, shared, |progress| {
+                if let Some(app) = &app_handle {
+                    let _ = app.emit(IMPORT_PROGRESS_EVENT, &progress);
+                }
+                if let Some(job) = job {
+                    job.progress(
+                        progress.boxes_done,
+                        progress.boxes_total,
+                        Some(format!("{} secrets imported", progress.secrets_imported)),
+                    );
+                    job.check_cancelled()?;
+                }
+                Ok(())
+            });
+        self.record_overwritten("Vault import", before);
+
+        let mut result = match imported {
+            Ok(result) => result,
+            Err(ZapError::Cancelled) => {
+                let _ = self.storage.log(
+                    LogAction::ImportVault,
+                    "Vault import cancelled, boxes imported before that were kept".to_string(),
+                    None,
+                );
+                return Err(ZapError::Cancelled);
+            }
+            Err(e) => return Err(e),
+        };
+
+        let _ = self.storage.log(
+            LogAction::ImportVault,
+            format!(
+                "Imported vault ({} boxes, {} shared boxes updated, {} secrets)",
+                result.boxes_imported, result.boxes_updated, result.secrets_imported
+            ),
+            if result.has_errors() {
+                Some(format!("{} errors", result.errors.len()))
+            } else {
+                None
+            },
+        );
+
+        result.signature = signature;
+        Ok(result)
+    }
+
+    // Signing seed for exports, created on first use and kept wrapped with the master key
+    fn export_signing_key(&self, master_key: &[u8; 32]) -> Result<[u8; 32], ZapError> {
+        if let Some(wrapped) = self.storage.load_export_signing_key()? {
+            return self.crypto.unwrap_key(&wrapped, master_key);
+        }
+
+        let signing_key = self.crypto.generate_data_key();
+        self.storage
+            .save_export_signing_key(&self.crypto.wrap_key(&signing_key, master_key)?)?;
+        Ok(signing_key)
+    }
+
+    // Check an import file against our own key and the trusted ones, auditing any warning
+    fn check_export_signature(
+        &self,
+        json_data: &str,
+        master_key: &[u8; 32],
+    ) -> Result<SignatureStatus, ZapError> {
+        let mut trusted_keys = self.storage.load_settings()?.trusted_signing_keys;
+        let own_key = self.export_signing_key(master_key)?;
+        trusted_keys.push(self.crypto.signing_public_key(&own_key));
+
+        let status = self
+            .import_export
+            .verify_vault_export(json_data, &trusted_keys)?;
+        if status.needs_warning() {
+            let _ = self.storage.log(
+                LogAction::ImportSignatureWarning,
+                match status {
+                    SignatureStatus::Invalid => "Import file was modified after it was signed",
+                    _ => "Import file was signed by a vault that isn't trusted",
+                }
+                .to_string(),
+                None,
+            );
+        }
+        Ok(status)
+    }
+
+    // The job variant reports per secret and can stop between any two of them
+    fn run_export_vault(&self, job: Option<&JobContext>) -> Result<String, ZapError> {
+        let master_key = self.get_master_key()?;
+        let vault_stats = self.stats.get_vault_stats()?;
+        let exported = AtomicUsize::new(0);
+
+        let signing_key = self.export_signing_key(&master_key)?;
+        let result = self
+            .import_export
+            .export_vault(&master_key, &signing_key, |secret, key| {
+                if let Some(job) = job {
+                    job.check_cancelled()?;
+                    let done = exported.fetch_add(1, Ordering::Relaxed) + 1;
+                    job.progress(done, vault_stats.total_secrets, None);
+                }
+                self.decrypt_secret_cached(secret, key)
+            })?;
+
+        let _ = self.storage.log(
+            LogAction::ExportVault,
+            format!(
+                "Exported vault ({} boxes, {} secrets)",
+                vault_stats.total_boxes, vault_stats.total_secrets
+            ),
+            None,
+        );
+
+        Ok(result)
+    }
+
+    // Sliding one-minute window over all reveals, audited when it trips
+    fn check_reveal_rate_limit(&self, secret_name: &str) -> Result<(), ZapError> {
+        let limit = self.storage.load_settings()?.reveal_limit_per_minute;
+        if limit == 0 {
+            return Ok(());
+        }
+
+        let mut reveal_times = self.reveal_times.lock().unwrap();
+        while reveal_times
+            .front()
+            .is_some_and(|revealed_at| revealed_at.elapsed() > REVEAL_WINDOW)
+        {
+            reveal_times.pop_front();
+        }
+
+        if reveal_times.len() >= limit as usize {
+            let retry_after = reveal_times
+                .front()
+                .map(|oldest| REVEAL_WINDOW.saturating_sub(oldest.elapsed()).as_secs() + 1)
+                .unwrap_or(REVEAL_WINDOW.as_secs());
+            drop(reveal_times);
+
+            let _ = self.storage.log(
+                LogAction::RevealRateLimited,
+                format!("Reveal of secret '{}' blocked by rate limit", secret_name),
+                Some(format!("limit: {} per minute", limit)),
+            );
+            return Err(ZapError::RevealRateLimited(retry_after));
+        }
+
+        reveal_times.push_back(Instant::now());
+        Ok(())
+    }
+
+    // Give pre-hierarchy boxes a data key and move their secrets off the master key
+    fn migrate_box_keys(
+        &self,
+        master_key: &[u8; 32],
+        job: Option<&JobContext>,
+    ) -> Result<(), ZapError> {
+        let boxes: Vec<Box> = self
+            .storage
+            .get_all_boxes()?
+            .into_iter()
+            .filter(|box_item| box_item.wrapped_key.is_none())
+            .collect();
+        if !boxes.is_empty() {
+            self.take_vault_snapshot(SnapshotReason::Reencrypt)?;
+        }
+
+        let mut migrated = Vec::new();
+        let total = boxes.len();
+
+        // Each box switches in its own batch, so stopping between two leaves the rest for later
+        let result: Result<(), ZapError> = boxes.into_iter().try_for_each(|mut box_item| {
+            if let Some(job) = job {
+                job.check_cancelled()?;
+                let message = "Moving boxes to per-box data keys".to_string();
+                job.progress(migrated.len(), total, Some(message));
+            }
+
+            let data_key = self.crypto.generate_data_key();
+            let mut secrets = self.storage.get_secrets_by_box_id(&box_item.id)?;
+            for secret in &mut secrets {
+                let value = self.crypto.decrypt_secret(secret, master_key)?;
+                secret.encrypted_value = self.crypto.encrypt_secret(secret, &value, &data_key)?;
+            }
+            box_item.wrapped_key = Some(self.crypto.wrap_key(&data_key, master_key)?);
+
+            // The box and its secrets switch keys in one batch
+            self.storage.save_rekeyed(std::slice::from_ref(&box_item), &secrets)?;
+            migrated.push(box_item.name);
+            Ok(())
+        });
+
+        if !migrated.is_empty() {
+            let _ = self.storage.log(
+                LogAction::MigrateBoxKeys,
+                format!("Moved {} boxes to per-box data keys", migrated.len()),
+                Some(migrated.join(", ")),
+            );
+        }
+        result
+    }
+
+    // Bind secrets written before AAD to their record, then refuse unbound ciphertexts
+    fn migrate_secret_aad(
+        &self,
+        master_key: &[u8; 32],
+        job: Option<&JobContext>,
+    ) -> Result<(), ZapError> {
+        if self.storage.secrets_bound()? {
+            CryptoService::set_require_bound_secrets(true);
+            return Ok(());
+        }
+
+        let mut rebound = Vec::new();
+        let boxes = self.storage.get_all_boxes()?;
+        for (done, box_item) in boxes.iter().enumerate() {
+            // Nothing is saved until every box is done, a stop leaves all of it for later
+            if let Some(job) = job {
+                job.check_cancelled()?;
+                let message = "Binding secrets to their records".to_string();
+                job.progress(done, boxes.len(), Some(message));
+            }
+            let box_key = self.crypto.box_key(box_item, master_key)?;
+            for mut secret in self.storage.get_secrets_by_box_id(&box_item.id)? {
+                if secret.encrypted_value.bound {
+                    continue;
+                }
+                let value = self.crypto.decrypt_secret(&secret, &box_key)?;
+                secret.encrypted_value = self.crypto.encrypt_secret(&secret, &value, &box_key)?;
+                rebound.push(secret);
+            }
+        }
+
+        if !rebound.is_empty() {
+            self.take_vault_snapshot(SnapshotReason::Reencrypt)?;
+        }
+        self.storage.save_bound_secrets(&rebound)?;
+        CryptoService::set_require_bound_secrets(true);
+
+        if !rebound.is_empty() {
+            let _ = self.storage.log(
+                LogAction::MigrateSecretAad,
+                format!("Bound {} secrets to their records", rebound.len()),
+                None,
+            );
+        }
+        Ok(())
+    }
+
+    // Unmigrated data keeps decrypting the old way, so a failure here is not fatal.
+    // Tracked as a Reencrypt job when there is work, for its progress events, but run before
+    // unlock returns: a secret written while its box switches keys would be lost.
+    fn run_unlock_migrations(&self) {
+        let app_handle = self.app_handle.lock().unwrap().clone();
+        let job = self
+            .unlock_migrations_pending()
+            .then(|| self.jobs.start(JobKind::Reencrypt, app_handle));
+
+        let result = self.get_master_key().and_then(|master_key| {
+            self.migrate_box_keys(&master_key, job.as_ref())?;
+            self.migrate_secret_aad(&master_key, job.as_ref())
+        });
+        match &result {
+            Ok(()) => {}
+            Err(ZapError::Cancelled) => tracing::info!("Vault encryption migration cancelled"),
+            Err(e) => tracing::error!("Failed to migrate vault encryption: {}", e),
+        }
+        if let Some(job) = job {
+            job.finish(result.map(|()| serde_json::Value::Null));
+        }
+    }
+
+    fn unlock_migrations_pending(&self) -> bool {
+        let boxes_pending = self.storage.get_all_boxes().map_or(true, |boxes| {
+            boxes.iter().any(|box_item| box_item.wrapped_key.is_none())
+        });
+        boxes_pending || !self.storage.secrets_bound().unwrap_or(false)
+    }
+
+    // Move every box key to the master key returned by `change_credentials`.
+    // The remembered key and recovery key wrap the old master key, so both are replaced.
+    fn rekey_master_key<F>(
+        &self,
+        old_master_key: &[u8; 32],
+        change_credentials: F,
+    ) -> Result<EmergencyKit, ZapError>
+    where
+        F: FnOnce(&AuthService) -> Result<[u8; 32], ZapError>,
+    {
+        self.take_vault_snapshot(SnapshotReason::Reencrypt)?;
+
+        // Legacy boxes get a data key first, so only keys need re-wrapping
+        self.migrate_box_keys(old_master_key, None)?;
+
+        // Unwrap every box key before touching the password so a failure leaves the vault intact
+        let mut boxes = self.storage.get_all_boxes()?;
+        let mut data_keys = Vec::with_capacity(boxes.len());
+        for box_item in &boxes {
+            data_keys.push(self.crypto.box_key(box_item, old_master_key)?);
+        }
+        let signing_key = match self.storage.load_export_signing_key()? {
+            Some(wrapped) => Some(self.crypto.unwrap_key(&wrapped, old_master_key)?),
+            None => None,
+        };
+
+        let previous_config = self.auth.get_config();
+        let new_master_key = change_credentials(&self.auth)?;
+
+        // Nothing reaches disk until the single batch below, so a failure in between leaves the
+        // old password, box keys and recovery key working
+        let persisted = (|| -> Result<EmergencyKit, ZapError> {
+            for (box_item, data_key) in boxes.iter_mut().zip(&data_keys) {
+                box_item.wrapped_key = Some(self.crypto.wrap_key(data_key, &new_master_key)?);
+            }
+            let signing_key = match signing_key {
+                Some(signing_key) => Some(self.crypto.wrap_key(&signing_key, &new_master_key)?),
+                None => None,
+            };
+            let kit = self.issue_recovery_key(&new_master_key)?;
+            let config = self
+                .auth
+                .get_config()
+                .ok_or(ZapError::AuthError("Auth not initialized".to_string()))?;
+
+            self.storage
+                .save_rekeyed_credentials(&boxes, signing_key.as_ref(), &config)?;
+            Ok(kit)
+        })();
+
+        match persisted {
+            Ok(kit) => {
+                self.forget_master_key();
+                Ok(kit)
+            }
+            Err(e) => {
+                // The session holds the new master key, which no longer matches anything stored
+                self.lock();
+                self.auth.initialize(previous_config)?;
+                Err(e)
+            }
+        }
+    }
+
+    // Wrap the current master key with a new recovery key and persist it
+    fn create_recovery_key(&self) -> Result<EmergencyKit, ZapError> {
+        let kit = self.issue_recovery_key(&self.get_master_key()?)?;
+        let config = self.auth.get_config();
+        if let Some(config) = config {
+            self.storage.save_auth_config(&config)?;
+        }
+
+        Ok(kit)
+    }
+
+    // Wrap the master key with a new recovery key in the in-memory auth config (not persisted)
+    fn issue_recovery_key(&self, master_key: &[u8; 32]) -> Result<EmergencyKit, ZapError> {
+        let (recovery_bytes, recovery_key) = self.crypto.generate_recovery_key();
+        let wrapped_master_key = self
+            .crypto
+            .encrypt(&hex::encode(master_key), &recovery_bytes)?;
+        let created_at = chrono::Utc::now();
+
+        self.auth.set_recovery(Some(RecoveryConfig {
+            wrapped_master_key,
+            created_at,
+        }));
+
+        Ok(EmergencyKit {
+            recovery_key,
+            created_at,
+            data_directory: get_data_directory()?.display().to_string(),
+            instructions: "Store this recovery key somewhere safe (printed, offline). \
+                It can unlock your vault and reset your master password if you forget it. \
+                Anyone holding it can read your secrets."
+                .to_string(),
+        })
+    }
+
+    fn decode_master_key(&self, master_key_hex: &str) -> Result<[u8; 32], ZapError> {
+        let key_bytes = hex::decode(master_key_hex)?;
+        if key_bytes.len() != 32 {
+            return Err(ZapError::CryptoError("Invalid master key length".to_string()));
+        }
+        let mut master_key = [0u8; 32];
+        master_key.copy_from_slice(&key_bytes);
+        Ok(master_key)
+    }
+
+    fn remember_master_key(&self) -> Result<(), ZapError> {
+        if self.auth.hardware_key_enabled() {
+            return Err(ZapError::ValidationError(
+                "Remember me isn't available while unlock requires a YubiKey".to_string(),
+            ));
+        }
+
+        let master_key = self.get_master_key()?;
+        let wrap_key = self.keyring.create_wrap_key()?;
+        let wrapped_master_key = self.crypto.encrypt(&hex::encode(master_key), &wrap_key)?;
+
+        self.storage.save_remembered_key(&RememberedKey {
+            wrapped_master_key,
+            boot_time: self.keyring.current_boot_time(),
+            created_at: chrono::Utc::now(),
+        })?;
+
+        let _ = self.storage.log(
+            LogAction::RememberMeEnabled,
+            "Master key wrapped in OS keyring until lock or reboot".to_string(),
+            None,
+        );
+        Ok(())
+    }
+
+    fn forget_master_key(&self) {
+        let had_key = matches!(self.storage.load_remembered_key(), Ok(Some(_)));
+
+        let _ = self.storage.clear_remembered_key();
+        let _ = self.keyring.delete_wrap_key();
+
+        if had_key {
+            let _ = self.storage.log(
+                LogAction::RememberMeCleared,
+                "Remembered master key removed from OS keyring".to_string(),
+                None,
+            );
+        }
+    }
+
+    fn relocate_sessions_directory(&self, new_dir: Option<&str>) -> Result<(), ZapError> {
+        let old_dir = get_sessions_directory()?;
+        let target_dir = match new_dir {
+            Some(path) => validate_sessions_directory(path)?,
+            None => {
+                let default_dir = get_default_sessions_directory()?;
+                std::fs::create_dir_all(&default_dir)?;
+                default_dir
+            }
+        };
+
+        let moved = migrate_session_files(
+            &old_dir,
+            &target_dir,
+            is_session_file,
+            session_activity_file_name,
+        )?;
+        set_sessions_directory_override(new_dir.map(|_| target_dir.clone()))?;
+
+        let _ = self.storage.log(
+            LogAction::MoveSessionsDirectory,
+            format!("Sessions directory changed ({} session files moved)", moved),
+            Some(format!("{} -> {}", old_dir.display(), target_dir.display())),
+        );
+
+        Ok(())
+    }
+
+    fn write_backup(&self, directory: &str, keep_last: usize) -> Result<PathBuf, ZapError> {
+        let dir = validate_backup_directory(directory)?;
+        let master_key = self.get_master_key()?;
+        let config = self
+            .auth
+            .get_config()
+            .ok_or(ZapError::AuthError("Auth not initialized".to_string()))?;
+        let (salt, kdf_params) = (config.salt, config.kdf_params);
+
+        let signing_key = self.export_signing_key(&master_key)?;
+        let vault_json = self
+            .import_export
+            .export_vault(&master_key, &signing_key, |secret, key| {
+                self.decrypt_secret_cached(secret, key)
+            })?;
+        let content = self
+            .import_export
+            .seal_backup(&vault_json, &master_key, &salt, kdf_params)?;
+
+        // Written under another name first, so sync clients never upload half a file
+        let file_name = format!(
+            "{}{}.json",
+            BACKUP_FILE_PREFIX,
+            chrono::Utc::now().format("%Y%m%d-%H%M%S")
+        );
+        let path = dir.join(file_name);
+        let partial = path.with_extension("partial");
+        std::fs::write(&partial, content)?;
+        std::fs::rename(&partial, &path)?;
+
+        prune_backups(&dir, BACKUP_FILE_PREFIX, keep_last)?;
+        Ok(path)
+    }
+
+    pub fn get_master_key(&self) -> Result<[u8; 32], ZapError> {
+        self.auth.get_master_key().ok_or(ZapError::SessionExpired)
+    }
+
+    /// Decrypt through the opt-in cache (Settings: decrypted_cache_seconds, 0 = off)
+    pub fn decrypt_secret_cached(
+        &self,
+        secret: &Secret,
+        box_key: &[u8; 32],
+    ) -> Result<String, ZapError> {
+        self.decrypt_variant_cached(secret, None, box_key)
+    }
+
+    /// Like decrypt_secret_cached, for the secret's value in `environment` when it has one
+    pub fn decrypt_variant_cached(
+        &self,
+        secret: &Secret,
+        environment: Option<&str>,
+        box_key: &[u8; 32],
+    ) -> Result<String, ZapError> {
+        if !self.decrypted_cache.lock().unwrap().is_enabled() {
+            return self.crypto.decrypt_secret_for(secret, environment, box_key);
+        }
+
+        // Every encryption has its own nonce, so variants never collide in the cache
+        let nonce = match environment.and_then(|env| secret.variants.get(env)) {
+            Some(data) => &data.nonce,
+            None => &secret.encrypted_value.nonce,
+        };
+        if let Some(value) = self.decrypted_cache.lock().unwrap().get(&secret.id, nonce) {
+            return Ok(value);
+        }
+
+        let value = self.crypto.decrypt_secret_for(secret, environment, box_key)?;
+        self.decrypted_cache
+            .lock()
+            .unwrap()
+            .insert(&secret.id, nonce, value.clone());
+        Ok(value)
+    }
+
+    /// The environment asked for, else Settings: default_environment (None = base values)
+    pub fn environment_or_default(
+        &self,
+        environment: Option<String>,
+    ) -> Result<Option<String>, ZapError> {
+        match environment {
+            Some(environment) => Ok(Some(environment)),
+            None => Ok(self.storage.load_settings()?.default_environment),
+        }
+    }
+
+    /// Data key for a box's secrets (the master key for boxes not yet migrated)
+    pub fn box_key(&self, box_item: &Box) -> Result<[u8; 32], ZapError> {
+        self.crypto.box_key(box_item, &self.get_master_key()?)
+    }
+
+    pub fn get_session_time_left(&self) -> u32 {
+        self.auth.get_session_time_left()
+    }
+
+    fn start_session_timer(&self) {
+        let mut timer_guard = self.session_timer.lock().unwrap();
+
+        if let Some(handle) = timer_guard.take() {
+            handle.abort();
+        }
+
+        let auth = Arc::clone(&self.auth);
+        let storage_clone = Arc::clone(&self.storage);
+        let cache_clone = Arc::clone(&self.decrypted_cache);
+        let undo_clone = Arc::clone(&self.undo_journal);
+        let proxy_clone = Arc::clone(&self.proxy_sessions);
+        let app_handle = self.app_handle.lock().unwrap().clone();
+        let warning_shown = Arc::clone(&self.lock_warning_shown);
+        let last_verified = Arc::clone(&self.last_verified);
+        let settings = self.storage.load_settings().unwrap_or_default();
+        let warning_seconds = settings.lock_warning_seconds;
+        let reauth_minutes = settings.reauth_minutes;
+        warning_shown.store(false, Ordering::SeqCst);
 
         let handle = tokio::spawn(async move {
             let mut interval = tokio::time::interval(Duration::from_secs(1));
+            interval.set_missed_tick_behavior(MissedTickBehavior::Skip);
+            // Wall clock, so time spent asleep counts towards the timeout
+            let mut counted_until = chrono::Utc::now();
+            let mut last_info: Option<SessionInfo> = None;
             loop {
                 interval.tick().await;
 
-                if let Ok(auth) = auth_clone.try_lock() {
-                    let was_unlocked = auth.is_unlocked();
+                let elapsed = (chrono::Utc::now() - counted_until).num_seconds().max(0);
+                counted_until += chrono::Duration::seconds(elapsed);
+
+                let was_unlocked = auth.is_unlocked();
+                for _ in 0..elapsed {
                     auth.tick_session();
-                    let still_unlocked = auth.is_unlocked();
-
-                    if was_unlocked && !still_unlocked {
-                        let _ = storage_clone.log(
-                            "Session_Expired".to_string(),
-                            "Session expired due to inactivity".to_string(),
-                            None,
-                        );
-                        break;
+                }
+                let still_unlocked = auth.is_unlocked();
+                let time_left = auth.get_session_time_left();
+
+                let info = SessionInfo {
+                    is_locked: !still_unlocked,
+                    time_left_seconds: time_left,
+                    phase: SessionPhase::of(
+                        !still_unlocked,
+                        time_left,
+                        warning_seconds,
+                        reauth_lapsed(&last_verified, reauth_minutes),
+                    ),
+                };
+                let changed = last_info
+                    .as_ref()
+                    .is_none_or(|last| info.is_transition_from(last, elapsed as u32));
+                if changed {
+                    if let Some(app) = &app_handle {
+                        let _ = app.emit(SESSION_STATE_EVENT, &info);
                     }
-
-                    if !still_unlocked {
-                        break;
+                }
+                last_info = Some(info);
+
+                // Warn once per countdown, re-arm when activity pushes it back up
+                if still_unlocked && warning_seconds > 0 {
+                    if time_left > warning_seconds {
+                        warning_shown.store(false, Ordering::SeqCst);
+                    } else if !warning_shown.swap(true, Ordering::SeqCst) {
+                        if let Some(app) = &app_handle {
+                            warn_session_expiring(app, time_left);
+                        }
                     }
-                } else {
-                    continue;
+                }
+
+                if was_unlocked && !still_unlocked {
+                    let _ = storage_clone.log(
+                        LogAction::SessionExpired,
+                        "Session expired due to inactivity".to_string(),
+                        None,
+                    );
+                    cache_clone.lock().unwrap().clear();
+                    undo_clone.lock().unwrap().clear();
+                    proxy_clone.lock().unwrap().clear();
+                    break;
+                }
+
+                if !still_unlocked {
+                    break;
                 }
             }
         });
@@ -722,6 +5081,7 @@ impl AppState {
     }
 
     fn stop_session_timer(&self) {
+        self.lock_warning_shown.store(false, Ordering::SeqCst);
         let mut timer_guard = self.session_timer.lock().unwrap();
         if let Some(handle) = timer_guard.take() {
             handle.abort();
@@ -729,8 +5089,131 @@ impl AppState {
     }
 }
 
-unsafe impl Send for AppState {}
-unsafe impl Sync for AppState {}
+// Process-wide settings that have to be in place before any command runs
+fn apply_stored_settings(storage: &StorageService, decrypted_cache: &Mutex<DecryptedCache>) {
+    // Apply the configured sessions directory before any session file is touched
+    if let Ok(settings) = storage.load_settings() {
+        let override_dir = settings.sessions_directory.as_deref().map(PathBuf::from);
+        if let Err(e) = set_sessions_directory_override(override_dir) {
+            tracing::error!("Failed to apply sessions directory: {}", e);
+        }
+        if let Err(e) = logging::apply_settings(&settings.file_logging) {
+            tracing::error!("Failed to start file logging: {}", e);
+        }
+        CryptoService::set_default_algorithm(settings.cipher);
+        decrypted_cache
+            .lock()
+            .unwrap()
+            .set_ttl(Duration::from_secs(settings.decrypted_cache_seconds as u64));
+    }
+    // Follows the stored marker both ways, a restored snapshot may predate the binding
+    match storage.secrets_bound() {
+        Ok(bound) => CryptoService::set_require_bound_secrets(bound),
+        Err(e) => tracing::error!("Failed to read the secret binding marker: {}", e),
+    }
+
+    // The moved databases opened, the copies they came from can go
+    match finish_data_directory_move() {
+        Ok(Some(previous)) => {
+            let _ = storage.log(
+                LogAction::MoveDataDirectory,
+                "Data directory moved".to_string(),
+                get_data_directory()
+                    .ok()
+                    .map(|dir| format!("{} -> {}", previous.display(), dir.display())),
+            );
+        }
+        Ok(None) => {}
+        Err(e) => tracing::error!("Failed to finish moving the data directory: {}", e),
+    }
+}
+
+// ", prod values" suffix for export log messages
+fn environment_note(environment: Option<&str>) -> String {
+    environment
+        .map(|environment| format!(", {} values", environment))
+        .unwrap_or_default()
+}
+
+// A replica record's box or secret name, for reports
+fn replica_record_name(ours: Option<&ReplicaRecord>, theirs: &ReplicaRecord) -> String {
+    ours.and_then(|record| record.content.as_ref())
+        .or(theirs.content.as_ref())
+        .map(|content| content.name().to_string())
+        .unwrap_or_else(|| "(deleted)".to_string())
+}
+
+// Whether sensitive actions need the password again (never when reauth_minutes is 0)
+fn reauth_lapsed(last_verified: &Mutex<Option<Instant>>, reauth_minutes: u32) -> bool {
+    if reauth_minutes == 0 {
+        return false;
+    }
+
+    let window = Duration::from_secs(reauth_minutes as u64 * 60);
+    !matches!(
+        *last_verified.lock().unwrap(),
+        Some(verified_at) if verified_at.elapsed() <= window
+    )
+}
+
+// Settings that loosen secret access, keep the vault unlocked longer or run code on our behalf
+// need a fresh password entry
+fn security_settings_changed(previous: &Settings, next: &Settings) -> bool {
+    // Hooks, replicas and mappings have no PartialEq, so compare their serialized form
+    fn differs<T: serde::Serialize>(a: &T, b: &T) -> bool {
+        serde_json::to_value(a).ok() != serde_json::to_value(b).ok()
+    }
+
+    previous.clipboard_only_reveal != next.clipboard_only_reveal
+        || previous.remember_me != next.remember_me
+        || previous.max_session_minutes != next.max_session_minutes
+        || previous.password_timeout_minutes != next.password_timeout_minutes
+        || previous.sessions_directory != next.sessions_directory
+        || previous.reveal_limit_per_minute != next.reveal_limit_per_minute
+        || previous.reauth_minutes != next.reauth_minutes
+        || previous.decrypted_cache_seconds != next.decrypted_cache_seconds
+        || previous.trusted_signing_keys != next.trusted_signing_keys
+        || previous.app_access != next.app_access
+        || differs(&previous.hooks, &next.hooks)
+        || differs(&previous.replicas, &next.replicas)
+        || differs(&previous.sync_mappings, &next.sync_mappings)
+}
+
+// Event for an open window, plus a notification in case the app is hidden
+fn prompt_app_access(app: &AppHandle, request: &AppAccessRequest) {
+    let _ = app.emit(APP_ACCESS_EVENT, request);
+
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title("Zap - access request")
+        .body(format!(
+            "{} wants the secrets of '{}' - open Zap to allow or deny",
+            request.exe_path, request.session_name
+        ))
+        .show()
+    {
+        tracing::warn!("Failed to show access request: {}", e);
+    }
+}
+
+// Desktop notification plus an event so an open window can offer "extend"
+fn warn_session_expiring(app: &AppHandle, seconds_left: u32) {
+    let _ = app.emit(SESSION_EXPIRING_EVENT, seconds_left);
+
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title("Zap")
+        .body(format!(
+            "Vault locks in {} seconds — click to extend",
+            seconds_left
+        ))
+        .show()
+    {
+        tracing::warn!("Failed to show lock warning: {}", e);
+    }
+}
 
 impl Drop for AppState {
     fn drop(&mut self) {
@@ -738,9 +5221,10 @@ impl Drop for AppState {
 
         // Log app shutdown
         let _ = self.storage.log(
-            "App_Shutdown".to_string(),
+            LogAction::AppShutdown,
             "Application shutdown".to_string(),
             None,
         );
+        let _ = self.storage.flush();
     }
 }
@@ -1,14 +1,39 @@
 // src/states/dev_state.rs
 
-use crate::models::{ActiveSessionInfo, DevSession, DevStats, ZapError};
+use crate::logging::redact;
+use crate::models::project_model::{
+    envrc_block, ENVRC_BLOCK_END, ENVRC_BLOCK_START, ENVRC_FILE_NAME, ENV_FILE_NAME,
+    PROJECT_FILE_NAME,
+};
+use crate::models::dev_model::{
+    is_session_file, session_activity_file_name, SESSION_ACTIVITY_EXTENSION,
+    SESSION_FILE_FORMAT_VERSION,
+};
+use crate::models::proxy_model::MAX_PROXY_SESSION_MINUTES;
+use crate::models::{
+    ActiveSessionInfo, Box, CliSessionFile, DevSession, DevStats, EnvHygieneReport, EnvShredResult,
+    HookEvent, LogAction, MultilineFormat, ProdApproval, ProjectBinding, ProxySessionToken,
+    RenderedTemplate, SecretSelection, SessionFileCompatibility, SessionFileSchema,
+    SessionFileStatus, SshConfigExport, TemplateMode, ZapError,
+};
+use crate::models::secret_model::env_var_name;
 use crate::services::DevService;
 use crate::states::AppState;
 use chrono::{DateTime, Utc};
-use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
-use std::path::PathBuf;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
 use std::sync::Arc;
+use crate::utils::env_parser::{escape_env_value, parse_env};
+use crate::utils::input_validation::name_key;
+use crate::utils::path_resolvers::get_cli_schema_path;
+use crate::utils::proxy_sessions::ProxySession;
 use crate::utils::path_resolvers::get_sessions_directory as get_shared_sessions_directory;
+use crate::utils::secret_template::render_template;
+use crate::utils::ssh_config::{
+    collect_hosts, render_host, SSH_CONFIG_FILE_NAME, SSH_EXPORT_DIRECTORY,
+};
+use ts_rs::TS;
 
 
 pub struct DevState {
@@ -16,15 +41,6 @@ pub struct DevState {
     pub(crate) app_state: Arc<AppState>,
 }
 
-#[derive(Serialize, Deserialize)]
-struct CliSessionFile {
-    pub session_name: String,
-    pub box_name: String,
-    pub session_key: String,
-    pub encrypted_secrets: HashMap<String, String>,
-    pub created_at: DateTime<Utc>,
-}
-
 impl DevState {
     pub fn new(app_state: Arc<AppState>) -> Self {
         Self {
@@ -33,23 +49,37 @@ impl DevState {
         }
     }
 
-    // Create session - writes to both database and session file for CLI
+    // Create session - writes to both database and session file for CLI.
+    // Secrets with a variant for the environment (or Settings: default_environment) use it.
+    // The selection narrows which of the box's secrets are copied.
     pub async fn create_session(
         &self,
         session_name: String,
         box_id: String,
+        environment: Option<String>,
+        selection: SecretSelection,
+        approval: Option<ProdApproval>,
     ) -> Result<(), ZapError> {
-        let master_key = self.app_state.get_master_key()?;
         let box_item = self.app_state.storage.get_box(&box_id)?;
+        self.approve_prod_session(&session_name, &box_item, approval)?;
+        let box_key = self.app_state.box_key(&box_item)?;
         let box_secrets = self.app_state.storage.get_secrets_by_box_id(&box_id)?;
+        let box_secrets = self.dev_service.select_secrets(box_secrets, &selection)?;
+        let environment = self.app_state.environment_or_default(environment)?;
 
         let session = self.dev_service.create_session_from_box(
             session_name.clone(),
             &box_item,
             &box_secrets,
-            &master_key,
+            |secret| {
+                self.app_state
+                    .decrypt_variant_cached(secret, environment.as_deref(), &box_key)
+            },
         )?;
 
+        // Refuse before saving anything if the installed CLI couldn't read the file
+        self.ensure_cli_can_read_sessions()?;
+
         // Save to database first
         self.app_state
             .storage
@@ -59,14 +89,125 @@ impl DevState {
         self.write_session_file_for_cli(&session)?;
 
         let _ = self.app_state.storage.log(
-            "Create_Dev_Session".to_string(),
+            LogAction::CreateDevSession,
             format!(
                 "Dev session '{}' created from box '{}'",
                 session_name, box_item.name
             ),
-            None,
+            environment.map(|environment| format!("environment: {}", environment)),
+        );
+
+        self.app_state.fire_hook(
+            HookEvent::SessionStarted,
+            &[("box_name", &box_item.name), ("session_name", &session_name)],
+        );
+
+        Ok(())
+    }
+
+    // Proxy session - like create_session, but nothing is written to disk. The CLI gets a
+    // bearer token and fetches values from the app while it stays unlocked.
+    pub async fn create_proxy_session(
+        &self,
+        session_name: String,
+        box_id: String,
+        environment: Option<String>,
+        ttl_minutes: u32,
+        selection: SecretSelection,
+        approval: Option<ProdApproval>,
+    ) -> Result<ProxySessionToken, ZapError> {
+        if ttl_minutes == 0 || ttl_minutes > MAX_PROXY_SESSION_MINUTES {
+            return Err(ZapError::ValidationError(format!(
+                "Proxy sessions must last between 1 and {} minutes",
+                MAX_PROXY_SESSION_MINUTES
+            )));
+        }
+
+        let box_item = self.app_state.storage.get_box(&box_id)?;
+        self.approve_prod_session(&session_name, &box_item, approval)?;
+        let box_key = self.app_state.box_key(&box_item)?;
+        let box_secrets = self.app_state.storage.get_secrets_by_box_id(&box_id)?;
+        let box_secrets = self.dev_service.select_secrets(box_secrets, &selection)?;
+        let environment = self.app_state.environment_or_default(environment)?;
+
+        let session = self.dev_service.create_session_from_box(
+            session_name.clone(),
+            &box_item,
+            &box_secrets,
+            |secret| {
+                self.app_state
+                    .decrypt_variant_cached(secret, environment.as_deref(), &box_key)
+            },
+        )?;
+
+        // Random like a session key, but only ever used to look the session up
+        let token = hex::encode(self.dev_service.generate_session_key());
+        let expires_at = chrono::Utc::now() + chrono::Duration::minutes(ttl_minutes as i64);
+        let secrets_count = session.encrypted_secrets.len();
+        self.app_state.add_proxy_session(
+            token.clone(),
+            ProxySession {
+                session_name: session_name.clone(),
+                box_name: box_item.name.clone(),
+                session_key: session.session_key,
+                encrypted_secrets: session.encrypted_secrets,
+                expires_at,
+                fetch_count: 0,
+            },
+        )?;
+
+        let _ = self.app_state.storage.log(
+            LogAction::CreateProxySession,
+            format!(
+                "Proxy session '{}' created from box '{}'",
+                session_name, box_item.name
+            ),
+            Some(format!("expires in {} minutes", ttl_minutes)),
+        );
+
+        self.app_state.fire_hook(
+            HookEvent::SessionStarted,
+            &[("box_name", &box_item.name), ("session_name", &session_name)],
         );
 
+        Ok(ProxySessionToken {
+            session_name,
+            box_name: box_item.name,
+            token,
+            secrets_count,
+            expires_at,
+        })
+    }
+
+    // Settings: require_prod_session_approval - friction before prod values land on disk.
+    // The justification goes to the audit log, a wrong password stops here.
+    fn approve_prod_session(
+        &self,
+        session_name: &str,
+        box_item: &Box,
+        approval: Option<ProdApproval>,
+    ) -> Result<(), ZapError> {
+        let settings = self.app_state.storage.load_settings()?;
+        if !settings.require_prod_session_approval || !box_item.is_prod() {
+            return Ok(());
+        }
+
+        let Some(approval) = approval else {
+            return Err(ZapError::ProdApprovalRequired(box_item.name.clone()));
+        };
+        if approval.justification.trim().is_empty() {
+            return Err(ZapError::ProdApprovalRequired(box_item.name.clone()));
+        }
+        self.app_state.verify_password(&approval.password)?;
+
+        let _ = self.app_state.storage.log(
+            LogAction::ApproveProdSession,
+            format!(
+                "Prod access approved for dev session '{}' from box '{}'",
+                session_name, box_item.name
+            ),
+            Some(format!("justification: {}", approval.justification.trim())),
+        );
         Ok(())
     }
 
@@ -92,9 +233,58 @@ impl DevState {
         self.remove_session_file_for_cli(&session_name)?;
 
         let _ = self.app_state.storage.log(
-            "Stop_Dev_Session".to_string(),
+            LogAction::StopDevSession,
             format!("Dev session '{}' stopped", session_name),
-            box_name,
+            box_name.clone(),
+        );
+
+        self.app_state.fire_hook(
+            HookEvent::SessionStopped,
+            &[
+                ("box_name", box_name.as_deref().unwrap_or_default()),
+                ("session_name", &session_name),
+            ],
+        );
+
+        Ok(())
+    }
+
+    // Rename a session in place. The session key and secrets stay the same, so shells that
+    // already loaded it keep working. Project files naming the old session aren't updated.
+    pub async fn rename_session(&self, old_name: String, new_name: String) -> Result<(), ZapError> {
+        self.validate_session_name(&new_name)?;
+        let storage = &self.app_state.storage;
+        let mut session = storage
+            .get_dev_session_by_name(&old_name)?
+            .ok_or_else(|| ZapError::session_not_found(&old_name))?;
+        if old_name == new_name {
+            return Ok(());
+        }
+        if storage.session_exists(&new_name)? || self.session_file_exists(&new_name) {
+            return Err(ZapError::session_already_exists(&new_name));
+        }
+        self.ensure_cli_can_read_sessions()?;
+
+        session.session_name = new_name.clone();
+        storage.save_dev_session_by_name(&new_name, &session)?;
+
+        // The new file lands complete before the old one goes, so the CLI always finds one
+        if let Err(e) = self.write_session_file_for_cli(&session) {
+            let _ = storage.delete_dev_session_by_name(&new_name);
+            return Err(e);
+        }
+        let sessions_dir = self.get_sessions_directory()?;
+        let _ = std::fs::rename(
+            sessions_dir.join(session_activity_file_name(&old_name)),
+            sessions_dir.join(session_activity_file_name(&new_name)),
+        );
+        storage.delete_dev_session_by_name(&old_name)?;
+        self.remove_session_file_for_cli(&old_name)?;
+
+        let _ = storage.log(
+            LogAction::RenameDevSession,
+            format!("Dev session '{}' renamed to '{}'", old_name, new_name),
+            Some(session.box_name.clone()),
         );
 
         Ok(())
@@ -113,7 +303,7 @@ impl DevState {
         self.clear_all_session_files()?;
 
         let _ = self.app_state.storage.log(
-            "Clear_All_Dev_Sessions".to_string(),
+            LogAction::ClearAllDevSessions,
             format!("Cleared {} dev sessions", session_count),
             Some(session_names.join(", ")),
         );
@@ -124,21 +314,23 @@ impl DevState {
     // Smart session listing - checks both database and file existence
     pub async fn get_all_sessions(&self) -> Result<Vec<ActiveSessionInfo>, ZapError> {
         let db_sessions = self.app_state.storage.get_all_dev_sessions()?;
+        let lifetime_hours = self.dev_session_hours();
         let mut active_sessions = Vec::new();
         let mut cleanup_needed = Vec::new();
+        let mut expired = Vec::new();
 
         for (session_name, session) in db_sessions {
+            if session.is_expired(lifetime_hours) {
+                expired.push(session_name);
+                continue;
+            }
+
             // Check if the session file actually exists on disk
             let file_exists = self.session_file_exists(&session_name);
 
             if file_exists {
                 // Session is truly active (both DB entry and file exist)
-                active_sessions.push(ActiveSessionInfo {
-                    session_name: session.session_name.clone(), // Clone to avoid move issues
-                    box_name: session.box_name.clone(),         // Clone to avoid move issues
-                    secrets_count: session.secrets_count(),
-                    is_active: true,
-                });
+                active_sessions.push(self.session_info(&session, lifetime_hours));
             } else {
                 // Session file missing - CLI must have deleted it, mark for cleanup
                 cleanup_needed.push(session_name);
@@ -147,16 +339,147 @@ impl DevState {
 
         // Clean up orphaned database entries where files were deleted by CLI
         for session_name in cleanup_needed {
-            println!("Cleaning up orphaned session: {}", session_name);
+            tracing::info!("Cleaning up orphaned session {}", redact(&session_name));
             let _ = self
                 .app_state
                 .storage
                 .delete_dev_session_by_name(&session_name);
         }
 
+        for session_name in expired {
+            self.stop_expired_session(session_name).await;
+        }
+
         Ok(active_sessions)
     }
 
+    // Sessions past the configured lifetime are stopped like any other
+    async fn stop_expired_session(&self, session_name: String) {
+        let _ = self.app_state.storage.log(
+            LogAction::DevSessionExpired,
+            format!("Dev session '{}' reached its lifetime and was stopped", session_name),
+            None,
+        );
+        let _ = self.stop_session(session_name).await;
+    }
+
+    // Stop sessions the CLI hasn't loaded for longer than Settings: dev_session_idle_hours.
+    // Returns how many were stopped.
+    pub async fn reap_idle_sessions(&self) -> Result<usize, ZapError> {
+        let idle_hours = self.dev_session_idle_hours();
+        if idle_hours == 0 {
+            return Ok(0);
+        }
+
+        let idle_limit = idle_hours as u64 * 3600;
+        let mut reaped = 0;
+        for (session_name, session) in self.app_state.storage.get_all_dev_sessions()? {
+            let idle_seconds = self.idle_seconds(&session, self.session_last_used(&session_name));
+            if idle_seconds < idle_limit {
+                continue;
+            }
+
+            let _ = self.app_state.storage.log(
+                LogAction::DevSessionIdle,
+                format!(
+                    "Dev session '{}' went unused for {} hours and was stopped",
+                    session_name, idle_hours
+                ),
+                Some(session.box_name.clone()),
+            );
+            if self.stop_session(session_name).await.is_ok() {
+                reaped += 1;
+            }
+        }
+        Ok(reaped)
+    }
+
+    fn session_info(&self, session: &DevSession, lifetime_hours: u32) -> ActiveSessionInfo {
+        let last_used_at = self.session_last_used(&session.session_name);
+        ActiveSessionInfo {
+            session_name: session.session_name.clone(),
+            box_id: session.box_id.clone(),
+            box_name: session.box_name.clone(),
+            secrets_count: session.secrets_count(),
+            is_active: true,
+            created_at: session.created_at,
+            expires_at: session.expires_at(lifetime_hours),
+            last_used_at,
+            idle_seconds: self.idle_seconds(session, last_used_at),
+            is_stale: self.is_session_stale(session),
+        }
+    }
+
+    // When the CLI last loaded the session (None if it never has)
+    fn session_last_used(&self, session_name: &str) -> Option<DateTime<Utc>> {
+        let path = self
+            .get_sessions_directory()
+            .ok()?
+            .join(session_activity_file_name(session_name));
+        let seconds = std::fs::read_to_string(path).ok()?.trim().parse().ok()?;
+        DateTime::from_timestamp(seconds, 0)
+    }
+
+    // Sessions never used count as idle since creation (0 when that's unknown too)
+    fn idle_seconds(&self, session: &DevSession, last_used_at: Option<DateTime<Utc>>) -> u64 {
+        last_used_at
+            .max(session.created_at)
+            .map(|since| (Utc::now() - since).num_seconds().max(0) as u64)
+            .unwrap_or(0)
+    }
+
+    // Source box (or one of its secrets) changed after the session copied it
+    fn is_session_stale(&self, session: &DevSession) -> bool {
+        let Some(created_at) = session.created_at else {
+            return false; // Unknown creation time, can't tell
+        };
+
+        let storage = &self.app_state.storage;
+        let Ok(box_item) = storage.get_box(&session.box_id) else {
+            return true; // Source box was deleted
+        };
+        if box_item.updated_at > created_at {
+            return true;
+        }
+
+        storage
+            .list_secrets_by_box_id(&session.box_id)
+            .map(|secrets| secrets.iter().any(|secret| secret.updated_at > created_at))
+            .unwrap_or(false)
+    }
+
+    fn dev_session_hours(&self) -> u32 {
+        self.app_state
+            .storage
+            .load_settings()
+            .map(|settings| settings.dev_session_hours)
+            .unwrap_or(0)
+    }
+
+    fn dev_session_idle_hours(&self) -> u32 {
+        self.app_state
+            .storage
+            .load_settings()
+            .map(|settings| settings.dev_session_idle_hours)
+            .unwrap_or(0)
+    }
+
+    // Session file formats this build writes and reads, next to the paired CLI's
+    pub async fn get_session_file_schema(&self) -> Result<SessionFileCompatibility, ZapError> {
+        let desktop = SessionFileSchema::current();
+        let cli = self.paired_cli_schema();
+        let compatible = match &cli {
+            Some(cli) => cli.can_read(desktop.format_version),
+            None => true,
+        };
+
+        Ok(SessionFileCompatibility {
+            desktop,
+            cli,
+            compatible,
+        })
+    }
+
     // Smart session info - checks both database and file
     pub async fn get_session_info(
         &self,
@@ -167,18 +490,18 @@ impl DevState {
             .storage
             .get_dev_session_by_name(session_name)?
         {
+            let lifetime_hours = self.dev_session_hours();
+            if session.is_expired(lifetime_hours) {
+                self.stop_expired_session(session_name.to_string()).await;
+                return Ok(None);
+            }
+
             // Check if session file actually exists on disk
             if self.session_file_exists(session_name) {
-                // Clone fields instead of moving them so we can still call methods on session
-                Ok(Some(ActiveSessionInfo {
-                    session_name: session.session_name.clone(),
-                    box_name: session.box_name.clone(),
-                    secrets_count: session.secrets_count(),
-                    is_active: true,
-                }))
+                Ok(Some(self.session_info(&session, lifetime_hours)))
             } else {
                 // Session file missing - clean up the database entry
-                println!("Cleaning up orphaned session: {}", session_name);
+                tracing::info!("Cleaning up orphaned session {}", redact(&session_name));
                 let _ = self
                     .app_state
                     .storage
@@ -190,6 +513,184 @@ impl DevState {
         }
     }
 
+    // Render a session as .env using its own key - no vault access needed
+    pub async fn export_session_as_env(
+        &self,
+        session_name: &str,
+        prefix: Option<&str>,
+    ) -> Result<String, ZapError> {
+        let session = self
+            .app_state
+            .storage
+            .get_dev_session_by_name(session_name)?
+            .ok_or_else(|| ZapError::SessionNotFound(session_name.to_string()))?;
+
+        let mut env_content = String::new();
+        env_content.push_str(&format!("# Session: {}\n", session.session_name));
+        env_content.push_str(&format!("# Box: {}\n", session.box_name));
+        env_content.push_str(&format!(
+            "# Exported: {}\n\n",
+            chrono::Utc::now().format("%Y-%m-%d %H:%M UTC")
+        ));
+
+        // Each secret's .env style from the vault (metadata only), escaped if the box is gone
+        let multiline_formats: HashMap<String, MultilineFormat> = self
+            .app_state
+            .storage
+            .list_secrets_by_box_id(&session.box_id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|secret| (secret.name, secret.multiline_format))
+            .collect();
+
+        let mut secret_names: Vec<&String> = session.encrypted_secrets.keys().collect();
+        secret_names.sort();
+
+        for secret_name in secret_names {
+            let value = self.dev_service.decrypt_secret_for_cli(
+                &session.encrypted_secrets[secret_name],
+                &session.session_key,
+            )?;
+            let multiline = multiline_formats
+                .get(secret_name)
+                .copied()
+                .unwrap_or_default();
+            env_content.push_str(&format!(
+                "{}={}\n",
+                env_var_name(secret_name, prefix),
+                escape_env_value(&value, multiline)
+            ));
+        }
+
+        let _ = self.app_state.storage.log(
+            LogAction::ExportDevSession,
+            format!(
+                "Exported dev session '{}' as .env ({} secrets)",
+                session.session_name,
+                session.secrets_count()
+            ),
+            prefix.map(str::to_string),
+        );
+
+        Ok(env_content)
+    }
+
+    // ssh_config Host blocks for the session's host credentials (see utils::ssh_config), with
+    // private keys written as 0600 files next to them. All of it goes when the session stops.
+    pub async fn export_session_as_ssh_config(
+        &self,
+        session_name: &str,
+    ) -> Result<SshConfigExport, ZapError> {
+        let session = self
+            .app_state
+            .storage
+            .get_dev_session_by_name(session_name)?
+            .ok_or_else(|| ZapError::SessionNotFound(session_name.to_string()))?;
+
+        let mut values = BTreeMap::new();
+        for (secret_name, data) in &session.encrypted_secrets {
+            let value = self
+                .dev_service
+                .decrypt_secret_for_cli(data, &session.session_key)?;
+            values.insert(secret_name.clone(), value);
+        }
+        let hosts = collect_hosts(&session.box_name, &values)?;
+        if hosts.is_empty() {
+            return Err(ZapError::ValidationError(
+                "No host credentials in this session - name secrets like SERVER_HOST, \
+                 SERVER_USER and SERVER_SSH_KEY"
+                    .to_string(),
+            ));
+        }
+
+        // Start from an empty folder so keys of hosts removed since last time don't linger
+        let ssh_dir = self.ssh_export_directory(session_name)?;
+        remove_ssh_export(&ssh_dir);
+        create_private_dir(&ssh_dir)?;
+
+        let mut config = format!(
+            "# Session: {}\n# Box: {}\n\n",
+            session.session_name, session.box_name
+        );
+        let mut key_files = 0;
+        for host in &hosts {
+            let identity_file = match &host.private_key {
+                Some(key) => {
+                    // OpenSSH refuses keys without a final newline
+                    let mut key = key.replace("\r\n", "\n").trim_end().to_string();
+                    key.push('\n');
+                    let path = ssh_dir.join(format!("{}.key", host.alias));
+                    write_private_file(&path, &key)?;
+                    key_files += 1;
+                    Some(path)
+                }
+                None => None,
+            };
+            config.push_str(&render_host(host, identity_file.as_deref()));
+            config.push('\n');
+        }
+
+        let config_path = ssh_dir.join(SSH_CONFIG_FILE_NAME);
+        write_private_file(&config_path, &config)?;
+
+        let _ = self.app_state.storage.log(
+            LogAction::ExportDevSession,
+            format!(
+                "Exported dev session '{}' as ssh_config ({} hosts)",
+                session.session_name,
+                hosts.len()
+            ),
+            Some(format!("{} key files", key_files)),
+        );
+
+        Ok(SshConfigExport {
+            config,
+            config_path: config_path.display().to_string(),
+            hosts: hosts.into_iter().map(|host| host.alias).collect(),
+            key_files,
+        })
+    }
+
+    // Fill `{{secret:Box/Secret}}` placeholders from a session - only its own box resolves
+    pub async fn render_template_from_session(
+        &self,
+        session_name: &str,
+        template: &str,
+        mode: TemplateMode,
+    ) -> Result<RenderedTemplate, ZapError> {
+        let session = self
+            .app_state
+            .storage
+            .get_dev_session_by_name(session_name)?
+            .ok_or_else(|| ZapError::SessionNotFound(session_name.to_string()))?;
+
+        let rendered = render_template(template, mode, |box_name, secret_name| {
+            if name_key(box_name) != name_key(&session.box_name) {
+                return Ok(None);
+            }
+            let secret = session
+                .encrypted_secrets
+                .iter()
+                .find(|(name, _)| name_key(name) == name_key(secret_name));
+            match secret {
+                Some((_, encrypted_data)) => Ok(Some(
+                    self.dev_service
+                        .decrypt_secret_for_cli(encrypted_data, &session.session_key)?,
+                )),
+                None => Ok(None),
+            }
+        })?;
+
+        let _ = self.app_state.storage.log(
+            LogAction::RenderTemplate,
+            format!("Rendered a template from dev session '{}'", session.session_name),
+            (!rendered.missing.is_empty())
+                .then(|| format!("Unresolved: {}", rendered.missing.join(", "))),
+        );
+
+        Ok(rendered)
+    }
+
     // Smart session count - only counts sessions with both DB entry and file
     pub async fn has_any_sessions(&self) -> Result<bool, ZapError> {
         let sessions = self.get_all_sessions().await?;
@@ -212,6 +713,67 @@ impl DevState {
         }
     }
 
+    // Reconcile a single session file after an external change (called by the session watcher)
+    pub async fn reconcile_session_file(
+        &self,
+        session_name: &str,
+    ) -> Result<SessionFileStatus, ZapError> {
+        let db_session = self
+            .app_state
+            .storage
+            .get_dev_session_by_name(session_name)?;
+        let file_exists = self.session_file_exists(session_name);
+
+        let status = match (db_session, file_exists) {
+            (Some(_), false) => {
+                // CLI (or user) deleted the file - drop the database entry and what's left
+                self.app_state
+                    .storage
+                    .delete_dev_session_by_name(session_name)?;
+                self.remove_session_file_for_cli(session_name)?;
+
+                let _ = self.app_state.storage.log(
+                    LogAction::DevSessionRemovedExternally,
+                    format!("Dev session '{}' file was removed outside the app", session_name),
+                    None,
+                );
+                SessionFileStatus::Removed
+            }
+            (Some(session), true) => {
+                if self.session_file_matches(&session)? {
+                    SessionFileStatus::Valid
+                } else {
+                    // Contents changed outside the app - revoke the session
+                    self.app_state
+                        .storage
+                        .delete_dev_session_by_name(session_name)?;
+                    self.remove_session_file_for_cli(session_name)?;
+
+                    let _ = self.app_state.storage.log(
+                        LogAction::DevSessionTampered,
+                        format!(
+                            "Dev session '{}' file was modified outside the app and has been revoked",
+                            session_name
+                        ),
+                        Some(session.box_name),
+                    );
+                    SessionFileStatus::Tampered
+                }
+            }
+            (None, true) => {
+                let _ = self.app_state.storage.log(
+                    LogAction::DevSessionUntracked,
+                    format!("Unknown session file '{}.json' found", session_name),
+                    None,
+                );
+                SessionFileStatus::Untracked
+            }
+            (None, false) => SessionFileStatus::Removed,
+        };
+
+        Ok(status)
+    }
+
     // Get available boxes that can be used for dev sessions
     pub async fn get_available_dev_boxes(&self) -> Result<Vec<DevBoxInfo>, ZapError> {
         let all_boxes = self.app_state.storage.get_all_boxes()?;
@@ -233,13 +795,12 @@ impl DevState {
 
     // Get development statistics
     pub async fn get_dev_stats(&self) -> Result<DevStats, ZapError> {
-        let vault_stats = self.app_state.storage.get_vault_stats()?;
+        let vault_stats = self.app_state.stats.get_vault_stats()?;
         let active_sessions = self.get_all_sessions().await?; // Uses smart checking
 
         Ok(DevStats {
             total_boxes: vault_stats.total_boxes,
             dev_boxes: vault_stats.dev_boxes,
-            secure_boxes: vault_stats.secure_boxes,
             total_secrets: vault_stats.total_secrets,
             active_sessions_count: active_sessions.len(), // Real active count
         })
@@ -262,26 +823,337 @@ impl DevState {
         self.dev_service.validate_session_key(session_key_hex)
     }
 
+    // Project Binding Operations
+
+    // Bind a project directory to a box - writes zap.json into the directory
+    pub async fn bind_project(
+        &self,
+        project_path: String,
+        box_id: String,
+        session_name: String,
+    ) -> Result<ProjectBinding, ZapError> {
+        let project_dir = self.resolve_project_directory(&project_path)?;
+        self.validate_session_name(&session_name)?;
+
+        let box_item = self.app_state.storage.get_box(&box_id)?;
+        if !box_item.dev_mode {
+            return Err(ZapError::InvalidDevBox(
+                "Box is not enabled for dev mode".to_string(),
+            ));
+        }
+
+        let binding = ProjectBinding::new(box_item.id, box_item.name.clone(), session_name);
+        self.write_project_file(&project_dir, &binding)?;
+
+        let _ = self.app_state.storage.log(
+            LogAction::BindProject,
+            format!(
+                "Project directory bound to box '{}' (session '{}')",
+                box_item.name, binding.default_session_name
+            ),
+            Some(project_dir.display().to_string()),
+        );
+
+        Ok(binding)
+    }
+
+    // Remove the zap.json binding from a project directory
+    pub async fn unbind_project(&self, project_path: String) -> Result<(), ZapError> {
+        let project_dir = self.resolve_project_directory(&project_path)?;
+        let binding = self.read_project_file(&project_dir)?;
+
+        std::fs::remove_file(project_dir.join(PROJECT_FILE_NAME))?;
+        self.remove_envrc_block(&project_dir)?;
+
+        let _ = self.app_state.storage.log(
+            LogAction::UnbindProject,
+            format!("Project directory unbound from box '{}'", binding.box_name),
+            Some(project_dir.display().to_string()),
+        );
+
+        Ok(())
+    }
+
+    // Read the binding for a project directory (None if the directory isn't bound)
+    pub async fn get_project_binding(
+        &self,
+        project_path: &str,
+    ) -> Result<Option<ProjectBinding>, ZapError> {
+        let project_dir = self.resolve_project_directory(project_path)?;
+        if !project_dir.join(PROJECT_FILE_NAME).exists() {
+            return Ok(None);
+        }
+
+        self.read_project_file(&project_dir).map(Some)
+    }
+
+    // Create (or reuse) the default session for a bound project directory
+    pub async fn create_session_for_project(
+        &self,
+        project_path: String,
+    ) -> Result<String, ZapError> {
+        let project_dir = self.resolve_project_directory(&project_path)?;
+        let mut binding = self.read_project_file(&project_dir)?;
+        let session_name = binding.default_session_name.clone();
+
+        let existing = self
+            .app_state
+            .storage
+            .get_dev_session_by_name(&session_name)?;
+
+        match existing {
+            Some(session)
+                if session.box_id == binding.box_id && self.session_file_exists(&session_name) =>
+            {
+                // Session already running for this project - reuse it
+            }
+            Some(_) if self.session_file_exists(&session_name) => {
+                return Err(ZapError::session_already_exists(&session_name));
+            }
+            _ => {
+                // No way to ask for approval here, prod boxes need a session started in the app
+                self.create_session(
+                    session_name.clone(),
+                    binding.box_id.clone(),
+                    None,
+                    SecretSelection::default(),
+                    None,
+                )
+                .await?;
+            }
+        }
+
+        // Point the CLI at the project session
+        let session = self
+            .app_state
+            .storage
+            .get_dev_session_by_name(&session_name)?
+            .ok_or_else(|| ZapError::session_not_found(&session_name))?;
+
+        // Same env var names the CLI writes on `zap use`
+        let mut env_names: Vec<String> = self
+            .app_state
+            .storage
+            .list_secrets_by_box_id(&session.box_id)?
+            .iter()
+            .filter(|secret| session.encrypted_secrets.contains_key(&secret.name))
+            .map(|secret| secret.to_env_var_name(None))
+            .collect();
+        env_names.sort();
+
+        binding.box_name = session.box_name.clone();
+        binding.current_session = session_name.clone();
+        binding.available_secrets = env_names;
+        self.write_project_file(&project_dir, &binding)?;
+
+        Ok(session_name)
+    }
+
+    // Start the project session and add the direnv hook to the project's .envrc
+    pub async fn write_envrc(&self, project_path: String) -> Result<String, ZapError> {
+        let project_dir = self.resolve_project_directory(&project_path)?;
+        let session_name = self.create_session_for_project(project_path).await?;
+
+        let envrc_path = project_dir.join(ENVRC_FILE_NAME);
+        let existing = match std::fs::read_to_string(&envrc_path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut content = strip_envrc_block(&existing);
+        if !content.is_empty() && !content.ends_with('\n') {
+            content.push('\n');
+        }
+        content.push_str(&envrc_block());
+        content.push('\n');
+
+        let temp_path = envrc_path.with_extension("tmp");
+        std::fs::write(&temp_path, content)?;
+        std::fs::rename(&temp_path, &envrc_path)?;
+
+        let _ = self.app_state.storage.log(
+            LogAction::WriteEnvrc,
+            format!("direnv hook written for session '{}'", session_name),
+            Some(project_dir.display().to_string()),
+        );
+
+        Ok(envrc_path.display().to_string())
+    }
+
+    // Whether a bound project still has a loose .env, and whether git would pick it up
+    pub async fn check_env_hygiene(
+        &self,
+        project_path: &str,
+    ) -> Result<EnvHygieneReport, ZapError> {
+        let project_dir = self.resolve_project_directory(project_path)?;
+        self.read_project_file(&project_dir)?;
+
+        let env_path = project_dir.join(ENV_FILE_NAME);
+        let variable_count = match std::fs::read_to_string(&env_path) {
+            Ok(content) => parse_env(&content, |_| None).map_or(0, |parsed| parsed.vars.len()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => 0,
+            Err(e) => return Err(e.into()),
+        };
+        let git_repository = project_dir.ancestors().any(|dir| dir.join(".git").exists());
+        let gitignored = if git_repository {
+            Some(env_file_gitignored(&project_dir).await)
+        } else {
+            None
+        };
+
+        Ok(EnvHygieneReport {
+            env_path: env_path.display().to_string(),
+            env_file_exists: env_path.is_file(),
+            git_repository,
+            gitignored,
+            variable_count,
+        })
+    }
+
+    // Move a bound project's .env into its box, then overwrite and delete the file.
+    // The file is only shredded when every variable was imported.
+    pub async fn import_and_shred_env(
+        &self,
+        project_path: String,
+    ) -> Result<EnvShredResult, ZapError> {
+        let project_dir = self.resolve_project_directory(&project_path)?;
+        let binding = self.read_project_file(&project_dir)?;
+
+        let env_path = project_dir.join(ENV_FILE_NAME);
+        if !env_path.is_file() {
+            return Err(ZapError::ValidationError(format!(
+                "No {} file in {}",
+                ENV_FILE_NAME,
+                project_dir.display()
+            )));
+        }
+        let content = std::fs::read_to_string(&env_path)?;
+
+        let import = self
+            .app_state
+            .import_env_to_box(&content, binding.box_id.clone(), false)
+            .await?;
+        if import.has_errors() {
+            return Ok(EnvShredResult {
+                import,
+                shredded: false,
+            });
+        }
+
+        shred_file(&env_path)?;
+
+        let _ = self.app_state.storage.log(
+            LogAction::ShredEnvFile,
+            format!(
+                "Imported and shredded {} for box '{}'",
+                ENV_FILE_NAME, binding.box_name
+            ),
+            Some(project_dir.display().to_string()),
+        );
+
+        Ok(EnvShredResult {
+            import,
+            shredded: true,
+        })
+    }
+
+    fn resolve_project_directory(&self, project_path: &str) -> Result<PathBuf, ZapError> {
+        let trimmed = project_path.trim();
+        if trimmed.is_empty() {
+            return Err(ZapError::ValidationError(
+                "Project path cannot be empty".to_string(),
+            ));
+        }
+
+        let project_dir = PathBuf::from(trimmed);
+        if !project_dir.is_dir() {
+            return Err(ZapError::ValidationError(format!(
+                "Project directory does not exist: {}",
+                project_dir.display()
+            )));
+        }
+
+        Ok(project_dir)
+    }
+
+    fn read_project_file(&self, project_dir: &Path) -> Result<ProjectBinding, ZapError> {
+        let file_path = project_dir.join(PROJECT_FILE_NAME);
+        let content =
+            std::fs::read_to_string(&file_path).map_err(|_| ZapError::InvalidProjectContext)?;
+
+        let binding: ProjectBinding =
+            serde_json::from_str(&content).map_err(|_| ZapError::InvalidProjectContext)?;
+
+        if binding.box_id.trim().is_empty() {
+            return Err(ZapError::InvalidProjectContext);
+        }
+        self.validate_session_name(&binding.default_session_name)
+            .map_err(|_| ZapError::InvalidProjectContext)?;
+
+        Ok(binding)
+    }
+
+    fn write_project_file(
+        &self,
+        project_dir: &Path,
+        binding: &ProjectBinding,
+    ) -> Result<(), ZapError> {
+        let file_path = project_dir.join(PROJECT_FILE_NAME);
+        let json_content = serde_json::to_string_pretty(binding)?;
+
+        // Write to temp file first, then rename for atomic operation
+        let temp_path = file_path.with_extension("tmp");
+        std::fs::write(&temp_path, json_content)?;
+        std::fs::rename(temp_path, file_path)?;
+        Ok(())
+    }
+
+    // Drop Zap's block from .envrc, deleting the file if nothing else was in it
+    fn remove_envrc_block(&self, project_dir: &Path) -> Result<(), ZapError> {
+        let envrc_path = project_dir.join(ENVRC_FILE_NAME);
+        let Ok(existing) = std::fs::read_to_string(&envrc_path) else {
+            return Ok(());
+        };
+        if !existing.contains(ENVRC_BLOCK_START) {
+            return Ok(());
+        }
+
+        let remaining = strip_envrc_block(&existing);
+        if remaining.trim().is_empty() {
+            std::fs::remove_file(&envrc_path)?;
+        } else {
+            std::fs::write(&envrc_path, remaining)?;
+        }
+        Ok(())
+    }
+
     // Session File Operations for CLI
 
+    // Schema published by the CLI, None if it never ran or predates versioning
+    fn paired_cli_schema(&self) -> Option<SessionFileSchema> {
+        let content = std::fs::read_to_string(get_cli_schema_path().ok()?).ok()?;
+        serde_json::from_str(&content).ok()
+    }
+
+    fn ensure_cli_can_read_sessions(&self) -> Result<(), ZapError> {
+        match self.paired_cli_schema() {
+            Some(cli) if !cli.can_read(SESSION_FILE_FORMAT_VERSION) => {
+                Err(ZapError::IncompatibleSessionFormat(format!(
+                    "the installed CLI reads formats {}-{}, this version writes format {} - \
+                     update the CLI",
+                    cli.min_format_version, cli.format_version, SESSION_FILE_FORMAT_VERSION
+                )))
+            }
+            _ => Ok(()),
+        }
+    }
+
     fn write_session_file_for_cli(&self, session: &DevSession) -> Result<(), ZapError> {
         let sessions_dir = self.get_sessions_directory()?;
         std::fs::create_dir_all(&sessions_dir)?;
 
-        // Convert encrypted secrets to hex format for CLI
-        let mut hex_secrets = HashMap::new();
-        for (name, encrypted_data) in &session.encrypted_secrets {
-            let serialized = serde_json::to_vec(encrypted_data)?;
-            hex_secrets.insert(name.clone(), hex::encode(serialized));
-        }
-
-        let cli_session = CliSessionFile {
-            session_name: session.session_name.clone(),
-            box_name: session.box_name.clone(),
-            session_key: hex::encode(session.session_key),
-            encrypted_secrets: hex_secrets,
-            created_at: chrono::Utc::now(),
-        };
+        let cli_session = CliSessionFile::from_session(session)?;
 
         let file_path = sessions_dir.join(format!("{}.json", session.session_name));
         let json_content = serde_json::to_string_pretty(&cli_session)?;
@@ -303,6 +1175,20 @@ impl DevState {
         Ok(())
     }
 
+    // Compare the CLI session file with the database record
+    fn session_file_matches(&self, session: &DevSession) -> Result<bool, ZapError> {
+        let sessions_dir = self.get_sessions_directory()?;
+        let file_path = sessions_dir.join(format!("{}.json", session.session_name));
+
+        let content = std::fs::read_to_string(file_path)?;
+        let cli_session = match CliSessionFile::parse(&content) {
+            Ok(parsed) => parsed,
+            Err(_) => return Ok(false),
+        };
+
+        cli_session.matches(session)
+    }
+
     fn remove_session_file_for_cli(&self, session_name: &str) -> Result<(), ZapError> {
         let sessions_dir = self.get_sessions_directory()?;
         let file_path = sessions_dir.join(format!("{}.json", session_name));
@@ -310,6 +1196,8 @@ impl DevState {
         if file_path.exists() {
             std::fs::remove_file(file_path)?;
         }
+        let _ = std::fs::remove_file(sessions_dir.join(session_activity_file_name(session_name)));
+        remove_ssh_export(&self.ssh_export_directory(session_name)?);
 
         Ok(())
     }
@@ -318,12 +1206,21 @@ impl DevState {
         let sessions_dir = self.get_sessions_directory()?;
 
         if sessions_dir.exists() {
-            for entry in std::fs::read_dir(sessions_dir)? {
-                let entry = entry?;
-                if entry.path().extension().and_then(|s| s.to_str()) == Some("json") {
-                    let _ = std::fs::remove_file(entry.path());
+            for entry in std::fs::read_dir(&sessions_dir)? {
+                let path = entry?.path();
+                let extension = path.extension().and_then(|s| s.to_str());
+                if is_session_file(&path) || extension == Some(SESSION_ACTIVITY_EXTENSION) {
+                    let _ = std::fs::remove_file(&path);
+                }
+            }
+
+            let ssh_root = sessions_dir.join(SSH_EXPORT_DIRECTORY);
+            if let Ok(entries) = std::fs::read_dir(&ssh_root) {
+                for entry in entries.flatten() {
+                    remove_ssh_export(&entry.path());
                 }
             }
+            let _ = std::fs::remove_dir_all(ssh_root);
         }
 
         Ok(())
@@ -340,16 +1237,123 @@ impl DevState {
         }
     }
 
+    fn ssh_export_directory(&self, session_name: &str) -> Result<PathBuf, ZapError> {
+        Ok(self
+            .get_sessions_directory()?
+            .join(SSH_EXPORT_DIRECTORY)
+            .join(session_name))
+    }
+
     fn get_sessions_directory(&self) -> Result<PathBuf, ZapError> {
         get_shared_sessions_directory()
             .map_err(|e| ZapError::StorageError(format!("Failed to get sessions directory: {}", e)))
     }
 }
 
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, serde::Serialize, TS)]
 pub struct DevBoxInfo {
     pub id: String,
     pub name: String,
     pub description: Option<String>,
     pub secrets_count: usize,
 }
+
+// Ask git itself so nested .gitignore files and global excludes count; falls back to the
+// project's own .gitignore when git isn't installed
+async fn env_file_gitignored(project_dir: &Path) -> bool {
+    let status = tokio::process::Command::new("git")
+        .arg("-C")
+        .arg(project_dir)
+        .args(["check-ignore", "-q", "--", ENV_FILE_NAME])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .await;
+
+    match status.ok().and_then(|status| status.code()) {
+        Some(0) => true,
+        Some(1) => false,
+        _ => std::fs::read_to_string(project_dir.join(".gitignore")).is_ok_and(|gitignore| {
+            gitignore
+                .lines()
+                .map(str::trim)
+                .any(|pattern| matches!(pattern, ".env" | "/.env" | ".env*" | "*.env" | "**/.env"))
+        }),
+    }
+}
+
+// Overwrite with random bytes before deleting. Best effort: SSDs and copy-on-write
+// filesystems may keep the old blocks around.
+fn shred_file(path: &Path) -> Result<(), ZapError> {
+    use rand::RngCore;
+    use std::io::Write;
+
+    let length = std::fs::metadata(path)?.len() as usize;
+    let mut noise = vec![0u8; length];
+    rand::rng().fill_bytes(&mut noise);
+
+    let mut file = std::fs::OpenOptions::new().write(true).open(path)?;
+    file.write_all(&noise)?;
+    file.sync_all()?;
+    drop(file);
+
+    std::fs::remove_file(path)?;
+    Ok(())
+}
+
+// Key files are shredded like imported .env files, then the folder is removed
+fn remove_ssh_export(ssh_dir: &Path) {
+    if let Ok(entries) = std::fs::read_dir(ssh_dir) {
+        for entry in entries.flatten() {
+            let _ = shred_file(&entry.path());
+        }
+    }
+    let _ = std::fs::remove_dir_all(ssh_dir);
+}
+
+fn create_private_dir(path: &Path) -> Result<(), ZapError> {
+    std::fs::create_dir_all(path)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+// Owner-only from the moment it's created, never briefly readable by others
+fn write_private_file(path: &Path, content: &str) -> Result<(), ZapError> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    file.write_all(content.as_bytes())?;
+    Ok(())
+}
+
+// .envrc content without Zap's managed block (user lines are kept as-is)
+fn strip_envrc_block(content: &str) -> String {
+    let mut kept = Vec::new();
+    let mut in_block = false;
+    for line in content.lines() {
+        match line.trim() {
+            ENVRC_BLOCK_START => in_block = true,
+            ENVRC_BLOCK_END => in_block = false,
+            _ if !in_block => kept.push(line),
+            _ => {}
+        }
+    }
+
+    let mut stripped = kept.join("\n");
+    if !stripped.is_empty() {
+        stripped.push('\n');
+    }
+    stripped
+}
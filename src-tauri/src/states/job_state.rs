@@ -0,0 +1,199 @@
+// src/states/job_state.rs - Tracked long-running operations with progress and cancellation
+
+use crate::models::{JobInfo, JobKind, JobStatus, ZapError};
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter};
+
+pub const JOB_PROGRESS_EVENT: &str = "job-progress";
+
+// Finished jobs kept around for get_job / list_jobs
+const FINISHED_JOBS_KEPT: usize = 20;
+
+struct JobEntry {
+    info: JobInfo,
+    cancelled: Arc<AtomicBool>,
+    payload: Option<String>, // Output kept out of JobInfo, see JobContext::hand_over
+}
+
+#[derive(Clone, Default)]
+pub struct JobRegistry {
+    jobs: Arc<Mutex<HashMap<String, JobEntry>>>,
+}
+
+impl JobRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a running job; the returned context reports on it
+    pub fn start(&self, kind: JobKind, app: Option<AppHandle>) -> JobContext {
+        let info = JobInfo::new(kind);
+        let cancelled = Arc::new(AtomicBool::new(false));
+        let context = JobContext {
+            id: info.id.clone(),
+            registry: self.clone(),
+            cancelled: Arc::clone(&cancelled),
+            app,
+        };
+
+        let mut jobs = self.jobs.lock().unwrap();
+        prune_finished(&mut jobs);
+        jobs.insert(
+            info.id.clone(),
+            JobEntry {
+                info,
+                cancelled,
+                payload: None,
+            },
+        );
+        context
+    }
+
+    pub fn get(&self, job_id: &str) -> Result<JobInfo, ZapError> {
+        self.jobs
+            .lock()
+            .unwrap()
+            .get(job_id)
+            .map(|entry| entry.info.clone())
+            .ok_or_else(|| ZapError::JobNotFound(job_id.to_string()))
+    }
+
+    /// Newest first
+    pub fn list(&self) -> Vec<JobInfo> {
+        let mut jobs: Vec<JobInfo> = self
+            .jobs
+            .lock()
+            .unwrap()
+            .values()
+            .map(|entry| entry.info.clone())
+            .collect();
+        jobs.sort_by_key(|job| Reverse(job.started_at));
+        jobs
+    }
+
+    /// Ask a job to stop; it does at its next checkpoint
+    pub fn cancel(&self, job_id: &str) -> Result<(), ZapError> {
+        let jobs = self.jobs.lock().unwrap();
+        let entry = jobs
+            .get(job_id)
+            .ok_or_else(|| ZapError::JobNotFound(job_id.to_string()))?;
+
+        if !entry.info.is_finished() {
+            entry.cancelled.store(true, Ordering::Release);
+        }
+        Ok(())
+    }
+
+    /// Hand out a job's payload, once
+    pub fn take_payload(&self, job_id: &str) -> Result<String, ZapError> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let entry = jobs
+            .get_mut(job_id)
+            .ok_or_else(|| ZapError::JobNotFound(job_id.to_string()))?;
+
+        entry.payload.take().ok_or_else(|| {
+            ZapError::ValidationError(format!(
+                "Job '{}' has no output waiting, it is handed out once",
+                job_id
+            ))
+        })
+    }
+
+    /// Cancel everything still running and forget every job (vault lock). Jobs still
+    /// running finish into nothing: no result is kept and no event is sent.
+    pub fn clear(&self) {
+        let mut jobs = self.jobs.lock().unwrap();
+        for entry in jobs.values() {
+            entry.cancelled.store(true, Ordering::Release);
+        }
+        jobs.clear();
+    }
+
+    fn update(&self, job_id: &str, apply: impl FnOnce(&mut JobInfo)) -> Option<JobInfo> {
+        let mut jobs = self.jobs.lock().unwrap();
+        let entry = jobs.get_mut(job_id)?;
+        apply(&mut entry.info);
+        Some(entry.info.clone())
+    }
+}
+
+fn prune_finished(jobs: &mut HashMap<String, JobEntry>) {
+    let mut finished: Vec<(String, chrono::DateTime<chrono::Utc>)> = jobs
+        .values()
+        .filter(|entry| entry.info.is_finished())
+        .map(|entry| (entry.info.id.clone(), entry.info.started_at))
+        .collect();
+    if finished.len() < FINISHED_JOBS_KEPT {
+        return;
+    }
+
+    finished.sort_by_key(|(_, started_at)| Reverse(*started_at));
+    for (job_id, _) in finished.into_iter().skip(FINISHED_JOBS_KEPT - 1) {
+        jobs.remove(&job_id);
+    }
+}
+
+/// Handed to the work of a job: progress reporting and cancellation checks
+pub struct JobContext {
+    id: String,
+    registry: JobRegistry,
+    cancelled: Arc<AtomicBool>,
+    app: Option<AppHandle>,
+}
+
+impl JobContext {
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Err(Cancelled) once cancel_job was called - use `?` at safe stopping points
+    pub fn check_cancelled(&self) -> Result<(), ZapError> {
+        if self.cancelled.load(Ordering::Acquire) {
+            return Err(ZapError::Cancelled);
+        }
+        Ok(())
+    }
+
+    pub fn progress(&self, done: usize, total: usize, message: Option<String>) {
+        let info = self.registry.update(&self.id, |info| {
+            info.done = done;
+            info.total = total;
+            if message.is_some() {
+                info.message = message;
+            }
+        });
+        self.emit(info);
+    }
+
+    /// Keep output that must not reach JobInfo or the progress event (a vault export) until
+    /// take_job_payload hands it out. Dropped if the vault locks first.
+    pub fn hand_over(&self, payload: String) {
+        if let Some(entry) = self.registry.jobs.lock().unwrap().get_mut(&self.id) {
+            entry.payload = Some(payload);
+        }
+    }
+
+    pub fn finish(self, result: Result<serde_json::Value, ZapError>) {
+        let info = self.registry.update(&self.id, |info| match result {
+            Ok(value) => {
+                info.status = JobStatus::Completed;
+                info.result = Some(value);
+            }
+            Err(ZapError::Cancelled) => info.status = JobStatus::Cancelled,
+            Err(e) => {
+                info.status = JobStatus::Failed;
+                info.error = Some(e.to_string());
+            }
+        });
+        self.emit(info);
+    }
+
+    fn emit(&self, info: Option<JobInfo>) {
+        if let (Some(app), Some(info)) = (&self.app, info) {
+            let _ = app.emit(JOB_PROGRESS_EVENT, info);
+        }
+    }
+}
@@ -3,7 +3,9 @@
 
 pub mod app_state;
 pub mod dev_state;
+pub mod job_state;
 
 // Re-export the main states
 pub use app_state::AppState;
 pub use dev_state::{DevBoxInfo, DevState};
+pub use job_state::{JobContext, JobRegistry};
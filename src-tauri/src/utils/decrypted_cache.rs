@@ -0,0 +1,95 @@
+// src/utils/decrypted_cache.rs - Short-lived LRU of decrypted secret values (opt-in)
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+pub const DECRYPTED_CACHE_CAPACITY: usize = 256;
+
+struct CachedValue {
+    nonce: Vec<u8>, // Re-encryption changes the nonce, so a stale entry never matches
+    value: String,
+    cached_at: Instant,
+}
+
+pub struct DecryptedCache {
+    entries: HashMap<String, CachedValue>,
+    recency: VecDeque<String>, // Least recently used at the front
+    capacity: usize,
+    ttl: Duration, // Settings: decrypted_cache_seconds, zero = off
+}
+
+impl DecryptedCache {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            recency: VecDeque::new(),
+            capacity,
+            ttl: Duration::ZERO,
+        }
+    }
+
+    /// Follows the settings, so lookups don't have to read them per secret
+    pub fn set_ttl(&mut self, ttl: Duration) {
+        self.ttl = ttl;
+        if ttl.is_zero() {
+            self.clear();
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        !self.ttl.is_zero()
+    }
+
+    pub fn get(&mut self, secret_id: &str, nonce: &[u8]) -> Option<String> {
+        let ttl = self.ttl;
+        let fresh = self
+            .entries
+            .get(secret_id)
+            .is_some_and(|entry| entry.nonce == nonce && entry.cached_at.elapsed() <= ttl);
+
+        if !fresh {
+            self.remove(secret_id);
+            return None;
+        }
+
+        self.touch(secret_id);
+        self.entries.get(secret_id).map(|entry| entry.value.clone())
+    }
+
+    pub fn insert(&mut self, secret_id: &str, nonce: &[u8], value: String) {
+        if self.capacity == 0 || !self.is_enabled() {
+            return;
+        }
+
+        self.entries.insert(
+            secret_id.to_string(),
+            CachedValue {
+                nonce: nonce.to_vec(),
+                value,
+                cached_at: Instant::now(),
+            },
+        );
+        self.touch(secret_id);
+
+        while self.recency.len() > self.capacity {
+            if let Some(evicted) = self.recency.pop_front() {
+                self.entries.remove(&evicted);
+            }
+        }
+    }
+
+    pub fn remove(&mut self, secret_id: &str) {
+        self.entries.remove(secret_id);
+        self.recency.retain(|id| id != secret_id);
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+
+    fn touch(&mut self, secret_id: &str) {
+        self.recency.retain(|id| id != secret_id);
+        self.recency.push_back(secret_id.to_string());
+    }
+}
@@ -0,0 +1,424 @@
+// src/utils/env_parser.rs - .env grammar (export/unset, multiline quotes, ${VAR} interpolation)
+//
+// Unquoted values only expand ${VAR}, so a bare `$` (in a password, say) is kept as written.
+// `\$` is a literal dollar sign in unquoted and double-quoted values.
+
+use crate::models::{MultilineFormat, ZapError};
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::str::Chars;
+
+pub struct ParsedEnv {
+    pub vars: Vec<(String, String)>, // In file order, later assignments win
+    pub warnings: Vec<String>,
+}
+
+/// Quote and escape a value so `parse_env` reads the exact value back.
+/// `Quoted` keeps line breaks as they are, inside the double quotes.
+pub fn escape_env_value(value: &str, multiline: MultilineFormat) -> String {
+    if !value
+        .chars()
+        .any(|c| c.is_whitespace() || matches!(c, '"' | '\'' | '#' | '$' | '\\'))
+    {
+        return value.to_string();
+    }
+
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('$', "\\$")
+        .replace('\r', "\\r");
+    let escaped = match multiline {
+        MultilineFormat::Escaped => escaped.replace('\n', "\\n"),
+        MultilineFormat::Quoted => escaped,
+    };
+    format!("\"{}\"", escaped)
+}
+
+/// Parse .env content; `lookup` resolves references not defined earlier in the file
+pub fn parse_env<F>(content: &str, lookup: F) -> Result<ParsedEnv, ZapError>
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut parser = Parser {
+        chars: content.trim_start_matches('\u{feff}').chars().peekable(),
+        line: 1,
+    };
+    let mut vars: Vec<(String, String)> = Vec::new();
+    let mut index: HashMap<String, usize> = HashMap::new();
+    let mut warnings = Vec::new();
+
+    loop {
+        parser.skip_blank();
+        let Some(&c) = parser.chars.peek() else {
+            break;
+        };
+
+        if c == '#' {
+            parser.skip_line();
+            continue;
+        }
+
+        let line = parser.line;
+        let mut key = parser.read_word();
+
+        if key == "unset" {
+            for name in parser.read_rest_of_line().split_whitespace() {
+                if let Some(position) = index.remove(name) {
+                    vars.remove(position);
+                    index = vars
+                        .iter()
+                        .enumerate()
+                        .map(|(i, (k, _))| (k.clone(), i))
+                        .collect();
+                }
+            }
+            continue;
+        }
+
+        if key == "export" && parser.peek_is_inline_space() {
+            parser.skip_inline_space();
+            key = parser.read_word();
+        }
+
+        if !is_valid_key(&key) {
+            warnings.push(format!("Line {}: invalid variable name '{}'", line, key));
+            parser.skip_line();
+            continue;
+        }
+
+        parser.skip_inline_space();
+        if parser.chars.peek() != Some(&'=') {
+            // `export FOO` without a value only marks an existing variable
+            parser.skip_line();
+            continue;
+        }
+        parser.chars.next();
+        parser.skip_inline_space();
+
+        let mut unresolved = Vec::new();
+        let value = {
+            let resolve = |name: &str| {
+                index
+                    .get(name)
+                    .map(|&position| vars[position].1.clone())
+                    .or_else(|| lookup(name))
+            };
+
+            match parser.chars.peek() {
+                Some(&'"') => {
+                    parser.chars.next();
+                    let raw = parser
+                        .read_quoted('"', true)
+                        .ok_or_else(|| unterminated(line, &key))?;
+                    parser.skip_line();
+                    render(&raw, true, &resolve, &mut unresolved)
+                }
+                Some(&'\'') => {
+                    parser.chars.next();
+                    let raw = parser
+                        .read_quoted('\'', false)
+                        .ok_or_else(|| unterminated(line, &key))?;
+                    parser.skip_line();
+                    raw
+                }
+                _ => {
+                    let rest = parser.read_rest_of_line();
+                    let raw = strip_inline_comment(&rest);
+                    render(raw.trim(), false, &resolve, &mut unresolved)
+                }
+            }
+        };
+
+        for name in unresolved {
+            warnings.push(format!(
+                "Line {}: '{}' references undefined variable '{}'",
+                line, key, name
+            ));
+        }
+
+        match index.get(&key) {
+            Some(&position) => vars[position].1 = value,
+            None => {
+                index.insert(key.clone(), vars.len());
+                vars.push((key, value));
+            }
+        }
+    }
+
+    Ok(ParsedEnv { vars, warnings })
+}
+
+struct Parser<'a> {
+    chars: Peekable<Chars<'a>>,
+    line: usize,
+}
+
+impl Parser<'_> {
+    fn next(&mut self) -> Option<char> {
+        let c = self.chars.next();
+        if c == Some('\n') {
+            self.line += 1;
+        }
+        c
+    }
+
+    fn skip_blank(&mut self) {
+        while self.chars.peek().is_some_and(|c| c.is_whitespace()) {
+            self.next();
+        }
+    }
+
+    fn peek_is_inline_space(&mut self) -> bool {
+        matches!(self.chars.peek(), Some(' ') | Some('\t'))
+    }
+
+    fn skip_inline_space(&mut self) {
+        while self.peek_is_inline_space() {
+            self.next();
+        }
+    }
+
+    fn skip_line(&mut self) {
+        while let Some(c) = self.next() {
+            if c == '\n' {
+                break;
+            }
+        }
+    }
+
+    fn read_word(&mut self) -> String {
+        let mut word = String::new();
+        while let Some(&c) = self.chars.peek() {
+            if c.is_whitespace() || c == '=' {
+                break;
+            }
+            word.push(c);
+            self.next();
+        }
+        word
+    }
+
+    fn read_rest_of_line(&mut self) -> String {
+        let mut rest = String::new();
+        while let Some(c) = self.next() {
+            if c == '\n' {
+                break;
+            }
+            rest.push(c);
+        }
+        rest.trim_end_matches('\r').to_string()
+    }
+
+    // Raw text up to the closing quote (escapes kept for `render`), None if unterminated
+    fn read_quoted(&mut self, quote: char, allow_escapes: bool) -> Option<String> {
+        let mut raw = String::new();
+        while let Some(c) = self.next() {
+            if allow_escapes && c == '\\' {
+                raw.push(c);
+                raw.push(self.next()?);
+            } else if c == quote {
+                return Some(raw);
+            } else {
+                raw.push(c);
+            }
+        }
+        None
+    }
+}
+
+// Expand ${VAR} / ${VAR:-default} references, plus escapes and $VAR in double quotes
+fn render<F>(raw: &str, double_quoted: bool, resolve: &F, unresolved: &mut Vec<String>) -> String
+where
+    F: Fn(&str) -> Option<String>,
+{
+    let mut output = String::new();
+    let mut chars = raw.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'$') => {
+                chars.next();
+                output.push('$');
+            }
+            '\\' if double_quoted => match chars.next() {
+                Some('n') => output.push('\n'),
+                Some('r') => output.push('\r'),
+                Some('t') => output.push('\t'),
+                Some(escaped @ ('"' | '\\' | '$')) => output.push(escaped),
+                Some(other) => {
+                    output.push('\\');
+                    output.push(other);
+                }
+                None => output.push('\\'),
+            },
+            '$' if chars.peek() == Some(&'{') => {
+                chars.next();
+                let expression: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                let (name, default) = match expression.split_once(":-") {
+                    Some((name, default)) => (name, Some(default)),
+                    None => (expression.as_str(), None),
+                };
+                match resolve(name).or_else(|| default.map(str::to_string)) {
+                    Some(value) => output.push_str(&value),
+                    None => unresolved.push(name.to_string()),
+                }
+            }
+            '$' if double_quoted && chars.peek().is_some_and(is_name_start) => {
+                let mut name = String::new();
+                while let Some(&c) = chars.peek() {
+                    if !(c.is_ascii_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    name.push(c);
+                    chars.next();
+                }
+                match resolve(&name) {
+                    Some(value) => output.push_str(&value),
+                    None => unresolved.push(name),
+                }
+            }
+            _ => output.push(c),
+        }
+    }
+
+    output
+}
+
+// `FOO=bar # note` -> `bar`, but `FOO=a#b` keeps the hash
+fn strip_inline_comment(value: &str) -> &str {
+    match value.find(" #").or_else(|| value.find("\t#")) {
+        Some(position) => &value[..position],
+        None => value,
+    }
+}
+
+fn is_name_start(c: &char) -> bool {
+    c.is_ascii_alphabetic() || *c == '_'
+}
+
+fn is_valid_key(key: &str) -> bool {
+    let mut chars = key.chars();
+    chars.next().is_some_and(|c| is_name_start(&c))
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '.' | '-'))
+}
+
+fn unterminated(line: usize, key: &str) -> ZapError {
+    ZapError::ValidationError(format!(
+        "Line {}: value for '{}' has an unterminated quote",
+        line, key
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(content: &str) -> ParsedEnv {
+        parse_env(content, |name| {
+            (name == "HOME").then(|| "/home/zap".to_string())
+        })
+        .unwrap()
+    }
+
+    fn value<'a>(parsed: &'a ParsedEnv, key: &str) -> &'a str {
+        parsed
+            .vars
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+            .unwrap_or_else(|| panic!("{} was not parsed", key))
+    }
+
+    #[test]
+    fn unquoted_values_keep_a_bare_dollar() {
+        let parsed = parse("PASSWORD=pa$word\nPRICE=$5\n");
+        assert_eq!(value(&parsed, "PASSWORD"), "pa$word");
+        assert_eq!(value(&parsed, "PRICE"), "$5");
+        assert!(parsed.warnings.is_empty());
+    }
+
+    #[test]
+    fn unquoted_values_expand_braced_references() {
+        let parsed = parse("BASE=/srv\nDATA=${BASE}/data\nCACHE=${HOME}/cache\n");
+        assert_eq!(value(&parsed, "DATA"), "/srv/data");
+        assert_eq!(value(&parsed, "CACHE"), "/home/zap/cache");
+    }
+
+    #[test]
+    fn escaped_dollar_is_literal() {
+        let parsed = parse("A=\\${HOME}\nB=\"\\$HOME and \\${HOME}\"\n");
+        assert_eq!(value(&parsed, "A"), "${HOME}");
+        assert_eq!(value(&parsed, "B"), "$HOME and ${HOME}");
+    }
+
+    #[test]
+    fn double_quotes_expand_references_and_escapes() {
+        let parsed = parse("GREETING=\"hi $HOME\\t${MISSING:-x}\\n\\\"q\\\"\"\n");
+        assert_eq!(value(&parsed, "GREETING"), "hi /home/zap\tx\n\"q\"");
+    }
+
+    #[test]
+    fn single_quotes_are_literal() {
+        let parsed = parse("RAW='$HOME \\n ${HOME}'\n");
+        assert_eq!(value(&parsed, "RAW"), "$HOME \\n ${HOME}");
+    }
+
+    #[test]
+    fn undefined_references_warn() {
+        let parsed = parse("URL=\"${NOPE}/x\"\n");
+        assert_eq!(value(&parsed, "URL"), "/x");
+        assert_eq!(parsed.warnings.len(), 1);
+    }
+
+    #[test]
+    fn quoted_values_span_lines() {
+        let parsed = parse("KEY=\"-----BEGIN-----\nline\n-----END-----\" # pem\nNEXT=1\n");
+        assert_eq!(
+            value(&parsed, "KEY"),
+            "-----BEGIN-----\nline\n-----END-----"
+        );
+        assert_eq!(value(&parsed, "NEXT"), "1");
+    }
+
+    #[test]
+    fn unterminated_quote_is_an_error() {
+        assert!(parse_env("KEY=\"open\nNEXT=1\n", |_| None).is_err());
+    }
+
+    #[test]
+    fn export_prefix_and_comments() {
+        let parsed = parse("# comment\nexport TOKEN=abc # note\nexport\tHASH=a#b\nexport TOKEN\n");
+        assert_eq!(value(&parsed, "TOKEN"), "abc");
+        assert_eq!(value(&parsed, "HASH"), "a#b");
+        assert_eq!(parsed.vars.len(), 2);
+    }
+
+    #[test]
+    fn later_assignments_win_and_unset_removes() {
+        let parsed = parse("A=1\nB=2\nA=3\nunset B\n");
+        assert_eq!(parsed.vars, vec![("A".to_string(), "3".to_string())]);
+    }
+
+    #[test]
+    fn escaped_values_read_back_exactly() {
+        let values = [
+            "plain",
+            "with space",
+            "pa$word ${HOME} \\$",
+            "quote \" and 'single' # hash",
+            "back\\slash\r\nand\nlines",
+        ];
+        for format in [MultilineFormat::Escaped, MultilineFormat::Quoted] {
+            let content: String = values
+                .iter()
+                .enumerate()
+                .map(|(i, v)| format!("V{}={}\n", i, escape_env_value(v, format)))
+                .collect();
+            let parsed = parse_env(&content, |_| None).unwrap();
+            for (i, v) in values.iter().enumerate() {
+                assert_eq!(value(&parsed, &format!("V{}", i)), *v);
+            }
+        }
+    }
+}
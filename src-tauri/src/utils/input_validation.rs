@@ -0,0 +1,235 @@
+// src/utils/input_validation.rs - Normalizes command input before it reaches the services
+
+use crate::models::settings_model::MAX_SECRET_VALUE_KIB;
+use crate::models::secret_model::MAX_ENVIRONMENT_LENGTH;
+use crate::models::{Secret, ZapError};
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::UnicodeSegmentation;
+
+pub const MAX_ID_LENGTH: usize = 64;
+pub const MAX_BOX_NAME_LENGTH: usize = 50;
+pub const MAX_DESCRIPTION_LENGTH: usize = 75;
+pub const MAX_SECRET_NAME_LENGTH: usize = 75;
+pub const MAX_SECRET_VALUE_BYTES: usize = MAX_SECRET_VALUE_KIB as usize * 1024; // Hard ceiling
+pub const MAX_SESSION_NAME_LENGTH: usize = 30;
+pub const MAX_TAG_LENGTH: usize = 15;
+pub const MAX_QUERY_LENGTH: usize = 100;
+pub const MAX_PATH_LENGTH: usize = 4096;
+pub const MAX_TEMPLATE_LENGTH: usize = 1_000_000;
+pub const MAX_KEY_LENGTH: usize = 256; // Public / private keys pasted by the user
+pub const MAX_JUSTIFICATION_LENGTH: usize = 200;
+
+/// Trim, NFC-normalize and length-check a free text field
+pub fn text(field: &str, value: String, max_chars: usize) -> Result<String, ZapError> {
+    reject_nulls(field, &value)?;
+
+    let normalized: String = value.trim().nfc().collect();
+    check_length(field, &normalized, max_chars)?;
+    Ok(normalized)
+}
+
+/// Like `text`, but blank input becomes None
+pub fn optional_text(
+    field: &str,
+    value: Option<String>,
+    max_chars: usize,
+) -> Result<Option<String>, ZapError> {
+    match value {
+        Some(value) => {
+            let normalized = text(field, value, max_chars)?;
+            Ok((!normalized.is_empty()).then_some(normalized))
+        }
+        None => Ok(None),
+    }
+}
+
+/// Non-empty text field (names)
+pub fn required_text(field: &str, value: String, max_chars: usize) -> Result<String, ZapError> {
+    let normalized = text(field, value, max_chars)?;
+    if normalized.is_empty() {
+        return Err(validation_error(field, "cannot be empty"));
+    }
+    Ok(normalized)
+}
+
+/// Record ids coming back from the frontend
+pub fn id(field: &str, value: String) -> Result<String, ZapError> {
+    required_text(field, value, MAX_ID_LENGTH)
+}
+
+pub fn ids(field: &str, values: Vec<String>) -> Result<Vec<String>, ZapError> {
+    values.into_iter().map(|value| id(field, value)).collect()
+}
+
+pub fn tags(values: Vec<String>) -> Result<Vec<String>, ZapError> {
+    values
+        .into_iter()
+        .map(|value| text("Tag", value, MAX_TAG_LENGTH))
+        .collect()
+}
+
+pub fn secret_names(values: Vec<String>) -> Result<Vec<String>, ZapError> {
+    values
+        .into_iter()
+        .map(|value| required_text("Secret name", value, MAX_SECRET_NAME_LENGTH))
+        .collect()
+}
+
+/// Secret values are kept byte-for-byte (no trimming or normalization)
+pub fn secret_value(value: String) -> Result<String, ZapError> {
+    reject_nulls("Secret value", &value)?;
+    secret_value_size(&value, MAX_SECRET_VALUE_BYTES)?;
+    Ok(value)
+}
+
+/// CRLF and lone CR line breaks become LF, e.g. a key pasted from a Windows editor
+pub fn normalize_line_endings(value: String) -> String {
+    if !value.contains('\r') {
+        return value;
+    }
+    value.replace("\r\n", "\n").replace('\r', "\n")
+}
+
+/// Values are capped in bytes (Settings: max_secret_value_kib), not characters
+pub fn secret_value_size(value: &str, max_bytes: usize) -> Result<(), ZapError> {
+    if value.len() > max_bytes {
+        return Err(validation_error(
+            "Secret value",
+            &format!("cannot exceed {} KiB", max_bytes / 1024),
+        ));
+    }
+    Ok(())
+}
+
+/// Environment selector for secret variants, lowercased (see Secret::validate_environment)
+pub fn environment(value: String) -> Result<String, ZapError> {
+    let normalized = required_text("Environment", value, MAX_ENVIRONMENT_LENGTH)?.to_lowercase();
+    Secret::validate_environment(&normalized)?;
+    Ok(normalized)
+}
+
+/// Like `environment`, but blank input becomes None
+pub fn optional_environment(value: Option<String>) -> Result<Option<String>, ZapError> {
+    optional_text("Environment", value, MAX_ENVIRONMENT_LENGTH)?
+        .map(environment)
+        .transpose()
+}
+
+/// Config templates are rendered as given (no trimming or normalization)
+pub fn template(value: String) -> Result<String, ZapError> {
+    reject_nulls("Template", &value)?;
+    check_length("Template", &value, MAX_TEMPLATE_LENGTH)?;
+    Ok(value)
+}
+
+/// Uniqueness key for names: casefolded, trimmed, inner whitespace collapsed
+pub fn name_key(name: &str) -> String {
+    name.nfc()
+        .collect::<String>()
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// User-perceived characters, so an emoji or accented letter counts once
+pub fn grapheme_count(value: &str) -> usize {
+    value.graphemes(true).count()
+}
+
+// PRIVATE HELPERS
+
+fn reject_nulls(field: &str, value: &str) -> Result<(), ZapError> {
+    if value.contains('\0') {
+        return Err(validation_error(field, "cannot contain null characters"));
+    }
+    Ok(())
+}
+
+fn check_length(field: &str, value: &str, max_chars: usize) -> Result<(), ZapError> {
+    if grapheme_count(value) > max_chars {
+        return Err(validation_error(
+            field,
+            &format!("cannot exceed {} characters", max_chars),
+        ));
+    }
+    Ok(())
+}
+
+fn validation_error(field: &str, reason: &str) -> ZapError {
+    ZapError::ValidationError(format!("{} {}", field, reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::Box;
+    use proptest::prelude::*;
+
+    // One user-perceived character each, several code points for most
+    const CLUSTERS: [&str; 11] = [
+        "a",
+        "\u{e9}",                                      // precomposed e acute
+        "e\u{301}",                                    // e + combining acute
+        "a\u{30a}\u{323}",                             // a + two combining marks
+        "\u{1f468}\u{200d}\u{1f469}\u{200d}\u{1f467}", // ZWJ family
+        "\u{1f44d}\u{1f3fd}",                          // thumbs up + skin tone
+        "\u{1f1eb}\u{1f1f7}",                          // regional indicator pair (flag)
+        "\u{5e9}\u{5c1}\u{5b8}",                       // Hebrew shin + dot + qamats
+        "\u{628}\u{650}",                              // Arabic beh + kasra
+        "\u{627}",                                     // Arabic alef
+        "\u{200f}",                                    // right-to-left mark
+    ];
+
+    fn clusters(range: std::ops::Range<usize>) -> impl Strategy<Value = Vec<&'static str>> {
+        prop::collection::vec(prop::sample::select(CLUSTERS.to_vec()), range)
+    }
+
+    proptest! {
+        #[test]
+        fn grapheme_count_counts_clusters(parts in clusters(0..120)) {
+            let value = parts.concat();
+            prop_assert_eq!(grapheme_count(&value), parts.len());
+            prop_assert!(grapheme_count(&value) <= value.chars().count());
+        }
+
+        #[test]
+        fn normalizing_keeps_the_cluster_count(parts in clusters(1..120)) {
+            let value = parts.concat();
+            let normalized: String = value.nfc().collect();
+            prop_assert_eq!(grapheme_count(&normalized), parts.len());
+        }
+
+        #[test]
+        fn text_limits_user_perceived_characters(parts in clusters(1..120), max in 1usize..100) {
+            let result = text("Name", parts.concat(), max);
+            prop_assert_eq!(result.is_ok(), parts.len() <= max);
+        }
+
+        #[test]
+        fn box_names_allow_fifty_characters(parts in clusters(1..80)) {
+            let name = format!("  {}  ", parts.concat());
+            prop_assert_eq!(
+                Box::validate_name(&name).is_ok(),
+                parts.len() <= MAX_BOX_NAME_LENGTH
+            );
+        }
+
+        #[test]
+        fn secret_names_allow_two_to_seventy_five_characters(parts in clusters(0..100)) {
+            let name = parts.concat();
+            prop_assert_eq!(
+                Secret::validate_name(&name).is_ok(),
+                (2..=MAX_SECRET_NAME_LENGTH).contains(&parts.len())
+            );
+        }
+
+        #[test]
+        fn name_key_ignores_case_spacing_and_composition(parts in clusters(1..40)) {
+            let composed: String = parts.concat().nfc().collect();
+            let decomposed: String = parts.concat().nfd().collect();
+            let spaced = format!(" {} ", decomposed.to_uppercase());
+            prop_assert_eq!(name_key(&composed), name_key(&spaced));
+        }
+    }
+}
@@ -1,5 +1,18 @@
 // src-tauri/src/utils/mod.rs
 
+pub mod decrypted_cache;
+pub mod env_parser;
+pub mod input_validation;
 pub mod path_resolvers; // We'll add this later for CLI auto-install
+pub mod peer_process;
+pub mod proxy_sessions;
+pub mod secret_clipboard;
+pub mod secret_lint;
+pub mod secret_template;
+pub mod ssh_config;
+pub mod striped_locks;
+pub mod tfvars;
+pub mod undo_journal;
+pub mod write_coordinator;
 
 pub use path_resolvers::*;
\ No newline at end of file
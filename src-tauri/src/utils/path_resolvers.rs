@@ -1,6 +1,7 @@
 // src-tauri/src/utils/path_resolvers.rs
 
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::{OnceLock, RwLock};
 use thiserror::Error;
 
 // ================================
@@ -9,6 +10,31 @@ use thiserror::Error;
 pub const APP_IDENTIFIER: &str = "com.devtool.zap";
 pub const DATA_DIR: &str = "data";
 pub const SESSIONS_DIR: &str = "sessions";
+pub const DIAGNOSTICS_DIR: &str = "diagnostics";
+pub const SNAPSHOTS_DIR: &str = "snapshots"; // Inside the data directory
+pub const SESSIONS_DIR_POINTER_FILE: &str = "sessions_dir"; // Read by the CLI to find relocated session files
+pub const CLI_SCHEMA_FILE: &str = "cli_session_schema.json"; // Written by the CLI: formats it reads
+pub const PROXY_ENDPOINT_FILE: &str = "proxy_endpoint.json"; // Written by the app: proxy port
+pub const SESSIONS_DIR_MARKER_FILE: &str = ".zap-sessions"; // In a chosen sessions directory
+pub const DATA_DIR_POINTER_FILE: &str = "data_dir"; // Where the user moved the databases to
+pub const DATA_DIR_ROLLBACK_FILE: &str = "data_dir.rollback"; // Previous one, until the next start
+pub const VAULT_DB_FILE: &str = "vault.db";
+pub const SESSIONS_DB_FILE: &str = "sessions.db";
+pub const LOGS_DB_FILE: &str = "logs.db";
+pub const PORTABLE_MARKER_FILE: &str = "zap.portable"; // Next to the executable: run portable
+pub const PORTABLE_FLAG: &str = "--portable"; // `--portable` or `--portable=DIR`
+pub const PORTABLE_DIR_ENV: &str = "ZAP_PORTABLE_DIR"; // Lets a CLI elsewhere find portable data
+pub const PORTABLE_DATA_DIR: &str = "zap-data"; // Default portable root, next to the executable
+
+// User-configured sessions directory (Settings), None = default location
+static SESSIONS_DIR_OVERRIDE: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+// Data directory from the pointer file, read once: the open databases only move on restart
+static DATA_DIR_OVERRIDE: OnceLock<Option<PathBuf>> = OnceLock::new();
+
+// Where the base directory comes from. Unless set before the first lookup: PortablePaths
+// when a marker file or ZAP_PORTABLE_DIR says so, otherwise DesktopPaths
+static PATH_PROVIDER: OnceLock<Box<dyn PathProvider>> = OnceLock::new();
 
 // ================================
 // ERRORS
@@ -24,42 +50,222 @@ pub enum PathError {
 }
 
 // ================================
-// CORE PATH FUNCTIONS
+// PLATFORM PATH PROVIDERS
 // ================================
 
-/// Get the base application directory
+/// Where the platform keeps the app's files. Every path in this file hangs off it.
+pub trait PathProvider: Send + Sync {
+    /// Shared with the CLI: session files, the sessions pointer, CLI schema, proxy endpoint
+    fn app_base_directory(&self) -> Result<PathBuf, PathError>;
+
+    /// Databases
+    fn data_directory(&self) -> Result<PathBuf, PathError> {
+        Ok(self.app_base_directory()?.join(DATA_DIR))
+    }
+
+    /// Opt-in diagnostic log files
+    fn diagnostics_directory(&self) -> Result<PathBuf, PathError> {
+        Ok(self.app_base_directory()?.join(DIAGNOSTICS_DIR))
+    }
+}
+
+/// The config directory Tauri gives the app, found without a Tauri app. Only the CLI uses it,
+/// which needs nothing but the shared directory.
 /// Returns: ~/.config/com.devtool.zap (Linux), ~/Library/Application Support/com.devtool.zap (macOS), %APPDATA%/com.devtool.zap (Windows)
-pub fn get_app_base_directory() -> Result<PathBuf, PathError> {
-    let base_dir = match std::env::consts::OS {
-        "windows" => std::env::var("APPDATA")
-            .map(PathBuf::from)
-            .unwrap_or_else(|_| PathBuf::from(r"C:\Users\Default\AppData\Roaming")),
-        "macos" => {
-            let home =
-                std::env::var("HOME").map_err(|_| PathError::EnvVarNotFound("HOME".to_string()))?;
-            PathBuf::from(home)
-                .join("Library")
-                .join("Application Support")
-        }
-        _ => {
-            let home =
-                std::env::var("HOME").map_err(|_| PathError::EnvVarNotFound("HOME".to_string()))?;
-            PathBuf::from(home).join(".config")
+pub struct DesktopPaths;
+
+impl PathProvider for DesktopPaths {
+    fn app_base_directory(&self) -> Result<PathBuf, PathError> {
+        dirs::config_dir()
+            .map(|dir| dir.join(APP_IDENTIFIER))
+            .ok_or_else(|| {
+                PathError::PathResolution("No config directory for this user".to_string())
+            })
+    }
+}
+
+/// Directories Tauri resolves for the app: config (shared with the CLI), data and logs.
+/// On Android / iOS they are inside the app sandbox.
+pub struct TauriPaths {
+    base_dir: PathBuf,
+    data_dir: PathBuf,
+    log_dir: PathBuf,
+}
+
+impl TauriPaths {
+    pub fn resolve<R: tauri::Runtime>(
+        paths: &tauri::path::PathResolver<R>,
+    ) -> Result<Self, PathError> {
+        let resolved =
+            |dir: tauri::Result<PathBuf>| dir.map_err(|e| PathError::PathResolution(e.to_string()));
+        Ok(Self {
+            base_dir: resolved(paths.app_config_dir())?,
+            data_dir: resolved(paths.app_data_dir())?.join(DATA_DIR),
+            log_dir: resolved(paths.app_log_dir())?,
+        })
+    }
+}
+
+impl PathProvider for TauriPaths {
+    fn app_base_directory(&self) -> Result<PathBuf, PathError> {
+        Ok(self.base_dir.clone())
+    }
+
+    fn data_directory(&self) -> Result<PathBuf, PathError> {
+        Ok(self.data_dir.clone())
+    }
+
+    fn diagnostics_directory(&self) -> Result<PathBuf, PathError> {
+        Ok(self.log_dir.clone())
+    }
+}
+
+/// Everything under one folder the user carries around, e.g. on a USB stick
+pub struct PortablePaths {
+    root: PathBuf,
+}
+
+impl PortablePaths {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+}
+
+impl PathProvider for PortablePaths {
+    fn app_base_directory(&self) -> Result<PathBuf, PathError> {
+        Ok(self.root.clone())
+    }
+}
+
+/// The portable root if this run is portable, from (first match wins) a `--portable[=DIR]`
+/// launch argument, ZAP_PORTABLE_DIR, or a zap.portable marker next to the executable whose
+/// first line may name the directory. Relative directories are taken from the executable's
+/// folder, and no directory given means zap-data/ there.
+pub fn portable_directory(launch_args: &[String]) -> Option<PathBuf> {
+    let exe_dir = std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf));
+    let resolve = |dir: &str| {
+        let dir = PathBuf::from(dir.trim());
+        match &exe_dir {
+            Some(exe_dir) if dir.is_relative() => exe_dir.join(dir),
+            _ => dir,
         }
     };
 
-    Ok(base_dir.join(APP_IDENTIFIER))
+    let flag = launch_args
+        .iter()
+        .find_map(|arg| match arg.strip_prefix(PORTABLE_FLAG)? {
+            "" => Some(None),
+            value => value.strip_prefix('=').map(Some),
+        });
+    if let Some(dir) = flag {
+        return Some(resolve(dir.unwrap_or(PORTABLE_DATA_DIR)));
+    }
+
+    if let Some(dir) = std::env::var(PORTABLE_DIR_ENV)
+        .ok()
+        .filter(|dir| !dir.trim().is_empty())
+    {
+        return Some(resolve(&dir));
+    }
+
+    let marker = exe_dir.as_ref()?.join(PORTABLE_MARKER_FILE);
+    if !marker.is_file() {
+        return None;
+    }
+    let named = std::fs::read_to_string(&marker).unwrap_or_else(|e| {
+        tracing::warn!("Failed to read {}: {}", marker.display(), e);
+        String::new()
+    });
+    match named.lines().next().filter(|line| !line.trim().is_empty()) {
+        Some(dir) => Some(resolve(dir)),
+        None => Some(resolve(PORTABLE_DATA_DIR)),
+    }
+}
+
+/// Resolve paths through `provider` from now on. Must run before anything looks up a path,
+/// paths handed out earlier would point somewhere else.
+pub fn set_path_provider(provider: impl PathProvider + 'static) -> Result<(), PathError> {
+    PATH_PROVIDER.set(Box::new(provider)).map_err(|_| {
+        PathError::PathResolution("Paths were resolved before the platform was set".to_string())
+    })
+}
+
+fn path_provider() -> &'static dyn PathProvider {
+    PATH_PROVIDER
+        .get_or_init(|| match portable_directory(&[]) {
+            Some(root) => Box::new(PortablePaths::new(root)),
+            None => Box::new(DesktopPaths),
+        })
+        .as_ref()
+}
+
+// ================================
+// CORE PATH FUNCTIONS
+// ================================
+
+/// Get the base application directory, as the platform's PathProvider places it
+pub fn get_app_base_directory() -> Result<PathBuf, PathError> {
+    path_provider().app_base_directory()
 }
 
 /// Get the data directory for databases
-/// Returns: com.devtool.zap/data/
+/// Returns: the directory the user moved them to, or the default one
 pub fn get_data_directory() -> Result<PathBuf, PathError> {
-    Ok(get_app_base_directory()?.join(DATA_DIR))
+    if let Some(dir) = DATA_DIR_OVERRIDE.get_or_init(read_data_directory_pointer) {
+        return Ok(dir.clone());
+    }
+    get_default_data_directory()
+}
+
+/// The moved-to data directory when it's missing, e.g. on an external drive that isn't
+/// plugged in. Creating it would start an empty vault in its place.
+pub fn unavailable_data_directory() -> Option<PathBuf> {
+    DATA_DIR_OVERRIDE
+        .get_or_init(read_data_directory_pointer)
+        .as_ref()
+        .filter(|dir| !dir.is_dir())
+        .cloned()
+}
+
+/// Get the data directory the platform's PathProvider places (ignores any move)
+/// Returns: ~/.local/share/com.devtool.zap/data (Linux), com.devtool.zap/data/ elsewhere
+pub fn get_default_data_directory() -> Result<PathBuf, PathError> {
+    path_provider().data_directory()
+}
+
+/// Get the directory for opt-in diagnostic log files
+/// Returns: the platform's log directory for the app, e.g. ~/Library/Logs/com.devtool.zap
+pub fn get_diagnostics_directory() -> Result<PathBuf, PathError> {
+    path_provider().diagnostics_directory()
 }
 
 /// Get the sessions directory for CLI session files  
-/// Returns: com.devtool.zap/sessions/
+/// Returns: the configured override, or com.devtool.zap/sessions/
 pub fn get_sessions_directory() -> Result<PathBuf, PathError> {
+    if let Some(dir) = SESSIONS_DIR_OVERRIDE.read().unwrap().clone() {
+        return Ok(dir);
+    }
+    get_default_sessions_directory()
+}
+
+/// Get the sessions directory from outside the app (the CLI)
+/// Returns: the directory in the pointer file, or com.devtool.zap/sessions/
+pub fn get_cli_sessions_directory() -> Result<PathBuf, PathError> {
+    let pointer_path = get_app_base_directory()?.join(SESSIONS_DIR_POINTER_FILE);
+    if pointer_path.exists() {
+        let custom_dir = std::fs::read_to_string(&pointer_path)?;
+        if !custom_dir.trim().is_empty() {
+            return Ok(PathBuf::from(custom_dir.trim()));
+        }
+    }
+    get_default_sessions_directory()
+}
+
+/// Get the default sessions directory (ignores any override)
+/// Returns: com.devtool.zap/sessions/
+pub fn get_default_sessions_directory() -> Result<PathBuf, PathError> {
     Ok(get_app_base_directory()?.join(SESSIONS_DIR))
 }
 
@@ -69,17 +275,47 @@ pub fn get_sessions_directory() -> Result<PathBuf, PathError> {
 
 /// Get vault database path
 pub fn get_vault_db_path() -> Result<PathBuf, PathError> {
-    Ok(get_data_directory()?.join("vault.db"))
+    Ok(get_data_directory()?.join(VAULT_DB_FILE))
 }
 
 /// Get sessions database path  
 pub fn get_sessions_db_path() -> Result<PathBuf, PathError> {
-    Ok(get_data_directory()?.join("sessions.db"))
+    Ok(get_data_directory()?.join(SESSIONS_DB_FILE))
 }
 
 /// Get logs database path
 pub fn get_logs_db_path() -> Result<PathBuf, PathError> {
-    Ok(get_data_directory()?.join("logs.db"))
+    Ok(get_data_directory()?.join(LOGS_DB_FILE))
+}
+
+/// Copy of the vault database taken after the last successful start
+pub fn get_vault_snapshot_path() -> Result<PathBuf, PathError> {
+    Ok(get_data_directory()?.join("vault.db.old"))
+}
+
+/// Copy of the vault taken before migrating it up from schema `version`
+pub fn get_migration_backup_path(version: u32) -> Result<PathBuf, PathError> {
+    Ok(get_data_directory()?.join(format!("vault.db.pre-v{}", version)))
+}
+
+/// Copies of the vault taken before risky operations, one folder each
+pub fn get_snapshots_directory() -> Result<PathBuf, PathError> {
+    Ok(get_data_directory()?.join(SNAPSHOTS_DIR))
+}
+
+/// Marker for a storage compaction that runs on the next start
+pub fn get_compaction_marker_path() -> Result<PathBuf, PathError> {
+    Ok(get_data_directory()?.join("compact_pending"))
+}
+
+/// Schema file the CLI writes so the app knows which session file formats it reads
+pub fn get_cli_schema_path() -> Result<PathBuf, PathError> {
+    Ok(get_app_base_directory()?.join(CLI_SCHEMA_FILE))
+}
+
+/// Port the running app serves proxy sessions on, read by the CLI
+pub fn get_proxy_endpoint_path() -> Result<PathBuf, PathError> {
+    Ok(get_app_base_directory()?.join(PROXY_ENDPOINT_FILE))
 }
 
 // ================================
@@ -88,19 +324,431 @@ pub fn get_logs_db_path() -> Result<PathBuf, PathError> {
 
 /// Ensure all necessary directories exist
 pub fn ensure_directories_exist() -> Result<(), PathError> {
-    let dirs = [
-        get_app_base_directory()?,
-        get_data_directory()?,
-        get_sessions_directory()?,
-    ];
+    let mut dirs = vec![get_app_base_directory()?, get_sessions_directory()?];
+    // A moved data directory is never recreated, startup reports it unavailable instead
+    if unavailable_data_directory().is_none() {
+        dirs.push(get_data_directory()?);
+    }
 
     for dir in &dirs {
         if !dir.exists() {
             std::fs::create_dir_all(dir)?;
-            println!("📁 Created directory: {}", dir.display());
+            tracing::info!("Created directory {}", dir.display());
+        }
+    }
+
+    Ok(())
+}
+
+// ================================
+// LEGACY DIRECTORY MIGRATION
+// ================================
+
+/// Move databases and diagnostic logs out of the config directory, where versions resolving
+/// paths from HOME / APPDATA kept everything. Session files stay, the CLI looks for them there.
+/// Returns: one "from -> to" line per directory moved
+pub fn migrate_legacy_directories() -> Result<Vec<String>, PathError> {
+    let targets = [
+        (DATA_DIR, get_default_data_directory()?),
+        (DIAGNOSTICS_DIR, get_diagnostics_directory()?),
+    ];
+    let mut moved = Vec::new();
+    for legacy_base in legacy_base_directories() {
+        for (name, to) in &targets {
+            let from = legacy_base.join(name);
+            if &from == to || !from.is_dir() {
+                continue;
+            }
+            if has_entries(to)? {
+                tracing::warn!(
+                    "Leaving {} in place, {} is already in use",
+                    from.display(),
+                    to.display()
+                );
+                continue;
+            }
+
+            move_directory(&from, to)?;
+            tracing::info!("Moved {} to {}", from.display(), to.display());
+            moved.push(format!("{} -> {}", from.display(), to.display()));
+        }
+    }
+    Ok(moved)
+}
+
+// Where the env-var resolution put the base directory, plus today's base in case the two
+// differ (XDG_CONFIG_HOME set, say). Without the variable there was nothing to keep.
+fn legacy_base_directories() -> Vec<PathBuf> {
+    let legacy = match std::env::consts::OS {
+        "windows" => std::env::var("APPDATA").ok().map(PathBuf::from),
+        "macos" => std::env::var("HOME").ok().map(|home| {
+            PathBuf::from(home)
+                .join("Library")
+                .join("Application Support")
+        }),
+        _ => std::env::var("HOME")
+            .ok()
+            .map(|home| PathBuf::from(home).join(".config")),
+    };
+
+    let mut dirs: Vec<PathBuf> = legacy
+        .map(|dir| dir.join(APP_IDENTIFIER))
+        .into_iter()
+        .chain(get_app_base_directory().ok())
+        .collect();
+    dirs.dedup();
+    dirs
+}
+
+fn has_entries(dir: &Path) -> Result<bool, PathError> {
+    if !dir.is_dir() {
+        return Ok(false);
+    }
+    Ok(std::fs::read_dir(dir)?.next().is_some())
+}
+
+// rename fails across filesystems - then copy next to the target and rename that into place,
+// so an interrupted move leaves the original whole and is redone on the next start
+fn move_directory(from: &Path, to: &Path) -> Result<(), PathError> {
+    if let Some(parent) = to.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    if to.is_dir() {
+        std::fs::remove_dir(to)?; // Empty, checked by the caller
+    }
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+
+    let partial = to.with_extension("partial");
+    if partial.exists() {
+        std::fs::remove_dir_all(&partial)?;
+    }
+    copy_directory(from, &partial)?;
+    std::fs::rename(&partial, to)?;
+    std::fs::remove_dir_all(from)?;
+    Ok(())
+}
+
+fn copy_directory(from: &Path, to: &Path) -> Result<(), PathError> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let entry = entry?;
+        let target = to.join(entry.file_name());
+        if entry.file_type()?.is_dir() {
+            copy_directory(&entry.path(), &target)?;
+        } else {
+            std::fs::copy(entry.path(), &target)?;
+        }
+    }
+    Ok(())
+}
+
+// ================================
+// DATA DIRECTORY MOVE
+// ================================
+
+// The first start after a move falls back to the previous directory when the new one holds
+// no vault (an encrypted volume that isn't mounted, say), and makes that permanent
+fn read_data_directory_pointer() -> Option<PathBuf> {
+    let base = get_app_base_directory().ok()?;
+    let pointed = std::fs::read_to_string(base.join(DATA_DIR_POINTER_FILE))
+        .ok()
+        .map(|dir| PathBuf::from(dir.trim()))
+        .filter(|dir| !dir.as_os_str().is_empty());
+
+    let rollback = base.join(DATA_DIR_ROLLBACK_FILE);
+    let Ok(previous) = std::fs::read_to_string(&rollback) else {
+        return pointed;
+    };
+    let default_dir = get_default_data_directory().ok();
+    let target = pointed.clone().or_else(|| default_dir.clone())?;
+    if target.join(VAULT_DB_FILE).exists() {
+        return pointed;
+    }
+
+    let previous = PathBuf::from(previous.trim());
+    tracing::warn!(
+        "No vault in {}, moving back to {}",
+        target.display(),
+        previous.display()
+    );
+    let previous = (default_dir.as_ref() != Some(&previous)).then_some(previous);
+    if let Err(e) = write_data_directory_pointer(&base, previous.as_deref()) {
+        tracing::error!("Failed to restore the data directory pointer: {}", e);
+    }
+    let _ = std::fs::remove_file(&rollback);
+    previous
+}
+
+fn write_data_directory_pointer(base: &Path, dir: Option<&Path>) -> Result<(), PathError> {
+    let pointer_path = base.join(DATA_DIR_POINTER_FILE);
+    match dir {
+        Some(dir) => std::fs::write(&pointer_path, dir.to_string_lossy().as_bytes())?,
+        None => {
+            if pointer_path.exists() {
+                std::fs::remove_file(&pointer_path)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Validate a directory to move the databases to and create it if needed. It must be empty,
+/// so a failed move can clear it and a finished one knows what it copied.
+pub fn validate_data_directory(path: &str) -> Result<PathBuf, PathError> {
+    let dir = PathBuf::from(path.trim());
+    if !dir.is_absolute() {
+        return Err(PathError::PathResolution(
+            "Data directory must be an absolute path".to_string(),
+        ));
+    }
+    if dir.exists() && !dir.is_dir() {
+        return Err(PathError::PathResolution(format!(
+            "Not a directory: {}",
+            dir.display()
+        )));
+    }
+    if has_entries(&dir)? {
+        return Err(PathError::PathResolution(format!(
+            "Choose an empty folder, {} has files in it",
+            dir.display()
+        )));
+    }
+
+    std::fs::create_dir_all(&dir)?;
+
+    // The databases hold the vault - keep the directory private
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mut perms = std::fs::metadata(&dir)?.permissions();
+        perms.set_mode(0o700);
+        std::fs::set_permissions(&dir, perms)?;
+    }
+
+    let probe = dir.join(".zap-write-test");
+    std::fs::write(&probe, b"")?;
+    std::fs::remove_file(&probe)?;
+
+    Ok(dir)
+}
+
+/// Open the databases from `dir` on the next start (None = the default directory), falling
+/// back to `previous` if `dir` has no vault by then
+pub fn set_data_directory_pointer(dir: Option<&Path>, previous: &Path) -> Result<(), PathError> {
+    let base = get_app_base_directory()?;
+    std::fs::write(
+        base.join(DATA_DIR_ROLLBACK_FILE),
+        previous.to_string_lossy().as_bytes(),
+    )?;
+    write_data_directory_pointer(&base, dir)
+}
+
+/// Once a start opened the moved databases: remove what was copied from the previous
+/// directory, and the rollback marker with it
+/// Returns: the previous directory, None when no move was waiting to finish
+pub fn finish_data_directory_move() -> Result<Option<PathBuf>, PathError> {
+    let rollback = get_app_base_directory()?.join(DATA_DIR_ROLLBACK_FILE);
+    if !rollback.exists() {
+        return Ok(None);
+    }
+
+    let previous = PathBuf::from(std::fs::read_to_string(&rollback)?.trim());
+    let current = get_data_directory()?;
+    if previous != current && previous.is_dir() {
+        for entry in std::fs::read_dir(&current)? {
+            let copied = previous.join(entry?.file_name());
+            if copied.is_dir() {
+                std::fs::remove_dir_all(&copied)?;
+            } else if copied.exists() {
+                std::fs::remove_file(&copied)?;
+            }
+        }
+        // Stays if the user kept anything else in it
+        let _ = std::fs::remove_dir(&previous);
+    }
+
+    std::fs::remove_file(&rollback)?;
+    Ok(Some(previous))
+}
+
+// ================================
+// AUTOMATIC EXPORT DIRECTORY
+// ================================
+
+/// Validate the automatic export folder and create it if needed
+pub fn validate_backup_directory(path: &str) -> Result<PathBuf, PathError> {
+    let dir = PathBuf::from(path.trim());
+    if !dir.is_absolute() {
+        return Err(PathError::PathResolution(
+            "Automatic export folder must be an absolute path".to_string(),
+        ));
+    }
+    if dir.exists() && !dir.is_dir() {
+        return Err(PathError::PathResolution(format!(
+            "Not a directory: {}",
+            dir.display()
+        )));
+    }
+
+    std::fs::create_dir_all(&dir)?;
+    Ok(dir)
+}
+
+/// Remove all but the newest `keep_last` files starting with `prefix`, 0 = keep all
+pub fn prune_backups(dir: &Path, prefix: &str, keep_last: usize) -> Result<usize, PathError> {
+    if keep_last == 0 {
+        return Ok(0);
+    }
+
+    // Names embed the timestamp, so they sort oldest first
+    let mut backups: Vec<PathBuf> = std::fs::read_dir(dir)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension().and_then(|ext| ext.to_str()) == Some("json")
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(prefix))
+        })
+        .collect();
+    backups.sort();
+
+    let excess = backups.len().saturating_sub(keep_last);
+    for path in &backups[..excess] {
+        std::fs::remove_file(path)?;
+    }
+    Ok(excess)
+}
+
+// ================================
+// SESSIONS DIRECTORY OVERRIDE
+// ================================
+
+/// Validate a user-provided sessions directory and create it if needed. An existing one must
+/// be empty or one we set up before, and private already - we never chmod a folder we didn't make.
+pub fn validate_sessions_directory(path: &str) -> Result<PathBuf, PathError> {
+    let trimmed = path.trim();
+    if trimmed.is_empty() {
+        return Err(PathError::PathResolution(
+            "Sessions directory cannot be empty".to_string(),
+        ));
+    }
+
+    let dir = PathBuf::from(trimmed);
+    if !dir.is_absolute() {
+        return Err(PathError::PathResolution(
+            "Sessions directory must be an absolute path".to_string(),
+        ));
+    }
+    if dir.exists() && !dir.is_dir() {
+        return Err(PathError::PathResolution(format!(
+            "Not a directory: {}",
+            dir.display()
+        )));
+    }
+
+    let marker = dir.join(SESSIONS_DIR_MARKER_FILE);
+    if dir.exists() {
+        if has_entries(&dir)? && !marker.exists() {
+            return Err(PathError::PathResolution(format!(
+                "{} already has files in it, choose an empty folder for session files",
+                dir.display()
+            )));
         }
+
+        // Session files hold session keys - other users must not be able to list or read them
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            if std::fs::metadata(&dir)?.permissions().mode() & 0o077 != 0 {
+                return Err(PathError::PathResolution(format!(
+                    "{} is accessible by other users, restrict it to your account (chmod 700)",
+                    dir.display()
+                )));
+            }
+        }
+    } else {
+        std::fs::create_dir_all(&dir)?;
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mut perms = std::fs::metadata(&dir)?.permissions();
+            perms.set_mode(0o700);
+            std::fs::set_permissions(&dir, perms)?;
+        }
+    }
+
+    // Also makes sure we can actually write there
+    std::fs::write(&marker, b"")?;
+
+    Ok(dir)
+}
+
+/// Set (or clear) the sessions directory override and mirror it for the CLI
+pub fn set_sessions_directory_override(dir: Option<PathBuf>) -> Result<(), PathError> {
+    let pointer_path = get_app_base_directory()?.join(SESSIONS_DIR_POINTER_FILE);
+
+    match &dir {
+        Some(dir) => std::fs::write(&pointer_path, dir.to_string_lossy().as_bytes())?,
+        None => {
+            if pointer_path.exists() {
+                std::fs::remove_file(&pointer_path)?;
+            }
+        }
+    }
+
+    *SESSIONS_DIR_OVERRIDE.write().unwrap() = dir;
+    Ok(())
+}
+
+/// Move the session files (and their activity files) from one directory to another. Anything
+/// else in the old directory, .json or not, stays where it is. The session file format lives
+/// in the models, so the caller says which files are sessions and how activity files are named.
+/// Returns: number of session files moved
+pub fn migrate_session_files(
+    from: &Path,
+    to: &Path,
+    is_session_file: impl Fn(&Path) -> bool,
+    activity_file_name: impl Fn(&str) -> String,
+) -> Result<usize, PathError> {
+    if from == to || !from.exists() {
+        return Ok(0);
     }
 
+    std::fs::create_dir_all(to)?;
+
+    let mut moved = 0;
+    for entry in std::fs::read_dir(from)? {
+        let path = entry?.path();
+        if !is_session_file(&path) {
+            continue;
+        }
+
+        let session_name = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .ok_or_else(|| PathError::PathResolution("Invalid session file name".to_string()))?;
+        let activity_file = activity_file_name(session_name);
+
+        move_session_file(&path, &to.join(path.file_name().unwrap_or_default()))?;
+        if from.join(&activity_file).exists() {
+            move_session_file(&from.join(&activity_file), &to.join(&activity_file))?;
+        }
+        moved += 1;
+    }
+
+    Ok(moved)
+}
+
+// rename fails across filesystems (e.g. to a ramdisk) - fall back to copy + delete
+fn move_session_file(from: &Path, to: &Path) -> Result<(), PathError> {
+    if std::fs::rename(from, to).is_err() {
+        std::fs::copy(from, to)?;
+        std::fs::remove_file(from)?;
+    }
     Ok(())
 }
 
@@ -127,21 +775,20 @@ pub fn get_all_paths() -> Result<Vec<(String, PathBuf)>, PathError> {
         ("Vault DB".to_string(), get_vault_db_path()?),
         ("Sessions DB".to_string(), get_sessions_db_path()?),
         ("Logs DB".to_string(), get_logs_db_path()?),
+        ("Diagnostics Directory".to_string(), get_diagnostics_directory()?),
     ])
 }
 
-/// Print all paths for debugging
+/// Log all paths for debugging
 pub fn debug_print_paths() {
-    println!("🔧 Zap Path Configuration:");
     match get_all_paths() {
         Ok(paths) => {
             for (name, path) in paths {
-                let exists = if path.exists() { "✅" } else { "❌" };
-                println!("   {} {}: {}", exists, name, path.display());
+                tracing::debug!(exists = path.exists(), "{}: {}", name, path.display());
             }
         }
         Err(e) => {
-            println!("Error getting paths: {}", e);
+            tracing::warn!("Error getting paths: {}", e);
         }
     }
 }
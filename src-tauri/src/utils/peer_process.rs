@@ -0,0 +1,46 @@
+// src/utils/peer_process.rs - Which program is on the other end of a loopback TCP connection.
+// The OS socket table gives the peer's PID, sysinfo turns that into an executable path.
+
+use netstat2::{get_sockets_info, AddressFamilyFlags, ProtocolFlags, ProtocolSocketInfo};
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use sysinfo::{Pid, ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+#[derive(Debug, Clone)]
+pub struct PeerProcess {
+    pub pid: u32,
+    pub exe_path: PathBuf,
+}
+
+/// The process holding the other end of a connection accepted on `local` (None when the
+/// socket table or the process can't be read)
+pub fn identify(local: SocketAddr, peer: SocketAddr) -> Option<PeerProcess> {
+    let family = if peer.is_ipv4() {
+        AddressFamilyFlags::IPV4
+    } else {
+        AddressFamilyFlags::IPV6
+    };
+    let sockets = get_sockets_info(family, ProtocolFlags::TCP).ok()?;
+
+    // The peer's socket is the one bound to its port and connected to ours
+    let pid = sockets
+        .into_iter()
+        .find_map(|socket| match socket.protocol_socket_info {
+            ProtocolSocketInfo::Tcp(tcp)
+                if tcp.local_port == peer.port() && tcp.remote_port == local.port() =>
+            {
+                socket.associated_pids.first().copied()
+            }
+            _ => None,
+        })?;
+
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::Some(&[Pid::from_u32(pid)]),
+        true,
+        ProcessRefreshKind::new().with_exe(UpdateKind::Always),
+    );
+    let exe_path = system.process(Pid::from_u32(pid))?.exe()?.to_path_buf();
+
+    Some(PeerProcess { pid, exe_path })
+}
@@ -0,0 +1,77 @@
+// src/utils/proxy_sessions.rs - Proxy dev sessions, held only in memory (emptied on lock and expiry)
+// Values stay encrypted under a per-session key until the CLI presents the session's token.
+
+use crate::models::EncryptedData;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+
+pub const MAX_PROXY_SESSIONS: usize = 20;
+
+pub struct ProxySession {
+    pub session_name: String,
+    pub box_name: String,
+    pub session_key: [u8; 32],
+    pub encrypted_secrets: HashMap<String, EncryptedData>, // secret_name -> encrypted_data
+    pub expires_at: DateTime<Utc>,
+    pub fetch_count: u32,
+}
+
+pub struct ProxySessions {
+    by_token: HashMap<String, ProxySession>,
+}
+
+impl ProxySessions {
+    pub fn new() -> Self {
+        Self {
+            by_token: HashMap::new(),
+        }
+    }
+
+    /// Add a session, replacing one with the same name (its old token stops working)
+    pub fn insert(&mut self, token: String, session: ProxySession) {
+        self.remove(&session.session_name);
+        self.by_token.insert(token, session);
+    }
+
+    pub fn get_mut(&mut self, token: &str) -> Option<&mut ProxySession> {
+        self.prune_expired();
+        self.by_token.get_mut(token)
+    }
+
+    /// Whether a session with this name was removed
+    pub fn remove(&mut self, session_name: &str) -> bool {
+        let before = self.by_token.len();
+        self.by_token
+            .retain(|_, session| session.session_name != session_name);
+        self.by_token.len() != before
+    }
+
+    pub fn sessions(&mut self) -> Vec<&ProxySession> {
+        self.prune_expired();
+        self.by_token.values().collect()
+    }
+
+    pub fn len(&mut self) -> usize {
+        self.prune_expired();
+        self.by_token.len()
+    }
+
+    pub fn is_empty(&mut self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&mut self) {
+        self.by_token.clear();
+    }
+
+    fn prune_expired(&mut self) {
+        let now = Utc::now();
+        self.by_token.retain(|_, session| session.expires_at > now);
+    }
+}
+
+impl Default for ProxySessions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
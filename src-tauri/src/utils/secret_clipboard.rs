@@ -0,0 +1,49 @@
+// src/utils/secret_clipboard.rs - Secret copies tagged for clipboard managers (Settings:
+// clipboard_exclude_from_history). Windows keeps them out of Win+V history, cloud clipboard
+// sync and monitoring apps; Linux sets x-kde-passwordManagerHint, which Klipper and most
+// other managers honor. macOS copies are untagged.
+
+use crate::models::ZapError;
+use arboard::{Clipboard, Set};
+use std::sync::Mutex;
+
+// Kept open - on Linux the copied text disappears when its Clipboard is dropped
+static CLIPBOARD: Mutex<Option<Clipboard>> = Mutex::new(None);
+
+pub fn copy_secret(value: String, exclude_from_history: bool) -> Result<(), ZapError> {
+    let mut clipboard = CLIPBOARD.lock().unwrap();
+    if clipboard.is_none() {
+        *clipboard = Some(Clipboard::new().map_err(copy_failed)?);
+    }
+
+    let set = clipboard.as_mut().unwrap().set();
+    let set = if exclude_from_history {
+        with_exclusion_hints(set)
+    } else {
+        set
+    };
+    set.text(value).map_err(copy_failed)
+}
+
+#[cfg(target_os = "windows")]
+fn with_exclusion_hints(set: Set<'_>) -> Set<'_> {
+    use arboard::SetExtWindows;
+    set.exclude_from_monitoring()
+        .exclude_from_cloud()
+        .exclude_from_history()
+}
+
+#[cfg(all(unix, not(target_os = "macos")))]
+fn with_exclusion_hints(set: Set<'_>) -> Set<'_> {
+    use arboard::SetExtLinux;
+    set.exclude_from_history()
+}
+
+#[cfg(target_os = "macos")]
+fn with_exclusion_hints(set: Set<'_>) -> Set<'_> {
+    set
+}
+
+fn copy_failed(e: arboard::Error) -> ZapError {
+    ZapError::PlatformError(format!("Failed to copy to clipboard: {}", e))
+}
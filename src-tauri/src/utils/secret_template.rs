@@ -0,0 +1,55 @@
+// src/utils/secret_template.rs - `{{secret:BoxName/SecretName}}` placeholders in config files
+
+use crate::models::{RenderedTemplate, TemplateMode, ZapError};
+use regex::Regex;
+use std::sync::OnceLock;
+
+static PLACEHOLDER_REGEX: OnceLock<Regex> = OnceLock::new();
+
+fn placeholder_regex() -> &'static Regex {
+    PLACEHOLDER_REGEX
+        .get_or_init(|| Regex::new(r"\{\{\s*secret:([^/{}]+)/([^{}]+?)\s*\}\}").unwrap())
+}
+
+/// Replace every placeholder with `resolve(box_name, secret_name)`, None = missing
+pub fn render_template<F>(
+    template: &str,
+    mode: TemplateMode,
+    mut resolve: F,
+) -> Result<RenderedTemplate, ZapError>
+where
+    F: FnMut(&str, &str) -> Result<Option<String>, ZapError>,
+{
+    let mut content = String::with_capacity(template.len());
+    let mut missing: Vec<String> = Vec::new();
+    let mut last_end = 0;
+
+    for captures in placeholder_regex().captures_iter(template) {
+        let placeholder = captures.get(0).unwrap();
+        let box_name = captures[1].trim();
+        let secret_name = captures[2].trim();
+
+        content.push_str(&template[last_end..placeholder.start()]);
+        match resolve(box_name, secret_name)? {
+            Some(value) => content.push_str(&value),
+            None => {
+                let reference = format!("{}/{}", box_name, secret_name);
+                if !missing.contains(&reference) {
+                    missing.push(reference);
+                }
+                content.push_str(placeholder.as_str());
+            }
+        }
+        last_end = placeholder.end();
+    }
+    content.push_str(&template[last_end..]);
+
+    if mode == TemplateMode::Strict && !missing.is_empty() {
+        return Err(ZapError::ValidationError(format!(
+            "Template references missing secrets: {}",
+            missing.join(", ")
+        )));
+    }
+
+    Ok(RenderedTemplate { content, missing })
+}
@@ -0,0 +1,117 @@
+// src/utils/ssh_config.rs - ssh_config Host blocks from secrets named by convention.
+// `<ALIAS>_HOST` (or _HOSTNAME), `<ALIAS>_USER`, `<ALIAS>_PORT` and `<ALIAS>_SSH_KEY` (or
+// _PRIVATE_KEY) describe one host. Bare HOST / USER / ... use the box name as the alias.
+
+use crate::models::secret_model::env_var_name;
+use crate::models::ZapError;
+use std::collections::BTreeMap;
+use std::path::Path;
+
+// Under the sessions directory, one folder per session with `config` and the key files
+pub const SSH_EXPORT_DIRECTORY: &str = "ssh";
+pub const SSH_CONFIG_FILE_NAME: &str = "config";
+
+#[derive(Debug, Clone, Default)]
+pub struct SshHost {
+    pub alias: String,
+    pub host_name: String,
+    pub user: Option<String>,
+    pub port: Option<String>,
+    pub private_key: Option<String>,
+}
+
+enum HostField {
+    HostName,
+    User,
+    Port,
+    PrivateKey,
+}
+
+const FIELD_SUFFIXES: [(&str, HostField); 7] = [
+    ("HOSTNAME", HostField::HostName),
+    ("HOST", HostField::HostName),
+    ("USERNAME", HostField::User),
+    ("USER", HostField::User),
+    ("PORT", HostField::Port),
+    ("SSH_KEY", HostField::PrivateKey),
+    ("PRIVATE_KEY", HostField::PrivateKey),
+];
+
+/// Hosts described by `values` (secret name -> value), sorted by alias. Groups without a
+/// host name are left out; values that would break the config file are refused.
+pub fn collect_hosts(
+    box_name: &str,
+    values: &BTreeMap<String, String>,
+) -> Result<Vec<SshHost>, ZapError> {
+    let mut hosts: BTreeMap<String, SshHost> = BTreeMap::new();
+
+    for (secret_name, value) in values {
+        let name = env_var_name(secret_name, None);
+        let Some((prefix, field)) = FIELD_SUFFIXES.iter().find_map(|(suffix, field)| {
+            if name == *suffix {
+                Some(("", field))
+            } else {
+                name.strip_suffix(&format!("_{}", suffix))
+                    .map(|prefix| (prefix, field))
+            }
+        }) else {
+            continue;
+        };
+
+        let alias = if prefix.is_empty() {
+            host_alias(box_name)
+        } else {
+            host_alias(prefix)
+        };
+        let host = hosts.entry(alias.clone()).or_insert_with(|| SshHost {
+            alias,
+            ..SshHost::default()
+        });
+
+        match field {
+            HostField::PrivateKey => host.private_key = Some(value.clone()),
+            HostField::HostName => host.host_name = config_value(secret_name, value)?,
+            HostField::User => host.user = Some(config_value(secret_name, value)?),
+            HostField::Port => host.port = Some(config_value(secret_name, value)?),
+        }
+    }
+
+    Ok(hosts
+        .into_values()
+        .filter(|host| !host.host_name.is_empty())
+        .collect())
+}
+
+/// One `Host` block, pointing IdentityFile at the materialized key when there is one
+pub fn render_host(host: &SshHost, identity_file: Option<&Path>) -> String {
+    let mut block = format!("Host {}\n    HostName {}\n", host.alias, host.host_name);
+    if let Some(user) = &host.user {
+        block.push_str(&format!("    User {}\n", user));
+    }
+    if let Some(port) = &host.port {
+        block.push_str(&format!("    Port {}\n", port));
+    }
+    if let Some(path) = identity_file {
+        block.push_str(&format!("    IdentityFile \"{}\"\n", path.display()));
+        block.push_str("    IdentitiesOnly yes\n");
+    }
+    block
+}
+
+// "PROD_DB" -> "prod-db", box names -> "my-box"
+fn host_alias(name: &str) -> String {
+    env_var_name(name, None).to_lowercase().replace('_', "-")
+}
+
+// Single-token values only - whitespace or quotes could smuggle in extra directives
+fn config_value(secret_name: &str, value: &str) -> Result<String, ZapError> {
+    let value = value.trim();
+    let breaks_config = |c: char| c.is_whitespace() || c == '"' || c.is_control();
+    if value.is_empty() || value.chars().any(breaks_config) {
+        return Err(ZapError::ValidationError(format!(
+            "'{}' can't be used in ssh_config (it must be a single word)",
+            secret_name
+        )));
+    }
+    Ok(value.to_string())
+}
@@ -0,0 +1,32 @@
+// src/utils/striped_locks.rs - A fixed set of mutexes picked by key hash
+// Writers on the same key are serialized; unrelated keys only wait when they share a stripe.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, MutexGuard};
+
+pub const BOX_LOCK_STRIPES: usize = 32;
+
+pub struct StripedLocks {
+    stripes: Vec<Mutex<()>>,
+}
+
+impl StripedLocks {
+    pub fn new(count: usize) -> Self {
+        Self {
+            stripes: (0..count.max(1)).map(|_| Mutex::new(())).collect(),
+        }
+    }
+
+    /// Not re-entrant - while holding a guard, don't lock any other key (it may share the stripe)
+    pub fn lock(&self, key: &str) -> MutexGuard<'_, ()> {
+        let mut hasher = DefaultHasher::new();
+        key.hash(&mut hasher);
+        let index = (hasher.finish() % self.stripes.len() as u64) as usize;
+
+        // The guard protects no data, so a writer that panicked leaves nothing half-done here
+        self.stripes[index]
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
@@ -0,0 +1,47 @@
+// src/utils/tfvars.rs - Terraform / OpenTofu variable files. Each secret becomes a string
+// variable named after its env var, lowercased: "DB Password" -> db_password.
+
+use crate::models::secret_model::env_var_name;
+use crate::models::ZapError;
+
+/// HCL identifier for a secret; identifiers can't start with a digit, so those get a `_`
+pub fn variable_name(secret_name: &str) -> Result<String, ZapError> {
+    let name = env_var_name(secret_name, None).to_lowercase();
+    if name.is_empty() {
+        return Err(ZapError::ValidationError(format!(
+            "'{}' has no letters or digits to name a variable after",
+            secret_name
+        )));
+    }
+    if name.starts_with(|c: char| c.is_ascii_digit()) {
+        return Ok(format!("_{}", name));
+    }
+    Ok(name)
+}
+
+/// Double-quoted HCL string that Terraform reads back as exactly `value`
+pub fn hcl_string(value: &str) -> String {
+    let mut quoted = String::with_capacity(value.len() + 2);
+    quoted.push('"');
+
+    let mut chars = value.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\r' => quoted.push_str("\\r"),
+            '\t' => quoted.push_str("\\t"),
+            // "${" and "%{" open template sequences; doubled, they stay literal
+            '$' | '%' if chars.peek() == Some(&'{') => {
+                quoted.push(c);
+                quoted.push(c);
+            }
+            c if c.is_control() => quoted.push_str(&format!("\\u{:04X}", c as u32)),
+            c => quoted.push(c),
+        }
+    }
+
+    quoted.push('"');
+    quoted
+}
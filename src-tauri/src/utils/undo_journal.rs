@@ -0,0 +1,57 @@
+// src/utils/undo_journal.rs - The last few destructive operations, restorable for a few minutes
+// Records are kept as stored (values stay encrypted), in memory only and emptied on lock.
+
+use crate::models::{Box, Secret};
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+pub const UNDO_JOURNAL_CAPACITY: usize = 10;
+pub const UNDO_WINDOW: Duration = Duration::from_secs(5 * 60);
+
+pub enum UndoOperation {
+    // Deleted or overwritten secrets, put back as they were
+    Secrets(Vec<Secret>),
+    // Deleted boxes with the secrets they held
+    Boxes(Vec<(Box, Vec<Secret>)>),
+}
+
+pub struct UndoEntry {
+    pub description: String, // e.g. "Delete secret 'API_KEY'"
+    pub operation: UndoOperation,
+    recorded_at: Instant,
+}
+
+pub struct UndoJournal {
+    entries: VecDeque<UndoEntry>, // Most recent at the back
+    capacity: usize,
+}
+
+impl UndoJournal {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            entries: VecDeque::new(),
+            capacity,
+        }
+    }
+
+    pub fn record(&mut self, description: String, operation: UndoOperation) {
+        self.entries.push_back(UndoEntry {
+            description,
+            operation,
+            recorded_at: Instant::now(),
+        });
+        while self.entries.len() > self.capacity {
+            self.entries.pop_front();
+        }
+    }
+
+    /// Remove and return the most recent entry, if it is still within the undo window
+    pub fn take_latest(&mut self) -> Option<UndoEntry> {
+        self.entries.retain(|entry| entry.recorded_at.elapsed() <= UNDO_WINDOW);
+        self.entries.pop_back()
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
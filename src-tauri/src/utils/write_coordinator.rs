@@ -0,0 +1,77 @@
+// src/utils/write_coordinator.rs - Coalesces sled flushes off the write path
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, Weak};
+use std::thread;
+use std::time::Duration;
+
+pub const FLUSH_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Default)]
+struct Pending {
+    databases: Mutex<Vec<sled::Db>>,
+    dirty: AtomicBool, // Set by writers, cleared by whoever flushes next
+}
+
+impl Pending {
+    fn flush_all(&self) -> Result<(), sled::Error> {
+        for db in self.databases.lock().unwrap().iter() {
+            db.flush()?;
+        }
+        Ok(())
+    }
+}
+
+/// Writers mark the databases dirty instead of flushing; a background thread flushes
+/// them together at most once per interval. Bulk operations end up with one flush.
+pub struct WriteCoordinator {
+    pending: Arc<Pending>,
+}
+
+impl WriteCoordinator {
+    pub fn new(interval: Duration) -> Self {
+        let pending = Arc::new(Pending::default());
+        let background = Arc::downgrade(&pending);
+
+        let spawned = thread::Builder::new()
+            .name("zap-flush".to_string())
+            .spawn(move || flush_loop(background, interval));
+        if let Err(e) = spawned {
+            // sled still flushes on its own timer, writes are just less prompt
+            tracing::warn!("Failed to start flush thread: {}", e);
+        }
+
+        Self { pending }
+    }
+
+    pub fn register(&self, db: &sled::Db) {
+        self.pending.databases.lock().unwrap().push(db.clone());
+    }
+
+    pub fn mark_dirty(&self) {
+        self.pending.dirty.store(true, Ordering::Release);
+    }
+
+    /// Flush everything now (lock, shutdown)
+    pub fn flush_now(&self) -> Result<(), sled::Error> {
+        self.pending.dirty.store(false, Ordering::Release);
+        self.pending.flush_all()
+    }
+}
+
+// Stops once the coordinator (and with it the storage) is dropped
+fn flush_loop(pending: Weak<Pending>, interval: Duration) {
+    loop {
+        thread::sleep(interval);
+        let Some(pending) = pending.upgrade() else {
+            return;
+        };
+
+        if pending.dirty.swap(false, Ordering::AcqRel) {
+            if let Err(e) = pending.flush_all() {
+                tracing::error!("Background flush failed: {}", e);
+                pending.dirty.store(true, Ordering::Release); // Try again next tick
+            }
+        }
+    }
+}
@@ -1,15 +1,32 @@
 // src/window_manager.rs - CLEAN AUTOMATIC APPROACH
 
+use crate::models::{DockSide, WidthUnit, WindowLayout};
+use crate::states::AppState;
 use serde::{Deserialize, Serialize};
-use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, Runtime};
+use std::sync::Arc;
+use tauri::{
+    AppHandle, Manager, PhysicalPosition, PhysicalSize, Runtime, WebviewUrl, WebviewWindowBuilder,
+    Window, WindowEvent,
+};
+use ts_rs::TS;
 
-#[derive(Debug, Serialize, Deserialize)]
+pub const MINI_WINDOW_LABEL: &str = "mini";
+const MINI_WINDOW_SIZE: (f64, f64) = (260.0, 220.0); // Logical pixels
+
+#[derive(Debug, Serialize, Deserialize, TS)]
 pub struct WindowState {
     pub is_visible: bool,
     pub current_position: (i32, i32),
     pub window_size: (u32, u32),
 }
 
+// How far the frontend has to slide the window to move it fully off its docked edge
+#[derive(Debug, Serialize, TS)]
+pub struct SlideInfo {
+    pub dock: DockSide,
+    pub distance: u32, // Offset (physical px) at which the window is completely off-screen
+}
+
 #[derive(Debug, Clone)]
 pub struct ScreenInfo {
     pub width: u32,
@@ -43,18 +60,36 @@ impl WindowManager {
         }
     }
 
-    /// Calculate sidebar dimensions (30% width, 90% height)
-    pub fn calculate_sidebar_layout(screen: &ScreenInfo) -> (u32, u32, i32, i32) {
-        // 30% of screen width, 90% of screen height
-        let window_width = (screen.width as f64 * 0.25) as u32;
-        let window_height = (screen.height as f64 * 0.90) as u32;
+    /// Layout from Settings (defaults if settings can't be read yet)
+    pub fn get_layout<R: Runtime>(app: &AppHandle<R>) -> WindowLayout {
+        app.try_state::<Arc<AppState>>()
+            .and_then(|app_state| app_state.get_settings().ok())
+            .map(|settings| settings.window_layout)
+            .unwrap_or_default()
+    }
+
+    /// Calculate sidebar dimensions and position for the configured layout
+    pub fn calculate_sidebar_layout(
+        screen: &ScreenInfo,
+        layout: &WindowLayout,
+    ) -> (u32, u32, i32, i32) {
+        let margin = (layout.margin as f64 * screen.scale_factor) as u32;
+        let requested_width = match layout.width_unit {
+            WidthUnit::Percent => screen.width as f64 * layout.width as f64 / 100.0,
+            WidthUnit::Pixels => layout.width as f64 * screen.scale_factor,
+        } as u32;
+
+        // Never wider than the screen minus the margin
+        let window_width = requested_width.min(screen.width.saturating_sub(margin));
+        let window_height = (screen.height as f64 * layout.height_percent as f64 / 100.0) as u32;
 
-        // Position on the right side with small margin
-        let margin = (10.0 * screen.scale_factor) as u32;
-        let x = (screen.width - window_width - margin) as i32;
+        let x = match layout.dock {
+            DockSide::Left => margin as i32,
+            DockSide::Right => (screen.width - window_width - margin) as i32,
+        };
         let y = ((screen.height - window_height) / 2) as i32; // Vertically centered
 
-        println!(
+        tracing::debug!(
             "Sidebar layout: {}x{} at ({}, {}) for screen {}x{}",
             window_width, window_height, x, y, screen.width, screen.height
         );
@@ -62,20 +97,32 @@ impl WindowManager {
         (window_width, window_height, x, y)
     }
 
-    /// Set window to initial sidebar mode (30% width, 90% height, right-aligned)
-    pub fn initialize_sidebar_window<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+    /// Resize and reposition the window for the current layout settings
+    pub fn apply_layout<R: Runtime>(app: &AppHandle<R>) -> Result<(u32, u32, i32, i32), String> {
         let window = app
             .get_webview_window("main")
             .ok_or("Main window not found")?;
 
         let screen = Self::get_screen_info(app);
-        let (width, height, x, y) = Self::calculate_sidebar_layout(&screen);
+        let (width, height, x, y) = Self::calculate_sidebar_layout(&screen, &Self::get_layout(app));
 
-        // Apply size and position
         window
             .set_size(PhysicalSize::new(width, height))
             .and_then(|_| window.set_position(PhysicalPosition::new(x, y)))
-            .and_then(|_| window.set_always_on_top(false))
+            .map_err(|e| format!("Failed to apply window layout: {}", e))?;
+
+        Ok((width, height, x, y))
+    }
+
+    /// Set window to initial sidebar mode (size and docking from settings)
+    pub fn initialize_sidebar_window<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+        let window = app
+            .get_webview_window("main")
+            .ok_or("Main window not found")?;
+
+        let (width, height, x, y) = Self::apply_layout(app)?;
+        window
+            .set_always_on_top(false)
             .and_then(|_| window.set_skip_taskbar(false))
             .map_err(|e| format!("Failed to initialize window: {}", e))?;
 
@@ -83,13 +130,141 @@ impl WindowManager {
         window.show().ok();
         window.set_focus().ok();
 
-        println!(
+        tracing::debug!(
             "Window initialized in sidebar mode: {}x{} at ({}, {})",
             width, height, x, y
         );
         Ok(())
     }
 
+    /// Offset range for a slide-in/out animation from the docked edge
+    pub fn get_slide_info<R: Runtime>(app: &AppHandle<R>) -> SlideInfo {
+        let layout = Self::get_layout(app);
+        let screen = Self::get_screen_info(app);
+        let (width, _, _, _) = Self::calculate_sidebar_layout(&screen, &layout);
+        let margin = (layout.margin as f64 * screen.scale_factor) as u32;
+
+        SlideInfo {
+            dock: layout.dock,
+            distance: width + margin,
+        }
+    }
+
+    /// Move the window `offset` pixels from its docked position towards the docked edge
+    pub fn set_slide_offset<R: Runtime>(app: &AppHandle<R>, offset: u32) -> Result<(), String> {
+        let window = app
+            .get_webview_window("main")
+            .ok_or("Main window not found")?;
+
+        let layout = Self::get_layout(app);
+        let screen = Self::get_screen_info(app);
+        let (_, _, x, y) = Self::calculate_sidebar_layout(&screen, &layout);
+        let x = match layout.dock {
+            DockSide::Left => x - offset as i32,
+            DockSide::Right => x + offset as i32,
+        };
+
+        window
+            .set_position(PhysicalPosition::new(x, y))
+            .map_err(|e| format!("Failed to move window: {}", e))
+    }
+
+    /// Show at a slide offset (0 = docked) so the frontend can animate the rest of the way in
+    pub fn show_sidebar<R: Runtime>(app: &AppHandle<R>, offset: u32) -> Result<(), String> {
+        let window = app
+            .get_webview_window("main")
+            .ok_or("Main window not found")?;
+
+        Self::set_slide_offset(app, offset)?;
+        window
+            .show()
+            .and_then(|_| window.set_focus())
+            .map_err(|e| format!("Failed to show: {}", e))
+    }
+
+    pub fn hide_sidebar<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+        let window = app
+            .get_webview_window("main")
+            .ok_or("Main window not found")?;
+
+        window.hide().map_err(|e| format!("Failed to hide: {}", e))
+    }
+
+    /// Hide the sidebar on focus loss when auto-hide is enabled
+    pub fn on_window_event<R: Runtime>(window: &Window<R>, event: &WindowEvent) {
+        if window.label() != "main" || !matches!(event, WindowEvent::Focused(false)) {
+            return;
+        }
+
+        let auto_hide = window
+            .try_state::<Arc<AppState>>()
+            .and_then(|app_state| app_state.get_settings().ok())
+            .is_some_and(|settings| settings.auto_hide_on_blur);
+
+        if auto_hide {
+            window.hide().ok();
+        }
+    }
+
+    /// Open (or focus) the always-on-top mini window listing active dev sessions.
+    /// It loads the same frontend, which renders the mini view based on the window label.
+    pub fn open_mini_window<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+        if let Some(window) = app.get_webview_window(MINI_WINDOW_LABEL) {
+            return window
+                .show()
+                .and_then(|_| window.set_focus())
+                .map_err(|e| format!("Failed to show mini window: {}", e));
+        }
+
+        let screen = Self::get_screen_info(app);
+        let layout = Self::get_layout(app);
+        let (width, height) = MINI_WINDOW_SIZE;
+        let margin = layout.margin as f64;
+        let screen_width = screen.width as f64 / screen.scale_factor;
+
+        // Top corner on the docked side
+        let x = match layout.dock {
+            DockSide::Left => margin,
+            DockSide::Right => screen_width - width - margin,
+        };
+
+        WebviewWindowBuilder::new(app, MINI_WINDOW_LABEL, WebviewUrl::default())
+            .title("Zap Sessions")
+            .inner_size(width, height)
+            .position(x, margin)
+            .resizable(false)
+            .maximizable(false)
+            .minimizable(false)
+            .always_on_top(true)
+            .skip_taskbar(true)
+            .build()
+            .map_err(|e| format!("Failed to open mini window: {}", e))?;
+
+        Ok(())
+    }
+
+    pub fn close_mini_window<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+        match app.get_webview_window(MINI_WINDOW_LABEL) {
+            Some(window) => window
+                .close()
+                .map_err(|e| format!("Failed to close mini window: {}", e)),
+            None => Ok(()),
+        }
+    }
+
+    /// Bring the sidebar back (second launch of the app)
+    pub fn focus_main_window<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
+        let window = app
+            .get_webview_window("main")
+            .ok_or("Main window not found")?;
+
+        window
+            .unminimize()
+            .and_then(|_| window.show())
+            .and_then(|_| window.set_focus())
+            .map_err(|e| format!("Failed to show: {}", e))
+    }
+
     /// Toggle visibility only (for global shortcut)
     pub fn toggle_visibility<R: Runtime>(app: &AppHandle<R>) -> Result<(), String> {
         let window = app
@@ -150,3 +325,27 @@ pub fn handle_get_window_state<R: Runtime>(app: AppHandle<R>) -> Result<WindowSt
 pub fn handle_initialize_right_edge<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
     WindowManager::initialize_sidebar_window(&app)
 }
+
+pub fn handle_apply_window_layout<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    WindowManager::apply_layout(&app).map(|_| ())
+}
+
+pub fn handle_show_sidebar<R: Runtime>(app: AppHandle<R>, offset: u32) -> Result<(), String> {
+    WindowManager::show_sidebar(&app, offset)
+}
+
+pub fn handle_hide_sidebar<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    WindowManager::hide_sidebar(&app)
+}
+
+pub fn handle_set_slide_offset<R: Runtime>(app: AppHandle<R>, offset: u32) -> Result<(), String> {
+    WindowManager::set_slide_offset(&app, offset)
+}
+
+pub fn handle_open_mini_window<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    WindowManager::open_mini_window(&app)
+}
+
+pub fn handle_close_mini_window<R: Runtime>(app: AppHandle<R>) -> Result<(), String> {
+    WindowManager::close_mini_window(&app)
+}
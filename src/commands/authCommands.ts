@@ -1,12 +1,27 @@
 //src/commands/authCommands.ts
 import { core } from "@tauri-apps/api";
-import type { SessionInfo } from "../types";
+import type {
+    EmergencyKit,
+    HardwareKeyStatus,
+    SessionInfo,
+    StartupStatus,
+    StorageRecovery,
+} from "../types";
 
 export const authCommands = {
     async initializeApp(): Promise<boolean> {
         return await core.invoke("initialize_app");
     },
 
+    async getStartupStatus(): Promise<StartupStatus> {
+        return await core.invoke("get_startup_status");
+    },
+
+    // Moves damaged databases aside (never deletes them) and reopens storage
+    async recoverStorage(action: StorageRecovery): Promise<StartupStatus> {
+        return await core.invoke("recover_storage", { action });
+    },
+
     async unlockVault(password: string): Promise<boolean> {
         return await core.invoke("unlock_vault", { password });
     },
@@ -26,4 +41,23 @@ export const authCommands = {
     async getSessionInfo(): Promise<SessionInfo> {
         return await core.invoke("get_session_info");
     },
-};
\ No newline at end of file
+
+    // Resets the inactivity countdown - call on real user input only, never from a poll
+    async touchSession(): Promise<void> {
+        return await core.invoke("touch_session");
+    },
+
+    // Available while locked, so the lock screen knows to ask for a PIN
+    async getHardwareKeyStatus(): Promise<HardwareKeyStatus> {
+        return await core.invoke("get_hardware_key_status");
+    },
+
+    // Slot must already be programmed for HMAC-SHA1 challenge-response
+    async enableHardwareKey(password: string, pin: string, slot: number): Promise<EmergencyKit> {
+        return await core.invoke("enable_hardware_key", { password, pin, slot });
+    },
+
+    async disableHardwareKey(pin: string, newPassword: string): Promise<EmergencyKit> {
+        return await core.invoke("disable_hardware_key", { pin, newPassword });
+    },
+};
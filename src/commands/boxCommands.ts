@@ -1,6 +1,6 @@
 // src/commands/boxCommands.ts
 import { core } from "@tauri-apps/api";
-import type { Box } from "../types";
+import type { Box, BoxDiff, BoxSearchResult, UndoResult } from "../types";
 
 export const boxCommands = {
     async getAllBoxes(): Promise<Box[]> {
@@ -49,7 +49,21 @@ export const boxCommands = {
         return await core.invoke("delete_selected_boxes", { boxIds });
     },
 
-    async searchBoxesGlobal(query: string, tags: string[]): Promise<Box[]> {
+    // Makes a read-only shared box editable; newer exports from its owner stop applying
+    async detachSharedBox(boxId: string): Promise<Box> {
+        return await core.invoke("detach_shared_box", { boxId });
+    },
+
+    async searchBoxesGlobal(query: string, tags: string[]): Promise<BoxSearchResult[]> {
         return await core.invoke("search_boxes_global", { query, tags });
     },
+
+    async diffBoxes(boxAId: string, boxBId: string): Promise<BoxDiff> {
+        return await core.invoke("diff_boxes", { boxAId, boxBId });
+    },
+
+    // Available for 5 minutes after a delete or an import that overwrote secrets
+    async undoLastOperation(): Promise<UndoResult> {
+        return await core.invoke("undo_last_operation");
+    },
 };
\ No newline at end of file
@@ -1,15 +1,73 @@
 // src/commands/devCommands.ts
 import { core } from "@tauri-apps/api";
-import type { ActiveSessionInfo, DevStats, DevBoxInfo } from "../types";
+import type {
+    ActiveSessionInfo,
+    DevStats,
+    DevBoxInfo,
+    ProxySessionInfo,
+    ProxySessionToken,
+    RenderedTemplate,
+    SecretSelection,
+    SessionFileCompatibility,
+    SshConfigExport,
+    TemplateMode
+} from "../types";
 
 export const devCommands = {
-    async createSession(sessionName: string, boxId: string): Promise<void> {
+    // Boxes tagged "prod" may need the master password and a justification
+    // (Settings: require_prod_session_approval, error code PROD_APPROVAL_REQUIRED)
+    async createSession(
+        sessionName: string,
+        boxId: string,
+        environment?: string,
+        selection?: SecretSelection,
+        approval?: { password: string; justification: string }
+    ): Promise<void> {
         return await core.invoke("create_session", {
             sessionName,
-            boxId
+            boxId,
+            environment,
+            include: selection?.include,
+            exclude: selection?.exclude,
+            password: approval?.password,
+            justification: approval?.justification
         });
     },
 
+    // Secrets stay in the app's memory and are served to `zap --token` until the TTL runs out
+    async createProxySession(
+        sessionName: string,
+        boxId: string,
+        ttlMinutes: number = 15,
+        environment?: string,
+        selection?: SecretSelection,
+        approval?: { password: string; justification: string }
+    ): Promise<ProxySessionToken> {
+        return await core.invoke("create_proxy_session", {
+            sessionName,
+            boxId,
+            environment,
+            ttlMinutes,
+            include: selection?.include,
+            exclude: selection?.exclude,
+            password: approval?.password,
+            justification: approval?.justification
+        });
+    },
+
+    async getProxySessions(): Promise<ProxySessionInfo[]> {
+        return await core.invoke("get_proxy_sessions");
+    },
+
+    async revokeProxySession(sessionName: string): Promise<void> {
+        return await core.invoke("revoke_proxy_session", { sessionName });
+    },
+
+    // Answer an "app-access-requested" event; remember adds the program to the allow/deny list
+    async answerAppAccess(requestId: string, allow: boolean, remember: boolean): Promise<void> {
+        return await core.invoke("answer_app_access", { requestId, allow, remember });
+    },
+
     async getAllSessions(): Promise<ActiveSessionInfo[]> {
         return await core.invoke("get_all_sessions");
     },
@@ -20,6 +78,35 @@ export const devCommands = {
         });
     },
 
+    async exportSessionAsEnv(
+        sessionName: string,
+        prefix: string | null
+    ): Promise<string> {
+        return await core.invoke("export_session_as_env", { sessionName, prefix });
+    },
+
+    // Host blocks from secrets named like SERVER_HOST, SERVER_USER, SERVER_PORT, SERVER_SSH_KEY
+    async exportSessionAsSshConfig(sessionName: string): Promise<SshConfigExport> {
+        return await core.invoke("export_session_as_ssh_config", { sessionName });
+    },
+
+    async renderTemplateFromSession(
+        sessionName: string,
+        template: string,
+        mode: TemplateMode = "strict"
+    ): Promise<RenderedTemplate> {
+        return await core.invoke("render_template_from_session", {
+            sessionName,
+            template,
+            mode
+        });
+    },
+
+    // Keeps the session key, so shells that already loaded the session keep working
+    async renameSession(oldName: string, newName: string): Promise<void> {
+        return await core.invoke("rename_session", { oldName, newName });
+    },
+
     async stopSession(sessionName: string): Promise<void> {
         return await core.invoke("stop_session", {
             sessionName
@@ -42,6 +129,10 @@ export const devCommands = {
         return await core.invoke("get_dev_stats");
     },
 
+    async getSessionFileSchema(): Promise<SessionFileCompatibility> {
+        return await core.invoke("get_session_file_schema");
+    },
+
     async validateSessionName(sessionName: string): Promise<boolean> {
         try {
             const result: boolean = await core.invoke("validate_session_name", {
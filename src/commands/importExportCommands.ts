@@ -1,27 +1,163 @@
 // src/commands/importExportCommands.ts
 import { core } from "@tauri-apps/api";
-import type { ImportResult } from "../types";
+import type {
+    ExternalSecretsExport,
+    GithubPushReport,
+    GitlabSyncReport,
+    GitlabTarget,
+    ImportResult,
+    MergeStrategy,
+    RenderedTemplate,
+    SmartImportPreview,
+    TemplateMode,
+    TfvarsFormat,
+    VaultDiff
+} from "../types";
 
 export const importExportCommands = {
     async exportVault(): Promise<string> {
         return await core.invoke("export_vault");
     },
 
+    // Hex public key teammates add to trusted_signing_keys to trust this vault's exports
+    async getExportSigningKey(): Promise<string> {
+        return await core.invoke("get_export_signing_key");
+    },
+
     async exportBoxAsEnv(
         boxId: string,
-        prefix: string | null
+        prefix: string | null,
+        environment?: string
+    ): Promise<string> {
+        return await core.invoke("export_box_as_env", { boxId, prefix, environment });
+    },
+
+    async exportBoxAsTfvars(
+        boxId: string,
+        format: TfvarsFormat = "hcl",
+        environment?: string
     ): Promise<string> {
-        return await core.invoke("export_box_as_env", { boxId, prefix });
+        return await core.invoke("export_box_as_tfvars", { boxId, format, environment });
+    },
+
+    // `tokenSecretId` is a vault secret holding a GitHub token with Secrets write access.
+    // Without `githubEnvironment` the repository's Actions secrets are set.
+    async pushBoxToGithub(
+        boxId: string,
+        repository: string,
+        tokenSecretId: string,
+        githubEnvironment?: string,
+        environment?: string
+    ): Promise<GithubPushReport> {
+        return await core.invoke("push_box_to_github", {
+            boxId,
+            repository,
+            githubEnvironment,
+            tokenSecretId,
+            environment
+        });
+    },
+
+    // What a sync would create or update, run before syncBoxToGitlab
+    async previewGitlabSync(
+        boxId: string,
+        target: GitlabTarget,
+        tokenSecretId: string,
+        environment?: string
+    ): Promise<GitlabSyncReport> {
+        return await core.invoke("preview_gitlab_sync", {
+            boxId,
+            target,
+            tokenSecretId,
+            environment
+        });
+    },
+
+    // `tokenSecretId` is a vault secret holding a GitLab token with the api scope
+    async syncBoxToGitlab(
+        boxId: string,
+        target: GitlabTarget,
+        tokenSecretId: string,
+        environment?: string
+    ): Promise<GitlabSyncReport> {
+        return await core.invoke("sync_box_to_gitlab", {
+            boxId,
+            target,
+            tokenSecretId,
+            environment
+        });
+    },
+
+    // `recipient` is the teammate's age public key (age1...)
+    async exportBoxForRecipient(boxId: string, recipient: string): Promise<string> {
+        return await core.invoke("export_box_for_recipient", { boxId, recipient });
+    },
+
+    // `propertyPaths` maps secret names to dotted properties, the rest use their env var name
+    async exportBoxForExternalSecrets(
+        boxId: string,
+        propertyPaths?: Record<string, string>,
+        environment?: string
+    ): Promise<ExternalSecretsExport> {
+        return await core.invoke("export_box_for_external_secrets", {
+            boxId,
+            propertyPaths,
+            environment
+        });
+    },
+
+    async renderTemplate(
+        template: string,
+        mode: TemplateMode = "strict"
+    ): Promise<RenderedTemplate> {
+        return await core.invoke("render_template", { template, mode });
     },
 
     async importVault(jsonData: string): Promise<ImportResult> {
         return await core.invoke("import_vault", { jsonData });
     },
 
+    // Adds what's missing and settles differing boxes / secrets by `strategy`, deletes nothing
+    async importVaultMerge(
+        jsonData: string,
+        strategy: MergeStrategy = "keep_newest"
+    ): Promise<ImportResult> {
+        return await core.invoke("import_vault_merge", { jsonData, strategy });
+    },
+
+    // Run before overwriting an older export to see what it would change
+    async diffVaultAgainstExport(jsonData: string): Promise<VaultDiff> {
+        return await core.invoke("diff_vault_against_export", { jsonData });
+    },
+
+    // `identity` is the age private key (AGE-SECRET-KEY-1...) matching the recipient
+    async importSharedBox(encrypted: string, identity: string): Promise<ImportResult> {
+        return await core.invoke("import_shared_box", { encrypted, identity });
+    },
+
+    // Writes an encrypted backup to the automatic export folder now, resolves to its path
+    async runAutoExportNow(): Promise<string> {
+        return await core.invoke("run_auto_export_now");
+    },
+
+    // `password` is the master password the backup was written under
+    async importEncryptedBackup(content: string, password: string): Promise<ImportResult> {
+        return await core.invoke("import_encrypted_backup", { content, password });
+    },
+
     async importEnvToBox(
         envContent: string,
         targetBoxId: string
     ): Promise<ImportResult> {
         return await core.invoke("import_env_to_box", { envContent, targetBoxId });
     },
+
+    // Detects .env, JSON, CSV or key: value lines; call before smartImport to show what it adds
+    async previewSmartImport(content: string, targetBoxId: string): Promise<SmartImportPreview> {
+        return await core.invoke("preview_smart_import", { content, targetBoxId });
+    },
+
+    async smartImport(content: string, targetBoxId: string): Promise<ImportResult> {
+        return await core.invoke("smart_import", { content, targetBoxId });
+    },
 };
\ No newline at end of file
@@ -4,6 +4,9 @@ export { boxCommands } from './boxCommands';
 export { secretCommands } from './secretCommands';
 export { devCommands, sessionHelpers } from './devCommands';
 export { importExportCommands } from './importExportCommands';
+export { jobCommands } from './jobCommands';
 export { statsCommands } from './statsCommands';
 export { settingsCommands } from './settingsCommands';
+export { syncCommands } from './syncCommands';
 export { logCommands } from './logCommands';
+export { windowCommands } from './windowCommands';
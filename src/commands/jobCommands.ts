@@ -0,0 +1,79 @@
+// src/commands/jobCommands.ts
+import { core } from "@tauri-apps/api";
+import { listen } from "@tauri-apps/api/event";
+import type { JobInfo, LanTransferOffer, TransferScope } from "../types";
+
+// Start calls return a job id; follow it with "job-progress" events or getJob
+export const jobCommands = {
+    async startImportVaultJob(jsonData: string): Promise<string> {
+        return await core.invoke("start_import_vault_job", { jsonData });
+    },
+
+    // Completes with a null result, takeJobPayload then hands out the export JSON once
+    async startExportVaultJob(): Promise<string> {
+        return await core.invoke("start_export_vault_job");
+    },
+
+    // Checks every password secret now, the result is the newly compromised ones
+    async startBreachCheckJob(): Promise<string> {
+        return await core.invoke("start_breach_check_job");
+    },
+
+    // Offer the vault or a box to a device on the same network, for 10 minutes
+    async startLanTransfer(scope: TransferScope): Promise<LanTransferOffer> {
+        return await core.invoke("start_lan_transfer", { scope });
+    },
+
+    // Takes the code shown on the sending device, or its link
+    async startLanReceive(code: string): Promise<string> {
+        return await core.invoke("start_lan_receive", { code });
+    },
+
+    async getJob(jobId: string): Promise<JobInfo> {
+        return await core.invoke("get_job", { jobId });
+    },
+
+    async listJobs(): Promise<JobInfo[]> {
+        return await core.invoke("list_jobs");
+    },
+
+    async takeJobPayload(jobId: string): Promise<string> {
+        return await core.invoke("take_job_payload", { jobId });
+    },
+
+    async cancelJob(jobId: string): Promise<void> {
+        return await core.invoke("cancel_job", { jobId });
+    },
+
+    // Follow a job until it finishes: resolves with the completed JobInfo, rejects when it
+    // fails or is cancelled. A vault lock forgets the job, which then never finishes here.
+    async waitForJob(jobId: string, onProgress?: (job: JobInfo) => void): Promise<JobInfo> {
+        let settle: (job: JobInfo) => void = () => {};
+        const finished = new Promise<JobInfo>((resolve, reject) => {
+            settle = job => {
+                if (job.status === "completed") {
+                    resolve(job);
+                } else if (job.status === "cancelled") {
+                    reject(new Error("Cancelled"));
+                } else if (job.status === "failed") {
+                    reject(new Error(job.error ?? "Job failed"));
+                }
+            };
+        });
+
+        const unlisten = await listen<JobInfo>("job-progress", event => {
+            if (event.payload.id !== jobId) return;
+            onProgress?.(event.payload);
+            settle(event.payload);
+        });
+        try {
+            // It may have finished before the listener was attached
+            const current = await jobCommands.getJob(jobId);
+            onProgress?.(current);
+            settle(current);
+            return await finished;
+        } finally {
+            unlisten();
+        }
+    },
+};
@@ -1,12 +1,19 @@
 // src/commands/logCommands.ts
 import { core } from "@tauri-apps/api";
-import type { LogEntry } from "../types";
+import type { LogEntry, LogSeverity } from "../types";
 
 export const logCommands = {
     async getAllLogs(): Promise<LogEntry[]> {
         return await core.invoke("get_all_logs");
     },
 
+    async getFilteredLogs(
+        minSeverity?: LogSeverity,
+        action?: string,
+    ): Promise<LogEntry[]> {
+        return await core.invoke("get_filtered_logs", { minSeverity, action });
+    },
+
     async clearAllLogs(password: string): Promise<number> {
         return await core.invoke("clear_all_logs", { password });
     },
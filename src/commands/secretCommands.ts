@@ -1,6 +1,18 @@
 // src/commands/secretCommands.ts
 import { core } from "@tauri-apps/api";
-import type { Secret } from "../types";
+import type {
+    CompromisedSecret,
+    DeepSearchMatch,
+    OneTimeShare,
+    RecentSecret,
+    RedeemedShare,
+    SavedSecret,
+    Secret,
+    SecretSearchResult,
+    SecretTemplate,
+    ValueEncoding,
+    MultilineFormat
+} from "../types";
 
 export const secretCommands = {
     async getAllSecrets(): Promise<Secret[]> {
@@ -14,27 +26,77 @@ export const secretCommands = {
     async createSecret(
         boxId: string,
         name: string,
-        value: string
-    ): Promise<string> {
+        value: string,
+        valueEncoding?: ValueEncoding,
+        multilineFormat?: MultilineFormat,
+        normalizeLineEndings?: boolean
+    ): Promise<SavedSecret> {
         return await core.invoke("create_secret", {
             boxId,
             name,
-            value
+            value,
+            valueEncoding,
+            multilineFormat,
+            normalizeLineEndings
+        });
+    },
+
+    async getSecretTemplates(): Promise<SecretTemplate[]> {
+        return await core.invoke("get_secret_templates");
+    },
+
+    // The value is generated in the backend; `templateFields` fill composed templates
+    async createSecretFromTemplate(
+        boxId: string,
+        name: string,
+        templateId: string,
+        templateFields: Record<string, string> = {}
+    ): Promise<SavedSecret> {
+        return await core.invoke("create_secret", {
+            boxId,
+            name,
+            templateId,
+            templateFields
         });
     },
 
     async updateSecret(
         secretId: string,
         name: string | null,
-        value: string | null
-    ): Promise<void> {
+        value: string | null,
+        valueEncoding?: ValueEncoding,
+        multilineFormat?: MultilineFormat,
+        normalizeLineEndings?: boolean
+    ): Promise<SavedSecret> {
         return await core.invoke("update_secret", {
             secretId,
             name,
-            value
+            value,
+            valueEncoding,
+            multilineFormat,
+            normalizeLineEndings
+        });
+    },
+
+    // Value the secret takes in one environment (lowercase name, e.g. "prod")
+    async setSecretVariant(
+        secretId: string,
+        environment: string,
+        value: string,
+        normalizeLineEndings?: boolean
+    ): Promise<SavedSecret> {
+        return await core.invoke("set_secret_variant", {
+            secretId,
+            environment,
+            value,
+            normalizeLineEndings
         });
     },
 
+    async removeSecretVariant(secretId: string, environment: string): Promise<void> {
+        return await core.invoke("remove_secret_variant", { secretId, environment });
+    },
+
     async deleteSecret(secretId: string): Promise<void> {
         return await core.invoke("delete_secret", { secretId });
     },
@@ -53,11 +115,50 @@ export const secretCommands = {
         });
     },
 
-    async revealSecretValue(secretId: string): Promise<string> {
-        return await core.invoke("reveal_secret_value", { secretId });
+    // Without an environment, Settings' default_environment applies
+    async revealSecretValue(secretId: string, environment?: string): Promise<string> {
+        return await core.invoke("reveal_secret_value", { secretId, environment });
     },
 
-    async searchSecretsInBox(boxId: string, query: string): Promise<Secret[]> {
+    // Favorites are a local preference, updated_at doesn't change
+    async setSecretFavorite(secretId: string, favorite: boolean): Promise<Secret> {
+        return await core.invoke("set_secret_favorite", { secretId, favorite });
+    },
+
+    async getFavoriteSecrets(): Promise<Secret[]> {
+        return await core.invoke("get_favorite_secrets");
+    },
+
+    // Passwords whose current value appears in a known breach, most seen first
+    async getCompromisedSecrets(): Promise<CompromisedSecret[]> {
+        return await core.invoke("get_compromised_secrets");
+    },
+
+    // Only a 5 character hash prefix per password is sent to Have I Been Pwned
+    async runBreachCheckNow(): Promise<CompromisedSecret[]> {
+        return await core.invoke("run_breach_check_now");
+    },
+
+    // Newest first, at most 50
+    async getRecentSecrets(limit: number = 10): Promise<RecentSecret[]> {
+        return await core.invoke("get_recent_secrets", { limit });
+    },
+
+    async searchSecretsInBox(boxId: string, query: string): Promise<SecretSearchResult[]> {
         return await core.invoke("search_secrets_in_box", { boxId, query });
     },
+
+    // Searches decrypted values; only the matching secrets' names are returned
+    async deepSearch(password: string, query: string): Promise<DeepSearchMatch[]> {
+        return await core.invoke("deep_search", { password, query });
+    },
+
+    // Single use is tracked by the redeeming vault only, keep the expiry short
+    async createOneTimeShare(secretId: string, ttlMinutes: number): Promise<OneTimeShare> {
+        return await core.invoke("create_one_time_share", { secretId, ttlMinutes });
+    },
+
+    async redeemShare(blob: string, passphrase: string): Promise<RedeemedShare> {
+        return await core.invoke("redeem_share", { blob, passphrase });
+    },
 };
\ No newline at end of file
@@ -1,6 +1,6 @@
 // src/commands/settingsCommands.ts 
 import { core } from "@tauri-apps/api";
-import type { Settings } from "../types";
+import type { DataDirectoryInfo, Settings } from "../types";
 
 export const settingsCommands = {
     async getSettings(): Promise<Settings> {
@@ -12,4 +12,14 @@ export const settingsCommands = {
             newSettings
         });
     },
+
+    async getDataDirectoryInfo(): Promise<DataDirectoryInfo> {
+        return await core.invoke("get_data_directory_info");
+    },
+
+    // Copies the databases to an empty folder (null = back to the default) and restarts the
+    // app into it, so this never resolves on success
+    async moveDataDirectory(path: string | null): Promise<void> {
+        return await core.invoke("move_data_directory", { path });
+    },
 };
\ No newline at end of file
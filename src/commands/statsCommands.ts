@@ -1,9 +1,33 @@
 // src/commands/statsCommands.ts 
 import { core } from "@tauri-apps/api";
-import type { VaultStats } from "../types";
+import type { DiagnosticsReport, StorageUsage, VaultSnapshot, VaultStats } from "../types";
 
 export const statsCommands = {
     async getVaultStats(): Promise<VaultStats> {
         return await core.invoke("get_vault_stats");
     },
+
+    async getStorageUsage(): Promise<StorageUsage> {
+        return await core.invoke("get_storage_usage");
+    },
+
+    // Newest first
+    async listVaultSnapshots(): Promise<VaultSnapshot[]> {
+        return await core.invoke("list_vault_snapshots");
+    },
+
+    // Locks the vault when done, unlock it with the password in use when the snapshot was taken
+    async restoreSnapshot(snapshotId: string): Promise<void> {
+        return await core.invoke("restore_snapshot", { snapshotId });
+    },
+
+    // Works while locked, for support tickets
+    async runDiagnostics(): Promise<DiagnosticsReport> {
+        return await core.invoke("run_diagnostics");
+    },
+
+    // Schedules compaction and restarts the app into it, so this never resolves on success
+    async compactStorage(): Promise<void> {
+        return await core.invoke("compact_storage");
+    },
 };
@@ -0,0 +1,77 @@
+// src/commands/syncCommands.ts
+import { core } from "@tauri-apps/api";
+import type {
+    ConflictResolution,
+    ReplicaSyncReport,
+    SyncConflict,
+    SyncDiff,
+    SyncProviderConfig,
+    SyncResult
+} from "../types";
+
+// Mappings are edited through Settings.sync_mappings
+export const syncCommands = {
+    async diffSyncMapping(mappingId: string): Promise<SyncDiff> {
+        return await core.invoke("diff_sync_mapping", { mappingId });
+    },
+
+    // Creates or overwrites remote secrets that are missing or differ, deletes nothing
+    async pushSyncMapping(mappingId: string): Promise<SyncResult> {
+        return await core.invoke("push_sync_mapping", { mappingId });
+    },
+
+    // Creates or overwrites secrets in the box that are missing or differ, deletes nothing
+    async pullSyncMapping(mappingId: string): Promise<SyncResult> {
+        return await core.invoke("pull_sync_mapping", { mappingId });
+    },
+
+    // One-off pull without a mapping, path keeps only store names starting with it
+    async previewProviderPull(
+        provider: SyncProviderConfig,
+        credentialsSecretId: string,
+        path: string | null,
+        targetBoxId: string
+    ): Promise<SyncDiff> {
+        return await core.invoke("preview_provider_pull", {
+            provider,
+            credentialsSecretId,
+            path,
+            targetBoxId
+        });
+    },
+
+    // Imports the selected remote names from previewProviderPull, deletes nothing
+    async pullFromProvider(
+        provider: SyncProviderConfig,
+        credentialsSecretId: string,
+        path: string | null,
+        targetBoxId: string,
+        selected: string[]
+    ): Promise<SyncResult> {
+        return await core.invoke("pull_from_provider", {
+            provider,
+            credentialsSecretId,
+            path,
+            targetBoxId,
+            selected
+        });
+    },
+
+    // Replicas are edited through Settings.replicas
+    async syncReplica(replicaId: string): Promise<ReplicaSyncReport> {
+        return await core.invoke("sync_replica", { replicaId });
+    },
+
+    async listSyncConflicts(replicaId: string): Promise<SyncConflict[]> {
+        return await core.invoke("list_sync_conflicts", { replicaId });
+    },
+
+    // Returns the open conflicts, the choice is applied by the next syncReplica
+    async resolveSyncConflict(
+        replicaId: string,
+        recordKey: string,
+        resolution: ConflictResolution
+    ): Promise<SyncConflict[]> {
+        return await core.invoke("resolve_sync_conflict", { replicaId, recordKey, resolution });
+    }
+};
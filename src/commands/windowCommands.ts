@@ -0,0 +1,13 @@
+// src/commands/windowCommands.ts
+import { core } from "@tauri-apps/api";
+
+export const windowCommands = {
+    // Always-on-top window listing active dev sessions
+    async openMiniWindow(): Promise<void> {
+        return await core.invoke("open_mini_window");
+    },
+
+    async closeMiniWindow(): Promise<void> {
+        return await core.invoke("close_mini_window");
+    },
+};
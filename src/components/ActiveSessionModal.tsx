@@ -6,6 +6,15 @@ import { useDevStore } from "../stores";
 import { truncateName } from "../utils";
 import type { ActiveSessionInfo } from "../types";
 
+// Unix seconds -> short local date/time
+const formatSessionTime = (seconds: number) =>
+    new Date(seconds * 1000).toLocaleString(undefined, {
+        month: "short",
+        day: "numeric",
+        hour: "2-digit",
+        minute: "2-digit"
+    });
+
 interface ActiveSessionModalProps {
     isOpen: boolean;
     onClose: () => void;
@@ -95,6 +104,15 @@ export const ActiveSessionModal: React.FC<ActiveSessionModalProps> = ({
                         <p className="text-gray-400 text-xs font-mono truncate">
                             Box: {truncateName(session.box_name, 15)} • {session.secrets_count} secrets
                         </p>
+                        {(session.created_at || session.is_stale) && (
+                            <p className="text-gray-500 text-xs font-mono truncate">
+                                {session.created_at && `Started ${formatSessionTime(session.created_at)}`}
+                                {session.expires_at && ` • Ends ${formatSessionTime(session.expires_at)}`}
+                                {session.is_stale && (
+                                    <span className="text-yellow-400"> • Box changed, restart to update</span>
+                                )}
+                            </p>
+                        )}
                     </div>
                 </div>
 
@@ -1,6 +1,13 @@
 // src/hooks/useGlobalSessionManager.ts
 import { useEffect, useRef } from "react";
+import { listen } from "@tauri-apps/api/event";
+import { authCommands } from "../commands";
 import { useAuthStore } from "../stores";
+import type { SessionInfo } from "../types";
+
+// Input events that count as activity, and how often they may reach the backend
+const ACTIVITY_EVENTS = ["pointerdown", "keydown", "wheel"] as const;
+const ACTIVITY_THROTTLE_MS = 15000;
 
 interface UseGlobalSessionManagerOptions {
     onSessionExpired: () => void;
@@ -9,13 +16,12 @@ interface UseGlobalSessionManagerOptions {
 export function useGlobalSessionManager({ onSessionExpired }: UseGlobalSessionManagerOptions) {
     const isUnlocked = useAuthStore(state => state.isUnlocked);
     const decrementFrontendTimer = useAuthStore(state => state.decrementFrontendTimer);
-    const refreshSessionInfo = useAuthStore(state => state.refreshSessionInfo);
+    const applySessionInfo = useAuthStore(state => state.applySessionInfo);
     const frontendTimeLeft = useAuthStore(state => state.frontendTimeLeft);
 
     // Refs to prevent stale closures
     const onSessionExpiredRef = useRef(onSessionExpired);
     const frontendTimerRef = useRef<NodeJS.Timeout | null>(null);
-    const backendSyncRef = useRef<NodeJS.Timeout | null>(null);
 
     // Update ref when callback changes
     useEffect(() => {
@@ -30,10 +36,6 @@ export function useGlobalSessionManager({ onSessionExpired }: UseGlobalSessionMa
                 clearInterval(frontendTimerRef.current);
                 frontendTimerRef.current = null;
             }
-            if (backendSyncRef.current) {
-                clearInterval(backendSyncRef.current);
-                backendSyncRef.current = null;
-            }
             return;
         }
 
@@ -44,23 +46,46 @@ export function useGlobalSessionManager({ onSessionExpired }: UseGlobalSessionMa
             decrementFrontendTimer();
         }, 1000);
 
-        // Backend 5-second sync for accuracy
-        backendSyncRef.current = setInterval(() => {
-            refreshSessionInfo();
-        }, 5000);
+        // Backend pushes phase changes and timer jumps (activity, sleep/wake) instead of polling
+        const unlisten = listen<SessionInfo>("session-state", event => {
+            applySessionInfo(event.payload);
+        });
 
         return () => {
             if (frontendTimerRef.current) {
                 clearInterval(frontendTimerRef.current);
                 frontendTimerRef.current = null;
             }
-            if (backendSyncRef.current) {
-                clearInterval(backendSyncRef.current);
-                backendSyncRef.current = null;
-            }
+            unlisten.then(stop => stop());
             console.log("🛑 Stopped global session timers");
         };
-    }, [isUnlocked, decrementFrontendTimer, refreshSessionInfo]);
+    }, [isUnlocked, decrementFrontendTimer, applySessionInfo]);
+
+    // Only real input keeps the vault unlocked, reads and background polls don't
+    useEffect(() => {
+        if (!isUnlocked) {
+            return;
+        }
+
+        let lastTouch = 0;
+        const handleActivity = () => {
+            const now = Date.now();
+            if (now - lastTouch < ACTIVITY_THROTTLE_MS) {
+                return;
+            }
+            lastTouch = now;
+            authCommands.touchSession().catch(() => {
+                // Locked in the meantime, the session-state event takes it from here
+            });
+        };
+
+        ACTIVITY_EVENTS.forEach(event =>
+            window.addEventListener(event, handleActivity, { passive: true })
+        );
+        return () => {
+            ACTIVITY_EVENTS.forEach(event => window.removeEventListener(event, handleActivity));
+        };
+    }, [isUnlocked]);
 
     // Auto-logout when frontend timer expires
     useEffect(() => {
@@ -1,9 +1,14 @@
 import React from "react";
 import ReactDOM from "react-dom/client";
+import { getCurrentWindow } from "@tauri-apps/api/window";
 import App from "./App";
+import { MiniSessionsScreen } from "./screens";
+
+// The mini sessions window loads the same bundle, pick the view by window label
+const isMiniWindow = getCurrentWindow().label === "mini";
 
 ReactDOM.createRoot(document.getElementById("root") as HTMLElement).render(
   <React.StrictMode>
-    <App />
+    {isMiniWindow ? <MiniSessionsScreen /> : <App />}
   </React.StrictMode>,
 );
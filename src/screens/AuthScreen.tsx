@@ -4,7 +4,7 @@ import { Lock, Shield, Clock, X } from "lucide-react";
 import { Button, Input } from "../components";
 import { useToastHelpers } from "../components";
 import { useAuthStore } from "../stores";
-import { validatePassword } from "../utils";
+import { getErrorCode, getErrorMessage, validatePassword } from "../utils";
 
 interface AuthScreenProps {
     onSuccess: () => void;
@@ -74,7 +74,12 @@ export const AuthScreen: React.FC<AuthScreenProps> = ({
             }
             // onSuccess will be called automatically via useEffect when isUnlocked becomes true
         } catch (error) {
-            toast.error("Authentication failed", error instanceof Error ? error.message : "Authentication failed");
+            if (getErrorCode(error) === "INCORRECT_PASSWORD") {
+                setPassword("");
+                toast.error("Incorrect password", "Check the password and try again");
+            } else {
+                toast.error("Authentication failed", getErrorMessage(error));
+            }
         }
     };
 
@@ -50,7 +50,7 @@ export const HomeScreen: React.FC<HomeScreenProps> = ({
     const { lock } = useAuthStore();
     const { createSecret } = useSecretStore();
     const { sessions, totalSessions, loadAllSessions, stopSession } = useDevStore();
-    const { exportBoxAsEnv, importEnvToBox, importVault } = useImportExportStore();
+    const { exportBoxAsEnv, importEnvToBox, importVault, activeJob, cancelActiveJob } = useImportExportStore();
 
     // Load data on mount
     useEffect(() => {
@@ -527,6 +527,7 @@ export const HomeScreen: React.FC<HomeScreenProps> = ({
                 toast.error("Some imports failed", `${result.errors.length} errors occurred`);
             }
         } catch (error) {
+            loadBoxes().catch(() => {}); // A cancelled import keeps the boxes it finished
             toast.error("Import failed", error instanceof Error ? error.message : "Failed to import vault");
         }
     };
@@ -775,6 +776,19 @@ export const HomeScreen: React.FC<HomeScreenProps> = ({
 
                     {/* 🎯 RESPONSIVE Main Content */}
                     <main className="flex-1 min-h-0 overflow-y-auto custom-scrollbar px-3 py-4 sm:px-4 sm:py-6">
+                        {/* Vault import running as a job */}
+                        {activeJob?.kind === "import" && (
+                            <div className="flex items-center justify-between gap-3 mb-3 sm:mb-4 px-3 py-2 border border-gray-700 rounded font-mono text-xs sm:text-sm text-gray-300">
+                                <span>
+                                    Importing vault
+                                    {activeJob.total > 0 ? ` - ${activeJob.done}/${activeJob.total} boxes` : "..."}
+                                </span>
+                                <Button variant="secondary" size="sm" onClick={() => cancelActiveJob()}>
+                                    Cancel
+                                </Button>
+                            </div>
+                        )}
+
                         {/* 🎯 RESPONSIVE Loading */}
                         {isLoading && (
                             <div className="text-center py-6 sm:py-8">
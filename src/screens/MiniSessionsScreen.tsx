@@ -0,0 +1,69 @@
+// src/screens/MiniSessionsScreen.tsx - Content of the always-on-top "mini" window
+import React, { useEffect } from "react";
+import { Lock, Terminal, X } from "lucide-react";
+import { Button } from "../components/Button";
+import { useAuthStore, useDevStore } from "../stores";
+import { windowCommands } from "../commands";
+
+const REFRESH_INTERVAL_MS = 5000;
+
+export const MiniSessionsScreen: React.FC = () => {
+    const sessions = useDevStore(state => state.sessions);
+    const loadAllSessions = useDevStore(state => state.loadAllSessions);
+    const error = useDevStore(state => state.error);
+    const lock = useAuthStore(state => state.lock);
+
+    // Sessions change from the main window and the CLI, so poll. get_all_sessions doesn't
+    // count as activity, an open mini window never keeps the vault from auto-locking.
+    useEffect(() => {
+        loadAllSessions();
+        const interval = setInterval(loadAllSessions, REFRESH_INTERVAL_MS);
+        return () => clearInterval(interval);
+    }, [loadAllSessions]);
+
+    const handleLock = async () => {
+        try {
+            await lock();
+        } finally {
+            await windowCommands.closeMiniWindow();
+        }
+    };
+
+    return (
+        <div className="h-screen bg-black text-white font-mono flex flex-col p-2 gap-2">
+            <div className="flex items-center justify-between">
+                <span className="text-xs text-gray-400">Active sessions</span>
+                <div className="flex items-center gap-1">
+                    <Button variant="danger" size="xs" icon={Lock} onClick={handleLock}>
+                        Lock
+                    </Button>
+                    <Button
+                        variant="ghost"
+                        size="xs"
+                        icon={X}
+                        onClick={() => windowCommands.closeMiniWindow()}
+                    />
+                </div>
+            </div>
+
+            <div className="flex-1 overflow-y-auto space-y-1">
+                {error ? (
+                    <p className="text-xs text-gray-500">{error}</p>
+                ) : sessions.length === 0 ? (
+                    <p className="text-xs text-gray-500">No active sessions</p>
+                ) : (
+                    sessions.map(session => (
+                        <div
+                            key={session.session_name}
+                            className="flex items-center gap-1.5 border border-gray-700 rounded px-2 py-1"
+                        >
+                            <Terminal className="w-3 h-3 flex-shrink-0 text-gray-400" />
+                            <span className="text-xs truncate flex-1">{session.session_name}</span>
+                            <span className="text-xs text-gray-500 truncate">{session.box_name}</span>
+                        </div>
+                    ))
+                )}
+            </div>
+        </div>
+    );
+};
@@ -4,3 +4,4 @@ export { BoxScreen } from './BoxScreen';
 export { HomeScreen } from './HomeScreen';
 export { SettingsScreen } from './SettingsScreen';
 export { LogsScreen } from './LogsScreen';
+export { MiniSessionsScreen } from './MiniSessionsScreen';
@@ -2,7 +2,7 @@
 
 import { create } from "zustand";
 import { authCommands, settingsCommands } from "../commands";
-import { validatePassword, getErrorMessage } from "../utils";
+import { validatePassword, getErrorCode, getErrorMessage } from "../utils";
 import type { Settings, SessionInfo } from "../types";
 
 interface AuthState {
@@ -24,6 +24,7 @@ interface AuthActions {
     unlock: (password: string, confirmPassword?: string) => Promise<void>;
     lock: () => Promise<void>;
     refreshSessionInfo: () => Promise<void>;
+    applySessionInfo: (sessionInfo: SessionInfo) => void;
     updateSessionTimeout: (minutes: number) => Promise<void>;
 
     // Frontend timer actions
@@ -169,7 +170,14 @@ export const useAuthStore = create<AuthStore>((set, get) => ({
 
             set({ sessionInfo });
         } catch (error) {
-            // On error, assume session is expired
+            // Backend errors other than an expired session leave the vault unlocked
+            const code = getErrorCode(error);
+            if (code !== null && code !== "SESSION_EXPIRED") {
+                set({ error: getErrorMessage(error) });
+                return;
+            }
+
+            // Expired, or the backend couldn't be reached - assume the session is gone
             set({
                 isUnlocked: false,
                 sessionInfo: null,
@@ -179,6 +187,27 @@ export const useAuthStore = create<AuthStore>((set, get) => ({
         }
     },
 
+    // From the "session-state" event - the backend's time left is authoritative
+    applySessionInfo: (sessionInfo: SessionInfo) => {
+        if (!get().isUnlocked) return;
+
+        if (sessionInfo.is_locked) {
+            set({
+                isUnlocked: false,
+                sessionInfo: null,
+                frontendTimeLeft: 0,
+                lastBackendSync: 0,
+            });
+            return;
+        }
+
+        set({
+            sessionInfo,
+            frontendTimeLeft: sessionInfo.time_left_seconds,
+            lastBackendSync: Date.now(),
+        });
+    },
+
     updateSessionTimeout: async (minutes: number) => {
         if (minutes < 5 || minutes > 60) {
             throw new Error("Session timeout must be between 5 and 60 minutes");
@@ -1,7 +1,7 @@
 // src/stores/boxStore.ts
 import { create } from "zustand";
 import { boxCommands, statsCommands } from "../commands";
-import { getErrorMessage } from "../utils";
+import { getErrorCode, getErrorMessage } from "../utils";
 import type { Box, BoxFormData, VaultStats } from "../types";
 
 interface BoxState {
@@ -102,6 +102,14 @@ export const useBoxStore = create<BoxStore>((set) => ({
 
             set({ boxes, stats, isLoading: false });
         } catch (error) {
+            // Already deleted elsewhere (another window, a replica pull), just drop it
+            if (getErrorCode(error) === "BOX_NOT_FOUND") {
+                set((state) => ({
+                    boxes: state.boxes.filter(box => box.id !== boxId),
+                    isLoading: false,
+                }));
+                return;
+            }
             const errorMessage = getErrorMessage(error);
             set({ error: errorMessage, isLoading: false });
             throw new Error(errorMessage);
@@ -1,12 +1,13 @@
 // src/stores/importExportStore.ts 
 import { create } from "zustand";
-import { importExportCommands } from "../commands";
+import { importExportCommands, jobCommands } from "../commands";
 import { getErrorMessage } from "../utils";
-import type { ImportResult } from "../types";
+import type { ImportResult, JobInfo } from "../types";
 
 interface ImportExportState {
     isLoading: boolean;
     error: string | null;
+    activeJob: JobInfo | null; // Vault import or export running as a job, with its progress
 }
 
 interface ImportExportActions {
@@ -14,6 +15,7 @@ interface ImportExportActions {
     exportBoxAsEnv: (boxId: string, prefix?: string) => Promise<string>;
     importVault: (jsonData: string) => Promise<ImportResult>;
     importEnvToBox: (envContent: string, targetBoxId: string) => Promise<ImportResult>;
+    cancelActiveJob: () => Promise<void>;
     clearError: () => void;
     reset: () => void;
 }
@@ -23,20 +25,23 @@ type ImportExportStore = ImportExportState & ImportExportActions;
 const initialState: ImportExportState = {
     isLoading: false,
     error: null,
+    activeJob: null,
 };
 
-export const useImportExportStore = create<ImportExportStore>((set) => ({
+export const useImportExportStore = create<ImportExportStore>((set, get) => ({
     ...initialState,
 
     exportVault: async () => {
         set({ isLoading: true, error: null });
         try {
-            const exportData = await importExportCommands.exportVault();
-            set({ isLoading: false });
+            const jobId = await jobCommands.startExportVaultJob();
+            await jobCommands.waitForJob(jobId, job => set({ activeJob: job }));
+            const exportData = await jobCommands.takeJobPayload(jobId);
+            set({ isLoading: false, activeJob: null });
             return exportData;
         } catch (error) {
             const errorMessage = getErrorMessage(error);
-            set({ error: errorMessage, isLoading: false });
+            set({ error: errorMessage, isLoading: false, activeJob: null });
             throw new Error(errorMessage);
         }
     },
@@ -57,12 +62,13 @@ export const useImportExportStore = create<ImportExportStore>((set) => ({
     importVault: async (jsonData: string) => {
         set({ isLoading: true, error: null });
         try {
-            const result = await importExportCommands.importVault(jsonData);
-            set({ isLoading: false });
-            return result;
+            const jobId = await jobCommands.startImportVaultJob(jsonData);
+            const job = await jobCommands.waitForJob(jobId, job => set({ activeJob: job }));
+            set({ isLoading: false, activeJob: null });
+            return job.result as ImportResult;
         } catch (error) {
             const errorMessage = getErrorMessage(error);
-            set({ error: errorMessage, isLoading: false });
+            set({ error: errorMessage, isLoading: false, activeJob: null });
             throw new Error(errorMessage);
         }
     },
@@ -80,6 +86,14 @@ export const useImportExportStore = create<ImportExportStore>((set) => ({
         }
     },
 
+    // Boxes an import finished before the cancel are kept
+    cancelActiveJob: async () => {
+        const job = get().activeJob;
+        if (job) {
+            await jobCommands.cancelJob(job.id);
+        }
+    },
+
     clearError: () => set({ error: null }),
     reset: () => set(initialState),
 }));
\ No newline at end of file
@@ -3,13 +3,18 @@
 import { create } from "zustand";
 import { boxCommands, secretCommands } from "../commands";
 import { getErrorMessage } from "../utils";
-import type { Box, Secret } from "../types";
+import type { Box, MatchSpan, Secret } from "../types";
 
 interface SearchState {
     // Results
     boxResults: Box[] | null;
     secretResults: Secret[] | null;
 
+    // Match metadata keyed by box / secret id, for highlighting
+    boxMatches: Record<string, MatchSpan[]>;
+    boxMatchedTags: Record<string, string[]>;
+    secretMatches: Record<string, MatchSpan[]>;
+
     // Query state
     activeQuery: string;
     activeBoxId: string | null; // For secret search within box
@@ -31,6 +36,9 @@ type SearchStore = SearchState & SearchActions;
 const initialState: SearchState = {
     boxResults: null,
     secretResults: null,
+    boxMatches: {},
+    boxMatchedTags: {},
+    secretMatches: {},
     activeQuery: "",
     activeBoxId: null,
     isLoading: false,
@@ -43,10 +51,13 @@ export const useSearchStore = create<SearchStore>((set) => ({
     searchBoxes: async (query: string, tags: string[]) => {
         set({ isLoading: true, error: null, activeQuery: query, activeBoxId: null });
         try {
-            const boxResults = await boxCommands.searchBoxesGlobal(query, tags);
+            const results = await boxCommands.searchBoxesGlobal(query, tags);
             set({
-                boxResults,
+                boxResults: results.map(result => result.item),
+                boxMatches: Object.fromEntries(results.map(r => [r.item.id, r.matches])),
+                boxMatchedTags: Object.fromEntries(results.map(r => [r.item.id, r.matched_tags])),
                 secretResults: null, // Clear secret results when searching boxes
+                secretMatches: {},
                 isLoading: false
             });
         } catch (error) {
@@ -60,10 +71,13 @@ export const useSearchStore = create<SearchStore>((set) => ({
     searchSecretsInBox: async (boxId: string, query: string) => {
         set({ isLoading: true, error: null, activeQuery: query, activeBoxId: boxId });
         try {
-            const secretResults = await secretCommands.searchSecretsInBox(boxId, query);
+            const results = await secretCommands.searchSecretsInBox(boxId, query);
             set({
-                secretResults,
+                secretResults: results.map(result => result.item),
+                secretMatches: Object.fromEntries(results.map(r => [r.item.id, r.matches])),
                 boxResults: null, // Clear box results when searching secrets
+                boxMatches: {},
+                boxMatchedTags: {},
                 isLoading: false
             });
         } catch (error) {
@@ -77,6 +91,9 @@ export const useSearchStore = create<SearchStore>((set) => ({
     clearSearch: () => set({
         boxResults: null,
         secretResults: null,
+        boxMatches: {},
+        boxMatchedTags: {},
+        secretMatches: {},
         activeQuery: "",
         activeBoxId: null
     }),
@@ -1,21 +1,22 @@
 // src/stores/secretsStore.ts
 import { create } from "zustand";
 import { secretCommands } from "../commands";
-import { copyToClipboard, getErrorMessage } from "../utils";
-import type { Secret, SecretFormData } from "../types";
+import { copyToClipboard, getErrorCode, getErrorMessage } from "../utils";
+import type { SavedSecret, Secret, SecretFormData, SecretWarning } from "../types";
 
 interface SecretsState {
     secrets: Secret[];
     boxSecrets: Map<string, Secret[]>;
     isLoading: boolean;
     error: string | null;
+    warnings: SecretWarning[]; // From the last create/update, the secret was still saved
 }
 
 interface SecretsActions {
     loadAllSecrets: () => Promise<void>;
     loadSecretsByBox: (boxId: string) => Promise<void>;
-    createSecret: (boxId: string, data: SecretFormData) => Promise<string>;
-    updateSecret: (secretId: string, data: Partial<SecretFormData>) => Promise<void>;
+    createSecret: (boxId: string, data: SecretFormData) => Promise<SavedSecret>;
+    updateSecret: (secretId: string, data: Partial<SecretFormData>) => Promise<SavedSecret>;
     deleteSecret: (secretId: string) => Promise<void>;
 
     revealSecretValue: (secretId: string) => Promise<string>;
@@ -32,6 +33,7 @@ const initialState: SecretsState = {
     boxSecrets: new Map(),
     isLoading: false,
     error: null,
+    warnings: [],
 };
 
 export const useSecretStore = create<SecretsStore>((set, get) => ({
@@ -61,12 +63,15 @@ export const useSecretStore = create<SecretsStore>((set, get) => ({
     },
 
     createSecret: async (boxId: string, data: SecretFormData) => {
-        set({ isLoading: true, error: null });
+        set({ isLoading: true, error: null, warnings: [] });
         try {
-            const secretId = await secretCommands.createSecret(
+            const saved = await secretCommands.createSecret(
                 boxId,
                 data.name,
-                data.value
+                data.value,
+                data.value_encoding,
+                data.multiline_format,
+                data.normalize_line_endings
             );
 
             const [allSecrets, boxSecretsArray] = await Promise.all([
@@ -81,9 +86,10 @@ export const useSecretStore = create<SecretsStore>((set, get) => ({
             set({
                 secrets: allSecrets,
                 boxSecrets: newBoxSecrets,
-                isLoading: false
+                isLoading: false,
+                warnings: saved.warnings
             });
-            return secretId;
+            return saved;
         } catch (error) {
             const errorMessage = getErrorMessage(error);
             set({ error: errorMessage, isLoading: false });
@@ -92,12 +98,15 @@ export const useSecretStore = create<SecretsStore>((set, get) => ({
     },
 
     updateSecret: async (secretId: string, data: Partial<SecretFormData>) => {
-        set({ isLoading: true, error: null });
+        set({ isLoading: true, error: null, warnings: [] });
         try {
-            await secretCommands.updateSecret(
+            const saved = await secretCommands.updateSecret(
                 secretId,
                 data.name || null,
-                data.value || null
+                data.value || null,
+                data.value_encoding,
+                data.multiline_format,
+                data.normalize_line_endings
             );
 
             // Reload data after update
@@ -117,14 +126,17 @@ export const useSecretStore = create<SecretsStore>((set, get) => ({
                 set({
                     secrets: allSecrets,
                     boxSecrets: newBoxSecrets,
-                    isLoading: false
+                    isLoading: false,
+                    warnings: saved.warnings
                 });
             } else {
                 set({
                     secrets: allSecrets,
-                    isLoading: false
+                    isLoading: false,
+                    warnings: saved.warnings
                 });
             }
+            return saved;
         } catch (error) {
             const errorMessage = getErrorMessage(error);
             set({ error: errorMessage, isLoading: false });
@@ -159,6 +171,20 @@ export const useSecretStore = create<SecretsStore>((set, get) => ({
                 isLoading: false
             });
         } catch (error) {
+            // Already deleted elsewhere (another window, a replica pull), just drop it
+            if (getErrorCode(error) === "SECRET_NOT_FOUND") {
+                const { secrets, boxSecrets } = get();
+                const newBoxSecrets = new Map(boxSecrets);
+                for (const [boxId, boxSecretsArray] of boxSecrets) {
+                    newBoxSecrets.set(boxId, boxSecretsArray.filter(s => s.id !== secretId));
+                }
+                set({
+                    secrets: secrets.filter(s => s.id !== secretId),
+                    boxSecrets: newBoxSecrets,
+                    isLoading: false
+                });
+                return;
+            }
             const errorMessage = getErrorMessage(error);
             set({ error: errorMessage, isLoading: false });
             throw new Error(errorMessage);
@@ -6,7 +6,44 @@ export interface AuthConfig {
     salt: number[];
 }
 
+// grace_period = sensitive actions need the password again, expiring = auto-lock is close
+export type SessionPhase = "locked" | "unlocked" | "grace_period" | "expiring";
+
+// Also the payload of the "session-state" event, sent on every phase change or timer jump
 export interface SessionInfo {
     is_locked: boolean;
     time_left_seconds: number;
-}
\ No newline at end of file
+    phase: SessionPhase;
+}
+
+// Printable emergency kit - only returned right after a recovery key is generated
+export interface EmergencyKit {
+    recovery_key: string;
+    created_at: number;
+    data_directory: string;
+    instructions: string;
+}
+
+// Whether the lock screen asks for a PIN plus YubiKey instead of the password
+export interface HardwareKeyStatus {
+    enabled: boolean;
+    slot: number | null;
+    device_present: boolean;
+}
+
+export type DatabaseKind = "vault" | "sessions" | "logs";
+
+// Result of opening the databases at startup
+export type StartupStatus =
+    | { status: "ready" }
+    | { status: "locked"; database: DatabaseKind } // Another process holds the database
+    | {
+        status: "corrupted";
+        database: DatabaseKind;
+        error: string;
+        snapshot_available: boolean;
+    }
+    | { status: "data_directory_unavailable"; path: string } // Moved-to folder is missing
+    | { status: "failed"; error: string };
+
+export type StorageRecovery = "retry" | "restore_snapshot" | "repair";
\ No newline at end of file
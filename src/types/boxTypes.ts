@@ -1,4 +1,11 @@
 // src/types/boxTypes.ts
+import { EncryptedData } from "./secretTypes";
+
+export interface BoxProvenance {
+    source_box_id: string | null; // Box id on the exporting machine
+    exported_at: number; // Unix timestamp from chrono
+    imported_at: number; // Unix timestamp from chrono
+}
 
 export interface Box {
     id: string;
@@ -7,10 +14,31 @@ export interface Box {
     tags: string[];
     dev_mode: boolean;
     secrets_count: number;
+    wrapped_key: EncryptedData | null; // Per-box data key wrapped by the master key
+    read_only: boolean; // Shared by a teammate, updated only by importing their newer export
+    provenance: BoxProvenance | null;
     created_at: number; // Unix timestamp from chrono
     updated_at: number; // Unix timestamp from chrono
 }
 
+// Secret names only - values are compared in the backend and never returned
+export interface BoxDiff {
+    box_a_id: string;
+    box_a_name: string;
+    box_b_id: string;
+    box_b_name: string;
+    only_in_a: string[];
+    only_in_b: string[];
+    different_value: string[]; // Names as they appear in box A
+    same_value_count: number;
+}
+
+export interface UndoResult {
+    description: string; // The operation that was undone
+    restored: number; // Boxes or secrets put back
+    skipped: string[]; // Names taken by a newer item since
+}
+
 export interface BoxStats {
     total_boxes: number;
     dev_boxes: number;
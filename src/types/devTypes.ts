@@ -2,9 +2,50 @@
 
 export interface ActiveSessionInfo {
     session_name: string;
+    box_id: string;
     box_name: string;
     secrets_count: number;
     is_active: boolean;
+    created_at: number | null; // Unix seconds, null for sessions created before tracking
+    expires_at: number | null; // null = never expires
+    last_used_at: number | null; // Unix seconds, null = the CLI hasn't loaded it yet
+    idle_seconds: number; // Since last use, or since creation when never used
+    is_stale: boolean; // Source box changed since the session was created
+}
+
+// Returned once - the token is what the CLI passes as --token / ZAP_SESSION_TOKEN
+export interface ProxySessionToken {
+    session_name: string;
+    box_name: string;
+    token: string;
+    secrets_count: number;
+    expires_at: number; // Unix seconds
+}
+
+export interface ProxySessionInfo {
+    session_name: string;
+    box_name: string;
+    secrets_count: number;
+    fetch_count: number;
+    expires_at: number; // Unix seconds
+}
+
+// Payload of the "app-access-requested" event - a program on neither list in
+// Settings.app_access wants a proxy session. Unanswered after 60 seconds it's denied.
+export interface AppAccessRequest {
+    request_id: string;
+    exe_path: string;
+    pid: number;
+    session_name: string;
+}
+
+// ssh_config written for a session - Include config_path from ~/.ssh/config.
+// The folder (and its 0600 key files) is removed when the session stops.
+export interface SshConfigExport {
+    config: string;
+    config_path: string;
+    hosts: string[];
+    key_files: number;
 }
 
 export interface DevStats {
@@ -14,6 +55,17 @@ export interface DevStats {
     active_sessions_count: number;
 }
 
+export interface SessionFileSchema {
+    format_version: number;
+    min_format_version: number;
+}
+
+export interface SessionFileCompatibility {
+    desktop: SessionFileSchema;
+    cli: SessionFileSchema | null;
+    compatible: boolean;
+}
+
 export interface DevBoxInfo {
     id: string;
     name: string;
@@ -21,6 +73,12 @@ export interface DevBoxInfo {
     secrets_count: number;
 }
 
+// Secret names picked for a session - include narrows the box, exclude then drops names
+export interface SecretSelection {
+    include?: string[];
+    exclude?: string[];
+}
+
 export interface CreateSessionRequest {
     session_name: string;
     box_id: string;
@@ -22,6 +22,51 @@ export type ZapErrorType =
     | 'SerializationError'
     | 'Utf8Error';
 
+// Stable codes sent by every backend command
+export type ZapErrorCode =
+    | 'STORAGE_ERROR'
+    | 'DATABASE_ERROR'
+    | 'IO_ERROR'
+    | 'VALIDATION_ERROR'
+    | 'INVALID_TAGS'
+    | 'BOX_NOT_FOUND'
+    | 'BOX_ALREADY_EXISTS'
+    | 'BOX_CAPACITY_EXCEEDED'
+    | 'INVALID_DEV_BOX'
+    | 'READ_ONLY_BOX'
+    | 'PROD_APPROVAL_REQUIRED'
+    | 'SECRET_NOT_FOUND'
+    | 'SECRET_ALREADY_EXISTS_IN_BOX'
+    | 'REVEAL_RATE_LIMITED'
+    | 'PLAINTEXT_REVEAL_DISABLED'
+    | 'INVALID_REVEAL_HANDLE'
+    | 'AUTH_ERROR'
+    | 'INCORRECT_PASSWORD'
+    | 'SESSION_EXPIRED'
+    | 'REAUTHENTICATION_REQUIRED'
+    | 'CRYPTO_ERROR'
+    | 'SESSION_NOT_FOUND'
+    | 'SESSION_ALREADY_EXISTS'
+    | 'NO_SESSIONS_EXIST'
+    | 'INVALID_SESSION_NAME'
+    | 'INVALID_SESSION_KEY'
+    | 'NO_CURRENT_SESSION'
+    | 'INVALID_PROJECT_CONTEXT'
+    | 'SESSIONS_DATABASE_NOT_FOUND'
+    | 'INCOMPATIBLE_SESSION_FORMAT'
+    | 'PLATFORM_ERROR'
+    | 'SERIALIZATION_ERROR'
+    | 'UTF8_ERROR'
+    | 'JOB_NOT_FOUND'
+    | 'CANCELLED';
+
+// Error payload rejected by core.invoke
+export interface ZapCommandError {
+    code: ZapErrorCode;
+    message: string;
+    details: string | null;
+}
+
 // Frontend error handling
 export interface AppError {
     type: ZapErrorType;
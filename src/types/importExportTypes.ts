@@ -1,27 +1,167 @@
 // src/types/importExportTypes.ts
 
 export interface VaultExport {
-    version: string;
+    version: string; // "2.0" adds ids and created_at / updated_at, "1.0" files still import
     total_boxes: number;
     total_secrets: number;
     boxes: BoxExport[];
+    signature?: ExportSignature; // Missing in unsigned or hand-made files
 }
 
+// Ed25519 over the export's compact JSON without the signature
+export interface ExportSignature {
+    public_key: string; // Hex
+    signature: string; // Hex
+}
+
+// trusted = this vault or a trusted key, untrusted = another vault, invalid = modified
+export type SignatureStatus = "unsigned" | "trusted" | "untrusted" | "invalid";
+
 export interface BoxExport {
     name: string;
     description: string | null;
     tags: string[];
     dev_mode: boolean;
     secrets: SecretExport[];
+    id?: string; // Box id on the exporting machine, reused on import when free
+    exported_at?: number; // Unix timestamp, newer exports refresh read-only shared boxes
+    created_at?: number; // Unix timestamps, missing in 1.0 exports
+    updated_at?: number;
 }
 
 export interface SecretExport {
+    id?: string; // Reused on import when free
     name: string;
     value: string;
+    env_name?: string;
+    created_at?: number; // Unix timestamps, missing in 1.0 exports
+    updated_at?: number;
+}
+
+// Terraform / OpenTofu variable files: terraform.tfvars or *.auto.tfvars.json
+export type TfvarsFormat = "hcl" | "json";
+
+// JSON for the external-secrets webhook provider. ExternalSecrets name the box by
+// remote_key and pick values out of document with each property's json_path.
+export interface ExternalSecretsExport {
+    remote_key: string; // Box name as a DNS-style slug
+    document: string; // Pretty-printed JSON object
+    properties: ExternalSecretProperty[];
+}
+
+export interface ExternalSecretProperty {
+    secret_name: string;
+    property: string; // remoteRef.property, dotted for nested values
+    json_path: string; // "$.database.password"
+}
+
+// Outcome of pushing one secret to GitHub Actions
+export type GithubSecretStatus = "created" | "updated" | "failed";
+
+export interface GithubSecretResult {
+    secret_name: string;
+    github_name: string; // As workflows read it: secrets.DB_PASSWORD
+    status: GithubSecretStatus;
+    error: string | null; // Set when the push failed
+}
+
+export interface GithubPushReport {
+    repository: string; // "owner/repo"
+    github_environment: string | null; // null = repository secrets
+    results: GithubSecretResult[];
+}
+
+// Where GitLab CI/CD variables go. path is "group/project", "group/subgroup" or the numeric id
+export interface GitlabTarget {
+    instance_url?: string | null; // Omitted = gitlab.com, or a self-managed instance
+    scope: "project" | "group";
+    path: string;
+    environment_scope?: string | null; // Omitted = "*", all environments
+}
+
+// update = value, masked or protected differ
+export type GitlabChange = "create" | "update" | "unchanged";
+
+// One secret in a GitLab sync, values are compared in the backend and never sent back
+export interface GitlabVariableChange {
+    secret_name: string;
+    key: string;
+    change: GitlabChange;
+    masked: boolean; // Whenever GitLab can mask the value
+    protected: boolean; // Boxes tagged prod, protected branches and tags only
+    value_changed: boolean; // False for flag-only updates
+    note: string | null; // Why the value isn't masked
+    error: string | null; // Unusable key, or the update GitLab refused
+}
+
+// Returned by the preview and by the sync itself (applied)
+export interface GitlabSyncReport {
+    target: string; // "group/project (project)"
+    environment_scope: string;
+    applied: boolean;
+    variables: GitlabVariableChange[];
+    remote_only: string[]; // Keys GitLab has that the box doesn't, left alone
 }
 
+// How a merge import settles items that exist on both sides with different content.
+// keep_newest compares updated_at; items without one (1.0 exports) keep the local side
+export type MergeStrategy = "keep_local" | "keep_import" | "keep_newest";
+
 export interface ImportResult {
     boxes_imported: number;
+    boxes_updated: number; // Shared boxes refreshed, or local boxes changed by a merge
     secrets_imported: number;
+    secrets_updated: number; // Merge conflicts settled in favour of the import
     errors: string[];
-}
\ No newline at end of file
+    signature: SignatureStatus; // Warn the user when untrusted or invalid
+}
+
+// The vault now against an earlier export, by name. "added" = only in the vault,
+// "removed" = only in the export. Values are compared in the backend, never returned.
+export interface VaultDiff {
+    boxes_added: string[];
+    boxes_removed: string[];
+    boxes_changed: BoxChanges[];
+}
+
+export interface BoxChanges {
+    box_name: string;
+    details_changed: boolean; // Description, tags or dev mode
+    secrets_added: string[];
+    secrets_removed: string[];
+    secrets_changed: string[];
+}
+
+// Payload of the "import-progress" event, sent after each box of a vault import
+export interface ImportProgress {
+    boxes_done: number;
+    boxes_total: number;
+    secrets_imported: number;
+}
+
+// How smart import read pasted text ("key_value" = `key: value` lines)
+export type SmartImportFormat = "env" | "json" | "csv" | "key_value";
+
+// One pasted value as it would be imported, values are never sent back
+export interface SmartImportEntry {
+    name: string;
+    env_name: string | null;
+    already_exists: boolean; // Skipped on import, the box keeps its value
+    error: string | null; // Invalid name, empty or oversized value, skipped on import
+}
+
+export interface SmartImportPreview {
+    format: SmartImportFormat;
+    entries: SmartImportEntry[];
+    warnings: string[]; // Lines or rows that couldn't be read, repeated names
+    fits_in_box: boolean; // False when the new secrets would pass the 75 secret limit
+}
+
+// strict: any unresolved {{secret:Box/Secret}} fails the render
+// lenient: unresolved placeholders stay in place and are listed in `missing`
+export type TemplateMode = "strict" | "lenient";
+
+export interface RenderedTemplate {
+    content: string;
+    missing: string[];
+}
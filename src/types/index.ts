@@ -1,28 +1,74 @@
 // src/types/index.ts
 
 // Auth types
-export type { AuthConfig, SessionInfo } from './authTypes';
+export type {
+    AuthConfig,
+    SessionInfo,
+    SessionPhase,
+    EmergencyKit,
+    HardwareKeyStatus,
+    DatabaseKind,
+    StartupStatus,
+    StorageRecovery
+} from './authTypes';
 
 // Box types
 export type {
     Box,
-    BoxStats
+    BoxDiff,
+    BoxProvenance,
+    BoxStats,
+    UndoResult
 } from './boxTypes';
 
 // Secret types 
 export type {
     Secret,
     EncryptedData,
+    CipherAlgorithm,
+    ValueEncoding,
+    MultilineFormat,
     Settings,
+    SecretWarningCode,
+    SecretWarning,
+    SavedSecret,
+    PasswordStrength,
     VaultStats,
-    SearchResults
+    KeyGroupUsage,
+    DatabaseUsage,
+    StorageUsage,
+    DataDirectoryInfo,
+    SnapshotReason,
+    VaultSnapshot,
+    PathDiagnostic,
+    DatabaseDiagnostic,
+    KdfParams,
+    DiagnosticsReport,
+    SearchResults,
+    MatchSpan,
+    BoxSearchResult,
+    SecretSearchResult,
+    DeepSearchMatch,
+    RecentSecret,
+    CompromisedSecret,
+    SecretTemplate,
+    SecretTemplateField,
+    OneTimeShare,
+    RedeemedShare
 } from './secretTypes';
 
 // Dev types 
 export type {
     ActiveSessionInfo,
+    ProxySessionToken,
+    ProxySessionInfo,
+    AppAccessRequest,
+    SshConfigExport,
     DevStats,
     DevBoxInfo,
+    SessionFileSchema,
+    SessionFileCompatibility,
+    SecretSelection,
     CreateSessionRequest,
     SessionValidationResult,
     DevSessionsState,
@@ -32,14 +78,61 @@ export type {
 // Import/Export types 
 export type {
     VaultExport,
+    ExportSignature,
+    SignatureStatus,
     BoxExport,
     SecretExport,
-    ImportResult
+    TfvarsFormat,
+    ExternalSecretsExport,
+    ExternalSecretProperty,
+    GithubSecretStatus,
+    GithubSecretResult,
+    GithubPushReport,
+    GitlabTarget,
+    GitlabChange,
+    GitlabVariableChange,
+    GitlabSyncReport,
+    ImportResult,
+    ImportProgress,
+    MergeStrategy,
+    TemplateMode,
+    RenderedTemplate,
+    VaultDiff,
+    BoxChanges,
+    SmartImportFormat,
+    SmartImportEntry,
+    SmartImportPreview
 } from './importExportTypes';
 
+// Job types
+export type {
+    JobKind,
+    JobStatus,
+    JobInfo,
+    TransferScope,
+    LanTransferOffer,
+    LanTransferReceipt
+} from './jobTypes';
+
+// Sync types
+export type {
+    SyncProviderConfig,
+    SyncMapping,
+    SyncState,
+    SyncDiffEntry,
+    SyncDiff,
+    SyncResult,
+    ReplicaTarget,
+    ReplicaConfig,
+    ConflictResolution,
+    SyncConflict,
+    ReplicaSyncReport
+} from './syncTypes';
+
 // ✅ NEW: Log types
 export type {
     LogEntry,
+    LogSeverity,
     LogFilters,
 } from './logTypes';
 
@@ -56,6 +149,8 @@ export type {
 // Error types
 export type {
     ZapErrorType,
+    ZapErrorCode,
+    ZapCommandError,
     AppError,
     AppResult
 } from './errorTypes';
@@ -0,0 +1,39 @@
+// src/types/jobTypes.ts
+
+// transfer = LAN transfer, either side; reencrypt = encryption migrations run at unlock
+export type JobKind = "import" | "export" | "transfer" | "reencrypt" | "breach_check";
+
+export type JobStatus = "running" | "completed" | "failed" | "cancelled";
+
+// Also the payload of the "job-progress" event
+export interface JobInfo {
+    id: string;
+    kind: JobKind;
+    status: JobStatus;
+    done: number;
+    total: number; // 0 = not known yet
+    message: string | null;
+    // ImportResult for imports and received transfers, LanTransferReceipt for sent transfers,
+    // CompromisedSecret[] for breach checks. null for exports: takeJobPayload hands out the JSON
+    result: unknown | null;
+    error: string | null;
+    started_at: number;
+}
+
+export type TransferScope = { type: "vault" } | { type: "box"; box_id: string };
+
+// Shown on the sending device; the code is typed on the receiving one, or the link / QR used
+export interface LanTransferOffer {
+    job_id: string; // Completes once the receiver has imported, cancelJob withdraws the offer
+    code: string; // xxxx-xxxxx-xxxxx
+    link: string | null; // null when no LAN address was found, the code still works
+    qr_svg: string | null; // The link as a QR code
+    expires_at: number;
+}
+
+// Result of a sending job
+export interface LanTransferReceipt {
+    boxes_imported: number;
+    secrets_imported: number;
+    errors: string[];
+}
@@ -1,9 +1,11 @@
 // src/types/logTypes.ts
 
+export type LogSeverity = "info" | "warning" | "security";
 
 export interface LogEntry {
     id: string;
     action: string;
+    severity: LogSeverity;
     message: string;
     content: string | null;
     timestamp: number; // Unix timestamp from chrono
@@ -1,11 +1,21 @@
 // src/types/secretTypes.ts
+import { DatabaseKind, StartupStatus } from "./authTypes";
 import { Box } from "./boxTypes";
 
 export interface Secret {
     id: string;
     box_id: string; // Foreign key to box
     name: string;
-    encrypted_value: EncryptedData;
+    encrypted_value: EncryptedData; // Empty in listings when value_in_blob
+    value_in_blob: boolean; // Large value, fetched only when revealed or used
+    value_encoding: ValueEncoding;
+    multiline_format: MultilineFormat; // .env export style for values with line breaks
+    variants: Record<string, EncryptedData>; // Per-environment values, e.g. "prod"
+    env_name: string | null; // Original env var name from .env import
+    favorite: boolean; // Pinned for quick access, ranked first in search
+    strength: PasswordStrength | null; // Scored when a password-kind value is saved
+    rotation_interval_days: number | null; // null = no rotation reminders
+    last_rotated_at: number | null; // Unix timestamp, null = count from created_at
     created_at: number; // Unix timestamp from chrono
     updated_at: number; // Unix timestamp from chrono
 }
@@ -14,22 +24,217 @@ export interface EncryptedData {
     cipher: number[]; 
     nonce: number[];   
     tag: number[]; 
+    algorithm: CipherAlgorithm;
+    bound: boolean; // Authenticated with its secret id and box id
 }
 
+export type CipherAlgorithm = 'aes-256-gcm' | 'xchacha20-poly1305';
+
+// Binary values (certificates, keystores) are sent and revealed as base64 text
+export type ValueEncoding = 'utf8' | 'base64';
+
+// 'escaped' writes KEY="a\nb" on one line, 'quoted' keeps the line breaks (PEM blocks)
+export type MultilineFormat = 'escaped' | 'quoted';
+
 export interface Settings {
     password_timeout_minutes: number;
     theme: string;
 }
 
+export type SecretWarningCode =
+    | "surrounding_whitespace"
+    | "surrounding_quotes"
+    | "invalid_url"
+    | "invalid_aws_access_key_id"
+    | "invalid_aws_secret_access_key";
+
+// Advisory check on a saved value - never contains the value itself
+export interface SecretWarning {
+    code: SecretWarningCode;
+    message: string;
+}
+
+export interface SavedSecret {
+    id: string;
+    warnings: SecretWarning[];
+    strength: PasswordStrength | null; // Only for password-kind secrets (DB_PASSWORD ...)
+}
+
+// zxcvbn estimate - score runs from 0 (guessable) to 4 (very unguessable)
+export interface PasswordStrength {
+    score: number;
+    crack_time: string; // Human readable, e.g. "3 hours"
+    crack_time_seconds: number; // Offline attack on a slow hash
+    feedback: string | null;
+}
+
 export interface VaultStats {
     total_boxes: number;
     total_secrets: number;
     last_updated: number;
 }
 
+// Records sharing a key prefix ("box", "secret", "box_name" ...)
+export interface KeyGroupUsage {
+    prefix: string;
+    records: number;
+    bytes: number;
+}
+
+export interface DatabaseUsage {
+    database: DatabaseKind;
+    bytes_on_disk: number;
+    live_bytes: number; // Roughly what compaction would shrink it to
+    records: number;
+    groups: KeyGroupUsage[]; // Largest first
+}
+
+export interface StorageUsage {
+    databases: DatabaseUsage[];
+    total_bytes_on_disk: number;
+    compaction_scheduled: boolean; // Runs on the next start
+}
+
+// Where the databases are kept, see settingsCommands.moveDataDirectory
+export interface DataDirectoryInfo {
+    path: string;
+    default_path: string; // Where they go back to when moved with no path
+    moved: boolean;
+}
+
+// What was about to happen when a vault snapshot was taken
+export type SnapshotReason =
+    | "import"
+    | "merge_import"
+    | "bulk_delete"
+    | "reencrypt" // Master key change or a key migration
+    | "restore"; // The vault as it was before another snapshot was restored
+
+// Copy of the vault taken before a risky operation, the newest 10 are kept
+export interface VaultSnapshot {
+    id: string; // Passed to restoreSnapshot
+    reason: SnapshotReason;
+    created_at: number;
+    bytes: number;
+}
+
+export interface PathDiagnostic {
+    name: string;
+    path: string;
+    exists: boolean;
+    is_dir: boolean;
+    read_only: boolean;
+    mode: string | null; // Unix permission bits in octal, e.g. "700"
+}
+
+export interface DatabaseDiagnostic {
+    database: DatabaseKind;
+    open: boolean;
+}
+
+export interface KdfParams {
+    memory_kib: number;
+    iterations: number;
+    parallelism: number;
+}
+
+// Health check for support tickets - never contains names or secret values
+export interface DiagnosticsReport {
+    generated_at: number;
+    app_version: string;
+    os: string;
+    arch: string;
+    startup_status: StartupStatus;
+    paths: PathDiagnostic[];
+    databases: DatabaseDiagnostic[];
+    schema_version: number | null; // null when the vault isn't open
+    latest_schema_version: number;
+    orphaned_session_files: number; // Session files with no session in the app
+    missing_session_files: number; // Sessions in the app whose file is gone
+    last_backup_at: number | null; // Last automatic export
+    keyring_available: boolean;
+    keyring_error: string | null;
+    kdf_params: KdfParams | null; // null before first-time setup
+    locked: boolean;
+    errors: string[]; // Checks that couldn't run
+}
+
 export interface SearchResults {
     matching_secrets: Secret[];
     matching_boxes: Box[];
     query: string;
     total_matches: number;
+}
+
+// Byte range [start, end) into the matched field's UTF-8 text
+export interface MatchSpan {
+    field: "name" | "tag";
+    start: number;
+    end: number;
+    tag?: string; // Set for tag matches
+}
+
+export interface BoxSearchResult {
+    item: Box;
+    matches: MatchSpan[];
+    matched_tags: string[];
+}
+
+export interface SecretSearchResult {
+    item: Secret;
+    matches: MatchSpan[];
+}
+
+// Built-in recipe a secret value can be generated from (random tokens, database URLs)
+export interface SecretTemplate {
+    id: string; // Pass to createSecretFromTemplate
+    name: string;
+    description: string;
+    fields: SecretTemplateField[]; // Empty for pure generators
+}
+
+export interface SecretTemplateField {
+    key: string;
+    label: string;
+    required: boolean;
+    sensitive: boolean; // Mask the input, e.g. a password
+}
+
+// Entry of the "Recent" list: a secret that was revealed or copied
+export interface RecentSecret {
+    item: Secret;
+    box_name: string;
+    last_used_at: number; // Unix seconds
+    use_count: number;
+}
+
+// A password whose current value was found by the Have I Been Pwned breach check
+export interface CompromisedSecret {
+    item: Secret;
+    box_name: string;
+    pwned_count: number; // Times seen in known breaches
+    checked_at: number; // Unix seconds
+}
+
+// A secret whose value contains the deep search query (the value is never returned)
+export interface DeepSearchMatch {
+    secret_id: string;
+    secret_name: string;
+    box_id: string;
+    box_name: string;
+}
+
+// Hand the blob and the passphrase over separately. Redeeming once is only enforced per vault:
+// whoever has both can still open the share in another Zap install until it expires.
+export interface OneTimeShare {
+    share_id: string;
+    blob: string;
+    passphrase: string; // Shown once, never stored
+    expires_at: number; // Unix seconds
+}
+
+export interface RedeemedShare {
+    share_id: string;
+    secret_name: string;
+    value: string;
 }
\ No newline at end of file
@@ -0,0 +1,82 @@
+// src/types/syncTypes.ts
+
+// Where a box's secrets live remotely. The credential is a vault secret: the service
+// principal's client secret for Azure, a service account JSON key for GCP
+export type SyncProviderConfig =
+    | { type: "azure_key_vault"; vault_url: string; tenant_id: string; client_id: string }
+    | { type: "gcp_secret_manager"; project_id: string };
+
+// Kept in Settings.sync_mappings
+export interface SyncMapping {
+    id: string;
+    box_id: string;
+    provider: SyncProviderConfig;
+    prefix?: string | null; // Remote names start with it, e.g. "myapp-"; others are ignored
+    credentials_secret_id: string; // Never synced itself
+}
+
+export type SyncState = "only_local" | "only_remote" | "different" | "same";
+
+// One name in a diff, values are compared in the backend and never sent back
+export interface SyncDiffEntry {
+    remote_name: string;
+    secret_name: string | null; // null = not in the box yet
+    state: SyncState;
+}
+
+export interface SyncDiff {
+    mapping_id: string | null; // null for a one-off pull_from_provider
+    provider: string; // "Azure Key Vault https://..."
+    entries: SyncDiffEntry[];
+}
+
+export interface SyncResult {
+    mapping_id: string | null;
+    changed: string[]; // Remote names pushed, or secret names pulled
+    errors: string[];
+}
+
+// Whole-vault two-way sync target, kept in Settings.replicas. The snapshot is sealed with the
+// passphrase in key_secret_id, so every device needs a vault secret with the same passphrase
+export type ReplicaTarget =
+    | { type: "file"; path: string }
+    | { type: "git"; repository: string; file_name: string } // A local clone, pulled and pushed
+    | {
+          type: "provider";
+          provider: SyncProviderConfig;
+          credentials_secret_id: string;
+          secret_name: string;
+      }
+    | {
+          type: "web_dav"; // Nextcloud, ownCloud... folder URL, signed in with an app password
+          url: string;
+          username: string;
+          password_secret_id: string;
+      };
+
+export interface ReplicaConfig {
+    id: string;
+    target: ReplicaTarget;
+    key_secret_id: string; // Never synced itself
+}
+
+export type ConflictResolution = "keep_local" | "keep_remote";
+
+// A box or secret changed on both sides since the last sync
+export interface SyncConflict {
+    record_key: string; // "box:<id>" or "secret:<id>"
+    name: string;
+    local_deleted: boolean;
+    remote_deleted: boolean;
+    local_updated_at: number;
+    remote_updated_at: number;
+    resolution: ConflictResolution | null; // Applied by the next sync
+}
+
+export interface ReplicaSyncReport {
+    replica_id: string;
+    pushed: string[]; // Box and secret names
+    pulled: string[];
+    conflicts: SyncConflict[];
+    errors: string[];
+}
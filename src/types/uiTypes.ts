@@ -1,9 +1,13 @@
 // src/types/uiTypes.ts
 import { Box } from "./boxTypes";
+import { MultilineFormat, ValueEncoding } from "./secretTypes";
 
 export interface SecretFormData {
     name: string;
     value: string;
+    value_encoding?: ValueEncoding; // Defaults to utf8
+    multiline_format?: MultilineFormat; // Defaults to escaped
+    normalize_line_endings?: boolean; // CRLF -> LF before saving
     box_id?: string;
 }
 
@@ -1,6 +1,6 @@
 // src/utils/index.ts
 
-import type { ValidationResult } from "../types";
+import type { ValidationResult, ZapCommandError, ZapErrorCode } from "../types";
 
 // TIME & DATE FORMATTING 
 
@@ -47,6 +47,12 @@ export function formatSessionDuration(startTime: number): string {
 
 // BOX VALIDATION
 
+// User-perceived characters - matches backend grapheme counting
+export function graphemeLength(value: string): number {
+    const segmenter = new Intl.Segmenter(undefined, { granularity: "grapheme" });
+    return Array.from(segmenter.segment(value)).length;
+}
+
 // Box name validation - matches backend (50 chars max, cannot be empty)
 export function validateBoxName(name: string): ValidationResult {
     const trimmed = name.trim();
@@ -55,7 +61,7 @@ export function validateBoxName(name: string): ValidationResult {
         return { isValid: false, message: "Box name cannot be empty" };
     }
 
-    if (trimmed.length > 50) {
+    if (graphemeLength(trimmed) > 50) {
         return { isValid: false, message: "Box name cannot exceed 50 characters" };
     }
 
@@ -64,7 +70,7 @@ export function validateBoxName(name: string): ValidationResult {
 
 // Box description validation - matches backend (75 chars max)
 export function validateBoxDescription(description: string): ValidationResult {
-    if (description.length > 75) {
+    if (graphemeLength(description) > 75) {
         return { isValid: false, message: "Box description cannot exceed 75 characters" };
     }
 
@@ -77,11 +83,11 @@ export function validateBoxDescription(description: string): ValidationResult {
 export function validateSecretName(name: string): ValidationResult {
     const trimmed = name.trim();
 
-    if (trimmed.length < 2) {
+    if (graphemeLength(trimmed) < 2) {
         return { isValid: false, message: "Secret name must be at least 2 characters for .ENV compatibility" };
     }
 
-    if (trimmed.length > 75) {
+    if (graphemeLength(trimmed) > 75) {
         return { isValid: false, message: "Secret name cannot exceed 75 characters" };
     }
 
@@ -257,8 +263,17 @@ export async function copyToClipboard(text: string): Promise<void> {
 
 // GENERAL UTILITIES
 
+export function isZapCommandError(error: unknown): error is ZapCommandError {
+    return typeof error === "object" && error !== null && "code" in error && "message" in error;
+}
+
+export function getErrorCode(error: unknown): ZapErrorCode | null {
+    return isZapCommandError(error) ? error.code : null;
+}
+
 // Get error message from unknown error
 export function getErrorMessage(error: unknown): string {
+    if (isZapCommandError(error)) return error.message;
     if (error instanceof Error) return error.message;
     if (typeof error === "string") return error;
     return "An unknown error occurred";
@@ -37,6 +37,8 @@ setup(
     entry_points={
         "console_scripts": [
             "zap=zap_cli.cli:cli",
+            "git-credential-zap=zap_cli.cli:git_credential",
+            "docker-credential-zap=zap_cli.cli:docker_credential",
         ],
     },
     keywords="credentials secrets environment variables development",
@@ -5,16 +5,19 @@ Zap CLI - Inject secrets from dev sessions as environment variables
 
 import json
 import os
+import shlex
 import sys
 import subprocess
+import time
 from pathlib import Path
-from typing import Optional, List
+from typing import Optional, List, Tuple
 import click
 from cryptography.hazmat.primitives.ciphers.aead import AESGCM
 from rich.console import Console
 from rich.table import Table
 
 console = Console()
+err_console = Console(stderr=True)
 
 # ================================
 # PATH RESOLUTION
@@ -23,11 +26,19 @@ console = Console()
 APP_IDENTIFIER = "com.devtool.zap"
 DATA_DIR = "data"
 SESSIONS_DIR = "sessions"
+SESSIONS_DIR_POINTER_FILE = "sessions_dir"
+CLI_SCHEMA_FILE = "cli_session_schema.json"
+SESSION_ACTIVITY_EXTENSION = "last_used"  # <session>.last_used, read by the app's idle timeout
 BIN_DIR = "bin"
+PORTABLE_DIR_ENV = "ZAP_PORTABLE_DIR"
 
 
 def get_app_base_directory() -> Path:
     """Get the base app directory (same as Rust version)"""
+    portable_dir = os.getenv(PORTABLE_DIR_ENV, "").strip()
+    if portable_dir:
+        return Path(portable_dir)
+
     if sys.platform == "win32":
         base = Path(os.getenv("APPDATA", "C:\\Users\\Default\\AppData\\Roaming"))
     elif sys.platform == "darwin":
@@ -39,24 +50,72 @@ def get_app_base_directory() -> Path:
 
 
 def get_sessions_directory() -> Path:
-    """Get sessions directory"""
+    """Get sessions directory (honors the desktop app's configured override)"""
+    pointer = get_app_base_directory() / SESSIONS_DIR_POINTER_FILE
+    if pointer.exists():
+        custom_dir = pointer.read_text().strip()
+        if custom_dir:
+            return Path(custom_dir)
     return get_app_base_directory() / SESSIONS_DIR
 
 
+def publish_schema():
+    """Tell the desktop app which session file formats this CLI reads"""
+    schema = {
+        "format_version": SESSION_FILE_FORMAT_VERSION,
+        "min_format_version": MIN_SESSION_FILE_FORMAT_VERSION,
+    }
+    try:
+        base = get_app_base_directory()
+        base.mkdir(parents=True, exist_ok=True)
+        (base / CLI_SCHEMA_FILE).write_text(json.dumps(schema, indent=2))
+    except OSError:
+        pass
+
+
 def get_session_file_path(session_name: str) -> Path:
     """Get path to specific session file"""
     return get_sessions_directory() / f"{session_name}.json"
 
 
+def record_activity(session_name: str):
+    """Tell the desktop app the session is still in use (Settings: dev_session_idle_hours)"""
+    try:
+        activity_path = get_sessions_directory() / f"{session_name}.{SESSION_ACTIVITY_EXTENSION}"
+        activity_path.write_text(str(int(time.time())))
+    except OSError:
+        pass
+
+
 # ================================
 # DATA STRUCTURES
 # ================================
 
+# Session file formats this CLI reads (matches Rust SessionFileSchema)
+SESSION_FILE_FORMAT_VERSION = 1
+MIN_SESSION_FILE_FORMAT_VERSION = 1
+
+
+class IncompatibleSessionFormat(Exception):
+    pass
+
 
 class SessionFile:
     """Session file structure (matches Rust CliSessionFile)"""
 
     def __init__(self, data: dict):
+        # Files written before versioning are format 1
+        self.format_version = data.get("format_version", 1)
+        if not (
+            MIN_SESSION_FILE_FORMAT_VERSION
+            <= self.format_version
+            <= SESSION_FILE_FORMAT_VERSION
+        ):
+            raise IncompatibleSessionFormat(
+                f"session '{data.get('session_name')}' uses format {self.format_version}, "
+                f"this CLI reads formats {MIN_SESSION_FILE_FORMAT_VERSION}-"
+                f"{SESSION_FILE_FORMAT_VERSION} - update the CLI"
+            )
         self.session_name = data["session_name"]
         self.box_name = data["box_name"]
         self.session_key = bytes.fromhex(data["session_key"])
@@ -73,6 +132,9 @@ class SessionFile:
         try:
             with open(session_path, "r") as f:
                 return cls(json.load(f))
+        except IncompatibleSessionFormat as e:
+            err_console.print(f"[red]zap: {e}[/red]")
+            return None
         except (json.JSONDecodeError, KeyError, IOError):
             return None
 
@@ -88,6 +150,9 @@ class SessionFile:
             try:
                 with open(file, "r") as f:
                     sessions.append(cls(json.load(f)))
+            except IncompatibleSessionFormat as e:
+                err_console.print(f"[yellow]zap: {e}[/yellow]")
+                continue
             except (json.JSONDecodeError, KeyError, IOError):
                 continue
 
@@ -101,6 +166,12 @@ class ProjectContext:
         self.app = data.get("app", "zap")
         self.current_session = data["current_session"]
         self.available_secrets = data.get("available_secrets", [])
+        # Keep desktop-managed binding fields (box_id, default_session_name, ...)
+        self.extra = {
+            k: v
+            for k, v in data.items()
+            if k not in ("app", "current_session", "available_secrets")
+        }
 
     @classmethod
     def load(cls) -> Optional["ProjectContext"]:
@@ -121,6 +192,7 @@ class ProjectContext:
         with open(zap_file, "w") as f:
             json.dump(
                 {
+                    **self.extra,
                     "app": self.app,
                     "current_session": self.current_session,
                     "available_secrets": self.available_secrets,
@@ -171,7 +243,7 @@ def decrypt_secret(hex_encrypted_data: str, session_key: bytes) -> str:
 @click.version_option(version="0.1.1")
 def cli():
     """Zap CLI - Inject secrets as environment variables"""
-    pass
+    publish_schema()
 
 
 @cli.command()
@@ -275,13 +347,11 @@ def use(session_name: str):
     ]
     available_secrets.sort()
 
-    context = ProjectContext(
-        {
-            "app": "zap",
-            "current_session": session_name,
-            "available_secrets": available_secrets,
-        }
+    context = ProjectContext.load() or ProjectContext(
+        {"app": "zap", "current_session": session_name}
     )
+    context.current_session = session_name
+    context.available_secrets = available_secrets
     context.save()
 
     console.print(
@@ -323,6 +393,7 @@ def run(command, session, verbose, prefix):
         console.print(f"[red]Session '{session_name}' not found[/red]")
         console.print("[dim]Use 'zap list' to see available sessions[/dim]")
         sys.exit(1)
+    record_activity(session_name)
 
     # Prepare environment
     env = os.environ.copy()
@@ -362,6 +433,45 @@ def run(command, session, verbose, prefix):
     sys.exit(result.returncode)
 
 
+@cli.command()
+@click.option("--session", "-s", help="Session name override")
+@click.option("--prefix", "-p", help="Prefix for environment variable names")
+@click.option(
+    "--shell", "shell_format", is_flag=True, help="Print shell export statements (direnv)"
+)
+def export(session, prefix, shell_format):
+    """Print session secrets to stdout (for direnv: eval "$(zap export --shell)")"""
+    # stdout carries only variables - messages go to stderr
+    session_name = session
+    if not session_name:
+        context = ProjectContext.load()
+        if not context:
+            err_console.print("[red]zap: no current session set for this directory[/red]")
+            sys.exit(1)
+        session_name = context.current_session
+
+    session_file = SessionFile.load(session_name)
+    if not session_file:
+        err_console.print(
+            f"[red]zap: session '{session_name}' not found - start it in the Zap app[/red]"
+        )
+        sys.exit(1)
+    record_activity(session_name)
+
+    for secret_name, hex_encrypted in session_file.encrypted_secrets.items():
+        try:
+            decrypted_value = decrypt_secret(hex_encrypted, session_file.session_key)
+        except Exception as e:
+            err_console.print(f"[red]zap: failed to decrypt {secret_name}: {e}[/red]")
+            continue
+
+        env_var_name = secret_name_to_env_var(secret_name, prefix)
+        if shell_format:
+            click.echo(f"export {env_var_name}={shlex.quote(decrypted_value)}")
+        else:
+            click.echo(f"{env_var_name}={json.dumps(decrypted_value)}")
+
+
 @cli.command()
 @click.argument("session_name")
 def stop(session_name: str):
@@ -440,5 +550,176 @@ def status():
     console.print("\n[green]Ready to run commands with 'zap run -- <command>'[/green]")
 
 
+# ================================
+# CREDENTIAL HELPERS
+# ================================
+
+
+def load_host_credentials(
+    session_name: str, host: str
+) -> Optional[Tuple[Optional[str], str]]:
+    """(username, token) for a host from a session: secrets "<host>" and "<host> username"
+
+    Returns None when the session isn't running or has no secret for the host.
+    """
+    session_file = SessionFile.load(session_name)
+    if not session_file:
+        err_console.print(f"[yellow]zap: session '{session_name}' is not running[/yellow]")
+        return None
+    record_activity(session_name)
+
+    secrets_by_env_var = {
+        secret_name_to_env_var(name): hex_encrypted
+        for name, hex_encrypted in session_file.encrypted_secrets.items()
+    }
+    host_var = secret_name_to_env_var(host)
+    if host_var not in secrets_by_env_var:
+        return None
+
+    try:
+        token = decrypt_secret(secrets_by_env_var[host_var], session_file.session_key)
+        username_hex = secrets_by_env_var.get(f"{host_var}_USERNAME")
+        username = (
+            decrypt_secret(username_hex, session_file.session_key) if username_hex else None
+        )
+    except Exception as e:
+        err_console.print(f"[red]zap: failed to decrypt credentials for {host}: {e}[/red]")
+        return None
+
+    return username, token
+
+
+def list_host_credentials(session_name: str) -> dict:
+    """{host: username} for every host token in a session (docker `list`)"""
+    session_file = SessionFile.load(session_name)
+    if not session_file:
+        return {}
+
+    hosts = {}
+    for name in session_file.encrypted_secrets:
+        if secret_name_to_env_var(name).endswith("_USERNAME"):
+            continue
+        credentials = load_host_credentials(session_name, name)
+        if credentials:
+            hosts[name] = credentials[0] or ""
+    return hosts
+
+
+# git
+
+GIT_SESSION_ENV = "ZAP_GIT_SESSION"
+GIT_SESSION_CONFIG = "zap.gitSession"
+DEFAULT_GIT_SESSION = "git"
+
+
+def get_git_session_name() -> str:
+    """Session backing git credentials: $ZAP_GIT_SESSION, `git config zap.gitSession`, or 'git'"""
+    session_name = os.getenv(GIT_SESSION_ENV)
+    if session_name:
+        return session_name
+
+    try:
+        result = subprocess.run(
+            ["git", "config", "--get", GIT_SESSION_CONFIG],
+            capture_output=True,
+            text=True,
+        )
+        if result.returncode == 0 and result.stdout.strip():
+            return result.stdout.strip()
+    except OSError:
+        pass
+
+    return DEFAULT_GIT_SESSION
+
+
+def read_git_credential_request() -> dict:
+    """Parse git's key=value request from stdin (ends at a blank line or EOF)"""
+    request = {}
+    for line in sys.stdin:
+        line = line.rstrip("\n")
+        if not line:
+            break
+        key, _, value = line.partition("=")
+        request[key] = value
+    return request
+
+
+@click.command()
+@click.argument("operation", type=click.Choice(["get", "store", "erase"]))
+def git_credential(operation: str):
+    """git credential helper backed by a Zap session
+
+    Tokens are looked up by host: a secret named "github.com" (env var GITHUB_COM)
+    is the password, and an optional "github.com username" secret the username.
+
+    Setup: git config --global credential.helper zap
+    """
+    request = read_git_credential_request()
+
+    # The vault is only writable from the desktop app - git's store/erase are no-ops
+    if operation != "get":
+        return
+
+    host = request.get("host")
+    if not host:
+        return
+
+    # Print nothing when missing so git falls through to the next helper
+    credentials = load_host_credentials(get_git_session_name(), host)
+    if not credentials:
+        return
+
+    stored_username, password = credentials
+    username = request.get("username") or stored_username
+    if username:
+        click.echo(f"username={username}")
+    click.echo(f"password={password}")
+
+
+# docker
+
+DOCKER_SESSION_ENV = "ZAP_DOCKER_SESSION"
+DEFAULT_DOCKER_SESSION = "registries"
+
+
+def registry_host(server_url: str) -> str:
+    """https://index.docker.io/v1/ -> index.docker.io"""
+    host = server_url.strip()
+    if "://" in host:
+        host = host.split("://", 1)[1]
+    return host.split("/", 1)[0]
+
+
+@click.command()
+@click.argument("operation", type=click.Choice(["get", "store", "erase", "list"]))
+def docker_credential(operation: str):
+    """docker credential helper backed by a Zap session ("registries" by default)
+
+    Setup: set "credsStore": "zap" in ~/.docker/config.json
+    """
+    session_name = os.getenv(DOCKER_SESSION_ENV) or DEFAULT_DOCKER_SESSION
+    payload = sys.stdin.read()
+
+    if operation == "get":
+        server_url = payload.strip()
+        credentials = load_host_credentials(session_name, registry_host(server_url))
+        if not credentials:
+            # Exact message docker checks for to treat the registry as anonymous
+            click.echo("credentials not found in native keychain")
+            sys.exit(1)
+
+        username, secret = credentials
+        click.echo(
+            json.dumps({"ServerURL": server_url, "Username": username or "", "Secret": secret})
+        )
+    elif operation == "list":
+        click.echo(json.dumps(list_host_credentials(session_name)))
+    else:
+        # `docker login` / `logout` succeed, but tokens are managed in the desktop app
+        err_console.print(
+            "[yellow]zap: add or remove registry tokens in the Zap desktop app[/yellow]"
+        )
+
+
 if __name__ == "__main__":
     cli()